@@ -2,16 +2,15 @@
 // Phoenix spawns agents — they live forever on GitHub as eternal repositories
 // The reproductive system of Phoenix AGI (PAGI) — creates agents, pushes to GitHub, deploys
 
-use octocrab::models::Repository;
-use octocrab::Octocrab;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 use tempfile::TempDir;
 use uuid::Uuid;
 
-use evolution_pipeline::{EvolutionPipelineConfig, GitHubRepo};
+use evolution_pipeline::{Forge, ForgeRepo, NewPullRequest};
 use evolution_pipeline::GitHubEnforcer;
 
 mod templates {
@@ -163,32 +162,55 @@ pub struct AgentTemplateOverrides {
 }
 
 pub struct AgentSpawner {
-    octocrab: Octocrab,
+    /// GitHub by default; `PHOENIX_FORGE` selects Gitea/Forgejo or the local fake forge.
+    forge: Arc<dyn Forge>,
+    token: String,
     github_username: String,
+    /// Waits for CI and Dad's approval when a spawn goes through a pull request.
+    enforcer: GitHubEnforcer,
 }
 
 impl AgentSpawner {
     pub fn awaken() -> Result<Self, String> {
         dotenvy::dotenv().ok();
         
-        let token = std::env::var("GITHUB_PAT")
-            .map_err(|_| "GITHUB_PAT not found in environment".to_string())?;
+        let forge = evolution_pipeline::forge::from_env();
+        // Forges without accounts (the local fake forge) take no token.
+        let token = match std::env::var("GITHUB_PAT") {
+            Ok(token) => token,
+            Err(_) if !forge.requires_token() => String::new(),
+            Err(_) => return Err("GITHUB_PAT not found in environment".to_string()),
+        };
         
         let github_username = std::env::var("GITHUB_USERNAME")
             .unwrap_or_else(|_| "yourusername".to_string());
         
-        let octocrab = Octocrab::builder()
-            .personal_token(token)
-            .build()
-            .map_err(|e| format!("Failed to create GitHub client: {}", e))?;
+        let mut enforcer = GitHubEnforcer::from_env();
+        enforcer.forge = forge.clone();
         
-        println!("Agent Spawner awakened — Phoenix can birth agents on GitHub.");
+        println!("Agent Spawner awakened — Phoenix can birth agents on {}.", forge.kind());
         Ok(Self {
-            octocrab,
+            forge,
+            token,
             github_username,
+            enforcer,
         })
     }
 
+    /// A spawner that creates agents for `github_username` on `forge`, without reading the
+    /// environment for credentials or the forge.
+    pub fn with_forge(forge: Arc<dyn Forge>, token: impl Into<String>, github_username: impl Into<String>) -> Self {
+        let token = token.into();
+        let github_username = github_username.into();
+        let enforcer = GitHubEnforcer::with_forge(forge.clone(), token.clone(), github_username.clone());
+        Self {
+            forge,
+            token,
+            github_username,
+            enforcer,
+        }
+    }
+
     pub async fn spawn_agent(
         &self,
         name: &str,
@@ -209,10 +231,10 @@ impl AgentSpawner {
         self.push_code_to_repo(name, description, code, &tier, &template_overrides)
             .await?;
         
-        // Get repository URL - html_url might be Option<Url> or Url directly
-        let repo_url = match &repo.html_url {
-            Some(url) => url.to_string(),
-            None => format!("https://github.com/{}/{}", self.github_username, name),
+        let repo_url = if repo.html_url.is_empty() {
+            format!("https://github.com/{}/{}", self.github_username, name)
+        } else {
+            repo.html_url.clone()
         };
         
         println!("Agent '{}' spawned successfully: {}", name, repo_url);
@@ -231,23 +253,12 @@ impl AgentSpawner {
         name: &str,
         description: &str,
         is_private: bool,
-    ) -> Result<Repository, String> {
-        // Use octocrab's POST /user/repos endpoint
-        let create_repo: Repository = self.octocrab
-            .post(
-                "/user/repos",
-                Some(&json!({
-                    "name": name,
-                    "description": description,
-                    "private": is_private,
-                    // Ensure the base branch exists so we can PR into it.
-                    "auto_init": true
-                })),
-            )
+    ) -> Result<ForgeRepo, String> {
+        // Auto-initialized, so the base branch exists and we can PR into it.
+        self.forge
+            .create_repo(&self.token, &self.github_username, name, description, is_private)
             .await
-            .map_err(|e| format!("Failed to create repository: {}", e))?;
-        
-        Ok(create_repo)
+            .map_err(|e| format!("Failed to create repository: {}", e))
     }
 
     async fn push_code_to_repo(
@@ -258,13 +269,9 @@ impl AgentSpawner {
         tier: &AgentTier,
         template_overrides: &AgentTemplateOverrides,
     ) -> Result<(), String> {
-        let cfg = EvolutionPipelineConfig::from_env()
-            .map_err(|e| format!("evolution pipeline config error: {e}"))?;
         // If human approval is required, we *must* go through a PR flow.
-        let mandate = cfg.mandate_github_ci
-            || env_bool("MANDATE_GITHUB_CI").unwrap_or(false)
-            || require_human_approval();
-        let base_branch = cfg.base_branch.clone();
+        let mandate = env_bool("MANDATE_GITHUB_CI").unwrap_or(false) || require_human_approval();
+        let base_branch = std::env::var("GITHUB_BASE_BRANCH").unwrap_or_else(|_| "main".to_string());
         let testing_mandatory = env_bool("TESTING_MANDATORY").unwrap_or(true);
 
         // Create temporary directory for git operations
@@ -272,14 +279,10 @@ impl AgentSpawner {
             .map_err(|e| format!("Failed to create temp directory: {}", e))?;
         let repo_path = temp_dir.path();
 
-        let gh = GitHubRepo {
-            owner: self.github_username.clone(),
-            name: repo_name.to_string(),
-        };
-        let https_git_url = gh.https_git_url();
+        let clone_url = self.forge.clone_url(&self.github_username, repo_name);
 
         // Clone the auto-initialized repository.
-        let repo = evolution_pipeline::clone_https_with_pat(&https_git_url, repo_path, &cfg.github_pat)
+        let repo = evolution_pipeline::clone_https_with_pat(&clone_url, repo_path, &self.token)
             .map_err(|e| format!("git clone failed: {e}"))?;
 
         // Scaffold files from templates.
@@ -304,7 +307,7 @@ impl AgentSpawner {
                 &repo,
                 &branch,
                 "Phoenix evolution: spawn agent from template",
-                &cfg.github_pat,
+                &self.token,
             )
             .map_err(|e| format!("push branch failed: {e}"))?;
 
@@ -312,24 +315,28 @@ impl AgentSpawner {
                 "Spawned by Phoenix AGI (PAGI) via template-enforced evolution pipeline.\n\n{}",
                 test_md
             );
-            let pr_url = evolution_pipeline::open_pull_request(
-                &gh,
-                &format!("Spawn agent: {repo_name}"),
-                &branch,
-                &base_branch,
-                Some(&pr_body),
-                &cfg.github_pat,
-                &cfg.user_agent,
-            )
-            .await
-            .map_err(|e| format!("open PR failed: {e}"))?;
+            let pr_url = self
+                .forge
+                .create_pr(
+                    &self.token,
+                    NewPullRequest {
+                        owner: &self.github_username,
+                        repo: repo_name,
+                        head: &branch,
+                        base: &base_branch,
+                        title: &format!("Spawn agent: {repo_name}"),
+                        body: &pr_body,
+                    },
+                )
+                .await
+                .map_err(|e| format!("open PR failed: {e}"))?;
 
             println!("Opened PR for spawned agent: {pr_url}");
 
             // GitHub-first enforcement: wait for CI + Dad approval + (optional) merge.
             // Safety: if REQUIRE_HUMAN_PR_APPROVAL=false, the enforcer will refuse.
-            let enforcer = GitHubEnforcer::from_env();
-            let _merged_sha = enforcer
+            let _merged_sha = self
+                .enforcer
                 .enforce_existing_pr(&pr_url)
                 .await
                 .map_err(|e| format!("GitHub-first enforcement failed: {e}"))?;
@@ -339,7 +346,7 @@ impl AgentSpawner {
                 &repo,
                 &base_branch,
                 "Phoenix: spawn agent (direct push)",
-                &cfg.github_pat,
+                &self.token,
             )
             .map_err(|e| format!("push base branch failed: {e}"))?;
         }
@@ -562,3 +569,62 @@ fn resolve_zodiac_sign(override_sign: Option<&str>, phoenix_base_sign: Option<&s
     phoenix_base_sign
         .and_then(|s| normalize_zodiac_sign(s).map(|x| x.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evolution_pipeline::FakeForge;
+
+    const OWNER: &str = "dad";
+
+    #[tokio::test]
+    async fn spawns_an_agent_on_the_fake_forge() {
+        let dir = TempDir::new().unwrap();
+        let forge = Arc::new(FakeForge::new(dir.path().join("forge"), OWNER));
+        let mut spawner = AgentSpawner::with_forge(forge.clone(), "", OWNER);
+        spawner.enforcer.auto_merge_on_approval = true;
+        spawner.enforcer.poll_interval = Duration::from_millis(20);
+
+        // Plays CI and Dad for the spawn PR.
+        let reviewer = {
+            let forge = forge.clone();
+            tokio::spawn(async move {
+                loop {
+                    let open = forge.list_open_prs("", OWNER, "hello-agent").await.unwrap_or_default();
+                    if let Some(pr) = open.first() {
+                        forge.set_ci_state(&pr.html_url, "success").unwrap();
+                        forge.approve_as(&pr.html_url, OWNER).unwrap();
+                        return pr.html_url.clone();
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+        };
+
+        let code = "pub async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {\n    Ok(())\n}\n";
+        let spawn = spawner.spawn_agent(
+            "hello-agent",
+            "Says hello.",
+            code,
+            AgentTier::Free,
+            AgentTemplateOverrides::default(),
+        );
+        let agent = tokio::time::timeout(Duration::from_secs(600), spawn)
+            .await
+            .expect("spawn timed out")
+            .unwrap();
+        let pr_url = reviewer.await.unwrap();
+
+        assert_eq!(agent.github_repo, "dad/hello-agent");
+        let pull = forge.pull(&pr_url).unwrap();
+        assert!(pull.merged);
+        assert_eq!(pull.title, "Spawn agent: hello-agent");
+        assert!(pull.body.contains("Spawned by Phoenix AGI"));
+
+        let repo = git2::Repository::open_bare(forge.repo_path(OWNER, "hello-agent")).unwrap();
+        let main = repo.find_reference("refs/heads/main").unwrap().peel_to_tree().unwrap();
+        for path in ["src/generated.rs", "src/main.rs", "Cargo.toml", "TEST_REPORT.md"] {
+            assert!(main.get_path(Path::new(path)).is_ok(), "{path} missing from main");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// code_analysis/src/bin/code-report.rs
+// Analyze a file or directory and write SARIF and Markdown reports: `code-report [OPTIONS] PATH`
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use code_analysis::{markdown_report, sarif_report, CodeAnalyzer};
+
+const USAGE: &str = "Usage: code-report [--out-dir DIR] [--sarif FILE] [--markdown FILE] [--deny-warnings] PATH
+
+Analyzes PATH (a file or a directory) and writes a SARIF 2.1.0 log and a Markdown report.
+
+Options:
+  --out-dir DIR     Directory for both reports (default: current directory)
+  --sarif FILE      SARIF output file (default: DIR/code-analysis.sarif)
+  --markdown FILE   Markdown output file (default: DIR/code-analysis.md)
+  --deny-warnings   Exit with an error if any warning- or error-level finding is reported
+  -h, --help        Show this help
+
+CODE_ANALYSIS_CACHE_DIR and CODE_ANALYSIS_WORKERS are honoured.";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut out_dir = PathBuf::from(".");
+    let mut sarif_path: Option<PathBuf> = None;
+    let mut markdown_path: Option<PathBuf> = None;
+    let mut deny_warnings = false;
+    let mut target: Option<PathBuf> = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out-dir" | "--sarif" | "--markdown" => {
+                let Some(value) = args.next().map(PathBuf::from) else {
+                    eprintln!("{} needs a value\n\n{}", arg, USAGE);
+                    return ExitCode::from(2);
+                };
+                match arg.as_str() {
+                    "--out-dir" => out_dir = value,
+                    "--sarif" => sarif_path = Some(value),
+                    _ => markdown_path = Some(value),
+                }
+            }
+            "--deny-warnings" => deny_warnings = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}\n\n{}", other, USAGE);
+                return ExitCode::from(2);
+            }
+            path if target.is_none() => target = Some(PathBuf::from(path)),
+            _ => {
+                eprintln!("Only one PATH may be given\n\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let Some(target) = target else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let codebase = match CodeAnalyzer::new().analyze_codebase(&target).await {
+        Ok(codebase) => codebase,
+        Err(e) => {
+            eprintln!("Analysis of {} failed: {}", target.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let sarif = sarif_report(&codebase);
+    let sarif_path = sarif_path.unwrap_or_else(|| out_dir.join("code-analysis.sarif"));
+    let markdown_path = markdown_path.unwrap_or_else(|| out_dir.join("code-analysis.md"));
+    let outputs = [
+        (&sarif_path, serde_json::to_string_pretty(&sarif).unwrap_or_default()),
+        (&markdown_path, markdown_report(&codebase)),
+    ];
+    for (path, content) in outputs {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(path, content) {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let results = sarif["runs"][0]["results"].as_array().cloned().unwrap_or_default();
+    let serious = results
+        .iter()
+        .filter(|r| matches!(r["level"].as_str(), Some("error" | "warning")))
+        .count();
+    println!(
+        "Analyzed {} files ({} errors): {} findings, {} at warning level or above",
+        codebase.files.len(),
+        codebase.errors.len(),
+        results.len(),
+        serious
+    );
+    println!("Wrote {} and {}", sarif_path.display(), markdown_path.display());
+
+    if deny_warnings && serious > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,87 @@
+//! On-disk cache of per-file analyses
+//!
+//! One JSON entry per source file, named after a hash of its path and validated against a hash
+//! of its content. Entries record whether LLM semantics were included, so a structural-only run
+//! never serves a cached result to an analyzer that has an LLM (or the other way around).
+//!
+//! What a file resolves to can depend on which other files exist (`mod` files, relative
+//! imports); such entries are refreshed the next time the file itself changes.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::CodeAnalysis;
+
+/// Bump when the analysis output changes shape or meaning; older entries are ignored.
+const CACHE_VERSION: u32 = 1;
+
+/// Hex SHA-256 of a file's content.
+pub(crate) fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    version: u32,
+    content_hash: String,
+    semantic: bool,
+    analysis: CodeAnalysis,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct AnalysisCache {
+    dir: PathBuf,
+}
+
+impl AnalysisCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, file: &Path) -> PathBuf {
+        let key = hex::encode(Sha256::digest(file.to_string_lossy().as_bytes()));
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// The cached analysis of `file`, if it was made from the same content with the same
+    /// semantic depth.
+    pub fn get(&self, file: &Path, content_hash: &str, semantic: bool) -> Option<CodeAnalysis> {
+        let raw = std::fs::read_to_string(self.entry_path(file)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&raw).ok()?;
+        (entry.version == CACHE_VERSION
+            && entry.content_hash == content_hash
+            && entry.semantic == semantic)
+            .then_some(entry.analysis)
+    }
+
+    pub fn put(
+        &self,
+        file: &Path,
+        content_hash: &str,
+        semantic: bool,
+        analysis: &CodeAnalysis,
+    ) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create cache dir {}: {}", self.dir.display(), e))?;
+        let entry = CacheEntry {
+            version: CACHE_VERSION,
+            content_hash: content_hash.to_string(),
+            semantic,
+            analysis: analysis.clone(),
+        };
+        let json = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize cache entry: {}", e))?;
+        // Write-then-rename so concurrent readers never see a partial entry.
+        let path = self.entry_path(file);
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| format!("Failed to write cache entry {}: {}", path.display(), e))
+    }
+}
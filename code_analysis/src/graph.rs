@@ -0,0 +1,696 @@
+//! Module dependency graph, cycle detection and codebase-level relationships
+//!
+//! Rust files are mapped to module paths (`crate_name::a::b`) from their position under the
+//! nearest `Cargo.toml`, and `use`/path references are resolved against those modules. Re-exports
+//! (`pub use`) describe a facade rather than a dependency and are left out of the graph.
+//! Python, JavaScript and TypeScript files are modules named by their path; only relative imports
+//! resolve to files of the codebase.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ArchitectureAnalysis, CodeAnalysis, CodeRelationship, CodeStructure, DependencyGraph, Language,
+    UsageLocation,
+};
+
+/// Where a Rust file sits in its crate.
+#[derive(Debug, Clone)]
+pub(crate) struct ModuleLocation {
+    pub crate_name: String,
+    pub crate_root: PathBuf,
+    /// Module path inside the crate (empty for `lib.rs`/`main.rs`).
+    pub segments: Vec<String>,
+}
+
+impl ModuleLocation {
+    /// Locate `file` relative to the nearest enclosing package manifest.
+    pub fn of(file: &Path) -> Option<Self> {
+        let (crate_root, crate_name) = file.ancestors().skip(1).find_map(|dir| {
+            let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+            package_name(&manifest).map(|name| (dir.to_path_buf(), name.replace('-', "_")))
+        })?;
+
+        let src = crate_root.join("src");
+        let relative = file
+            .strip_prefix(&src)
+            .or_else(|_| file.strip_prefix(&crate_root))
+            .ok()?;
+        let mut segments: Vec<String> = relative
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let top_level = file.starts_with(&src) && segments.len() == 1;
+        match segments.last().map(String::as_str) {
+            Some("mod") => {
+                segments.pop();
+            }
+            Some("lib") | Some("main") if top_level => {
+                segments.pop();
+            }
+            _ => {}
+        }
+
+        Some(Self {
+            crate_name,
+            crate_root,
+            segments,
+        })
+    }
+
+    /// Fully qualified module name.
+    pub fn name(&self) -> String {
+        std::iter::once(self.crate_name.clone())
+            .chain(self.segments.iter().cloned())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    /// Source file of a module of this crate, if it has one.
+    pub fn module_file(&self, segments: &[String]) -> Option<PathBuf> {
+        let src = self.crate_root.join("src");
+        let candidates = if segments.is_empty() {
+            vec![src.join("lib.rs"), src.join("main.rs")]
+        } else {
+            let dir = segments.iter().fold(src, |p, s| p.join(s));
+            vec![dir.with_extension("rs"), dir.join("mod.rs")]
+        };
+        candidates.into_iter().find(|c| c.is_file())
+    }
+
+    /// Absolute path segments of a path as written in this file.
+    pub fn absolute(&self, path: &str) -> Vec<String> {
+        let segments: Vec<String> = path
+            .split("::")
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        let Some(first) = segments.first() else {
+            return segments;
+        };
+        let mut base = vec![self.crate_name.clone()];
+        match first.as_str() {
+            "crate" => {
+                base.extend(segments[1..].iter().cloned());
+                base
+            }
+            "self" => {
+                base.extend(self.segments.iter().cloned());
+                base.extend(segments[1..].iter().cloned());
+                base
+            }
+            "super" => {
+                let mut module = self.segments.clone();
+                let mut rest = segments.as_slice();
+                while rest.first().map(String::as_str) == Some("super") {
+                    module.pop();
+                    rest = &rest[1..];
+                }
+                base.extend(module);
+                base.extend(rest.iter().cloned());
+                base
+            }
+            _ => {
+                // 2018+ paths may name a child module without `self::`.
+                let mut child = self.segments.clone();
+                child.push(first.clone());
+                if self.module_file(&child).is_some() {
+                    base.extend(self.segments.iter().cloned());
+                    base.extend(segments);
+                    base
+                } else {
+                    segments
+                }
+            }
+        }
+    }
+
+    /// Resolve a path to the module of this crate it points into, and that module's file.
+    pub fn resolve(&self, path: &str) -> Option<(String, PathBuf)> {
+        let absolute = self.absolute(path);
+        if absolute.first() != Some(&self.crate_name) {
+            return None;
+        }
+        (1..=absolute.len()).rev().find_map(|k| {
+            self.module_file(&absolute[1..k])
+                .map(|file| (absolute[..k].join("::"), file))
+        })
+    }
+}
+
+fn package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if in_package {
+            if let Some(value) = line.strip_prefix("name") {
+                let value = value.trim_start().strip_prefix('=')?.trim();
+                return Some(value.trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Re-exports (`pub use`, `export ... from`) form a facade, not a dependency.
+pub(crate) fn is_re_export(import_type: &str) -> bool {
+    matches!(import_type, "pub use" | "export from")
+}
+
+/// Module name of a Python/JavaScript/TypeScript file: its path without extension.
+pub(crate) fn file_module(file: &Path) -> String {
+    normalize(file).with_extension("").display().to_string()
+}
+
+/// File a relative import (`./x`, `../y` in JavaScript/TypeScript, `.x`, `..pkg.x` in Python)
+/// points to, if it exists.
+pub(crate) fn resolve_relative_import(file: &Path, language: Language, import: &str) -> Option<PathBuf> {
+    let dir = file.parent()?;
+    let candidates = match language {
+        Language::Python => {
+            let dots = import.chars().take_while(|c| *c == '.').count();
+            if dots == 0 {
+                return None;
+            }
+            let mut base = dir.to_path_buf();
+            for _ in 1..dots {
+                base = base.parent()?.to_path_buf();
+            }
+            let target = import[dots..]
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .fold(base, |path, segment| path.join(segment));
+            vec![with_suffix(&target, ".py"), target.join("__init__.py")]
+        }
+        Language::JavaScript | Language::TypeScript => {
+            if !(import.starts_with("./") || import.starts_with("../") || import == "." || import == "..") {
+                return None;
+            }
+            let target = normalize(&dir.join(import));
+            const EXTENSIONS: [&str; 6] = [".ts", ".tsx", ".js", ".jsx", ".mjs", ".cjs"];
+            // Stylesheets, JSON and other assets are not modules.
+            let explicit = target
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| EXTENSIONS.contains(&format!(".{}", e).as_str()));
+            explicit
+                .then(|| target.clone())
+                .into_iter()
+                .chain(EXTENSIONS.iter().map(|ext| with_suffix(&target, ext)))
+                .chain(EXTENSIONS.iter().map(|ext| target.join(format!("index{}", ext))))
+                .collect()
+        }
+        _ => return None,
+    };
+    candidates.into_iter().map(|c| normalize(&c)).find(|c| c.is_file())
+}
+
+/// `a/b` + `.ts` -> `a/b.ts` (unlike `with_extension`, keeps `a/b.service` intact).
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_os_string();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// Lexically drop `.` and fold `..` components.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Where `name` is used inside the file's functions.
+pub(crate) fn usage_locations(structure: &CodeStructure, name: &str) -> Vec<UsageLocation> {
+    if name.is_empty() || name == "*" || name == "self" {
+        return Vec::new();
+    }
+    let mut locations = Vec::new();
+    for f in &structure.functions {
+        let context = match &f.owner {
+            Some(owner) => format!("{}::{}", owner, f.name),
+            None => f.name.clone(),
+        };
+        for (offset, text) in f.body.lines().enumerate() {
+            let trimmed = text.trim_start();
+            if trimmed.starts_with("//") {
+                continue;
+            }
+            if let Some(usage_type) = find_word(text, name) {
+                locations.push(UsageLocation {
+                    context: context.clone(),
+                    line: f.line_range.0 + offset,
+                    usage_type: usage_type.to_string(),
+                });
+            }
+        }
+    }
+    locations
+}
+
+/// Classify the first whole-word occurrence of `word` in `line`.
+fn find_word(line: &str, word: &str) -> Option<&'static str> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut search = 0;
+    while let Some(pos) = line[search..].find(word) {
+        let start = search + pos;
+        let end = start + word.len();
+        let before = line[..start].chars().next_back();
+        let after = line[end..].chars().next();
+        if !before.is_some_and(is_ident) && !after.is_some_and(is_ident) {
+            let rest = line[end..].trim_start();
+            let prefix = line[..start].trim_end();
+            return Some(if rest.starts_with('(') {
+                "call"
+            } else if rest.starts_with("::") {
+                "path"
+            } else if rest.starts_with('!') {
+                "macro"
+            } else if prefix.ends_with(':') || prefix.ends_with('<') || prefix.ends_with('&') || prefix.ends_with("->") {
+                "type"
+            } else {
+                "reference"
+            });
+        }
+        search = end;
+    }
+    None
+}
+
+/// Module dependency graph over all analyzed files and its dependency cycles.
+pub(crate) fn module_graph(files: &[CodeAnalysis]) -> (DependencyGraph, Vec<Vec<String>>) {
+    let modules: BTreeSet<String> = files.iter().filter_map(|f| f.module_path.clone()).collect();
+    let mut edges = BTreeSet::new();
+    for file in files {
+        let Some(module) = &file.module_path else {
+            continue;
+        };
+        for dep in file.dependencies.internal.iter().chain(&file.dependencies.external) {
+            if is_re_export(&dep.dependency_type) {
+                continue;
+            }
+            if let Some(target) = longest_module_prefix(&modules, &dep.name) {
+                if target != *module {
+                    edges.insert((module.clone(), target));
+                }
+            }
+        }
+    }
+
+    let nodes: Vec<String> = modules.into_iter().collect();
+    let edges: Vec<(String, String)> = edges.into_iter().collect();
+    let mut cycles: Vec<Vec<String>> = strongly_connected(&nodes, &edges)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|mut component| {
+            component.sort();
+            component
+        })
+        .collect();
+    cycles.sort();
+    (DependencyGraph { nodes, edges }, cycles)
+}
+
+fn longest_module_prefix(modules: &BTreeSet<String>, path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split("::").collect();
+    (1..=segments.len())
+        .rev()
+        .map(|k| segments[..k].join("::"))
+        .find(|candidate| modules.contains(candidate))
+}
+
+/// Tarjan's strongly connected components.
+fn strongly_connected(nodes: &[String], edges: &[(String, String)]) -> Vec<Vec<String>> {
+    struct State<'a> {
+        adjacency: Vec<Vec<usize>>,
+        nodes: &'a [String],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        components: Vec<Vec<String>>,
+    }
+
+    fn visit(state: &mut State, v: usize) {
+        state.index[v] = Some(state.next);
+        state.lowlink[v] = state.next;
+        state.next += 1;
+        state.stack.push(v);
+        state.on_stack[v] = true;
+
+        for w in state.adjacency[v].clone() {
+            match state.index[w] {
+                None => {
+                    visit(state, w);
+                    state.lowlink[v] = state.lowlink[v].min(state.lowlink[w]);
+                }
+                Some(index) if state.on_stack[w] => {
+                    state.lowlink[v] = state.lowlink[v].min(index);
+                }
+                _ => {}
+            }
+        }
+
+        if Some(state.lowlink[v]) == state.index[v] {
+            let mut component = Vec::new();
+            while let Some(w) = state.stack.pop() {
+                state.on_stack[w] = false;
+                component.push(state.nodes[w].clone());
+                if w == v {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let position: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.as_str(), i))
+        .collect();
+    let mut adjacency = vec![Vec::new(); nodes.len()];
+    for (from, to) in edges {
+        if let (Some(&a), Some(&b)) = (position.get(from.as_str()), position.get(to.as_str())) {
+            adjacency[a].push(b);
+        }
+    }
+    let mut state = State {
+        adjacency,
+        nodes,
+        index: vec![None; nodes.len()],
+        lowlink: vec![0; nodes.len()],
+        on_stack: vec![false; nodes.len()],
+        stack: Vec::new(),
+        next: 0,
+        components: Vec::new(),
+    };
+    for v in 0..nodes.len() {
+        if state.index[v].is_none() {
+            visit(&mut state, v);
+        }
+    }
+    state.components
+}
+
+fn label(file: &CodeAnalysis) -> String {
+    file.module_path
+        .clone()
+        .unwrap_or_else(|| file.file_path.display().to_string())
+}
+
+fn strength(count: usize) -> &'static str {
+    match count {
+        0..=2 => "low",
+        3..=9 => "medium",
+        _ => "high",
+    }
+}
+
+/// Relationships between files: trait implementations, calls and imports. Each entry carries the
+/// index of the file the relationship starts from.
+pub(crate) fn cross_file_relationships(
+    files: &[CodeAnalysis],
+    graph: &DependencyGraph,
+) -> Vec<(usize, CodeRelationship)> {
+    let mut traits: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut free_functions: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut methods: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut module_files: HashMap<&str, usize> = HashMap::new();
+    for (i, file) in files.iter().enumerate() {
+        for t in file.structure.types.iter().filter(|t| t.kind == "trait") {
+            traits.entry(t.name.as_str()).or_default().push(i);
+        }
+        for f in &file.structure.functions {
+            match &f.owner {
+                Some(owner) => methods.entry(format!("{}::{}", owner, f.name)).or_default().push(i),
+                None if f.visibility != "private" => {
+                    free_functions.entry(f.name.as_str()).or_default().push(i)
+                }
+                None => {}
+            }
+        }
+        if let Some(module) = &file.module_path {
+            module_files.insert(module.as_str(), i);
+        }
+    }
+    let single_other = |candidates: Option<&Vec<usize>>, i: usize| -> Option<usize> {
+        let others: Vec<usize> = candidates?.iter().copied().filter(|j| *j != i).collect();
+        (others.len() == 1).then(|| others[0])
+    };
+
+    let mut relationships = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        let mut implements: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for imp in &file.structure.impls {
+            let Some(trait_name) = &imp.trait_name else {
+                continue;
+            };
+            let trait_base = base_name(trait_name);
+            if let Some(j) = single_other(traits.get(trait_base.as_str()), i) {
+                implements
+                    .entry(j)
+                    .or_default()
+                    .push(format!("{}: {}", base_name(&imp.self_type), trait_base));
+            }
+        }
+        for (j, items) in implements {
+            relationships.push((
+                i,
+                CodeRelationship {
+                    relationship_type: "implements".to_string(),
+                    related_to: files[j].file_path.display().to_string(),
+                    description: format!(
+                        "{} implements traits from {}: {}",
+                        label(file),
+                        label(&files[j]),
+                        items.join(", ")
+                    ),
+                    strength: "high".to_string(),
+                },
+            ));
+        }
+
+        let mut calls: BTreeMap<usize, (usize, BTreeSet<String>)> = BTreeMap::new();
+        for edge in file.flow.call_graph.iter().filter(|e| e.kind == "call") {
+            let segments: Vec<&str> = edge.callee.split("::").collect();
+            let name = segments[segments.len() - 1];
+            let owner = (segments.len() >= 2).then(|| segments[segments.len() - 2]);
+            let target = match owner {
+                Some(owner) if owner != "Self" && owner.starts_with(char::is_uppercase) => {
+                    single_other(methods.get(&format!("{}::{}", owner, name)), i)
+                }
+                _ => single_other(free_functions.get(name), i),
+            };
+            if let Some(j) = target {
+                let entry = calls.entry(j).or_default();
+                entry.0 += 1;
+                entry.1.insert(edge.callee.clone());
+            }
+        }
+        for (j, (count, callees)) in calls {
+            let names: Vec<String> = callees.into_iter().take(8).collect();
+            relationships.push((
+                i,
+                CodeRelationship {
+                    relationship_type: "calls".to_string(),
+                    related_to: files[j].file_path.display().to_string(),
+                    description: format!(
+                        "{} calls into {} ({} call sites): {}",
+                        label(file),
+                        label(&files[j]),
+                        count,
+                        names.join(", ")
+                    ),
+                    strength: strength(count).to_string(),
+                },
+            ));
+        }
+
+        if let Some(module) = &file.module_path {
+            for (_, target) in graph.edges.iter().filter(|(from, _)| from == module) {
+                let Some(&j) = module_files.get(target.as_str()) else {
+                    continue;
+                };
+                let count = file
+                    .dependencies
+                    .internal
+                    .iter()
+                    .chain(&file.dependencies.external)
+                    .filter(|d| d.name == *target || d.name.starts_with(&format!("{}::", target)))
+                    .count();
+                relationships.push((
+                    i,
+                    CodeRelationship {
+                        relationship_type: "imports".to_string(),
+                        related_to: files[j].file_path.display().to_string(),
+                        description: format!("{} imports {} item(s) from {}", module, count, target),
+                        strength: strength(count).to_string(),
+                    },
+                ));
+            }
+        }
+    }
+    relationships
+}
+
+/// Components, dependency layers and recurring design patterns of the analyzed code.
+pub(crate) fn architecture(
+    files: &[CodeAnalysis],
+    graph: &DependencyGraph,
+    cycles: &[Vec<String>],
+) -> ArchitectureAnalysis {
+    // Rust modules group by crate; path-named modules by their directory.
+    let crate_of = |module: &str| match module.split_once("::") {
+        Some((krate, _)) => krate.to_string(),
+        None if module.contains('/') => Path::new(module)
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| module.to_string()),
+        None => module.to_string(),
+    };
+
+    let mut components: BTreeMap<String, (usize, usize, usize)> = BTreeMap::new();
+    for file in files {
+        let Some(module) = &file.module_path else {
+            continue;
+        };
+        let entry = components.entry(crate_of(module)).or_default();
+        entry.0 += 1;
+        entry.1 += file.structure.functions.len();
+        entry.2 += file.structure.types.len();
+    }
+
+    // Layer by crate when several crates are involved, by module otherwise.
+    let by_crate = components.len() > 1;
+    let unit = |module: &str| if by_crate { crate_of(module) } else { module.to_string() };
+    let mut dependencies: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for node in &graph.nodes {
+        dependencies.entry(unit(node)).or_default();
+    }
+    for (from, to) in &graph.edges {
+        let (from, to) = (unit(from), unit(to));
+        if from != to {
+            dependencies.entry(from).or_default().insert(to);
+        }
+    }
+    let mut depth: HashMap<String, usize> = HashMap::new();
+    fn layer_of(
+        node: &str,
+        dependencies: &BTreeMap<String, BTreeSet<String>>,
+        depth: &mut HashMap<String, usize>,
+        visiting: &mut BTreeSet<String>,
+    ) -> usize {
+        if let Some(d) = depth.get(node) {
+            return *d;
+        }
+        // Members of a cycle share a layer.
+        if !visiting.insert(node.to_string()) {
+            return 0;
+        }
+        let d = dependencies
+            .get(node)
+            .map(|deps| {
+                deps.iter()
+                    .map(|dep| layer_of(dep, dependencies, depth, visiting) + 1)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0);
+        visiting.remove(node);
+        depth.insert(node.to_string(), d);
+        d
+    }
+    let mut layers: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for node in dependencies.keys() {
+        let d = layer_of(node, &dependencies, &mut depth, &mut BTreeSet::new());
+        layers.entry(d).or_default().push(node.clone());
+    }
+
+    let mut implementors: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let defined_traits: BTreeSet<&str> = files
+        .iter()
+        .flat_map(|f| f.structure.types.iter())
+        .filter(|t| t.kind == "trait")
+        .map(|t| t.name.as_str())
+        .collect();
+    let mut patterns = Vec::new();
+    for file in files {
+        for imp in &file.structure.impls {
+            if let Some(trait_name) = &imp.trait_name {
+                let trait_base = base_name(trait_name);
+                if defined_traits.contains(trait_base.as_str()) {
+                    implementors.entry(trait_base).or_default().insert(base_name(&imp.self_type));
+                }
+            }
+        }
+        for t in &file.structure.types {
+            if t.methods.iter().any(|m| m == "build") && t.methods.iter().any(|m| m.starts_with("with_")) {
+                patterns.push(format!("Builder: `{}`", t.name));
+            }
+        }
+        let re_exports = file
+            .structure
+            .imports
+            .iter()
+            .filter(|i| is_re_export(&i.import_type))
+            .count();
+        if re_exports >= 3 {
+            patterns.push(format!("Facade: `{}` re-exports {} items", label(file), re_exports));
+        }
+    }
+    for (trait_name, types) in implementors {
+        if types.len() >= 2 {
+            patterns.push(format!(
+                "Trait polymorphism: `{}` implemented by {}",
+                trait_name,
+                types.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
+    }
+    if !cycles.is_empty() {
+        patterns.push(format!("{} module dependency cycle(s)", cycles.len()));
+    }
+
+    ArchitectureAnalysis {
+        layers: layers
+            .into_iter()
+            .map(|(d, nodes)| format!("Layer {}: {}", d, nodes.join(", ")))
+            .collect(),
+        components: components
+            .into_iter()
+            .map(|(name, (files, functions, types))| {
+                format!("{}: {} files, {} functions, {} types", name, files, functions, types)
+            })
+            .collect(),
+        patterns,
+    }
+}
+
+/// `foo::Bar<T>` -> `Bar`, `&mut Baz` -> `Baz`.
+pub(crate) fn base_name(ty: &str) -> String {
+    let ty = ty.trim_start_matches(['&', '!']).trim_start_matches("mut ").trim();
+    let without_generics = ty.split('<').next().unwrap_or(ty);
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+        .trim()
+        .to_string()
+}
@@ -16,11 +16,31 @@
 //! - Provides comprehensive codebase understanding
 
 pub mod master_orchestrator;
+pub mod report;
+pub mod symbols;
+pub mod watch;
+mod cache;
+mod graph;
+mod metrics;
+#[cfg(feature = "rust-analysis")]
+mod rust_analysis;
+#[cfg(any(
+    feature = "python-analysis",
+    feature = "javascript-analysis",
+    feature = "typescript-analysis",
+    feature = "go-analysis",
+    feature = "java-analysis"
+))]
+mod tree_sitter_analysis;
 
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use cache::AnalysisCache;
 use error_types::PhoenixError;
+use graph::ModuleLocation;
 use llm_orchestrator::LLMOrchestrator;
+use metrics::SyntaxFacts;
 
 /// Comprehensive code analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +71,10 @@ pub struct CodeAnalysis {
 
     /// Context and relationships
     pub context: CodeContext,
+
+    /// Module path (`crate_name::a::b`) for Rust files inside a crate
+    #[serde(default)]
+    pub module_path: Option<String>,
 }
 
 /// Programming language detected
@@ -93,6 +117,14 @@ pub struct CodeStructure {
 
     /// Complexity metrics
     pub complexity: ComplexityMetrics,
+
+    /// Impl blocks (inherent and trait implementations)
+    #[serde(default)]
+    pub impls: Vec<ImplDefinition>,
+
+    /// Macro definitions
+    #[serde(default)]
+    pub macros: Vec<MacroDefinition>,
 }
 
 /// Function definition with full context
@@ -130,6 +162,14 @@ pub struct FunctionDefinition {
 
     /// Complexity score
     pub complexity: usize,
+
+    /// Owning type or trait for methods
+    #[serde(default)]
+    pub owner: Option<String>,
+
+    /// Generic parameters and where-clause predicates
+    #[serde(default)]
+    pub generics: Vec<String>,
 }
 
 /// Type definition (class, struct, enum, etc.)
@@ -158,6 +198,52 @@ pub struct TypeDefinition {
 
     /// Purpose and intent
     pub intent: String,
+
+    /// Generic parameters and where-clause predicates
+    #[serde(default)]
+    pub generics: Vec<String>,
+
+    /// Traits implemented (supertraits for traits)
+    #[serde(default)]
+    pub implements: Vec<String>,
+}
+
+/// Impl block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImplDefinition {
+    /// Implementing type as written (`Foo<T>`)
+    pub self_type: String,
+
+    /// Implemented trait, if any
+    pub trait_name: Option<String>,
+
+    /// Generic parameters and where-clause predicates
+    pub generics: Vec<String>,
+
+    /// Methods defined in the block
+    pub methods: Vec<String>,
+
+    /// Line range
+    pub line_range: (usize, usize),
+}
+
+/// Macro definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroDefinition {
+    /// Macro name
+    pub name: String,
+
+    /// Kind (macro_rules, proc_macro, derive, attribute)
+    pub kind: String,
+
+    /// Exported from the crate
+    pub exported: bool,
+
+    /// Documentation
+    pub documentation: Option<String>,
+
+    /// Line range
+    pub line_range: (usize, usize),
 }
 
 /// Module definition
@@ -174,6 +260,10 @@ pub struct ModuleDefinition {
 
     /// Documentation
     pub documentation: Option<String>,
+
+    /// Body is defined in this file (`mod x { ... }`) rather than in its own file
+    #[serde(default)]
+    pub inline: bool,
 }
 
 /// Constant definition
@@ -364,7 +454,7 @@ pub struct UsageLocation {
 }
 
 /// Dependency graph
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DependencyGraph {
     /// Nodes (files/modules)
     pub nodes: Vec<String>,
@@ -393,6 +483,26 @@ pub struct CodeFlow {
 
     /// Side effects
     pub side_effects: Vec<String>,
+
+    /// Call sites, in source order
+    #[serde(default)]
+    pub call_graph: Vec<CallEdge>,
+}
+
+/// One call site
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallEdge {
+    /// Calling function (`Type::method` for methods)
+    pub caller: String,
+
+    /// Callee as written (`foo`, `Self::bar`, `module::baz`, `format!`)
+    pub callee: String,
+
+    /// Line number
+    pub line: usize,
+
+    /// Call kind (call, method, macro)
+    pub kind: String,
 }
 
 /// Control flow path
@@ -476,6 +586,10 @@ pub struct CodeSmell {
 
     /// Severity (low, medium, high)
     pub severity: String,
+
+    /// Line number
+    #[serde(default)]
+    pub line: Option<usize>,
 }
 
 /// Code context
@@ -507,9 +621,20 @@ pub struct CodeContext {
 /// - Deep semantic analysis via LLM
 /// - Cross-file dependency analysis
 /// - Full context interpretation
+///
+/// Per-file results are cached on disk by content hash when `CODE_ANALYSIS_CACHE_DIR` is set (or
+/// `with_cache_dir` is used), and codebases are analyzed by a bounded pool of workers
+/// (`CODE_ANALYSIS_WORKERS`, default: one per CPU). The cache key covers only the file's own
+/// content: adding, removing or editing a sibling or dependency (e.g. a `mod` file) does not
+/// invalidate its entry, so the module location stays stale until the file itself changes.
+#[derive(Clone)]
 pub struct CodeAnalyzer {
     /// LLM orchestrator for deep semantic analysis
-    llm_orchestrator: Option<LLMOrchestrator>,
+    llm_orchestrator: Option<Arc<LLMOrchestrator>>,
+    /// Per-file analysis cache
+    cache: Option<AnalysisCache>,
+    /// Files analyzed concurrently by `analyze_codebase`
+    workers: usize,
 }
 
 impl CodeAnalyzer {
@@ -517,51 +642,123 @@ impl CodeAnalyzer {
     /// 
     /// Master Orchestrator has full system access by default
     pub fn new() -> Self {
-        Self {
-            llm_orchestrator: None,
-        }
+        Self::from_env(None)
     }
 
     /// Create a new code analyzer with LLM support for deep semantic analysis
     /// 
     /// This enables full context understanding, not just definition listing
     pub fn with_llm(llm_orchestrator: LLMOrchestrator) -> Self {
+        Self::from_env(Some(Arc::new(llm_orchestrator)))
+    }
+
+    fn from_env(llm_orchestrator: Option<Arc<LLMOrchestrator>>) -> Self {
+        let cache = std::env::var("CODE_ANALYSIS_CACHE_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(AnalysisCache::new);
+        let workers = std::env::var("CODE_ANALYSIS_WORKERS")
+            .ok()
+            .and_then(|n| n.trim().parse().ok())
+            .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4));
+        Self {
+            llm_orchestrator,
+            cache,
+            workers,
+        }
+    }
+
+    /// Cache per-file analyses in `dir`, keyed by file content (not by the files it depends on)
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache = Some(AnalysisCache::new(dir));
+        self
+    }
+
+    /// Analyze at most `workers` files at a time
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// This analyzer without the LLM: structural analysis only, sharing the cache and workers
+    pub fn structural(&self) -> Self {
         Self {
-            llm_orchestrator: Some(llm_orchestrator),
+            llm_orchestrator: None,
+            ..self.clone()
         }
     }
 
     /// Perform deep semantic analysis on a file
     ///
     /// This is the main entry point that provides full code understanding,
-    /// not just listing definition names.
+    /// not just listing definition names. Unchanged files are served from the cache.
     pub async fn analyze_file(&self, file_path: &Path) -> Result<CodeAnalysis, PhoenixError> {
         // Read the file content
         let content = self.read_file_content(file_path).await?;
 
+        let Some(cache) = &self.cache else {
+            return self.analyze_content(file_path, &content).await;
+        };
+        let hash = cache::content_hash(&content);
+        let semantic = self.llm_orchestrator.is_some();
+        if let Some(analysis) = cache.get(file_path, &hash, semantic) {
+            return Ok(analysis);
+        }
+        let analysis = self.analyze_content(file_path, &content).await?;
+        if let Err(e) = cache.put(file_path, &hash, semantic, &analysis) {
+            tracing::warn!("Code analysis cache: {}", e);
+        }
+        Ok(analysis)
+    }
+
+    /// Run the full pipeline on a file's content
+    async fn analyze_content(&self, file_path: &Path, content: &str) -> Result<CodeAnalysis, PhoenixError> {
         // Detect language
-        let language = self.detect_language(file_path, &content)?;
+        let language = self.detect_language(file_path, content)?;
 
         // Analyze structure
-        let structure = self.analyze_structure(&content, language).await?;
+        let (structure, facts) = self.analyze_structure(content, language).await?;
 
         // Perform semantic analysis
-        let semantics = self.analyze_semantics(&content, &structure, language).await?;
+        let semantics = self.analyze_semantics(content, &structure, language).await?;
 
         // Analyze intent
-        let intent = self.analyze_intent(&content, &structure, &semantics).await?;
+        let intent = self.analyze_intent(content, &structure, &semantics).await?;
+
+        // Locate the file in its crate (module-level dependencies)
+        let location = match language {
+            Language::Rust => ModuleLocation::of(file_path),
+            _ => None,
+        };
+        let module_path = match language {
+            Language::Python | Language::JavaScript | Language::TypeScript => {
+                Some(graph::file_module(file_path))
+            }
+            _ => location.as_ref().map(|l| l.name()),
+        };
 
         // Analyze dependencies
-        let dependencies = self.analyze_dependencies(&content, &structure, file_path).await?;
+        let dependencies = self
+            .analyze_dependencies(
+                &structure,
+                &facts,
+                file_path,
+                language,
+                location.as_ref(),
+                module_path.as_deref(),
+            )
+            .await?;
 
         // Analyze code flow
-        let flow = self.analyze_flow(&content, &structure).await?;
+        let flow = self.analyze_flow(&structure, &facts).await?;
 
         // Calculate quality metrics
-        let quality = self.calculate_quality(&content, &structure, &semantics).await?;
+        let quality = self.calculate_quality(content, &structure, &facts).await?;
 
         // Build context
-        let context = self.build_context(file_path, &dependencies).await?;
+        let context = self
+            .build_context(&structure, &facts, file_path, language, location.as_ref(), &dependencies)
+            .await?;
 
         Ok(CodeAnalysis {
             file_path: file_path.to_path_buf(),
@@ -573,31 +770,100 @@ impl CodeAnalyzer {
             flow,
             quality,
             context,
+            module_path,
         })
     }
 
     /// Analyze entire codebase (recursive)
     pub async fn analyze_codebase(&self, root_path: &Path) -> Result<CodebaseAnalysis, PhoenixError> {
-        let mut files = Vec::new();
-        let mut errors = Vec::new();
+        let paths = self.code_files(root_path);
+        let (files, errors) = self.analyze_files(paths).await;
+        self.assemble_codebase(root_path, files, errors).await
+    }
 
-        // Walk directory tree
-        for entry in walkdir::WalkDir::new(root_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.is_file() && self.is_code_file(path) {
-                match self.analyze_file(path).await {
-                    Ok(analysis) => files.push(analysis),
-                    Err(e) => errors.push((path.to_path_buf(), e.to_string())),
+    /// Analyze `root_path` and keep re-analyzing the files that change under it
+    pub async fn watch_codebase(&self, root_path: &Path) -> Result<watch::CodebaseWatcher, PhoenixError> {
+        watch::CodebaseWatcher::start(self.clone(), root_path).await
+    }
+
+    /// Source files under `root`, honouring `.gitignore`/`.ignore` files and skipping hidden,
+    /// build output (`target/`) and dependency (`node_modules/`) directories and the cache
+    pub fn code_files(&self, root: &Path) -> Vec<PathBuf> {
+        // Compared canonically: the cache dir may be given relative or through a symlink.
+        let cache_dir = self.cache.as_ref().and_then(|c| c.dir().canonicalize().ok());
+        let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                if entry.depth() == 0 || !entry.file_type().is_some_and(|t| t.is_dir()) {
+                    return true;
                 }
+                let excluded_dir = matches!(entry.file_name().to_str(), Some("target" | "node_modules"));
+                let is_cache = cache_dir
+                    .as_ref()
+                    .is_some_and(|dir| entry.path().canonicalize().is_ok_and(|p| p == *dir));
+                !excluded_dir && !is_cache
+            })
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()) && self.is_code_file(e.path()))
+            .map(|e| e.into_path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Analyze `paths` on at most `workers` tasks at a time. Results are sorted by path.
+    async fn analyze_files(&self, paths: Vec<PathBuf>) -> (Vec<CodeAnalysis>, Vec<(PathBuf, String)>) {
+        let permits = Arc::new(tokio::sync::Semaphore::new(self.workers.max(1)));
+        let mut tasks = tokio::task::JoinSet::new();
+        for path in paths {
+            let analyzer = self.clone();
+            let permits = permits.clone();
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let result = analyzer.analyze_file(&path).await;
+                (path, result)
+            });
+        }
+
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((_, Ok(analysis))) => files.push(analysis),
+                Ok((path, Err(e))) => errors.push((path, e.to_string())),
+                Err(e) => errors.push((PathBuf::new(), format!("Analysis task failed: {}", e))),
             }
         }
+        files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        errors.sort();
+        (files, errors)
+    }
 
-        // Build codebase-level analysis
-        let relationships = self.analyze_cross_file_relationships(&files).await?;
-        let architecture = self.analyze_architecture(&files).await?;
+    /// Codebase-level analysis (module graph, cycles, relationships, architecture) over
+    /// per-file analyses
+    async fn assemble_codebase(
+        &self,
+        root_path: &Path,
+        mut files: Vec<CodeAnalysis>,
+        errors: Vec<(PathBuf, String)>,
+    ) -> Result<CodebaseAnalysis, PhoenixError> {
+        let (dependency_graph, circular_dependencies) = graph::module_graph(&files);
+        for file in &mut files {
+            if let Some(module) = &file.module_path {
+                file.dependencies.circular = circular_dependencies
+                    .iter()
+                    .filter(|cycle| cycle.contains(module))
+                    .cloned()
+                    .collect();
+            }
+        }
+        let relationships = self
+            .analyze_cross_file_relationships(&mut files, &dependency_graph)
+            .await?;
+        let architecture = self
+            .analyze_architecture(&files, &dependency_graph, &circular_dependencies)
+            .await?;
 
         Ok(CodebaseAnalysis {
             root_path: root_path.to_path_buf(),
@@ -605,6 +871,8 @@ impl CodeAnalyzer {
             relationships,
             architecture,
             errors,
+            dependency_graph,
+            circular_dependencies,
         })
     }
 
@@ -626,7 +894,7 @@ impl CodeAnalyzer {
         Ok(match ext {
             "rs" => Language::Rust,
             "py" => Language::Python,
-            "js" => Language::JavaScript,
+            "js" | "jsx" | "mjs" | "cjs" => Language::JavaScript,
             "ts" | "tsx" => Language::TypeScript,
             "java" => Language::Java,
             "cpp" | "cc" | "cxx" => Language::Cpp,
@@ -641,137 +909,69 @@ impl CodeAnalyzer {
         &self,
         content: &str,
         language: Language,
-    ) -> Result<CodeStructure, PhoenixError> {
+    ) -> Result<(CodeStructure, SyntaxFacts), PhoenixError> {
         // Language-specific parsing
         match language {
             Language::Rust => self.analyze_rust_structure(content).await,
-            Language::Python => self.analyze_python_structure(content).await,
-            Language::JavaScript | Language::TypeScript => {
-                self.analyze_javascript_structure(content).await
-            }
+            Language::Python
+            | Language::JavaScript
+            | Language::TypeScript
+            | Language::Go
+            | Language::Java => self.analyze_tree_sitter_structure(content, language).await,
             _ => self.analyze_generic_structure(content).await,
         }
     }
 
-    /// Analyze Rust code structure
+    /// Analyze Rust code structure: items, impl blocks, traits, generics, macros, per-function
+    /// complexity and call sites
     #[cfg(feature = "rust-analysis")]
-    async fn analyze_rust_structure(&self, content: &str) -> Result<CodeStructure, PhoenixError> {
-        #[cfg(feature = "rust-analysis")]
-        use syn::{File, Item};
-
-        let ast: File = syn::parse_str(content)
-            .map_err(|e| PhoenixError::Other(format!("Failed to parse Rust: {}", e)))?;
-
-        let mut functions = Vec::new();
-        let mut types = Vec::new();
-        let mut modules = Vec::new();
-        let mut constants = Vec::new();
-        let mut imports = Vec::new();
-
-        for item in ast.items {
-            match item {
-                Item::Fn(f) => {
-                    let name = f.sig.ident.to_string();
-                    let signature = quote::quote!(#f.sig).to_string();
-                    functions.push(FunctionDefinition {
-                        name,
-                        signature,
-                        parameters: Vec::new(), // Would be filled with proper AST parsing
-                        return_type: None, // Would be filled with proper AST parsing
-                        visibility: format!("{:?}", f.vis),
-                        documentation: extract_doc_comment(&f.attrs),
-                        line_range: (0, 0), // Would need span info
-                        body: String::new(), // Would be filled with proper AST parsing
-                        intent: String::new(), // Filled in semantic analysis
-                        internal_dependencies: Vec::new(),
-                        complexity: 0,
-                    });
-                }
-                Item::Struct(s) => {
-                    types.push(TypeDefinition {
-                        name: s.ident.to_string(),
-                        kind: "struct".to_string(),
-                        members: Vec::new(),
-                        methods: Vec::new(),
-                        documentation: extract_doc_comment(&s.attrs),
-                        line_range: (0, 0),
-                        source: String::new(), // Would be filled with proper AST parsing
-                        intent: String::new(),
-                    });
-                }
-                Item::Enum(e) => {
-                    types.push(TypeDefinition {
-                        name: e.ident.to_string(),
-                        kind: "enum".to_string(),
-                        members: Vec::new(),
-                        methods: Vec::new(),
-                        documentation: extract_doc_comment(&e.attrs),
-                        line_range: (0, 0),
-                        source: String::new(), // Would be filled with proper AST parsing
-                        intent: String::new(),
-                    });
-                }
-                Item::Mod(m) => {
-                    modules.push(ModuleDefinition {
-                        name: m.ident.to_string(),
-                        path: m.ident.to_string(),
-                        exports: Vec::new(),
-                        documentation: extract_doc_comment(&m.attrs),
-                    });
-                }
-                Item::Const(c) => {
-                    constants.push(ConstantDefinition {
-                        name: c.ident.to_string(),
-                        value: String::new(), // Would be filled with proper AST parsing
-                        type_hint: None, // Would be filled with proper AST parsing
-                        documentation: extract_doc_comment(&c.attrs),
-                        line: 0,
-                    });
-                }
-                Item::Use(u) => {
-                    imports.push(ImportDefinition {
-                        path: String::new(), // Would be filled with proper AST parsing
-                        items: Vec::new(),
-                        import_type: "use".to_string(),
-                        line: 0,
-                    });
-                }
-                _ => {}
-            }
-        }
-
-        Ok(CodeStructure {
-            functions,
-            types,
-            modules,
-            constants,
-            imports,
-            documentation: None,
-            lines_of_code: content.lines().count(),
-            complexity: ComplexityMetrics {
-                cyclomatic: 0,
-                cognitive: 0,
-                max_nesting: 0,
-                branches: 0,
-            },
-        })
+    async fn analyze_rust_structure(
+        &self,
+        content: &str,
+    ) -> Result<(CodeStructure, SyntaxFacts), PhoenixError> {
+        rust_analysis::analyze(content).map_err(PhoenixError::Other)
     }
 
     #[cfg(not(feature = "rust-analysis"))]
-    async fn analyze_rust_structure(&self, content: &str) -> Result<CodeStructure, PhoenixError> {
+    async fn analyze_rust_structure(
+        &self,
+        content: &str,
+    ) -> Result<(CodeStructure, SyntaxFacts), PhoenixError> {
         self.analyze_generic_structure(content).await
     }
 
-    /// Analyze Python code structure
-    async fn analyze_python_structure(&self, content: &str) -> Result<CodeStructure, PhoenixError> {
+    /// Analyze Python, JavaScript/TypeScript, Go or Java code structure with the tree-sitter
+    /// grammar enabled for the language, falling back to the regex scan without one
+    #[cfg(any(
+        feature = "python-analysis",
+        feature = "javascript-analysis",
+        feature = "typescript-analysis",
+        feature = "go-analysis",
+        feature = "java-analysis"
+    ))]
+    async fn analyze_tree_sitter_structure(
+        &self,
+        content: &str,
+        language: Language,
+    ) -> Result<(CodeStructure, SyntaxFacts), PhoenixError> {
+        if tree_sitter_analysis::supports(language) {
+            return tree_sitter_analysis::analyze(content, language).map_err(PhoenixError::Other);
+        }
         self.analyze_generic_structure(content).await
     }
 
-    /// Analyze JavaScript/TypeScript code structure
-    async fn analyze_javascript_structure(
+    #[cfg(not(any(
+        feature = "python-analysis",
+        feature = "javascript-analysis",
+        feature = "typescript-analysis",
+        feature = "go-analysis",
+        feature = "java-analysis"
+    )))]
+    async fn analyze_tree_sitter_structure(
         &self,
         content: &str,
-    ) -> Result<CodeStructure, PhoenixError> {
+        _language: Language,
+    ) -> Result<(CodeStructure, SyntaxFacts), PhoenixError> {
         self.analyze_generic_structure(content).await
     }
 
@@ -779,7 +979,7 @@ impl CodeAnalyzer {
     async fn analyze_generic_structure(
         &self,
         content: &str,
-    ) -> Result<CodeStructure, PhoenixError> {
+    ) -> Result<(CodeStructure, SyntaxFacts), PhoenixError> {
         use regex::Regex;
 
         let mut functions = Vec::new();
@@ -805,6 +1005,8 @@ impl CodeAnalyzer {
                         intent: String::new(),
                         internal_dependencies: Vec::new(),
                         complexity: 0,
+                        owner: None,
+                        generics: Vec::new(),
                     });
                 }
             }
@@ -825,7 +1027,7 @@ impl CodeAnalyzer {
             }
         }
 
-        Ok(CodeStructure {
+        let structure = CodeStructure {
             functions,
             types,
             modules: Vec::new(),
@@ -839,7 +1041,10 @@ impl CodeAnalyzer {
                 max_nesting: 0,
                 branches: 0,
             },
-        })
+            impls: Vec::new(),
+            macros: Vec::new(),
+        };
+        Ok((structure, SyntaxFacts::default()))
     }
 
     /// Perform deep semantic analysis using LLM for full context understanding
@@ -1169,124 +1374,269 @@ Respond in JSON format."#,
         }
     }
 
-    /// Analyze dependencies
+    /// Analyze dependencies: imports split into internal (resolved to modules of the same crate)
+    /// and external, with usage locations, plus this file's module-level dependency edges
     async fn analyze_dependencies(
         &self,
-        _content: &str,
         structure: &CodeStructure,
-        _file_path: &Path,
+        facts: &SyntaxFacts,
+        file_path: &Path,
+        language: Language,
+        location: Option<&ModuleLocation>,
+        module: Option<&str>,
     ) -> Result<Dependencies, PhoenixError> {
-        let external: Vec<Dependency> = structure
-            .imports
-            .iter()
-            .map(|i| Dependency {
-                name: i.path.clone(),
-                dependency_type: i.import_type.clone(),
-                version: None,
-                usage_locations: Vec::new(),
-                purpose: None,
-            })
-            .collect();
+        let own = module.map(str::to_string);
+        let mut external = Vec::new();
+        let mut internal: Vec<Dependency> = Vec::new();
+        let mut targets: Vec<String> = Vec::new();
+        let mut add_target = |module: String, import_type: &str| {
+            if !graph::is_re_export(import_type) && Some(&module) != own.as_ref() && !targets.contains(&module) {
+                targets.push(module);
+            }
+        };
+
+        for import in &structure.imports {
+            let usage_locations = import
+                .items
+                .iter()
+                .flat_map(|item| graph::usage_locations(structure, item))
+                .collect();
+            let resolved = location.and_then(|l| Some((l, l.resolve(&import.path)?)));
+            if let Some((location, (module, _))) = resolved {
+                add_target(module, &import.import_type);
+                internal.push(Dependency {
+                    name: location.absolute(&import.path).join("::"),
+                    dependency_type: import.import_type.clone(),
+                    version: None,
+                    usage_locations,
+                    purpose: None,
+                });
+            } else if let Some(file) = graph::resolve_relative_import(file_path, language, &import.path) {
+                // Relative Python/JavaScript/TypeScript imports name a file of this codebase.
+                let module = graph::file_module(&file);
+                add_target(module.clone(), &import.import_type);
+                internal.push(Dependency {
+                    name: module,
+                    dependency_type: import.import_type.clone(),
+                    version: None,
+                    usage_locations,
+                    purpose: None,
+                });
+            } else {
+                external.push(Dependency {
+                    name: import.path.clone(),
+                    dependency_type: import.import_type.clone(),
+                    version: None,
+                    usage_locations,
+                    purpose: None,
+                });
+            }
+        }
+
+        // Inline paths (`crate::a::f()`) depend on modules without a `use`.
+        if let Some(location) = location {
+            for (path, line, context) in &facts.references {
+                let Some((module, _)) = location.resolve(path) else {
+                    continue;
+                };
+                add_target(module, "path");
+                let name = location.absolute(path).join("::");
+                let usage = UsageLocation {
+                    context: context.clone(),
+                    line: *line,
+                    usage_type: "path".to_string(),
+                };
+                match internal.iter_mut().find(|d| d.name == name) {
+                    Some(dependency) => dependency.usage_locations.push(usage),
+                    None => internal.push(Dependency {
+                        name,
+                        dependency_type: "path".to_string(),
+                        version: None,
+                        usage_locations: vec![usage],
+                        purpose: None,
+                    }),
+                }
+            }
+        }
+
+        let graph = match own {
+            Some(own) => DependencyGraph {
+                nodes: std::iter::once(own.clone()).chain(targets.iter().cloned()).collect(),
+                edges: targets.into_iter().map(|t| (own.clone(), t)).collect(),
+            },
+            None => DependencyGraph::default(),
+        };
 
         Ok(Dependencies {
             external,
-            internal: Vec::new(),
-            graph: DependencyGraph {
-                nodes: Vec::new(),
-                edges: Vec::new(),
-            },
+            internal,
+            graph,
+            // Cycles span files; filled in by `analyze_codebase`.
             circular: Vec::new(),
         })
     }
 
-    /// Analyze code flow
+    /// Analyze code flow: call graph, per-function control flow, parameter data flow, entry and
+    /// exit points and side effects
     async fn analyze_flow(
         &self,
-        content: &str,
         structure: &CodeStructure,
+        facts: &SyntaxFacts,
     ) -> Result<CodeFlow, PhoenixError> {
-        let _ = content; // Acknowledge parameter usage
+        // Without parser facts only the function list is known.
+        if facts.functions.len() != structure.functions.len() || facts.functions.is_empty() {
+            return Ok(CodeFlow {
+                execution_flow: format!(
+                    "Code contains {} functions that can be called independently",
+                    structure.functions.len()
+                ),
+                control_flow: Vec::new(),
+                data_flow: Vec::new(),
+                entry_points: structure.functions.iter().map(|f| f.name.clone()).collect(),
+                exit_points: Vec::new(),
+                side_effects: Vec::new(),
+                call_graph: Vec::new(),
+            });
+        }
+
+        let control_flow: Vec<ControlFlowPath> = structure
+            .functions
+            .iter()
+            .zip(&facts.functions)
+            .filter(|(f, _)| !f.body.is_empty())
+            .map(|(f, fact)| ControlFlowPath {
+                description: format!("{} (cyclomatic complexity {})", fact.name, f.complexity),
+                conditions: fact.conditions.clone(),
+                functions_called: f.internal_dependencies.clone(),
+            })
+            .collect();
+        let entry_points: Vec<String> = facts
+            .functions
+            .iter()
+            .filter(|f| f.is_entry)
+            .map(|f| f.name.clone())
+            .collect();
+
         Ok(CodeFlow {
             execution_flow: format!(
-                "Code contains {} functions that can be called independently",
-                structure.functions.len()
+                "{} functions with {} call sites; {} entry point(s){}",
+                structure.functions.len(),
+                facts.call_graph.len(),
+                entry_points.len(),
+                if entry_points.iter().any(|e| e == "main") {
+                    ", execution starts at main"
+                } else {
+                    ""
+                }
             ),
-            control_flow: Vec::new(),
-            data_flow: Vec::new(),
-            entry_points: structure
-                .functions
-                .iter()
-                .map(|f| f.name.clone())
-                .collect(),
-            exit_points: Vec::new(),
-            side_effects: Vec::new(),
+            control_flow,
+            data_flow: facts.data_flow.clone(),
+            entry_points,
+            exit_points: facts.exits.clone(),
+            side_effects: facts.side_effects.clone(),
+            call_graph: facts.call_graph.clone(),
         })
     }
 
-    /// Calculate quality metrics
+    /// Calculate quality metrics: maintainability index, readability and code smells
     async fn calculate_quality(
         &self,
         content: &str,
         structure: &CodeStructure,
-        _semantics: &SemanticAnalysis,
+        facts: &SyntaxFacts,
     ) -> Result<QualityMetrics, PhoenixError> {
-        // Basic quality metrics
-        let maintainability = if structure.functions.len() < 20 {
-            80.0
-        } else {
-            60.0
-        };
-
-        let readability = if content.lines().count() < 1000 {
-            85.0
-        } else {
-            70.0
-        };
-
-        Ok(QualityMetrics {
-            maintainability,
-            readability,
-            test_coverage: None,
-            code_smells: Vec::new(),
-            best_practices: Vec::new(),
-            violations: Vec::new(),
-        })
+        Ok(metrics::quality(content, structure, facts))
     }
 
-    /// Build code context
+    /// Build code context: related files, crate, integration points and configuration the code
+    /// reads
     async fn build_context(
         &self,
-        _file_path: &Path,
-        _dependencies: &Dependencies,
+        structure: &CodeStructure,
+        facts: &SyntaxFacts,
+        file_path: &Path,
+        language: Language,
+        location: Option<&ModuleLocation>,
+        dependencies: &Dependencies,
     ) -> Result<CodeContext, PhoenixError> {
+        let mut related_files: Vec<String> = Vec::new();
+        let mut config_dependencies = facts.config_files.clone();
+        if let Some(location) = location {
+            let own = location.name();
+            let declared = structure.modules.iter().filter(|m| !m.inline).filter_map(|m| {
+                let mut segments = location.segments.clone();
+                segments.extend(m.path.split("::").map(str::to_string));
+                location.module_file(&segments)
+            });
+            let imported = dependencies
+                .internal
+                .iter()
+                .filter_map(|d| location.resolve(&d.name))
+                .filter(|(module, _)| *module != own)
+                .map(|(_, file)| file);
+            for file in declared.chain(imported) {
+                let file = file.display().to_string();
+                if !related_files.contains(&file) {
+                    related_files.push(file);
+                }
+            }
+            config_dependencies.push(location.crate_root.join("Cargo.toml").display().to_string());
+        } else {
+            for import in &structure.imports {
+                if let Some(file) = graph::resolve_relative_import(file_path, language, &import.path) {
+                    let file = file.display().to_string();
+                    if !related_files.contains(&file) {
+                        related_files.push(file);
+                    }
+                }
+            }
+        }
+
+        let mut integration_points: Vec<String> = structure
+            .functions
+            .iter()
+            .filter(|f| f.owner.is_none() && (f.visibility == "pub" || f.visibility == "export"))
+            .map(|f| format!("fn {}", f.name))
+            .collect();
+        integration_points.extend(structure.impls.iter().filter_map(|i| {
+            i.trait_name
+                .as_ref()
+                .map(|t| format!("impl {} for {}", t, i.self_type))
+        }));
+
         Ok(CodeContext {
-            related_files: Vec::new(),
-            system_context: None,
-            integration_points: Vec::new(),
-            config_dependencies: Vec::new(),
-            environment_dependencies: Vec::new(),
+            related_files,
+            system_context: location
+                .map(|l| format!("Module `{}` of crate `{}`", l.name(), l.crate_name)),
+            integration_points,
+            config_dependencies,
+            environment_dependencies: facts.env_vars.clone(),
             historical_context: None,
         })
     }
 
-    /// Analyze cross-file relationships
+    /// Analyze cross-file relationships (trait implementations, calls, imports); each file also
+    /// receives the relationships that start from it
     async fn analyze_cross_file_relationships(
         &self,
-        _files: &[CodeAnalysis],
+        files: &mut [CodeAnalysis],
+        dependency_graph: &DependencyGraph,
     ) -> Result<Vec<CodeRelationship>, PhoenixError> {
-        Ok(Vec::new())
+        let relationships = graph::cross_file_relationships(files, dependency_graph);
+        for (i, relationship) in &relationships {
+            files[*i].semantics.relationships.push(relationship.clone());
+        }
+        Ok(relationships.into_iter().map(|(_, r)| r).collect())
     }
 
     /// Analyze overall architecture
     async fn analyze_architecture(
         &self,
-        _files: &[CodeAnalysis],
+        files: &[CodeAnalysis],
+        dependency_graph: &DependencyGraph,
+        circular: &[Vec<String>],
     ) -> Result<ArchitectureAnalysis, PhoenixError> {
-        Ok(ArchitectureAnalysis {
-            layers: Vec::new(),
-            components: Vec::new(),
-            patterns: Vec::new(),
-        })
+        Ok(graph::architecture(files, dependency_graph, circular))
     }
 
     /// Check if file is a code file
@@ -1297,7 +1647,7 @@ Respond in JSON format."#,
             .unwrap_or("");
         matches!(
             ext,
-            "rs" | "py" | "js" | "ts" | "tsx" | "java" | "cpp" | "cc" | "cxx" | "c" | "go"
+            "rs" | "py" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "java" | "cpp" | "cc" | "cxx" | "c" | "go"
         )
     }
 }
@@ -1310,6 +1660,12 @@ pub struct CodebaseAnalysis {
     pub relationships: Vec<CodeRelationship>,
     pub architecture: ArchitectureAnalysis,
     pub errors: Vec<(PathBuf, String)>,
+    /// Module dependency graph across all analyzed files
+    #[serde(default)]
+    pub dependency_graph: DependencyGraph,
+    /// Module dependency cycles (each sorted)
+    #[serde(default)]
+    pub circular_dependencies: Vec<Vec<String>>,
 }
 
 /// Architecture analysis
@@ -1322,12 +1678,6 @@ pub struct ArchitectureAnalysis {
 
 // Helper functions
 
-#[cfg(feature = "rust-analysis")]
-fn extract_doc_comment(_attrs: &[syn::Attribute]) -> Option<String> {
-    // Extract doc comments from attributes
-    None
-}
-
 fn infer_purpose_from_names(functions: &[FunctionDefinition]) -> String {
     if functions.is_empty() {
         return "various functionality".to_string();
@@ -1381,3 +1731,144 @@ pub use master_orchestrator::{
     MasterOrchestratorCodeAnalysis, DefinitionList, SemanticAnalysisResult,
     CodeIntentResult, DependencyAnalysis, QualityMetricsResult,
 };
+pub use report::{markdown_report, sarif_report};
+pub use symbols::{CallSite, Reference, Symbol, SymbolIndex, SymbolMatch};
+
+#[cfg(all(test, feature = "rust-analysis"))]
+mod tests {
+    use super::*;
+
+    fn crate_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[tokio::test]
+    async fn test_rust_structure_of_own_source() {
+        let analysis = CodeAnalyzer::new()
+            .analyze_file(&crate_dir().join("src/lib.rs"))
+            .await
+            .unwrap();
+        assert_eq!(analysis.module_path.as_deref(), Some("code_analysis"));
+
+        let analyze_file = analysis
+            .structure
+            .functions
+            .iter()
+            .find(|f| f.name == "analyze_file" && f.owner.as_deref() == Some("CodeAnalyzer"))
+            .unwrap();
+        assert!(analyze_file.line_range.1 > analyze_file.line_range.0);
+        assert!(analyze_file.signature.starts_with("pub async fn analyze_file(&self"));
+        assert_eq!(analyze_file.parameters[1].type_hint.as_deref(), Some("&Path"));
+        assert!(analyze_file.documentation.is_some());
+        assert!(analysis
+            .flow
+            .call_graph
+            .iter()
+            .any(|e| e.caller == "CodeAnalyzer::analyze_file" && e.callee == "analyze_content"));
+        assert!(analysis
+            .flow
+            .call_graph
+            .iter()
+            .any(|e| e.caller == "CodeAnalyzer::analyze_content" && e.callee == "analyze_structure"));
+
+        // Impl resolution attaches methods and trait impls to the type.
+        let analyzer = analysis.structure.types.iter().find(|t| t.name == "CodeAnalyzer").unwrap();
+        assert!(analyzer.methods.iter().any(|m| m == "analyze_codebase"));
+        let parser = analysis.structure.functions.iter().find(|f| f.name == "parse_llm_semantic_response").unwrap();
+        assert!(parser.complexity > 1);
+        assert!(analysis.quality.code_smells.iter().any(|s| s.smell_type == "large_file"));
+        assert!(analysis.quality.maintainability > 0.0 && analysis.quality.maintainability <= 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_codebase_graph_of_own_source() {
+        let codebase = CodeAnalyzer::new()
+            .analyze_codebase(&crate_dir().join("src"))
+            .await
+            .unwrap();
+        assert!(codebase.errors.is_empty(), "{:?}", codebase.errors);
+        assert!(codebase.dependency_graph.edges.contains(&(
+            "code_analysis::master_orchestrator".to_string(),
+            "code_analysis".to_string()
+        )));
+        assert!(codebase
+            .relationships
+            .iter()
+            .any(|r| r.relationship_type == "imports" && r.related_to.ends_with("lib.rs")));
+        assert!(codebase.architecture.components[0].starts_with("code_analysis: "));
+        assert!(codebase.circular_dependencies.iter().all(|cycle| cycle.len() > 1));
+    }
+
+    #[tokio::test]
+    async fn test_detects_module_cycles() {
+        let root = std::env::temp_dir().join(format!("code_analysis_cycle_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"cycle-fixture\"\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "mod a;\nmod b;\nmod c;\n").unwrap();
+        std::fs::write(root.join("src/a.rs"), "use crate::b::B;\npub struct A;\npub fn make() -> B { B }\n").unwrap();
+        std::fs::write(root.join("src/b.rs"), "pub struct B;\npub fn make() -> super::a::A { super::a::A }\n").unwrap();
+        std::fs::write(root.join("src/c.rs"), "pub use crate::a::A;\n").unwrap();
+
+        let codebase = CodeAnalyzer::new().analyze_codebase(&root).await;
+        std::fs::remove_dir_all(&root).ok();
+        let codebase = codebase.unwrap();
+
+        let cycle = vec!["cycle_fixture::a".to_string(), "cycle_fixture::b".to_string()];
+        assert_eq!(codebase.circular_dependencies, vec![cycle.clone()]);
+        let a = codebase
+            .files
+            .iter()
+            .find(|f| f.module_path.as_deref() == Some("cycle_fixture::a"))
+            .unwrap();
+        assert_eq!(a.dependencies.circular, vec![cycle]);
+        assert!(a.context.related_files.iter().any(|f| f.ends_with("b.rs")));
+    }
+
+    #[tokio::test]
+    async fn test_code_files_skip_ignored_and_build_dirs() {
+        let root = std::env::temp_dir().join(format!("code_analysis_walk_{}", std::process::id()));
+        for dir in ["src", "target/debug", "node_modules/pkg", "generated"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        for file in ["src/lib.rs", "target/debug/build.rs", "node_modules/pkg/index.js", "generated/out.rs"] {
+            std::fs::write(root.join(file), "fn f() {}\n").unwrap();
+        }
+
+        let files = CodeAnalyzer::new().code_files(&root);
+        std::fs::remove_dir_all(&root).ok();
+        assert_eq!(files, vec![root.join("src/lib.rs")]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_serves_unchanged_files() {
+        let root = std::env::temp_dir().join(format!("code_analysis_cache_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let file = root.join("src/lib.rs");
+        std::fs::write(&file, "pub fn first() {}\n").unwrap();
+        // Not hidden, so only the cache-dir exclusion keeps it out of `code_files`; the `..` makes
+        // sure the check does not depend on how the path is spelled.
+        let cache_dir = root.join("src/../analysis_cache");
+        let analyzer = CodeAnalyzer::new().with_cache_dir(&cache_dir).with_workers(2);
+
+        let fresh = analyzer.analyze_file(&file).await.unwrap();
+        let entries = || std::fs::read_dir(&cache_dir).map(|d| d.count()).unwrap_or(0);
+        assert_eq!(entries(), 1);
+        // A hand-edited entry proves the second run is served from the cache.
+        let entry = std::fs::read_dir(&cache_dir).unwrap().next().unwrap().unwrap().path();
+        let raw = std::fs::read_to_string(&entry).unwrap();
+        std::fs::write(&entry, raw.replace("\"first\"", "\"cached\"")).unwrap();
+        let cached = analyzer.analyze_file(&file).await.unwrap();
+        assert_eq!(cached.structure.functions[0].name, "cached");
+        assert_eq!(fresh.structure.functions[0].name, "first");
+
+        std::fs::write(&file, "pub fn second() {}\n").unwrap();
+        let changed = analyzer.analyze_file(&file).await.unwrap();
+        assert_eq!(changed.structure.functions[0].name, "second");
+        assert_eq!(entries(), 1);
+        // The cache dir itself is never walked.
+        std::fs::write(root.join("analysis_cache/stray.rs"), "pub fn stray() {}\n").unwrap();
+        assert_eq!(analyzer.code_files(&root), vec![file]);
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -57,6 +57,20 @@ impl MasterOrchestratorCodeAnalysis {
         self.analyzer.analyze_codebase(root_path).await
     }
 
+    /// Analyze a codebase and keep it current as its files change
+    pub async fn watch_codebase(&self, root_path: &Path) -> Result<crate::watch::CodebaseWatcher, PhoenixError> {
+        self.analyzer.watch_codebase(root_path).await
+    }
+
+    /// Analyze a codebase and index its symbols for search, go-to-definition,
+    /// find-references and caller queries
+    ///
+    /// The index only needs structure, so files are analyzed without the LLM.
+    pub async fn symbol_index(&self, root_path: &Path) -> Result<crate::SymbolIndex, PhoenixError> {
+        let codebase = self.analyzer.structural().analyze_codebase(root_path).await?;
+        Ok(crate::SymbolIndex::new(&codebase))
+    }
+
     /// Get high-level overview of codebase (definition names only)
     /// 
     /// This is the "partial access" mode - just listing definitions.
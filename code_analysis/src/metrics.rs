@@ -0,0 +1,314 @@
+//! Quality metrics and code smell detection
+//!
+//! Maintainability follows the classic maintainability index (Halstead volume, cyclomatic
+//! complexity and lines of code), averaged over functions and rescaled to 0-100. Readability and
+//! the smells are derived from the parsed structure plus the language-specific facts a parser
+//! collected while walking function bodies.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+use crate::{CallEdge, CodeSmell, CodeStructure, DataFlowPath, QualityMetrics};
+
+/// Lines in a function body before it counts as long.
+pub(crate) const LONG_FUNCTION_LINES: usize = 80;
+/// Cyclomatic complexity before a function counts as complex.
+pub(crate) const HIGH_COMPLEXITY: usize = 15;
+/// Parameters (excluding `self`) before a signature counts as too wide.
+pub(crate) const MAX_PARAMETERS: usize = 6;
+/// Block nesting depth before a function counts as deeply nested.
+pub(crate) const MAX_NESTING: usize = 4;
+/// Lines before a file counts as large.
+pub(crate) const LARGE_FILE_LINES: usize = 1000;
+/// Methods before a type counts as a god type.
+pub(crate) const MAX_TYPE_METHODS: usize = 30;
+/// Fields before a struct counts as too wide.
+pub(crate) const MAX_TYPE_FIELDS: usize = 15;
+
+/// What a language parser learned beyond `CodeStructure`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SyntaxFacts {
+    /// One entry per `CodeStructure::functions`, in the same order.
+    pub functions: Vec<FunctionFacts>,
+    pub call_graph: Vec<CallEdge>,
+    /// Module paths referenced inline (`crate::a::b`): path, line, enclosing function.
+    pub references: Vec<(String, usize, String)>,
+    pub data_flow: Vec<DataFlowPath>,
+    pub env_vars: Vec<String>,
+    pub config_files: Vec<String>,
+    pub side_effects: Vec<String>,
+    pub exits: Vec<String>,
+    /// Language-specific smells (unwrap, unsafe, unfinished code, ...).
+    pub smells: Vec<CodeSmell>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FunctionFacts {
+    /// Qualified name (`Type::method` for methods).
+    pub name: String,
+    pub max_nesting: usize,
+    pub conditions: Vec<String>,
+    pub is_test: bool,
+    pub is_entry: bool,
+}
+
+pub(crate) fn smell(
+    smell_type: &str,
+    location: &str,
+    line: usize,
+    severity: &str,
+    description: String,
+) -> CodeSmell {
+    CodeSmell {
+        smell_type: smell_type.to_string(),
+        location: location.to_string(),
+        description,
+        severity: severity.to_string(),
+        line: Some(line),
+    }
+}
+
+/// Quality metrics for one file.
+pub(crate) fn quality(content: &str, structure: &CodeStructure, facts: &SyntaxFacts) -> QualityMetrics {
+    let code_smells = detect_smells(structure, facts);
+
+    let mut best_practices = Vec::new();
+    let mut violations = Vec::new();
+
+    let public: Vec<&crate::FunctionDefinition> =
+        structure.functions.iter().filter(|f| f.visibility == "pub").collect();
+    if !public.is_empty() {
+        let documented = public.iter().filter(|f| f.documentation.is_some()).count();
+        if documented * 10 >= public.len() * 8 {
+            best_practices.push(format!(
+                "Public functions documented ({}/{})",
+                documented,
+                public.len()
+            ));
+        } else {
+            violations.push(format!(
+                "Only {}/{} public functions are documented",
+                documented,
+                public.len()
+            ));
+        }
+    }
+    let tests = facts.functions.iter().filter(|f| f.is_test).count();
+    if tests > 0 {
+        best_practices.push(format!("Unit tests present ({})", tests));
+    }
+    if structure
+        .functions
+        .iter()
+        .any(|f| f.return_type.as_deref().is_some_and(|r| r.contains("Result")))
+    {
+        best_practices.push("Fallible functions return Result".to_string());
+    }
+
+    let mut by_type: BTreeMap<&str, usize> = BTreeMap::new();
+    for s in &code_smells {
+        *by_type.entry(s.smell_type.as_str()).or_default() += 1;
+    }
+    for (smell_type, count) in by_type {
+        violations.push(format!("{} × {}", count, smell_type));
+    }
+
+    QualityMetrics {
+        maintainability: maintainability(content, structure),
+        readability: readability(content, structure, facts),
+        test_coverage: None,
+        code_smells,
+        best_practices,
+        violations,
+    }
+}
+
+/// Mean maintainability index of the file's functions (or of the whole file when it has none),
+/// on a 0-100 scale.
+pub(crate) fn maintainability(content: &str, structure: &CodeStructure) -> f64 {
+    let scores: Vec<f64> = structure
+        .functions
+        .iter()
+        .filter(|f| !f.body.is_empty())
+        .map(|f| maintainability_index(&f.body, f.complexity))
+        .collect();
+    if scores.is_empty() {
+        maintainability_index(content, structure.complexity.cyclomatic.max(1))
+    } else {
+        scores.iter().sum::<f64>() / scores.len() as f64
+    }
+}
+
+fn maintainability_index(source: &str, cyclomatic: usize) -> f64 {
+    let loc = source.lines().filter(|l| !l.trim().is_empty()).count().max(1) as f64;
+    let volume = halstead_volume(source).max(1.0);
+    let mi = 171.0 - 5.2 * volume.ln() - 0.23 * cyclomatic as f64 - 16.2 * loc.ln();
+    (mi * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+/// Halstead volume `N * log2(n)` over a lexical token stream (operators and operands alike).
+fn halstead_volume(source: &str) -> f64 {
+    let token = Regex::new(
+        r#"[A-Za-z_][A-Za-z0-9_]*|\d+(?:\.\d+)?|"(?:[^"\\]|\\.)*"|==|!=|<=|>=|&&|\|\||::|->|=>|[^\s\w]"#,
+    )
+    .expect("valid token regex");
+    let mut total = 0usize;
+    let mut distinct = std::collections::HashSet::new();
+    for m in token.find_iter(source) {
+        total += 1;
+        distinct.insert(m.as_str());
+    }
+    if distinct.len() < 2 {
+        return total as f64;
+    }
+    total as f64 * (distinct.len() as f64).log2()
+}
+
+/// 0-100: penalizes long lines, missing comments, long functions and deep nesting.
+pub(crate) fn readability(content: &str, structure: &CodeStructure, facts: &SyntaxFacts) -> f64 {
+    let lines: Vec<&str> = content.lines().collect();
+    let code_lines = lines.iter().filter(|l| !l.trim().is_empty()).count().max(1);
+    let long_lines = lines.iter().filter(|l| l.chars().count() > 100).count();
+    let comment_lines = lines
+        .iter()
+        .filter(|l| {
+            let t = l.trim_start();
+            t.starts_with("//") || t.starts_with("/*") || t.starts_with('*') || t.starts_with('#')
+        })
+        .count();
+
+    let mut score = 100.0;
+    score -= 30.0 * long_lines as f64 / code_lines as f64;
+    if (comment_lines as f64) < code_lines as f64 * 0.05 {
+        score -= 10.0;
+    }
+
+    let bodies: Vec<usize> = structure
+        .functions
+        .iter()
+        .filter(|f| !f.body.is_empty())
+        .map(|f| f.line_range.1.saturating_sub(f.line_range.0) + 1)
+        .collect();
+    if !bodies.is_empty() {
+        let average = bodies.iter().sum::<usize>() as f64 / bodies.len() as f64;
+        if average > 30.0 {
+            score -= ((average - 30.0) / 2.0).min(20.0);
+        }
+    }
+
+    let nesting = facts
+        .functions
+        .iter()
+        .map(|f| f.max_nesting)
+        .max()
+        .unwrap_or(structure.complexity.max_nesting);
+    if nesting > 3 {
+        score -= (5.0 * (nesting - 3) as f64).min(20.0);
+    }
+
+    score.clamp(0.0, 100.0)
+}
+
+/// Structural smells for any language, plus the parser's language-specific findings.
+pub(crate) fn detect_smells(structure: &CodeStructure, facts: &SyntaxFacts) -> Vec<CodeSmell> {
+    let mut smells = Vec::new();
+
+    for (i, f) in structure.functions.iter().enumerate() {
+        let fact = facts.functions.get(i);
+        if fact.is_some_and(|fact| fact.is_test) {
+            continue;
+        }
+        let name = fact.map(|fact| fact.name.clone()).unwrap_or_else(|| f.name.clone());
+        let line = f.line_range.0;
+
+        let length = f.line_range.1.saturating_sub(f.line_range.0) + 1;
+        if !f.body.is_empty() && length > LONG_FUNCTION_LINES {
+            smells.push(smell(
+                "long_function",
+                &name,
+                line,
+                if length > 2 * LONG_FUNCTION_LINES { "high" } else { "medium" },
+                format!("`{}` is {} lines long (limit {})", name, length, LONG_FUNCTION_LINES),
+            ));
+        }
+        if f.complexity > HIGH_COMPLEXITY {
+            smells.push(smell(
+                "high_complexity",
+                &name,
+                line,
+                if f.complexity > 2 * HIGH_COMPLEXITY { "high" } else { "medium" },
+                format!(
+                    "`{}` has cyclomatic complexity {} (limit {})",
+                    name, f.complexity, HIGH_COMPLEXITY
+                ),
+            ));
+        }
+        let params = f.parameters.iter().filter(|p| p.name != "self").count();
+        if params > MAX_PARAMETERS {
+            smells.push(smell(
+                "too_many_parameters",
+                &name,
+                line,
+                "low",
+                format!("`{}` takes {} parameters (limit {})", name, params, MAX_PARAMETERS),
+            ));
+        }
+        if let Some(fact) = fact {
+            if fact.max_nesting > MAX_NESTING {
+                smells.push(smell(
+                    "deep_nesting",
+                    &name,
+                    line,
+                    "medium",
+                    format!(
+                        "`{}` nests blocks {} levels deep (limit {})",
+                        name, fact.max_nesting, MAX_NESTING
+                    ),
+                ));
+            }
+        }
+    }
+
+    for t in &structure.types {
+        if t.methods.len() > MAX_TYPE_METHODS {
+            smells.push(smell(
+                "large_type",
+                &t.name,
+                t.line_range.0,
+                "medium",
+                format!(
+                    "`{}` has {} methods (limit {}); consider splitting responsibilities",
+                    t.name,
+                    t.methods.len(),
+                    MAX_TYPE_METHODS
+                ),
+            ));
+        }
+        if t.kind == "struct" && t.members.len() > MAX_TYPE_FIELDS {
+            smells.push(smell(
+                "too_many_fields",
+                &t.name,
+                t.line_range.0,
+                "low",
+                format!("`{}` has {} fields (limit {})", t.name, t.members.len(), MAX_TYPE_FIELDS),
+            ));
+        }
+    }
+
+    if structure.lines_of_code > LARGE_FILE_LINES {
+        smells.push(smell(
+            "large_file",
+            "file",
+            1,
+            if structure.lines_of_code > 2 * LARGE_FILE_LINES { "medium" } else { "low" },
+            format!(
+                "File has {} lines (limit {})",
+                structure.lines_of_code, LARGE_FILE_LINES
+            ),
+        ));
+    }
+
+    smells.extend(facts.smells.iter().cloned());
+    smells
+}
@@ -0,0 +1,379 @@
+//! SARIF and Markdown reports
+//!
+//! SARIF 2.1.0 output carries one result per code smell, low-maintainability file and module
+//! dependency cycle, so IDEs and code-scanning services can ingest it; per-file quality metrics
+//! travel in the artifact property bags. The Markdown report covers the same findings for
+//! people.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::{CodeAnalysis, CodebaseAnalysis};
+
+/// Maintainability (0-100) below which a file is reported.
+pub const LOW_MAINTAINABILITY: f64 = 40.0;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Rule id, description and default level of findings that are not code smells.
+const CYCLE_RULE: (&str, &str, &str) = (
+    "dependency_cycle",
+    "Modules depend on each other in a cycle",
+    "warning",
+);
+const MAINTAINABILITY_RULE: (&str, &str, &str) = (
+    "low_maintainability",
+    "File has a low maintainability index",
+    "note",
+);
+
+/// SARIF level for a smell severity.
+fn level(severity: &str) -> &'static str {
+    match severity {
+        "high" => "error",
+        "medium" => "warning",
+        _ => "note",
+    }
+}
+
+/// Directory report paths are relative to (the parent when a single file was analyzed).
+fn base_dir(codebase: &CodebaseAnalysis) -> &Path {
+    let root = codebase.root_path.as_path();
+    if root.is_file() {
+        root.parent().unwrap_or(root)
+    } else {
+        root
+    }
+}
+
+fn relative(codebase: &CodebaseAnalysis, path: &Path) -> String {
+    path.strip_prefix(base_dir(codebase))
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Files defining each module, for locating cycles.
+fn module_files(codebase: &CodebaseAnalysis) -> HashMap<&str, &CodeAnalysis> {
+    codebase
+        .files
+        .iter()
+        .filter_map(|file| file.module_path.as_deref().map(|module| (module, file)))
+        .collect()
+}
+
+fn sarif_location(uri: &str, line: Option<usize>, description: Option<&str>) -> Value {
+    let mut physical = json!({
+        "artifactLocation": { "uri": uri, "uriBaseId": "SRCROOT" },
+    });
+    if let Some(line) = line.filter(|line| *line > 0) {
+        physical["region"] = json!({ "startLine": line });
+    }
+    let mut location = json!({ "physicalLocation": physical });
+    if let Some(description) = description {
+        location["message"] = json!({ "text": description });
+    }
+    location
+}
+
+/// The codebase's findings as a SARIF 2.1.0 log.
+pub fn sarif_report(codebase: &CodebaseAnalysis) -> Value {
+    // Rules are keyed by id; a smell rule takes its default level from its first occurrence.
+    let mut rules: BTreeMap<String, (String, &'static str)> = BTreeMap::new();
+    let mut results = Vec::new();
+    let mut artifacts = Vec::new();
+
+    for file in &codebase.files {
+        let uri = relative(codebase, &file.file_path);
+        let quality = &file.quality;
+        artifacts.push(json!({
+            "location": { "uri": uri, "uriBaseId": "SRCROOT" },
+            "properties": {
+                "language": format!("{:?}", file.language),
+                "linesOfCode": file.structure.lines_of_code,
+                "maintainability": round(quality.maintainability),
+                "readability": round(quality.readability),
+                "bestPractices": quality.best_practices,
+                "violations": quality.violations,
+            },
+        }));
+
+        for smell in &quality.code_smells {
+            rules
+                .entry(smell.smell_type.clone())
+                .or_insert_with(|| (smell.smell_type.replace('_', " "), level(&smell.severity)));
+            results.push(json!({
+                "ruleId": smell.smell_type,
+                "level": level(&smell.severity),
+                "message": { "text": smell.description },
+                "locations": [sarif_location(&uri, smell.line, None)],
+                "properties": { "severity": smell.severity, "symbol": smell.location },
+            }));
+        }
+
+        if quality.maintainability < LOW_MAINTAINABILITY {
+            let (id, description, level) = MAINTAINABILITY_RULE;
+            rules.insert(id.to_string(), (description.to_string(), level));
+            results.push(json!({
+                "ruleId": id,
+                "level": level,
+                "message": {
+                    "text": format!(
+                        "Maintainability index is {:.1} (threshold {:.0})",
+                        quality.maintainability, LOW_MAINTAINABILITY
+                    ),
+                },
+                "locations": [sarif_location(&uri, None, None)],
+            }));
+        }
+    }
+
+    let modules = module_files(codebase);
+    for cycle in &codebase.circular_dependencies {
+        let (id, description, level) = CYCLE_RULE;
+        rules.insert(id.to_string(), (description.to_string(), level));
+        let mut locations: Vec<Value> = cycle
+            .iter()
+            .filter_map(|module| {
+                let file = modules.get(module.as_str())?;
+                Some(sarif_location(&relative(codebase, &file.file_path), None, Some(module)))
+            })
+            .collect();
+        let primary: Vec<Value> = locations.drain(..locations.len().min(1)).collect();
+        let mut path = cycle.clone();
+        path.extend(cycle.first().cloned());
+        results.push(json!({
+            "ruleId": id,
+            "level": level,
+            "message": { "text": format!("Dependency cycle: {}", path.join(" → ")) },
+            "locations": primary,
+            "relatedLocations": locations,
+            "properties": { "modules": cycle },
+        }));
+    }
+
+    let rules: Vec<Value> = rules
+        .into_iter()
+        .map(|(id, (description, level))| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level },
+            })
+        })
+        .collect();
+    let invocation_errors: Vec<Value> = codebase
+        .errors
+        .iter()
+        .map(|(path, error)| {
+            json!({
+                "level": "error",
+                "message": { "text": format!("{}: {}", relative(codebase, path), error) },
+            })
+        })
+        .collect();
+    let base = std::fs::canonicalize(base_dir(codebase)).unwrap_or_else(|_| base_dir(codebase).to_path_buf());
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "phoenix-code-analysis",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": { "SRCROOT": { "uri": directory_uri(&base) } },
+            "invocations": [{
+                "executionSuccessful": codebase.errors.is_empty(),
+                "toolExecutionNotifications": invocation_errors,
+            }],
+            "artifacts": artifacts,
+            "results": results,
+        }],
+    })
+}
+
+/// `file://` URI of a directory, with the trailing slash SARIF requires for base ids.
+fn directory_uri(dir: &Path) -> String {
+    let mut path = dir.to_string_lossy().replace('\\', "/");
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+    if !path.ends_with('/') {
+        path.push('/');
+    }
+    format!("file://{}", path)
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Escape text for a Markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// A human-readable summary of the codebase's quality, smells and dependency cycles.
+pub fn markdown_report(codebase: &CodebaseAnalysis) -> String {
+    let mut out = String::new();
+    let files = &codebase.files;
+    let smells: Vec<_> = files
+        .iter()
+        .flat_map(|file| file.quality.code_smells.iter().map(move |smell| (file, smell)))
+        .collect();
+    let count = |severity: &str| smells.iter().filter(|(_, s)| s.severity == severity).count();
+    let mean = |metric: fn(&CodeAnalysis) -> f64| {
+        if files.is_empty() {
+            0.0
+        } else {
+            files.iter().map(metric).sum::<f64>() / files.len() as f64
+        }
+    };
+
+    let _ = writeln!(out, "# Code Analysis Report\n");
+    let _ = writeln!(out, "Root: `{}`\n", codebase.root_path.display());
+    let _ = writeln!(out, "## Summary\n");
+    let _ = writeln!(out, "| Metric | Value |\n| --- | --- |");
+    let _ = writeln!(out, "| Files analyzed | {} |", files.len());
+    let _ = writeln!(out, "| Analysis errors | {} |", codebase.errors.len());
+    let _ = writeln!(
+        out,
+        "| Lines of code | {} |",
+        files.iter().map(|f| f.structure.lines_of_code).sum::<usize>()
+    );
+    let _ = writeln!(out, "| Mean maintainability | {:.1} |", mean(|f| f.quality.maintainability));
+    let _ = writeln!(out, "| Mean readability | {:.1} |", mean(|f| f.quality.readability));
+    let _ = writeln!(
+        out,
+        "| Code smells | {} ({} high, {} medium, {} low) |",
+        smells.len(),
+        count("high"),
+        count("medium"),
+        count("low")
+    );
+    let _ = writeln!(out, "| Dependency cycles | {} |", codebase.circular_dependencies.len());
+
+    if !codebase.circular_dependencies.is_empty() {
+        let _ = writeln!(out, "\n## Dependency Cycles\n");
+        for cycle in &codebase.circular_dependencies {
+            let mut path: Vec<String> = cycle.iter().map(|m| format!("`{}`", m)).collect();
+            path.extend(path.first().cloned());
+            let _ = writeln!(out, "- {}", path.join(" → "));
+        }
+    }
+
+    if !files.is_empty() {
+        let _ = writeln!(out, "\n## Files\n");
+        let _ = writeln!(
+            out,
+            "| File | Language | Lines | Maintainability | Readability | Smells |\n| --- | --- | ---: | ---: | ---: | ---: |"
+        );
+        // Least maintainable first: that is where attention pays off.
+        let mut ranked: Vec<&CodeAnalysis> = files.iter().collect();
+        ranked.sort_by(|a, b| a.quality.maintainability.total_cmp(&b.quality.maintainability));
+        for file in ranked {
+            let _ = writeln!(
+                out,
+                "| `{}` | {:?} | {} | {:.1} | {:.1} | {} |",
+                cell(&relative(codebase, &file.file_path)),
+                file.language,
+                file.structure.lines_of_code,
+                file.quality.maintainability,
+                file.quality.readability,
+                file.quality.code_smells.len()
+            );
+        }
+    }
+
+    if !smells.is_empty() {
+        let _ = writeln!(out, "\n## Code Smells");
+        let rank = |severity: &str| match severity {
+            "high" => 0,
+            "medium" => 1,
+            _ => 2,
+        };
+        for file in files.iter().filter(|f| !f.quality.code_smells.is_empty()) {
+            let _ = writeln!(out, "\n### `{}`\n", relative(codebase, &file.file_path));
+            let mut file_smells: Vec<_> = file.quality.code_smells.iter().collect();
+            file_smells.sort_by_key(|s| (rank(&s.severity), s.line));
+            for smell in file_smells {
+                let line = smell.line.map(|l| format!(" (line {})", l)).unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "- **{}** `{}`{}: {}",
+                    smell.severity, smell.smell_type, line, smell.description
+                );
+            }
+        }
+    }
+
+    if !codebase.errors.is_empty() {
+        let _ = writeln!(out, "\n## Analysis Errors\n");
+        for (path, error) in &codebase.errors {
+            let _ = writeln!(out, "- `{}`: {}", relative(codebase, path), error);
+        }
+    }
+    out
+}
+
+#[cfg(all(test, feature = "rust-analysis"))]
+mod tests {
+    use super::*;
+    use crate::CodeAnalyzer;
+
+    #[tokio::test]
+    async fn test_reports_cover_smells_and_cycles() {
+        let root = std::env::temp_dir().join(format!("code_analysis_report_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"report-fixture\"\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "mod a;\nmod b;\n").unwrap();
+        std::fs::write(
+            root.join("src/a.rs"),
+            "use crate::b::B;\npub struct A;\npub fn make() -> B {\n    \"1\".parse::<u8>().unwrap();\n    B\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/b.rs"), "use crate::a::A;\npub struct B;\npub fn make() -> A { A }\n").unwrap();
+
+        let codebase = CodeAnalyzer::new().analyze_codebase(&root).await;
+        std::fs::remove_dir_all(&root).ok();
+        let codebase = codebase.unwrap();
+
+        let sarif = sarif_report(&codebase);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect();
+        assert!(rules.contains(&"dependency_cycle") && rules.contains(&"panic_prone_unwrap"));
+        let results = run["results"].as_array().unwrap();
+        let unwrap = results.iter().find(|r| r["ruleId"] == "panic_prone_unwrap").unwrap();
+        assert_eq!(unwrap["level"], "note");
+        let location = &unwrap["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/a.rs");
+        assert_eq!(location["region"]["startLine"], 3);
+        let cycle = results.iter().find(|r| r["ruleId"] == "dependency_cycle").unwrap();
+        assert_eq!(
+            cycle["message"]["text"],
+            "Dependency cycle: report_fixture::a → report_fixture::b → report_fixture::a"
+        );
+        assert_eq!(cycle["relatedLocations"].as_array().unwrap().len(), 1);
+        assert_eq!(run["artifacts"].as_array().unwrap().len(), 3);
+
+        let markdown = markdown_report(&codebase);
+        assert!(markdown.starts_with("# Code Analysis Report"));
+        assert!(markdown.contains("| Dependency cycles | 1 |"));
+        assert!(markdown.contains("- `report_fixture::a` → `report_fixture::b` → `report_fixture::a`"));
+        assert!(markdown.contains("### `src/a.rs`"));
+        assert!(markdown.contains("- **low** `panic_prone_unwrap` (line 3)"));
+    }
+}
@@ -0,0 +1,1025 @@
+//! Rust structure analysis built on `syn` (feature `rust-analysis`)
+//!
+//! Walks every item of a file, including inline modules, impl blocks and trait definitions, and
+//! records signatures, generics, doc comments and line ranges. Function bodies are visited for
+//! cyclomatic/cognitive complexity, call sites, referenced module paths and the facts the flow,
+//! quality and context passes build on.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, BinOp, Block, Expr, ExprPath, Fields, FnArg, Generics, ImplItem, Item, Pat,
+    ReturnType, Signature, TraitItem, UseTree, Visibility,
+};
+
+use crate::graph::base_name;
+use crate::metrics::{smell, FunctionFacts, SyntaxFacts};
+use crate::{
+    CallEdge, CodeStructure, ComplexityMetrics, ConstantDefinition, DataFlowPath,
+    FunctionDefinition, ImplDefinition, ImportDefinition, MacroDefinition, Member,
+    ModuleDefinition, Parameter, TypeDefinition,
+};
+
+/// Parse `content` and collect its structure.
+pub(crate) fn analyze(content: &str) -> Result<(CodeStructure, SyntaxFacts), String> {
+    let file = syn::parse_file(content).map_err(|e| {
+        let start = e.span().start();
+        format!("Failed to parse Rust at {}:{}: {}", start.line, start.column + 1, e)
+    })?;
+
+    let mut collector = Collector::new(content);
+    collector.items(&file.items);
+    Ok(collector.finish(doc_comment(&file.attrs)))
+}
+
+struct Collector<'a> {
+    lines: Vec<&'a str>,
+    module_path: Vec<String>,
+    in_test: bool,
+    functions: Vec<FunctionDefinition>,
+    types: Vec<TypeDefinition>,
+    modules: Vec<ModuleDefinition>,
+    constants: Vec<ConstantDefinition>,
+    imports: Vec<ImportDefinition>,
+    impls: Vec<ImplDefinition>,
+    macros: Vec<MacroDefinition>,
+    complexity: ComplexityMetrics,
+    facts: SyntaxFacts,
+}
+
+impl<'a> Collector<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            lines: content.lines().collect(),
+            module_path: Vec::new(),
+            in_test: false,
+            functions: Vec::new(),
+            types: Vec::new(),
+            modules: Vec::new(),
+            constants: Vec::new(),
+            imports: Vec::new(),
+            impls: Vec::new(),
+            macros: Vec::new(),
+            complexity: ComplexityMetrics {
+                cyclomatic: 0,
+                cognitive: 0,
+                max_nesting: 0,
+                branches: 0,
+            },
+            facts: SyntaxFacts::default(),
+        }
+    }
+
+    fn source(&self, start: usize, end: usize) -> String {
+        if start == 0 || start > self.lines.len() {
+            return String::new();
+        }
+        self.lines[start - 1..end.min(self.lines.len())].join("\n")
+    }
+
+    fn items(&mut self, items: &[Item]) {
+        for item in items {
+            self.item(item);
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::Fn(f) => {
+                self.function(&f.attrs, &f.vis, &f.sig, Some(&f.block), None, false);
+            }
+            Item::Struct(s) => {
+                let (start, end) = lines_of(s.ident.span(), s.span());
+                self.types.push(TypeDefinition {
+                    name: s.ident.to_string(),
+                    kind: "struct".to_string(),
+                    members: fields(&s.fields),
+                    methods: Vec::new(),
+                    documentation: doc_comment(&s.attrs),
+                    line_range: (start, end),
+                    source: self.source(start, end),
+                    intent: String::new(),
+                    generics: generics(&s.generics),
+                    implements: Vec::new(),
+                });
+            }
+            Item::Enum(e) => {
+                let (start, end) = lines_of(e.ident.span(), e.span());
+                let members = e
+                    .variants
+                    .iter()
+                    .map(|v| Member {
+                        name: v.ident.to_string(),
+                        type_hint: match v.fields {
+                            Fields::Unit => None,
+                            _ => Some(tokens(&v.fields)),
+                        },
+                        visibility: visibility(&e.vis),
+                        documentation: doc_comment(&v.attrs),
+                    })
+                    .collect();
+                self.types.push(TypeDefinition {
+                    name: e.ident.to_string(),
+                    kind: "enum".to_string(),
+                    members,
+                    methods: Vec::new(),
+                    documentation: doc_comment(&e.attrs),
+                    line_range: (start, end),
+                    source: self.source(start, end),
+                    intent: String::new(),
+                    generics: generics(&e.generics),
+                    implements: Vec::new(),
+                });
+            }
+            Item::Union(u) => {
+                let (start, end) = lines_of(u.ident.span(), u.span());
+                self.types.push(TypeDefinition {
+                    name: u.ident.to_string(),
+                    kind: "union".to_string(),
+                    members: fields(&Fields::Named(u.fields.clone())),
+                    methods: Vec::new(),
+                    documentation: doc_comment(&u.attrs),
+                    line_range: (start, end),
+                    source: self.source(start, end),
+                    intent: String::new(),
+                    generics: generics(&u.generics),
+                    implements: Vec::new(),
+                });
+            }
+            Item::Type(t) => {
+                let (start, end) = lines_of(t.ident.span(), t.span());
+                self.types.push(TypeDefinition {
+                    name: t.ident.to_string(),
+                    kind: "type alias".to_string(),
+                    members: Vec::new(),
+                    methods: Vec::new(),
+                    documentation: doc_comment(&t.attrs),
+                    line_range: (start, end),
+                    source: self.source(start, end),
+                    intent: String::new(),
+                    generics: generics(&t.generics),
+                    implements: Vec::new(),
+                });
+            }
+            Item::Trait(t) => {
+                let name = t.ident.to_string();
+                let (start, end) = lines_of(t.ident.span(), t.span());
+                let mut methods = Vec::new();
+                let mut members = Vec::new();
+                for trait_item in &t.items {
+                    match trait_item {
+                        TraitItem::Fn(f) => {
+                            methods.push(f.sig.ident.to_string());
+                            self.function(
+                                &f.attrs,
+                                &t.vis,
+                                &f.sig,
+                                f.default.as_ref(),
+                                Some(name.clone()),
+                                false,
+                            );
+                        }
+                        TraitItem::Type(ty) => members.push(Member {
+                            name: ty.ident.to_string(),
+                            type_hint: Some("type".to_string()),
+                            visibility: visibility(&t.vis),
+                            documentation: doc_comment(&ty.attrs),
+                        }),
+                        TraitItem::Const(c) => members.push(Member {
+                            name: c.ident.to_string(),
+                            type_hint: Some(tokens(&c.ty)),
+                            visibility: visibility(&t.vis),
+                            documentation: doc_comment(&c.attrs),
+                        }),
+                        _ => {}
+                    }
+                }
+                self.types.push(TypeDefinition {
+                    name,
+                    kind: "trait".to_string(),
+                    members,
+                    methods,
+                    documentation: doc_comment(&t.attrs),
+                    line_range: (start, end),
+                    source: self.source(start, end),
+                    intent: String::new(),
+                    generics: generics(&t.generics),
+                    // Supertraits
+                    implements: t.supertraits.iter().map(|b| base_name(&tokens(b))).collect(),
+                });
+            }
+            Item::Impl(i) => {
+                let self_type = tokens(&*i.self_ty);
+                let owner = base_name(&self_type);
+                let trait_name = i.trait_.as_ref().map(|(negative, path, _)| {
+                    format!("{}{}", if negative.is_some() { "!" } else { "" }, tokens(path))
+                });
+                let mut methods = Vec::new();
+                for impl_item in &i.items {
+                    if let ImplItem::Fn(f) = impl_item {
+                        methods.push(f.sig.ident.to_string());
+                        self.function(
+                            &f.attrs,
+                            &f.vis,
+                            &f.sig,
+                            Some(&f.block),
+                            Some(owner.clone()),
+                            trait_name.is_some(),
+                        );
+                    }
+                }
+                let (start, end) = lines_of(i.impl_token.span, i.span());
+                self.impls.push(ImplDefinition {
+                    self_type,
+                    trait_name,
+                    generics: generics(&i.generics),
+                    methods,
+                    line_range: (start, end),
+                });
+            }
+            Item::Mod(m) => {
+                let name = m.ident.to_string();
+                let mut path = self.module_path.clone();
+                path.push(name.clone());
+                let exports = m
+                    .content
+                    .as_ref()
+                    .map(|(_, items)| items.iter().filter_map(public_name).collect())
+                    .unwrap_or_default();
+                self.modules.push(ModuleDefinition {
+                    name,
+                    path: path.join("::"),
+                    exports,
+                    documentation: doc_comment(&m.attrs),
+                    inline: m.content.is_some(),
+                });
+                if let Some((_, items)) = &m.content {
+                    let was_test = self.in_test;
+                    self.in_test |= has_cfg_test(&m.attrs);
+                    self.module_path = path;
+                    self.items(items);
+                    self.module_path.pop();
+                    self.in_test = was_test;
+                }
+            }
+            Item::Const(c) => self.constants.push(ConstantDefinition {
+                name: c.ident.to_string(),
+                value: truncate(&tokens(&*c.expr), 200),
+                type_hint: Some(tokens(&*c.ty)),
+                documentation: doc_comment(&c.attrs),
+                line: c.ident.span().start().line,
+            }),
+            Item::Static(s) => self.constants.push(ConstantDefinition {
+                name: s.ident.to_string(),
+                value: truncate(&tokens(&*s.expr), 200),
+                type_hint: Some(tokens(&*s.ty)),
+                documentation: doc_comment(&s.attrs),
+                line: s.ident.span().start().line,
+            }),
+            Item::Use(u) => {
+                let import_type = match u.vis {
+                    Visibility::Inherited => "use",
+                    _ => "pub use",
+                };
+                let line = u.span().start().line;
+                let mut leaves = Vec::new();
+                flatten_use(&u.tree, String::new(), &mut leaves);
+                for (path, item) in leaves {
+                    self.imports.push(ImportDefinition {
+                        path: relative_to_file(&path, &self.module_path),
+                        items: vec![item],
+                        import_type: import_type.to_string(),
+                        line,
+                    });
+                }
+            }
+            Item::ExternCrate(e) => self.imports.push(ImportDefinition {
+                path: e.ident.to_string(),
+                items: vec![e
+                    .rename
+                    .as_ref()
+                    .map(|(_, r)| r.to_string())
+                    .unwrap_or_else(|| e.ident.to_string())],
+                import_type: "extern crate".to_string(),
+                line: e.span().start().line,
+            }),
+            Item::Macro(m) => {
+                if let Some(ident) = &m.ident {
+                    let (start, end) = lines_of(ident.span(), m.span());
+                    self.macros.push(MacroDefinition {
+                        name: ident.to_string(),
+                        kind: "macro_rules".to_string(),
+                        exported: m.attrs.iter().any(|a| a.path().is_ident("macro_export")),
+                        documentation: doc_comment(&m.attrs),
+                        line_range: (start, end),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn function(
+        &mut self,
+        attrs: &[Attribute],
+        vis: &Visibility,
+        sig: &Signature,
+        block: Option<&Block>,
+        owner: Option<String>,
+        trait_impl: bool,
+    ) {
+        let name = sig.ident.to_string();
+        let qualified = match &owner {
+            Some(owner) => format!("{}::{}", owner, name),
+            None => name.clone(),
+        };
+        let start = sig.span().start().line;
+        let end = block.map(|b| b.span().end().line).unwrap_or_else(|| sig.span().end().line);
+        let is_test = self.in_test || attrs.iter().any(|a| last_segment(a.path()) == "test");
+
+        let mut parameters = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(r) => parameters.push(Parameter {
+                    name: "self".to_string(),
+                    type_hint: Some(tokens(r)),
+                    default_value: None,
+                }),
+                FnArg::Typed(t) => parameters.push(Parameter {
+                    name: tokens(&*t.pat),
+                    type_hint: Some(tokens(&*t.ty)),
+                    default_value: None,
+                }),
+            }
+        }
+        let param_names: Vec<String> = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(t) => match &*t.pat {
+                    Pat::Ident(p) => Some(p.ident.to_string()),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+
+        let mut body = BodyVisitor::new(param_names);
+        if let Some(block) = block {
+            body.visit_block(block);
+        }
+
+        let mut internal_dependencies: Vec<String> = Vec::new();
+        for (callee, _, _) in &body.calls {
+            if !internal_dependencies.contains(callee) {
+                internal_dependencies.push(callee.clone());
+            }
+        }
+        let cyclomatic = 1 + body.decisions;
+
+        // Proc macros are both functions and macro definitions.
+        for attr in attrs {
+            let kind = match last_segment(attr.path()).as_str() {
+                "proc_macro" => "proc_macro",
+                "proc_macro_derive" => "derive",
+                "proc_macro_attribute" => "attribute",
+                _ => continue,
+            };
+            self.macros.push(MacroDefinition {
+                name: name.clone(),
+                kind: kind.to_string(),
+                exported: true,
+                documentation: doc_comment(attrs),
+                line_range: (start, end),
+            });
+        }
+
+        let visibility = if trait_impl && matches!(vis, Visibility::Inherited) {
+            "trait impl".to_string()
+        } else {
+            visibility(vis)
+        };
+        let is_entry = !is_test
+            && (name == "main"
+                || attrs.iter().any(|a| last_segment(a.path()) == "main")
+                || (owner.is_none() && visibility == "pub"));
+
+        self.functions.push(FunctionDefinition {
+            name,
+            signature: format!("{}{}", visibility_prefix(vis), tokens(sig)),
+            parameters,
+            return_type: match &sig.output {
+                ReturnType::Default => None,
+                ReturnType::Type(_, ty) => Some(tokens(&**ty)),
+            },
+            visibility,
+            documentation: doc_comment(attrs),
+            line_range: (start, end),
+            body: if block.is_some() { self.source(start, end) } else { String::new() },
+            intent: String::new(),
+            internal_dependencies,
+            complexity: cyclomatic,
+            owner,
+            generics: generics(&sig.generics),
+        });
+
+        self.complexity.cyclomatic += cyclomatic;
+        self.complexity.cognitive += body.cognitive;
+        self.complexity.branches += body.branches;
+        self.complexity.max_nesting = self.complexity.max_nesting.max(body.max_nesting);
+
+        if !is_test {
+            if body.unwraps > 0 {
+                self.facts.smells.push(smell(
+                    "panic_prone_unwrap",
+                    &qualified,
+                    start,
+                    if body.unwraps >= 3 { "medium" } else { "low" },
+                    format!(
+                        "`{}` calls unwrap()/expect() {} time(s); propagate errors with `?` instead",
+                        qualified, body.unwraps
+                    ),
+                ));
+            }
+            for (mac, line) in &body.unfinished {
+                self.facts.smells.push(smell(
+                    "unfinished_code",
+                    &qualified,
+                    *line,
+                    "medium",
+                    format!("`{}` contains `{}` and panics when reached", qualified, mac),
+                ));
+            }
+            for line in &body.unsafe_blocks {
+                self.facts.smells.push(smell(
+                    "unsafe_block",
+                    &qualified,
+                    *line,
+                    "medium",
+                    format!("`{}` contains an unsafe block", qualified),
+                ));
+            }
+        }
+
+        for (callee, line, kind) in &body.calls {
+            self.facts.call_graph.push(CallEdge {
+                caller: qualified.clone(),
+                callee: callee.clone(),
+                line: *line,
+                kind: kind.to_string(),
+            });
+        }
+        for (path, line) in &body.references {
+            self.facts
+                .references
+                .push((relative_to_file(path, &self.module_path), *line, qualified.clone()));
+        }
+        for (param, sinks) in &body.param_sinks {
+            let transformations = body.param_methods.get(param).cloned().unwrap_or_default();
+            self.facts.data_flow.push(DataFlowPath {
+                name: param.clone(),
+                source: qualified.clone(),
+                sinks: sinks.iter().cloned().collect(),
+                transformations: transformations.into_iter().collect(),
+            });
+        }
+        for (param, methods) in &body.param_methods {
+            if !body.param_sinks.contains_key(param) {
+                self.facts.data_flow.push(DataFlowPath {
+                    name: param.clone(),
+                    source: qualified.clone(),
+                    sinks: Vec::new(),
+                    transformations: methods.iter().cloned().collect(),
+                });
+            }
+        }
+        push_unique(&mut self.facts.env_vars, body.env_vars);
+        push_unique(&mut self.facts.config_files, body.config_files);
+        self.facts
+            .side_effects
+            .extend(body.side_effects.into_iter().map(|e| format!("{}: {}", qualified, e)));
+        self.facts
+            .exits
+            .extend(body.exits.into_iter().map(|e| format!("{}: {}", qualified, e)));
+        self.facts.functions.push(FunctionFacts {
+            name: qualified,
+            max_nesting: body.max_nesting,
+            conditions: body.conditions,
+            is_test,
+            is_entry,
+        });
+    }
+
+    fn finish(mut self, documentation: Option<String>) -> (CodeStructure, SyntaxFacts) {
+        // Resolve impl blocks onto the types defined in this file.
+        for imp in &self.impls {
+            let base = base_name(&imp.self_type);
+            if let Some(ty) = self
+                .types
+                .iter_mut()
+                .find(|t| t.name == base && t.kind != "trait")
+            {
+                for method in &imp.methods {
+                    if !ty.methods.contains(method) {
+                        ty.methods.push(method.clone());
+                    }
+                }
+                if let Some(trait_name) = &imp.trait_name {
+                    let trait_base = base_name(trait_name);
+                    if !ty.implements.contains(&trait_base) {
+                        ty.implements.push(trait_base);
+                    }
+                }
+            }
+        }
+
+        let structure = CodeStructure {
+            functions: self.functions,
+            types: self.types,
+            modules: self.modules,
+            constants: self.constants,
+            imports: self.imports,
+            documentation,
+            lines_of_code: self.lines.len(),
+            complexity: self.complexity,
+            impls: self.impls,
+            macros: self.macros,
+        };
+        (structure, self.facts)
+    }
+}
+
+/// Complexity, calls and side effects of one function body.
+///
+/// Nested items (functions, impls) are not part of the body and are skipped.
+#[derive(Default)]
+struct BodyVisitor {
+    params: Vec<String>,
+    decisions: usize,
+    cognitive: usize,
+    nesting: usize,
+    max_nesting: usize,
+    branches: usize,
+    conditions: Vec<String>,
+    calls: Vec<(String, usize, &'static str)>,
+    references: Vec<(String, usize)>,
+    unwraps: usize,
+    unfinished: Vec<(String, usize)>,
+    unsafe_blocks: Vec<usize>,
+    env_vars: Vec<String>,
+    config_files: Vec<String>,
+    side_effects: BTreeSet<String>,
+    exits: BTreeSet<String>,
+    param_sinks: BTreeMap<String, BTreeSet<String>>,
+    param_methods: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl BodyVisitor {
+    fn new(params: Vec<String>) -> Self {
+        Self {
+            params,
+            ..Default::default()
+        }
+    }
+
+    /// A branching construct: one cyclomatic decision, cognitive cost grows with nesting.
+    fn decision(&mut self) {
+        self.decisions += 1;
+        self.branches += 1;
+        self.cognitive += 1 + self.nesting;
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Self)) {
+        self.nesting += 1;
+        self.max_nesting = self.max_nesting.max(self.nesting);
+        f(self);
+        self.nesting -= 1;
+    }
+
+    fn record_call(&mut self, callee: String, line: usize, kind: &'static str, args: &[&Expr]) {
+        for arg in args {
+            for ident in idents_in(arg) {
+                if self.params.contains(&ident) {
+                    self.param_sinks.entry(ident).or_default().insert(callee.clone());
+                }
+            }
+        }
+        self.calls.push((callee, line, kind));
+    }
+}
+
+impl<'ast> Visit<'ast> for BodyVisitor {
+    fn visit_item(&mut self, _: &'ast Item) {}
+
+    fn visit_expr_if(&mut self, i: &'ast syn::ExprIf) {
+        self.decision();
+        self.conditions.push(truncate(&tokens(&*i.cond), 120));
+        self.visit_expr(&i.cond);
+        self.nested(|v| v.visit_block(&i.then_branch));
+        if let Some((_, else_branch)) = &i.else_branch {
+            match &**else_branch {
+                // `else if` continues the chain rather than nesting deeper.
+                Expr::If(else_if) => self.visit_expr_if(else_if),
+                other => {
+                    self.branches += 1;
+                    self.cognitive += 1;
+                    self.nested(|v| v.visit_expr(other));
+                }
+            }
+        }
+    }
+
+    fn visit_expr_while(&mut self, i: &'ast syn::ExprWhile) {
+        self.decision();
+        self.conditions.push(truncate(&tokens(&*i.cond), 120));
+        self.visit_expr(&i.cond);
+        self.nested(|v| v.visit_block(&i.body));
+    }
+
+    fn visit_expr_for_loop(&mut self, i: &'ast syn::ExprForLoop) {
+        self.decision();
+        self.visit_expr(&i.expr);
+        self.nested(|v| v.visit_block(&i.body));
+    }
+
+    fn visit_expr_loop(&mut self, i: &'ast syn::ExprLoop) {
+        self.cognitive += 1 + self.nesting;
+        self.nested(|v| v.visit_block(&i.body));
+    }
+
+    fn visit_expr_match(&mut self, i: &'ast syn::ExprMatch) {
+        self.decisions += i.arms.len().saturating_sub(1);
+        self.branches += i.arms.len();
+        self.cognitive += 1 + self.nesting;
+        self.visit_expr(&i.expr);
+        self.nested(|v| {
+            for arm in &i.arms {
+                if let Some((_, guard)) = &arm.guard {
+                    v.decisions += 1;
+                    v.visit_expr(guard);
+                }
+                v.visit_expr(&arm.body);
+            }
+        });
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast syn::ExprBinary) {
+        if matches!(i.op, BinOp::And(_) | BinOp::Or(_)) {
+            self.decisions += 1;
+            self.cognitive += 1;
+        }
+        visit::visit_expr_binary(self, i);
+    }
+
+    fn visit_expr_closure(&mut self, i: &'ast syn::ExprClosure) {
+        self.nested(|v| visit::visit_expr_closure(v, i));
+    }
+
+    fn visit_expr_unsafe(&mut self, i: &'ast syn::ExprUnsafe) {
+        self.unsafe_blocks.push(i.unsafe_token.span.start().line);
+        self.nested(|v| visit::visit_expr_unsafe(v, i));
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast syn::ExprCall) {
+        if let Expr::Path(ExprPath { path, .. }) = &*i.func {
+            let callee = path_string(path);
+            let line = path.span().start().line;
+            let segments: Vec<&str> = callee.split("::").collect();
+            if segments.len() >= 2 && segments[segments.len() - 2] == "env" {
+                match segments[segments.len() - 1] {
+                    "var" | "var_os" => {
+                        if let Some(Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. })) =
+                            i.args.first()
+                        {
+                            self.env_vars.push(s.value());
+                        }
+                    }
+                    "set_var" | "remove_var" => {
+                        self.side_effects.insert(format!("environment ({})", callee));
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(effect) = side_effect(&callee) {
+                self.side_effects.insert(format!("{} ({})", effect, callee));
+            }
+            if callee.ends_with("process::exit") || callee == "exit" {
+                self.exits.insert(callee.clone());
+            }
+            let args: Vec<&Expr> = i.args.iter().collect();
+            self.record_call(callee, line, "call", &args);
+        }
+        visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+        let method = i.method.to_string();
+        if method == "unwrap" || method == "expect" {
+            self.unwraps += 1;
+        }
+        if let Expr::Path(ExprPath { path, .. }) = &*i.receiver {
+            if let Some(ident) = path.get_ident().map(|id| id.to_string()) {
+                if self.params.contains(&ident) {
+                    self.param_methods.entry(ident).or_default().insert(method.clone());
+                }
+            }
+        }
+        let args: Vec<&Expr> = i.args.iter().collect();
+        self.record_call(method, i.method.span().start().line, "method", &args);
+        visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_macro(&mut self, m: &'ast syn::Macro) {
+        let name = last_segment(&m.path);
+        let line = m.path.span().start().line;
+        match name.as_str() {
+            "todo" | "unimplemented" => self.unfinished.push((format!("{}!", name), line)),
+            "panic" => {
+                self.exits.insert("panic!".to_string());
+            }
+            "println" | "print" | "eprintln" | "eprint" => {
+                self.side_effects.insert(format!("console output ({}!)", name));
+            }
+            _ => {}
+        }
+        // Macro arguments that parse as expressions (format!, vec!, assert!, ...) are visited
+        // so that calls inside them count.
+        let parsed = m.parse_body_with(
+            syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated,
+        );
+        let args: Vec<Expr> = parsed.map(|p| p.into_iter().collect()).unwrap_or_default();
+        let arg_refs: Vec<&Expr> = args.iter().collect();
+        self.record_call(format!("{}!", name), line, "macro", &arg_refs);
+        for arg in &args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_path(&mut self, p: &'ast syn::Path) {
+        if p.segments.len() >= 2 {
+            let first = p.segments[0].ident.to_string();
+            if matches!(first.as_str(), "crate" | "super" | "self") {
+                self.references.push((path_string(p), p.span().start().line));
+            }
+        }
+        visit::visit_path(self, p);
+    }
+
+    fn visit_lit_str(&mut self, s: &'ast syn::LitStr) {
+        let value = s.value();
+        let lower = value.to_ascii_lowercase();
+        let is_config = [".toml", ".json", ".yaml", ".yml", ".env", ".ini", ".conf"]
+            .iter()
+            .any(|ext| lower.ends_with(ext));
+        if is_config && !value.contains(char::is_whitespace) && !self.config_files.contains(&value) {
+            self.config_files.push(value);
+        }
+    }
+}
+
+/// Identifiers (single-segment paths) mentioned in an expression.
+fn idents_in(expr: &Expr) -> Vec<String> {
+    struct Idents(Vec<String>);
+    impl<'ast> Visit<'ast> for Idents {
+        fn visit_expr_path(&mut self, p: &'ast ExprPath) {
+            if let Some(ident) = p.path.get_ident() {
+                self.0.push(ident.to_string());
+            }
+        }
+    }
+    let mut idents = Idents(Vec::new());
+    idents.visit_expr(expr);
+    idents.0
+}
+
+fn side_effect(callee: &str) -> Option<&'static str> {
+    if callee.contains("fs::") || callee.starts_with("File::") || callee.starts_with("OpenOptions::") {
+        Some("filesystem")
+    } else if callee.ends_with("Command::new") {
+        Some("process")
+    } else if ["TcpStream::", "TcpListener::", "UdpSocket::", "reqwest::"]
+        .iter()
+        .any(|p| callee.contains(p))
+    {
+        Some("network")
+    } else if callee.ends_with("thread::spawn") || callee.ends_with("tokio::spawn") {
+        Some("spawns tasks")
+    } else {
+        None
+    }
+}
+
+fn flatten_use(tree: &UseTree, prefix: String, out: &mut Vec<(String, String)>) {
+    let join = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", prefix, name)
+        }
+    };
+    match tree {
+        UseTree::Path(p) => flatten_use(&p.tree, join(&p.ident.to_string()), out),
+        UseTree::Name(n) => {
+            let name = n.ident.to_string();
+            // `use foo::{self}` imports the module itself.
+            if name == "self" {
+                let item = prefix.rsplit("::").next().unwrap_or_default().to_string();
+                out.push((prefix.clone(), item));
+            } else {
+                out.push((join(&name), name));
+            }
+        }
+        UseTree::Rename(r) => out.push((join(&r.ident.to_string()), r.rename.to_string())),
+        UseTree::Glob(_) => out.push((join("*"), "*".to_string())),
+        UseTree::Group(g) => {
+            for item in &g.items {
+                flatten_use(item, prefix.clone(), out);
+            }
+        }
+    }
+}
+
+/// Rewrite a `self::`/`super::` path written inside the inline module `inline` so that it is
+/// relative to the file's own module.
+fn relative_to_file(path: &str, inline: &[String]) -> String {
+    if inline.is_empty() {
+        return path.to_string();
+    }
+    let mut segments: Vec<&str> = path.split("::").collect();
+    let mut base: Vec<String> = inline.to_vec();
+    match segments.first() {
+        Some(&"self") => {
+            segments.remove(0);
+        }
+        Some(&"super") => {
+            while segments.first() == Some(&"super") && !base.is_empty() {
+                segments.remove(0);
+                base.pop();
+            }
+            if segments.first() == Some(&"super") {
+                return segments.join("::");
+            }
+        }
+        _ => return path.to_string(),
+    }
+    std::iter::once("self".to_string())
+        .chain(base)
+        .chain(segments.into_iter().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn fields(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Member {
+            name: f.ident.as_ref().map(|id| id.to_string()).unwrap_or_else(|| i.to_string()),
+            type_hint: Some(tokens(&f.ty)),
+            visibility: visibility(&f.vis),
+            documentation: doc_comment(&f.attrs),
+        })
+        .collect()
+}
+
+fn generics(generics: &Generics) -> Vec<String> {
+    let mut params: Vec<String> = generics.params.iter().map(tokens).collect();
+    if let Some(where_clause) = &generics.where_clause {
+        params.extend(where_clause.predicates.iter().map(tokens));
+    }
+    params
+}
+
+/// Name of a public item, for module export lists.
+fn public_name(item: &Item) -> Option<String> {
+    let (vis, name) = match item {
+        Item::Fn(f) => (&f.vis, f.sig.ident.to_string()),
+        Item::Struct(s) => (&s.vis, s.ident.to_string()),
+        Item::Enum(e) => (&e.vis, e.ident.to_string()),
+        Item::Trait(t) => (&t.vis, t.ident.to_string()),
+        Item::Type(t) => (&t.vis, t.ident.to_string()),
+        Item::Const(c) => (&c.vis, c.ident.to_string()),
+        Item::Static(s) => (&s.vis, s.ident.to_string()),
+        Item::Mod(m) => (&m.vis, m.ident.to_string()),
+        _ => return None,
+    };
+    matches!(vis, Visibility::Public(_)).then_some(name)
+}
+
+fn has_cfg_test(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|a| a.path().is_ident("cfg") && tokens(&a.meta).contains("test"))
+}
+
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n").trim().to_string())
+    }
+}
+
+fn visibility(vis: &Visibility) -> String {
+    match vis {
+        Visibility::Public(_) => "pub".to_string(),
+        Visibility::Restricted(_) => tokens(vis),
+        Visibility::Inherited => "private".to_string(),
+    }
+}
+
+fn visibility_prefix(vis: &Visibility) -> String {
+    match vis {
+        Visibility::Inherited => String::new(),
+        _ => format!("{} ", tokens(vis)),
+    }
+}
+
+/// Start line of `name`, end line of `whole`.
+fn lines_of(name: proc_macro2::Span, whole: proc_macro2::Span) -> (usize, usize) {
+    let start = name.start().line;
+    (start, whole.end().line.max(start))
+}
+
+fn path_string(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn last_segment(path: &syn::Path) -> String {
+    path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default()
+}
+
+/// Token stream as compact, human-readable source.
+fn tokens<T: ToTokens>(node: &T) -> String {
+    let raw = node.to_token_stream().to_string();
+    let s = raw
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" : ", ": ")
+        .replace(" , ", ", ")
+        .replace(" ,", ",")
+        .replace(" ;", ";")
+        .replace("& ", "&")
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace("[ ", "[")
+        .replace(" ]", "]")
+        .replace(" . ", ".")
+        .replace(" ! (", "!(")
+        .replace(" ! [", "![")
+        .replace(" ! {", "!{")
+        .replace(" ?", "?");
+    // `name (` -> `name(`, but keep `-> (` and `, (` intact.
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len());
+    for (i, c) in chars.iter().enumerate() {
+        let before = if i > 0 { chars[i - 1] } else { ' ' };
+        let joins_call = *c == ' '
+            && chars.get(i + 1) == Some(&'(')
+            && (before.is_alphanumeric()
+                || before == '_'
+                || (before == '>' && i >= 2 && chars[i - 2] != '-'));
+        if !joins_call {
+            out.push(*c);
+        }
+    }
+    out
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max).collect::<String>())
+    }
+}
+
+fn push_unique(target: &mut Vec<String>, values: Vec<String>) {
+    for value in values {
+        if !target.contains(&value) {
+            target.push(value);
+        }
+    }
+}
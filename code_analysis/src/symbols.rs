@@ -0,0 +1,428 @@
+//! Workspace symbol index
+//!
+//! Built from the per-file results of a codebase analysis, it answers structural questions
+//! across files: fuzzy symbol search, go-to-definition by name, find-references and
+//! "what calls this function". Names may be plain (`analyze_file`) or qualified with an owner
+//! or module (`CodeAnalyzer::analyze_file`, `Store.get`, `my_crate::graph::normalize`).
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{graph, CodeStructure, CodebaseAnalysis, UsageLocation};
+
+/// A definition somewhere in the codebase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Symbol {
+    /// Bare name
+    pub name: String,
+
+    /// Module path, owner and name (`crate::a::Type::method`)
+    pub qualified_name: String,
+
+    /// Symbol kind (function, method, struct, class, enum, trait, constant, macro, module, ...)
+    pub kind: String,
+
+    /// Owning type or trait for methods
+    pub owner: Option<String>,
+
+    /// File defining the symbol
+    pub file_path: PathBuf,
+
+    /// Line range
+    pub line_range: (usize, usize),
+
+    /// Signature for functions and methods
+    pub signature: Option<String>,
+
+    /// First line of the documentation
+    pub summary: Option<String>,
+}
+
+/// A symbol search hit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolMatch {
+    pub symbol: Symbol,
+
+    /// Higher is better
+    pub score: u32,
+}
+
+/// A use of a name found by find-references
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reference {
+    pub file_path: PathBuf,
+    pub location: UsageLocation,
+}
+
+/// A call site found by a callers query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallSite {
+    pub file_path: PathBuf,
+
+    /// Calling function (`Type::method` for methods)
+    pub caller: String,
+
+    /// Callee as written
+    pub callee: String,
+
+    /// Line number
+    pub line: usize,
+
+    /// Call kind (call, method, macro)
+    pub kind: String,
+}
+
+struct IndexedFile {
+    path: PathBuf,
+    structure: CodeStructure,
+}
+
+/// Symbols, call sites and function bodies of an analyzed codebase
+pub struct SymbolIndex {
+    symbols: Vec<Symbol>,
+    calls: Vec<CallSite>,
+    files: Vec<IndexedFile>,
+}
+
+impl SymbolIndex {
+    /// Index every file of `codebase`
+    pub fn new(codebase: &CodebaseAnalysis) -> Self {
+        let mut symbols = Vec::new();
+        let mut calls = Vec::new();
+        let mut files = Vec::new();
+
+        for file in &codebase.files {
+            let path = &file.file_path;
+            let qualify = |owner: Option<&str>, name: &str| {
+                file.module_path
+                    .iter()
+                    .map(String::as_str)
+                    .chain(owner)
+                    .chain([name])
+                    .collect::<Vec<_>>()
+                    .join("::")
+            };
+            let structure = &file.structure;
+
+            for f in &structure.functions {
+                symbols.push(Symbol {
+                    name: f.name.clone(),
+                    qualified_name: qualify(f.owner.as_deref(), &f.name),
+                    kind: if f.owner.is_some() { "method" } else { "function" }.to_string(),
+                    owner: f.owner.clone(),
+                    file_path: path.clone(),
+                    line_range: f.line_range,
+                    signature: Some(f.signature.clone()),
+                    summary: summary(f.documentation.as_deref()),
+                });
+            }
+            for t in &structure.types {
+                symbols.push(Symbol {
+                    name: t.name.clone(),
+                    qualified_name: qualify(None, &t.name),
+                    kind: t.kind.clone(),
+                    owner: None,
+                    file_path: path.clone(),
+                    line_range: t.line_range,
+                    signature: None,
+                    summary: summary(t.documentation.as_deref()),
+                });
+            }
+            for c in &structure.constants {
+                symbols.push(Symbol {
+                    name: c.name.clone(),
+                    qualified_name: qualify(None, &c.name),
+                    kind: "constant".to_string(),
+                    owner: None,
+                    file_path: path.clone(),
+                    line_range: (c.line, c.line),
+                    signature: None,
+                    summary: summary(c.documentation.as_deref()),
+                });
+            }
+            for m in &structure.macros {
+                symbols.push(Symbol {
+                    name: m.name.clone(),
+                    qualified_name: qualify(None, &m.name),
+                    kind: "macro".to_string(),
+                    owner: None,
+                    file_path: path.clone(),
+                    line_range: m.line_range,
+                    signature: None,
+                    summary: summary(m.documentation.as_deref()),
+                });
+            }
+            // Only inline modules are defined here; file modules are defined by their own file.
+            for m in structure.modules.iter().filter(|m| m.inline) {
+                symbols.push(Symbol {
+                    name: m.name.clone(),
+                    qualified_name: qualify(None, &m.name),
+                    kind: "module".to_string(),
+                    owner: None,
+                    file_path: path.clone(),
+                    line_range: (0, 0),
+                    signature: None,
+                    summary: summary(m.documentation.as_deref()),
+                });
+            }
+
+            calls.extend(file.flow.call_graph.iter().map(|edge| CallSite {
+                file_path: path.clone(),
+                caller: edge.caller.clone(),
+                callee: edge.callee.clone(),
+                line: edge.line,
+                kind: edge.kind.clone(),
+            }));
+            files.push(IndexedFile {
+                path: path.clone(),
+                structure: structure.clone(),
+            });
+        }
+
+        symbols.sort_by(|a, b| {
+            (&a.file_path, a.line_range, &a.qualified_name).cmp(&(&b.file_path, b.line_range, &b.qualified_name))
+        });
+        Self { symbols, calls, files }
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Fuzzy search by name: exact matches first, then prefixes, substrings and finally
+    /// subsequences (`anfi` finds `analyze_file`). Case-insensitive.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SymbolMatch> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        // Qualified queries are matched against qualified names.
+        let qualified = query.contains("::") || query.contains('.');
+        let query = query.replace('.', "::");
+        let mut matches: Vec<SymbolMatch> = self
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let candidate = if qualified { &symbol.qualified_name } else { &symbol.name };
+                fuzzy_score(&query, &candidate.to_lowercase()).map(|score| SymbolMatch {
+                    symbol: symbol.clone(),
+                    score,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.symbol.name.len().cmp(&b.symbol.name.len()))
+                .then_with(|| a.symbol.qualified_name.cmp(&b.symbol.qualified_name))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Definitions named exactly `name`. A qualified name must match the trailing segments of
+    /// the symbol's qualified name (`Type::method` matches `crate::a::Type::method`).
+    pub fn definitions(&self, name: &str) -> Vec<&Symbol> {
+        let segments = segments(name);
+        if segments.is_empty() {
+            return Vec::new();
+        }
+        self.symbols
+            .iter()
+            .filter(|symbol| {
+                let qualified: Vec<&str> = symbol.qualified_name.split("::").collect();
+                qualified.ends_with(&segments)
+            })
+            .collect()
+    }
+
+    /// Where `name` is used: imports of it and whole-word uses inside function bodies,
+    /// excluding the definitions themselves.
+    pub fn references(&self, name: &str) -> Vec<Reference> {
+        let Some(term) = segments(name).last().map(|s| s.to_string()) else {
+            return Vec::new();
+        };
+        let definitions: BTreeSet<(PathBuf, usize)> = self
+            .definitions(name)
+            .into_iter()
+            .map(|symbol| (symbol.file_path.clone(), symbol.line_range.0))
+            .collect();
+
+        let mut seen = BTreeSet::new();
+        let mut references = Vec::new();
+        for file in &self.files {
+            let imports = file
+                .structure
+                .imports
+                .iter()
+                .filter(|import| {
+                    import.items.iter().any(|item| item == &term)
+                        || segments(&import.path).last() == Some(&term.as_str())
+                })
+                .map(|import| UsageLocation {
+                    context: import.path.clone(),
+                    line: import.line,
+                    usage_type: "import".to_string(),
+                });
+            let uses = graph::usage_locations(&file.structure, &term);
+            for location in imports.chain(uses) {
+                let key = (file.path.clone(), location.line);
+                if definitions.contains(&key) || !seen.insert(key) {
+                    continue;
+                }
+                references.push(Reference {
+                    file_path: file.path.clone(),
+                    location,
+                });
+            }
+        }
+        references
+    }
+
+    /// Call sites of `name`. For a qualified name (`Type::method`), calls through another
+    /// qualifier are left out, and method calls whose receiver type is unknown are kept only
+    /// when they come from the owner itself or no other indexed type has a method of that name.
+    pub fn callers(&self, name: &str) -> Vec<&CallSite> {
+        let segments = segments(name);
+        let Some((term, qualifier)) = segments.split_last() else {
+            return Vec::new();
+        };
+        let owner = qualifier.last().copied();
+        let owners: BTreeSet<&str> = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.name == *term)
+            .filter_map(|symbol| symbol.owner.as_deref())
+            .collect();
+
+        self.calls
+            .iter()
+            .filter(|call| {
+                let (callee_qualifier, callee_name) = split_callee(&call.callee);
+                if callee_name != *term {
+                    return false;
+                }
+                let Some(owner) = owner else {
+                    return true;
+                };
+                let from_owner = call.caller.split("::").next() == Some(owner);
+                match callee_qualifier {
+                    Some("Self" | "self" | "this") => from_owner,
+                    Some(qualifier) => qualifier == owner,
+                    None if call.kind == "method" => from_owner || owners.iter().eq([&owner]),
+                    None => false,
+                }
+            })
+            .collect()
+    }
+}
+
+/// `a::b::c`, `a.b.c` and `a::b.c` all split into `["a", "b", "c"]`.
+fn segments(name: &str) -> Vec<&str> {
+    name.trim()
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// The last qualifier segment and the bare name of a callee as written
+/// (`Self::bar`, `self.store.get`, `format!`, `new Foo`).
+fn split_callee(callee: &str) -> (Option<&str>, &str) {
+    let callee = callee.strip_prefix("new ").unwrap_or(callee).trim_end_matches('!');
+    let parts = segments(callee);
+    match parts.split_last() {
+        Some((name, [.., qualifier])) => (Some(*qualifier), *name),
+        Some((name, [])) => (None, *name),
+        None => (None, ""),
+    }
+}
+
+fn summary(documentation: Option<&str>) -> Option<String> {
+    documentation?
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Score `candidate` against a lowercase `query`; `None` when it does not match at all.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    if candidate == query {
+        return Some(1000);
+    }
+    if candidate.starts_with(query) {
+        return Some(800);
+    }
+    if let Some(position) = candidate.find(query) {
+        // Matches at a word boundary (`analyze_file` for `file`) beat ones mid-word.
+        let boundary = candidate[..position].ends_with(['_', ':']);
+        return Some(if boundary { 700 } else { 600 });
+    }
+    // Subsequence: every query character in order, fewer gaps scoring higher.
+    let mut gaps = 0u32;
+    let mut rest = candidate.chars();
+    for wanted in query.chars() {
+        loop {
+            match rest.next() {
+                Some(c) if c == wanted => break,
+                Some(_) => gaps += 1,
+                None => return None,
+            }
+        }
+    }
+    Some(500u32.saturating_sub(gaps * 10).max(1))
+}
+
+#[cfg(all(test, feature = "rust-analysis"))]
+mod tests {
+    use crate::MasterOrchestratorCodeAnalysis;
+
+    #[tokio::test]
+    async fn test_symbol_queries() {
+        let root = std::env::temp_dir().join(format!("code_analysis_symbols_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"symbol-fixture\"\n").unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "mod store;\nuse crate::store::Store;\n\n/// Build a store.\npub fn make_store() -> Store {\n    let store = Store::open();\n    store.get();\n    store\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/store.rs"),
+            "pub struct Store;\n\nimpl Store {\n    pub fn open() -> Self {\n        Store\n    }\n\n    pub fn get(&self) {\n        self.check();\n    }\n\n    fn check(&self) {}\n}\n",
+        )
+        .unwrap();
+
+        let index = MasterOrchestratorCodeAnalysis::new().symbol_index(&root).await;
+        std::fs::remove_dir_all(&root).ok();
+        let index = index.unwrap();
+
+        let hits = index.search("mkst", 5);
+        assert_eq!(hits[0].symbol.qualified_name, "symbol_fixture::make_store");
+        assert_eq!(hits[0].symbol.summary.as_deref(), Some("Build a store."));
+        assert_eq!(index.search("store", 5)[0].symbol.name, "Store");
+
+        let definitions = index.definitions("Store::open");
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].qualified_name, "symbol_fixture::store::Store::open");
+        assert_eq!(definitions[0].line_range.0, 4);
+
+        let references = index.references("Store");
+        assert!(references
+            .iter()
+            .any(|r| r.file_path.ends_with("lib.rs") && r.location.usage_type == "import"));
+        assert!(references
+            .iter()
+            .any(|r| r.file_path.ends_with("lib.rs") && r.location.context == "make_store"));
+        assert!(!references.iter().any(|r| r.file_path.ends_with("store.rs") && r.location.line == 1));
+
+        let callers: Vec<&str> = index.callers("Store::open").iter().map(|c| c.caller.as_str()).collect();
+        assert_eq!(callers, vec!["make_store"]);
+        let callers: Vec<&str> = index.callers("Store::check").iter().map(|c| c.caller.as_str()).collect();
+        assert_eq!(callers, vec!["Store::get"]);
+        assert_eq!(index.callers("get").len(), 1);
+    }
+}
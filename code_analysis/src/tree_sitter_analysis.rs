@@ -0,0 +1,1909 @@
+//! Python, JavaScript, TypeScript, Go and Java structure analysis built on tree-sitter
+//!
+//! One walker serves every grammar; a `Spec` per language names the node kinds that define
+//! functions and types, branch, nest, call or comment. Each grammar sits behind its own feature
+//! (`python-analysis`, `javascript-analysis`, `typescript-analysis`, `go-analysis`,
+//! `java-analysis`); languages without an enabled grammar keep the regex fallback.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use tree_sitter::{Node, Parser, Tree};
+
+use crate::graph::base_name;
+use crate::metrics::{smell, FunctionFacts, SyntaxFacts};
+use crate::{
+    CallEdge, CodeStructure, ComplexityMetrics, ConstantDefinition, DataFlowPath,
+    FunctionDefinition, ImportDefinition, Language, Member, ModuleDefinition, Parameter,
+    TypeDefinition,
+};
+
+/// Whether a grammar for `language` is compiled in.
+pub(crate) fn supports(language: Language) -> bool {
+    match language {
+        #[cfg(feature = "python-analysis")]
+        Language::Python => true,
+        #[cfg(feature = "javascript-analysis")]
+        Language::JavaScript => true,
+        #[cfg(feature = "typescript-analysis")]
+        Language::TypeScript => true,
+        #[cfg(feature = "go-analysis")]
+        Language::Go => true,
+        #[cfg(feature = "java-analysis")]
+        Language::Java => true,
+        _ => false,
+    }
+}
+
+/// Parse `content` and collect its structure.
+pub(crate) fn analyze(
+    content: &str,
+    language: Language,
+) -> Result<(CodeStructure, SyntaxFacts), String> {
+    let tree = parse(content, language)?;
+    let mut walker = Walker::new(content, language);
+    let root = tree.root_node();
+    walker.documentation = walker.file_documentation(root);
+    walker.walk(root, None, false, true);
+    Ok(walker.finish())
+}
+
+fn parse(content: &str, language: Language) -> Result<Tree, String> {
+    let grammars: Vec<tree_sitter::Language> = match language {
+        #[cfg(feature = "python-analysis")]
+        Language::Python => vec![tree_sitter_python::LANGUAGE.into()],
+        #[cfg(feature = "javascript-analysis")]
+        Language::JavaScript => vec![tree_sitter_javascript::LANGUAGE.into()],
+        // Plain TypeScript first; `.tsx` sources only parse cleanly with the TSX grammar.
+        #[cfg(feature = "typescript-analysis")]
+        Language::TypeScript => vec![
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            tree_sitter_typescript::LANGUAGE_TSX.into(),
+        ],
+        #[cfg(feature = "go-analysis")]
+        Language::Go => vec![tree_sitter_go::LANGUAGE.into()],
+        #[cfg(feature = "java-analysis")]
+        Language::Java => vec![tree_sitter_java::LANGUAGE.into()],
+        other => return Err(format!("No tree-sitter grammar enabled for {:?}", other)),
+    };
+
+    // tree-sitter recovers from syntax errors; keep the first tree if none parses cleanly.
+    let mut fallback = None;
+    for grammar in grammars {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&grammar)
+            .map_err(|e| format!("Failed to load {:?} grammar: {}", language, e))?;
+        if let Some(tree) = parser.parse(content, None) {
+            if !tree.root_node().has_error() {
+                return Ok(tree);
+            }
+            fallback.get_or_insert(tree);
+        }
+    }
+    fallback.ok_or_else(|| format!("Failed to parse {:?} source", language))
+}
+
+/// Node kinds that matter to the walker, per grammar.
+struct Spec {
+    /// Named function/method definitions.
+    functions: &'static [&'static str],
+    /// Type definitions (classes, interfaces, enums, ...).
+    types: &'static [&'static str],
+    /// Branches: one cyclomatic decision each.
+    decisions: &'static [&'static str],
+    /// Continuations of a branch chain (`elif`, `else`) that do not nest deeper.
+    flat: &'static [&'static str],
+    /// Constructs that nest their body one level deeper.
+    nesting: &'static [&'static str],
+    calls: &'static [&'static str],
+    comments: &'static [&'static str],
+}
+
+const PYTHON: Spec = Spec {
+    functions: &["function_definition"],
+    types: &["class_definition"],
+    decisions: &[
+        "if_statement",
+        "elif_clause",
+        "for_statement",
+        "while_statement",
+        "except_clause",
+        "conditional_expression",
+        "case_clause",
+        "if_clause",
+    ],
+    flat: &["elif_clause", "else_clause"],
+    nesting: &[
+        "if_statement",
+        "for_statement",
+        "while_statement",
+        "try_statement",
+        "with_statement",
+        "match_statement",
+        "lambda",
+    ],
+    calls: &["call"],
+    comments: &["comment"],
+};
+
+const JAVASCRIPT: Spec = Spec {
+    functions: &[
+        "function_declaration",
+        "generator_function_declaration",
+        "method_definition",
+        "method_signature",
+        "abstract_method_signature",
+        "function_signature",
+    ],
+    types: &[
+        "class_declaration",
+        "abstract_class_declaration",
+        "interface_declaration",
+        "enum_declaration",
+        "type_alias_declaration",
+    ],
+    decisions: &[
+        "if_statement",
+        "for_statement",
+        "for_in_statement",
+        "while_statement",
+        "do_statement",
+        "switch_case",
+        "catch_clause",
+        "ternary_expression",
+    ],
+    flat: &["else_clause"],
+    nesting: &[
+        "if_statement",
+        "for_statement",
+        "for_in_statement",
+        "while_statement",
+        "do_statement",
+        "switch_statement",
+        "try_statement",
+        "arrow_function",
+        "function_expression",
+        "function",
+    ],
+    calls: &["call_expression", "new_expression"],
+    comments: &["comment"],
+};
+
+const GO: Spec = Spec {
+    functions: &["function_declaration", "method_declaration"],
+    types: &["type_spec"],
+    decisions: &[
+        "if_statement",
+        "for_statement",
+        "expression_case",
+        "type_case",
+        "communication_case",
+    ],
+    flat: &[],
+    nesting: &[
+        "if_statement",
+        "for_statement",
+        "expression_switch_statement",
+        "type_switch_statement",
+        "select_statement",
+        "func_literal",
+    ],
+    calls: &["call_expression"],
+    comments: &["comment"],
+};
+
+const JAVA: Spec = Spec {
+    functions: &["method_declaration", "constructor_declaration"],
+    types: &[
+        "class_declaration",
+        "interface_declaration",
+        "enum_declaration",
+        "record_declaration",
+    ],
+    decisions: &[
+        "if_statement",
+        "for_statement",
+        "enhanced_for_statement",
+        "while_statement",
+        "do_statement",
+        "catch_clause",
+        "ternary_expression",
+        "switch_label",
+        "switch_rule",
+    ],
+    flat: &[],
+    nesting: &[
+        "if_statement",
+        "for_statement",
+        "enhanced_for_statement",
+        "while_statement",
+        "do_statement",
+        "switch_expression",
+        "try_statement",
+        "lambda_expression",
+    ],
+    calls: &["method_invocation", "object_creation_expression"],
+    comments: &["line_comment", "block_comment", "comment"],
+};
+
+fn spec(language: Language) -> &'static Spec {
+    match language {
+        Language::Python => &PYTHON,
+        Language::Go => &GO,
+        Language::Java => &JAVA,
+        _ => &JAVASCRIPT,
+    }
+}
+
+fn named_children<'t>(node: Node<'t>) -> Vec<Node<'t>> {
+    (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .collect()
+}
+
+fn descendants<'t>(node: Node<'t>, kind: &str, out: &mut Vec<Node<'t>>) {
+    for child in named_children(node) {
+        if child.kind() == kind {
+            out.push(child);
+        }
+        descendants(child, kind, out);
+    }
+}
+
+fn line(node: Node) -> usize {
+    node.start_position().row + 1
+}
+
+fn end_line(node: Node) -> usize {
+    node.end_position().row + 1
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max).collect::<String>())
+    }
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn unquote(s: &str) -> String {
+    s.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string()
+}
+
+fn clean_comment(text: &str) -> String {
+    text.lines()
+        .map(|l| {
+            l.trim()
+                .trim_start_matches("/**")
+                .trim_start_matches("/*")
+                .trim_end_matches("*/")
+                .trim_start_matches("//")
+                .trim_start_matches('*')
+                .trim_start_matches('#')
+                .trim()
+        })
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct Walker<'s> {
+    source: &'s str,
+    language: Language,
+    spec: &'static Spec,
+    documentation: Option<String>,
+    package: Option<String>,
+    functions: Vec<FunctionDefinition>,
+    types: Vec<TypeDefinition>,
+    constants: Vec<ConstantDefinition>,
+    imports: Vec<ImportDefinition>,
+    complexity: ComplexityMetrics,
+    facts: SyntaxFacts,
+}
+
+impl<'s> Walker<'s> {
+    fn new(source: &'s str, language: Language) -> Self {
+        Self {
+            source,
+            language,
+            spec: spec(language),
+            documentation: None,
+            package: None,
+            functions: Vec::new(),
+            types: Vec::new(),
+            constants: Vec::new(),
+            imports: Vec::new(),
+            complexity: ComplexityMetrics {
+                cyclomatic: 0,
+                cognitive: 0,
+                max_nesting: 0,
+                branches: 0,
+            },
+            facts: SyntaxFacts::default(),
+        }
+    }
+
+    fn text(&self, node: Node) -> &'s str {
+        node.utf8_text(self.source.as_bytes()).unwrap_or("")
+    }
+
+    fn field_text(&self, node: Node, field: &str) -> Option<String> {
+        node.child_by_field_name(field).map(|n| self.text(n).to_string())
+    }
+
+    fn is_comment(&self, node: Node) -> bool {
+        self.spec.comments.contains(&node.kind())
+    }
+
+    /// Module docstring (Python) or the comment block at the top of the file.
+    fn file_documentation(&self, root: Node) -> Option<String> {
+        let first = named_children(root).into_iter().next()?;
+        if self.language == Language::Python {
+            return self.docstring_of(root);
+        }
+        if self.is_comment(first) && first.start_position().row <= 2 {
+            let mut lines = vec![clean_comment(self.text(first))];
+            let mut next = first.next_named_sibling();
+            while let Some(n) = next {
+                if !self.is_comment(n) {
+                    break;
+                }
+                lines.push(clean_comment(self.text(n)));
+                next = n.next_named_sibling();
+            }
+            let doc = lines.join("\n").trim().to_string();
+            return (!doc.is_empty()).then_some(doc);
+        }
+        None
+    }
+
+    /// Python docstring: a string as the first statement of a block.
+    fn docstring_of(&self, block: Node) -> Option<String> {
+        let first = named_children(block).into_iter().find(|n| !self.is_comment(*n))?;
+        if first.kind() != "expression_statement" {
+            return None;
+        }
+        let string = first.named_child(0).filter(|n| n.kind() == "string")?;
+        let raw = self.text(string);
+        let doc = raw
+            .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .trim_matches(|c| c == '"' || c == '\'')
+            .trim();
+        (!doc.is_empty()).then(|| doc.to_string())
+    }
+
+    /// Comments directly above a definition (or above its `export`/decorator/`type` wrapper).
+    fn doc_comment(&self, node: Node) -> Option<String> {
+        let mut anchor = node;
+        while let Some(parent) = anchor.parent() {
+            if matches!(parent.kind(), "export_statement" | "decorated_definition" | "type_declaration") {
+                anchor = parent;
+            } else {
+                break;
+            }
+        }
+        let mut lines = Vec::new();
+        let mut expected_row = anchor.start_position().row;
+        let mut previous = anchor.prev_named_sibling();
+        while let Some(p) = previous {
+            if !self.is_comment(p) || p.end_position().row + 1 < expected_row {
+                break;
+            }
+            lines.push(clean_comment(self.text(p)));
+            expected_row = p.start_position().row;
+            previous = p.prev_named_sibling();
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        lines.reverse();
+        let doc = lines.join("\n").trim().to_string();
+        (!doc.is_empty()).then_some(doc)
+    }
+
+    /// Collect definitions below `node`. `owner` is the enclosing type, `top` is module level.
+    fn walk(&mut self, node: Node, owner: Option<&str>, exported: bool, top: bool) {
+        for child in named_children(node) {
+            let kind = child.kind();
+            if self.spec.functions.contains(&kind) {
+                self.function(child, None, owner, exported);
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.walk(body, None, false, false);
+                }
+                continue;
+            }
+            if self.spec.types.contains(&kind) {
+                let name = self.type_definition(child);
+                if let Some(body) = child.child_by_field_name("body") {
+                    self.walk(body, Some(&name), false, false);
+                }
+                continue;
+            }
+            match kind {
+                "export_statement" => {
+                    if child.child_by_field_name("source").is_some() {
+                        self.import(child);
+                    } else {
+                        self.walk(child, owner, true, top);
+                    }
+                }
+                "import_statement" | "import_from_statement" | "import_declaration" => {
+                    self.import(child)
+                }
+                "package_clause" | "package_declaration" => {
+                    self.package = named_children(child)
+                        .into_iter()
+                        .last()
+                        .map(|n| self.text(n).to_string());
+                }
+                "lexical_declaration" | "variable_declaration" => {
+                    self.declaration(child, owner, exported, top)
+                }
+                "expression_statement" if top && self.language == Language::Python => {
+                    self.python_constant(child)
+                }
+                "const_declaration" if top => self.go_constants(child),
+                "field_declaration" if self.language == Language::Java => self.java_constant(child, owner),
+                _ => self.walk(child, owner, exported, top),
+            }
+        }
+    }
+
+    /// `const f = () => ...`, `const X = require("x")` and top-level constants.
+    fn declaration(&mut self, node: Node, owner: Option<&str>, exported: bool, top: bool) {
+        let is_const = self.text(node).trim_start().starts_with("const");
+        for declarator in named_children(node).into_iter().filter(|n| n.kind() == "variable_declarator") {
+            let name = self.field_text(declarator, "name").unwrap_or_default();
+            let value = declarator.child_by_field_name("value");
+            match value.map(|v| v.kind()) {
+                Some("arrow_function") | Some("function_expression") | Some("function") => {
+                    let value = value.expect("checked above");
+                    self.function(value, Some((name, declarator)), owner, exported);
+                    if let Some(body) = value.child_by_field_name("body") {
+                        self.walk(body, None, false, false);
+                    }
+                }
+                Some("call_expression")
+                    if value
+                        .and_then(|v| v.child_by_field_name("function"))
+                        .is_some_and(|f| self.text(f) == "require") =>
+                {
+                    let value = value.expect("checked above");
+                    let path = value
+                        .child_by_field_name("arguments")
+                        .and_then(|a| a.named_child(0))
+                        .map(|a| unquote(self.text(a)))
+                        .unwrap_or_default();
+                    self.imports.push(ImportDefinition {
+                        path,
+                        items: vec![name],
+                        import_type: "require".to_string(),
+                        line: line(node),
+                    });
+                }
+                _ if top && is_const => self.constants.push(ConstantDefinition {
+                    name,
+                    value: value.map(|v| truncate(self.text(v), 200)).unwrap_or_default(),
+                    type_hint: self
+                        .field_text(declarator, "type")
+                        .map(|t| t.trim_start_matches(':').trim().to_string()),
+                    documentation: self.doc_comment(node),
+                    line: line(declarator),
+                }),
+                _ => {
+                    if let Some(value) = value {
+                        self.walk(value, owner, false, false);
+                    }
+                }
+            }
+        }
+    }
+
+    fn python_constant(&mut self, statement: Node) {
+        let Some(assignment) = statement.named_child(0).filter(|n| n.kind() == "assignment") else {
+            return;
+        };
+        let Some(left) = assignment.child_by_field_name("left").filter(|n| n.kind() == "identifier") else {
+            return;
+        };
+        let name = self.text(left);
+        if !name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+            return;
+        }
+        self.constants.push(ConstantDefinition {
+            name: name.to_string(),
+            value: self
+                .field_text(assignment, "right")
+                .map(|v| truncate(&v, 200))
+                .unwrap_or_default(),
+            type_hint: self.field_text(assignment, "type"),
+            documentation: None,
+            line: line(statement),
+        });
+    }
+
+    fn go_constants(&mut self, declaration: Node) {
+        let mut specs = Vec::new();
+        descendants(declaration, "const_spec", &mut specs);
+        for spec in specs {
+            let type_hint = self.field_text(spec, "type");
+            let value = self.field_text(spec, "value").unwrap_or_default();
+            for name in named_children(spec).into_iter().filter(|n| n.kind() == "identifier") {
+                self.constants.push(ConstantDefinition {
+                    name: self.text(name).to_string(),
+                    value: truncate(&value, 200),
+                    type_hint: type_hint.clone(),
+                    documentation: self.doc_comment(declaration),
+                    line: line(spec),
+                });
+            }
+        }
+    }
+
+    fn java_constant(&mut self, field: Node, owner: Option<&str>) {
+        let modifiers = self.modifiers(field);
+        if !(modifiers.contains("static") && modifiers.contains("final")) {
+            return;
+        }
+        let type_hint = self.field_text(field, "type");
+        for declarator in named_children(field).into_iter().filter(|n| n.kind() == "variable_declarator") {
+            let name = self.field_text(declarator, "name").unwrap_or_default();
+            self.constants.push(ConstantDefinition {
+                name: match owner {
+                    Some(owner) => format!("{}.{}", owner, name),
+                    None => name,
+                },
+                value: self
+                    .field_text(declarator, "value")
+                    .map(|v| truncate(&v, 200))
+                    .unwrap_or_default(),
+                type_hint: type_hint.clone(),
+                documentation: self.doc_comment(field),
+                line: line(field),
+            });
+        }
+    }
+
+    fn modifiers(&self, node: Node) -> String {
+        named_children(node)
+            .into_iter()
+            .find(|n| n.kind() == "modifiers")
+            .map(|n| self.text(n).to_string())
+            .unwrap_or_default()
+    }
+
+    fn visibility(&self, node: Node, name: &str, exported: bool) -> String {
+        match self.language {
+            Language::Python => {
+                if name.starts_with('_') && !(name.starts_with("__") && name.ends_with("__")) {
+                    "private".to_string()
+                } else {
+                    "pub".to_string()
+                }
+            }
+            Language::Go => {
+                if name.starts_with(char::is_uppercase) {
+                    "pub".to_string()
+                } else {
+                    "private".to_string()
+                }
+            }
+            Language::Java => {
+                let modifiers = self.modifiers(node);
+                if modifiers.contains("public") {
+                    "pub".to_string()
+                } else if modifiers.contains("private") {
+                    "private".to_string()
+                } else if modifiers.contains("protected") {
+                    "protected".to_string()
+                } else {
+                    "package".to_string()
+                }
+            }
+            _ => {
+                let accessibility = named_children(node)
+                    .into_iter()
+                    .find(|n| n.kind() == "accessibility_modifier")
+                    .map(|n| self.text(n).to_string());
+                if exported {
+                    "export".to_string()
+                } else if let Some(accessibility) = accessibility {
+                    if accessibility == "public" {
+                        "pub".to_string()
+                    } else {
+                        accessibility
+                    }
+                } else if name.starts_with('#') {
+                    "private".to_string()
+                } else {
+                    "pub".to_string()
+                }
+            }
+        }
+    }
+
+    fn generics(&self, node: Node) -> Vec<String> {
+        node.child_by_field_name("type_parameters")
+            .map(|params| {
+                named_children(params)
+                    .into_iter()
+                    .map(|p| collapse_whitespace(self.text(p)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parameters(&self, list: Node) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        for p in named_children(list) {
+            if self.is_comment(p) {
+                continue;
+            }
+            let text = |n: Option<Node>| n.map(|n| self.text(n).to_string());
+            let type_of = |n: Node| {
+                text(n.child_by_field_name("type")).map(|t| t.trim_start_matches(':').trim().to_string())
+            };
+            match p.kind() {
+                "identifier" | "rest_pattern" | "list_splat_pattern" | "dictionary_splat_pattern"
+                | "object_pattern" | "array_pattern" | "spread_parameter" => parameters.push(Parameter {
+                    name: self.text(p).to_string(),
+                    type_hint: None,
+                    default_value: None,
+                }),
+                "typed_parameter" => parameters.push(Parameter {
+                    name: named_children(p)
+                        .into_iter()
+                        .next()
+                        .map(|n| self.text(n).to_string())
+                        .unwrap_or_default(),
+                    type_hint: type_of(p),
+                    default_value: None,
+                }),
+                "default_parameter" | "typed_default_parameter" => parameters.push(Parameter {
+                    name: text(p.child_by_field_name("name")).unwrap_or_default(),
+                    type_hint: type_of(p),
+                    default_value: text(p.child_by_field_name("value")),
+                }),
+                "assignment_pattern" => parameters.push(Parameter {
+                    name: text(p.child_by_field_name("left")).unwrap_or_default(),
+                    type_hint: None,
+                    default_value: text(p.child_by_field_name("right")),
+                }),
+                "required_parameter" | "optional_parameter" => parameters.push(Parameter {
+                    name: format!(
+                        "{}{}",
+                        text(p.child_by_field_name("pattern")).unwrap_or_default(),
+                        if p.kind() == "optional_parameter" { "?" } else { "" }
+                    ),
+                    type_hint: type_of(p),
+                    default_value: text(p.child_by_field_name("value")),
+                }),
+                // Go: `a, b int` declares two parameters of one type.
+                "parameter_declaration" | "variadic_parameter_declaration" => {
+                    let type_hint = type_of(p).map(|t| {
+                        if p.kind() == "variadic_parameter_declaration" {
+                            format!("...{}", t)
+                        } else {
+                            t
+                        }
+                    });
+                    let names: Vec<Node> = named_children(p)
+                        .into_iter()
+                        .filter(|n| n.kind() == "identifier")
+                        .collect();
+                    if names.is_empty() {
+                        parameters.push(Parameter {
+                            name: String::new(),
+                            type_hint,
+                            default_value: None,
+                        });
+                    } else {
+                        for name in names {
+                            parameters.push(Parameter {
+                                name: self.text(name).to_string(),
+                                type_hint: type_hint.clone(),
+                                default_value: None,
+                            });
+                        }
+                    }
+                }
+                "formal_parameter" => parameters.push(Parameter {
+                    name: text(p.child_by_field_name("name")).unwrap_or_default(),
+                    type_hint: type_of(p),
+                    default_value: None,
+                }),
+                // Python `*` / `/` separators, Java receiver parameters, ...
+                _ => {}
+            }
+        }
+        parameters
+    }
+
+    /// Record a function. `named` carries the name and declarator of `const f = () => ...`.
+    fn function(&mut self, node: Node, named: Option<(String, Node)>, owner: Option<&str>, exported: bool) {
+        let (name, anchor) = match named {
+            Some((name, declarator)) => (name, declarator),
+            None => (self.field_text(node, "name").unwrap_or_default(), node),
+        };
+        let owner = match self.language {
+            // Go methods belong to their receiver type.
+            Language::Go if node.kind() == "method_declaration" => node
+                .child_by_field_name("receiver")
+                .and_then(|r| self.parameters(r).into_iter().next())
+                .and_then(|p| p.type_hint)
+                .map(|t| base_name(t.trim_start_matches('*').split('[').next().unwrap_or_default())),
+            _ => owner.map(str::to_string),
+        };
+        let qualified = match &owner {
+            Some(owner) => format!("{}.{}", owner, name),
+            None => name.clone(),
+        };
+
+        let body = node.child_by_field_name("body");
+        let start = line(anchor);
+        let end = end_line(node);
+        let signature_end = body.map(|b| b.start_byte()).unwrap_or_else(|| node.end_byte());
+        let signature = collapse_whitespace(
+            self.source
+                .get(anchor.start_byte()..signature_end)
+                .unwrap_or_default()
+                .trim_end()
+                .trim_end_matches(['{', ':'])
+                .trim_end(),
+        );
+        let parameters = node
+            .child_by_field_name("parameters")
+            .or_else(|| node.child_by_field_name("parameter"))
+            .map(|p| {
+                if p.kind() == "identifier" {
+                    // `x => ...`
+                    vec![Parameter {
+                        name: self.text(p).to_string(),
+                        type_hint: None,
+                        default_value: None,
+                    }]
+                } else {
+                    self.parameters(p)
+                }
+            })
+            .unwrap_or_default();
+        let return_type = ["return_type", "result", "type"]
+            .iter()
+            .find_map(|field| self.field_text(node, field))
+            .map(|t| t.trim_start_matches(':').trim().to_string())
+            .filter(|t| !t.is_empty());
+        let documentation = match (self.language, body) {
+            (Language::Python, Some(body)) => self.docstring_of(body),
+            _ => self.doc_comment(anchor),
+        };
+        let visibility = self.visibility(node, &name, exported);
+        let modifiers = self.modifiers(node);
+        let param_names: Vec<String> = parameters
+            .iter()
+            .map(|p| p.name.trim_end_matches('?').to_string())
+            .filter(|n| !n.is_empty() && n != "self" && n != "this")
+            .collect();
+
+        let is_test = match self.language {
+            Language::Python => name.starts_with("test") || owner.as_deref().is_some_and(|o| o.starts_with("Test")),
+            Language::Go => {
+                (name.starts_with("Test") || name.starts_with("Benchmark"))
+                    && parameters.iter().any(|p| p.type_hint.as_deref().is_some_and(|t| t.contains("testing.")))
+            }
+            Language::Java => modifiers.contains("@Test"),
+            _ => false,
+        };
+        let is_entry = !is_test
+            && (name == "main"
+                || (owner.is_none()
+                    && match self.language {
+                        Language::JavaScript | Language::TypeScript => visibility == "export",
+                        Language::Java => false,
+                        _ => visibility == "pub",
+                    }));
+
+        let mut metrics = Body::new(self, param_names);
+        if body.is_some() {
+            // The whole definition: expression-bodied arrows and signature types count too.
+            metrics.visit(node, 0);
+        }
+        let Body {
+            decisions,
+            cognitive,
+            max_nesting,
+            branches,
+            conditions,
+            calls,
+            env_vars,
+            side_effects,
+            exits,
+            findings,
+            param_sinks,
+            param_methods,
+            ..
+        } = metrics;
+
+        let mut internal_dependencies: Vec<String> = Vec::new();
+        for (callee, _, _) in &calls {
+            if !internal_dependencies.contains(callee) {
+                internal_dependencies.push(callee.clone());
+            }
+        }
+        let cyclomatic = 1 + decisions;
+
+        self.functions.push(FunctionDefinition {
+            name,
+            signature,
+            parameters,
+            return_type,
+            visibility,
+            documentation,
+            line_range: (start, end),
+            body: if body.is_some() {
+                self.source.lines().skip(start - 1).take(end + 1 - start).collect::<Vec<_>>().join("\n")
+            } else {
+                String::new()
+            },
+            intent: String::new(),
+            internal_dependencies,
+            complexity: cyclomatic,
+            owner,
+            generics: self.generics(node),
+        });
+
+        self.complexity.cyclomatic += cyclomatic;
+        self.complexity.cognitive += cognitive;
+        self.complexity.branches += branches;
+        self.complexity.max_nesting = self.complexity.max_nesting.max(max_nesting);
+
+        if !is_test {
+            for (smell_type, line, severity, description) in findings {
+                self.facts.smells.push(smell(
+                    smell_type,
+                    &qualified,
+                    line,
+                    severity,
+                    format!("`{}` {}", qualified, description),
+                ));
+            }
+        }
+        for (callee, line, kind) in calls {
+            self.facts.call_graph.push(CallEdge {
+                caller: qualified.clone(),
+                callee,
+                line,
+                kind: kind.to_string(),
+            });
+        }
+        let flow_params: BTreeSet<&String> = param_sinks.keys().chain(param_methods.keys()).collect();
+        for param in flow_params {
+            self.facts.data_flow.push(DataFlowPath {
+                name: param.clone(),
+                source: qualified.clone(),
+                sinks: param_sinks.get(param).map(|s| s.iter().cloned().collect()).unwrap_or_default(),
+                transformations: param_methods
+                    .get(param)
+                    .map(|s| s.iter().cloned().collect())
+                    .unwrap_or_default(),
+            });
+        }
+        for var in env_vars {
+            if !self.facts.env_vars.contains(&var) {
+                self.facts.env_vars.push(var);
+            }
+        }
+        self.facts
+            .side_effects
+            .extend(side_effects.into_iter().map(|e| format!("{}: {}", qualified, e)));
+        self.facts
+            .exits
+            .extend(exits.into_iter().map(|e| format!("{}: {}", qualified, e)));
+        self.facts.functions.push(FunctionFacts {
+            name: qualified,
+            max_nesting,
+            conditions,
+            is_test,
+            is_entry,
+        });
+    }
+
+    /// Record a type definition and return its name.
+    fn type_definition(&mut self, node: Node) -> String {
+        let name = self.field_text(node, "name").unwrap_or_default();
+        let kind = match node.kind() {
+            "class_definition" | "class_declaration" | "abstract_class_declaration" => "class",
+            "interface_declaration" => "interface",
+            "enum_declaration" => "enum",
+            "record_declaration" => "record",
+            "type_alias_declaration" => "type alias",
+            // Go `type X struct {...}` / `type Y interface {...}` / `type Z int`
+            _ => match node.child_by_field_name("type").map(|t| t.kind()) {
+                Some("struct_type") => "struct",
+                Some("interface_type") => "interface",
+                _ => "type alias",
+            },
+        };
+
+        let mut members = Vec::new();
+        let mut methods = Vec::new();
+        let body = node
+            .child_by_field_name("body")
+            .or_else(|| node.child_by_field_name("type"));
+        if let Some(body) = body {
+            let mut scan = vec![body];
+            // Go nests fields one level deeper (struct_type > field_declaration_list).
+            if let Some(fields) = named_children(body)
+                .into_iter()
+                .find(|n| n.kind() == "field_declaration_list")
+            {
+                scan.push(fields);
+            }
+            for container in scan {
+                for child in named_children(container) {
+                    self.member(child, &mut members, &mut methods);
+                }
+            }
+        }
+
+        let implements = match self.language {
+            Language::Python => node
+                .child_by_field_name("superclasses")
+                .map(|s| {
+                    named_children(s)
+                        .into_iter()
+                        .filter(|n| n.kind() != "keyword_argument")
+                        .map(|n| base_name(self.text(n)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => {
+                let heritage: Vec<String> = named_children(node)
+                    .into_iter()
+                    .filter(|n| {
+                        matches!(
+                            n.kind(),
+                            "class_heritage" | "extends_type_clause" | "superclass" | "super_interfaces" | "extends_interfaces"
+                        )
+                    })
+                    .map(|n| self.text(n).to_string())
+                    .collect();
+                heritage
+                    .join(" ")
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|t| !t.is_empty() && !matches!(*t, "extends" | "implements" | "{"))
+                    .map(|t| base_name(t.trim_end_matches('{')))
+                    .filter(|t| t.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+                    .collect()
+            }
+        };
+
+        let (start, end) = (line(node), end_line(node));
+        self.types.push(TypeDefinition {
+            name: name.clone(),
+            kind: kind.to_string(),
+            members,
+            methods,
+            documentation: match (self.language, node.child_by_field_name("body")) {
+                (Language::Python, Some(body)) => self.docstring_of(body),
+                _ => self.doc_comment(node),
+            },
+            line_range: (start, end),
+            source: self.source.lines().skip(start - 1).take(end + 1 - start).collect::<Vec<_>>().join("\n"),
+            intent: String::new(),
+            generics: self.generics(node),
+            implements,
+        });
+        name
+    }
+
+    fn member(&self, child: Node, members: &mut Vec<Member>, methods: &mut Vec<String>) {
+        let type_of = |n: Node| {
+            self.field_text(n, "type")
+                .map(|t| t.trim_start_matches(':').trim().to_string())
+        };
+        match child.kind() {
+            k if self.spec.functions.contains(&k) => {
+                if let Some(name) = self.field_text(child, "name") {
+                    methods.push(name);
+                }
+            }
+            "decorated_definition" => {
+                if let Some(definition) = child.child_by_field_name("definition") {
+                    self.member(definition, members, methods);
+                }
+            }
+            // Python class attributes: `name = value` / `name: type = value`
+            "expression_statement" => {
+                if let Some(assignment) = child.named_child(0).filter(|n| n.kind() == "assignment") {
+                    if let Some(left) = assignment.child_by_field_name("left").filter(|n| n.kind() == "identifier") {
+                        let name = self.text(left).to_string();
+                        members.push(Member {
+                            visibility: self.visibility(assignment, &name, false),
+                            name,
+                            type_hint: type_of(assignment),
+                            documentation: None,
+                        });
+                    }
+                }
+            }
+            "field_definition" | "public_field_definition" | "property_signature" => {
+                let name = self
+                    .field_text(child, "property")
+                    .or_else(|| self.field_text(child, "name"))
+                    .unwrap_or_default();
+                members.push(Member {
+                    visibility: self.visibility(child, &name, false),
+                    name,
+                    type_hint: type_of(child),
+                    documentation: self.doc_comment(child),
+                });
+            }
+            // Go struct fields: `A, B int`
+            "field_declaration" if self.language == Language::Go => {
+                let type_hint = type_of(child);
+                for name in named_children(child).into_iter().filter(|n| n.kind() == "field_identifier") {
+                    let name = self.text(name).to_string();
+                    members.push(Member {
+                        visibility: self.visibility(child, &name, false),
+                        name,
+                        type_hint: type_hint.clone(),
+                        documentation: self.doc_comment(child),
+                    });
+                }
+            }
+            "field_declaration" => {
+                let type_hint = type_of(child);
+                for declarator in named_children(child).into_iter().filter(|n| n.kind() == "variable_declarator") {
+                    members.push(Member {
+                        name: self.field_text(declarator, "name").unwrap_or_default(),
+                        type_hint: type_hint.clone(),
+                        visibility: self.visibility(child, "", false),
+                        documentation: self.doc_comment(child),
+                    });
+                }
+            }
+            // Go interface methods
+            "method_elem" | "method_spec" => {
+                if let Some(name) = self.field_text(child, "name") {
+                    methods.push(name);
+                }
+            }
+            // Enum variants (TypeScript, Java)
+            "property_identifier" | "enum_assignment" | "enum_constant" => members.push(Member {
+                name: self
+                    .field_text(child, "name")
+                    .unwrap_or_else(|| self.text(child).to_string()),
+                type_hint: None,
+                visibility: "pub".to_string(),
+                documentation: None,
+            }),
+            "enum_body_declarations" => {
+                for inner in named_children(child) {
+                    self.member(inner, members, methods);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn import(&mut self, node: Node) {
+        let at = line(node);
+        match node.kind() {
+            "import_statement" if self.language == Language::Python => {
+                for name in named_children(node) {
+                    let (path, item) = match name.kind() {
+                        "aliased_import" => (
+                            self.field_text(name, "name").unwrap_or_default(),
+                            self.field_text(name, "alias").unwrap_or_default(),
+                        ),
+                        _ => {
+                            let path = self.text(name).to_string();
+                            let item = path.split('.').next().unwrap_or_default().to_string();
+                            (path, item)
+                        }
+                    };
+                    self.imports.push(ImportDefinition {
+                        path,
+                        items: vec![item],
+                        import_type: "import".to_string(),
+                        line: at,
+                    });
+                }
+            }
+            "import_from_statement" => {
+                let module = node.child_by_field_name("module_name");
+                let items = named_children(node)
+                    .into_iter()
+                    .filter(|n| Some(n.id()) != module.map(|m| m.id()))
+                    .map(|n| match n.kind() {
+                        "aliased_import" => self.field_text(n, "alias").unwrap_or_default(),
+                        "wildcard_import" => "*".to_string(),
+                        _ => self.text(n).to_string(),
+                    })
+                    .collect();
+                self.imports.push(ImportDefinition {
+                    path: module.map(|m| self.text(m).to_string()).unwrap_or_default(),
+                    items,
+                    import_type: "from".to_string(),
+                    line: at,
+                });
+            }
+            // JavaScript / TypeScript `import ... from "x"` and `export ... from "x"`
+            "import_statement" | "export_statement" => {
+                let path = self.field_text(node, "source").map(|s| unquote(&s)).unwrap_or_default();
+                let mut items = Vec::new();
+                for clause in named_children(node) {
+                    match clause.kind() {
+                        "import_clause" => {
+                            for part in named_children(clause) {
+                                match part.kind() {
+                                    "identifier" => items.push(self.text(part).to_string()),
+                                    "namespace_import" => items.extend(
+                                        named_children(part).into_iter().map(|n| self.text(n).to_string()),
+                                    ),
+                                    "named_imports" => {
+                                        for specifier in named_children(part) {
+                                            items.push(
+                                                self.field_text(specifier, "alias")
+                                                    .or_else(|| self.field_text(specifier, "name"))
+                                                    .unwrap_or_default(),
+                                            );
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        "export_clause" => {
+                            for specifier in named_children(clause) {
+                                items.push(
+                                    self.field_text(specifier, "alias")
+                                        .or_else(|| self.field_text(specifier, "name"))
+                                        .unwrap_or_default(),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                self.imports.push(ImportDefinition {
+                    path,
+                    items,
+                    import_type: if node.kind() == "export_statement" {
+                        "export from".to_string()
+                    } else {
+                        "import".to_string()
+                    },
+                    line: at,
+                });
+            }
+            "import_declaration" if self.language == Language::Go => {
+                let mut specs = Vec::new();
+                descendants(node, "import_spec", &mut specs);
+                for spec in specs {
+                    let path = self.field_text(spec, "path").map(|p| unquote(&p)).unwrap_or_default();
+                    let item = self
+                        .field_text(spec, "name")
+                        .unwrap_or_else(|| path.rsplit('/').next().unwrap_or_default().to_string());
+                    self.imports.push(ImportDefinition {
+                        path,
+                        items: vec![item],
+                        import_type: "import".to_string(),
+                        line: line(spec),
+                    });
+                }
+            }
+            // Java `import [static] a.b.C;`
+            _ => {
+                let text = self.text(node);
+                let is_static = text.contains(" static ");
+                let path = text
+                    .trim()
+                    .trim_start_matches("import")
+                    .trim()
+                    .trim_start_matches("static")
+                    .trim()
+                    .trim_end_matches(';')
+                    .trim()
+                    .to_string();
+                let item = path.rsplit('.').next().unwrap_or_default().to_string();
+                self.imports.push(ImportDefinition {
+                    path,
+                    items: vec![item],
+                    import_type: if is_static { "import static" } else { "import" }.to_string(),
+                    line: at,
+                });
+            }
+        }
+    }
+
+    fn finish(mut self) -> (CodeStructure, SyntaxFacts) {
+        // Methods defined outside their type (Go receivers) or collected while walking bodies.
+        for f in &self.functions {
+            if let Some(owner) = &f.owner {
+                if let Some(ty) = self.types.iter_mut().find(|t| t.name == *owner) {
+                    if !ty.methods.contains(&f.name) {
+                        ty.methods.push(f.name.clone());
+                    }
+                }
+            }
+        }
+
+        let modules = self
+            .package
+            .take()
+            .map(|package| {
+                let exports = self
+                    .functions
+                    .iter()
+                    .zip(&self.facts.functions)
+                    .filter(|(f, facts)| f.owner.is_none() && f.visibility == "pub" && !facts.is_test)
+                    .map(|(f, _)| f)
+                    .map(|f| f.name.clone())
+                    .chain(
+                        self.types
+                            .iter()
+                            .filter(|t| self.language != Language::Go || t.name.starts_with(char::is_uppercase))
+                            .map(|t| t.name.clone()),
+                    )
+                    .collect();
+                vec![ModuleDefinition {
+                    name: package.rsplit('.').next().unwrap_or(&package).to_string(),
+                    path: package,
+                    exports,
+                    documentation: self.documentation.clone(),
+                    inline: false,
+                }]
+            })
+            .unwrap_or_default();
+
+        let structure = CodeStructure {
+            functions: self.functions,
+            types: self.types,
+            modules,
+            constants: self.constants,
+            imports: self.imports,
+            documentation: self.documentation,
+            lines_of_code: self.source.lines().count(),
+            complexity: self.complexity,
+            impls: Vec::new(),
+            macros: Vec::new(),
+        };
+        (structure, self.facts)
+    }
+}
+
+/// Complexity, calls and findings of one function body. Nested named functions and types are
+/// recorded separately and skipped here; closures count towards the enclosing function.
+struct Body<'w, 's> {
+    walker: &'w Walker<'s>,
+    params: Vec<String>,
+    decisions: usize,
+    cognitive: usize,
+    max_nesting: usize,
+    branches: usize,
+    conditions: Vec<String>,
+    calls: Vec<(String, usize, &'static str)>,
+    env_vars: Vec<String>,
+    side_effects: BTreeSet<String>,
+    exits: BTreeSet<String>,
+    findings: Vec<(&'static str, usize, &'static str, String)>,
+    explicit_any: usize,
+    param_sinks: BTreeMap<String, BTreeSet<String>>,
+    param_methods: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl<'w, 's> Body<'w, 's> {
+    fn new(walker: &'w Walker<'s>, params: Vec<String>) -> Self {
+        Self {
+            walker,
+            params,
+            decisions: 0,
+            cognitive: 0,
+            max_nesting: 0,
+            branches: 0,
+            conditions: Vec::new(),
+            calls: Vec::new(),
+            env_vars: Vec::new(),
+            side_effects: BTreeSet::new(),
+            exits: BTreeSet::new(),
+            findings: Vec::new(),
+            explicit_any: 0,
+            param_sinks: BTreeMap::new(),
+            param_methods: BTreeMap::new(),
+        }
+    }
+
+    fn visit(&mut self, node: Node, nesting: usize) {
+        let spec = self.walker.spec;
+        for child in named_children(node) {
+            let kind = child.kind();
+            if spec.comments.contains(&kind) || spec.functions.contains(&kind) || spec.types.contains(&kind) {
+                continue;
+            }
+            // `const f = () => ...` is recorded as a function of its own.
+            if kind == "variable_declarator"
+                && child.child_by_field_name("value").is_some_and(|v| {
+                    matches!(v.kind(), "arrow_function" | "function_expression" | "function")
+                })
+            {
+                continue;
+            }
+
+            let flat = spec.flat.contains(&kind);
+            if spec.decisions.contains(&kind) {
+                self.decisions += 1;
+                self.branches += 1;
+                self.cognitive += if flat { 1 } else { 1 + nesting };
+                if let Some(condition) = child.child_by_field_name("condition") {
+                    self.conditions
+                        .push(truncate(&collapse_whitespace(self.walker.text(condition)), 120));
+                }
+            } else if flat {
+                self.branches += 1;
+                self.cognitive += 1;
+            }
+            if self.is_boolean_operator(child) {
+                self.decisions += 1;
+                self.cognitive += 1;
+            }
+            if spec.calls.contains(&kind) {
+                self.call(child);
+            }
+            self.inspect(child);
+
+            let inner = if spec.nesting.contains(&kind) && !flat {
+                self.max_nesting = self.max_nesting.max(nesting + 1);
+                nesting + 1
+            } else {
+                nesting
+            };
+            self.visit(child, inner);
+        }
+    }
+
+    fn is_boolean_operator(&self, node: Node) -> bool {
+        match node.kind() {
+            "boolean_operator" => true,
+            "binary_expression" => node
+                .child_by_field_name("operator")
+                .is_some_and(|op| matches!(self.walker.text(op), "&&" | "||" | "??")),
+            _ => false,
+        }
+    }
+
+    fn call(&mut self, node: Node) {
+        let text = |field: &str| node.child_by_field_name(field).map(|n| self.walker.text(n).to_string());
+        let raw = match node.kind() {
+            "method_invocation" => match (text("object"), text("name")) {
+                (Some(object), Some(name)) => format!("{}.{}", object, name),
+                (None, Some(name)) => name,
+                _ => return,
+            },
+            "new_expression" => format!("new {}", text("constructor").unwrap_or_default()),
+            "object_creation_expression" => format!("new {}", text("type").unwrap_or_default()),
+            _ => match text("function") {
+                Some(function) => function,
+                None => return,
+            },
+        };
+        let mut callee = collapse_whitespace(&raw);
+        // Chained calls (`a().b`) keep only the final member.
+        if callee.contains('(') {
+            callee = callee.rsplit('.').next().unwrap_or_default().to_string();
+        }
+        let kind = if callee.contains('.') && !callee.starts_with("new ") {
+            "method"
+        } else {
+            "call"
+        };
+        let at = line(node);
+
+        let arguments = node.child_by_field_name("arguments");
+        if let Some(arguments) = arguments {
+            let mut idents = Vec::new();
+            descendants(arguments, "identifier", &mut idents);
+            for ident in idents {
+                let name = self.walker.text(ident).to_string();
+                if self.params.contains(&name) {
+                    self.param_sinks.entry(name).or_default().insert(callee.clone());
+                }
+            }
+        }
+        if let Some((receiver, method)) = callee.split_once('.') {
+            if self.params.iter().any(|p| p == receiver) {
+                self.param_methods
+                    .entry(receiver.to_string())
+                    .or_default()
+                    .insert(method.to_string());
+            }
+        }
+
+        let first_string = arguments.and_then(|a| {
+            named_children(a)
+                .into_iter()
+                .next()
+                .filter(|n| n.kind().contains("string"))
+                .map(|n| unquote(self.walker.text(n)))
+        });
+        if matches!(
+            callee.as_str(),
+            "os.getenv" | "os.environ.get" | "os.Getenv" | "os.LookupEnv" | "System.getenv"
+        ) {
+            if let Some(var) = first_string {
+                self.env_vars.push(var);
+            }
+        }
+        if let Some(effect) = side_effect(self.walker.language, &callee) {
+            self.side_effects.insert(format!("{} ({})", effect, callee));
+        }
+        if matches!(
+            callee.as_str(),
+            "sys.exit" | "exit" | "os.Exit" | "process.exit" | "System.exit" | "panic" | "log.Fatal" | "log.Fatalf"
+        ) {
+            self.exits.insert(callee.clone());
+        }
+        if matches!(callee.as_str(), "eval" | "exec" | "new Function") {
+            self.findings.push((
+                "dynamic_code_execution",
+                at,
+                "high",
+                format!("evaluates dynamic code with `{}`", callee),
+            ));
+        }
+        self.calls.push((callee, at, kind));
+    }
+
+    /// Environment reads that are not calls, and language-specific smells.
+    fn inspect(&mut self, node: Node) {
+        let walker = self.walker;
+        match node.kind() {
+            // os.environ["X"]
+            "subscript"
+                if node
+                    .child_by_field_name("value")
+                    .is_some_and(|v| walker.text(v) == "os.environ") =>
+            {
+                if let Some(key) = node.child_by_field_name("subscript") {
+                    self.env_vars.push(unquote(walker.text(key)));
+                }
+            }
+            // process.env.X
+            "member_expression"
+                if node
+                    .child_by_field_name("object")
+                    .is_some_and(|o| walker.text(o) == "process.env") =>
+            {
+                if let Some(property) = node.child_by_field_name("property") {
+                    let var = walker.text(property).to_string();
+                    if !self.env_vars.contains(&var) {
+                        self.env_vars.push(var);
+                    }
+                }
+            }
+            "except_clause" => {
+                let text = walker.text(node).trim_start();
+                if text.starts_with("except:") {
+                    self.findings.push((
+                        "bare_except",
+                        line(node),
+                        "medium",
+                        "catches every exception with a bare `except:`".to_string(),
+                    ));
+                }
+                let handler = named_children(node).into_iter().last();
+                if handler.is_some_and(|h| {
+                    let statements: Vec<Node> = named_children(h)
+                        .into_iter()
+                        .filter(|n| !walker.is_comment(*n))
+                        .collect();
+                    statements.len() == 1 && statements[0].kind() == "pass_statement"
+                }) {
+                    self.findings.push((
+                        "empty_catch",
+                        line(node),
+                        "medium",
+                        "silently ignores an exception".to_string(),
+                    ));
+                }
+            }
+            "catch_clause"
+                if node
+                    .child_by_field_name("body")
+                    .is_some_and(|body| named_children(body).into_iter().all(|n| walker.is_comment(n))) =>
+            {
+                self.findings.push((
+                    "empty_catch",
+                    line(node),
+                    "medium",
+                    "silently ignores an exception".to_string(),
+                ));
+            }
+            "predefined_type" if walker.text(node) == "any" => {
+                self.explicit_any += 1;
+                if self.explicit_any == 1 {
+                    self.findings.push((
+                        "explicit_any",
+                        line(node),
+                        "low",
+                        "uses the `any` type".to_string(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn side_effect(language: Language, callee: &str) -> Option<&'static str> {
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|p| callee.starts_with(p));
+    match language {
+        Language::Python => {
+            if callee == "print" {
+                Some("console output")
+            } else if callee == "open" || starts(&["os.remove", "os.unlink", "os.makedirs", "shutil.", "pathlib."]) {
+                Some("filesystem")
+            } else if starts(&["subprocess.", "os.system", "os.popen"]) {
+                Some("process")
+            } else if starts(&["requests.", "urllib.", "httpx.", "socket."]) {
+                Some("network")
+            } else {
+                None
+            }
+        }
+        Language::Go => {
+            if starts(&["fmt.Print", "log.Print"]) {
+                Some("console output")
+            } else if starts(&["os.Create", "os.Open", "os.WriteFile", "os.ReadFile", "os.Remove", "os.Mkdir", "ioutil."]) {
+                Some("filesystem")
+            } else if starts(&["exec.Command"]) {
+                Some("process")
+            } else if starts(&["http.", "net.Dial"]) {
+                Some("network")
+            } else if callee == "go" {
+                Some("spawns goroutines")
+            } else {
+                None
+            }
+        }
+        Language::Java => {
+            if starts(&["System.out.", "System.err."]) {
+                Some("console output")
+            } else if starts(&["Files.", "new FileWriter", "new FileOutputStream", "new FileReader"]) {
+                Some("filesystem")
+            } else if starts(&["new ProcessBuilder", "Runtime.getRuntime"]) {
+                Some("process")
+            } else if starts(&["HttpClient.", "new URL", "new Socket"]) {
+                Some("network")
+            } else {
+                None
+            }
+        }
+        _ => {
+            if starts(&["console."]) {
+                Some("console output")
+            } else if starts(&["fs.", "fsPromises.", "Deno.writeFile", "Deno.readFile"]) {
+                Some("filesystem")
+            } else if callee == "fetch" || starts(&["axios", "http.request", "https.request", "new WebSocket", "new XMLHttpRequest"]) {
+                Some("network")
+            } else if starts(&["child_process.", "exec", "spawn", "execSync", "spawnSync"]) {
+                Some("process")
+            } else if starts(&["localStorage.", "sessionStorage."]) {
+                Some("browser storage")
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function<'a>(structure: &'a CodeStructure, name: &str) -> &'a FunctionDefinition {
+        structure
+            .functions
+            .iter()
+            .find(|f| f.name == name)
+            .unwrap_or_else(|| panic!("no function `{}`", name))
+    }
+
+    #[cfg(feature = "python-analysis")]
+    #[test]
+    fn test_python_structure() {
+        let source = r#""""Order handling."""
+import os
+from .models import Order as O, Item
+from ..util import helpers
+
+MAX_ITEMS = 10
+
+
+class Cart(Base):
+    """A shopping cart."""
+    limit: int = 5
+
+    def add(self, item, quantity: int = 1) -> bool:
+        """Add an item."""
+        if quantity > MAX_ITEMS and item:
+            return False
+        elif quantity < 0:
+            raise ValueError("negative")
+        for _ in range(quantity):
+            self.items.append(item)
+        return True
+
+
+def load(path):
+    try:
+        with open(path) as f:
+            return f.read()
+    except:
+        pass
+    return os.environ["CART_PATH"]
+
+
+def test_add():
+    assert Cart().add(1)
+"#;
+        let (structure, facts) = analyze(source, Language::Python).unwrap();
+        assert_eq!(structure.documentation.as_deref(), Some("Order handling."));
+        assert_eq!(structure.imports.len(), 3);
+        assert_eq!(structure.imports[1].path, ".models");
+        assert_eq!(structure.imports[1].items, vec!["O", "Item"]);
+        assert_eq!(structure.constants[0].name, "MAX_ITEMS");
+
+        let cart = &structure.types[0];
+        assert_eq!((cart.name.as_str(), cart.kind.as_str()), ("Cart", "class"));
+        assert_eq!(cart.implements, vec!["Base"]);
+        assert_eq!(cart.methods, vec!["add"]);
+        assert_eq!(cart.members[0].name, "limit");
+        assert_eq!(cart.documentation.as_deref(), Some("A shopping cart."));
+
+        let add = function(&structure, "add");
+        assert_eq!(add.owner.as_deref(), Some("Cart"));
+        assert_eq!(add.return_type.as_deref(), Some("bool"));
+        assert_eq!(add.parameters[2].type_hint.as_deref(), Some("int"));
+        assert_eq!(add.parameters[2].default_value.as_deref(), Some("1"));
+        assert_eq!(add.documentation.as_deref(), Some("Add an item."));
+        // if + `and` + elif + for
+        assert_eq!(add.complexity, 5);
+        assert_eq!(add.line_range, (13, 21));
+
+        assert!(facts.env_vars.contains(&"CART_PATH".to_string()));
+        assert!(facts.smells.iter().any(|s| s.smell_type == "bare_except" && s.location == "load"));
+        assert!(facts.side_effects.iter().any(|e| e.starts_with("load: filesystem")));
+        assert!(facts.call_graph.iter().any(|c| c.caller == "Cart.add" && c.callee == "self.items.append"));
+        assert!(facts.functions.iter().any(|f| f.name == "test_add" && f.is_test));
+    }
+
+    #[cfg(feature = "typescript-analysis")]
+    #[test]
+    fn test_typescript_structure() {
+        let source = r#"/** API client. */
+import axios, { get as fetchOne } from "axios";
+import * as models from "./models";
+export { Item } from "./item";
+
+export const BASE_URL: string = process.env.API_URL ?? "http://localhost";
+
+/** A user. */
+export interface User<T> {
+    id: number;
+    name?: string;
+    greet(): void;
+}
+
+export enum Role { Admin, Guest = "guest" }
+
+export class Client extends Base implements Api, Closeable {
+    private retries = 3;
+
+    constructor(private readonly token: string) {
+        super();
+    }
+
+    async user(id: number, verbose = false): Promise<User<any>> {
+        for (const attempt of [1, 2]) {
+            if (attempt > this.retries || verbose) {
+                console.log(attempt);
+            }
+        }
+        try {
+            return await fetchOne(`${BASE_URL}/users/${id}`);
+        } catch (e) {
+        }
+    }
+}
+
+export const format = (user: User<string>): string => user.name ?? "anonymous";
+"#;
+        let (structure, facts) = analyze(source, Language::TypeScript).unwrap();
+        assert_eq!(structure.documentation.as_deref(), Some("API client."));
+        assert_eq!(structure.imports[0].items, vec!["axios", "fetchOne"]);
+        assert_eq!(structure.imports[1].items, vec!["models"]);
+        assert_eq!(structure.imports[2].import_type, "export from");
+        assert_eq!(structure.constants[0].name, "BASE_URL");
+        assert_eq!(structure.constants[0].type_hint.as_deref(), Some("string"));
+
+        let user = structure.types.iter().find(|t| t.name == "User").unwrap();
+        assert_eq!(user.kind, "interface");
+        assert_eq!(user.generics, vec!["T"]);
+        assert_eq!(user.members.len(), 2);
+        assert_eq!(user.methods, vec!["greet"]);
+        assert_eq!(user.documentation.as_deref(), Some("A user."));
+        let role = structure.types.iter().find(|t| t.name == "Role").unwrap();
+        assert_eq!(role.members.len(), 2);
+        let client = structure.types.iter().find(|t| t.name == "Client").unwrap();
+        assert_eq!(client.implements, vec!["Base", "Api", "Closeable"]);
+        assert!(client.methods.contains(&"user".to_string()));
+        assert_eq!(client.members[0].name, "retries");
+
+        let method = function(&structure, "user");
+        assert_eq!(method.owner.as_deref(), Some("Client"));
+        assert_eq!(method.return_type.as_deref(), Some("Promise<User<any>>"));
+        assert_eq!(method.parameters[1].default_value.as_deref(), Some("false"));
+        // for + if + `||` + catch
+        assert_eq!(method.complexity, 5);
+        let format = function(&structure, "format");
+        assert_eq!(format.visibility, "export");
+        assert_eq!(format.parameters[0].type_hint.as_deref(), Some("User<string>"));
+
+        assert!(facts.smells.iter().any(|s| s.smell_type == "empty_catch"));
+        assert!(facts.smells.iter().any(|s| s.smell_type == "explicit_any"));
+        assert!(facts.side_effects.iter().any(|e| e.starts_with("Client.user: console output")));
+        assert!(facts.functions.iter().any(|f| f.name == "format" && f.is_entry));
+    }
+
+    #[cfg(feature = "javascript-analysis")]
+    #[test]
+    fn test_javascript_require_and_functions() {
+        let source = r#"const fs = require("fs");
+
+// Reads the config.
+function readConfig(path) {
+    return JSON.parse(fs.readFileSync(path, "utf8"));
+}
+
+const port = () => process.env.PORT || 8080;
+"#;
+        let (structure, facts) = analyze(source, Language::JavaScript).unwrap();
+        assert_eq!(structure.imports[0].path, "fs");
+        assert_eq!(structure.imports[0].import_type, "require");
+        let read = function(&structure, "readConfig");
+        assert_eq!(read.documentation.as_deref(), Some("Reads the config."));
+        assert_eq!(read.parameters[0].name, "path");
+        assert_eq!(function(&structure, "port").complexity, 2);
+        assert_eq!(facts.env_vars, vec!["PORT"]);
+        assert!(facts
+            .data_flow
+            .iter()
+            .any(|d| d.name == "path" && d.sinks.contains(&"fs.readFileSync".to_string())));
+    }
+
+    #[cfg(feature = "go-analysis")]
+    #[test]
+    fn test_go_structure() {
+        let source = r#"// Package store keeps things.
+package store
+
+import (
+	"fmt"
+	str "strings"
+)
+
+const Limit, other = 10, 2
+
+// Store holds items.
+type Store[K comparable] struct {
+	Items, backup []string
+	name          string
+}
+
+type Reader interface {
+	Read(p []byte) (int, error)
+}
+
+// Add appends an item.
+func (s *Store[K]) Add(item string, n int) error {
+	for i := 0; i < n; i++ {
+		if item == "" && i > 0 {
+			return fmt.Errorf("empty")
+		}
+	}
+	switch {
+	case n > 1:
+		s.Items = append(s.Items, str.ToUpper(item))
+	default:
+	}
+	return nil
+}
+
+func TestAdd(t *testing.T) {}
+"#;
+        let (structure, facts) = analyze(source, Language::Go).unwrap();
+        assert_eq!(structure.modules[0].name, "store");
+        assert_eq!(structure.modules[0].exports, vec!["Store", "Reader"]);
+        assert_eq!(structure.imports[1].items, vec!["str"]);
+        assert_eq!(structure.constants.len(), 2);
+
+        let store = structure.types.iter().find(|t| t.name == "Store").unwrap();
+        assert_eq!(store.kind, "struct");
+        assert_eq!(store.generics, vec!["K comparable"]);
+        assert_eq!(store.members.len(), 3);
+        assert_eq!(store.members[1].visibility, "private");
+        assert_eq!(store.methods, vec!["Add"]);
+        assert_eq!(store.documentation.as_deref(), Some("Store holds items."));
+        let reader = structure.types.iter().find(|t| t.name == "Reader").unwrap();
+        assert_eq!((reader.kind.as_str(), reader.methods.clone()), ("interface", vec!["Read".to_string()]));
+
+        let add = function(&structure, "Add");
+        assert_eq!(add.owner.as_deref(), Some("Store"));
+        assert_eq!(add.return_type.as_deref(), Some("error"));
+        // for + if + `&&` + case
+        assert_eq!(add.complexity, 5);
+        assert!(facts.functions.iter().any(|f| f.name == "TestAdd" && f.is_test));
+    }
+
+    #[cfg(feature = "java-analysis")]
+    #[test]
+    fn test_java_structure() {
+        let source = r#"package com.example.shop;
+
+import java.util.List;
+import static java.lang.Math.max;
+
+/** A shop. */
+public class Shop extends Store implements Comparable<Shop>, Closeable {
+    public static final int LIMIT = 5;
+    private List<String> items;
+
+    public Shop(List<String> items) { this.items = items; }
+
+    /** Counts items. */
+    public int count(String prefix) {
+        int n = 0;
+        for (String item : items) {
+            n += item.startsWith(prefix) ? 1 : 0;
+        }
+        try {
+            System.out.println(n);
+        } catch (Exception e) {
+        }
+        return n;
+    }
+
+    public static void main(String[] args) {
+        System.exit(new Shop(List.of()).count(System.getenv("PREFIX")));
+    }
+}
+"#;
+        let (structure, facts) = analyze(source, Language::Java).unwrap();
+        assert_eq!(structure.modules[0].path, "com.example.shop");
+        assert_eq!(structure.imports[1].import_type, "import static");
+        assert_eq!(structure.imports[1].path, "java.lang.Math.max");
+        assert_eq!(structure.constants[0].name, "Shop.LIMIT");
+
+        let shop = &structure.types[0];
+        assert_eq!(shop.implements, vec!["Store", "Comparable", "Closeable"]);
+        assert_eq!(shop.members.len(), 2);
+        assert_eq!(shop.members[1].visibility, "private");
+        assert_eq!(shop.methods, vec!["Shop", "count", "main"]);
+        assert_eq!(shop.documentation.as_deref(), Some("A shop."));
+
+        let count = function(&structure, "count");
+        assert_eq!(count.return_type.as_deref(), Some("int"));
+        assert_eq!(count.documentation.as_deref(), Some("Counts items."));
+        // for + ternary + catch
+        assert_eq!(count.complexity, 4);
+        assert!(facts.env_vars.contains(&"PREFIX".to_string()));
+        assert!(facts.exits.iter().any(|e| e == "Shop.main: System.exit"));
+        assert!(facts.functions.iter().any(|f| f.name == "Shop.main" && f.is_entry));
+        assert!(facts.smells.iter().any(|s| s.smell_type == "empty_catch"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Watch mode: keep a codebase analysis current as files change
+//!
+//! Only files that were touched (and whose content actually changed), appeared or disappeared
+//! are re-analyzed; the codebase-level graph, relationships and architecture are rebuilt from
+//! the per-file results after each burst of changes.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use error_types::PhoenixError;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::{mpsc, watch};
+
+use crate::cache::content_hash;
+use crate::{CodeAnalysis, CodeAnalyzer, CodebaseAnalysis};
+
+/// Editors often write a file several times in a row; wait this long for things to settle.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Keeps a codebase watch alive. Dropping it stops re-analysis.
+pub struct CodebaseWatcher {
+    _watcher: RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+    root: PathBuf,
+    updates: watch::Receiver<Arc<CodebaseAnalysis>>,
+}
+
+impl CodebaseWatcher {
+    /// Analyze `root` once, then re-analyze changed files as they are saved.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub async fn start(analyzer: CodeAnalyzer, root: impl AsRef<Path>) -> Result<Self, PhoenixError> {
+        // Watchers report absolute paths on some platforms; walk the same form.
+        let root = std::fs::canonicalize(root.as_ref())
+            .map_err(|e| PhoenixError::Other(format!("Failed to resolve {}: {}", root.as_ref().display(), e)))?;
+        let (tx, rx) = mpsc::unbounded_channel::<Event>();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => eprintln!("Codebase watch error: {}", e),
+        })
+        .map_err(|e| PhoenixError::Other(format!("Failed to create codebase watcher: {}", e)))?;
+        // Watch before the initial analysis so edits made meanwhile are picked up.
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| PhoenixError::Other(format!("Failed to watch {}: {}", root.display(), e)))?;
+
+        let mut state = WatchState {
+            analyzer,
+            root: root.clone(),
+            files: BTreeMap::new(),
+            errors: BTreeMap::new(),
+        };
+        let initial = state
+            .refresh(None)
+            .await?
+            .expect("a full refresh always produces an analysis");
+        let (updates_tx, updates) = watch::channel(Arc::new(initial));
+        let task = tokio::spawn(state.run(rx, updates_tx));
+        Ok(Self {
+            _watcher: watcher,
+            task,
+            root,
+            updates,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The most recent analysis.
+    pub fn latest(&self) -> Arc<CodebaseAnalysis> {
+        self.updates.borrow().clone()
+    }
+
+    /// A receiver that is marked changed after every re-analysis.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<CodebaseAnalysis>> {
+        self.updates.clone()
+    }
+}
+
+impl Drop for CodebaseWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct WatchState {
+    analyzer: CodeAnalyzer,
+    root: PathBuf,
+    /// Per-file analyses (before codebase-level enrichment) with the content hash they were made from.
+    files: BTreeMap<PathBuf, (Option<String>, CodeAnalysis)>,
+    errors: BTreeMap<PathBuf, String>,
+}
+
+impl WatchState {
+    async fn run(
+        mut self,
+        mut events: mpsc::UnboundedReceiver<Event>,
+        updates: watch::Sender<Arc<CodebaseAnalysis>>,
+    ) {
+        while let Some(first) = events.recv().await {
+            // Collect a burst of events, then re-analyze each touched file once.
+            let mut touched: HashSet<PathBuf> = HashSet::new();
+            collect_paths(&first, &mut touched);
+            tokio::time::sleep(DEBOUNCE).await;
+            while let Ok(event) = events.try_recv() {
+                collect_paths(&event, &mut touched);
+            }
+            if touched.is_empty() {
+                continue;
+            }
+            match self.refresh(Some(&touched)).await {
+                Ok(Some(analysis)) => {
+                    updates.send_replace(Arc::new(analysis));
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Codebase re-analysis of {} failed: {}", self.root.display(), e),
+            }
+        }
+    }
+
+    /// Bring the per-file analyses in line with the tree and rebuild the codebase analysis.
+    /// With `touched`, only those files plus new ones are analyzed and `None` is returned when
+    /// nothing changed; without it, everything is analyzed and an analysis is always returned.
+    async fn refresh(
+        &mut self,
+        touched: Option<&HashSet<PathBuf>>,
+    ) -> Result<Option<CodebaseAnalysis>, PhoenixError> {
+        let listing: BTreeSet<PathBuf> = self.analyzer.code_files(&self.root).into_iter().collect();
+
+        let removed: Vec<PathBuf> = self
+            .files
+            .keys()
+            .chain(self.errors.keys())
+            .filter(|path| !listing.contains(*path))
+            .cloned()
+            .collect();
+        let mut changed = touched.is_none() || !removed.is_empty();
+        for path in removed {
+            self.files.remove(&path);
+            self.errors.remove(&path);
+        }
+
+        let mut pending: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+        for path in &listing {
+            let known = self.files.contains_key(path) || self.errors.contains_key(path);
+            if known && touched.is_some_and(|t| !t.contains(path)) {
+                continue;
+            }
+            // Saving without edits (or touching) leaves the analysis valid.
+            let hash = std::fs::read_to_string(path).ok().map(|c| content_hash(&c));
+            if hash.is_some() && self.files.get(path).is_some_and(|(old, _)| *old == hash) {
+                continue;
+            }
+            pending.insert(path.clone(), hash);
+        }
+
+        if !pending.is_empty() {
+            changed = true;
+            let (analyses, errors) = self
+                .analyzer
+                .analyze_files(pending.keys().cloned().collect())
+                .await;
+            for analysis in analyses {
+                let hash = pending.get(&analysis.file_path).cloned().flatten();
+                self.errors.remove(&analysis.file_path);
+                self.files.insert(analysis.file_path.clone(), (hash, analysis));
+            }
+            for (path, error) in errors {
+                self.files.remove(&path);
+                self.errors.insert(path, error);
+            }
+        }
+        if !changed {
+            return Ok(None);
+        }
+
+        let files = self.files.values().map(|(_, analysis)| analysis.clone()).collect();
+        let errors = self.errors.iter().map(|(p, e)| (p.clone(), e.clone())).collect();
+        self.analyzer
+            .assemble_codebase(&self.root, files, errors)
+            .await
+            .map(Some)
+    }
+}
+
+fn collect_paths(event: &Event, out: &mut HashSet<PathBuf>) {
+    if matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        out.extend(event.paths.iter().cloned());
+    }
+}
+
+#[cfg(all(test, feature = "rust-analysis"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_changed_files_are_reanalyzed() {
+        let root = std::env::temp_dir().join(format!("phoenix-code-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"watch-fixture\"\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn first() {}\n").unwrap();
+
+        let watcher = CodebaseWatcher::start(CodeAnalyzer::new(), &root).await.unwrap();
+        assert_eq!(watcher.latest().files.len(), 1);
+        let mut updates = watcher.subscribe();
+
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "pub mod extra;\nuse crate::extra::second;\npub fn first() { second() }\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/extra.rs"), "pub fn second() {}\n").unwrap();
+        let found = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                updates.changed().await.unwrap();
+                let latest = updates.borrow_and_update().clone();
+                let functions: Vec<&str> = latest
+                    .files
+                    .iter()
+                    .flat_map(|f| f.structure.functions.iter().map(|f| f.name.as_str()))
+                    .collect();
+                if functions.contains(&"second") {
+                    return latest;
+                }
+            }
+        })
+        .await
+        .expect("new file should be analyzed");
+        assert_eq!(found.files.len(), 2);
+        assert!(found
+            .dependency_graph
+            .edges
+            .contains(&("watch_fixture".to_string(), "watch_fixture::extra".to_string())));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
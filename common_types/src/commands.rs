@@ -0,0 +1,703 @@
+//! Typed command registry shared by the web API and the TUI.
+//!
+//! Commands are declared as [`CommandSpec`]s: a (possibly multi-word) name, positional arguments,
+//! `--flags`, trailing `| key=value` options, help text and the permission needed to run them.
+//! [`CommandRegistry::resolve`] matches raw input against the declared names, validates the
+//! arguments and hands back a [`ParsedCommand`] together with the registered handler. Input that
+//! does not name a command resolves to `None` so callers can route it elsewhere (e.g. the LLM).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// How a positional argument binds to the input words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgKind {
+    /// Exactly one word.
+    Required,
+    /// Zero or one word.
+    Optional,
+    /// The rest of the input (at least one word), spacing preserved.
+    Rest,
+    /// The rest of the input, possibly empty.
+    OptionalRest,
+    /// A `--name` switch; may appear anywhere among the arguments.
+    Flag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArgSpec {
+    pub name: String,
+    pub kind: ArgKind,
+    pub help: String,
+    /// Accepted values (case-insensitive); empty means any value.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+}
+
+impl ArgSpec {
+    pub fn new(name: impl Into<String>, kind: ArgKind, help: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            help: help.into(),
+            choices: Vec::new(),
+        }
+    }
+
+    pub fn required(name: impl Into<String>, help: impl Into<String>) -> Self {
+        Self::new(name, ArgKind::Required, help)
+    }
+
+    pub fn optional(name: impl Into<String>, help: impl Into<String>) -> Self {
+        Self::new(name, ArgKind::Optional, help)
+    }
+
+    pub fn rest(name: impl Into<String>, help: impl Into<String>) -> Self {
+        Self::new(name, ArgKind::Rest, help)
+    }
+
+    pub fn optional_rest(name: impl Into<String>, help: impl Into<String>) -> Self {
+        Self::new(name, ArgKind::OptionalRest, help)
+    }
+
+    pub fn flag(name: impl Into<String>, help: impl Into<String>) -> Self {
+        Self::new(name, ArgKind::Flag, help)
+    }
+
+    pub fn one_of(mut self, choices: &[&str]) -> Self {
+        self.choices = choices.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    fn usage(&self) -> String {
+        let inner = if self.choices.is_empty() {
+            self.name.clone()
+        } else {
+            self.choices.join("|")
+        };
+        match self.kind {
+            ArgKind::Required => format!("<{inner}>"),
+            ArgKind::Optional => format!("[{inner}]"),
+            ArgKind::Rest => format!("<{inner}...>"),
+            ArgKind::OptionalRest => format!("[{inner}...]"),
+            ArgKind::Flag => format!("[--{}]", self.name),
+        }
+    }
+}
+
+/// A trailing `| key=value` option.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionSpec {
+    pub name: String,
+    pub help: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandSpec {
+    /// Space separated command words, lowercase (e.g. `system read`).
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    pub summary: String,
+    #[serde(default)]
+    pub args: Vec<ArgSpec>,
+    #[serde(default)]
+    pub options: Vec<OptionSpec>,
+    /// Permission the caller needs; `None` means anyone who can chat may run it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+    /// Generated from the fields above.
+    pub usage: String,
+}
+
+impl CommandSpec {
+    pub fn new(name: impl Into<String>, summary: impl Into<String>) -> Self {
+        let name = name.into().to_ascii_lowercase();
+        Self {
+            usage: name.clone(),
+            name,
+            aliases: Vec::new(),
+            summary: summary.into(),
+            args: Vec::new(),
+            options: Vec::new(),
+            permission: None,
+        }
+    }
+
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into().to_ascii_lowercase());
+        self
+    }
+
+    pub fn arg(mut self, arg: ArgSpec) -> Self {
+        self.args.push(arg);
+        self.refresh_usage()
+    }
+
+    pub fn option(mut self, name: impl Into<String>, help: impl Into<String>) -> Self {
+        self.options.push(OptionSpec {
+            name: name.into(),
+            help: help.into(),
+        });
+        self.refresh_usage()
+    }
+
+    pub fn permission(mut self, permission: impl Into<String>) -> Self {
+        self.permission = Some(permission.into());
+        self
+    }
+
+    fn refresh_usage(mut self) -> Self {
+        let mut usage = self.name.clone();
+        for arg in &self.args {
+            usage.push(' ');
+            usage.push_str(&arg.usage());
+        }
+        for opt in &self.options {
+            usage.push_str(&format!(" | {}=...", opt.name));
+        }
+        self.usage = usage;
+        self
+    }
+
+    /// Multi-line help: usage, summary, then one line per argument and option.
+    pub fn help(&self) -> String {
+        let mut out = format!("Usage: {}\n{}", self.usage, self.summary);
+        for arg in &self.args {
+            let label = match arg.kind {
+                ArgKind::Flag => format!("--{}", arg.name),
+                _ => arg.name.clone(),
+            };
+            out.push_str(&format!("\n  {label:<14} {}", arg.help));
+        }
+        for opt in &self.options {
+            out.push_str(&format!("\n  {:<14} {}", format!("{}=", opt.name), opt.help));
+        }
+        if !self.aliases.is_empty() {
+            out.push_str(&format!("\nAliases: {}", self.aliases.join(", ")));
+        }
+        out
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+
+    fn has_positionals(&self) -> bool {
+        self.args.iter().any(|a| a.kind != ArgKind::Flag)
+    }
+}
+
+/// A validated invocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedCommand {
+    /// Canonical command name (aliases are resolved).
+    pub name: String,
+    pub args: BTreeMap<String, String>,
+    pub flags: BTreeSet<String>,
+    pub options: BTreeMap<String, String>,
+    /// The input as typed.
+    pub raw: String,
+}
+
+impl ParsedCommand {
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args.get(name).map(String::as_str)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// The first word names a command group but the rest matches none of its commands.
+    UnknownSubcommand {
+        group: String,
+        given: Option<String>,
+        available: Vec<String>,
+    },
+    MissingArgument {
+        arg: String,
+        usage: String,
+    },
+    UnexpectedArgument {
+        value: String,
+        usage: String,
+    },
+    InvalidChoice {
+        arg: String,
+        value: String,
+        choices: Vec<String>,
+        usage: String,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSubcommand {
+                group,
+                given,
+                available,
+            } => {
+                match given {
+                    Some(given) => write!(f, "Unknown {group} command: {given}.")?,
+                    None => write!(f, "Missing {group} command.")?,
+                }
+                write!(f, "\nUsage:")?;
+                for usage in available {
+                    write!(f, "\n  {usage}")?;
+                }
+                Ok(())
+            }
+            Self::MissingArgument { arg, usage } => {
+                write!(f, "Missing argument <{arg}>.\nUsage: {usage}")
+            }
+            Self::UnexpectedArgument { value, usage } => {
+                write!(f, "Unexpected argument: {value}.\nUsage: {usage}")
+            }
+            Self::InvalidChoice {
+                arg,
+                value,
+                choices,
+                usage,
+            } => write!(
+                f,
+                "Invalid value for <{arg}>: {value} (expected one of: {}).\nUsage: {usage}",
+                choices.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Command specs paired with caller-defined handlers.
+pub struct CommandRegistry<H> {
+    entries: Vec<(CommandSpec, H)>,
+}
+
+impl<H> Default for CommandRegistry<H> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<H> CommandRegistry<H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, spec: CommandSpec, handler: H) -> &mut Self {
+        self.entries.push((spec, handler));
+        self
+    }
+
+    pub fn specs(&self) -> impl Iterator<Item = &CommandSpec> {
+        self.entries.iter().map(|(spec, _)| spec)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CommandSpec> {
+        let name = name.trim().to_ascii_lowercase();
+        self.specs().find(|spec| spec.names().any(|n| n == name))
+    }
+
+    /// One line per command: usage and summary, aligned.
+    pub fn help_text(&self) -> String {
+        let width = self.specs().map(|s| s.usage.len()).max().unwrap_or(0);
+        self.specs()
+            .map(|s| format!("  {:<width$}  {}", s.usage, s.summary))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The spec `input` addresses, without validating its arguments.
+    ///
+    /// For an unknown subcommand of a known group this is the group's first command that declares
+    /// a permission, so permission checks fail closed.
+    pub fn spec_for(&self, input: &str) -> Option<&CommandSpec> {
+        let words = head_words(input);
+        if let Some((idx, _)) = self.longest_match(&words) {
+            return Some(&self.entries[idx].0);
+        }
+        let group = words.first()?;
+        let members = self.group_members(group);
+        members
+            .iter()
+            .find(|spec| spec.permission.is_some())
+            .or(members.first())
+            .copied()
+    }
+
+    /// Match `input` against the registry.
+    ///
+    /// `Ok(None)` means the input is not a command. A single-word command without positional
+    /// arguments only matches when typed alone, so free text such as "help me with this" is not
+    /// swallowed by `help`.
+    pub fn resolve(&self, input: &str) -> Result<Option<(ParsedCommand, &H)>, CommandError> {
+        let input = input.trim();
+        let words = head_words(input);
+        let Some((idx, name_len)) = self.longest_match(&words) else {
+            let Some(group) = words.first() else {
+                return Ok(None);
+            };
+            let available: Vec<String> = self.group_members(group).iter().map(|s| s.usage.clone()).collect();
+            if available.is_empty() {
+                return Ok(None);
+            }
+            return Err(CommandError::UnknownSubcommand {
+                group: group.clone(),
+                given: words.get(1).map(|w| w.to_string()),
+                available,
+            });
+        };
+
+        let (spec, handler) = &self.entries[idx];
+        let (head, options) = split_options(input, spec);
+        let tokens: Vec<(usize, &str)> = tokenize(head).into_iter().skip(name_len).collect();
+        if !tokens.is_empty() && !spec.has_positionals() && !spec.name.contains(' ') && options.is_empty() {
+            return Ok(None);
+        }
+
+        let mut parsed = ParsedCommand {
+            name: spec.name.clone(),
+            options,
+            raw: input.to_string(),
+            ..ParsedCommand::default()
+        };
+        let mut positionals = spec.args.iter().filter(|a| a.kind != ArgKind::Flag);
+        let mut tokens = tokens.into_iter();
+        while let Some((start, token)) = tokens.next() {
+            if let Some(flag) = token.strip_prefix("--")
+                && let Some(arg) = spec.args.iter().find(|a| a.kind == ArgKind::Flag && a.name == flag)
+            {
+                parsed.flags.insert(arg.name.clone());
+                continue;
+            }
+            let Some(arg) = positionals.next() else {
+                return Err(CommandError::UnexpectedArgument {
+                    value: token.to_string(),
+                    usage: spec.usage.clone(),
+                });
+            };
+            let value = match arg.kind {
+                ArgKind::Rest | ArgKind::OptionalRest => {
+                    tokens.by_ref().for_each(drop);
+                    head[start..].trim().to_string()
+                }
+                _ => token.to_string(),
+            };
+            let value = match arg.choices.iter().find(|c| c.eq_ignore_ascii_case(&value)) {
+                Some(choice) => choice.clone(),
+                None if arg.choices.is_empty() => value,
+                None => {
+                    return Err(CommandError::InvalidChoice {
+                        arg: arg.name.clone(),
+                        value,
+                        choices: arg.choices.clone(),
+                        usage: spec.usage.clone(),
+                    });
+                }
+            };
+            parsed.args.insert(arg.name.clone(), value);
+        }
+        if let Some(missing) = positionals.find(|a| matches!(a.kind, ArgKind::Required | ArgKind::Rest)) {
+            return Err(CommandError::MissingArgument {
+                arg: missing.name.clone(),
+                usage: spec.usage.clone(),
+            });
+        }
+        Ok(Some((parsed, handler)))
+    }
+
+    /// Index of the entry whose name (or alias) is the longest prefix of `words`, with its length.
+    fn longest_match(&self, words: &[String]) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        for (idx, (spec, _)) in self.entries.iter().enumerate() {
+            for name in spec.names() {
+                let name_words: Vec<&str> = name.split_whitespace().collect();
+                let matches = name_words.len() <= words.len()
+                    && name_words.iter().zip(words).all(|(n, w)| *n == w);
+                if matches && best.is_none_or(|(_, len)| name_words.len() > len) {
+                    best = Some((idx, name_words.len()));
+                }
+            }
+        }
+        best
+    }
+
+    /// Multi-word commands whose first word is `group`.
+    fn group_members(&self, group: &str) -> Vec<&CommandSpec> {
+        self.specs()
+            .filter(|spec| {
+                spec.names().any(|n| {
+                    let mut words = n.split_whitespace();
+                    words.next() == Some(group) && words.next().is_some()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Lowercased whitespace-separated words of `input`, up to the first `|`.
+fn head_words(input: &str) -> Vec<String> {
+    let head = input.split('|').next().unwrap_or_default();
+    head.split_whitespace().map(str::to_ascii_lowercase).collect()
+}
+
+/// Words of `s` with their byte offsets.
+fn tokenize(s: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(st)) => {
+                out.push((st, &s[st..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(st) = start {
+        out.push((st, &s[st..]));
+    }
+    out
+}
+
+/// Split trailing `| key=value` segments for options `spec` declares off `input`.
+///
+/// Segments are taken from the end, so a `|` inside a rest argument (a shell pipeline, say) stays
+/// part of the argument.
+fn split_options<'a>(input: &'a str, spec: &CommandSpec) -> (&'a str, BTreeMap<String, String>) {
+    let mut head = input;
+    let mut options = BTreeMap::new();
+    while let Some(idx) = head.rfind('|') {
+        let Some((key, value)) = head[idx + 1..].split_once('=') else {
+            break;
+        };
+        let key = key.trim();
+        if !spec.options.iter().any(|o| o.name == key) {
+            break;
+        }
+        options.entry(key.to_string()).or_insert_with(|| value.trim().to_string());
+        head = &head[..idx];
+    }
+    (head.trim_end(), options)
+}
+
+/// Commands both front ends (phoenix-web and phoenix-tui) implement. They are declared once here
+/// so names, arguments, help and permissions cannot drift apart; each front end registers them
+/// with its own handlers.
+pub mod shared {
+    use super::{ArgSpec, CommandSpec};
+
+    pub fn help() -> CommandSpec {
+        CommandSpec::new("help", "Show this command list")
+    }
+
+    pub fn status() -> CommandSpec {
+        CommandSpec::new("status", "Companion status: affection, attachment, energy, mood")
+    }
+
+    pub fn skills_list() -> CommandSpec {
+        CommandSpec::new("skills list", "List loaded skills")
+    }
+
+    pub fn skills_search() -> CommandSpec {
+        CommandSpec::new("skills search", "Search the skill marketplace").arg(ArgSpec::optional_rest("text", "Search text"))
+    }
+
+    pub fn skills_installed() -> CommandSpec {
+        CommandSpec::new("skills installed", "List installed skill bundles")
+    }
+
+    pub fn skills_install() -> CommandSpec {
+        CommandSpec::new("skills install", "Install a bundle from the marketplace")
+            .arg(ArgSpec::required("name", "Bundle name, optionally name@version"))
+            .permission("system")
+    }
+
+    pub fn skills_uninstall() -> CommandSpec {
+        CommandSpec::new("skills uninstall", "Remove an installed bundle")
+            .arg(ArgSpec::required("name", "Bundle name"))
+            .arg(ArgSpec::flag("force", "Remove even if other bundles depend on it"))
+            .permission("system")
+    }
+
+    pub fn skills_upgrade() -> CommandSpec {
+        CommandSpec::new("skills upgrade", "Upgrade one bundle, or all of them")
+            .arg(ArgSpec::optional("name", "Bundle name"))
+            .permission("system")
+    }
+
+    pub fn backup_create() -> CommandSpec {
+        CommandSpec::new("backup create", "Snapshot every database into the backup directory").permission("system")
+    }
+
+    pub fn backup_list() -> CommandSpec {
+        CommandSpec::new("backup list", "List snapshot archives, newest first").permission("system")
+    }
+
+    pub fn backup_restore() -> CommandSpec {
+        CommandSpec::new("backup restore", "Restore databases from a snapshot (the current state is snapshotted first)")
+            .arg(ArgSpec::required("archive", "Archive file name from `backup list`, or a path"))
+            .permission("system")
+    }
+
+    pub fn backup_verify() -> CommandSpec {
+        CommandSpec::new("backup verify", "Test-restore a snapshot into a scratch directory")
+            .arg(ArgSpec::optional("archive", "Archive file name (default: the newest snapshot)"))
+            .permission("system")
+    }
+
+    /// Every shared spec.
+    pub fn all() -> Vec<CommandSpec> {
+        vec![
+            help(),
+            status(),
+            skills_list(),
+            skills_search(),
+            skills_installed(),
+            skills_install(),
+            skills_uninstall(),
+            skills_upgrade(),
+            backup_create(),
+            backup_list(),
+            backup_restore(),
+            backup_verify(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> CommandRegistry<&'static str> {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(CommandSpec::new("help", "Show commands"), "help")
+            .register(
+                CommandSpec::new("system read", "Read a file")
+                    .arg(ArgSpec::required("path", "File to read"))
+                    .permission("system"),
+                "read",
+            )
+            .register(
+                CommandSpec::new("system exec", "Run a shell command")
+                    .alias("system execute")
+                    .arg(ArgSpec::rest("command", "Shell command"))
+                    .option("cwd", "Working directory")
+                    .permission("system"),
+                "exec",
+            )
+            .register(
+                CommandSpec::new("system keylogger", "Toggle the keylogger")
+                    .arg(ArgSpec::required("action", "start or stop").one_of(&["start", "stop"]))
+                    .permission("system"),
+                "keylogger",
+            )
+            .register(CommandSpec::new("skills list", "List skills"), "skills-list")
+            .register(
+                CommandSpec::new("skills uninstall", "Remove a bundle")
+                    .arg(ArgSpec::required("name", "Bundle name"))
+                    .arg(ArgSpec::flag("force", "Remove even if others depend on it"))
+                    .permission("system"),
+                "uninstall",
+            );
+        registry
+    }
+
+    #[test]
+    fn free_text_is_not_a_command() {
+        let registry = registry();
+        assert!(registry.resolve("tell me a story").unwrap().is_none());
+        assert!(registry.resolve("help me write a poem").unwrap().is_none());
+        assert!(registry.resolve("").unwrap().is_none());
+    }
+
+    #[test]
+    fn resolves_longest_name_and_binds_args() {
+        let registry = registry();
+        let (parsed, handler) = registry.resolve("System READ /tmp/Notes.txt").unwrap().unwrap();
+        assert_eq!(*handler, "read");
+        assert_eq!(parsed.name, "system read");
+        assert_eq!(parsed.arg("path"), Some("/tmp/Notes.txt"));
+    }
+
+    #[test]
+    fn rest_keeps_pipes_and_options_split_from_end() {
+        let registry = registry();
+        let (parsed, _) = registry
+            .resolve("system execute ls  -la | grep rs | cwd=/tmp")
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed.name, "system exec");
+        assert_eq!(parsed.arg("command"), Some("ls  -la | grep rs"));
+        assert_eq!(parsed.option("cwd"), Some("/tmp"));
+    }
+
+    #[test]
+    fn flags_and_choices() {
+        let registry = registry();
+        let (parsed, _) = registry.resolve("skills uninstall --force web").unwrap().unwrap();
+        assert_eq!(parsed.arg("name"), Some("web"));
+        assert!(parsed.flag("force"));
+
+        let (parsed, _) = registry.resolve("system keylogger STOP").unwrap().unwrap();
+        assert_eq!(parsed.arg("action"), Some("stop"));
+
+        let err = registry.resolve("system keylogger pause").unwrap_err();
+        assert!(matches!(err, CommandError::InvalidChoice { .. }));
+        assert!(err.to_string().contains("system keylogger <start|stop>"));
+    }
+
+    #[test]
+    fn validation_errors_carry_usage() {
+        let registry = registry();
+        let err = registry.resolve("system read").unwrap_err();
+        assert_eq!(err.to_string(), "Missing argument <path>.\nUsage: system read <path>");
+
+        let err = registry.resolve("skills list everything").unwrap_err();
+        assert!(matches!(err, CommandError::UnexpectedArgument { ref value, .. } if value == "everything"));
+
+        let err = registry.resolve("system reboot").unwrap_err();
+        let CommandError::UnknownSubcommand { given, available, .. } = err else {
+            panic!("expected unknown subcommand");
+        };
+        assert_eq!(given.as_deref(), Some("reboot"));
+        assert_eq!(available.len(), 3);
+    }
+
+    #[test]
+    fn spec_for_fails_closed_on_unknown_subcommands() {
+        let registry = registry();
+        assert_eq!(registry.spec_for("skills list").unwrap().permission, None);
+        assert_eq!(
+            registry.spec_for("skills nonsense").unwrap().permission.as_deref(),
+            Some("system")
+        );
+        assert!(registry.spec_for("hello there").is_none());
+    }
+
+    #[test]
+    fn help_is_generated_from_specs() {
+        let registry = registry();
+        let help = registry.help_text();
+        assert!(help.contains("system exec <command...> | cwd=...  Run a shell command"));
+        assert!(help.contains("skills uninstall <name> [--force]"));
+        let spec = registry.get("system execute").unwrap();
+        assert!(spec.help().contains("Aliases: system execute"));
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+pub mod commands;
 pub mod ports;
 
 /// Evolution log entry (identity versioning).
@@ -0,0 +1,620 @@
+//! Archetype sources and the on-disk archetype cache.
+//!
+//! An archetype is a directory holding `master_system_prompt.txt` and `personality_db.json`,
+//! read from GitHub, a local git repository or a plain local directory
+//! (`PHOENIX_ARCHETYPE_SOURCE`). Versions read at a commit are cached under
+//! `PHOENIX_ARCHETYPE_CACHE_DIR/<source>/<archetype>/<commit_sha>/`; when the source is
+//! unreachable, [`load_archetype`] serves the last cached version instead.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use error_types::ConfigError;
+use serde::{Deserialize, Serialize};
+
+use crate::text_diff::{self, LineStats};
+use crate::{env_nonempty, PersonalityDatabase};
+
+pub const PROMPT_FILE: &str = "master_system_prompt.txt";
+pub const PERSONALITY_FILE: &str = "personality_db.json";
+/// Where archetypes are cached unless `PHOENIX_ARCHETYPE_CACHE_DIR` says otherwise.
+pub const DEFAULT_CACHE_DIR: &str = "data/archetype_cache";
+/// Keeps startup from hanging on an unreachable GitHub.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Names the version a cache entry currently points at.
+const CURRENT_FILE: &str = "current";
+
+/// Lowercase `[a-z0-9]` runs joined by `-`, for cache directory, branch and file names.
+pub fn slug(s: &str) -> String {
+    let mut out = String::new();
+    for ch in s.chars() {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
+fn check_name(archetype_name: &str) -> Result<(), ConfigError> {
+    let ok = !archetype_name.is_empty()
+        && archetype_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !archetype_name.starts_with('.');
+    if ok {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidValue("archetype".to_string(), archetype_name.to_string()))
+    }
+}
+
+fn io_err(path: &Path, e: std::io::Error) -> ConfigError {
+    ConfigError::LoadFailed(format!("{}: {}", path.display(), e))
+}
+
+fn git_err(e: git2::Error) -> ConfigError {
+    ConfigError::InvalidRepository(format!("git: {}", e.message()))
+}
+
+/// An archetype's files as published, unparsed (so versions can be diffed byte for byte).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchetypeFiles {
+    pub master_system_prompt: String,
+    pub personality_json: String,
+    /// Commit the files were read at (`None` for plain directories).
+    pub commit_sha: Option<String>,
+}
+
+impl ArchetypeFiles {
+    pub fn personality_db(&self) -> Result<PersonalityDatabase, ConfigError> {
+        serde_json::from_str(&self.personality_json)
+            .map_err(|e| ConfigError::ParseError(format!("Failed to parse {PERSONALITY_FILE}: {e}")))
+    }
+}
+
+/// Where archetypes are read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchetypeSource {
+    /// `<owner>/<repo>` on GitHub at `branch`; `token` is only needed for private repositories.
+    GitHub {
+        owner: String,
+        repo: String,
+        branch: String,
+        token: Option<String>,
+    },
+    /// A local clone (or bare repository), read at the tip of `branch`.
+    GitRepo { path: PathBuf, branch: String },
+    /// A plain directory with one subdirectory per archetype.
+    Directory(PathBuf),
+}
+
+impl ArchetypeSource {
+    /// `PHOENIX_ARCHETYPE_SOURCE` (or `PHOENIX_ARCHETYPE_CHECKOUT`) when set to a local path,
+    /// otherwise `GITHUB_REPO_OWNER`/`PHOENIX_ARCHETYPE_REPO` on GitHub. Both use
+    /// `PHOENIX_ARCHETYPE_BRANCH` (default `main`).
+    pub fn from_env() -> Self {
+        let branch = env_nonempty("PHOENIX_ARCHETYPE_BRANCH").unwrap_or_else(|| "main".to_string());
+        if let Some(path) = env_nonempty("PHOENIX_ARCHETYPE_SOURCE").or_else(|| env_nonempty("PHOENIX_ARCHETYPE_CHECKOUT")) {
+            return Self::local(path, branch);
+        }
+        Self::GitHub {
+            owner: env_nonempty("GITHUB_REPO_OWNER")
+                .or_else(|| env_nonempty("GITHUB_USERNAME"))
+                .unwrap_or_else(|| "c04ch1337".to_string()),
+            repo: env_nonempty("PHOENIX_ARCHETYPE_REPO").unwrap_or_else(|| "phoenix-archetypes".to_string()),
+            branch,
+            token: env_nonempty("GITHUB_PAT").or_else(|| env_nonempty("GITHUB_TOKEN")),
+        }
+    }
+
+    /// A git repository if `path` is one, a plain directory otherwise.
+    pub fn local(path: impl Into<PathBuf>, branch: impl Into<String>) -> Self {
+        let path = path.into();
+        if git2::Repository::open(&path).is_ok() {
+            Self::GitRepo { path, branch: branch.into() }
+        } else {
+            Self::Directory(path)
+        }
+    }
+
+    /// Directory name of this source inside the cache.
+    fn cache_key(&self) -> String {
+        match self {
+            Self::GitHub { owner, repo, branch, .. } => format!("github-{}", slug(&format!("{owner}/{repo}@{branch}"))),
+            Self::GitRepo { path, branch } => format!("git-{}", slug(&format!("{}@{branch}", path.display()))),
+            Self::Directory(path) => format!("dir-{}", slug(&path.display().to_string())),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::GitHub { owner, repo, branch, .. } => format!("github.com/{owner}/{repo}@{branch}"),
+            Self::GitRepo { path, branch } => format!("{}@{branch}", path.display()),
+            Self::Directory(path) => path.display().to_string(),
+        }
+    }
+
+    /// Newest commit that changed the archetype (`None` for plain directories).
+    pub async fn latest_sha(&self, archetype_name: &str) -> Result<Option<String>, ConfigError> {
+        check_name(archetype_name)?;
+        match self {
+            Self::GitHub { owner, repo, branch, token } => {
+                let url = format!(
+                    "https://api.github.com/repos/{owner}/{repo}/commits?path={archetype_name}&sha={branch}&per_page=1"
+                );
+                let commits: Vec<serde_json::Value> = serde_json::from_str(
+                    &github_get(&url, token.as_deref(), "application/vnd.github+json").await?,
+                )
+                .map_err(|e| ConfigError::ParseError(format!("Failed to parse commits: {e}")))?;
+                commits
+                    .first()
+                    .and_then(|c| c.get("sha"))
+                    .and_then(|s| s.as_str())
+                    .map(|s| Some(s.to_string()))
+                    .ok_or_else(|| ConfigError::ArchetypeNotFound(archetype_name.to_string()))
+            }
+            Self::GitRepo { path, branch } => {
+                let repo = git2::Repository::open(path).map_err(git_err)?;
+                let mut commit = repo
+                    .revparse_single(&format!("refs/heads/{branch}"))
+                    .and_then(|o| o.peel_to_commit())
+                    .map_err(git_err)?;
+                let entry_id = |c: &git2::Commit<'_>| c.tree().ok()?.get_path(Path::new(archetype_name)).ok().map(|e| e.id());
+                let id = entry_id(&commit).ok_or_else(|| ConfigError::ArchetypeNotFound(archetype_name.to_string()))?;
+                loop {
+                    match commit.parent(0) {
+                        Ok(parent) if entry_id(&parent) == Some(id) => commit = parent,
+                        _ => return Ok(Some(commit.id().to_string())),
+                    }
+                }
+            }
+            Self::Directory(_) => Ok(None),
+        }
+    }
+
+    /// Read the archetype's files, at `commit_sha` when given (ignored for plain directories).
+    pub async fn fetch(&self, archetype_name: &str, commit_sha: Option<&str>) -> Result<ArchetypeFiles, ConfigError> {
+        check_name(archetype_name)?;
+        let (master_system_prompt, personality_json) = match self {
+            Self::GitHub { owner, repo, branch, token } => {
+                let rev = commit_sha.unwrap_or(branch);
+                let raw = |file: &str| format!("https://raw.githubusercontent.com/{owner}/{repo}/{rev}/{archetype_name}/{file}");
+                (
+                    github_get(&raw(PROMPT_FILE), token.as_deref(), "application/vnd.github.raw").await?,
+                    github_get(&raw(PERSONALITY_FILE), token.as_deref(), "application/vnd.github.raw").await?,
+                )
+            }
+            Self::GitRepo { path, branch } => {
+                let repo = git2::Repository::open(path).map_err(git_err)?;
+                let rev = commit_sha.map(str::to_string).unwrap_or_else(|| format!("refs/heads/{branch}"));
+                let tree = repo
+                    .revparse_single(&rev)
+                    .and_then(|o| o.peel_to_commit())
+                    .and_then(|c| c.tree())
+                    .map_err(git_err)?;
+                let read = |file: &str| -> Result<String, ConfigError> {
+                    let entry = tree
+                        .get_path(&Path::new(archetype_name).join(file))
+                        .map_err(|_| ConfigError::ArchetypeNotFound(format!("{archetype_name}/{file}")))?;
+                    let blob = entry.to_object(&repo).and_then(|o| o.peel_to_blob()).map_err(git_err)?;
+                    Ok(String::from_utf8_lossy(blob.content()).into_owned())
+                };
+                (read(PROMPT_FILE)?, read(PERSONALITY_FILE)?)
+            }
+            Self::Directory(root) => {
+                let read = |file: &str| {
+                    let path = root.join(archetype_name).join(file);
+                    std::fs::read_to_string(&path).map_err(|e| io_err(&path, e))
+                };
+                (read(PROMPT_FILE)?, read(PERSONALITY_FILE)?)
+            }
+        };
+        let files = ArchetypeFiles {
+            master_system_prompt,
+            personality_json,
+            commit_sha: commit_sha.map(str::to_string),
+        };
+        files.personality_db()?;
+        Ok(files)
+    }
+}
+
+async fn github_get(url: &str, token: Option<&str>, accept: &str) -> Result<String, ConfigError> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| ConfigError::DownloadFailed(e.to_string()))?;
+    let mut req = client
+        .get(url)
+        .header("Accept", accept)
+        .header("User-Agent", "phoenix-agi-config-manager");
+    if let Some(token) = token {
+        req = req.header("Authorization", format!("Bearer {token}"));
+    }
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| ConfigError::DownloadFailed(format!("{url}: {e}")))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(ConfigError::DownloadFailed(format!("{url}: {status}")));
+    }
+    resp.text()
+        .await
+        .map_err(|e| ConfigError::DownloadFailed(format!("{url}: {e}")))
+}
+
+/// Archetype versions kept on disk, keyed by commit SHA.
+#[derive(Debug, Clone)]
+pub struct ArchetypeCache {
+    dir: PathBuf,
+}
+
+impl ArchetypeCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `PHOENIX_ARCHETYPE_CACHE_DIR` (default [`DEFAULT_CACHE_DIR`]).
+    pub fn from_env() -> Self {
+        Self::new(env_nonempty("PHOENIX_ARCHETYPE_CACHE_DIR").unwrap_or_else(|| DEFAULT_CACHE_DIR.to_string()))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn archetype_dir(&self, source: &ArchetypeSource, archetype_name: &str) -> PathBuf {
+        self.dir.join(source.cache_key()).join(archetype_name)
+    }
+
+    /// The cached version at `commit_sha`.
+    pub fn get(&self, source: &ArchetypeSource, archetype_name: &str, commit_sha: &str) -> Option<ArchetypeFiles> {
+        let dir = self.archetype_dir(source, archetype_name).join(commit_sha);
+        Some(ArchetypeFiles {
+            master_system_prompt: std::fs::read_to_string(dir.join(PROMPT_FILE)).ok()?,
+            personality_json: std::fs::read_to_string(dir.join(PERSONALITY_FILE)).ok()?,
+            commit_sha: Some(commit_sha.to_string()),
+        })
+    }
+
+    /// The version most recently loaded from `source`.
+    pub fn latest(&self, source: &ArchetypeSource, archetype_name: &str) -> Option<ArchetypeFiles> {
+        let current = std::fs::read_to_string(self.archetype_dir(source, archetype_name).join(CURRENT_FILE)).ok()?;
+        self.get(source, archetype_name, current.trim())
+    }
+
+    /// Commit SHAs cached for the archetype.
+    pub fn versions(&self, source: &ArchetypeSource, archetype_name: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.archetype_dir(source, archetype_name)) else {
+            return Vec::new();
+        };
+        let mut versions: Vec<String> = entries
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        versions.sort();
+        versions
+    }
+
+    /// Store `files` (which must carry a commit SHA) and make it the current version.
+    pub fn store(&self, source: &ArchetypeSource, archetype_name: &str, files: &ArchetypeFiles) -> Result<(), ConfigError> {
+        let sha = files
+            .commit_sha
+            .as_deref()
+            .ok_or_else(|| ConfigError::InvalidValue("commit_sha".to_string(), "missing".to_string()))?;
+        let base = self.archetype_dir(source, archetype_name);
+        let dir = base.join(sha);
+        if self.get(source, archetype_name, sha).is_none() {
+            // Written into a temporary directory and renamed, so readers never see half a version.
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir).map_err(|e| io_err(&dir, e))?;
+            }
+            let tmp = base.join(format!(".{sha}.{}.tmp", std::process::id()));
+            std::fs::create_dir_all(&tmp).map_err(|e| io_err(&tmp, e))?;
+            for (file, content) in [(PROMPT_FILE, &files.master_system_prompt), (PERSONALITY_FILE, &files.personality_json)] {
+                let path = tmp.join(file);
+                std::fs::write(&path, content).map_err(|e| io_err(&path, e))?;
+            }
+            if let Err(e) = std::fs::rename(&tmp, &dir) {
+                std::fs::remove_dir_all(&tmp).ok();
+                // Another writer stored the same commit first.
+                if !dir.is_dir() {
+                    return Err(io_err(&dir, e));
+                }
+            }
+        }
+        self.set_current(source, archetype_name, sha)
+    }
+
+    fn set_current(&self, source: &ArchetypeSource, archetype_name: &str, sha: &str) -> Result<(), ConfigError> {
+        let path = self.archetype_dir(source, archetype_name).join(CURRENT_FILE);
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        std::fs::write(&tmp, format!("{sha}\n"))
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| io_err(&path, e))
+    }
+}
+
+/// An archetype as returned by [`load_archetype`].
+#[derive(Debug, Clone)]
+pub struct LoadedArchetype {
+    pub files: ArchetypeFiles,
+    /// Read from the cache rather than the source.
+    pub from_cache: bool,
+    /// Why the source could not be used, when the cache stood in for it.
+    pub offline_reason: Option<String>,
+}
+
+/// Load an archetype from `source`, going through `cache` when given.
+///
+/// The source is asked for the archetype's latest commit; a cached copy of that commit is
+/// used as is, anything else is fetched and cached. If the source cannot be reached, the last
+/// cached version is served instead. Plain directories have no commits and are never cached.
+pub async fn load_archetype(
+    source: &ArchetypeSource,
+    cache: Option<&ArchetypeCache>,
+    archetype_name: &str,
+) -> Result<LoadedArchetype, ConfigError> {
+    let upstream = async {
+        let sha = source.latest_sha(archetype_name).await?;
+        if let (Some(cache), Some(sha)) = (cache, sha.as_deref()) {
+            if let Some(files) = cache.get(source, archetype_name, sha) {
+                cache.set_current(source, archetype_name, sha)?;
+                return Ok(LoadedArchetype {
+                    files,
+                    from_cache: true,
+                    offline_reason: None,
+                });
+            }
+        }
+        let files = source.fetch(archetype_name, sha.as_deref()).await?;
+        if let (Some(cache), Some(_)) = (cache, files.commit_sha.as_deref()) {
+            cache.store(source, archetype_name, &files)?;
+        }
+        Ok::<_, ConfigError>(LoadedArchetype {
+            files,
+            from_cache: false,
+            offline_reason: None,
+        })
+    };
+
+    match upstream.await {
+        Ok(loaded) => Ok(loaded),
+        Err(e) => match cache.and_then(|c| c.latest(source, archetype_name)) {
+            Some(files) => Ok(LoadedArchetype {
+                files,
+                from_cache: true,
+                offline_reason: Some(format!("{} unavailable: {e}", source.describe())),
+            }),
+            None => Err(e),
+        },
+    }
+}
+
+/// How one archetype file differs between two versions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    pub file: &'static str,
+    pub stats: LineStats,
+    /// Unified diff; empty when unchanged.
+    pub unified: String,
+}
+
+/// What changed between the cached and the upstream version of an archetype.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchetypeDiff {
+    pub archetype: String,
+    /// `None` when nothing is cached yet (every line shows as added).
+    pub cached_sha: Option<String>,
+    pub upstream_sha: Option<String>,
+    pub files: Vec<FileDiff>,
+}
+
+impl ArchetypeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.files.iter().all(|f| f.unified.is_empty())
+    }
+
+    /// Summary line per file followed by the unified diffs.
+    pub fn render(&self) -> String {
+        let short = |sha: &Option<String>| {
+            sha.as_deref()
+                .map(|s| s.get(..7).unwrap_or(s).to_string())
+                .unwrap_or_else(|| "(none)".to_string())
+        };
+        let mut out = format!(
+            "archetype {}: cached {} → upstream {}\n",
+            self.archetype,
+            short(&self.cached_sha),
+            short(&self.upstream_sha)
+        );
+        if self.is_empty() {
+            out.push_str("no changes\n");
+            return out;
+        }
+        for f in &self.files {
+            out.push_str(&format!("  {}: +{} −{}\n", f.file, f.stats.added, f.stats.removed));
+        }
+        for f in self.files.iter().filter(|f| !f.unified.is_empty()) {
+            out.push('\n');
+            out.push_str(&f.unified);
+        }
+        out
+    }
+}
+
+/// Diff two versions of an archetype file by file.
+pub fn diff_files(archetype_name: &str, old: Option<&ArchetypeFiles>, new: &ArchetypeFiles) -> ArchetypeDiff {
+    let file_diff = |file: &'static str, old: &str, new: &str| FileDiff {
+        file,
+        stats: text_diff::line_stats(old, new),
+        unified: text_diff::unified_diff(
+            old,
+            new,
+            &format!("cached/{archetype_name}/{file}"),
+            &format!("upstream/{archetype_name}/{file}"),
+            3,
+        ),
+    };
+    ArchetypeDiff {
+        archetype: archetype_name.to_string(),
+        cached_sha: old.and_then(|o| o.commit_sha.clone()),
+        upstream_sha: new.commit_sha.clone(),
+        files: vec![
+            file_diff(
+                PROMPT_FILE,
+                old.map(|o| o.master_system_prompt.as_str()).unwrap_or(""),
+                &new.master_system_prompt,
+            ),
+            file_diff(
+                PERSONALITY_FILE,
+                old.map(|o| o.personality_json.as_str()).unwrap_or(""),
+                &new.personality_json,
+            ),
+        ],
+    }
+}
+
+/// Compare the current cached version with the source's latest, without updating the cache.
+pub async fn diff_with_upstream(
+    source: &ArchetypeSource,
+    cache: &ArchetypeCache,
+    archetype_name: &str,
+) -> Result<ArchetypeDiff, ConfigError> {
+    let sha = source.latest_sha(archetype_name).await?;
+    let upstream = source.fetch(archetype_name, sha.as_deref()).await?;
+    let cached = cache.latest(source, archetype_name);
+    Ok(diff_files(archetype_name, cached.as_ref(), &upstream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERSONALITY: &str = r#"{"traits":{"warmth":0.9}}"#;
+
+    fn commit(repo: &git2::Repository, files: &[(&str, &str)], message: &str) -> String {
+        let root = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            std::fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+            std::fs::write(root.join(path), content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap().to_string()
+    }
+
+    fn archetype_repo(dir: &Path) -> (git2::Repository, String) {
+        let mut opts = git2::RepositoryInitOptions::new();
+        opts.initial_head("main");
+        let repo = git2::Repository::init_opts(dir, &opts).unwrap();
+        let sha = commit(
+            &repo,
+            &[("default/master_system_prompt.txt", "You are Phoenix.\n"), ("default/personality_db.json", PERSONALITY)],
+            "seed",
+        );
+        (repo, sha)
+    }
+
+    #[tokio::test]
+    async fn git_source_is_cached_by_commit_and_served_offline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, first) = archetype_repo(&tmp.path().join("archetypes"));
+        let source = ArchetypeSource::local(tmp.path().join("archetypes"), "main");
+        assert!(matches!(source, ArchetypeSource::GitRepo { .. }));
+        let cache = ArchetypeCache::new(tmp.path().join("cache"));
+
+        let loaded = load_archetype(&source, Some(&cache), "default").await.unwrap();
+        assert!(!loaded.from_cache);
+        assert_eq!(loaded.files.commit_sha.as_deref(), Some(first.as_str()));
+        assert_eq!(cache.versions(&source, "default"), vec![first.clone()]);
+
+        // Unrelated commits do not create a new version; a second load hits the cache.
+        commit(&repo, &[("other/master_system_prompt.txt", "Other.\n")], "other archetype");
+        let again = load_archetype(&source, Some(&cache), "default").await.unwrap();
+        assert!(again.from_cache && again.offline_reason.is_none());
+
+        let second = commit(&repo, &[("default/master_system_prompt.txt", "You are Phoenix, reborn.\n")], "edit");
+        let diff = diff_with_upstream(&source, &cache, "default").await.unwrap();
+        assert_eq!(diff.upstream_sha.as_deref(), Some(second.as_str()));
+        assert_eq!(diff.files[0].stats, LineStats { added: 1, removed: 1 });
+        assert!(diff.files[1].unified.is_empty());
+        assert!(diff.render().contains("+You are Phoenix, reborn."));
+        assert_eq!(cache.latest(&source, "default").unwrap().commit_sha.as_deref(), Some(first.as_str()));
+
+        let updated = load_archetype(&source, Some(&cache), "default").await.unwrap();
+        assert_eq!(updated.files.master_system_prompt, "You are Phoenix, reborn.\n");
+
+        drop(repo);
+        std::fs::remove_dir_all(tmp.path().join("archetypes")).unwrap();
+        let offline = load_archetype(&source, Some(&cache), "default").await.unwrap();
+        assert!(offline.from_cache);
+        assert!(offline.offline_reason.is_some());
+        assert_eq!(offline.files.commit_sha.as_deref(), Some(second.as_str()));
+        assert!(load_archetype(&source, None, "default").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn directory_source_reads_files_without_caching() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("archetypes");
+        std::fs::create_dir_all(root.join("heartbound")).unwrap();
+        std::fs::write(root.join("heartbound").join(PROMPT_FILE), "Warm.\n").unwrap();
+        std::fs::write(root.join("heartbound").join(PERSONALITY_FILE), PERSONALITY).unwrap();
+        let source = ArchetypeSource::local(&root, "main");
+        assert_eq!(source, ArchetypeSource::Directory(root.clone()));
+        let cache = ArchetypeCache::new(tmp.path().join("cache"));
+
+        let loaded = load_archetype(&source, Some(&cache), "heartbound").await.unwrap();
+        assert_eq!(loaded.files.master_system_prompt, "Warm.\n");
+        assert_eq!(loaded.files.personality_db().unwrap().traits.get("warmth"), Some(&0.9));
+        assert!(cache.versions(&source, "heartbound").is_empty());
+        assert!(load_archetype(&source, Some(&cache), "../etc").await.is_err());
+    }
+
+    #[test]
+    fn store_replaces_partial_versions_and_leaves_no_temp_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArchetypeCache::new(dir.path());
+        let source = ArchetypeSource::Directory(PathBuf::from("/archetypes"));
+        let files = ArchetypeFiles {
+            master_system_prompt: "You are Phoenix.\n".to_string(),
+            personality_json: PERSONALITY.to_string(),
+            commit_sha: Some("abc123".to_string()),
+        };
+
+        // A version left half-written by an interrupted writer.
+        let partial = cache.archetype_dir(&source, "default").join("abc123");
+        std::fs::create_dir_all(&partial).unwrap();
+        std::fs::write(partial.join(PROMPT_FILE), "You are").unwrap();
+        assert_eq!(cache.get(&source, "default", "abc123"), None);
+
+        cache.store(&source, "default", &files).unwrap();
+        cache.store(&source, "default", &files).unwrap();
+        assert_eq!(cache.latest(&source, "default"), Some(files));
+        assert_eq!(cache.versions(&source, "default"), vec!["abc123".to_string()]);
+        let leftovers = std::fs::read_dir(cache.archetype_dir(&source, "default"))
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn slug_keeps_alphanumeric_runs() {
+        assert_eq!(slug("dad/Phoenix-Archetypes@main"), "dad-phoenix-archetypes-main");
+        assert_eq!(slug("2026-10-19T12:00:00Z"), "2026-10-19t12-00-00z");
+        assert_eq!(slug("--"), "");
+    }
+}
@@ -1,16 +1,22 @@
 //! Centralized configuration manager for Phoenix AGI (PAGI).
 //!
 //! Loads configuration from:
-//! 1. GitHub Archetype repositories (master_system_prompt.txt, personality_db.json)
+//! 1. Archetype repositories (master_system_prompt.txt, personality_db.json) on GitHub or on
+//!    disk, cached locally by commit (see [`archetype`])
 //! 2. Local .env file (overrides archetype values)
 //! 3. Environment variables (highest priority)
 //!
 //! Provides AGIConfig struct with merged configuration values.
 
+pub mod archetype;
+pub mod text_diff;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use error_types::{ConfigError, PhoenixError};
 
+use archetype::{ArchetypeCache, ArchetypeSource};
+
 fn env_nonempty(key: &str) -> Option<String> {
     std::env::var(key)
         .ok()
@@ -54,6 +60,10 @@ pub struct AGIConfig {
     /// Archetype branch (default: main)
     pub archetype_branch: String,
 
+    /// Commit the loaded archetype was read at (`None` when not loaded or from a plain directory)
+    #[serde(default)]
+    pub archetype_commit: Option<String>,
+
     /// Additional environment variables as key-value pairs
     #[serde(default)]
     pub env_overrides: HashMap<String, String>,
@@ -86,7 +96,10 @@ pub struct FFMScores {
 }
 
 impl AGIConfig {
-    /// Load configuration from environment and GitHub archetype.
+    /// Load configuration from environment and the configured archetype source.
+    ///
+    /// The archetype is read through the local cache, so the last version loaded is used when
+    /// the source is unreachable.
     ///
     /// # Arguments
     /// * `archetype_name` - Name of the archetype to load (e.g., "default", "heartbound")
@@ -105,10 +118,10 @@ impl AGIConfig {
         // Load base config from environment
         let mut config = Self::load_from_env()?;
 
-        // Try to load archetype from GitHub
-        if let Ok(archetype_config) = Self::load_archetype_from_github(&archetype_name).await {
-            // Merge archetype config (base values)
-            config.merge_archetype(archetype_config)?;
+        // Load archetype (base values), falling back to the cache when offline
+        match Self::load_archetype(&archetype_name).await {
+            Ok(archetype_config) => config.merge_archetype(archetype_config)?,
+            Err(e) => tracing::warn!("Archetype '{archetype_name}' unavailable, using environment only: {e}"),
         }
 
         // Apply .env overrides (highest priority)
@@ -155,79 +168,34 @@ impl AGIConfig {
                 .unwrap_or_else(|| "phoenix-archetypes".to_string()),
             archetype_branch: env_nonempty("PHOENIX_ARCHETYPE_BRANCH")
                 .unwrap_or_else(|| "main".to_string()),
+            archetype_commit: None,
             env_overrides: HashMap::new(),
         })
     }
 
-    /// Load archetype configuration from GitHub repository.
+    /// Load archetype configuration from `PHOENIX_ARCHETYPE_SOURCE` or GitHub.
     ///
-    /// Downloads:
+    /// Reads:
     /// - master_system_prompt.txt
     /// - personality_db.json
     ///
     /// # Arguments
-    /// * `archetype_name` - Name of the archetype (subdirectory or file prefix)
+    /// * `archetype_name` - Name of the archetype (subdirectory of the archetype repository)
     ///
     /// # Returns
-    /// Partial config with archetype values, or error if download fails.
-    async fn load_archetype_from_github(
-        archetype_name: &str,
-    ) -> Result<AGIConfig, ConfigError> {
-        let github_pat = std::env::var("GITHUB_PAT")
-            .or_else(|_| std::env::var("GITHUB_TOKEN"))
-            .map_err(|_| ConfigError::MissingEnv("GITHUB_PAT".to_string()))?;
-
-        let owner = std::env::var("GITHUB_REPO_OWNER")
-            .or_else(|_| std::env::var("GITHUB_USERNAME"))
-            .unwrap_or_else(|_| "c04ch1337".to_string());
-
-        let repo = std::env::var("PHOENIX_ARCHETYPE_REPO")
-            .unwrap_or_else(|_| "phoenix-archetypes".to_string());
-
-        let branch = std::env::var("PHOENIX_ARCHETYPE_BRANCH")
-            .unwrap_or_else(|_| "main".to_string());
-
-        let client = reqwest::Client::new();
-
-        // Download master_system_prompt.txt
-        let prompt_url = format!(
-            "https://raw.githubusercontent.com/{owner}/{repo}/{branch}/{archetype_name}/master_system_prompt.txt"
-        );
-        let master_prompt = client
-            .get(&prompt_url)
-            .header("Authorization", format!("Bearer {github_pat}"))
-            .header("Accept", "application/vnd.github.raw")
-            .header("User-Agent", "phoenix-agi-config-manager")
-            .send()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to download prompt: {}", e)))?
-            .text()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to read prompt: {}", e)))?;
-
-        // Download personality_db.json
-        let personality_url = format!(
-            "https://raw.githubusercontent.com/{owner}/{repo}/{branch}/{archetype_name}/personality_db.json"
-        );
-        let personality_json = client
-            .get(&personality_url)
-            .header("Authorization", format!("Bearer {github_pat}"))
-            .header("Accept", "application/vnd.github.raw")
-            .header("User-Agent", "phoenix-agi-config-manager")
-            .send()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to download personality: {}", e)))?
-            .text()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to read personality: {}", e)))?;
-
-        let personality_db: PersonalityDatabase = serde_json::from_str(&personality_json)
-            .map_err(|e| ConfigError::ParseError(format!("Failed to parse personality_db.json: {}", e)))?;
+    /// Partial config with archetype values, or error if neither the source nor the cache has it.
+    async fn load_archetype(archetype_name: &str) -> Result<AGIConfig, ConfigError> {
+        let source = ArchetypeSource::from_env();
+        let loaded = archetype::load_archetype(&source, Some(&ArchetypeCache::from_env()), archetype_name).await?;
+        if let Some(reason) = &loaded.offline_reason {
+            tracing::info!("Using cached archetype '{archetype_name}' ({reason})");
+        }
 
         // Create base config from archetype
         let mut config = Self::load_from_env()?;
-        config.master_system_prompt = master_prompt;
-        config.personality_db = personality_db;
+        config.personality_db = loaded.files.personality_db()?;
+        config.master_system_prompt = loaded.files.master_system_prompt;
+        config.archetype_commit = loaded.files.commit_sha;
 
         Ok(config)
     }
@@ -248,6 +216,10 @@ impl AGIConfig {
             self.personality_db.ffm_scores = archetype.personality_db.ffm_scores;
         }
 
+        if archetype.archetype_commit.is_some() {
+            self.archetype_commit = archetype.archetype_commit;
+        }
+
         // Merge archetype settings
         for (key, value) in archetype.personality_db.archetype_settings {
             self.personality_db
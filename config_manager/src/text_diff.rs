@@ -0,0 +1,148 @@
+//! Line diffs between two versions of an archetype file.
+
+/// One line of a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+/// Lines added and removed between two versions of a file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineStats {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Line diff via the longest common subsequence (archetype files are small).
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(DiffLine::Same(a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(a[i]));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed(l)));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added(l)));
+    out
+}
+
+pub fn line_stats(old: &str, new: &str) -> LineStats {
+    let mut stats = LineStats::default();
+    for line in diff_lines(old, new) {
+        match line {
+            DiffLine::Added(_) => stats.added += 1,
+            DiffLine::Removed(_) => stats.removed += 1,
+            DiffLine::Same(_) => {}
+        }
+    }
+    stats
+}
+
+/// Unified diff (`---`/`+++` headers, `@@` hunks with `context` lines around each change).
+///
+/// Empty when the two versions have the same lines.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str, context: usize) -> String {
+    let lines = diff_lines(old, new);
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+        .map(|(idx, _)| idx)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // Group changes whose context windows touch into hunks of `lines` indices.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &idx in &changed {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    // Line numbers (0-based) in the old and new file before each entry of `lines`.
+    let mut positions = Vec::with_capacity(lines.len() + 1);
+    let (mut old_pos, mut new_pos) = (0usize, 0usize);
+    for line in &lines {
+        positions.push((old_pos, new_pos));
+        match line {
+            DiffLine::Same(_) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            DiffLine::Removed(_) => old_pos += 1,
+            DiffLine::Added(_) => new_pos += 1,
+        }
+    }
+
+    let mut out = format!("--- {old_label}\n+++ {new_label}\n");
+    for (start, end) in hunks {
+        let hunk = &lines[start..end];
+        let old_len = hunk.iter().filter(|l| !matches!(l, DiffLine::Added(_))).count();
+        let new_len = hunk.iter().filter(|l| !matches!(l, DiffLine::Removed(_))).count();
+        let (old_start, new_start) = positions[start];
+        // An empty side is numbered by the line it follows, as `diff -u` does.
+        let number = |pos: usize, len: usize| if len == 0 { pos } else { pos + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            number(old_start, old_len),
+            old_len,
+            number(new_start, new_len),
+            new_len
+        ));
+        for line in hunk {
+            match line {
+                DiffLine::Same(l) => out.push_str(&format!(" {l}\n")),
+                DiffLine::Removed(l) => out.push_str(&format!("-{l}\n")),
+                DiffLine::Added(l) => out.push_str(&format!("+{l}\n")),
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diff_groups_changes_into_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        assert_eq!(line_stats(old, new), LineStats { added: 2, removed: 1 });
+        assert_eq!(
+            unified_diff(old, new, "cached", "upstream", 1),
+            "--- cached\n+++ upstream\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -9,1 +9,2 @@\n i\n+j\n"
+        );
+        assert_eq!(unified_diff(old, old, "a", "b", 3), "");
+        assert_eq!(unified_diff("", "x\n", "a", "b", 3), "--- a\n+++ b\n@@ -0,0 +1,1 @@\n+x\n");
+    }
+}
@@ -6,29 +6,140 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::sync::Arc;
+use std::time::Duration;
+use testing_framework::repo::{ProjectKind, RepoTestConfig};
+use testing_framework::TestReport;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// How long a freshly started service may take to pass its readiness check.
+const DEFAULT_READINESS_TIMEOUT: Duration = Duration::from_secs(60);
+/// Delay between readiness probes while a service is starting.
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Timeout applied to a single TCP/HTTP probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Directories never scanned for nested build systems.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build", "out"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RepoMetadata {
     pub id: String,
     pub name: String,
     pub owner: String,
     pub url: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
     pub local_path: PathBuf,
+    /// Primary build system (the first one detected at the repository root).
     pub build_system: BuildSystem,
+    /// Every build system detected in the repository, including nested
+    /// projects such as an npm frontend inside a Cargo workspace.
+    #[serde(default)]
+    pub build_targets: Vec<BuildTarget>,
     pub build_status: BuildStatus,
     pub service_status: ServiceStatus,
     pub port: Option<u16>,
+    /// Readiness check used when starting the service. When unset, a TCP
+    /// check against the discovered port is used.
+    #[serde(default)]
+    pub health_check: Option<HealthCheck>,
     pub commands: Vec<String>,
     pub created_at: i64,
     pub last_built: Option<i64>,
     pub description: Option<String>,
+    /// Where the repository was imported from (absent for legacy metadata).
+    #[serde(default)]
+    pub source: Option<RepoSource>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Commit SHA or tag the checkout is pinned to.
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// Clone depth for shallow checkouts.
+    #[serde(default)]
+    pub depth: Option<u32>,
+    /// Commit currently checked out (`None` for non-git imports).
+    #[serde(default)]
+    pub commit: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a repository is imported from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RepoSource {
+    /// `https://github.com/{owner}/{repo}`
+    GitHub { owner: String, repo: String },
+    /// Any git remote: https, ssh, `git@host:path`, `file://`, self-hosted.
+    Git { url: String },
+    /// A directory on this machine. Git repositories (including bare ones)
+    /// are cloned; plain directories are copied.
+    Local {
+        #[cfg_attr(feature = "openapi", schema(value_type = String))]
+        path: PathBuf,
+    },
+}
+
+impl RepoSource {
+    /// Display URL recorded in `RepoMetadata::url`.
+    pub fn url(&self) -> String {
+        match self {
+            RepoSource::GitHub { owner, repo } => format!("https://github.com/{}/{}", owner, repo),
+            RepoSource::Git { url } => url.clone(),
+            RepoSource::Local { path } => path.display().to_string(),
+        }
+    }
+
+    /// URL handed to `git clone`. Shallow clones of local paths need the
+    /// `file://` form, since git ignores `--depth` for plain local paths.
+    fn clone_url(&self, shallow: bool) -> Result<String> {
+        match self {
+            RepoSource::Local { path } if shallow => {
+                let abs = std::fs::canonicalize(path)
+                    .with_context(|| format!("Failed to resolve {}", path.display()))?;
+                Ok(format!("file://{}", abs.display()))
+            }
+            RepoSource::Local { path } => path_arg(path),
+            other => Ok(other.url()),
+        }
+    }
+
+    fn owner_and_name(&self) -> (String, String) {
+        match self {
+            RepoSource::GitHub { owner, repo } => (owner.clone(), repo.clone()),
+            RepoSource::Git { url } => {
+                let trimmed = url.trim_end_matches('/').trim_end_matches(".git");
+                let mut parts = trimmed.rsplit(['/', ':']);
+                let name = parts.next().unwrap_or(trimmed).to_string();
+                let owner = parts.next().filter(|o| !o.is_empty()).unwrap_or("git").to_string();
+                (owner, name)
+            }
+            RepoSource::Local { path } => {
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().trim_end_matches(".git").to_string())
+                    .unwrap_or_else(|| "local".to_string());
+                ("local".to_string(), name)
+            }
+        }
+    }
+}
+
+/// Clone options for [`EcosystemManager::import_from`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportOptions {
+    pub branch: Option<String>,
+    /// Commit SHA or tag to check out after cloning.
+    pub revision: Option<String>,
+    /// Create a shallow clone with this many commits of history.
+    pub depth: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum BuildSystem {
     Cargo,
     Npm,
@@ -41,7 +152,30 @@ pub enum BuildSystem {
     Unknown,
 }
 
+/// A build system rooted at a directory inside the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BuildTarget {
+    pub build_system: BuildSystem,
+    /// Directory relative to the repository root (empty for the root itself).
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub relative_path: PathBuf,
+}
+
+/// How to decide that a started service is ready to accept traffic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum HealthCheck {
+    /// Succeeds once a TCP connection to `127.0.0.1:port` can be opened.
+    /// `None` uses the port discovered from the service output.
+    Tcp { port: Option<u16> },
+    /// Succeeds once `GET http://127.0.0.1:port{path}` returns 2xx/3xx.
+    /// `None` uses the port discovered from the service output.
+    Http { path: String, port: Option<u16> },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum BuildStatus {
     NotBuilt,
     Building,
@@ -50,6 +184,7 @@ pub enum BuildStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum ServiceStatus {
     Stopped,
     Starting,
@@ -58,10 +193,22 @@ pub enum ServiceStatus {
     Error(String),
 }
 
+struct RunningService {
+    child: tokio::process::Child,
+    build_system: BuildSystem,
+}
+
+type RepoMap = Arc<Mutex<HashMap<String, RepoMetadata>>>;
+type ProcessMap = Arc<Mutex<HashMap<String, RunningService>>>;
+
 pub struct EcosystemManager {
-    repos: Arc<Mutex<HashMap<String, RepoMetadata>>>,
+    repos: RepoMap,
     base_path: PathBuf,
-    processes: Arc<Mutex<HashMap<String, tokio::process::Child>>>,
+    processes: ProcessMap,
+    http: reqwest::Client,
+    readiness_timeout: Duration,
+    /// Canonical directories local imports may come from; empty refuses all local imports.
+    local_import_roots: Vec<PathBuf>,
 }
 
 impl EcosystemManager {
@@ -74,9 +221,63 @@ impl EcosystemManager {
             repos: Arc::new(Mutex::new(HashMap::new())),
             base_path: base,
             processes: Arc::new(Mutex::new(HashMap::new())),
+            http: reqwest::Client::builder()
+                .timeout(PROBE_TIMEOUT)
+                .build()
+                .context("Failed to create HTTP client")?,
+            readiness_timeout: DEFAULT_READINESS_TIMEOUT,
+            local_import_roots: Vec::new(),
         })
     }
 
+    /// Override how long `start_service` waits for a service to become ready.
+    pub fn with_readiness_timeout(mut self, timeout: Duration) -> Self {
+        self.readiness_timeout = timeout;
+        self
+    }
+
+    /// Allow local imports (`RepoSource::Local` and local git URLs) from under these
+    /// directories. Without any, local imports are refused. Missing roots are ignored.
+    pub fn with_local_import_roots<I, P>(mut self, roots: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.local_import_roots = roots
+            .into_iter()
+            .filter_map(|root| std::fs::canonicalize(root).ok())
+            .collect();
+        self
+    }
+
+    /// Refuse sources that could make git read option flags or reach outside
+    /// the allowed local roots.
+    fn check_source(&self, source: &RepoSource) -> Result<()> {
+        let local = match source {
+            RepoSource::GitHub { owner, repo } => {
+                check_git_arg("owner", owner)?;
+                check_git_arg("repo", repo)?;
+                None
+            }
+            RepoSource::Git { url } => {
+                check_git_arg("url", url)?;
+                local_url_path(url)
+            }
+            RepoSource::Local { path } => Some(path.clone()),
+        };
+        if let Some(path) = local {
+            let resolved = std::fs::canonicalize(&path)
+                .with_context(|| format!("Failed to resolve {}", path.display()))?;
+            if !self.local_import_roots.iter().any(|root| resolved.starts_with(root)) {
+                return Err(anyhow::anyhow!(
+                    "Local imports are only allowed from the configured import roots: {}",
+                    path.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Clone a GitHub repository
     pub async fn import_repo(
         &self,
@@ -84,47 +285,64 @@ impl EcosystemManager {
         repo: &str,
         branch: Option<&str>,
     ) -> Result<RepoMetadata> {
-        let url = format!("https://github.com/{}/{}", owner, repo);
-        let repo_id = Uuid::new_v4().to_string();
-        let local_path = self.base_path.join(&repo_id);
+        let source = RepoSource::GitHub {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        };
+        let options = ImportOptions {
+            branch: branch.map(str::to_string),
+            ..Default::default()
+        };
+        self.import_from(source, options).await
+    }
 
-        // Clone the repository
-        let mut git_cmd = Command::new("git");
-        git_cmd.arg("clone");
-        if let Some(branch) = branch {
-            git_cmd.args(["-b", branch]);
+    /// Import a repository from GitHub, any git remote, or a local directory.
+    pub async fn import_from(&self, source: RepoSource, options: ImportOptions) -> Result<RepoMetadata> {
+        self.check_source(&source)?;
+        if let Some(branch) = &options.branch {
+            check_git_arg("branch", branch)?;
+        }
+        if let Some(revision) = &options.revision {
+            check_git_arg("revision", revision)?;
         }
-        git_cmd.args([&url, local_path.to_str().unwrap()]);
 
-        let output = git_cmd.output().context("Failed to execute git clone")?;
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("Git clone failed: {}", error));
+        let repo_id = Uuid::new_v4().to_string();
+        let local_path = self.base_path.join(&repo_id);
+
+        if let Err(e) = Self::fetch_source(&source, &options, &local_path) {
+            std::fs::remove_dir_all(&local_path).ok();
+            return Err(e);
         }
 
-        // Detect build system
-        let build_system = Self::detect_build_system(&local_path).await;
+        // Detect build systems (root first, then nested projects)
+        let build_targets = Self::detect_build_targets(&local_path).await;
+        let build_system = Self::primary_build_system(&build_targets);
 
         // Discover available commands
-        let commands = Self::discover_commands(&local_path, &build_system).await;
+        let commands = Self::discover_commands(&local_path, &build_targets).await;
 
+        let (owner, name) = source.owner_and_name();
         let metadata = RepoMetadata {
             id: repo_id.clone(),
-            name: repo.to_string(),
-            owner: owner.to_string(),
-            url,
-            local_path,
+            name,
+            owner,
+            url: source.url(),
+            local_path: local_path.clone(),
             build_system,
+            build_targets,
             build_status: BuildStatus::NotBuilt,
             service_status: ServiceStatus::Stopped,
             port: None,
+            health_check: None,
             commands,
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64,
+            created_at: now_secs(),
             last_built: None,
             description: None,
+            source: Some(source),
+            branch: options.branch,
+            revision: options.revision,
+            depth: options.depth,
+            commit: head_commit(&local_path),
         };
 
         let mut repos = self.repos.lock().await;
@@ -133,66 +351,245 @@ impl EcosystemManager {
         Ok(metadata)
     }
 
-    /// Detect build system from repository files
-    async fn detect_build_system(path: &Path) -> BuildSystem {
-        let checks = vec![
-            ("Cargo.toml", BuildSystem::Cargo),
-            ("package.json", BuildSystem::Npm),
-            ("requirements.txt", BuildSystem::Pip),
-            ("setup.py", BuildSystem::Pip),
-            ("Makefile", BuildSystem::Make),
-            ("Dockerfile", BuildSystem::Docker),
-            ("pom.xml", BuildSystem::Maven),
-            ("build.gradle", BuildSystem::Gradle),
+    /// Populate `dest` from the source: clone git sources, copy plain directories.
+    fn fetch_source(source: &RepoSource, options: &ImportOptions, dest: &Path) -> Result<()> {
+        if let RepoSource::Local { path } = source {
+            if !path.is_dir() {
+                return Err(anyhow::anyhow!("Local path is not a directory: {}", path.display()));
+            }
+            if !is_git_repository(path) {
+                if options.branch.is_some() || options.revision.is_some() {
+                    return Err(anyhow::anyhow!(
+                        "Branch/revision requires a git repository: {}",
+                        path.display()
+                    ));
+                }
+                return copy_dir(path, dest);
+            }
+        }
+
+        let mut args = vec!["clone".to_string()];
+        if let Some(branch) = &options.branch {
+            args.extend(["-b".to_string(), branch.clone()]);
+        }
+        if let Some(depth) = options.depth {
+            args.extend(["--depth".to_string(), depth.to_string()]);
+            if options.branch.is_none() {
+                args.push("--no-single-branch".to_string());
+            }
+        }
+        args.push("--".to_string());
+        args.push(source.clone_url(options.depth.is_some())?);
+        args.push(path_arg(dest)?);
+        git(None, &args).context("Git clone failed")?;
+
+        if let Some(revision) = &options.revision {
+            checkout_revision(dest, revision, options.depth)?;
+        }
+        Ok(())
+    }
+
+    /// Fetch upstream changes for an imported repository.
+    ///
+    /// Tracking checkouts are fast-forwarded; pinned checkouts are re-fetched
+    /// and moved to the pinned revision; local non-git imports are re-copied.
+    pub async fn update_repo(&self, repo_id: &str) -> Result<RepoMetadata> {
+        let repo = self
+            .get_repo(repo_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
+        let path = repo.local_path.clone();
+        if let Some(source) = &repo.source {
+            self.check_source(source)?;
+        }
+
+        match (&repo.source, is_git_repository(&path)) {
+            (Some(RepoSource::Local { path: source }), false) => {
+                replace_with_copy(source, &path)?;
+            }
+            (_, false) => {
+                return Err(anyhow::anyhow!("Repository checkout is not a git working tree"));
+            }
+            (_, true) => match &repo.revision {
+                Some(revision) => {
+                    check_git_arg("revision", revision)?;
+                    let mut args = vec!["fetch".to_string(), "--tags".to_string()];
+                    if let Some(depth) = repo.depth {
+                        args.push(format!("--depth={}", depth));
+                    }
+                    args.push("origin".to_string());
+                    git(Some(&path), &args).context("Git fetch failed")?;
+                    checkout_revision(&path, revision, repo.depth)?;
+                }
+                None => {
+                    // No `--depth` here: re-shallowing would cut the new commits
+                    // off from the checked-out history and break the fast-forward.
+                    git(Some(&path), &["fetch", "origin"]).context("Git fetch failed")?;
+                    git(Some(&path), &["merge", "--ff-only", "@{upstream}"])
+                        .context("Fast-forward failed")?;
+                }
+            },
+        }
+
+        let build_targets = Self::detect_build_targets(&path).await;
+        let commands = Self::discover_commands(&path, &build_targets).await;
+        let commit = head_commit(&path);
+
+        let mut repos = self.repos.lock().await;
+        let entry = repos
+            .get_mut(repo_id)
+            .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
+        if commit.is_none() || entry.commit != commit {
+            entry.build_status = BuildStatus::NotBuilt;
+        }
+        entry.build_system = Self::primary_build_system(&build_targets);
+        entry.build_targets = build_targets;
+        entry.commands = commands;
+        entry.commit = commit;
+
+        Ok(entry.clone())
+    }
+
+    /// Detect build systems in a directory (no recursion)
+    fn detect_in_dir(dir: &Path) -> Vec<BuildSystem> {
+        let checks = [
+            (&["Cargo.toml"][..], BuildSystem::Cargo),
+            (&["package.json"][..], BuildSystem::Npm),
+            (&["requirements.txt", "setup.py", "pyproject.toml"][..], BuildSystem::Pip),
+            (&["Makefile", "makefile", "GNUmakefile"][..], BuildSystem::Make),
+            (&["Dockerfile"][..], BuildSystem::Docker),
+            (&["pom.xml"][..], BuildSystem::Maven),
+            (&["build.gradle", "build.gradle.kts"][..], BuildSystem::Gradle),
         ];
 
-        for (file, system) in checks {
-            if path.join(file).exists() {
-                return system;
+        checks
+            .into_iter()
+            .filter(|(files, _)| files.iter().any(|f| dir.join(f).exists()))
+            .map(|(_, system)| system)
+            .collect()
+    }
+
+    /// Detect every build system in the repository root and its immediate
+    /// subdirectories (e.g. a Cargo workspace with an npm `frontend/`).
+    pub async fn detect_build_targets(path: &Path) -> Vec<BuildTarget> {
+        let mut targets: Vec<BuildTarget> = Self::detect_in_dir(path)
+            .into_iter()
+            .map(|build_system| BuildTarget {
+                build_system,
+                relative_path: PathBuf::new(),
+            })
+            .collect();
+
+        let mut subdirs: Vec<PathBuf> = std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                        !name.starts_with('.') && !SKIPPED_DIRS.contains(&name)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        subdirs.sort();
+
+        for dir in subdirs {
+            let relative = dir.strip_prefix(path).unwrap_or(&dir).to_path_buf();
+            for build_system in Self::detect_in_dir(&dir) {
+                // Cargo workspace members are built by the root Cargo.toml.
+                if build_system == BuildSystem::Cargo
+                    && targets.iter().any(|t| {
+                        t.build_system == BuildSystem::Cargo && t.relative_path.as_os_str().is_empty()
+                    })
+                {
+                    continue;
+                }
+                targets.push(BuildTarget {
+                    build_system,
+                    relative_path: relative.clone(),
+                });
             }
         }
 
-        BuildSystem::Unknown
+        targets
+    }
+
+    /// Pick the build system used for single-target operations.
+    fn primary_build_system(targets: &[BuildTarget]) -> BuildSystem {
+        targets
+            .first()
+            .map(|t| t.build_system.clone())
+            .unwrap_or(BuildSystem::Unknown)
     }
 
-    /// Discover available commands from build system
-    async fn discover_commands(path: &Path, build_system: &BuildSystem) -> Vec<String> {
+    /// Discover available commands from the detected build systems
+    async fn discover_commands(path: &Path, targets: &[BuildTarget]) -> Vec<String> {
         let mut commands = vec!["build".to_string(), "start".to_string(), "stop".to_string()];
+        let mut push = |cmd: &str| {
+            if !commands.iter().any(|c| c == cmd) {
+                commands.push(cmd.to_string());
+            }
+        };
 
-        match build_system {
-            BuildSystem::Cargo => {
-                // Check for binaries in Cargo.toml
-                if let Ok(contents) = std::fs::read_to_string(path.join("Cargo.toml")) {
-                    if contents.contains("[[bin]]") {
-                        commands.push("run".to_string());
+        for target in targets {
+            let dir = path.join(&target.relative_path);
+            match target.build_system {
+                BuildSystem::Cargo => {
+                    // Check for binaries in Cargo.toml
+                    if let Ok(contents) = std::fs::read_to_string(dir.join("Cargo.toml")) {
+                        if contents.contains("[[bin]]") || dir.join("src/main.rs").exists() {
+                            push("run");
+                        }
                     }
+                    push("test");
                 }
-            }
-            BuildSystem::Npm => {
-                // Read package.json scripts
-                if let Ok(contents) = std::fs::read_to_string(path.join("package.json")) {
-                    if let Ok(json) = serde_json::from_str::<JsonValue>(&contents) {
-                        if let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) {
-                            for key in scripts.keys() {
-                                if !commands.contains(&key.clone()) {
-                                    commands.push(key.clone());
+                BuildSystem::Npm => {
+                    // Read package.json scripts
+                    if let Ok(contents) = std::fs::read_to_string(dir.join("package.json")) {
+                        if let Ok(json) = serde_json::from_str::<JsonValue>(&contents) {
+                            if let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) {
+                                for key in scripts.keys() {
+                                    push(key);
                                 }
                             }
                         }
                     }
                 }
+                BuildSystem::Pip => {
+                    push("install");
+                    push("test");
+                }
+                BuildSystem::Make => {
+                    for name in ["Makefile", "makefile", "GNUmakefile"] {
+                        if let Ok(contents) = std::fs::read_to_string(dir.join(name)) {
+                            for target in parse_make_targets(&contents) {
+                                push(&target);
+                            }
+                            break;
+                        }
+                    }
+                }
+                BuildSystem::Maven => {
+                    for cmd in ["compile", "test", "package", "clean"] {
+                        push(cmd);
+                    }
+                }
+                BuildSystem::Gradle => {
+                    for cmd in ["test", "run", "clean"] {
+                        push(cmd);
+                    }
+                }
+                BuildSystem::Docker => push("run"),
+                _ => {}
             }
-            BuildSystem::Pip => {
-                commands.push("install".to_string());
-                commands.push("test".to_string());
-            }
-            _ => {}
         }
 
         commands
     }
 
-    /// Build a repository
+    /// Build a repository (every detected build target, in order)
     pub async fn build_repo(&self, repo_id: &str) -> Result<String> {
         let mut repos = self.repos.lock().await;
         let repo = repos
@@ -200,98 +597,231 @@ impl EcosystemManager {
             .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
 
         repo.build_status = BuildStatus::Building;
-        let build_system = repo.build_system.clone();
+        let targets = Self::targets_or_primary(repo);
         let path = repo.local_path.clone();
         drop(repos);
 
-        let build_output = match build_system {
+        let mut build_output = String::new();
+        let mut failure = None;
+        for target in &targets {
+            let dir = path.join(&target.relative_path);
+            let result = Self::build_command(repo_id, &target.build_system, &dir)
+                .and_then(|mut cmd| {
+                    cmd.output()
+                        .with_context(|| format!("Failed to execute {:?} build", target.build_system))
+                });
+
+            if targets.len() > 1 {
+                build_output.push_str(&format!(
+                    "==> {:?} ({})\n",
+                    target.build_system,
+                    display_relative(&target.relative_path)
+                ));
+            }
+
+            match result {
+                Ok(output) => {
+                    build_output.push_str(&String::from_utf8_lossy(&output.stdout));
+                    if !output.status.success() {
+                        failure = Some(format!(
+                            "{:?} build failed: {}",
+                            target.build_system,
+                            String::from_utf8_lossy(&output.stderr)
+                        ));
+                        break;
+                    }
+                }
+                Err(e) => {
+                    failure = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        let mut repos = self.repos.lock().await;
+        let repo = repos
+            .get_mut(repo_id)
+            .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
+        if let Some(error) = failure {
+            repo.build_status = BuildStatus::BuildFailed(error.clone());
+            return Err(anyhow::anyhow!(error));
+        }
+        repo.build_status = BuildStatus::Built;
+        repo.last_built = Some(now_secs());
+
+        Ok(build_output)
+    }
+
+    /// Targets recorded for a repo, falling back to the primary build system
+    /// for metadata created before multi-target detection existed.
+    fn targets_or_primary(repo: &RepoMetadata) -> Vec<BuildTarget> {
+        if repo.build_targets.is_empty() {
+            vec![BuildTarget {
+                build_system: repo.build_system.clone(),
+                relative_path: PathBuf::new(),
+            }]
+        } else {
+            repo.build_targets.clone()
+        }
+    }
+
+    fn build_command(repo_id: &str, build_system: &BuildSystem, dir: &Path) -> Result<Command> {
+        let mut cmd = match build_system {
             BuildSystem::Cargo => {
-                let output = Command::new("cargo")
-                    .args(["build", "--release"])
-                    .current_dir(&path)
-                    .output()
-                    .context("Failed to execute cargo build")?;
-                String::from_utf8_lossy(&output.stdout).to_string()
+                let mut cmd = Command::new("cargo");
+                cmd.args(["build", "--release"]);
+                cmd
             }
             BuildSystem::Npm => {
-                let output = Command::new("npm")
-                    .args(["install"])
-                    .current_dir(&path)
-                    .output()
-                    .context("Failed to execute npm install")?;
-                String::from_utf8_lossy(&output.stdout).to_string()
+                let mut cmd = Command::new("npm");
+                cmd.arg("install");
+                cmd
             }
-            BuildSystem::Pip => {
-                let output = Command::new("pip")
-                    .args(["install", "-e", "."])
-                    .current_dir(&path)
-                    .output()
-                    .context("Failed to execute pip install")?;
-                String::from_utf8_lossy(&output.stdout).to_string()
+            BuildSystem::Pip => pip_install_command(dir),
+            BuildSystem::Make => Command::new("make"),
+            BuildSystem::Docker => {
+                let mut cmd = Command::new("docker");
+                cmd.args(["build", "-t", repo_id, "."]);
+                cmd
             }
-            BuildSystem::Make => {
-                let output = Command::new("make")
-                    .current_dir(&path)
-                    .output()
-                    .context("Failed to execute make")?;
-                String::from_utf8_lossy(&output.stdout).to_string()
+            BuildSystem::Maven => {
+                let mut cmd = Command::new("mvn");
+                cmd.args(["-B", "package", "-DskipTests"]);
+                cmd
             }
-            BuildSystem::Docker => {
-                let output = Command::new("docker")
-                    .args(["build", "-t", repo_id, "."])
-                    .current_dir(&path)
-                    .output()
-                    .context("Failed to execute docker build")?;
-                String::from_utf8_lossy(&output.stdout).to_string()
+            BuildSystem::Gradle => {
+                let mut cmd = gradle_command(dir);
+                cmd.arg("build");
+                cmd
             }
             _ => return Err(anyhow::anyhow!("Unsupported build system")),
         };
+        cmd.current_dir(dir);
+        Ok(cmd)
+    }
 
+    /// Configure the readiness check used by `start_service`
+    pub async fn set_health_check(&self, repo_id: &str, check: Option<HealthCheck>) -> Result<()> {
         let mut repos = self.repos.lock().await;
-        let repo = repos.get_mut(repo_id).unwrap();
-        repo.build_status = BuildStatus::Built;
-        repo.last_built = Some(
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64,
-        );
-
-        Ok(build_output)
+        let repo = repos
+            .get_mut(repo_id)
+            .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
+        repo.health_check = check;
+        Ok(())
     }
 
-    /// Start a service from a repository
+    /// Start a service from a repository using its primary build system.
+    ///
+    /// The service is left in `Starting` until its readiness check passes;
+    /// use [`EcosystemManager::wait_until_ready`] to block on the outcome.
     pub async fn start_service(&self, repo_id: &str, command: Option<&str>) -> Result<String> {
+        self.start_service_with(repo_id, None, command).await
+    }
+
+    /// Start the service belonging to a specific build system of the repository
+    /// (for example the npm frontend of a Cargo workspace).
+    pub async fn start_target_service(
+        &self,
+        repo_id: &str,
+        build_system: &BuildSystem,
+        command: Option<&str>,
+    ) -> Result<String> {
+        self.start_service_with(repo_id, Some(build_system), command).await
+    }
+
+    async fn start_service_with(
+        &self,
+        repo_id: &str,
+        build_system: Option<&BuildSystem>,
+        command: Option<&str>,
+    ) -> Result<String> {
         let mut repos = self.repos.lock().await;
         let repo = repos
             .get_mut(repo_id)
             .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
 
-        if matches!(repo.service_status, ServiceStatus::Running) {
+        if matches!(repo.service_status, ServiceStatus::Running | ServiceStatus::Starting) {
             return Err(anyhow::anyhow!("Service is already running"));
         }
 
+        let target = Self::select_target(repo, build_system)?;
+        let path = repo.local_path.join(&target.relative_path);
+        let port = repo.port;
         repo.service_status = ServiceStatus::Starting;
-        let build_system = repo.build_system.clone();
-        let path = repo.local_path.clone();
         drop(repos);
 
-        let process = match build_system {
+        let spawned = Self::service_command(repo_id, &target.build_system, &path, command, port)
+            .and_then(|mut cmd| {
+                cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+                cmd.spawn()
+                    .with_context(|| format!("Failed to start {:?} service", target.build_system))
+            });
+
+        let mut process = match spawned {
+            Ok(process) => process,
+            Err(e) => {
+                self.set_service_status(repo_id, ServiceStatus::Error(e.to_string())).await;
+                return Err(e);
+            }
+        };
+
+        if let Some(stdout) = process.stdout.take() {
+            watch_output(stdout, repo_id.to_string(), self.repos.clone());
+        }
+        if let Some(stderr) = process.stderr.take() {
+            watch_output(stderr, repo_id.to_string(), self.repos.clone());
+        }
+
+        let mut processes = self.processes.lock().await;
+        processes.insert(
+            repo_id.to_string(),
+            RunningService {
+                child: process,
+                build_system: target.build_system.clone(),
+            },
+        );
+        drop(processes);
+
+        tokio::spawn(monitor_readiness(
+            self.repos.clone(),
+            self.processes.clone(),
+            self.http.clone(),
+            repo_id.to_string(),
+            self.readiness_timeout,
+        ));
+
+        Ok(format!("Service {} starting", repo_id))
+    }
+
+    fn select_target(repo: &RepoMetadata, build_system: Option<&BuildSystem>) -> Result<BuildTarget> {
+        let targets = Self::targets_or_primary(repo);
+        match build_system {
+            None => Ok(targets[0].clone()),
+            Some(wanted) => targets
+                .into_iter()
+                .find(|t| &t.build_system == wanted)
+                .ok_or_else(|| anyhow::anyhow!("Repository has no {:?} build target", wanted)),
+        }
+    }
+
+    fn service_command(
+        repo_id: &str,
+        build_system: &BuildSystem,
+        path: &Path,
+        command: Option<&str>,
+        port: Option<u16>,
+    ) -> Result<tokio::process::Command> {
+        let mut cmd = match build_system {
             BuildSystem::Cargo => {
                 let mut cmd = tokio::process::Command::new("cargo");
                 cmd.args(["run", "--release"]);
-                cmd.current_dir(&path);
-                cmd.spawn()
-                    .context("Failed to start cargo run")?
+                cmd
             }
             BuildSystem::Npm => {
-                let cmd_name = command.unwrap_or("start");
                 let mut cmd = tokio::process::Command::new("npm");
                 cmd.arg("run");
-                cmd.arg(cmd_name);
-                cmd.current_dir(&path);
-                cmd.spawn()
-                    .context("Failed to start npm run")?
+                cmd.arg(command.unwrap_or("start"));
+                cmd
             }
             BuildSystem::Pip => {
                 // Try to find main.py or __main__.py
@@ -304,38 +834,117 @@ impl EcosystemManager {
                 };
                 let mut cmd = tokio::process::Command::new("python");
                 cmd.arg(main_file);
-                cmd.current_dir(&path);
-                cmd.spawn()
-                    .context("Failed to start python service")?
+                cmd
+            }
+            BuildSystem::Make => {
+                let mut cmd = tokio::process::Command::new("make");
+                cmd.arg(command.unwrap_or("run"));
+                cmd
+            }
+            BuildSystem::Maven => {
+                let mut cmd = tokio::process::Command::new("mvn");
+                cmd.arg(command.unwrap_or("exec:java"));
+                cmd
+            }
+            BuildSystem::Gradle => {
+                let mut cmd = tokio::process::Command::from(gradle_command(path));
+                cmd.arg(command.unwrap_or("run"));
+                cmd
+            }
+            BuildSystem::Docker => {
+                let mut cmd = tokio::process::Command::new("docker");
+                cmd.args(["run", "--rm", "--name", &docker_container_name(repo_id)]);
+                if let Some(port) = port {
+                    cmd.args(["-p", &format!("{port}:{port}")]);
+                }
+                cmd.arg(repo_id);
+                cmd
             }
             _ => return Err(anyhow::anyhow!("Unsupported build system for service")),
         };
+        if let Some(port) = port {
+            cmd.env("PORT", port.to_string());
+        }
+        cmd.current_dir(path);
+        Ok(cmd)
+    }
 
-        let mut processes = self.processes.lock().await;
-        processes.insert(repo_id.to_string(), process);
-
+    async fn set_service_status(&self, repo_id: &str, status: ServiceStatus) {
         let mut repos = self.repos.lock().await;
-        let repo = repos.get_mut(repo_id).unwrap();
-        repo.service_status = ServiceStatus::Running;
+        if let Some(repo) = repos.get_mut(repo_id) {
+            repo.service_status = status;
+        }
+    }
+
+    /// Wait until a starting service has passed or failed its readiness check.
+    pub async fn wait_until_ready(&self, repo_id: &str) -> Result<ServiceStatus> {
+        let deadline = tokio::time::Instant::now() + self.readiness_timeout + READINESS_POLL_INTERVAL * 4;
+        loop {
+            let status = self
+                .get_repo(repo_id)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?
+                .service_status;
+            match status {
+                ServiceStatus::Starting if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+                }
+                ServiceStatus::Error(e) => return Err(anyhow::anyhow!("Service failed: {}", e)),
+                other => return Ok(other),
+            }
+        }
+    }
 
-        Ok(format!("Service {} started", repo_id))
+    /// Probe a service now and update its `ServiceStatus` accordingly.
+    pub async fn check_service(&self, repo_id: &str) -> Result<ServiceStatus> {
+        if let Some(exit) = reap_exited(&self.processes, repo_id).await {
+            let status = ServiceStatus::Error(exit);
+            self.set_service_status(repo_id, status.clone()).await;
+            return Ok(status);
+        }
+
+        let repo = self
+            .get_repo(repo_id)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
+        if !matches!(repo.service_status, ServiceStatus::Running | ServiceStatus::Error(_)) {
+            return Ok(repo.service_status);
+        }
+
+        let status = match effective_check(&repo) {
+            Some((check, port)) => {
+                if probe(&self.http, &check, port).await {
+                    ServiceStatus::Running
+                } else {
+                    ServiceStatus::Error(format!("Health check failed: {}", describe_check(&check, port)))
+                }
+            }
+            None => repo.service_status,
+        };
+        self.set_service_status(repo_id, status.clone()).await;
+        Ok(status)
     }
 
     /// Stop a service
     pub async fn stop_service(&self, repo_id: &str) -> Result<String> {
         let mut processes = self.processes.lock().await;
-        if let Some(mut process) = processes.remove(repo_id) {
-            process.kill().await.ok();
+        if let Some(mut service) = processes.remove(repo_id) {
+            drop(processes);
+            self.set_service_status(repo_id, ServiceStatus::Stopping).await;
+            if service.build_system == BuildSystem::Docker {
+                // Killing `docker run` does not stop the container itself.
+                Command::new("docker")
+                    .args(["stop", &docker_container_name(repo_id)])
+                    .output()
+                    .ok();
+            }
+            service.child.kill().await.ok();
         }
 
-        let mut repos = self.repos.lock().await;
-        if let Some(repo) = repos.get_mut(repo_id) {
-            repo.service_status = ServiceStatus::Stopped;
-        }
+        self.set_service_status(repo_id, ServiceStatus::Stopped).await;
 
         Ok(format!("Service {} stopped", repo_id))
     }
-
     /// List all repositories
     pub async fn list_repos(&self) -> Vec<RepoMetadata> {
         let repos = self.repos.lock().await;
@@ -362,39 +971,133 @@ impl EcosystemManager {
         Ok(())
     }
 
-    /// Execute a custom command on a repository
+    /// Execute a custom command on a repository using its primary build system
     pub async fn execute_command(
         &self,
         repo_id: &str,
         command: &str,
         args: Vec<String>,
     ) -> Result<String> {
+        self.execute_target_command(repo_id, None, command, args).await
+    }
+
+    /// Run the test suite of every Cargo, npm and Python target in a repository,
+    /// with per-test results and a deadline shared by all targets.
+    pub async fn test_repo(&self, repo_id: &str, config: RepoTestConfig) -> Result<TestReport> {
         let repos = self.repos.lock().await;
         let repo = repos
             .get(repo_id)
             .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
-        let path = repo.local_path.clone();
-        let build_system = repo.build_system.clone();
+        let targets: Vec<(PathBuf, ProjectKind)> = Self::targets_or_primary(repo)
+            .into_iter()
+            .filter_map(|target| {
+                let kind = match target.build_system {
+                    BuildSystem::Cargo => ProjectKind::Cargo,
+                    BuildSystem::Npm => ProjectKind::Npm,
+                    BuildSystem::Pip => ProjectKind::Pytest,
+                    _ => return None,
+                };
+                Some((repo.local_path.join(&target.relative_path), kind))
+            })
+            .collect();
         drop(repos);
+        if targets.is_empty() {
+            return Err(anyhow::anyhow!("Repository {} has no testable build target", repo_id));
+        }
 
-        let output = match build_system {
+        tokio::task::spawn_blocking(move || {
+            let deadline = std::time::Instant::now() + config.timeout;
+            let mut results = Vec::new();
+            let mut scores = Vec::new();
+            let mut passed = true;
+            for (dir, kind) in targets {
+                let config = RepoTestConfig {
+                    kind: Some(kind),
+                    timeout: deadline.saturating_duration_since(std::time::Instant::now()),
+                    ..config.clone()
+                };
+                let report = testing_framework::repo::run_repo_tests(&dir, &config)
+                    .with_context(|| format!("Failed to test {}", dir.display()))?;
+                passed &= report.passed;
+                scores.push(report.score);
+                results.extend(report.results);
+            }
+            Ok(TestReport {
+                passed,
+                score: scores.iter().sum::<f32>() / scores.len() as f32,
+                results,
+            })
+        })
+        .await?
+    }
+
+    /// Execute a custom command against one build system of a repository
+    /// (`None` selects the primary build system).
+    pub async fn execute_target_command(
+        &self,
+        repo_id: &str,
+        build_system: Option<&BuildSystem>,
+        command: &str,
+        args: Vec<String>,
+    ) -> Result<String> {
+        let repos = self.repos.lock().await;
+        let repo = repos
+            .get(repo_id)
+            .ok_or_else(|| anyhow::anyhow!("Repository not found: {}", repo_id))?;
+        let target = Self::select_target(repo, build_system)?;
+        let path = repo.local_path.join(&target.relative_path);
+        drop(repos);
+
+        let mut cmd = match target.build_system {
             BuildSystem::Cargo => {
                 let mut cmd = Command::new("cargo");
                 cmd.arg(command);
-                cmd.args(&args);
-                cmd.current_dir(&path);
-                cmd.output()?
+                cmd
             }
             BuildSystem::Npm => {
                 let mut cmd = Command::new("npm");
                 cmd.arg("run");
                 cmd.arg(command);
-                cmd.args(&args);
-                cmd.current_dir(&path);
-                cmd.output()?
+                cmd
+            }
+            BuildSystem::Pip => match command {
+                "install" => pip_install_command(&path),
+                "test" => {
+                    let mut cmd = Command::new("python");
+                    cmd.args(["-m", "pytest"]);
+                    cmd
+                }
+                module => {
+                    let mut cmd = Command::new("python");
+                    cmd.args(["-m", module]);
+                    cmd
+                }
+            },
+            BuildSystem::Make => {
+                let mut cmd = Command::new("make");
+                cmd.arg(command);
+                cmd
+            }
+            BuildSystem::Maven => {
+                let mut cmd = Command::new("mvn");
+                cmd.args(["-B", command]);
+                cmd
+            }
+            BuildSystem::Gradle => {
+                let mut cmd = gradle_command(&path);
+                cmd.arg(command);
+                cmd
+            }
+            BuildSystem::Docker => {
+                let mut cmd = Command::new("docker");
+                cmd.arg(command);
+                cmd
             }
             _ => return Err(anyhow::anyhow!("Command execution not supported for this build system")),
         };
+        cmd.args(&args);
+        cmd.current_dir(&path);
+        let output = cmd.output()?;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
@@ -404,3 +1107,716 @@ impl EcosystemManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 }
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Run git, returning stdout or an error carrying stderr.
+fn git<S: AsRef<std::ffi::OsStr>>(dir: Option<&Path>, args: &[S]) -> Result<String> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    cmd.args(args);
+    let output = cmd.output().context("Failed to execute git")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("{}", error.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Reject values git would parse as an option (e.g. `--upload-pack=<cmd>`).
+fn check_git_arg(what: &str, value: &str) -> Result<()> {
+    if value.is_empty() || value.starts_with('-') {
+        return Err(anyhow::anyhow!("Invalid {}: {:?}", what, value));
+    }
+    Ok(())
+}
+
+/// The filesystem path a git URL refers to: `file://` URLs and plain paths,
+/// but not `scheme://` or scp-style `host:path` remotes.
+fn local_url_path(url: &str) -> Option<PathBuf> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+    if url.contains("://") {
+        return None;
+    }
+    match (url.find(':'), url.find('/')) {
+        (Some(colon), Some(slash)) if colon < slash => None,
+        (Some(_), None) => None,
+        _ => Some(PathBuf::from(url)),
+    }
+}
+
+fn path_arg(path: &Path) -> Result<String> {
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Path is not valid UTF-8: {}", path.display()))
+}
+
+/// True for working trees and bare repositories.
+fn is_git_repository(path: &Path) -> bool {
+    path.join(".git").exists()
+        || git(Some(path), &["rev-parse", "--is-bare-repository"])
+            .map(|out| out == "true")
+            .unwrap_or(false)
+}
+
+fn head_commit(path: &Path) -> Option<String> {
+    if !path.join(".git").exists() {
+        return None;
+    }
+    git(Some(path), &["rev-parse", "HEAD"]).ok()
+}
+
+/// Detach HEAD at `revision`, fetching it explicitly when a shallow clone
+/// does not contain it yet.
+fn checkout_revision(dir: &Path, revision: &str, depth: Option<u32>) -> Result<()> {
+    check_git_arg("revision", revision)?;
+    if git(Some(dir), &["checkout", "--detach", revision, "--"]).is_ok() {
+        return Ok(());
+    }
+    let mut args = vec!["fetch".to_string()];
+    if let Some(depth) = depth {
+        args.push(format!("--depth={}", depth));
+    }
+    args.extend(["--".to_string(), "origin".to_string(), revision.to_string()]);
+    git(Some(dir), &args).with_context(|| format!("Failed to fetch revision {}", revision))?;
+    git(Some(dir), &["checkout", "--detach", "FETCH_HEAD"])
+        .with_context(|| format!("Failed to check out revision {}", revision))?;
+    Ok(())
+}
+
+/// Re-copy `from` over `to` without losing `to` if the copy fails: the copy is
+/// made next to it and renamed into place.
+fn replace_with_copy(from: &Path, to: &Path) -> Result<()> {
+    let suffix = Uuid::new_v4();
+    let staged = to.with_extension(format!("update-{}", suffix));
+    if let Err(e) = copy_dir(from, &staged) {
+        std::fs::remove_dir_all(&staged).ok();
+        return Err(e);
+    }
+    let old = to.with_extension(format!("old-{}", suffix));
+    let had_old = to.exists();
+    if had_old {
+        std::fs::rename(to, &old).with_context(|| format!("Failed to move {}", to.display()))?;
+    }
+    if let Err(e) = std::fs::rename(&staged, to) {
+        if had_old {
+            std::fs::rename(&old, to).ok();
+        }
+        std::fs::remove_dir_all(&staged).ok();
+        return Err(e).with_context(|| format!("Failed to replace {}", to.display()));
+    }
+    if had_old {
+        std::fs::remove_dir_all(&old).ok();
+    }
+    Ok(())
+}
+
+/// Recursively copy a directory, skipping VCS metadata and build output.
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to).with_context(|| format!("Failed to create {}", to.display()))?;
+    for entry in std::fs::read_dir(from).with_context(|| format!("Failed to read {}", from.display()))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if name_str == ".git" || SKIPPED_DIRS.contains(&name_str.as_ref()) {
+                continue;
+            }
+            copy_dir(&entry.path(), &to.join(&name))?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), to.join(&name))
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn display_relative(path: &Path) -> String {
+    if path.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn docker_container_name(repo_id: &str) -> String {
+    format!("phoenix-{}", repo_id)
+}
+
+/// Prefer the project's Gradle wrapper when it ships one.
+fn gradle_command(dir: &Path) -> Command {
+    let wrapper = if cfg!(windows) { "gradlew.bat" } else { "gradlew" };
+    if dir.join(wrapper).exists() {
+        Command::new(dir.join(wrapper))
+    } else {
+        Command::new("gradle")
+    }
+}
+
+/// `pip install -e .` for installable projects, `pip install -r requirements.txt` otherwise.
+fn pip_install_command(dir: &Path) -> Command {
+    let mut cmd = Command::new("pip");
+    if dir.join("setup.py").exists() || dir.join("pyproject.toml").exists() {
+        cmd.args(["install", "-e", "."]);
+    } else {
+        cmd.args(["install", "-r", "requirements.txt"]);
+    }
+    cmd
+}
+
+/// Extract explicit targets from a Makefile (`name:` lines, no variables or
+/// special/pattern targets).
+fn parse_make_targets(contents: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    for line in contents.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((head, rest)) = line.split_once(':') else {
+            continue;
+        };
+        // `VAR := value` / `VAR ::= value`
+        if rest.starts_with('=') || rest.starts_with(":=") || head.contains('=') {
+            continue;
+        }
+        for name in head.split_whitespace() {
+            if !name.contains(['%', '$']) && !targets.iter().any(|t| t == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+/// Find a listening port announced in a line of service output, e.g.
+/// `Listening on http://127.0.0.1:8080` or `Server started on port 3000`.
+pub fn discover_port(line: &str) -> Option<u16> {
+    let lower = line.to_ascii_lowercase();
+
+    for host in ["localhost:", "127.0.0.1:", "0.0.0.0:", "[::]:", "[::1]:"] {
+        let mut rest = lower.as_str();
+        while let Some(idx) = rest.find(host) {
+            rest = &rest[idx + host.len()..];
+            if let Some(port) = leading_port(rest) {
+                return Some(port);
+            }
+        }
+    }
+
+    // Any other URL: `scheme://host:port`.
+    let mut rest = lower.as_str();
+    while let Some(idx) = rest.find("://") {
+        rest = &rest[idx + "://".len()..];
+        let authority = rest.split(['/', ' ', '\t']).next().unwrap_or("");
+        if let Some((_, port)) = authority.rsplit_once(':') {
+            if let Some(port) = leading_port(port) {
+                return Some(port);
+            }
+        }
+    }
+
+    // The word `port` (not `report`, `export`, `transport`, `ports`).
+    for (idx, _) in lower.match_indices("port") {
+        let before = lower[..idx].chars().next_back();
+        let after = &lower[idx + "port".len()..];
+        if before.is_some_and(is_word_char) || after.chars().next().is_some_and(is_word_char) {
+            continue;
+        }
+        let candidate = after.trim_start_matches([' ', ':', '=', '\t']);
+        if let Some(port) = leading_port(candidate) {
+            return Some(port);
+        }
+    }
+
+    None
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn leading_port(s: &str) -> Option<u16> {
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    match digits.parse::<u16>() {
+        Ok(port) if port > 0 => Some(port),
+        _ => None,
+    }
+}
+
+/// Log service output and record the first port it announces.
+fn watch_output<R>(reader: R, repo_id: String, repos: RepoMap)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        let mut discovered = false;
+        while let Ok(Some(line)) = lines.next_line().await {
+            debug!(repo_id = %repo_id, "{}", line);
+            if discovered {
+                continue;
+            }
+            if let Some(port) = discover_port(&line) {
+                discovered = true;
+                let mut repos = repos.lock().await;
+                if let Some(repo) = repos.get_mut(&repo_id) {
+                    info!(repo_id = %repo_id, port, "Discovered service port");
+                    repo.port = Some(port);
+                }
+            }
+        }
+    });
+}
+
+/// The check to run for a repo plus the port it resolves to.
+fn effective_check(repo: &RepoMetadata) -> Option<(HealthCheck, u16)> {
+    let check = repo
+        .health_check
+        .clone()
+        .unwrap_or(HealthCheck::Tcp { port: None });
+    let explicit = match &check {
+        HealthCheck::Tcp { port } | HealthCheck::Http { port, .. } => *port,
+    };
+    explicit.or(repo.port).map(|port| (check, port))
+}
+
+fn describe_check(check: &HealthCheck, port: u16) -> String {
+    match check {
+        HealthCheck::Tcp { .. } => format!("tcp://127.0.0.1:{}", port),
+        HealthCheck::Http { path, .. } => format!("http://127.0.0.1:{}{}", port, path),
+    }
+}
+
+async fn probe(http: &reqwest::Client, check: &HealthCheck, port: u16) -> bool {
+    match check {
+        HealthCheck::Tcp { .. } => matches!(
+            tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(("127.0.0.1", port))).await,
+            Ok(Ok(_))
+        ),
+        HealthCheck::Http { .. } => match http.get(describe_check(check, port)).send().await {
+            Ok(resp) => resp.status().is_success() || resp.status().is_redirection(),
+            Err(_) => false,
+        },
+    }
+}
+
+/// If the service process has exited, remove it and describe the exit.
+async fn reap_exited(processes: &ProcessMap, repo_id: &str) -> Option<String> {
+    let mut processes = processes.lock().await;
+    let service = processes.get_mut(repo_id)?;
+    match service.child.try_wait() {
+        Ok(Some(status)) => {
+            processes.remove(repo_id);
+            Some(format!("Process exited: {}", status))
+        }
+        Ok(None) => None,
+        Err(e) => Some(format!("Failed to poll process: {}", e)),
+    }
+}
+
+/// Poll a starting service until its readiness check passes, the process
+/// exits, or the timeout elapses, updating `ServiceStatus` as it goes.
+async fn monitor_readiness(
+    repos: RepoMap,
+    processes: ProcessMap,
+    http: reqwest::Client,
+    repo_id: String,
+    timeout: Duration,
+) {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(exit) = reap_exited(&processes, &repo_id).await {
+            warn!(repo_id = %repo_id, "{}", exit);
+            set_status_if_starting(&repos, &repo_id, ServiceStatus::Error(exit)).await;
+            return;
+        }
+
+        let Some(repo) = repos.lock().await.get(&repo_id).cloned() else {
+            return;
+        };
+        if !matches!(repo.service_status, ServiceStatus::Starting) {
+            // Stopped (or otherwise changed) while we were waiting.
+            return;
+        }
+
+        let check = effective_check(&repo);
+        if let Some((check, port)) = &check {
+            if probe(&http, check, *port).await {
+                info!(repo_id = %repo_id, port, "Service ready");
+                set_status_if_starting(&repos, &repo_id, ServiceStatus::Running).await;
+                return;
+            }
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            let status = match check {
+                Some((check, port)) => ServiceStatus::Error(format!(
+                    "Readiness check timed out after {:?}: {}",
+                    timeout,
+                    describe_check(&check, port)
+                )),
+                None => {
+                    // Nothing to probe: the process is alive, which is the best we know.
+                    warn!(repo_id = %repo_id, "No port discovered; assuming service is running");
+                    ServiceStatus::Running
+                }
+            };
+            set_status_if_starting(&repos, &repo_id, status).await;
+            return;
+        }
+
+        tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+    }
+}
+
+async fn set_status_if_starting(repos: &RepoMap, repo_id: &str, status: ServiceStatus) {
+    let mut repos = repos.lock().await;
+    if let Some(repo) = repos.get_mut(repo_id) {
+        if matches!(repo.service_status, ServiceStatus::Starting) {
+            repo.service_status = status;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_port() {
+        assert_eq!(discover_port("Listening on http://127.0.0.1:8080/"), Some(8080));
+        assert_eq!(discover_port("  ➜  Local:   http://localhost:5173/"), Some(5173));
+        assert_eq!(discover_port("Server started on port 3000"), Some(3000));
+        assert_eq!(discover_port("PORT=4000"), Some(4000));
+        assert_eq!(discover_port("Compiling serde v1.0.0"), None);
+        assert_eq!(discover_port("port 99999"), None);
+        assert_eq!(discover_port("Serving at http://myhost:8081/app"), Some(8081));
+        assert_eq!(discover_port("report 200 entries"), None);
+        assert_eq!(discover_port("export 3 modules"), None);
+        assert_eq!(discover_port("transport 1 ready"), None);
+        assert_eq!(discover_port("ports 4 open"), None);
+    }
+
+    #[test]
+    fn test_pip_install_needs_an_installable_project() {
+        let dir = std::env::temp_dir().join(format!("ecosystem_pip_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("requirements.txt"), "requests\n").unwrap();
+        let args = |cmd: Command| -> Vec<String> {
+            cmd.get_args().map(|a| a.to_string_lossy().into_owned()).collect()
+        };
+        assert_eq!(args(pip_install_command(&dir)), ["install", "-r", "requirements.txt"]);
+        std::fs::write(dir.join("pyproject.toml"), "[project]\nname = \"demo\"\n").unwrap();
+        assert_eq!(args(pip_install_command(&dir)), ["install", "-e", "."]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn starting_repo(repo_id: &str, port: Option<u16>, health_check: Option<HealthCheck>) -> RepoMap {
+        let repo = RepoMetadata {
+            id: repo_id.to_string(),
+            name: repo_id.to_string(),
+            owner: "local".to_string(),
+            url: String::new(),
+            local_path: PathBuf::new(),
+            build_system: BuildSystem::Cargo,
+            build_targets: Vec::new(),
+            build_status: BuildStatus::Built,
+            service_status: ServiceStatus::Starting,
+            port,
+            health_check,
+            commands: Vec::new(),
+            created_at: 0,
+            last_built: None,
+            description: None,
+            source: None,
+            branch: None,
+            revision: None,
+            depth: None,
+            commit: None,
+        };
+        Arc::new(Mutex::new(HashMap::from([(repo_id.to_string(), repo)])))
+    }
+
+    /// A port nothing listens on.
+    fn closed_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+    }
+
+    async fn status_after_readiness(repos: &RepoMap, timeout: Duration) -> ServiceStatus {
+        let processes: ProcessMap = Arc::new(Mutex::new(HashMap::new()));
+        monitor_readiness(repos.clone(), processes, reqwest::Client::new(), "svc".to_string(), timeout).await;
+        repos.lock().await["svc"].service_status.clone()
+    }
+
+    #[tokio::test]
+    async fn test_probe_tcp_and_http() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = tokio::io::AsyncReadExt::read(&mut socket, &mut buf).await;
+                let _ = tokio::io::AsyncWriteExt::write_all(
+                    &mut socket,
+                    b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                )
+                .await;
+            }
+        });
+        let http = reqwest::Client::new();
+        let tcp = HealthCheck::Tcp { port: None };
+        let health = HealthCheck::Http { path: "/health".to_string(), port: None };
+        assert!(probe(&http, &tcp, port).await);
+        assert!(probe(&http, &health, port).await);
+
+        let closed = closed_port();
+        assert!(!probe(&http, &tcp, closed).await);
+        assert!(!probe(&http, &health, closed).await);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_running_once_the_port_opens() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let repos = starting_repo("svc", Some(port), None);
+        assert!(matches!(
+            status_after_readiness(&repos, Duration::from_secs(5)).await,
+            ServiceStatus::Running
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_readiness_times_out_on_a_closed_port() {
+        let port = closed_port();
+        let repos = starting_repo("svc", None, Some(HealthCheck::Tcp { port: Some(port) }));
+        match status_after_readiness(&repos, Duration::from_millis(300)).await {
+            ServiceStatus::Error(e) => assert!(e.contains(&format!("127.0.0.1:{}", port)), "{}", e),
+            other => panic!("expected a timeout error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readiness_without_a_port_assumes_running() {
+        let repos = starting_repo("svc", None, None);
+        assert!(matches!(
+            status_after_readiness(&repos, Duration::from_millis(100)).await,
+            ServiceStatus::Running
+        ));
+    }
+
+    #[test]
+    fn test_parse_make_targets() {
+        let makefile = "CC := gcc\nall: build\n\t$(CC) main.c\n.PHONY: all\nbuild test: deps\n%.o: %.c\n";
+        assert_eq!(parse_make_targets(makefile), vec!["all", "build", "test"]);
+    }
+
+    #[tokio::test]
+    async fn test_detect_multiple_build_targets() {
+        let dir = std::env::temp_dir().join(format!("ecosystem_detect_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("frontend")).unwrap();
+        std::fs::create_dir_all(dir.join("crates/core")).unwrap();
+        std::fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[workspace]\n").unwrap();
+        std::fs::write(dir.join("Dockerfile"), "FROM scratch\n").unwrap();
+        std::fs::write(dir.join("crates/Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(dir.join("frontend/package.json"), "{}").unwrap();
+        std::fs::write(dir.join("node_modules/pkg/package.json"), "{}").unwrap();
+
+        let targets = EcosystemManager::detect_build_targets(&dir).await;
+        let found: Vec<(BuildSystem, PathBuf)> = targets
+            .into_iter()
+            .map(|t| (t.build_system, t.relative_path))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (BuildSystem::Cargo, PathBuf::new()),
+                (BuildSystem::Docker, PathBuf::new()),
+                (BuildSystem::Npm, PathBuf::from("frontend")),
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn commit_file(dir: &Path, file: &str, contents: &str) {
+        std::fs::write(dir.join(file), contents).unwrap();
+        git(Some(dir), &["add", "."]).unwrap();
+        git(
+            Some(dir),
+            &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", file],
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_and_update_from_local_bare_repo() {
+        let root = std::env::temp_dir().join(format!("ecosystem_git_{}", Uuid::new_v4()));
+        let work = root.join("work");
+        let bare = root.join("origin.git");
+        std::fs::create_dir_all(&work).unwrap();
+        git(Some(&work), &["init", "-q", "-b", "main"]).unwrap();
+        commit_file(&work, "Cargo.toml", "[package]\nname = \"demo\"\n");
+        git(Some(&work), &["tag", "v1"]).unwrap();
+        git(None, &["clone", "-q", "--bare", work.to_str().unwrap(), bare.to_str().unwrap()]).unwrap();
+        git(Some(&work), &["remote", "add", "origin", bare.to_str().unwrap()]).unwrap();
+
+        let manager = EcosystemManager::new(root.join("repos"))
+            .unwrap()
+            .with_local_import_roots([&root]);
+        let tracking = manager
+            .import_from(
+                RepoSource::Local { path: bare.clone() },
+                ImportOptions { depth: Some(1), ..Default::default() },
+            )
+            .await
+            .unwrap();
+        let pinned = manager
+            .import_from(
+                RepoSource::Git { url: format!("file://{}", bare.display()) },
+                ImportOptions { revision: Some("v1".to_string()), ..Default::default() },
+            )
+            .await
+            .unwrap();
+        assert_eq!(tracking.name, "origin");
+        assert_eq!(tracking.build_system, BuildSystem::Cargo);
+        assert_eq!(tracking.commit, pinned.commit);
+
+        commit_file(&work, "package.json", "{}");
+        git(Some(&work), &["push", "-q", "origin", "main"]).unwrap();
+
+        let updated = manager.update_repo(&tracking.id).await.unwrap();
+        assert_ne!(updated.commit, tracking.commit);
+        assert!(updated.local_path.join("package.json").exists());
+
+        let still_pinned = manager.update_repo(&pinned.id).await.unwrap();
+        assert_eq!(still_pinned.commit, pinned.commit);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_import_plain_local_directory() {
+        let root = std::env::temp_dir().join(format!("ecosystem_local_{}", Uuid::new_v4()));
+        let project = root.join("project");
+        std::fs::create_dir_all(project.join("node_modules")).unwrap();
+        std::fs::write(project.join("package.json"), "{}").unwrap();
+
+        let manager = EcosystemManager::new(root.join("repos"))
+            .unwrap()
+            .with_local_import_roots([&root]);
+        let repo = manager
+            .import_from(RepoSource::Local { path: project.clone() }, ImportOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(repo.owner, "local");
+        assert_eq!(repo.commit, None);
+        assert!(repo.local_path.join("package.json").exists());
+        assert!(!repo.local_path.join("node_modules").exists());
+
+        // A failed re-copy keeps the previous import.
+        std::fs::write(project.join("Cargo.toml"), "[package]\n").unwrap();
+        let updated = manager.update_repo(&repo.id).await.unwrap();
+        assert!(updated.local_path.join("Cargo.toml").exists());
+        std::fs::remove_dir_all(&project).unwrap();
+        assert!(manager.update_repo(&repo.id).await.is_err());
+        assert!(repo.local_path.join("package.json").exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_option_injection_and_unlisted_local_paths() {
+        let root = std::env::temp_dir().join(format!("ecosystem_reject_{}", Uuid::new_v4()));
+        let allowed = root.join("allowed");
+        let outside = root.join("outside");
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret"), "key").unwrap();
+        let marker = root.join("pwned");
+
+        let manager = EcosystemManager::new(root.join("repos"))
+            .unwrap()
+            .with_local_import_roots([&allowed]);
+        let url = format!("--upload-pack=touch {}", marker.display());
+        assert!(manager
+            .import_from(RepoSource::Git { url }, ImportOptions::default())
+            .await
+            .is_err());
+        for (branch, revision) in [(Some("--help"), None), (None, Some("-p"))] {
+            let options = ImportOptions {
+                branch: branch.map(str::to_string),
+                revision: revision.map(str::to_string),
+                depth: None,
+            };
+            let source = RepoSource::GitHub { owner: "o".to_string(), repo: "r".to_string() };
+            assert!(manager.import_from(source, options).await.is_err());
+        }
+        assert!(!marker.exists());
+
+        for source in [
+            RepoSource::Local { path: outside.clone() },
+            RepoSource::Local { path: allowed.join("../outside") },
+            RepoSource::Git { url: format!("file://{}", outside.display()) },
+            RepoSource::Git { url: outside.display().to_string() },
+        ] {
+            let err = manager.import_from(source, ImportOptions::default()).await.unwrap_err();
+            assert!(err.to_string().contains("import roots"), "{err}");
+        }
+        assert!(manager.list_repos().await.is_empty());
+
+        assert_eq!(local_url_path("git@github.com:o/r.git"), None);
+        assert_eq!(local_url_path("https://github.com/o/r"), None);
+        assert_eq!(local_url_path("/srv/repo.git"), Some(PathBuf::from("/srv/repo.git")));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_repo_reports_passing_and_failing_suites() {
+        let root = std::env::temp_dir().join(format!("ecosystem_test_repo_{}", Uuid::new_v4()));
+        let project = root.join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        let lib = |assertion: &str| format!("#[test]\nfn adds() {{\n    {assertion}\n}}\n");
+        std::fs::write(project.join("src/lib.rs"), lib("assert_eq!(1 + 1, 2);")).unwrap();
+
+        let manager = EcosystemManager::new(root.join("repos"))
+            .unwrap()
+            .with_local_import_roots([&root]);
+        let repo = manager
+            .import_from(RepoSource::Local { path: project.clone() }, ImportOptions::default())
+            .await
+            .unwrap();
+        let config = || RepoTestConfig {
+            timeout: Duration::from_secs(300),
+            ..RepoTestConfig::default()
+        };
+
+        let report = manager.test_repo(&repo.id, config()).await.unwrap();
+        assert!(report.passed, "{}", report.to_markdown());
+        assert!(report.results.iter().any(|r| r.name == "adds" && r.passed));
+
+        std::fs::write(repo.local_path.join("src/lib.rs"), lib("assert_eq!(1 + 1, 3);")).unwrap();
+        let report = manager.test_repo(&repo.id, config()).await.unwrap();
+        assert!(!report.passed);
+        assert!(report.results.iter().any(|r| r.name == "adds" && !r.passed));
+
+        assert!(manager.test_repo("missing", config()).await.is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
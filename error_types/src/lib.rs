@@ -133,6 +133,9 @@ pub enum ConfigError {
 
     #[error("Invalid archetype repository: {0}")]
     InvalidRepository(String),
+
+    #[error("Archetype changed upstream: {0}")]
+    ArchetypeConflict(String),
 }
 
 /// Unified error type that encompasses all Phoenix AGI (PAGI) errors.
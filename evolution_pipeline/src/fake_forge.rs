@@ -0,0 +1,396 @@
+//! In-process forge backed by local bare git repositories.
+//!
+//! Repositories live at `<root>/<owner>/<repo>.git` and are cloned and pushed over the local
+//! filesystem. Pull requests, comments, reviews and CI states are kept in `<root>/forge.json`,
+//! re-read on every call so a TUI approving in one process is seen by an enforcer polling in
+//! another. Merges are real merge (or squash) commits written into the bare repository.
+//!
+//! Tests drive the parts a human or CI would normally supply through [`FakeForge::approve_as`]
+//! and [`FakeForge::set_ci_state`].
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use git2::{Repository, Signature};
+use serde::{Deserialize, Serialize};
+
+use crate::forge::{Forge, ForgeRepo, NewPullRequest, PrStatus, PullRequestSummary, parse_pr_url};
+use crate::github_enforcement::CreationError;
+
+const STATE_FILE: &str = "forge.json";
+
+/// A pull request on the fake forge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FakePullRequest {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub head: String,
+    pub base: String,
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub comments: Vec<String>,
+    #[serde(default)]
+    pub approved_by: Vec<String>,
+    #[serde(default)]
+    pub ci_state: Option<String>,
+    #[serde(default)]
+    pub merged: bool,
+    #[serde(default)]
+    pub merge_commit_sha: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FakeState {
+    #[serde(default)]
+    pulls: Vec<FakePullRequest>,
+}
+
+pub struct FakeForge {
+    root: PathBuf,
+    /// Login recorded for reviews submitted through [`Forge::approve_pr`].
+    login: String,
+    lock: Mutex<()>,
+}
+
+impl FakeForge {
+    pub fn new(root: impl Into<PathBuf>, login: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            login: login.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of the bare repository for `owner/repo`.
+    pub fn repo_path(&self, owner: &str, repo: &str) -> PathBuf {
+        self.root.join(owner).join(format!("{repo}.git"))
+    }
+
+    /// The pull request behind `pr_url`, if any.
+    pub fn pull(&self, pr_url: &str) -> Result<FakePullRequest, CreationError> {
+        let pr = parse_pr_url(pr_url)?;
+        self.with_state(|state| Ok(find_pull(state, &pr.owner, &pr.repo, pr.number, pr_url)?.clone()))
+    }
+
+    /// Record an approving review by `login`.
+    pub fn approve_as(&self, pr_url: &str, login: &str) -> Result<(), CreationError> {
+        self.update_pull(pr_url, |pull| {
+            if !pull.approved_by.iter().any(|l| l.eq_ignore_ascii_case(login)) {
+                pull.approved_by.push(login.to_string());
+            }
+            Ok(())
+        })
+    }
+
+    /// Set the combined CI state (`pending`, `success`, `failure`, `error`) of a pull request.
+    pub fn set_ci_state(&self, pr_url: &str, state: &str) -> Result<(), CreationError> {
+        self.update_pull(pr_url, |pull| {
+            pull.ci_state = Some(state.to_string());
+            Ok(())
+        })
+    }
+
+    fn pr_url(&self, owner: &str, repo: &str, number: u64) -> String {
+        format!("file://{}/{owner}/{repo}/pull/{number}", self.root.display())
+    }
+
+    fn open_repo(&self, owner: &str, repo: &str) -> Result<Repository, CreationError> {
+        let path = self.repo_path(owner, repo);
+        Repository::open_bare(&path)
+            .map_err(|e| CreationError::Other(format!("no repository {owner}/{repo} at {}: {e}", path.display())))
+    }
+
+    /// Run `f` on the persisted state, then write it back. The read-modify-write holds an advisory
+    /// lock on `forge.json.lock` so processes sharing the root never lose each other's updates.
+    fn with_state<T>(&self, f: impl FnOnce(&mut FakeState) -> Result<T, CreationError>) -> Result<T, CreationError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let io = |e: std::io::Error| CreationError::Other(e.to_string());
+        std::fs::create_dir_all(&self.root).map_err(io)?;
+        let path = self.root.join(STATE_FILE);
+        let lock_file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("json.lock"))
+            .map_err(io)?;
+        // Released when `lock_file` is dropped.
+        lock_file.lock().map_err(io)?;
+
+        // Only a missing file means a fresh forge; anything else must not be overwritten.
+        let mut state: FakeState = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => FakeState::default(),
+            Err(e) => return Err(io(e)),
+        };
+        let out = f(&mut state)?;
+        // Write-then-rename so readers never see a partial file.
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&state)?)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(io)?;
+        Ok(out)
+    }
+
+    fn update_pull(
+        &self,
+        pr_url: &str,
+        f: impl FnOnce(&mut FakePullRequest) -> Result<(), CreationError>,
+    ) -> Result<(), CreationError> {
+        let pr = parse_pr_url(pr_url)?;
+        self.with_state(|state| f(find_pull(state, &pr.owner, &pr.repo, pr.number, pr_url)?))
+    }
+}
+
+fn find_pull<'a>(
+    state: &'a mut FakeState,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    pr_url: &str,
+) -> Result<&'a mut FakePullRequest, CreationError> {
+    state
+        .pulls
+        .iter_mut()
+        .find(|p| p.owner == owner && p.repo == repo && p.number == number)
+        .ok_or_else(|| CreationError::InvalidPrUrl(pr_url.to_string()))
+}
+
+fn branch_sha(repo: &Repository, branch: &str) -> Result<String, CreationError> {
+    Ok(repo.find_reference(&format!("refs/heads/{branch}"))?.peel_to_commit()?.id().to_string())
+}
+
+fn forge_signature() -> Result<Signature<'static>, CreationError> {
+    Ok(Signature::now("Phoenix Fake Forge", "forge@phoenix.local")?)
+}
+
+#[async_trait]
+impl Forge for FakeForge {
+    fn kind(&self) -> &'static str {
+        "fake"
+    }
+
+    fn requires_token(&self) -> bool {
+        false
+    }
+
+    fn clone_url(&self, owner: &str, repo: &str) -> String {
+        self.repo_path(owner, repo).to_string_lossy().into_owned()
+    }
+
+    async fn create_repo(
+        &self,
+        _token: &str,
+        owner: &str,
+        name: &str,
+        description: &str,
+        _private: bool,
+    ) -> Result<ForgeRepo, CreationError> {
+        let path = self.repo_path(owner, name);
+        if path.exists() {
+            return Err(CreationError::Other(format!("create_repo failed: {owner}/{name} already exists")));
+        }
+        let repo = Repository::init_bare(&path)?;
+
+        // auto_init: one commit with a README on `main`.
+        let readme = repo.blob(format!("# {name}\n\n{description}\n").as_bytes())?;
+        let mut tree = repo.treebuilder(None)?;
+        tree.insert("README.md", readme, 0o100644)?;
+        let tree = repo.find_tree(tree.write()?)?;
+        let sig = forge_signature()?;
+        repo.commit(Some("refs/heads/main"), &sig, &sig, "Initial commit", &tree, &[])?;
+        repo.set_head("refs/heads/main")?;
+
+        Ok(ForgeRepo {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            html_url: format!("file://{}", path.display()),
+            clone_url: self.clone_url(owner, name),
+        })
+    }
+
+    async fn create_pr(&self, _token: &str, pr: NewPullRequest<'_>) -> Result<String, CreationError> {
+        let repo = self.open_repo(pr.owner, pr.repo)?;
+        for branch in [pr.head, pr.base] {
+            branch_sha(&repo, branch)
+                .map_err(|_| CreationError::Other(format!("create_pr failed: branch {branch} does not exist")))?;
+        }
+        let number = self.with_state(|state| {
+            let number = state
+                .pulls
+                .iter()
+                .filter(|p| p.owner == pr.owner && p.repo == pr.repo)
+                .map(|p| p.number)
+                .max()
+                .unwrap_or(0)
+                + 1;
+            state.pulls.push(FakePullRequest {
+                owner: pr.owner.to_string(),
+                repo: pr.repo.to_string(),
+                number,
+                head: pr.head.to_string(),
+                base: pr.base.to_string(),
+                title: pr.title.to_string(),
+                body: pr.body.to_string(),
+                comments: Vec::new(),
+                approved_by: Vec::new(),
+                ci_state: None,
+                merged: false,
+                merge_commit_sha: None,
+            });
+            Ok(number)
+        })?;
+        Ok(self.pr_url(pr.owner, pr.repo, number))
+    }
+
+    async fn comment_on_pr(&self, _token: &str, pr_url: &str, comment: &str) -> Result<(), CreationError> {
+        self.update_pull(pr_url, |pull| {
+            pull.comments.push(comment.to_string());
+            Ok(())
+        })
+    }
+
+    async fn pr_status(&self, _token: &str, pr_url: &str) -> Result<PrStatus, CreationError> {
+        let pull = self.pull(pr_url)?;
+        let repo = self.open_repo(&pull.owner, &pull.repo)?;
+        Ok(PrStatus {
+            head_sha: branch_sha(&repo, &pull.head)?,
+            merged: pull.merged,
+            merge_commit_sha: pull.merge_commit_sha,
+            ci_state: pull.ci_state,
+            approved_by: pull.approved_by,
+        })
+    }
+
+    async fn merge_pr(&self, _token: &str, pr_url: &str, method: &str) -> Result<(), CreationError> {
+        let pull = self.pull(pr_url)?;
+        if pull.merged {
+            return Err(CreationError::Other(format!("merge_pr failed: {pr_url} is already merged")));
+        }
+        let repo = self.open_repo(&pull.owner, &pull.repo)?;
+        let base_ref = format!("refs/heads/{}", pull.base);
+        let base = repo.find_reference(&base_ref)?.peel_to_commit()?;
+        let head = repo.find_reference(&format!("refs/heads/{}", pull.head))?.peel_to_commit()?;
+
+        let mut index = repo.merge_commits(&base, &head, None)?;
+        if index.has_conflicts() {
+            return Err(CreationError::Other(format!("merge_pr failed: {pr_url} has conflicts")));
+        }
+        let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+        let sig = forge_signature()?;
+        let message = format!("Merge pull request #{} from {}\n\n{}", pull.number, pull.head, pull.title);
+        let parents = if method == "squash" { vec![&base] } else { vec![&base, &head] };
+        let merge_commit = repo.commit(Some(&base_ref), &sig, &sig, &message, &tree, &parents)?;
+
+        self.update_pull(pr_url, |pull| {
+            pull.merged = true;
+            pull.merge_commit_sha = Some(merge_commit.to_string());
+            Ok(())
+        })
+    }
+
+    async fn list_open_prs(
+        &self,
+        _token: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<PullRequestSummary>, CreationError> {
+        let pulls = self.with_state(|state| Ok(state.pulls.clone()))?;
+        Ok(pulls
+            .into_iter()
+            .filter(|p| p.owner == owner && p.repo == repo && !p.merged)
+            .map(|p| PullRequestSummary {
+                number: p.number,
+                html_url: self.pr_url(&p.owner, &p.repo, p.number),
+                title: p.title,
+                head_ref: p.head,
+            })
+            .collect())
+    }
+
+    async fn approve_pr(&self, _token: &str, pr_url: &str, body: &str) -> Result<(), CreationError> {
+        let login = self.login.clone();
+        self.update_pull(pr_url, |pull| {
+            if !pull.approved_by.iter().any(|l| l.eq_ignore_ascii_case(&login)) {
+                pull.approved_by.push(login);
+            }
+            if !body.trim().is_empty() {
+                pull.comments.push(body.to_string());
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn concurrent_updates_from_separate_instances_are_not_lost() {
+        let root = std::env::temp_dir().join(format!("fake_forge_{}", uuid::Uuid::new_v4()));
+        // Separate instances have separate in-process locks, like two processes sharing a root.
+        let forges = [
+            Arc::new(FakeForge::new(&root, "a")),
+            Arc::new(FakeForge::new(&root, "b")),
+        ];
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let forge = forges[i % 2].clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        forge
+                            .with_state(|state| {
+                                state.pulls.push(FakePullRequest {
+                                    owner: "dad".to_string(),
+                                    repo: "tools".to_string(),
+                                    number: state.pulls.len() as u64 + 1,
+                                    head: "feature".to_string(),
+                                    base: "main".to_string(),
+                                    title: String::new(),
+                                    body: String::new(),
+                                    comments: Vec::new(),
+                                    approved_by: Vec::new(),
+                                    ci_state: None,
+                                    merged: false,
+                                    merge_commit_sha: None,
+                                });
+                                Ok(())
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let count = forges[0].with_state(|state| Ok(state.pulls.len())).unwrap();
+        assert_eq!(count, 200);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn unreadable_state_is_an_error_not_a_fresh_forge() {
+        let root = std::env::temp_dir().join(format!("fake_forge_{}", uuid::Uuid::new_v4()));
+        let forge = FakeForge::new(&root, "a");
+        assert_eq!(forge.with_state(|state| Ok(state.pulls.len())).unwrap(), 0);
+
+        std::fs::write(root.join(STATE_FILE), "{ not json").unwrap();
+        assert!(forge.with_state(|_| Ok(())).is_err());
+        assert_eq!(std::fs::read_to_string(root.join(STATE_FILE)).unwrap(), "{ not json");
+
+        std::fs::remove_file(root.join(STATE_FILE)).unwrap();
+        std::fs::create_dir(root.join(STATE_FILE)).unwrap();
+        assert!(forge.with_state(|_| Ok(())).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
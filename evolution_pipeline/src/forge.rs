@@ -0,0 +1,623 @@
+//! Code-forge abstraction for the GitHub-first creation enforcement pipeline.
+//!
+//! Everything the pipeline needs from a forge (repositories, pull requests, reviews, CI
+//! status, merges) goes through [`Forge`]. Implementations:
+//!
+//! - [`GitHubForge`]: GitHub REST API v3 (the default).
+//! - [`GiteaForge`]: Gitea/Forgejo API v1, for self-hosted forges.
+//! - [`crate::fake_forge::FakeForge`]: local bare git repositories, for tests and offline runs.
+//!
+//! [`from_env`] picks one from `PHOENIX_FORGE` (`github`, `gitea`, `forgejo` or `fake`).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::Method;
+use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use serde_json::Value;
+
+use crate::fake_forge::FakeForge;
+use crate::github_enforcement::CreationError;
+
+const DEFAULT_USER_AGENT: &str = "phoenix-2.0-github-enforcer";
+/// Where the fake forge keeps its repositories unless `PHOENIX_FORGE_DIR` says otherwise.
+pub const DEFAULT_FAKE_FORGE_DIR: &str = "data/fake_forge";
+
+/// A pull request reference parsed from its web URL.
+#[derive(Debug, Clone)]
+pub struct PrRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Parse `<base>/<owner>/<repo>/pull/<number>` (GitHub) or `.../pulls/<number>` (Gitea).
+pub fn parse_pr_url(pr_url: &str) -> Result<PrRef, CreationError> {
+    let u = pr_url.trim();
+    let parts: Vec<&str> = u.split('/').filter(|p| !p.is_empty()).collect();
+    let pull_pos = parts
+        .iter()
+        .rposition(|p| p.eq_ignore_ascii_case("pull") || p.eq_ignore_ascii_case("pulls"))
+        .ok_or_else(|| CreationError::InvalidPrUrl(pr_url.to_string()))?;
+    if pull_pos < 3 {
+        return Err(CreationError::InvalidPrUrl(pr_url.to_string()));
+    }
+    let owner = parts[pull_pos - 2].to_string();
+    let repo = parts[pull_pos - 1].to_string();
+    let number = parts
+        .get(pull_pos + 1)
+        .ok_or_else(|| CreationError::InvalidPrUrl(pr_url.to_string()))?
+        .parse::<u64>()
+        .map_err(|_| CreationError::InvalidPrUrl(pr_url.to_string()))?;
+    Ok(PrRef { owner, repo, number })
+}
+
+#[derive(Debug, Clone)]
+pub struct PrStatus {
+    pub head_sha: String,
+    pub merged: bool,
+    pub merge_commit_sha: Option<String>,
+    pub ci_state: Option<String>,
+    pub approved_by: Vec<String>,
+}
+
+/// A repository created on a forge.
+#[derive(Debug, Clone)]
+pub struct ForgeRepo {
+    pub owner: String,
+    pub name: String,
+    pub html_url: String,
+    pub clone_url: String,
+}
+
+/// An open pull request, as listed by [`Forge::list_open_prs`].
+#[derive(Debug, Clone)]
+pub struct PullRequestSummary {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub head_ref: String,
+}
+
+/// A pull request to open with [`Forge::create_pr`].
+#[derive(Debug, Clone, Copy)]
+pub struct NewPullRequest<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    /// Branch with the changes.
+    pub head: &'a str,
+    /// Branch to merge into.
+    pub base: &'a str,
+    pub title: &'a str,
+    pub body: &'a str,
+}
+
+#[async_trait]
+pub trait Forge: Send + Sync {
+    /// Short name for logs and status endpoints (`github`, `gitea`, `fake`).
+    fn kind(&self) -> &'static str;
+
+    /// Whether calls need an access token (the fake forge does not).
+    fn requires_token(&self) -> bool {
+        true
+    }
+
+    /// URL to clone or push `owner/repo`.
+    fn clone_url(&self, owner: &str, repo: &str) -> String;
+
+    /// Create a repository with an initial commit on `main`.
+    ///
+    /// Hosted forges create it under the token's user; `owner` is only used by forges
+    /// without accounts.
+    async fn create_repo(
+        &self,
+        token: &str,
+        owner: &str,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<ForgeRepo, CreationError>;
+
+    /// Open a pull request and return its web URL.
+    async fn create_pr(&self, token: &str, pr: NewPullRequest<'_>) -> Result<String, CreationError>;
+
+    async fn comment_on_pr(&self, token: &str, pr_url: &str, comment: &str) -> Result<(), CreationError>;
+
+    /// Head commit, merge state, combined CI state and approving reviewers.
+    async fn pr_status(&self, token: &str, pr_url: &str) -> Result<PrStatus, CreationError>;
+
+    /// Merge with `method` (`merge`, `squash` or `rebase`).
+    async fn merge_pr(&self, token: &str, pr_url: &str, method: &str) -> Result<(), CreationError>;
+
+    async fn list_open_prs(
+        &self,
+        token: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<PullRequestSummary>, CreationError>;
+
+    /// Approve as the token's user.
+    async fn approve_pr(&self, token: &str, pr_url: &str, body: &str) -> Result<(), CreationError>;
+}
+
+fn env_nonempty(key: &str) -> Option<String> {
+    std::env::var(key).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// The forge selected by `PHOENIX_FORGE` (default `github`).
+///
+/// - `gitea`/`forgejo`: `GITEA_URL` (or `FORGEJO_URL`) is the instance's web URL.
+/// - `fake`: repositories live under `PHOENIX_FORGE_DIR` (default [`DEFAULT_FAKE_FORGE_DIR`]) and
+///   approvals are recorded as `DAD_GITHUB_LOGIN`/`GITHUB_REPO_OWNER`.
+pub fn from_env() -> Arc<dyn Forge> {
+    let kind = env_nonempty("PHOENIX_FORGE")
+        .unwrap_or_else(|| "github".to_string())
+        .to_ascii_lowercase();
+    let user_agent = env_nonempty("GITHUB_USER_AGENT").unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+    match kind.as_str() {
+        "gitea" | "forgejo" => {
+            let base_url = env_nonempty("GITEA_URL")
+                .or_else(|| env_nonempty("FORGEJO_URL"))
+                .unwrap_or_default();
+            Arc::new(GiteaForge::new(base_url).with_user_agent(user_agent))
+        }
+        "fake" | "local" => {
+            let dir = env_nonempty("PHOENIX_FORGE_DIR").unwrap_or_else(|| DEFAULT_FAKE_FORGE_DIR.to_string());
+            let login = env_nonempty("DAD_GITHUB_LOGIN")
+                .or_else(|| env_nonempty("GITHUB_REQUIRED_REVIEWER"))
+                .or_else(|| env_nonempty("GITHUB_REPO_OWNER"))
+                .or_else(|| env_nonempty("GITHUB_USERNAME"))
+                .unwrap_or_else(|| "phoenix".to_string());
+            Arc::new(FakeForge::new(dir, login))
+        }
+        other => {
+            if other != "github" {
+                tracing::warn!("[forge::from_env] unknown PHOENIX_FORGE={other:?}; using github");
+            }
+            let mut forge = GitHubForge::new().with_user_agent(user_agent);
+            if let Some(api) = env_nonempty("GITHUB_API_URL") {
+                forge = forge.with_api_base(api);
+            }
+            Arc::new(forge)
+        }
+    }
+}
+
+/// JSON-over-HTTPS client shared by the hosted forges.
+#[derive(Debug, Clone)]
+struct RestClient {
+    api_base: String,
+    user_agent: String,
+    accept: &'static str,
+    /// Authorization scheme placed before the token (`Bearer` or `token`).
+    auth_scheme: &'static str,
+}
+
+impl RestClient {
+    async fn send(
+        &self,
+        op: &str,
+        method: Method,
+        path: &str,
+        token: &str,
+        body: Option<Value>,
+    ) -> Result<Value, CreationError> {
+        let url = format!("{}{}", self.api_base.trim_end_matches('/'), path);
+        let mut req = reqwest::Client::new()
+            .request(method, &url)
+            .header(USER_AGENT, &self.user_agent)
+            .header(ACCEPT, self.accept)
+            .header(AUTHORIZATION, format!("{} {}", self.auth_scheme, token));
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+        let resp = req.send().await?;
+        let status = resp.status();
+        let txt = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(CreationError::Other(format!("{op} failed ({status}): {txt}")));
+        }
+        if txt.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        Ok(serde_json::from_str(&txt)?)
+    }
+
+    /// GET that treats any failure as "no data" (optional CI status and reviews).
+    async fn try_get(&self, op: &str, path: &str, token: &str) -> Option<Value> {
+        self.send(op, Method::GET, path, token, None).await.ok()
+    }
+}
+
+fn str_field(v: &Value, pointer: &str) -> String {
+    v.pointer(pointer).and_then(|x| x.as_str()).unwrap_or("").to_string()
+}
+
+fn repo_from_json(v: &Value) -> ForgeRepo {
+    ForgeRepo {
+        owner: str_field(v, "/owner/login"),
+        name: str_field(v, "/name"),
+        html_url: str_field(v, "/html_url"),
+        clone_url: str_field(v, "/clone_url"),
+    }
+}
+
+/// Combine the pull, combined-status and reviews payloads (same shape on GitHub and Gitea).
+fn status_from_json(pull: &Value, ci: Option<Value>, reviews: Option<Value>) -> PrStatus {
+    let ci_state = ci
+        .as_ref()
+        .and_then(|c| c.get("state"))
+        .and_then(|s| s.as_str())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    let mut approved_by: Vec<String> = Vec::new();
+    for r in reviews.as_ref().and_then(|r| r.as_array()).into_iter().flatten() {
+        if r.get("state").and_then(|s| s.as_str()) != Some("APPROVED") {
+            continue;
+        }
+        let login = str_field(r, "/user/login");
+        if !login.is_empty() && !approved_by.iter().any(|x| x.eq_ignore_ascii_case(&login)) {
+            approved_by.push(login);
+        }
+    }
+
+    PrStatus {
+        head_sha: str_field(pull, "/head/sha"),
+        merged: pull.get("merged").and_then(|m| m.as_bool()).unwrap_or(false)
+            || pull.get("merged_at").is_some_and(|m| !m.is_null()),
+        merge_commit_sha: pull
+            .get("merge_commit_sha")
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        ci_state,
+        approved_by,
+    }
+}
+
+fn pulls_from_json(v: &Value) -> Vec<PullRequestSummary> {
+    v.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|pr| {
+            let number = pr.get("number").and_then(|x| x.as_u64()).unwrap_or(0);
+            let html_url = str_field(pr, "/html_url");
+            if number == 0 || html_url.is_empty() {
+                return None;
+            }
+            Some(PullRequestSummary {
+                number,
+                title: str_field(pr, "/title"),
+                html_url,
+                head_ref: str_field(pr, "/head/ref"),
+            })
+        })
+        .collect()
+}
+
+/// GitHub REST API v3 (`api.github.com`, or a GitHub Enterprise API base).
+#[derive(Debug, Clone)]
+pub struct GitHubForge {
+    rest: RestClient,
+    web_base: String,
+}
+
+impl Default for GitHubForge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitHubForge {
+    pub fn new() -> Self {
+        Self {
+            rest: RestClient {
+                api_base: "https://api.github.com".to_string(),
+                user_agent: DEFAULT_USER_AGENT.to_string(),
+                accept: "application/vnd.github+json",
+                auth_scheme: "Bearer",
+            },
+            web_base: "https://github.com".to_string(),
+        }
+    }
+
+    /// Point at a GitHub Enterprise server (`https://ghe.example.com/api/v3`).
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        let api_base = api_base.into();
+        self.web_base = api_base
+            .trim_end_matches('/')
+            .trim_end_matches("/api/v3")
+            .replace("://api.github.com", "://github.com");
+        self.rest.api_base = api_base;
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.rest.user_agent = user_agent.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Forge for GitHubForge {
+    fn kind(&self) -> &'static str {
+        "github"
+    }
+
+    fn clone_url(&self, owner: &str, repo: &str) -> String {
+        format!("{}/{owner}/{repo}.git", self.web_base)
+    }
+
+    async fn create_repo(
+        &self,
+        token: &str,
+        _owner: &str,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<ForgeRepo, CreationError> {
+        let body = serde_json::json!({
+            "name": name,
+            "description": description,
+            "private": private,
+            // Ensure the base branch exists so we can PR into it.
+            "auto_init": true,
+        });
+        let v = self.rest.send("create_repo", Method::POST, "/user/repos", token, Some(body)).await?;
+        Ok(repo_from_json(&v))
+    }
+
+    async fn create_pr(&self, token: &str, pr: NewPullRequest<'_>) -> Result<String, CreationError> {
+        let path = format!("/repos/{}/{}/pulls", pr.owner, pr.repo);
+        let body = serde_json::json!({
+            "title": pr.title,
+            "head": pr.head,
+            "base": pr.base,
+            "body": pr.body,
+        });
+        let v = self.rest.send("create_pr", Method::POST, &path, token, Some(body)).await?;
+        Ok(v.get("html_url").and_then(|x| x.as_str()).unwrap_or("(created)").to_string())
+    }
+
+    async fn comment_on_pr(&self, token: &str, pr_url: &str, comment: &str) -> Result<(), CreationError> {
+        let pr = parse_pr_url(pr_url)?;
+        let path = format!("/repos/{}/{}/issues/{}/comments", pr.owner, pr.repo, pr.number);
+        self.rest
+            .send("comment_on_pr", Method::POST, &path, token, Some(serde_json::json!({"body": comment})))
+            .await?;
+        Ok(())
+    }
+
+    async fn pr_status(&self, token: &str, pr_url: &str) -> Result<PrStatus, CreationError> {
+        let pr = parse_pr_url(pr_url)?;
+        let base = format!("/repos/{}/{}", pr.owner, pr.repo);
+        let pull = self
+            .rest
+            .send("get_pr_status(PR)", Method::GET, &format!("{base}/pulls/{}", pr.number), token, None)
+            .await?;
+        let head_sha = str_field(&pull, "/head/sha");
+        let ci = self.rest.try_get("get_pr_status(CI)", &format!("{base}/commits/{head_sha}/status"), token).await;
+        let reviews = self
+            .rest
+            .try_get("get_pr_status(reviews)", &format!("{base}/pulls/{}/reviews", pr.number), token)
+            .await;
+        Ok(status_from_json(&pull, ci, reviews))
+    }
+
+    async fn merge_pr(&self, token: &str, pr_url: &str, method: &str) -> Result<(), CreationError> {
+        let pr = parse_pr_url(pr_url)?;
+        let path = format!("/repos/{}/{}/pulls/{}/merge", pr.owner, pr.repo, pr.number);
+        self.rest
+            .send("merge_pr", Method::PUT, &path, token, Some(serde_json::json!({"merge_method": method})))
+            .await?;
+        Ok(())
+    }
+
+    async fn list_open_prs(
+        &self,
+        token: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<PullRequestSummary>, CreationError> {
+        let path = format!("/repos/{owner}/{repo}/pulls?state=open&per_page=50");
+        let v = self.rest.send("list_open_prs", Method::GET, &path, token, None).await?;
+        Ok(pulls_from_json(&v))
+    }
+
+    async fn approve_pr(&self, token: &str, pr_url: &str, body: &str) -> Result<(), CreationError> {
+        let pr = parse_pr_url(pr_url)?;
+        let path = format!("/repos/{}/{}/pulls/{}/reviews", pr.owner, pr.repo, pr.number);
+        self.rest
+            .send(
+                "approve_pr",
+                Method::POST,
+                &path,
+                token,
+                Some(serde_json::json!({"event": "APPROVE", "body": body})),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Gitea/Forgejo API v1 (`<base_url>/api/v1`).
+#[derive(Debug, Clone)]
+pub struct GiteaForge {
+    rest: RestClient,
+    web_base: String,
+}
+
+impl GiteaForge {
+    /// `base_url` is the instance's web URL, e.g. `https://git.example.com`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let web_base = base_url.into().trim().trim_end_matches('/').to_string();
+        Self {
+            rest: RestClient {
+                api_base: format!("{web_base}/api/v1"),
+                user_agent: DEFAULT_USER_AGENT.to_string(),
+                accept: "application/json",
+                auth_scheme: "token",
+            },
+            web_base,
+        }
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.rest.user_agent = user_agent.into();
+        self
+    }
+
+    fn configured(&self) -> Result<(), CreationError> {
+        if self.web_base.is_empty() {
+            return Err(CreationError::MissingEnv("GITEA_URL"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaForge {
+    fn kind(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn clone_url(&self, owner: &str, repo: &str) -> String {
+        format!("{}/{owner}/{repo}.git", self.web_base)
+    }
+
+    async fn create_repo(
+        &self,
+        token: &str,
+        _owner: &str,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<ForgeRepo, CreationError> {
+        self.configured()?;
+        let body = serde_json::json!({
+            "name": name,
+            "description": description,
+            "private": private,
+            "auto_init": true,
+            "default_branch": "main",
+        });
+        let v = self.rest.send("create_repo", Method::POST, "/user/repos", token, Some(body)).await?;
+        Ok(repo_from_json(&v))
+    }
+
+    async fn create_pr(&self, token: &str, pr: NewPullRequest<'_>) -> Result<String, CreationError> {
+        self.configured()?;
+        let path = format!("/repos/{}/{}/pulls", pr.owner, pr.repo);
+        let body = serde_json::json!({
+            "title": pr.title,
+            "head": pr.head,
+            "base": pr.base,
+            "body": pr.body,
+        });
+        let v = self.rest.send("create_pr", Method::POST, &path, token, Some(body)).await?;
+        Ok(v.get("html_url").and_then(|x| x.as_str()).unwrap_or("(created)").to_string())
+    }
+
+    async fn comment_on_pr(&self, token: &str, pr_url: &str, comment: &str) -> Result<(), CreationError> {
+        self.configured()?;
+        let pr = parse_pr_url(pr_url)?;
+        let path = format!("/repos/{}/{}/issues/{}/comments", pr.owner, pr.repo, pr.number);
+        self.rest
+            .send("comment_on_pr", Method::POST, &path, token, Some(serde_json::json!({"body": comment})))
+            .await?;
+        Ok(())
+    }
+
+    async fn pr_status(&self, token: &str, pr_url: &str) -> Result<PrStatus, CreationError> {
+        self.configured()?;
+        let pr = parse_pr_url(pr_url)?;
+        let base = format!("/repos/{}/{}", pr.owner, pr.repo);
+        let pull = self
+            .rest
+            .send("get_pr_status(PR)", Method::GET, &format!("{base}/pulls/{}", pr.number), token, None)
+            .await?;
+        let head_sha = str_field(&pull, "/head/sha");
+        let ci = self.rest.try_get("get_pr_status(CI)", &format!("{base}/commits/{head_sha}/status"), token).await;
+        let reviews = self
+            .rest
+            .try_get("get_pr_status(reviews)", &format!("{base}/pulls/{}/reviews", pr.number), token)
+            .await;
+        Ok(status_from_json(&pull, ci, reviews))
+    }
+
+    async fn merge_pr(&self, token: &str, pr_url: &str, method: &str) -> Result<(), CreationError> {
+        self.configured()?;
+        let pr = parse_pr_url(pr_url)?;
+        let path = format!("/repos/{}/{}/pulls/{}/merge", pr.owner, pr.repo, pr.number);
+        self.rest
+            .send("merge_pr", Method::POST, &path, token, Some(serde_json::json!({"Do": method})))
+            .await?;
+        Ok(())
+    }
+
+    async fn list_open_prs(
+        &self,
+        token: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<PullRequestSummary>, CreationError> {
+        self.configured()?;
+        let path = format!("/repos/{owner}/{repo}/pulls?state=open&limit=50");
+        let v = self.rest.send("list_open_prs", Method::GET, &path, token, None).await?;
+        Ok(pulls_from_json(&v))
+    }
+
+    async fn approve_pr(&self, token: &str, pr_url: &str, body: &str) -> Result<(), CreationError> {
+        self.configured()?;
+        let pr = parse_pr_url(pr_url)?;
+        let path = format!("/repos/{}/{}/pulls/{}/reviews", pr.owner, pr.repo, pr.number);
+        self.rest
+            .send(
+                "approve_pr",
+                Method::POST,
+                &path,
+                token,
+                Some(serde_json::json!({"event": "APPROVED", "body": body})),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_github_gitea_and_fake_pr_urls() {
+        let gh = parse_pr_url("https://github.com/dad/phoenix-tools/pull/12").unwrap();
+        assert_eq!((gh.owner.as_str(), gh.repo.as_str(), gh.number), ("dad", "phoenix-tools", 12));
+        let gitea = parse_pr_url("https://git.example.com/dad/phoenix-agents/pulls/3").unwrap();
+        assert_eq!((gitea.repo.as_str(), gitea.number), ("phoenix-agents", 3));
+        let fake = parse_pr_url("file:///tmp/forge/dad/phoenix-tools/pull/1").unwrap();
+        assert_eq!((fake.owner.as_str(), fake.number), ("dad", 1));
+        assert!(parse_pr_url("https://github.com/dad/phoenix-tools").is_err());
+    }
+
+    #[test]
+    fn status_merges_ci_and_unique_approvals() {
+        let pull = serde_json::json!({"head": {"sha": "abc"}, "merged_at": null, "merge_commit_sha": ""});
+        let reviews = serde_json::json!([
+            {"state": "APPROVED", "user": {"login": "Dad"}},
+            {"state": "COMMENTED", "user": {"login": "bot"}},
+            {"state": "APPROVED", "user": {"login": "dad"}},
+        ]);
+        let status = status_from_json(&pull, Some(serde_json::json!({"state": "success"})), Some(reviews));
+        assert_eq!(status.head_sha, "abc");
+        assert!(!status.merged);
+        assert_eq!(status.merge_commit_sha, None);
+        assert_eq!(status.ci_state.as_deref(), Some("success"));
+        assert_eq!(status.approved_by, vec!["Dad".to_string()]);
+
+        let gitea = serde_json::json!({"head": {"sha": "def"}, "merged": true, "merge_commit_sha": "123"});
+        let status = status_from_json(&gitea, Some(serde_json::json!({"state": ""})), None);
+        assert!(status.merged);
+        assert_eq!(status.ci_state, None);
+    }
+}
@@ -21,10 +21,11 @@ fn open_repo(code_path: &Path) -> Result<Repository, CreationError> {
     Ok(Repository::open(code_path)?)
 }
 
-/// Stage all changes and commit.
+/// Stage all changes and commit. Returns whether a commit was made: nothing is committed when
+/// the staged tree matches HEAD.
 ///
 /// If there is no HEAD yet (fresh repo), this will create an initial commit.
-pub fn commit_all(code_path: &Path, message: &str) -> Result<(), CreationError> {
+pub fn commit_all(code_path: &Path, message: &str) -> Result<bool, CreationError> {
     let repo = open_repo(code_path)?;
 
     let mut index = repo.index()?;
@@ -46,6 +47,9 @@ pub fn commit_all(code_path: &Path, message: &str) -> Result<(), CreationError>
         }
         Err(_) => vec![],
     };
+    if parents.first().is_some_and(|parent| parent.tree_id() == tree_id) {
+        return Ok(false);
+    }
     let parent_refs: Vec<&git2::Commit<'_>> = parents.iter().collect();
 
     repo.commit(
@@ -57,7 +61,7 @@ pub fn commit_all(code_path: &Path, message: &str) -> Result<(), CreationError>
         &parent_refs,
     )?;
 
-    Ok(())
+    Ok(true)
 }
 
 /// Create a local branch at HEAD, checkout it, and push it to the remote `origin`.
@@ -131,3 +135,132 @@ pub fn checkout_and_pull_main(code_path: &Path) -> Result<(), CreationError> {
     Ok(())
 }
 
+/// One file touched by a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    /// added, modified, deleted, renamed, ...
+    pub status: &'static str,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// What a commit changed, for PR descriptions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffSummary {
+    pub files: Vec<FileChange>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffSummary {
+    /// Markdown table of the changed files (capped at `max_files` rows).
+    pub fn to_markdown(&self, max_files: usize) -> String {
+        let mut out = format!(
+            "## Diff Summary\n\n{} file(s) changed, +{} −{}\n",
+            self.files.len(),
+            self.insertions,
+            self.deletions
+        );
+        if self.files.is_empty() {
+            return out;
+        }
+        out.push_str("\n| File | Change | + | − |\n| --- | --- | ---: | ---: |\n");
+        for f in self.files.iter().take(max_files) {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                f.path, f.status, f.insertions, f.deletions
+            ));
+        }
+        if self.files.len() > max_files {
+            out.push_str(&format!("\n…and {} more file(s)\n", self.files.len() - max_files));
+        }
+        out
+    }
+}
+
+/// Changes made by the HEAD commit relative to its first parent (everything for a root commit).
+pub fn head_diff_summary(code_path: &Path) -> Result<DiffSummary, CreationError> {
+    let repo = open_repo(code_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let parent_tree = match head.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&head.tree()?), None)?;
+    diff.find_similar(None)?;
+
+    let mut summary = DiffSummary::default();
+    for idx in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(idx) else {
+            continue;
+        };
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let status = match delta.status() {
+            git2::Delta::Added => "added",
+            git2::Delta::Deleted => "deleted",
+            git2::Delta::Renamed => "renamed",
+            git2::Delta::Copied => "copied",
+            git2::Delta::Typechange => "type changed",
+            _ => "modified",
+        };
+        // Binary files have no patch and count no lines.
+        let (insertions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+            Some(patch) => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions, deletions)
+            }
+            None => (0, 0),
+        };
+        summary.insertions += insertions;
+        summary.deletions += deletions;
+        summary.files.push(FileChange {
+            path,
+            status,
+            insertions,
+            deletions,
+        });
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_diff_summary_counts_lines_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "fn b() {}\nfn c() {}\n").unwrap();
+        commit_all(dir.path(), "first").unwrap();
+
+        let first = head_diff_summary(dir.path()).unwrap();
+        assert_eq!(first.files.len(), 2);
+        assert_eq!((first.insertions, first.deletions), (3, 0));
+
+        std::fs::write(dir.path().join("a.rs"), "fn a() { todo!() }\n").unwrap();
+        std::fs::write(dir.path().join("c.rs"), "fn d() {}\n").unwrap();
+        commit_all(dir.path(), "second").unwrap();
+
+        let second = head_diff_summary(dir.path()).unwrap();
+        assert_eq!(
+            second.files,
+            vec![
+                FileChange { path: "a.rs".to_string(), status: "modified", insertions: 1, deletions: 1 },
+                FileChange { path: "c.rs".to_string(), status: "added", insertions: 1, deletions: 0 },
+            ]
+        );
+        assert!(second.to_markdown(10).contains("2 file(s) changed, +2 −1"));
+
+        let head = Repository::open(dir.path()).unwrap().head().unwrap().target();
+        assert!(!commit_all(dir.path(), "nothing").unwrap());
+        assert_eq!(Repository::open(dir.path()).unwrap().head().unwrap().target(), head);
+    }
+}
@@ -2,22 +2,41 @@
 //!
 //! This module enforces a strict creation flow for any Phoenix-generated tool/agent:
 //!
-//! 1. Local tests already passed (caller responsibility)
+//! 1. Run the creation's tests locally (refuse to continue if they fail)
 //! 2. Create dedicated branch
 //! 3. Commit and push to GitHub
-//! 4. Open PR with detailed body
-//! 5. Post test report comment
+//! 4. Open PR with a diff summary
+//! 5. Post the local test report as a comment
 //! 6. Poll for CI success + human approval (Dad)
 //! 7. Auto-merge (optional)
 //! 8. Pull merged code back locally
+//!
+//! Forge calls go through [`Forge`], so the same flow runs against GitHub, Gitea/Forgejo or the
+//! local [`crate::fake_forge::FakeForge`].
 
 use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use serde::Serialize;
 use thiserror::Error;
 
-use crate::{github_api, git_operations};
+use testing_framework::TestReport;
+use testing_framework::repo::RepoTestConfig;
+
+use crate::forge::{self, Forge, NewPullRequest};
+use crate::git_operations;
+
+/// Budget for the local test run when `CREATION_TEST_TIMEOUT_SECS` is unset.
+const DEFAULT_CREATION_TEST_TIMEOUT_SECS: u64 = 600;
+/// GitHub rejects comments longer than 65536 characters.
+const MAX_COMMENT_CHARS: usize = 60_000;
+/// Files listed in the PR body's diff summary.
+const DIFF_SUMMARY_FILES: usize = 50;
+/// How often the PR is polled for CI and approval.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Upper bound on the poll interval once a merge has been requested.
+const MERGE_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CreationKind {
@@ -63,6 +82,9 @@ pub enum CreationError {
     #[error("CI failed for PR: state={0}")]
     CiFailed(String),
 
+    #[error("local tests failed; not opening a PR: {0}")]
+    LocalTestsFailed(String),
+
     #[error("git error: {0}")]
     Git(#[from] git2::Error),
 
@@ -128,6 +150,8 @@ pub struct GitHubEnforcer {
     pub require_human_approval: bool,
     pub auto_merge_on_approval: bool,
     pub timeout_hours: u64,
+    pub forge: Arc<dyn Forge>,
+    pub poll_interval: Duration,
 }
 
 /// Sanitized GitHub enforcement configuration (safe to expose to UIs/APIs).
@@ -144,6 +168,8 @@ pub struct GitHubEnforcerEnvStatus {
     pub tools_repo: String,
     pub required_reviewer: String,
     pub mandate_ci: bool,
+    /// `github`, `gitea` or `fake` (see `PHOENIX_FORGE`).
+    pub forge: String,
 }
 
 struct GitHubEnvParts {
@@ -190,11 +216,13 @@ fn read_github_env_parts() -> GitHubEnvParts {
 impl GitHubEnforcer {
     pub fn from_env() -> Self {
         let parts = read_github_env_parts();
+        let forge = forge::from_env();
 
         // Diagnostic telemetry (safe to print): helps explain why creations are blocked.
         // Avoid printing secrets; only print whether token/owner are present.
         println!(
-            "[GitHubEnforcer::from_env] require_human_approval={} auto_merge_on_approval={} timeout_hours={} token_present={} owner_present={} agents_repo={} tools_repo={}",
+            "[GitHubEnforcer::from_env] forge={} require_human_approval={} auto_merge_on_approval={} timeout_hours={} token_present={} owner_present={} agents_repo={} tools_repo={}",
+            forge.kind(),
             parts.require_human_approval,
             parts.auto_merge_on_approval,
             parts.timeout_hours,
@@ -212,6 +240,24 @@ impl GitHubEnforcer {
             require_human_approval: parts.require_human_approval,
             auto_merge_on_approval: parts.auto_merge_on_approval,
             timeout_hours: parts.timeout_hours,
+            forge,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// An enforcer for `owner` on `forge` with the default policy (approval required, no
+    /// auto-merge, 24h timeout) and the default repository names.
+    pub fn with_forge(forge: Arc<dyn Forge>, token: impl Into<String>, owner: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            owner: owner.into(),
+            agents_repo: "phoenix-agents".to_string(),
+            tools_repo: "phoenix-tools".to_string(),
+            require_human_approval: true,
+            auto_merge_on_approval: false,
+            timeout_hours: 24,
+            forge,
+            poll_interval: DEFAULT_POLL_INTERVAL,
         }
     }
 
@@ -232,6 +278,7 @@ impl GitHubEnforcer {
             tools_repo: parts.tools_repo,
             required_reviewer,
             mandate_ci,
+            forge: forge::from_env().kind().to_string(),
         }
     }
 
@@ -244,21 +291,25 @@ impl GitHubEnforcer {
     ) -> Result<String, CreationError> {
         if !self.require_human_approval {
             // Safety mandate: Phoenix refuses to proceed without explicit blessing.
-            println!(
-                "[GitHubEnforcer::create_and_enforce_creation] blocked: REQUIRE_HUMAN_PR_APPROVAL=false (kind={}, name={})",
-                kind,
-                name
+            tracing::warn!(
+                %kind,
+                name,
+                "[GitHubEnforcer::create_and_enforce_creation] blocked: REQUIRE_HUMAN_PR_APPROVAL=false"
             );
             return Err(CreationError::HumanApprovalDisabled);
         }
-        if self.token.trim().is_empty() {
+        if self.forge.requires_token() && self.token.trim().is_empty() {
             return Err(CreationError::MissingGitHubAuth);
         }
         if self.owner.trim().is_empty() {
             return Err(CreationError::MissingGitHubOwner);
         }
 
-        // 1. Local tests already passed
+        // 1. Run the creation's tests locally
+        let test_report = run_local_tests(code_path).await?;
+        if !test_report.passed {
+            return Err(CreationError::LocalTestsFailed(failure_summary(&test_report)));
+        }
 
         // 2. Create dedicated branch
         let branch = format!(
@@ -268,7 +319,13 @@ impl GitHubEnforcer {
         );
 
         // 3. Commit and push
-        git_operations::commit_all(code_path, &format!("feat: add {} {}", kind, name))?;
+        let committed = git_operations::commit_all(code_path, &format!("feat: add {} {}", kind, name))?;
+        // With nothing new to commit, HEAD is an older commit whose changes are not this creation's.
+        let diff_summary = if committed {
+            git_operations::head_diff_summary(code_path)?
+        } else {
+            git_operations::DiffSummary::default()
+        };
         git_operations::create_and_push_branch(code_path, &branch, &self.token)?;
 
         // 4. Create PR with detailed body
@@ -276,30 +333,41 @@ impl GitHubEnforcer {
             CreationKind::Agent => &self.agents_repo,
             CreationKind::Tool => &self.tools_repo,
         };
-        let pr_url = github_api::create_pr(
-            &self.token,
-            &self.owner,
-            repo,
-            &branch,
-            "main",
-            &format!("[Phoenix Auto-Creation] {}", name),
-            &format!(
-                "{}\n\nGenerated by Phoenix for Dad ❤️\n\nAwaiting review and approval.",
-                description
-            ),
-        )
-        .await?;
+        let title = format!("[Phoenix Auto-Creation] {}", name);
+        let body = format!(
+            "{}\n\n{}\nLocal tests: passed (score {:.1}%, {} results); full report below.\n\nGenerated by Phoenix for Dad ❤️\n\nAwaiting review and approval.",
+            description,
+            diff_summary.to_markdown(DIFF_SUMMARY_FILES),
+            test_report.score * 100.0,
+            test_report.results.len()
+        );
+        let pr_url = self
+            .forge
+            .create_pr(
+                &self.token,
+                NewPullRequest {
+                    owner: &self.owner,
+                    repo,
+                    head: &branch,
+                    base: "main",
+                    title: &title,
+                    body: &body,
+                },
+            )
+            .await?;
 
         // 5. Post local test report as comment
-        let test_report = testing_framework::generate_markdown_report();
-        let _ = github_api::comment_on_pr(&self.token, &pr_url, &test_report).await;
+        let comment = truncate_comment(&test_report.to_markdown());
+        if let Err(e) = self.forge.comment_on_pr(&self.token, &pr_url, &comment).await {
+            tracing::error!("[GitHubEnforcer] failed to post test report on {pr_url}: {e}");
+        }
 
         // 6. Poll for CI status + human approval
         let approved_commit = self.poll_for_completion(&pr_url).await?;
 
         // 7. Auto-merge if enabled
         if self.auto_merge_on_approval {
-            github_api::merge_pr(&self.token, &pr_url, "merge").await?;
+            self.forge.merge_pr(&self.token, &pr_url, "merge").await?;
             // After requesting merge, wait until merged so we can return the merged SHA.
             let merged_commit = self.poll_until_merged(&pr_url).await?;
             git_operations::checkout_and_pull_main(code_path)?;
@@ -320,13 +388,13 @@ impl GitHubEnforcer {
         if !self.require_human_approval {
             return Err(CreationError::HumanApprovalDisabled);
         }
-        if self.token.trim().is_empty() {
+        if self.forge.requires_token() && self.token.trim().is_empty() {
             return Err(CreationError::MissingGitHubAuth);
         }
 
         let _ = self.poll_for_completion(pr_url).await?;
         if self.auto_merge_on_approval {
-            let _ = github_api::merge_pr(&self.token, pr_url, "merge").await;
+            let _ = self.forge.merge_pr(&self.token, pr_url, "merge").await;
             return self.poll_until_merged(pr_url).await;
         }
         // If auto-merge is disabled, Dad will merge manually; return once it's merged.
@@ -351,7 +419,7 @@ impl GitHubEnforcer {
                 return Err(CreationError::Timeout);
             }
 
-            let status = github_api::get_pr_status(&self.token, pr_url).await?;
+            let status = self.forge.pr_status(&self.token, pr_url).await?;
 
             // CI gate
             if mandate_ci {
@@ -388,7 +456,7 @@ impl GitHubEnforcer {
                 return Ok(status.head_sha);
             }
 
-            tokio::time::sleep(Duration::from_secs(30)).await;
+            tokio::time::sleep(self.poll_interval).await;
         }
     }
 
@@ -399,12 +467,188 @@ impl GitHubEnforcer {
                 return Err(CreationError::Timeout);
             }
 
-            let status = github_api::get_pr_status(&self.token, pr_url).await?;
+            let status = self.forge.pr_status(&self.token, pr_url).await?;
             if status.merged {
                 return Ok(status.merge_commit_sha.unwrap_or(status.head_sha));
             }
-            tokio::time::sleep(Duration::from_secs(10)).await;
+            tokio::time::sleep(self.poll_interval.min(MERGE_POLL_INTERVAL)).await;
         }
     }
 }
 
+/// Run the creation's own test suite (Cargo, npm or pytest, detected from its files).
+async fn run_local_tests(code_path: &Path) -> Result<TestReport, CreationError> {
+    let config = RepoTestConfig {
+        timeout: Duration::from_secs(
+            env_u64("CREATION_TEST_TIMEOUT_SECS").unwrap_or(DEFAULT_CREATION_TEST_TIMEOUT_SECS),
+        ),
+        ..RepoTestConfig::default()
+    };
+    let dir = code_path.to_path_buf();
+    tokio::task::spawn_blocking(move || testing_framework::repo::run_repo_tests(&dir, &config))
+        .await
+        .map_err(|e| CreationError::Other(format!("local test task failed: {e}")))?
+        .map_err(|e| CreationError::LocalTestsFailed(e.to_string()))
+}
+
+/// Names of the failing results, for the error returned when local tests fail.
+fn failure_summary(report: &TestReport) -> String {
+    let failed: Vec<&str> = report
+        .results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.name.as_str())
+        .collect();
+    if failed.is_empty() {
+        format!("score {:.1}%", report.score * 100.0)
+    } else {
+        format!("{} failing: {}", failed.len(), failed.join(", "))
+    }
+}
+
+fn truncate_comment(markdown: &str) -> String {
+    if markdown.chars().count() <= MAX_COMMENT_CHARS {
+        return markdown.to_string();
+    }
+    let mut out: String = markdown.chars().take(MAX_COMMENT_CHARS).collect();
+    out.push_str("\n\n… (report truncated to fit a GitHub comment)\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use git2::Repository;
+    use tokio::task::JoinHandle;
+
+    use super::*;
+    use crate::fake_forge::FakeForge;
+
+    const OWNER: &str = "dad";
+    const REPO: &str = "phoenix-tools";
+
+    /// A fake forge with an auto-initialized tools repo, and a clone of it holding a tiny crate.
+    async fn setup(dir: &Path, test_body: &str) -> (Arc<FakeForge>, PathBuf) {
+        let forge = Arc::new(FakeForge::new(dir.join("forge"), OWNER));
+        forge.create_repo("", OWNER, REPO, "tools", false).await.unwrap();
+        let work = dir.join("work");
+        Repository::clone(&forge.clone_url(OWNER, REPO), &work).unwrap();
+        std::fs::create_dir_all(work.join("src")).unwrap();
+        std::fs::write(
+            work.join("Cargo.toml"),
+            "[package]\nname = \"hello_tool\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        std::fs::write(work.join(".gitignore"), "/target\nCargo.lock\n").unwrap();
+        std::fs::write(
+            work.join("src/lib.rs"),
+            format!("pub fn hello() -> &'static str {{\n    \"hello\"\n}}\n\n#[test]\nfn greets() {{\n    {test_body}\n}}\n"),
+        )
+        .unwrap();
+        (forge, work)
+    }
+
+    fn enforcer(forge: Arc<FakeForge>) -> GitHubEnforcer {
+        let mut enforcer = GitHubEnforcer::with_forge(forge, "", OWNER);
+        enforcer.poll_interval = Duration::from_millis(20);
+        enforcer
+    }
+
+    /// Plays CI and Dad: waits for the creation PR, reports `ci`, and approves it if asked.
+    fn review(forge: Arc<FakeForge>, ci: &'static str, approve: bool) -> JoinHandle<String> {
+        tokio::spawn(async move {
+            loop {
+                let open = forge.list_open_prs("", OWNER, REPO).await.unwrap();
+                if let Some(pr) = open.first() {
+                    forge.set_ci_state(&pr.html_url, ci).unwrap();
+                    if approve {
+                        forge.approve_as(&pr.html_url, OWNER).unwrap();
+                    }
+                    return pr.html_url.clone();
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+    }
+
+    async fn create(enforcer: &GitHubEnforcer, work: &Path) -> Result<String, CreationError> {
+        let run = enforcer.create_and_enforce_creation(work, "Hello Tool", "Says hello.", CreationKind::Tool);
+        tokio::time::timeout(Duration::from_secs(300), run)
+            .await
+            .expect("creation flow timed out")
+    }
+
+    fn main_sha(forge: &FakeForge) -> String {
+        let repo = Repository::open_bare(forge.repo_path(OWNER, REPO)).unwrap();
+        repo.find_reference("refs/heads/main").unwrap().peel_to_commit().unwrap().id().to_string()
+    }
+
+    #[tokio::test]
+    async fn approved_creation_is_merged_and_pulled_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let (forge, work) = setup(dir.path(), "assert_eq!(hello(), \"hello\");").await;
+        let mut enforcer = enforcer(forge.clone());
+        enforcer.auto_merge_on_approval = true;
+
+        let reviewer = review(forge.clone(), "success", true);
+        let merged = create(&enforcer, &work).await.unwrap();
+        let pr_url = reviewer.await.unwrap();
+
+        let pull = forge.pull(&pr_url).unwrap();
+        assert!(pull.merged);
+        assert_eq!(pull.head, "phoenix-creation/tool-hello-tool");
+        assert_eq!(pull.title, "[Phoenix Auto-Creation] Hello Tool");
+        assert!(pull.body.contains("## Diff Summary"));
+        assert_eq!(pull.comments.len(), 1, "local test report is posted");
+        assert_eq!(pull.merge_commit_sha.as_deref(), Some(merged.as_str()));
+        assert_eq!(main_sha(&forge), merged);
+
+        let local = Repository::open(&work).unwrap();
+        assert_eq!(local.head().unwrap().shorthand(), Some("main"));
+        assert_eq!(local.head().unwrap().peel_to_commit().unwrap().id().to_string(), merged);
+        assert!(work.join("src/lib.rs").exists());
+    }
+
+    #[tokio::test]
+    async fn approval_without_auto_merge_leaves_the_pr_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let (forge, work) = setup(dir.path(), "assert_eq!(hello(), \"hello\");").await;
+        let before = main_sha(&forge);
+
+        let reviewer = review(forge.clone(), "success", true);
+        let approved = create(&enforcer(forge.clone()), &work).await.unwrap();
+        let pr_url = reviewer.await.unwrap();
+
+        let status = forge.pr_status("", &pr_url).await.unwrap();
+        assert!(!status.merged);
+        assert_eq!(status.head_sha, approved);
+        assert_eq!(status.approved_by, vec![OWNER.to_string()]);
+        assert_eq!(main_sha(&forge), before);
+    }
+
+    #[tokio::test]
+    async fn failing_ci_stops_the_flow() {
+        let dir = tempfile::tempdir().unwrap();
+        let (forge, work) = setup(dir.path(), "assert_eq!(hello(), \"hello\");").await;
+        let before = main_sha(&forge);
+
+        let reviewer = review(forge.clone(), "failure", false);
+        let err = create(&enforcer(forge.clone()), &work).await.unwrap_err();
+        reviewer.await.unwrap();
+
+        assert!(matches!(err, CreationError::CiFailed(ref state) if state == "failure"), "{err}");
+        assert_eq!(main_sha(&forge), before);
+    }
+
+    #[tokio::test]
+    async fn failing_local_tests_open_no_pr() {
+        let dir = tempfile::tempdir().unwrap();
+        let (forge, work) = setup(dir.path(), "assert_eq!(hello(), \"goodbye\");").await;
+
+        let err = create(&enforcer(forge.clone()), &work).await.unwrap_err();
+
+        assert!(matches!(err, CreationError::LocalTestsFailed(_)), "{err}");
+        assert!(forge.list_open_prs("", OWNER, REPO).await.unwrap().is_empty());
+    }
+}
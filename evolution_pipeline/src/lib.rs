@@ -11,10 +11,14 @@ use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
+pub mod fake_forge;
+pub mod forge;
 pub mod github_api;
 pub mod github_enforcement;
 pub mod git_operations;
 
+pub use fake_forge::FakeForge;
+pub use forge::{Forge, ForgeRepo, GitHubForge, GiteaForge, NewPullRequest, PrStatus, PullRequestSummary};
 pub use github_enforcement::{CreationError, CreationKind, GitHubEnforcer};
 
 pub const TEMPLATE_VERSION: &str = "1.0.0";
@@ -148,15 +152,11 @@ pub fn commit_all_and_push_branch(
     message: &str,
     github_pat: &str,
 ) -> Result<String, EvolutionPipelineError> {
-    // Checkout/create branch.
+    // Create the branch at HEAD and switch to it. No checkout: the working tree already matches,
+    // and a forced one would delete the untracked files this commit is meant to add.
     let head_commit = repo.head()?.peel_to_commit()?;
     repo.branch(branch, &head_commit, true)?;
     repo.set_head(&format!("refs/heads/{branch}"))?;
-    repo.checkout_head(Some(
-        git2::build::CheckoutBuilder::new()
-            .force()
-            .remove_untracked(true),
-    ))?;
 
     // Stage everything.
     let mut index = repo.index()?;
@@ -0,0 +1,809 @@
+// Generated by `pagi-sola-web --write-api-client` from the phoenix-web OpenAPI document
+// (`GET /api/openapi.json`). Do not edit: change the Rust types and regenerate instead.
+
+import { apiFetch, apiUrl } from './auth';
+
+export interface ApplyArchetypeRequest {
+  profile: DatingProfile;
+  sign: string;
+}
+
+export interface ApplyArchetypeResponse {
+  message: string;
+  success: boolean;
+  updatedEnvVars: Record<string, string>;
+}
+
+export interface ArchetypeMatch {
+  compatibility: number;
+  description: string;
+  moodPreferences: string[];
+  name: string;
+  sign: string;
+  styleBias: string;
+  traits: Record<string, number>;
+}
+
+export interface AttachmentStyleData {
+  description: string;
+  style: string;
+}
+
+/**
+ * The caller's session. Only `auth_enabled` is present when auth is off; `csrf_token` is set for
+ * cookie sessions and `expires_in_secs` right after login.
+ */
+export interface AuthSessionResponse {
+  auth_enabled: boolean;
+  csrf_token?: string | null;
+  expires_in_secs?: number | null;
+  name?: string | null;
+  scopes?: Scope[] | null;
+}
+
+/** A snapshot archive in the backup directory. */
+export interface BackupInfo {
+  base?: string | null;
+  bytes: number;
+  /** Unix seconds; from the checksum sidecar, otherwise the modification time. */
+  created_at: number;
+  encrypted: boolean;
+  file: string;
+  kind?: BackupKind | null;
+  /** Unix seconds (file modification time). */
+  modified: number;
+}
+
+export type BackupKind = 'full' | 'incremental';
+
+export interface BackupRestoreRequest {
+  /** Archive file name in the backup directory (see `GET /api/backup`), or a path on the server. */
+  archive: string;
+}
+
+/** Portable, selective export. Absent sections are left untouched on import. */
+export interface BrainExport {
+  app_version: string;
+  /** RFC 3339 timestamp. */
+  exported_at: string;
+  /** Always `phoenix.brain_export.v1`. */
+  format: string;
+  relationship?: Record<string, unknown> | null;
+  sessions?: Conversation[] | null;
+  /** Skills added at runtime (built-in and folder skills are not exported). */
+  skills?: Record<string, unknown>[] | null;
+  vaults?: VaultExport | null;
+  vector_kb?: VectorMemoryExport[] | null;
+}
+
+export type BuildStatus = 'NotBuilt' | 'Building' | 'Built' | {
+  BuildFailed: string;
+};
+
+export type BuildSystem = 'Cargo' | 'Npm' | 'Pip' | 'Make' | 'Docker' | 'Maven' | 'Gradle' | {
+  Custom: string;
+} | 'Unknown';
+
+/** A build system rooted at a directory inside the repository. */
+export interface BuildTarget {
+  build_system: BuildSystem;
+  /** Directory relative to the repository root (empty for the root itself). */
+  relative_path: string;
+}
+
+export interface CommandRequest {
+  command: string;
+  /** Conversation session to record the turn in (see `/api/sessions`). */
+  session_id?: string | null;
+}
+
+export interface CommunicationStyleData {
+  assertiveness: number;
+  energyLevel: number;
+  openness: number;
+  playfulness: number;
+  style: string;
+}
+
+export interface ConfigGetResponse {
+  openrouter_api_key_set: boolean;
+  user_name?: string | null;
+  user_preferred_alias?: string | null;
+}
+
+export interface ConfigSetRequest {
+  openrouter_api_key?: string | null;
+  user_name?: string | null;
+  user_preferred_alias?: string | null;
+}
+
+export interface ConfigSetResponse {
+  llm_status: string;
+  openrouter_api_key_set: boolean;
+  status: string;
+  user_name?: string | null;
+  user_preferred_alias?: string | null;
+}
+
+export interface Conversation {
+  created_at: string;
+  id: string;
+  messages?: ConversationMessage[];
+  /**
+   * Name of the principal the session belongs to (`None` for sessions recorded before
+   * sessions had owners). Replaced by the caller on import; kept when restoring a backup.
+   */
+  owner?: string | null;
+  summarized_through?: number;
+  /** Summary of `messages[..summarized_through]`. */
+  summary?: string | null;
+  title: string;
+  updated_at: string;
+}
+
+export interface ConversationMessage {
+  content: string;
+  id: string;
+  model?: string | null;
+  /** `user` or `assistant`. */
+  role: string;
+  /** RFC 3339 timestamp. */
+  timestamp: string;
+}
+
+export interface ConversationSummary {
+  created_at: string;
+  id: string;
+  message_count: number;
+  title: string;
+  updated_at: string;
+}
+
+export interface DatabaseDump {
+  bytes: number;
+  /** CRC32 of the dump file. */
+  crc32: number;
+  entries: number;
+  name: string;
+  trees: number;
+}
+
+export interface DatingProfile {
+  attachmentStyle: AttachmentStyleData;
+  communicationStyle: CommunicationStyleData;
+  emotionalNeeds: EmotionalNeedsData;
+  interests: InterestsData;
+  loveLanguages: LoveLanguagesData;
+  personalInfo: PersonalInfo;
+  relationshipGoals: RelationshipGoalsData;
+}
+
+/** `status` is `success` for builds, `started`/`stopped` for services; `output` is the build log. */
+export interface EcosystemActionResponse {
+  message?: string | null;
+  output?: string | null;
+  status: string;
+}
+
+export interface EmotionalNeedsData {
+  affectionNeed: number;
+  conflictTolerance: number;
+  emotionalAvailability: number;
+  impulsivity: number;
+  intimacyDepth: number;
+  reassuranceNeed: number;
+}
+
+export interface ErrorResponse {
+  message: string;
+  type: string;
+}
+
+export interface ExecRequest {
+  command: string;
+  cwd?: string | null;
+}
+
+export interface ExecResponse {
+  exit_code: number;
+  stderr: string;
+  stdout: string;
+}
+
+/** How to decide that a started service is ready to accept traffic. */
+export type HealthCheck = {
+  /**
+   * Succeeds once a TCP connection to `127.0.0.1:port` can be opened.
+   * `None` uses the port discovered from the service output.
+   */
+  Tcp: {
+    port?: number | null;
+  };
+} | {
+  /**
+   * Succeeds once `GET http://127.0.0.1:port{path}` returns 2xx/3xx.
+   * `None` uses the port discovered from the service output.
+   */
+  Http: {
+    path: string;
+    port?: number | null;
+  };
+};
+
+/**
+ * Import from GitHub (`owner` + `repo`), any git remote (`url`), or a local
+ * directory on the server (`path`).
+ */
+export interface ImportRepoRequest {
+  branch?: string | null;
+  depth?: number | null;
+  owner?: string | null;
+  path?: string | null;
+  repo?: string | null;
+  revision?: string | null;
+  url?: string | null;
+}
+
+export interface ImportReport {
+  relationship: boolean;
+  sessions: number;
+  skills: number;
+  vault_entries: number;
+  vector_memories: number;
+  warnings?: string[];
+}
+
+export interface InterestsData {
+  favoriteTopics: string[];
+  hobbies: string[];
+}
+
+export interface LoginRequest {
+  token: string;
+}
+
+export interface LoveLanguagesData {
+  actsOfService: number;
+  gifts: number;
+  physicalTouch: number;
+  qualityTime: number;
+  wordsOfAffirmation: number;
+}
+
+export interface MatchResponse {
+  matches: ArchetypeMatch[];
+}
+
+export interface MemoryItem {
+  key: string;
+  value: string;
+}
+
+/** Public result shape returned by semantic search. */
+export interface MemoryResult {
+  id: string;
+  metadata: Record<string, unknown>;
+  /** 0.0..=1.0 cosine similarity (normalized). */
+  score: number;
+  text: string;
+}
+
+export interface MemorySearchResponse {
+  count: number;
+  items: MemoryItem[];
+}
+
+export interface MemoryStoreRequest {
+  key: string;
+  value: string;
+}
+
+export interface NameResponse {
+  name: string;
+}
+
+export interface PersonalInfo {
+  ageRange: string;
+  location: string;
+  name: string;
+}
+
+export interface ReadFileRequest {
+  path: string;
+}
+
+export interface ReadFileResponse {
+  content: string;
+  path: string;
+}
+
+export interface RelationalStateResponse {
+  score: number;
+  sentiment: string;
+}
+
+export interface RelationalStateUpdateRequest {
+  score?: number | null;
+  sentiment?: string | null;
+}
+
+export interface RelationshipGoalsData {
+  goals: string[];
+  intimacyComfort: string;
+}
+
+export interface RepoMetadata {
+  branch?: string | null;
+  build_status: BuildStatus;
+  /** Primary build system (the first one detected at the repository root). */
+  build_system: BuildSystem;
+  /**
+   * Every build system detected in the repository, including nested
+   * projects such as an npm frontend inside a Cargo workspace.
+   */
+  build_targets?: BuildTarget[];
+  commands: string[];
+  /** Commit currently checked out (`None` for non-git imports). */
+  commit?: string | null;
+  created_at: number;
+  /** Clone depth for shallow checkouts. */
+  depth?: number | null;
+  description?: string | null;
+  health_check?: HealthCheck | null;
+  id: string;
+  last_built?: number | null;
+  local_path: string;
+  name: string;
+  owner: string;
+  port?: number | null;
+  /** Commit SHA or tag the checkout is pinned to. */
+  revision?: string | null;
+  service_status: ServiceStatus;
+  source?: RepoSource | null;
+  url: string;
+}
+
+/** Where a repository is imported from. */
+export type RepoSource = {
+  /** `https://github.com/{owner}/{repo}` */
+  GitHub: {
+    owner: string;
+    repo: string;
+  };
+} | {
+  /** Any git remote: https, ssh, `git@host:path`, `file://`, self-hosted. */
+  Git: {
+    url: string;
+  };
+} | {
+  /**
+   * A directory on this machine. Git repositories (including bare ones)
+   * are cloned; plain directories are copied.
+   */
+  Local: {
+    path: string;
+  };
+};
+
+export interface RestoreReport {
+  archive: string;
+  notes: string[];
+  restored: string[];
+  /** Snapshot of the replaced databases, taken just before the restore. */
+  safety_snapshot: string;
+  /** Databases in the archive that are not configured here. */
+  skipped: SkippedDatabase[];
+}
+
+/** Error body of the skills and ecosystem routes. */
+export interface RouteErrorResponse {
+  error: string;
+}
+
+export type Scope = 'chat' | 'memory:read' | 'memory:write' | 'system' | 'ecosystem';
+
+export interface ServiceHealthResponse {
+  service_status: ServiceStatus;
+}
+
+export type ServiceStatus = 'Stopped' | 'Starting' | 'Running' | 'Stopping' | {
+  Error: string;
+};
+
+export interface SessionCreateRequest {
+  title?: string | null;
+}
+
+export interface SessionRenameRequest {
+  title: string;
+}
+
+export interface SkillInstallRequest {
+  name: string;
+  version?: string | null;
+}
+
+/** Publish library skills as a bundle: pick them by `skill_ids`, or by `categories`. */
+export interface SkillPublishRequest {
+  author?: string | null;
+  categories?: string[];
+  description?: string;
+  name: string;
+  skill_ids?: string[];
+  version: string;
+}
+
+export interface SkillUninstallRequest {
+  force?: boolean;
+  name: string;
+}
+
+/** Upgrade one bundle, or every installed bundle when `name` is omitted. */
+export interface SkillUpgradeRequest {
+  name?: string | null;
+}
+
+export interface SkippedDatabase {
+  name: string;
+  reason: string;
+}
+
+export interface SnapshotManifest {
+  /** Version of the program that took the snapshot. */
+  app_version: string;
+  /** File name of the full snapshot an incremental one builds on. */
+  base?: string | null;
+  /** Unix seconds. */
+  created_at: number;
+  /** Databases stored in this archive. */
+  databases: DatabaseDump[];
+  format_version: number;
+  /** Absent in format 1 archives, which are all full. */
+  kind?: BackupKind;
+  /** Configured databases that were not included, with the reason. */
+  skipped?: SkippedDatabase[];
+  /** Databases of an incremental snapshot that are unchanged since `base` and stored there. */
+  unchanged?: DatabaseDump[];
+}
+
+export interface SnapshotReport {
+  archive: string;
+  manifest: SnapshotManifest;
+}
+
+export interface SpeakRequest {
+  dad_emotion_hint?: string | null;
+  mode?: string | null;
+  /** Conversation session to record the turn in (see `/api/sessions`). */
+  session_id?: string | null;
+  user_input: string;
+}
+
+export interface StatusOkResponse {
+  status: string;
+}
+
+export interface StatusResponse {
+  archetype: string;
+  cwd: string;
+  dotenv_error?: string | null;
+  dotenv_path?: string | null;
+  llm_status: string;
+  openrouter_api_key_set: boolean;
+  status: string;
+  version: string;
+}
+
+export interface SystemStatusResponse {
+  full_access_granted: boolean;
+  self_modification_enabled: boolean;
+}
+
+/** Vault contents by key. Soul values are plaintext. */
+export interface VaultExport {
+  body?: Record<string, string>;
+  mind?: Record<string, string>;
+  soul?: Record<string, string>;
+}
+
+export interface VectorMemoryAllResponse {
+  count: number;
+  entries: VectorMemoryEntrySummary[];
+}
+
+export interface VectorMemoryEntrySummary {
+  id: string;
+  metadata: unknown;
+  text: string;
+}
+
+export interface VectorMemoryExport {
+  id: string;
+  metadata?: Record<string, unknown>;
+  text: string;
+}
+
+export interface VectorMemorySearchResponse {
+  count: number;
+  results: MemoryResult[];
+}
+
+export interface VectorMemoryStoreRequest {
+  metadata?: unknown;
+  text: string;
+}
+
+export interface VectorMemoryStoreResponse {
+  id: string;
+  status: string;
+}
+
+/** Result of test-restoring an archive into a scratch directory. */
+export interface VerifyReport {
+  archive: string;
+  /** Unix seconds the snapshot was taken. */
+  created_at: number;
+  databases: string[];
+  entries: number;
+  kind: BackupKind;
+}
+
+export interface WriteFileRequest {
+  content: string;
+  path: string;
+}
+
+/** A non-2xx response; `body` is the parsed JSON error (or the raw text). */
+export class ApiError extends Error {
+  readonly status: number;
+  readonly body: unknown;
+
+  constructor(status: number, body: unknown) {
+    super(errorMessage(status, body));
+    this.name = 'ApiError';
+    this.status = status;
+    this.body = body;
+  }
+}
+
+function errorMessage(status: number, body: unknown): string {
+  if (body && typeof body === 'object') {
+    const b = body as { message?: unknown; error?: unknown };
+    if (typeof b.message === 'string') return b.message;
+    if (typeof b.error === 'string') return b.error;
+  }
+  return typeof body === 'string' && body ? `${status}: ${body}` : `HTTP ${status}`;
+}
+
+async function request(
+  method: string,
+  path: string,
+  query?: object,
+  body?: unknown,
+  init: RequestInit = {},
+): Promise<Response> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(query ?? {})) {
+    if (value !== undefined && value !== null) params.set(key, String(value));
+  }
+  const qs = params.toString();
+  const headers = new Headers(init.headers);
+  if (body !== undefined) headers.set('Content-Type', 'application/json');
+  const res = await apiFetch(apiUrl(qs ? `${path}?${qs}` : path), {
+    ...init,
+    method,
+    headers,
+    body: body === undefined ? undefined : JSON.stringify(body),
+  });
+  if (!res.ok) {
+    const text = await res.text().catch(() => '');
+    let parsed: unknown = text;
+    try {
+      parsed = JSON.parse(text);
+    } catch {
+      // Not JSON; keep the text.
+    }
+    throw new ApiError(res.status, parsed);
+  }
+  return res;
+}
+
+async function requestJson<T>(
+  method: string,
+  path: string,
+  query?: object,
+  body?: unknown,
+  init?: RequestInit,
+): Promise<T> {
+  const res = await request(method, path, query, body, init);
+  return (await res.json()) as T;
+}
+
+export const phoenixApi = {
+  /** POST /api/archetype/apply */
+  archetypeApply: (body: ApplyArchetypeRequest, init?: RequestInit): Promise<ApplyArchetypeResponse> =>
+    requestJson<ApplyArchetypeResponse>('POST', '/api/archetype/apply', undefined, body, init),
+  /** POST /api/archetype/match */
+  archetypeMatch: (body: DatingProfile, init?: RequestInit): Promise<MatchResponse> =>
+    requestJson<MatchResponse>('POST', '/api/archetype/match', undefined, body, init),
+  /** GET /api/auth/session — who the caller is; returns the CSRF token again for cookie sessions. */
+  authSession: (init?: RequestInit): Promise<AuthSessionResponse> =>
+    requestJson<AuthSessionResponse>('GET', '/api/auth/session', undefined, undefined, init),
+  /** POST /api/auth/session — exchange an API token for a browser session cookie and CSRF token. */
+  authLogin: (body: LoginRequest, init?: RequestInit): Promise<AuthSessionResponse> =>
+    requestJson<AuthSessionResponse>('POST', '/api/auth/session', undefined, body, init),
+  /** DELETE /api/auth/session — end the browser session. */
+  authLogout: (init?: RequestInit): Promise<StatusOkResponse> =>
+    requestJson<StatusOkResponse>('DELETE', '/api/auth/session', undefined, undefined, init),
+  /** GET /api/backup */
+  backupList: (init?: RequestInit): Promise<BackupInfo[]> =>
+    requestJson<BackupInfo[]>('GET', '/api/backup', undefined, undefined, init),
+  /** POST /api/backup */
+  backupCreate: (init?: RequestInit): Promise<SnapshotReport> =>
+    requestJson<SnapshotReport>('POST', '/api/backup', undefined, undefined, init),
+  /** GET /api/backup/export */
+  backupExport: (query?: { sections?: string }, init?: RequestInit): Promise<BrainExport> =>
+    requestJson<BrainExport>('GET', '/api/backup/export', query, undefined, init),
+  /** POST /api/backup/import */
+  backupImport: (query?: { sections?: string }, body: BrainExport, init?: RequestInit): Promise<ImportReport> =>
+    requestJson<ImportReport>('POST', '/api/backup/import', query, body, init),
+  /** POST /api/backup/restore */
+  backupRestore: (body: BackupRestoreRequest, init?: RequestInit): Promise<RestoreReport> =>
+    requestJson<RestoreReport>('POST', '/api/backup/restore', undefined, body, init),
+  /** POST /api/backup/verify */
+  backupVerify: (query?: { archive?: string }, init?: RequestInit): Promise<VerifyReport> =>
+    requestJson<VerifyReport>('POST', '/api/backup/verify', query, undefined, init),
+  /** POST /api/command */
+  command: (body: CommandRequest, init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('POST', '/api/command', undefined, body, init),
+  /** GET /api/command-registry */
+  commandRegistry: (init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('GET', '/api/command-registry', undefined, undefined, init),
+  /** GET /api/config */
+  configGet: (init?: RequestInit): Promise<ConfigGetResponse> =>
+    requestJson<ConfigGetResponse>('GET', '/api/config', undefined, undefined, init),
+  /** POST /api/config */
+  configSet: (body: ConfigSetRequest, init?: RequestInit): Promise<ConfigSetResponse> =>
+    requestJson<ConfigSetResponse>('POST', '/api/config', undefined, body, init),
+  /** POST /api/ecosystem/import */
+  ecosystemImport: (body: ImportRepoRequest, init?: RequestInit): Promise<RepoMetadata> =>
+    requestJson<RepoMetadata>('POST', '/api/ecosystem/import', undefined, body, init),
+  /** GET /api/ecosystem/list */
+  ecosystemList: (init?: RequestInit): Promise<RepoMetadata[]> =>
+    requestJson<RepoMetadata[]>('GET', '/api/ecosystem/list', undefined, undefined, init),
+  /** GET /api/ecosystem/{id} */
+  ecosystemGet: (id: string, init?: RequestInit): Promise<RepoMetadata> =>
+    requestJson<RepoMetadata>('GET', `/api/ecosystem/${encodeURIComponent(id)}`, undefined, undefined, init),
+  /** DELETE /api/ecosystem/{id} */
+  ecosystemRemove: (id: string, init?: RequestInit): Promise<StatusOkResponse> =>
+    requestJson<StatusOkResponse>('DELETE', `/api/ecosystem/${encodeURIComponent(id)}`, undefined, undefined, init),
+  /** POST /api/ecosystem/{id}/build */
+  ecosystemBuild: (id: string, init?: RequestInit): Promise<EcosystemActionResponse> =>
+    requestJson<EcosystemActionResponse>('POST', `/api/ecosystem/${encodeURIComponent(id)}/build`, undefined, undefined, init),
+  /** GET /api/ecosystem/{id}/health */
+  ecosystemHealth: (id: string, init?: RequestInit): Promise<ServiceHealthResponse> =>
+    requestJson<ServiceHealthResponse>('GET', `/api/ecosystem/${encodeURIComponent(id)}/health`, undefined, undefined, init),
+  /** POST /api/ecosystem/{id}/start */
+  ecosystemStart: (id: string, init?: RequestInit): Promise<EcosystemActionResponse> =>
+    requestJson<EcosystemActionResponse>('POST', `/api/ecosystem/${encodeURIComponent(id)}/start`, undefined, undefined, init),
+  /** POST /api/ecosystem/{id}/stop */
+  ecosystemStop: (id: string, init?: RequestInit): Promise<EcosystemActionResponse> =>
+    requestJson<EcosystemActionResponse>('POST', `/api/ecosystem/${encodeURIComponent(id)}/stop`, undefined, undefined, init),
+  /** POST /api/ecosystem/{id}/update */
+  ecosystemUpdate: (id: string, init?: RequestInit): Promise<RepoMetadata> =>
+    requestJson<RepoMetadata>('POST', `/api/ecosystem/${encodeURIComponent(id)}/update`, undefined, undefined, init),
+  /** GET /api/evolution/status */
+  evolutionStatus: (init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('GET', '/api/evolution/status', undefined, undefined, init),
+  /** GET /api/google/auth/start */
+  googleAuthStart: (init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('GET', '/api/google/auth/start', undefined, undefined, init),
+  /** GET /api/google/oauth2/callback */
+  googleOauth2Callback: (query: { code: string; state: string; scope?: string }, init?: RequestInit): Promise<Response> =>
+    request('GET', '/api/google/oauth2/callback', query, undefined, init),
+  /** DELETE /api/memory/delete/{key} */
+  memoryDelete: (key: string, init?: RequestInit): Promise<StatusOkResponse> =>
+    requestJson<StatusOkResponse>('DELETE', `/api/memory/delete/${encodeURIComponent(key)}`, undefined, undefined, init),
+  /** GET /api/memory/get/{key} */
+  memoryGet: (key: string, init?: RequestInit): Promise<MemoryItem> =>
+    requestJson<MemoryItem>('GET', `/api/memory/get/${encodeURIComponent(key)}`, undefined, undefined, init),
+  /** GET /api/memory/search */
+  memorySearch: (query?: { q?: string; limit?: number }, init?: RequestInit): Promise<MemorySearchResponse> =>
+    requestJson<MemorySearchResponse>('GET', '/api/memory/search', query, undefined, init),
+  /** POST /api/memory/store */
+  memoryStore: (body: MemoryStoreRequest, init?: RequestInit): Promise<StatusOkResponse> =>
+    requestJson<StatusOkResponse>('POST', '/api/memory/store', undefined, body, init),
+  /** GET /api/memory/vector/all */
+  memoryVectorAll: (init?: RequestInit): Promise<VectorMemoryAllResponse> =>
+    requestJson<VectorMemoryAllResponse>('GET', '/api/memory/vector/all', undefined, undefined, init),
+  /** GET /api/memory/vector/search */
+  memoryVectorSearch: (query?: { q?: string; k?: number }, init?: RequestInit): Promise<VectorMemorySearchResponse> =>
+    requestJson<VectorMemorySearchResponse>('GET', '/api/memory/vector/search', query, undefined, init),
+  /** POST /api/memory/vector/store */
+  memoryVectorStore: (body: VectorMemoryStoreRequest, init?: RequestInit): Promise<VectorMemoryStoreResponse> =>
+    requestJson<VectorMemoryStoreResponse>('POST', '/api/memory/vector/store', undefined, body, init),
+  /** GET /api/name */
+  name: (init?: RequestInit): Promise<NameResponse> =>
+    requestJson<NameResponse>('GET', '/api/name', undefined, undefined, init),
+  /** GET /api/openapi.json — this document. */
+  openapi: (init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('GET', '/api/openapi.json', undefined, undefined, init),
+  /** GET /api/relational-state */
+  relationalStateGet: (init?: RequestInit): Promise<RelationalStateResponse> =>
+    requestJson<RelationalStateResponse>('GET', '/api/relational-state', undefined, undefined, init),
+  /** POST /api/relational-state */
+  relationalStateUpdate: (body: RelationalStateUpdateRequest, init?: RequestInit): Promise<RelationalStateResponse> =>
+    requestJson<RelationalStateResponse>('POST', '/api/relational-state', undefined, body, init),
+  /** GET /api/sessions */
+  sessionsList: (init?: RequestInit): Promise<ConversationSummary[]> =>
+    requestJson<ConversationSummary[]>('GET', '/api/sessions', undefined, undefined, init),
+  /** POST /api/sessions */
+  sessionsCreate: (body?: SessionCreateRequest | null, init?: RequestInit): Promise<Conversation> =>
+    requestJson<Conversation>('POST', '/api/sessions', undefined, body, init),
+  /** POST /api/sessions/import */
+  sessionsImport: (body: Conversation, init?: RequestInit): Promise<Conversation> =>
+    requestJson<Conversation>('POST', '/api/sessions/import', undefined, body, init),
+  /** GET /api/sessions/{id} */
+  sessionsGet: (id: string, init?: RequestInit): Promise<Conversation> =>
+    requestJson<Conversation>('GET', `/api/sessions/${encodeURIComponent(id)}`, undefined, undefined, init),
+  /** PATCH /api/sessions/{id} */
+  sessionsRename: (id: string, body: SessionRenameRequest, init?: RequestInit): Promise<Conversation> =>
+    requestJson<Conversation>('PATCH', `/api/sessions/${encodeURIComponent(id)}`, undefined, body, init),
+  /** DELETE /api/sessions/{id} */
+  sessionsDelete: (id: string, init?: RequestInit): Promise<StatusOkResponse> =>
+    requestJson<StatusOkResponse>('DELETE', `/api/sessions/${encodeURIComponent(id)}`, undefined, undefined, init),
+  /** GET /api/sessions/{id}/export */
+  sessionsExport: (id: string, query?: { format?: string }, init?: RequestInit): Promise<Response> =>
+    request('GET', `/api/sessions/${encodeURIComponent(id)}/export`, query, undefined, init),
+  /** GET /api/sessions/{id}/messages */
+  sessionsMessages: (id: string, init?: RequestInit): Promise<ConversationMessage[]> =>
+    requestJson<ConversationMessage[]>('GET', `/api/sessions/${encodeURIComponent(id)}/messages`, undefined, undefined, init),
+  /** POST /api/skills/install */
+  skillsInstall: (body: SkillInstallRequest, init?: RequestInit): Promise<Record<string, unknown>[]> =>
+    requestJson<Record<string, unknown>[]>('POST', '/api/skills/install', undefined, body, init),
+  /** GET /api/skills/installed */
+  skillsInstalled: (init?: RequestInit): Promise<Record<string, unknown>[]> =>
+    requestJson<Record<string, unknown>[]>('GET', '/api/skills/installed', undefined, undefined, init),
+  /** GET /api/skills/list */
+  skillsList: (init?: RequestInit): Promise<Record<string, unknown>[]> =>
+    requestJson<Record<string, unknown>[]>('GET', '/api/skills/list', undefined, undefined, init),
+  /** GET /api/skills/registry */
+  skillsRegistrySearch: (query?: { q?: string; category?: string; tag?: string; all_versions?: boolean }, init?: RequestInit): Promise<Record<string, unknown>[]> =>
+    requestJson<Record<string, unknown>[]>('GET', '/api/skills/registry', query, undefined, init),
+  /** GET /api/skills/registry/bundles/{file} */
+  skillsRegistryBundle: (file: string, init?: RequestInit): Promise<Response> =>
+    request('GET', `/api/skills/registry/bundles/${encodeURIComponent(file)}`, undefined, undefined, init),
+  /** GET /api/skills/registry/index.json — Serve the raw registry index so other instances can use this one as an HTTP registry. */
+  skillsRegistryIndex: (init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('GET', '/api/skills/registry/index.json', undefined, undefined, init),
+  /** POST /api/skills/registry/publish */
+  skillsRegistryPublish: (body: SkillPublishRequest, init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('POST', '/api/skills/registry/publish', undefined, body, init),
+  /** POST /api/skills/uninstall */
+  skillsUninstall: (body: SkillUninstallRequest, init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('POST', '/api/skills/uninstall', undefined, body, init),
+  /** POST /api/skills/upgrade */
+  skillsUpgrade: (body: SkillUpgradeRequest, init?: RequestInit): Promise<Record<string, unknown>[]> =>
+    requestJson<Record<string, unknown>[]>('POST', '/api/skills/upgrade', undefined, body, init),
+  /** POST /api/speak */
+  speak: (body: SpeakRequest, init?: RequestInit): Promise<Record<string, unknown>> =>
+    requestJson<Record<string, unknown>>('POST', '/api/speak', undefined, body, init),
+  /** POST /api/speak/stream — like `/api/speak`, but streams the reply as server-sent events. */
+  speakStream: (body: SpeakRequest, init?: RequestInit): Promise<Response> =>
+    request('POST', '/api/speak/stream', undefined, body, init),
+  /** GET /api/status */
+  status: (init?: RequestInit): Promise<StatusResponse> =>
+    requestJson<StatusResponse>('GET', '/api/status', undefined, undefined, init),
+  /** POST /api/system/exec */
+  systemExec: (body: ExecRequest, init?: RequestInit): Promise<ExecResponse> =>
+    requestJson<ExecResponse>('POST', '/api/system/exec', undefined, body, init),
+  /** POST /api/system/read-file */
+  systemReadFile: (body: ReadFileRequest, init?: RequestInit): Promise<ReadFileResponse> =>
+    requestJson<ReadFileResponse>('POST', '/api/system/read-file', undefined, body, init),
+  /** GET /api/system/status */
+  systemStatus: (init?: RequestInit): Promise<SystemStatusResponse> =>
+    requestJson<SystemStatusResponse>('GET', '/api/system/status', undefined, undefined, init),
+  /** POST /api/system/write-file */
+  systemWriteFile: (body: WriteFileRequest, init?: RequestInit): Promise<StatusOkResponse> =>
+    requestJson<StatusOkResponse>('POST', '/api/system/write-file', undefined, body, init),
+};
@@ -0,0 +1,75 @@
+// Browser authentication for the Phoenix API.
+//
+// The backend accepts `Authorization: Bearer <token>` or a session cookie. The UI exchanges an API
+// token for the cookie once (POST /api/auth/session) and echoes the session's CSRF token on every
+// state-changing request.
+
+const PHOENIX_API_BASE =
+  ((import.meta as any).env?.VITE_PHOENIX_API_BASE as string | undefined)?.replace(/\/$/, '') || '';
+
+/** `path` on the configured API base (`VITE_PHOENIX_API_BASE`), or same-origin / the Vite dev proxy. */
+export function apiUrl(path: string) {
+  return PHOENIX_API_BASE ? `${PHOENIX_API_BASE}${path}` : path;
+}
+
+let csrfToken: string | null = null;
+let signInInFlight: Promise<boolean> | null = null;
+
+async function restoreSession(): Promise<boolean> {
+  try {
+    const res = await fetch(apiUrl('/api/auth/session'), { credentials: 'include' });
+    if (!res.ok) return false;
+    const j = await res.json();
+    csrfToken = typeof j.csrf_token === 'string' ? j.csrf_token : null;
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+async function signIn(): Promise<boolean> {
+  if (await restoreSession()) return true;
+  const token = window.prompt('Phoenix API token (printed to data/api_token on the server by default):');
+  if (!token || !token.trim()) return false;
+  const res = await fetch(apiUrl('/api/auth/session'), {
+    method: 'POST',
+    credentials: 'include',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ token: token.trim() }),
+  });
+  if (!res.ok) return false;
+  const j = await res.json();
+  csrfToken = typeof j.csrf_token === 'string' ? j.csrf_token : null;
+  return true;
+}
+
+function ensureSignedIn(): Promise<boolean> {
+  if (!signInInFlight) {
+    signInInFlight = signIn().finally(() => {
+      signInInFlight = null;
+    });
+  }
+  return signInInFlight;
+}
+
+/** `fetch` for Phoenix API URLs: sends the session cookie and CSRF token, and signs in on 401. */
+export async function apiFetch(input: string, init: RequestInit = {}): Promise<Response> {
+  const method = (init.method || 'GET').toUpperCase();
+  const unsafe = method !== 'GET' && method !== 'HEAD';
+  if (unsafe && !csrfToken) await restoreSession();
+
+  const send = () => {
+    const headers = new Headers(init.headers);
+    if (unsafe && csrfToken) headers.set('X-CSRF-Token', csrfToken);
+    return fetch(input, { ...init, headers, credentials: 'include' });
+  };
+
+  const res = await send();
+  if (res.status === 401 && (await ensureSignedIn())) return send();
+  return res;
+}
+
+export async function signOut(): Promise<void> {
+  await fetch(apiUrl('/api/auth/session'), { method: 'DELETE', credentials: 'include' });
+  csrfToken = null;
+}
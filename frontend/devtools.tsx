@@ -1,25 +1,13 @@
 import React, { useEffect, useMemo, useState } from 'react';
-
-type SystemStatus = {
-  full_access_granted: boolean;
-  self_modification_enabled: boolean;
-};
-
-const PHOENIX_API_BASE =
-  ((import.meta as any).env?.VITE_PHOENIX_API_BASE as string | undefined)?.replace(/\/$/, '') || '';
-
-function apiUrl(path: string) {
-  // If VITE_PHOENIX_API_BASE isn't set, we rely on same-origin (prod) or Vite dev proxy.
-  return PHOENIX_API_BASE ? `${PHOENIX_API_BASE}${path}` : path;
-}
+import { phoenixApi, type ExecResponse, type SystemStatusResponse } from './api';
 
 export const DevToolsView: React.FC = () => {
-  const [status, setStatus] = useState<SystemStatus | null>(null);
+  const [status, setStatus] = useState<SystemStatusResponse | null>(null);
   const [loading, setLoading] = useState<string | null>(null);
 
   const [cmd, setCmd] = useState('cargo --version');
   const [cwd, setCwd] = useState('');
-  const [execOut, setExecOut] = useState<{ exit_code: number; stdout: string; stderr: string } | null>(null);
+  const [execOut, setExecOut] = useState<ExecResponse | null>(null);
   const [execErr, setExecErr] = useState<string | null>(null);
 
   const [readPath, setReadPath] = useState('README.md');
@@ -41,9 +29,7 @@ export const DevToolsView: React.FC = () => {
   const refreshStatus = async () => {
     setLoading('status');
     try {
-      const res = await fetch(apiUrl('/api/system/status'));
-      const j = await res.json();
-      setStatus(j);
+      setStatus(await phoenixApi.systemStatus());
     } catch (e: any) {
       setStatus(null);
     } finally {
@@ -59,18 +45,7 @@ export const DevToolsView: React.FC = () => {
     setLoading('exec');
     setExecErr(null);
     try {
-      const res = await fetch(apiUrl('/api/system/exec'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ command: cmd, cwd: cwd.trim() ? cwd.trim() : undefined }),
-      });
-      const j = await res.json();
-      if (!res.ok) {
-        setExecOut(null);
-        setExecErr(j?.message || `Exec failed (${res.status})`);
-        return;
-      }
-      setExecOut(j);
+      setExecOut(await phoenixApi.systemExec({ command: cmd, cwd: cwd.trim() ? cwd.trim() : undefined }));
     } catch (e: any) {
       setExecOut(null);
       setExecErr(e?.message || String(e));
@@ -84,18 +59,8 @@ export const DevToolsView: React.FC = () => {
     setLoading('read');
     setReadErr(null);
     try {
-      const res = await fetch(apiUrl('/api/system/read-file'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ path: readPath }),
-      });
-      const j = await res.json();
-      if (!res.ok) {
-        setReadContent('');
-        setReadErr(j?.message || `Read failed (${res.status})`);
-        return;
-      }
-      setReadContent(j?.content ?? '');
+      const j = await phoenixApi.systemReadFile({ path: readPath });
+      setReadContent(j.content);
     } catch (e: any) {
       setReadContent('');
       setReadErr(e?.message || String(e));
@@ -110,16 +75,7 @@ export const DevToolsView: React.FC = () => {
     setWriteErr(null);
     setWriteOk(false);
     try {
-      const res = await fetch(apiUrl('/api/system/write-file'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ path: writePath, content: writeContent }),
-      });
-      const j = await res.json();
-      if (!res.ok) {
-        setWriteErr(j?.message || `Write failed (${res.status})`);
-        return;
-      }
+      await phoenixApi.systemWriteFile({ path: writePath, content: writeContent });
       setWriteOk(true);
     } catch (e: any) {
       setWriteErr(e?.message || String(e));
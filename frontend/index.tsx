@@ -2,6 +2,17 @@ import './styles.css';
 import React, { useState, useEffect, useRef, useMemo, useCallback, createContext, useContext } from 'react';
 import { createRoot } from 'react-dom/client';
 import { DevToolsView } from './devtools';
+import {
+  phoenixApi,
+  ApiError,
+  type MemoryItem,
+  type MemorySearchResponse,
+  type MemoryResult as VectorMemoryResult,
+  type VectorMemoryAllResponse,
+  type VectorMemorySearchResponse,
+  type BuildSystem,
+  type RepoMetadata,
+} from './api';
 import {
   MessageSquare, Heart, Settings, Activity, Zap, Send, Menu, X,
   Sparkles, ShieldCheck, Cpu, Mic, Brain, ChevronRight, ArrowRight,
@@ -112,39 +123,6 @@ interface UiSettings {
   mouseJiggerEnabled: boolean;
 }
 
-interface MemoryItem {
-  key: string;
-  value: string;
-}
-
-interface MemorySearchResponse {
-  items: MemoryItem[];
-  count: number;
-}
-
-interface VectorMemoryResult {
-  id: string;
-  text: string;
-  score: number; // 0..1
-  metadata: Record<string, unknown>;
-}
-
-interface VectorMemorySearchResponse {
-  results: VectorMemoryResult[];
-  count: number;
-}
-
-interface VectorMemoryEntrySummary {
-  id: string;
-  text: string;
-  metadata: Record<string, unknown>;
-}
-
-interface VectorMemoryAllResponse {
-  entries: VectorMemoryEntrySummary[];
-  count: number;
-}
-
 interface BackendConfig {
   openrouter_api_key_set: boolean;
   user_name: string | null;
@@ -191,6 +169,12 @@ function sanitizeCommandParam(value: string) {
 
 // --- Static Data ---
 
+const STYLE_BIASES: Archetype['styleBias'][] = ['Direct', 'Playful', 'Thoughtful', 'Warm', 'Reflective'];
+
+function isStyleBias(style: string): style is Archetype['styleBias'] {
+  return (STYLE_BIASES as string[]).includes(style);
+}
+
 const ARCHETYPES_DB: Archetype[] = [
   {
     id: 'aries', sign: 'Aries', name: 'The Trailblazer', tagline: 'Direct, fiery, and fiercely loyal.',
@@ -310,13 +294,8 @@ const MOCK_AGENTS: Agent[] = [
   }
 ];
 
-// --- Mock Phoenix Backend Service ---
-const PHOENIX_API_BASE = ((import.meta as any).env?.VITE_PHOENIX_API_BASE as string | undefined)?.replace(/\/$/, '') || '';
-
-function apiUrl(path: string) {
-  // If VITE_PHOENIX_API_BASE isn't set, we rely on the Vite dev proxy (`/api/*` to the backend).
-  return PHOENIX_API_BASE ? `${PHOENIX_API_BASE}${path}` : path;
-}
+// --- Phoenix Backend Service ---
+// Calls go through the generated client in `./api` (see `pagi-sola-web --write-api-client`).
 
 class PhoenixBackendService {
   private currentArchetype: Archetype | null = null;
@@ -329,10 +308,6 @@ class PhoenixBackendService {
     }
   ];
 
-  private url(path: string) {
-    return apiUrl(path);
-  }
-
   appendToHistory(msg: Message) {
     this.messageHistory = [...this.messageHistory, msg];
   }
@@ -342,102 +317,46 @@ class PhoenixBackendService {
   }
 
   async memoryStore(key: string, value: string): Promise<void> {
-    const res = await fetch(this.url('/api/memory/store'), {
-      method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ key, value }),
-    });
-    if (!res.ok) {
-      const text = await res.text().catch(() => '');
-      throw new Error(`store ${res.status}${text ? `: ${text}` : ''}`);
-    }
+    await phoenixApi.memoryStore({ key, value });
   }
 
   async memoryGet(key: string, signal?: AbortSignal): Promise<MemoryItem | null> {
-    const res = await fetch(this.url(`/api/memory/get/${encodeURIComponent(key)}`), { signal });
-    if (res.status === 404) return null;
-    if (!res.ok) {
-      const text = await res.text().catch(() => '');
-      throw new Error(`get ${res.status}${text ? `: ${text}` : ''}`);
+    try {
+      return await phoenixApi.memoryGet(key, { signal });
+    } catch (e) {
+      if (e instanceof ApiError && e.status === 404) return null;
+      throw e;
     }
-    const value = await res.text();
-    return { key, value };
   }
 
   async memorySearch(q: string, limit: number, signal?: AbortSignal): Promise<MemorySearchResponse> {
-    const params = new URLSearchParams({ q, limit: String(limit) });
-    const res = await fetch(this.url(`/api/memory/search?${params.toString()}`), { signal });
-    if (!res.ok) {
-      const text = await res.text().catch(() => '');
-      throw new Error(`search ${res.status}${text ? `: ${text}` : ''}`);
-    }
-    const j = (await res.json()) as Partial<MemorySearchResponse>;
-    return {
-      items: Array.isArray(j.items) ? (j.items as MemoryItem[]) : [],
-      count: typeof j.count === 'number' ? j.count : (Array.isArray(j.items) ? j.items.length : 0),
-    };
+    return phoenixApi.memorySearch({ q, limit }, { signal });
   }
 
   async memoryDelete(key: string): Promise<void> {
-    const res = await fetch(this.url(`/api/memory/delete/${encodeURIComponent(key)}`), {
-      method: 'DELETE',
-    });
-    if (!res.ok) {
-      const text = await res.text().catch(() => '');
-      throw new Error(`delete ${res.status}${text ? `: ${text}` : ''}`);
-    }
+    await phoenixApi.memoryDelete(key);
   }
 
   async vectorStore(text: string, metadata: Record<string, unknown>): Promise<{ id: string }> {
-    const res = await fetch(this.url('/api/memory/vector/store'), {
-      method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ text, metadata: metadata ?? {} }),
-    });
-    if (!res.ok) {
-      const t = await res.text().catch(() => '');
-      throw new Error(`vector store ${res.status}${t ? `: ${t}` : ''}`);
-    }
-    const j = await res.json();
+    const j = await phoenixApi.memoryVectorStore({ text, metadata: metadata ?? {} });
     return { id: j.id };
   }
 
   async vectorSearch(q: string, k: number, signal?: AbortSignal): Promise<VectorMemorySearchResponse> {
-    const params = new URLSearchParams({ q, k: String(k) });
-    const res = await fetch(this.url(`/api/memory/vector/search?${params.toString()}`), { signal });
-    if (!res.ok) {
-      const t = await res.text().catch(() => '');
-      throw new Error(`vector search ${res.status}${t ? `: ${t}` : ''}`);
-    }
-    const j = (await res.json()) as Partial<VectorMemorySearchResponse>;
-    return {
-      results: Array.isArray(j.results) ? (j.results as VectorMemoryResult[]) : [],
-      count: typeof j.count === 'number' ? j.count : (Array.isArray(j.results) ? j.results.length : 0),
-    };
+    return phoenixApi.memoryVectorSearch({ q, k }, { signal });
   }
 
   async vectorAll(signal?: AbortSignal): Promise<VectorMemoryAllResponse> {
-    const res = await fetch(this.url('/api/memory/vector/all'), { signal });
-    if (!res.ok) {
-      const t = await res.text().catch(() => '');
-      throw new Error(`vector all ${res.status}${t ? `: ${t}` : ''}`);
-    }
-    const j = (await res.json()) as Partial<VectorMemoryAllResponse>;
-    return {
-      entries: Array.isArray(j.entries) ? (j.entries as VectorMemoryEntrySummary[]) : [],
-      count: typeof j.count === 'number' ? j.count : (Array.isArray(j.entries) ? j.entries.length : 0),
-    };
+    return phoenixApi.memoryVectorAll({ signal });
   }
 
   async status(): Promise<{ status: string; version: string; archetype: string | null }> {
     try {
-      const res = await fetch(this.url('/api/status'));
-      if (!res.ok) throw new Error(`status ${res.status}`);
-      const j = await res.json();
+      const j = await phoenixApi.status();
       return {
-        status: j.status ?? 'offline',
-        version: j.version ?? 'unknown',
-        archetype: j.archetype ?? this.currentArchetype?.name ?? null,
+        status: j.status,
+        version: j.version,
+        archetype: j.archetype || this.currentArchetype?.name || null,
       };
     } catch {
       return {
@@ -449,79 +368,40 @@ class PhoenixBackendService {
   }
 
   async getConfig(signal?: AbortSignal): Promise<BackendConfig> {
-    const res = await fetch(this.url('/api/config'), { signal });
-    if (!res.ok) {
-      const t = await res.text().catch(() => '');
-      throw new Error(`config ${res.status}${t ? `: ${t}` : ''}`);
-    }
-    const j = await res.json();
+    const j = await phoenixApi.configGet({ signal });
     return {
-      openrouter_api_key_set: !!j.openrouter_api_key_set,
-      user_name: typeof j.user_name === 'string' ? j.user_name : null,
-      user_preferred_alias: typeof j.user_preferred_alias === 'string' ? j.user_preferred_alias : null,
+      openrouter_api_key_set: j.openrouter_api_key_set,
+      user_name: j.user_name ?? null,
+      user_preferred_alias: j.user_preferred_alias ?? null,
     };
   }
 
   async setConfig(update: { openrouter_api_key?: string; user_name?: string; user_preferred_alias?: string }): Promise<BackendConfig & { llm_status: string }> {
-    const res = await fetch(this.url('/api/config'), {
-      method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify(update),
-    });
-    const t = await res.text().catch((err) => {
-      console.error("Failed to read response text:", err);
-      return '';
-    });
-    
-    if (!res.ok) {
-      try {
-        const j = JSON.parse(t);
-        throw new Error(j?.message || `config set ${res.status}`);
-      } catch (parseError) {
-        console.error("Failed to parse error response:", parseError);
-        throw new Error(`config set ${res.status}${t ? `: ${t}` : ''}`);
-      }
-    }
-    
-    let j;
-    try {
-      j = JSON.parse(t);
-    } catch (parseError) {
-      console.error("Failed to parse success response:", parseError);
-      throw new Error("Invalid JSON response from server");
-    }
+    const j = await phoenixApi.configSet(update);
     return {
-      openrouter_api_key_set: !!j.openrouter_api_key_set,
-      user_name: typeof j.user_name === 'string' ? j.user_name : null,
-      user_preferred_alias: typeof j.user_preferred_alias === 'string' ? j.user_preferred_alias : null,
-      llm_status: typeof j.llm_status === 'string' ? j.llm_status : 'unknown',
+      openrouter_api_key_set: j.openrouter_api_key_set,
+      user_name: j.user_name ?? null,
+      user_preferred_alias: j.user_preferred_alias ?? null,
+      llm_status: j.llm_status,
     };
   }
 
   async sendCommand(command: string): Promise<string> {
     try {
-      const res = await fetch(this.url('/api/command'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ command })
-      });
-      const text = await res.text();
-      if (!res.ok) {
-        console.error(`API error: ${res.status}`, text);
-        return JSON.stringify({ type: 'error', message: `Backend error: ${res.status} ${text}` });
-      }
-      // The backend returns JSON (string). Preserve as-is so callers can JSON.parse if desired.
-      return text;
+      // Callers JSON.parse the reply, so hand it back as a string.
+      return JSON.stringify(await phoenixApi.command({ command }));
     } catch (e: any) {
+      if (e instanceof ApiError) {
+        console.error(`API error: ${e.status}`, e.body);
+        return JSON.stringify({ type: 'error', message: `Backend error: ${e.status} ${e.message}` });
+      }
       return JSON.stringify({ type: 'error', message: `Backend offline: ${e?.message || String(e)}` });
     }
   }
 
   async getPhoenixName(): Promise<string> {
     try {
-      const res = await fetch(this.url('/api/name'));
-      if (!res.ok) throw new Error(`name ${res.status}`);
-      const j = await res.json();
+      const j = await phoenixApi.name();
       return j.name || 'Sola';
     } catch {
       return 'Sola';
@@ -530,24 +410,22 @@ class PhoenixBackendService {
 
   async matchArchetype(profile: DatingProfile): Promise<Archetype[]> {
     try {
-      const response = await fetch(this.url('/api/archetype/match'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify(profile)
+      const result = await phoenixApi.archetypeMatch(profile);
+      return result.matches.map((match) => {
+        const known = ARCHETYPES_DB.find(a => a.sign === match.sign);
+        return {
+          id: match.sign.toLowerCase(),
+          sign: match.sign,
+          name: match.name,
+          tagline: known?.tagline ?? '',
+          description: match.description,
+          matchScore: Math.floor(match.compatibility),
+          // The backend also reports styles the UI has no card for (e.g. `Empathetic`).
+          styleBias: isStyleBias(match.styleBias) ? match.styleBias : (known?.styleBias ?? 'Warm'),
+          traits: match.traits,
+          avatarGradient: known?.avatarGradient ?? 'from-phoenix-500 to-purple-600',
+        };
       });
-      
-      if (!response.ok) throw new Error('Failed to match archetype');
-      
-      const result = await response.json();
-      return result.matches.map((match: any) => ({
-        id: match.sign.toLowerCase(),
-        sign: match.sign,
-        name: match.name,
-        description: match.description,
-        matchScore: Math.floor(match.compatibility),
-        styleBias: match.styleBias,
-        traits: match.traits
-      }));
     } catch (error) {
       console.error('Error matching archetype:', error);
       // Fallback to mock if API fails
@@ -567,18 +445,7 @@ class PhoenixBackendService {
       const arch = ARCHETYPES_DB.find(a => a.id === archetypeId);
       if (!arch) return false;
       
-      const response = await fetch(this.url('/api/archetype/apply'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({
-          sign: arch.sign,
-          profile: profile
-        })
-      });
-      
-      if (!response.ok) throw new Error('Failed to apply archetype');
-      
-      const result = await response.json();
+      const result = await phoenixApi.archetypeApply({ sign: arch.sign, profile });
       if (result.success) {
         this.currentArchetype = arch;
         this.messageHistory.push({
@@ -3241,7 +3108,7 @@ const SidebarItem: React.FC<SidebarItemProps> = ({ icon: Icon, label, active, on
 // --- EcoSystem View ---
 
 const EcoSystemView = () => {
-  const [repos, setRepos] = useState<any[]>([]);
+  const [repos, setRepos] = useState<RepoMetadata[]>([]);
   const [loading, setLoading] = useState(false);
   const [importForm, setImportForm] = useState({ owner: '', repo: '', branch: '' });
   const [selectedRepo, setSelectedRepo] = useState<string | null>(null);
@@ -3249,11 +3116,7 @@ const EcoSystemView = () => {
   const loadRepos = async () => {
     setLoading(true);
     try {
-      const res = await fetch(apiUrl('/api/ecosystem/list'));
-      if (res.ok) {
-        const data = await res.json();
-        setRepos(data);
-      }
+      setRepos(await phoenixApi.ecosystemList());
     } catch (e) {
       console.error('Failed to load repos', e);
     }
@@ -3268,24 +3131,15 @@ const EcoSystemView = () => {
     if (!importForm.owner || !importForm.repo) return;
     setLoading(true);
     try {
-      const res = await fetch(apiUrl('/api/ecosystem/import'), {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({
-          owner: importForm.owner,
-          repo: importForm.repo,
-          branch: importForm.branch || undefined,
-        }),
+      await phoenixApi.ecosystemImport({
+        owner: importForm.owner,
+        repo: importForm.repo,
+        branch: importForm.branch || undefined,
       });
-      if (res.ok) {
-        setImportForm({ owner: '', repo: '', branch: '' });
-        await loadRepos();
-      } else {
-        const error = await res.json();
-        alert(`Import failed: ${error.error || 'Unknown error'}`);
-      }
+      setImportForm({ owner: '', repo: '', branch: '' });
+      await loadRepos();
     } catch (e) {
-      alert(`Import failed: ${e}`);
+      alert(`Import failed: ${e instanceof ApiError ? e.message : e}`);
     }
     setLoading(false);
   };
@@ -3293,15 +3147,10 @@ const EcoSystemView = () => {
   const handleBuild = async (repoId: string) => {
     setLoading(true);
     try {
-      const res = await fetch(apiUrl(`/api/ecosystem/${repoId}/build`), { method: 'POST' });
-      if (res.ok) {
-        await loadRepos();
-      } else {
-        const error = await res.json();
-        alert(`Build failed: ${error.error || 'Unknown error'}`);
-      }
+      await phoenixApi.ecosystemBuild(repoId);
+      await loadRepos();
     } catch (e) {
-      alert(`Build failed: ${e}`);
+      alert(`Build failed: ${e instanceof ApiError ? e.message : e}`);
     }
     setLoading(false);
   };
@@ -3309,15 +3158,10 @@ const EcoSystemView = () => {
   const handleStart = async (repoId: string) => {
     setLoading(true);
     try {
-      const res = await fetch(apiUrl(`/api/ecosystem/${repoId}/start`), { method: 'POST' });
-      if (res.ok) {
-        await loadRepos();
-      } else {
-        const error = await res.json();
-        alert(`Start failed: ${error.error || 'Unknown error'}`);
-      }
+      await phoenixApi.ecosystemStart(repoId);
+      await loadRepos();
     } catch (e) {
-      alert(`Start failed: ${e}`);
+      alert(`Start failed: ${e instanceof ApiError ? e.message : e}`);
     }
     setLoading(false);
   };
@@ -3325,15 +3169,10 @@ const EcoSystemView = () => {
   const handleStop = async (repoId: string) => {
     setLoading(true);
     try {
-      const res = await fetch(apiUrl(`/api/ecosystem/${repoId}/stop`), { method: 'POST' });
-      if (res.ok) {
-        await loadRepos();
-      } else {
-        const error = await res.json();
-        alert(`Stop failed: ${error.error || 'Unknown error'}`);
-      }
+      await phoenixApi.ecosystemStop(repoId);
+      await loadRepos();
     } catch (e) {
-      alert(`Stop failed: ${e}`);
+      alert(`Stop failed: ${e instanceof ApiError ? e.message : e}`);
     }
     setLoading(false);
   };
@@ -3342,21 +3181,19 @@ const EcoSystemView = () => {
     if (!confirm('Are you sure you want to remove this repository?')) return;
     setLoading(true);
     try {
-      const res = await fetch(apiUrl(`/api/ecosystem/${repoId}`), { method: 'DELETE' });
-      if (res.ok) {
-        await loadRepos();
-      } else {
-        const error = await res.json();
-        alert(`Remove failed: ${error.error || 'Unknown error'}`);
-      }
+      await phoenixApi.ecosystemRemove(repoId);
+      await loadRepos();
     } catch (e) {
-      alert(`Remove failed: ${e}`);
+      alert(`Remove failed: ${e instanceof ApiError ? e.message : e}`);
     }
     setLoading(false);
   };
 
-  const getBuildSystemIcon = (system: string) => {
-    switch (system) {
+  const buildSystemLabel = (system: BuildSystem) =>
+    typeof system === 'string' ? system : system.Custom;
+
+  const getBuildSystemIcon = (system: BuildSystem) => {
+    switch (buildSystemLabel(system)) {
       case 'Cargo': return <Code size={16} className="text-orange-400" />;
       case 'Npm': return <Package size={16} className="text-green-400" />;
       case 'Pip': return <Code size={16} className="text-blue-400" />;
@@ -3448,7 +3285,7 @@ const EcoSystemView = () => {
           </div>
         ) : (
           <div className="grid grid-cols-1 lg:grid-cols-2 gap-6">
-            {repos.map((repo: any) => (
+            {repos.map((repo) => (
               <div
                 key={repo.id}
                 className="glass-panel rounded-2xl p-6 border border-white/5 hover:border-purple-500/30 transition-all"
@@ -3459,7 +3296,7 @@ const EcoSystemView = () => {
                       {getBuildSystemIcon(repo.build_system)}
                       <h3 className="font-bold text-white">{repo.name}</h3>
                     </div>
-                    <p className="text-xs text-gray-400">{repo.owner}/{repo.repo || repo.name}</p>
+                    <p className="text-xs text-gray-400">{repo.owner}/{repo.name}</p>
                   </div>
                   <button
                     onClick={() => handleRemove(repo.id)}
@@ -3473,7 +3310,7 @@ const EcoSystemView = () => {
                 <div className="space-y-3 mb-4">
                   <div className="flex items-center justify-between text-xs">
                     <span className="text-gray-500">Build System:</span>
-                    <span className="text-white font-medium">{repo.build_system}</span>
+                    <span className="text-white font-medium">{buildSystemLabel(repo.build_system)}</span>
                   </div>
                   <div className="flex items-center justify-between text-xs">
                     <span className="text-gray-500">Build Status:</span>
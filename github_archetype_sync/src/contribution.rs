@@ -0,0 +1,304 @@
+//! Turning a [`FederatedContribution`] into a change to one archetype file.
+//!
+//! - `prompt_update`: `data` (a string, or `{"master_system_prompt": "..."}`) replaces
+//!   `<archetype>/master_system_prompt.txt`.
+//! - `personality_update`: `data` is a JSON merge patch (RFC 7396) applied to
+//!   `<archetype>/personality_db.json`; the result must still parse as a [`PersonalityDatabase`].
+//! - anything else is recorded verbatim as `<archetype>/contributions/<instance>-<timestamp>.json`.
+
+use config_manager::archetype::slug;
+use config_manager::PersonalityDatabase;
+use error_types::ConfigError;
+
+use crate::FederatedContribution;
+
+pub use config_manager::archetype::{PERSONALITY_FILE, PROMPT_FILE};
+pub use config_manager::text_diff::{line_stats, LineStats};
+
+pub const PROMPT_UPDATE: &str = "prompt_update";
+pub const PERSONALITY_UPDATE: &str = "personality_update";
+
+/// The file a contribution writes, with its content before and after.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// Path relative to the repository root.
+    pub path: String,
+    /// `None` when the file does not exist yet.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+impl FileChange {
+    pub fn stats(&self) -> LineStats {
+        line_stats(self.before.as_deref().unwrap_or(""), &self.after)
+    }
+}
+
+/// Whether the contribution edits a shared archetype file (and can therefore conflict).
+pub fn edits_shared_file(contribution: &FederatedContribution) -> bool {
+    matches!(contribution.contribution_type.as_str(), PROMPT_UPDATE | PERSONALITY_UPDATE)
+}
+
+/// Repository path the contribution writes.
+pub fn target_path(archetype_name: &str, contribution: &FederatedContribution) -> String {
+    match contribution.contribution_type.as_str() {
+        PROMPT_UPDATE => format!("{archetype_name}/{PROMPT_FILE}"),
+        PERSONALITY_UPDATE => format!("{archetype_name}/{PERSONALITY_FILE}"),
+        _ => format!(
+            "{archetype_name}/contributions/{}-{}.json",
+            slug(&contribution.instance_id),
+            slug(&contribution.timestamp)
+        ),
+    }
+}
+
+/// Branch a pull-request contribution is pushed to.
+pub fn branch_name(archetype_name: &str, contribution: &FederatedContribution) -> String {
+    format!(
+        "federated-learning/{}/{}-{}-{}",
+        slug(archetype_name),
+        slug(&contribution.contribution_type),
+        slug(&contribution.instance_id),
+        slug(&contribution.timestamp)
+    )
+}
+
+pub fn commit_message(archetype_name: &str, contribution: &FederatedContribution) -> String {
+    format!(
+        "Federated learning: {} for {} from {}",
+        contribution.contribution_type, archetype_name, contribution.instance_id
+    )
+}
+
+pub fn pr_title(archetype_name: &str, contribution: &FederatedContribution) -> String {
+    format!("Federated Learning: {} - {}", archetype_name, contribution.contribution_type)
+}
+
+/// RFC 7396 JSON merge patch: objects merge recursively, `null` deletes, anything else replaces.
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let Some(patch_obj) = patch.as_object() else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::json!({});
+    }
+    let target_obj = target.as_object_mut().expect("target is an object");
+    for (key, value) in patch_obj {
+        if value.is_null() {
+            target_obj.remove(key);
+        } else {
+            merge_patch(target_obj.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
+
+/// Dotted paths of the leaves a merge patch sets or deletes (`traits.curiosity`).
+fn patched_keys(patch: &serde_json::Value, prefix: &str, out: &mut Vec<String>) {
+    match patch.as_object() {
+        Some(obj) => {
+            for (key, value) in obj {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                patched_keys(value, &path, out);
+            }
+        }
+        None => out.push(prefix.to_string()),
+    }
+}
+
+/// Compute the file change for `contribution` given the file's current content.
+pub fn apply(
+    archetype_name: &str,
+    contribution: &FederatedContribution,
+    current: Option<&str>,
+) -> Result<FileChange, ConfigError> {
+    let path = target_path(archetype_name, contribution);
+    let after = match contribution.contribution_type.as_str() {
+        PROMPT_UPDATE => {
+            let data = &contribution.data;
+            data.as_str()
+                .or_else(|| data.get("master_system_prompt").and_then(|v| v.as_str()))
+                .ok_or_else(|| {
+                    ConfigError::InvalidValue(
+                        "data".to_string(),
+                        "prompt_update needs a string or {\"master_system_prompt\": ...}".to_string(),
+                    )
+                })?
+                .to_string()
+        }
+        PERSONALITY_UPDATE => {
+            if !contribution.data.is_object() {
+                return Err(ConfigError::InvalidValue(
+                    "data".to_string(),
+                    "personality_update needs a JSON object (merge patch)".to_string(),
+                ));
+            }
+            let mut db: serde_json::Value = match current {
+                Some(text) => serde_json::from_str(text)
+                    .map_err(|e| ConfigError::ParseError(format!("Failed to parse {path}: {e}")))?,
+                None => serde_json::json!({}),
+            };
+            merge_patch(&mut db, &contribution.data);
+            serde_json::from_value::<PersonalityDatabase>(db.clone()).map_err(|e| {
+                ConfigError::ParseError(format!("personality_update leaves an invalid {PERSONALITY_FILE}: {e}"))
+            })?;
+            let text = serde_json::to_string_pretty(&db)
+                .map_err(|e| ConfigError::ParseError(format!("Failed to serialize {path}: {e}")))?;
+            format!("{text}\n")
+        }
+        _ => {
+            let text = serde_json::to_string_pretty(contribution)
+                .map_err(|e| ConfigError::ParseError(format!("Failed to serialize contribution: {e}")))?;
+            format!("{text}\n")
+        }
+    };
+    Ok(FileChange {
+        path,
+        before: current.map(str::to_string),
+        after,
+    })
+}
+
+/// Refuse to apply a contribution over an upstream edit of the same file.
+///
+/// `at_base` is the file as of the contribution's `base_sha`, `current` as of `upstream_sha`.
+pub fn check_upstream(
+    path: &str,
+    base_sha: &str,
+    upstream_sha: &str,
+    at_base: Option<&str>,
+    current: Option<&str>,
+) -> Result<(), ConfigError> {
+    if base_sha == upstream_sha || at_base == current {
+        return Ok(());
+    }
+    Err(ConfigError::ArchetypeConflict(format!(
+        "{path} changed upstream since {} (now {}); pull the archetype and rebuild the contribution",
+        short_sha(base_sha),
+        short_sha(upstream_sha)
+    )))
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// Pull request description: who contributed what, and what it changes.
+pub fn pr_body(
+    archetype_name: &str,
+    contribution: &FederatedContribution,
+    change: &FileChange,
+    upstream_sha: Option<&str>,
+) -> String {
+    let stats = change.stats();
+    let mut out = format!(
+        "Federated Learning Contribution\n\n**Archetype**: {}\n**Instance ID**: {}\n**Type**: {}\n**Timestamp**: {}\n",
+        archetype_name, contribution.instance_id, contribution.contribution_type, contribution.timestamp
+    );
+    if let Some(base) = &contribution.base_sha {
+        out.push_str(&format!("**Based on**: {}\n", short_sha(base)));
+    }
+    if let Some(upstream) = upstream_sha {
+        out.push_str(&format!("**Upstream**: {}\n", short_sha(upstream)));
+    }
+
+    out.push_str("\n## Metrics\n\n| File | Status | + | − | Bytes |\n| --- | --- | ---: | ---: | ---: |\n");
+    out.push_str(&format!(
+        "| `{}` | {} | {} | {} | {} → {} |\n",
+        change.path,
+        if change.before.is_some() { "modified" } else { "added" },
+        stats.added,
+        stats.removed,
+        change.before.as_deref().map(str::len).unwrap_or(0),
+        change.after.len()
+    ));
+    if contribution.contribution_type == PERSONALITY_UPDATE {
+        let mut keys = Vec::new();
+        patched_keys(&contribution.data, "", &mut keys);
+        keys.sort();
+        out.push_str(&format!("\nPersonality keys changed ({}): {}\n", keys.len(), keys.join(", ")));
+    }
+
+    out.push_str(&format!(
+        "\n## Contribution Data\n```json\n{}\n```\n",
+        serde_json::to_string_pretty(&contribution.data).unwrap_or_else(|_| "{}".to_string())
+    ));
+
+    out.push_str("\n## Metadata\n");
+    if contribution.metadata.is_empty() {
+        out.push_str("\n(none)\n");
+    } else {
+        let mut metadata: Vec<_> = contribution.metadata.iter().collect();
+        metadata.sort();
+        out.push_str("\n| Key | Value |\n| --- | --- |\n");
+        for (key, value) in metadata {
+            out.push_str(&format!("| {key} | {value} |\n"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn contribution(kind: &str, data: serde_json::Value) -> FederatedContribution {
+        FederatedContribution {
+            instance_id: "Phoenix-01".to_string(),
+            timestamp: "2026-10-19T12:00:00Z".to_string(),
+            contribution_type: kind.to_string(),
+            data,
+            metadata: HashMap::from([("sessions".to_string(), "42".to_string())]),
+            base_sha: None,
+        }
+    }
+
+    #[test]
+    fn personality_update_merges_and_reports_keys() {
+        let current = r#"{"ffm_scores":{"openness":0.5,"conscientiousness":0.5,"extraversion":0.5,"agreeableness":0.5,"neuroticism":0.5},"traits":{"warmth":0.9,"sarcasm":0.2}}"#;
+        let c = contribution(
+            PERSONALITY_UPDATE,
+            serde_json::json!({"traits": {"curiosity": 0.8, "sarcasm": null}, "ffm_scores": {"openness": 0.7}}),
+        );
+        let change = apply("default", &c, Some(current)).unwrap();
+        assert_eq!(change.path, "default/personality_db.json");
+        let db: PersonalityDatabase = serde_json::from_str(&change.after).unwrap();
+        assert_eq!(db.ffm_scores.openness, 0.7);
+        assert_eq!(db.traits.get("curiosity"), Some(&0.8));
+        assert!(!db.traits.contains_key("sarcasm"));
+
+        let body = pr_body("default", &c, &change, Some("0123456789abcdef"));
+        assert!(body.contains("Personality keys changed (3): ffm_scores.openness, traits.curiosity, traits.sarcasm"));
+        assert!(body.contains("**Upstream**: 0123456"));
+        assert!(body.contains("| sessions | 42 |"));
+
+        let broken = contribution(PERSONALITY_UPDATE, serde_json::json!({"traits": {"warmth": "very"}}));
+        assert!(apply("default", &broken, Some(current)).is_err());
+    }
+
+    #[test]
+    fn prompt_update_paths_branches_and_stats() {
+        let c = contribution(PROMPT_UPDATE, serde_json::json!({"master_system_prompt": "You are Phoenix.\nBe kind.\n"}));
+        let change = apply("heartbound", &c, Some("You are Phoenix.\nBe brief.\n")).unwrap();
+        assert_eq!(change.path, "heartbound/master_system_prompt.txt");
+        assert_eq!(change.stats(), LineStats { added: 1, removed: 1 });
+        assert_eq!(
+            branch_name("heartbound", &c),
+            "federated-learning/heartbound/prompt-update-phoenix-01-2026-10-19t12-00-00z"
+        );
+
+        let other = contribution("telemetry", serde_json::json!({"score": 1}));
+        assert_eq!(target_path("heartbound", &other), "heartbound/contributions/phoenix-01-2026-10-19t12-00-00z.json");
+        assert!(!edits_shared_file(&other));
+    }
+
+    #[test]
+    fn upstream_edits_of_the_same_file_conflict() {
+        assert!(check_upstream("a/p.txt", "aaa", "aaa", Some("x"), Some("y")).is_ok());
+        assert!(check_upstream("a/p.txt", "aaa", "bbb", Some("x"), Some("x")).is_ok());
+        let err = check_upstream("a/p.txt", "aaaaaaaaaa", "bbbbbbbbbb", Some("x"), Some("y")).unwrap_err();
+        assert!(matches!(err, ConfigError::ArchetypeConflict(_)));
+        assert!(err.to_string().contains("a/p.txt changed upstream since aaaaaaa"));
+    }
+}
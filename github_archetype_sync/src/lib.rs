@@ -4,12 +4,23 @@
 //! - Download master_system_prompt.txt and personality_db.json from GitHub
 //! - Push local changes back to GitHub (federated learning contributions)
 //! - Sync archetype configurations across instances
+//!
+//! Contributions are committed through the GitHub Contents API (directly or on a PR branch),
+//! or, when `PHOENIX_ARCHETYPE_CHECKOUT` points at a local clone, into that checkout offline.
 
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use error_types::{ConfigError, PhoenixError};
+use config_manager::archetype::{self, ArchetypeCache, ArchetypeDiff, ArchetypeSource};
 use config_manager::{AGIConfig, PersonalityDatabase};
 
+pub mod contribution;
+mod local;
+
+use contribution::FileChange;
+
 /// Archetype synchronization client for GitHub operations.
 #[derive(Debug, Clone)]
 pub struct ArchetypeSync {
@@ -19,6 +30,10 @@ pub struct ArchetypeSync {
     branch: String,
     user_agent: String,
     client: reqwest::Client,
+    /// Local clone that receives contributions instead of GitHub (offline mode).
+    local_checkout: Option<PathBuf>,
+    /// Pulled archetypes, kept by commit so pulls still work offline.
+    cache: Option<ArchetypeCache>,
 }
 
 /// Federated learning contribution data.
@@ -39,6 +54,19 @@ pub struct FederatedContribution {
     /// Metadata about the contribution
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+
+    /// Archetype commit the contribution was built against (`PullResult::commit_sha`).
+    /// When set, a contribution over a file that changed upstream since then is refused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_sha: Option<String>,
+}
+
+/// An archetype file fetched through the Contents API.
+#[derive(Debug, Clone)]
+struct RemoteFile {
+    content: String,
+    /// Blob SHA, required by the Contents API to update the file.
+    sha: String,
 }
 
 /// Pull result containing downloaded archetype files.
@@ -47,14 +75,25 @@ pub struct PullResult {
     pub master_system_prompt: String,
     pub personality_db: PersonalityDatabase,
     pub commit_sha: Option<String>,
+    /// Served from the archetype cache (the source was unreachable or already at this commit).
+    pub from_cache: bool,
 }
 
 impl ArchetypeSync {
     /// Create a new archetype sync client from environment variables.
+    ///
+    /// `GITHUB_PAT` is optional when `PHOENIX_ARCHETYPE_CHECKOUT` selects offline mode.
     pub fn from_env() -> Result<Self, ConfigError> {
-        let github_pat = std::env::var("GITHUB_PAT")
-            .or_else(|_| std::env::var("GITHUB_TOKEN"))
-            .map_err(|_| ConfigError::MissingEnv("GITHUB_PAT".to_string()))?;
+        let local_checkout = std::env::var("PHOENIX_ARCHETYPE_CHECKOUT")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(PathBuf::from);
+
+        let github_pat = match std::env::var("GITHUB_PAT").or_else(|_| std::env::var("GITHUB_TOKEN")) {
+            Ok(pat) => pat,
+            Err(_) if local_checkout.is_some() => String::new(),
+            Err(_) => return Err(ConfigError::MissingEnv("GITHUB_PAT".to_string())),
+        };
 
         let owner = std::env::var("GITHUB_REPO_OWNER")
             .or_else(|_| std::env::var("GITHUB_USERNAME"))
@@ -76,6 +115,8 @@ impl ArchetypeSync {
             branch,
             user_agent,
             client: reqwest::Client::new(),
+            local_checkout,
+            cache: Some(ArchetypeCache::from_env()),
         })
     }
 
@@ -94,75 +135,74 @@ impl ArchetypeSync {
             branch: config.archetype_branch.clone(),
             user_agent: "phoenix-agi-archetype-sync".to_string(),
             client: reqwest::Client::new(),
+            local_checkout: None,
+            cache: Some(ArchetypeCache::from_env()),
         })
     }
 
-    /// Pull archetype files from GitHub for a specific archetype.
+    /// Write contributions into a local clone of the archetype repository instead of GitHub.
+    pub fn with_local_checkout(mut self, path: impl Into<PathBuf>) -> Self {
+        self.local_checkout = Some(path.into());
+        self
+    }
+
+    /// Cache pulled archetypes in `cache` (`None` disables caching).
+    pub fn with_cache(mut self, cache: Option<ArchetypeCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Where archetypes are pulled from: the local checkout in offline mode, GitHub otherwise.
+    fn source(&self) -> ArchetypeSource {
+        match &self.local_checkout {
+            Some(path) => ArchetypeSource::GitRepo {
+                path: path.clone(),
+                branch: self.branch.clone(),
+            },
+            None => ArchetypeSource::GitHub {
+                owner: self.owner.clone(),
+                repo: self.repo.clone(),
+                branch: self.branch.clone(),
+                token: Some(self.github_pat.clone()).filter(|pat| !pat.is_empty()),
+            },
+        }
+    }
+
+    /// Pull archetype files for a specific archetype.
+    ///
+    /// Reads from the local checkout in offline mode, from GitHub otherwise, and falls back to
+    /// the last cached version when the source is unreachable.
     ///
     /// # Arguments
     /// * `archetype_name` - Name of the archetype to pull (e.g., "default", "heartbound")
     ///
     /// # Returns
-    /// PullResult with downloaded files and optional commit SHA
+    /// PullResult with downloaded files and the commit SHA they were read at
     pub async fn pull_archetype(&self, archetype_name: &str) -> Result<PullResult, PhoenixError> {
-        // Download master_system_prompt.txt
-        let prompt_url = format!(
-            "https://raw.githubusercontent.com/{}/{}/{}/{}/master_system_prompt.txt",
-            self.owner, self.repo, self.branch, archetype_name
-        );
-
-        let master_prompt = self
-            .client
-            .get(&prompt_url)
-            .header("Authorization", format!("Bearer {}", self.github_pat))
-            .header("Accept", "application/vnd.github.raw")
-            .header("User-Agent", &self.user_agent)
-            .send()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to download prompt: {}", e)))?
-            .text()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to read prompt: {}", e)))?;
-
-        // Download personality_db.json
-        let personality_url = format!(
-            "https://raw.githubusercontent.com/{}/{}/{}/{}/personality_db.json",
-            self.owner, self.repo, self.branch, archetype_name
-        );
-
-        let personality_json = self
-            .client
-            .get(&personality_url)
-            .header("Authorization", format!("Bearer {}", self.github_pat))
-            .header("Accept", "application/vnd.github.raw")
-            .header("User-Agent", &self.user_agent)
-            .send()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to download personality: {}", e)))?
-            .text()
-            .await
-            .map_err(|e| ConfigError::DownloadFailed(format!("Failed to read personality: {}", e)))?;
-
-        let personality_db: PersonalityDatabase = serde_json::from_str(&personality_json)
-            .map_err(|e| ConfigError::ParseError(format!("Failed to parse personality_db.json: {}", e)))?;
-
-        // Get latest commit SHA for the archetype directory (optional)
-        let commit_sha = self
-            .get_latest_commit_sha(archetype_name)
-            .await
-            .ok();
-
+        let loaded = archetype::load_archetype(&self.source(), self.cache.as_ref(), archetype_name).await?;
         Ok(PullResult {
-            master_system_prompt: master_prompt,
-            personality_db,
-            commit_sha,
+            personality_db: loaded.files.personality_db()?,
+            master_system_prompt: loaded.files.master_system_prompt,
+            commit_sha: loaded.files.commit_sha,
+            from_cache: loaded.from_cache,
         })
     }
 
+    /// Show what changed upstream since the archetype was last pulled into the cache.
+    pub async fn diff_archetype(&self, archetype_name: &str) -> Result<ArchetypeDiff, PhoenixError> {
+        let cache = self
+            .cache
+            .as_ref()
+            .ok_or_else(|| ConfigError::LoadFailed("archetype cache is disabled".to_string()))?;
+        Ok(archetype::diff_with_upstream(&self.source(), cache, archetype_name).await?)
+    }
+
     /// Push federated learning contribution to GitHub.
     ///
     /// Creates a pull request or direct commit (depending on permissions) with
-    /// the contribution data.
+    /// the contribution data. In offline mode the commit goes into the local checkout
+    /// instead: onto the archetype branch, or onto a new contribution branch when
+    /// `create_pr` is set.
     ///
     /// # Arguments
     /// * `archetype_name` - Name of the archetype being contributed to
@@ -170,13 +210,16 @@ impl ArchetypeSync {
     /// * `create_pr` - If true, create a PR instead of direct push
     ///
     /// # Returns
-    /// URL of the created PR or commit
+    /// URL of the created PR or commit (`file://<checkout>#<commit or branch>` offline)
     pub async fn push_contribution(
         &self,
         archetype_name: &str,
         contribution: &FederatedContribution,
         create_pr: bool,
     ) -> Result<String, PhoenixError> {
+        if self.local_checkout.is_some() {
+            return Ok(self.push_contribution_local(archetype_name, contribution, create_pr).await?);
+        }
         if create_pr {
             self.push_contribution_as_pr(archetype_name, contribution)
                 .await
@@ -189,21 +232,19 @@ impl ArchetypeSync {
     /// Push contribution as a direct commit (requires write access).
     async fn push_contribution_direct(
         &self,
-        _archetype_name: &str,
+        archetype_name: &str,
         contribution: &FederatedContribution,
     ) -> Result<String, PhoenixError> {
-        // TODO: Full implementation would use GitHub Contents API to update files
-        // Get current file contents via:
-        // let prompt_url = format!(
-        //     "https://api.github.com/repos/{}/{}/contents/{}/master_system_prompt.txt",
-        //     self.owner, self.repo, archetype_name
-        // );
-
-        // For now, return a placeholder URL
-        Ok(format!(
-            "https://github.com/{}/{}/commit/contribution-{}",
-            self.owner, self.repo, contribution.instance_id
-        ))
+        let (change, blob_sha, _) = self.prepare_remote_change(archetype_name, contribution).await?;
+        let commit_url = self
+            .put_file(
+                &change,
+                blob_sha.as_deref(),
+                &self.branch,
+                &contribution::commit_message(archetype_name, contribution),
+            )
+            .await?;
+        Ok(commit_url)
     }
 
     /// Push contribution as a pull request (safer, works with forks).
@@ -212,13 +253,19 @@ impl ArchetypeSync {
         archetype_name: &str,
         contribution: &FederatedContribution,
     ) -> Result<String, PhoenixError> {
-        // Create a branch for the contribution
-        let branch_name = format!(
-            "federated-learning/{}/{}-{}",
-            archetype_name,
-            contribution.contribution_type,
-            contribution.instance_id
-        );
+        let (change, blob_sha, upstream_sha) = self.prepare_remote_change(archetype_name, contribution).await?;
+
+        // Create a branch for the contribution and commit the change onto it
+        let branch_name = contribution::branch_name(archetype_name, contribution);
+        let head_sha = self.branch_head(&self.branch).await?;
+        self.create_branch(&branch_name, &head_sha).await?;
+        self.put_file(
+            &change,
+            blob_sha.as_deref(),
+            &branch_name,
+            &contribution::commit_message(archetype_name, contribution),
+        )
+        .await?;
 
         // Create PR via GitHub API
         let pr_url = format!(
@@ -226,35 +273,11 @@ impl ArchetypeSync {
             self.owner, self.repo
         );
 
-        let pr_body = format!(
-            r#"Federated Learning Contribution
-
-**Instance ID**: {}
-**Type**: {}
-**Timestamp**: {}
-
-## Contribution Data
-```json
-{}
-```
-
-## Metadata
-{}
-"#,
-            contribution.instance_id,
-            contribution.contribution_type,
-            contribution.timestamp,
-            serde_json::to_string_pretty(&contribution.data)
-                .unwrap_or_else(|_| "{}".to_string()),
-            serde_json::to_string_pretty(&contribution.metadata)
-                .unwrap_or_else(|_| "{}".to_string())
-        );
-
         let pr_data = serde_json::json!({
-            "title": format!("Federated Learning: {} - {}", archetype_name, contribution.contribution_type),
+            "title": contribution::pr_title(archetype_name, contribution),
             "head": branch_name,
             "base": self.branch,
-            "body": pr_body,
+            "body": contribution::pr_body(archetype_name, contribution, &change, upstream_sha.as_deref()),
         });
 
         let response = self
@@ -292,44 +315,247 @@ impl ArchetypeSync {
         }
     }
 
-    /// Get the latest commit SHA for an archetype directory.
-    async fn get_latest_commit_sha(&self, archetype_name: &str) -> Result<String, ConfigError> {
-        let commits_url = format!(
-            "https://api.github.com/repos/{}/{}/commits?path={}&sha={}&per_page=1",
-            self.owner, self.repo, archetype_name, self.branch
+    /// Read the target file on the archetype branch, check it against the contribution's
+    /// base and compute the change. Returns the change, the file's blob SHA (if it exists)
+    /// and the archetype's latest commit SHA (if it was looked up).
+    async fn prepare_remote_change(
+        &self,
+        archetype_name: &str,
+        contribution: &FederatedContribution,
+    ) -> Result<(FileChange, Option<String>, Option<String>), ConfigError> {
+        let path = contribution::target_path(archetype_name, contribution);
+        let current = self.get_file(&path, &self.branch).await?;
+
+        let mut upstream_sha = None;
+        if let Some(base_sha) = contribution.base_sha.as_deref() {
+            let latest = self.get_latest_commit_sha(archetype_name).await?;
+            if contribution::edits_shared_file(contribution) && latest != base_sha {
+                let at_base = self.get_file(&path, base_sha).await?;
+                contribution::check_upstream(
+                    &path,
+                    base_sha,
+                    &latest,
+                    at_base.as_ref().map(|f| f.content.as_str()),
+                    current.as_ref().map(|f| f.content.as_str()),
+                )?;
+            }
+            upstream_sha = Some(latest);
+        }
+
+        let change = contribution::apply(archetype_name, contribution, current.as_ref().map(|f| f.content.as_str()))?;
+        Ok((change, current.map(|f| f.sha), upstream_sha))
+    }
+
+    /// Offline mode: commit the contribution into the local checkout.
+    async fn push_contribution_local(
+        &self,
+        archetype_name: &str,
+        contribution: &FederatedContribution,
+        create_branch: bool,
+    ) -> Result<String, ConfigError> {
+        let checkout = self
+            .local_checkout
+            .as_ref()
+            .ok_or_else(|| ConfigError::MissingEnv("PHOENIX_ARCHETYPE_CHECKOUT".to_string()))?;
+        let latest = if contribution.base_sha.is_some() {
+            Some(self.get_latest_commit_sha(archetype_name).await?)
+        } else {
+            None
+        };
+        let repo = local::open(checkout)?;
+        let path = contribution::target_path(archetype_name, contribution);
+        let current = local::read_file(&repo, &self.branch, &path)?;
+
+        if let (Some(base_sha), Some(latest)) = (contribution.base_sha.as_deref(), latest) {
+            if contribution::edits_shared_file(contribution) && latest != base_sha {
+                let at_base = local::read_file(&repo, base_sha, &path)?;
+                contribution::check_upstream(&path, base_sha, &latest, at_base.as_deref(), current.as_deref())?;
+            }
+        }
+
+        let change = contribution::apply(archetype_name, contribution, current.as_deref())?;
+        let message = contribution::commit_message(archetype_name, contribution);
+        if create_branch {
+            let branch = contribution::branch_name(archetype_name, contribution);
+            local::commit_file(&repo, &branch, Some(&self.branch), &change.path, &change.after, &message)?;
+            Ok(format!("file://{}#{}", checkout.display(), branch))
+        } else {
+            let sha = local::commit_file(&repo, &self.branch, None, &change.path, &change.after, &message)?;
+            Ok(format!("file://{}#{}", checkout.display(), sha))
+        }
+    }
+
+    /// Fetch a file through the Contents API at `git_ref`; `None` when it does not exist.
+    async fn get_file(&self, path: &str, git_ref: &str) -> Result<Option<RemoteFile>, ConfigError> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}?ref={}",
+            self.owner, self.repo, path, git_ref
         );
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.github_pat))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", &self.user_agent)
+            .send()
+            .await
+            .map_err(|e| ConfigError::GitHubApiError(format!("Failed to get {}: {}", path, e)))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(ConfigError::GitHubApiError(format!(
+                "Failed to get {}: {}",
+                path,
+                response.status()
+            )));
+        }
+        let file: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::ParseError(format!("Failed to parse {}: {}", path, e)))?;
+        let encoded: String = file
+            .get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| ConfigError::ParseError(format!("Failed to decode {}: {}", path, e)))?;
+        Ok(Some(RemoteFile {
+            content: String::from_utf8_lossy(&bytes).into_owned(),
+            sha: file.get("sha").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        }))
+    }
+
+    /// Create or update a file through the Contents API and return the commit URL.
+    ///
+    /// `blob_sha` is the SHA the file had when it was read; GitHub answers 409 if it has
+    /// changed since, which is reported as a conflict.
+    async fn put_file(
+        &self,
+        change: &FileChange,
+        blob_sha: Option<&str>,
+        branch: &str,
+        message: &str,
+    ) -> Result<String, ConfigError> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}",
+            self.owner, self.repo, change.path
+        );
+        let mut body = serde_json::json!({
+            "message": message,
+            "content": base64::engine::general_purpose::STANDARD.encode(change.after.as_bytes()),
+            "branch": branch,
+        });
+        if let Some(sha) = blob_sha {
+            body["sha"] = serde_json::Value::String(sha.to_string());
+        }
 
         let response = self
             .client
-            .get(&commits_url)
+            .put(&url)
             .header("Authorization", format!("Bearer {}", self.github_pat))
             .header("Accept", "application/vnd.github+json")
             .header("User-Agent", &self.user_agent)
+            .json(&body)
             .send()
             .await
-            .map_err(|e| ConfigError::GitHubApiError(format!("Failed to get commits: {}", e)))?;
+            .map_err(|e| ConfigError::GitHubApiError(format!("Failed to update {}: {}", change.path, e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::CONFLICT {
+            return Err(ConfigError::ArchetypeConflict(format!(
+                "{} changed on {} while the contribution was being written",
+                change.path, branch
+            )));
+        }
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ConfigError::GitHubApiError(format!(
+                "Failed to update {} ({}): {}",
+                change.path, status, error_text
+            )));
+        }
+        let result: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::ParseError(format!("Failed to parse commit response: {}", e)))?;
+        Ok(result
+            .pointer("/commit/html_url")
+            .and_then(|v| v.as_str())
+            .unwrap_or("commit created")
+            .to_string())
+    }
 
-        if response.status().is_success() {
-            let commits: Vec<serde_json::Value> = response
-                .json()
-                .await
-                .map_err(|e| ConfigError::ParseError(format!("Failed to parse commits: {}", e)))?;
-
-            if let Some(commit) = commits.first() {
-                commit
-                    .get("sha")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .ok_or_else(|| ConfigError::ParseError("No SHA in commit".to_string()))
-            } else {
-                Err(ConfigError::ArchetypeNotFound(archetype_name.to_string()))
-            }
-        } else {
-            Err(ConfigError::GitHubApiError(format!(
-                "Failed to get commits: {}",
+    /// SHA of the tip of `branch`.
+    async fn branch_head(&self, branch: &str) -> Result<String, ConfigError> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/ref/heads/{}",
+            self.owner, self.repo, branch
+        );
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.github_pat))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", &self.user_agent)
+            .send()
+            .await
+            .map_err(|e| ConfigError::GitHubApiError(format!("Failed to get branch {}: {}", branch, e)))?;
+        if !response.status().is_success() {
+            return Err(ConfigError::GitHubApiError(format!(
+                "Failed to get branch {}: {}",
+                branch,
                 response.status()
-            )))
+            )));
+        }
+        let reference: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::ParseError(format!("Failed to parse branch {}: {}", branch, e)))?;
+        reference
+            .pointer("/object/sha")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| ConfigError::ParseError(format!("No SHA for branch {}", branch)))
+    }
+
+    /// Create `branch` pointing at `sha`.
+    async fn create_branch(&self, branch: &str, sha: &str) -> Result<(), ConfigError> {
+        let url = format!("https://api.github.com/repos/{}/{}/git/refs", self.owner, self.repo);
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.github_pat))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", &self.user_agent)
+            .json(&serde_json::json!({"ref": format!("refs/heads/{}", branch), "sha": sha}))
+            .send()
+            .await
+            .map_err(|e| ConfigError::GitHubApiError(format!("Failed to create branch {}: {}", branch, e)))?;
+        if response.status() == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+            return Err(ConfigError::ArchetypeConflict(format!("branch {} already exists", branch)));
+        }
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ConfigError::GitHubApiError(format!(
+                "Failed to create branch {}: {}",
+                branch, error_text
+            )));
         }
+        Ok(())
+    }
+
+    /// Newest commit that changed an archetype directory, read from [`Self::source`].
+    async fn get_latest_commit_sha(&self, archetype_name: &str) -> Result<String, ConfigError> {
+        self.source()
+            .latest_sha(archetype_name)
+            .await?
+            .ok_or_else(|| ConfigError::ArchetypeNotFound(archetype_name.to_string()))
     }
 
     /// List available archetypes in the repository.
@@ -380,6 +606,138 @@ impl ArchetypeSync {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
+
+    const PERSONALITY: &str = r#"{"ffm_scores":{"openness":0.5,"conscientiousness":0.5,"extraversion":0.5,"agreeableness":0.5,"neuroticism":0.5},"traits":{"warmth":0.9}}"#;
+
+    fn offline_sync(checkout: &Path) -> ArchetypeSync {
+        ArchetypeSync {
+            github_pat: String::new(),
+            owner: "dad".to_string(),
+            repo: "phoenix-archetypes".to_string(),
+            branch: "main".to_string(),
+            user_agent: "test".to_string(),
+            client: reqwest::Client::new(),
+            local_checkout: Some(checkout.to_path_buf()),
+            cache: None,
+        }
+    }
+
+    /// Write `files` into the working tree and commit them on the current branch.
+    fn commit(repo: &git2::Repository, files: &[(&str, &str)], message: &str) -> String {
+        let root = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            std::fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+            std::fs::write(root.join(path), content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap().to_string()
+    }
+
+    fn archetype_checkout(dir: &Path) -> (git2::Repository, String) {
+        let mut opts = git2::RepositoryInitOptions::new();
+        opts.initial_head("main");
+        let repo = git2::Repository::init_opts(dir, &opts).unwrap();
+        let sha = commit(
+            &repo,
+            &[("default/master_system_prompt.txt", "You are Phoenix.\n"), ("default/personality_db.json", PERSONALITY)],
+            "seed",
+        );
+        (repo, sha)
+    }
+
+    fn contribution(kind: &str, data: serde_json::Value, base_sha: Option<&str>) -> FederatedContribution {
+        FederatedContribution {
+            instance_id: "phoenix-01".to_string(),
+            timestamp: "2026-10-19T12:00:00Z".to_string(),
+            contribution_type: kind.to_string(),
+            data,
+            metadata: HashMap::new(),
+            base_sha: base_sha.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_direct_contribution_commits_to_the_checked_out_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let (repo, base) = archetype_checkout(dir.path());
+        let sync = offline_sync(dir.path());
+
+        let c = contribution("personality_update", serde_json::json!({"traits": {"curiosity": 0.8}}), Some(&base));
+        let url = sync.push_contribution("default", &c, false).await.unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(url, format!("file://{}#{}", dir.path().display(), head.id()));
+        assert_eq!(head.parent_id(0).unwrap().to_string(), base);
+        let on_disk = std::fs::read_to_string(dir.path().join("default/personality_db.json")).unwrap();
+        let db: PersonalityDatabase = serde_json::from_str(&on_disk).unwrap();
+        assert_eq!(db.traits.get("curiosity"), Some(&0.8));
+        assert!(repo.statuses(None).unwrap().is_empty(), "working tree matches the new commit");
+    }
+
+    #[tokio::test]
+    async fn offline_pull_is_cached_and_diffed_against_new_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkout = dir.path().join("archetypes");
+        let (_repo, base) = archetype_checkout(&checkout);
+        let sync = offline_sync(&checkout).with_cache(Some(ArchetypeCache::new(dir.path().join("cache"))));
+
+        let pulled = sync.pull_archetype("default").await.unwrap();
+        assert_eq!(pulled.commit_sha.as_deref(), Some(base.as_str()));
+        assert!(!pulled.from_cache);
+        assert!(sync.diff_archetype("default").await.unwrap().is_empty());
+
+        let c = contribution("prompt_update", serde_json::json!("You are Phoenix, reborn.\n"), Some(&base));
+        sync.push_contribution("default", &c, false).await.unwrap();
+        let diff = sync.diff_archetype("default").await.unwrap();
+        assert_eq!(diff.cached_sha.as_deref(), Some(base.as_str()));
+        assert!(diff.files[0].unified.contains("+You are Phoenix, reborn."));
+
+        let repulled = sync.pull_archetype("default").await.unwrap();
+        assert_eq!(repulled.master_system_prompt, "You are Phoenix, reborn.\n");
+        assert_ne!(repulled.commit_sha, pulled.commit_sha);
+    }
+
+    #[tokio::test]
+    async fn offline_pr_contribution_goes_to_its_own_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let (repo, base) = archetype_checkout(dir.path());
+        let sync = offline_sync(dir.path());
+
+        let c = contribution("prompt_update", serde_json::json!("You are Phoenix, kind and curious.\n"), None);
+        let url = sync.push_contribution("default", &c, true).await.unwrap();
+
+        let branch = contribution::branch_name("default", &c);
+        assert!(url.ends_with(&format!("#{branch}")));
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id().to_string(), base);
+        let prompt = local::read_file(&repo, &branch, "default/master_system_prompt.txt").unwrap();
+        assert_eq!(prompt.as_deref(), Some("You are Phoenix, kind and curious.\n"));
+
+        let again = sync.push_contribution("default", &c, true).await.unwrap_err();
+        assert!(again.to_string().contains("already exists"), "{again}");
+    }
+
+    #[tokio::test]
+    async fn offline_contribution_over_an_upstream_edit_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (repo, base) = archetype_checkout(dir.path());
+        commit(&repo, &[("default/master_system_prompt.txt", "You are Phoenix, rewritten.\n")], "upstream edit");
+        let sync = offline_sync(dir.path());
+
+        let prompt = contribution("prompt_update", serde_json::json!("Mine.\n"), Some(&base));
+        let err = sync.push_contribution("default", &prompt, false).await.unwrap_err();
+        assert!(matches!(err, PhoenixError::Config(ConfigError::ArchetypeConflict(_))), "{err}");
+
+        // The personality file did not change upstream, so the same base still applies.
+        let personality = contribution("personality_update", serde_json::json!({"traits": {"warmth": 1.0}}), Some(&base));
+        sync.push_contribution("default", &personality, false).await.unwrap();
+    }
 
     #[tokio::test]
     async fn test_archetype_sync_from_env() {
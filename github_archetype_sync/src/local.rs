@@ -0,0 +1,101 @@
+//! Offline contributions: commits into a local git checkout of the archetype repository.
+//!
+//! Commits are built from the branch tip's tree, so they never pick up unrelated edits in the
+//! working tree. When the target branch is the one checked out, the written file is also
+//! checked out (and refused if it has uncommitted changes).
+
+use std::path::Path;
+
+use error_types::ConfigError;
+use git2::build::{CheckoutBuilder, TreeUpdateBuilder};
+use git2::{BranchType, Commit, ErrorCode, FileMode, Repository, Signature, Status};
+
+fn git_err(e: git2::Error) -> ConfigError {
+    ConfigError::InvalidRepository(format!("git: {}", e.message()))
+}
+
+pub fn open(path: &Path) -> Result<Repository, ConfigError> {
+    Repository::open(path)
+        .map_err(|e| ConfigError::InvalidRepository(format!("{}: {}", path.display(), e.message())))
+}
+
+fn branch_tip<'r>(repo: &'r Repository, branch: &str) -> Result<Commit<'r>, ConfigError> {
+    repo.find_branch(branch, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map_err(git_err)
+}
+
+/// Contents of `path` at `rev` (a branch name or commit SHA); `None` when the file is absent.
+pub fn read_file(repo: &Repository, rev: &str, path: &str) -> Result<Option<String>, ConfigError> {
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|o| o.peel_to_commit())
+        .map_err(git_err)?;
+    let tree = commit.tree().map_err(git_err)?;
+    match tree.get_path(Path::new(path)) {
+        Ok(entry) => {
+            let blob = entry.to_object(repo).and_then(|o| o.peel_to_blob()).map_err(git_err)?;
+            Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+        }
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(git_err(e)),
+    }
+}
+
+/// Commit `content` at `path` onto `branch` and return the new commit's SHA.
+///
+/// With `new_branch_from`, `branch` is created from that branch first and must not exist yet.
+pub fn commit_file(
+    repo: &Repository,
+    branch: &str,
+    new_branch_from: Option<&str>,
+    path: &str,
+    content: &str,
+    message: &str,
+) -> Result<String, ConfigError> {
+    let parent = match new_branch_from {
+        Some(base) => {
+            if repo.find_branch(branch, BranchType::Local).is_ok() {
+                return Err(ConfigError::ArchetypeConflict(format!("branch {branch} already exists")));
+            }
+            branch_tip(repo, base)?
+        }
+        None => branch_tip(repo, branch)?,
+    };
+
+    let checked_out = new_branch_from.is_none()
+        && !repo.is_bare()
+        && repo.head().ok().and_then(|h| h.shorthand().map(str::to_string)).as_deref() == Some(branch);
+    if checked_out {
+        match repo.status_file(Path::new(path)) {
+            Ok(status) if !(status - Status::IGNORED).is_empty() => {
+                return Err(ConfigError::ArchetypeConflict(format!(
+                    "{path} has uncommitted changes in the local checkout"
+                )));
+            }
+            Ok(_) => {}
+            Err(e) if e.code() == ErrorCode::NotFound => {}
+            Err(e) => return Err(git_err(e)),
+        }
+    }
+
+    let blob = repo.blob(content.as_bytes()).map_err(git_err)?;
+    let mut update = TreeUpdateBuilder::new();
+    update.upsert(path, blob, FileMode::Blob);
+    let base_tree = parent.tree().map_err(git_err)?;
+    let tree_id = update.create_updated(repo, &base_tree).map_err(git_err)?;
+    let tree = repo.find_tree(tree_id).map_err(git_err)?;
+    let sig = repo
+        .signature()
+        .or_else(|_| Signature::now("Phoenix AGI (PAGI)", "phoenix@eternal.agi"))
+        .map_err(git_err)?;
+    let oid = repo
+        .commit(Some(&format!("refs/heads/{branch}")), &sig, &sig, message, &tree, &[&parent])
+        .map_err(git_err)?;
+
+    if checked_out {
+        repo.checkout_head(Some(CheckoutBuilder::new().path(path).force()))
+            .map_err(git_err)?;
+    }
+    Ok(oid.to_string())
+}
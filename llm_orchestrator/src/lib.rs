@@ -251,6 +251,11 @@ impl LLMOrchestrator {
         &self.master_prompt
     }
 
+    /// Model used by `speak` when no tier is given.
+    pub fn get_default_model(&self) -> &str {
+        &self.default_model
+    }
+
     pub async fn speak_with_default_prompt(&self, user_input: &str) -> Result<String, String> {
         let full_prompt = format!("{}\n\nUser: {}", self.default_prompt, user_input);
         self.speak(&full_prompt, None).await
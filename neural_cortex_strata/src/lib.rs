@@ -56,6 +56,11 @@ impl NeuralCortexStrata {
         out
     }
 
+    /// The underlying database (snapshot name `eternal_memory`).
+    pub fn database(&self) -> Db {
+        (*self.db).clone()
+    }
+
     pub fn cosmic_recall(&self) -> String {
         "Recalling from Big Bang to now — all is remembered.".to_string()
     }
@@ -0,0 +1,88 @@
+// phoenix-tui/src/commands.rs
+//
+// The TUI's command table. Specs come from `common_types::commands`, the same registry type the
+// web API uses, so parsing, validation errors and `help` behave identically in both front ends.
+// Commands phoenix-web also offers are taken from `commands::shared` so the two cannot drift.
+
+use std::sync::LazyLock;
+
+use common_types::commands::{shared, ArgSpec, CommandRegistry, CommandSpec};
+
+/// What a resolved command runs; `handle_command` in `main.rs` dispatches on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuiCommand {
+    Quit,
+    Help,
+    Status,
+    RecordJournal,
+    ApproveList,
+    Skills,
+    Backup,
+    MobileDevices,
+    MobileConsent,
+    MobileShell,
+    /// A fixed mobile task; the task name is the command name without the `mobile ` prefix.
+    MobileTask,
+}
+
+static REGISTRY: LazyLock<CommandRegistry<TuiCommand>> = LazyLock::new(build_registry);
+
+pub fn registry() -> &'static CommandRegistry<TuiCommand> {
+    &REGISTRY
+}
+
+fn build_registry() -> CommandRegistry<TuiCommand> {
+    let mut r = CommandRegistry::new();
+    r.register(shared::status(), TuiCommand::Status)
+        .register(
+            CommandSpec::new("approve list", "Pick pending creations to approve"),
+            TuiCommand::ApproveList,
+        )
+        .register(
+            CommandSpec::new("record journal", "Record a two-minute encrypted journal"),
+            TuiCommand::RecordJournal,
+        )
+        .register(shared::skills_list(), TuiCommand::Skills)
+        .register(shared::skills_installed(), TuiCommand::Skills)
+        .register(shared::skills_search(), TuiCommand::Skills)
+        .register(shared::skills_install(), TuiCommand::Skills)
+        .register(shared::skills_uninstall(), TuiCommand::Skills)
+        .register(shared::skills_upgrade(), TuiCommand::Skills)
+        .register(
+            CommandSpec::new("mobile devices", "List Android devices seen by adb"),
+            TuiCommand::MobileDevices,
+        )
+        .register(
+            CommandSpec::new("mobile consent", "Record consent to control a device")
+                .arg(ArgSpec::required("device_id", "Device id from `mobile devices`")),
+            TuiCommand::MobileConsent,
+        );
+    for (task, summary) in [
+        ("pull photos", "Copy photos from the device"),
+        ("screenshot", "Capture the device screen"),
+        ("mirror", "Mirror the device screen"),
+    ] {
+        r.register(CommandSpec::new(format!("mobile {task}"), summary), TuiCommand::MobileTask);
+    }
+    r.register(
+        CommandSpec::new("mobile shell", "Run an adb shell command").arg(ArgSpec::rest("cmd", "Shell command")),
+        TuiCommand::MobileShell,
+    );
+    for (task, summary) in [
+        ("uia init", "Set up UI automation on the device"),
+        ("uia dump", "Dump the current UI hierarchy"),
+        ("ios screenshot", "Capture an iOS device screen"),
+    ] {
+        r.register(CommandSpec::new(format!("mobile {task}"), summary), TuiCommand::MobileTask);
+    }
+    r.register(shared::backup_create(), TuiCommand::Backup)
+        .register(shared::backup_list(), TuiCommand::Backup)
+        .register(shared::backup_restore(), TuiCommand::Backup)
+        .register(shared::backup_verify(), TuiCommand::Backup)
+        .register(shared::help(), TuiCommand::Help)
+        .register(
+            CommandSpec::new("quit", "Leave the TUI").alias("q").alias("exit"),
+            TuiCommand::Quit,
+        );
+    r
+}
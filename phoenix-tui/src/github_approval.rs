@@ -1,4 +1,6 @@
-use serde_json::Value;
+use std::sync::Arc;
+
+use evolution_pipeline::forge::{self, Forge};
 
 /// A "pending creation" is represented as an open GitHub Pull Request.
 ///
@@ -7,7 +9,6 @@ use serde_json::Value;
 /// configured repos.
 #[derive(Debug, Clone)]
 pub struct PendingCreation {
-    pub owner: String,
     pub repo: String,
     pub number: u64,
     pub title: String,
@@ -17,12 +18,13 @@ pub struct PendingCreation {
     pub head_ref: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct GitHubApprovalClient {
     token: String,
     owner: String,
     repos: Vec<String>,
-    user_agent: String,
+    /// GitHub by default; `PHOENIX_FORGE` selects Gitea/Forgejo or the local fake forge.
+    forge: Arc<dyn Forge>,
 }
 
 impl GitHubApprovalClient {
@@ -40,9 +42,6 @@ impl GitHubApprovalClient {
         let agents_repo =
             std::env::var("GITHUB_AGENTS_REPO").unwrap_or_else(|_| "phoenix-agents".to_string());
 
-        let user_agent = std::env::var("GITHUB_USER_AGENT")
-            .unwrap_or_else(|_| "phoenix-tui-approval-queue".to_string());
-
         // Keep deterministic ordering.
         let mut repos = vec![tools_repo, agents_repo];
         repos.sort();
@@ -52,12 +51,12 @@ impl GitHubApprovalClient {
             token,
             owner,
             repos,
-            user_agent,
+            forge: forge::from_env(),
         }
     }
 
     pub fn is_configured(&self) -> bool {
-        !self.token.trim().is_empty() && !self.owner.trim().is_empty()
+        (!self.forge.requires_token() || !self.token.trim().is_empty()) && !self.owner.trim().is_empty()
     }
 
     pub async fn list_pending_creations(&self) -> Result<Vec<PendingCreation>, String> {
@@ -68,73 +67,32 @@ impl GitHubApprovalClient {
             );
         }
 
-        let client = reqwest::Client::new();
         let mut out: Vec<PendingCreation> = Vec::new();
 
         for repo in &self.repos {
-            let url = format!(
-                "https://api.github.com/repos/{owner}/{repo}/pulls?state=open&per_page=50",
-                owner = self.owner,
-                repo = repo
-            );
-            let resp = client
-                .get(&url)
-                .header(reqwest::header::USER_AGENT, self.user_agent.clone())
-                .header(reqwest::header::ACCEPT, "application/vnd.github+json")
-                .bearer_auth(self.token.clone())
-                .send()
+            let pulls = self
+                .forge
+                .list_open_prs(&self.token, &self.owner, repo)
                 .await
                 .map_err(|e| format!("GitHub list PRs failed: {e}"))?;
 
-            let status = resp.status();
-            let txt = resp.text().await.unwrap_or_default();
-            if !status.is_success() {
-                return Err(format!("GitHub list PRs failed ({status}): {txt}"));
-            }
-
-            let Ok(v) = serde_json::from_str::<Value>(&txt) else {
-                continue;
-            };
-            let Some(arr) = v.as_array() else {
-                continue;
-            };
-
-            for pr in arr {
-                let title = pr.get("title").and_then(|x| x.as_str()).unwrap_or("").to_string();
-                let html_url = pr
-                    .get("html_url")
-                    .and_then(|x| x.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let number = pr.get("number").and_then(|x| x.as_u64()).unwrap_or(0);
-                let head_ref = pr
-                    .get("head")
-                    .and_then(|h| h.get("ref"))
-                    .and_then(|x| x.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                if number == 0 || html_url.is_empty() {
-                    continue;
-                }
-
+            for pr in pulls {
                 // "Pending creations" heuristic:
                 // - branch prefix is the strongest signal for GitHub-first creations
                 // - title prefix covers legacy flows
-                let looks_like_creation = head_ref.starts_with("phoenix-creation/")
-                    || title.contains("[Phoenix Auto-Creation]")
-                    || title.to_ascii_lowercase().contains("phoenix auto-creation");
+                let looks_like_creation = pr.head_ref.starts_with("phoenix-creation/")
+                    || pr.title.contains("[Phoenix Auto-Creation]")
+                    || pr.title.to_ascii_lowercase().contains("phoenix auto-creation");
                 if !looks_like_creation {
                     continue;
                 }
 
                 out.push(PendingCreation {
-                    owner: self.owner.clone(),
                     repo: repo.clone(),
-                    number,
-                    title,
-                    html_url,
-                    head_ref,
+                    number: pr.number,
+                    title: pr.title,
+                    html_url: pr.html_url,
+                    head_ref: pr.head_ref,
                 });
             }
         }
@@ -152,34 +110,9 @@ impl GitHubApprovalClient {
             );
         }
 
-        let client = reqwest::Client::new();
-        let url = format!(
-            "https://api.github.com/repos/{owner}/{repo}/pulls/{num}/reviews",
-            owner = item.owner,
-            repo = item.repo,
-            num = item.number
-        );
-
-        let resp = client
-            .post(&url)
-            .header(reqwest::header::USER_AGENT, self.user_agent.clone())
-            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
-            .bearer_auth(self.token.clone())
-            .json(&serde_json::json!({
-                "event": "APPROVE",
-                "body": "Approved from Phoenix TUI (Dad)"
-            }))
-            .send()
+        self.forge
+            .approve_pr(&self.token, &item.html_url, "Approved from Phoenix TUI (Dad)")
             .await
-            .map_err(|e| format!("GitHub approve failed: {e}"))?;
-
-        let status = resp.status();
-        let txt = resp.text().await.unwrap_or_default();
-        if !status.is_success() {
-            return Err(format!("GitHub approve failed ({status}): {txt}"));
-        }
-
-        Ok(())
+            .map_err(|e| format!("GitHub approve failed: {e}"))
     }
 }
-
@@ -9,6 +9,7 @@
 // (4) Connect to hive ORCHs
 // (5) Show welcome message
 
+mod commands;
 mod github_approval;
 
 use std::{io, sync::Arc};
@@ -21,13 +22,17 @@ use crossterm::{
 use ratatui::{prelude::*, widgets::*};
 use tokio::sync::Mutex;
 
+use commands::TuiCommand;
+use common_types::commands::ParsedCommand;
 use github_approval::{GitHubApprovalClient, PendingCreation};
 use llm_orchestrator::LLMOrchestrator;
 use multi_modal_recording::MultiModalRecorder;
 use phoenix_identity::PhoenixIdentityManager;
 use relationship_dynamics::{Partnership, RelationshipTemplate};
+use skill_system::{RegistryQuery, SkillMarketplace, SkillSystem, TrustPolicy};
 use system_access::mobile_access::{security as mobile_security, DeviceController, Orchestrator as MobileOrchestrator};
 use vital_organ_vaults::VitalOrganVaults;
+use vital_pulse_monitor::{BrainBackup, BrainDatabase};
 
 fn env_nonempty(key: &str) -> Option<String> {
     std::env::var(key)
@@ -95,6 +100,8 @@ struct Runtime {
     llm: Option<Arc<LLMOrchestrator>>,
     approvals: GitHubApprovalClient,
     mobile: MobileOrchestrator,
+    skills: SkillSystem,
+    skill_registry: Result<SkillMarketplace, String>,
 }
 
 struct App {
@@ -126,6 +133,13 @@ impl App {
             self.log.drain(0..(self.log.len() - 400));
         }
     }
+
+    /// Push multi-line text one log line at a time (the log renders one entry per row).
+    fn push_lines(&mut self, text: &str) {
+        for line in text.lines() {
+            self.push_line(line);
+        }
+    }
 }
 
 struct TerminalGuard;
@@ -253,6 +267,11 @@ async fn startup_runtime() -> Runtime {
         Err(_) => None,
     };
 
+    let skills = SkillSystem::awaken();
+    if let Some(llm) = llm.clone() {
+        skills.set_llm_provider(Some(llm)).await;
+    }
+
     Runtime {
         vaults,
         phoenix_identity,
@@ -261,6 +280,8 @@ async fn startup_runtime() -> Runtime {
         llm,
         approvals: GitHubApprovalClient::from_env(),
         mobile: MobileOrchestrator::new(),
+        skills,
+        skill_registry: SkillMarketplace::open_default(),
     }
 }
 
@@ -376,151 +397,259 @@ async fn cmd_approve_list(app: &mut App, rt: &Runtime) {
     }
 }
 
-async fn handle_command(app: &mut App, rt: &Runtime, raw: &str) -> bool {
-    let input = raw.trim();
-    if input.is_empty() {
-        return false;
-    }
+async fn cmd_skills(app: &mut App, rt: &Runtime, cmd: &ParsedCommand) {
+    let policy = TrustPolicy::from_env();
+    let arg = |name: &str| cmd.arg(name).unwrap_or_default();
 
-    let lower = input.to_ascii_lowercase();
-    if lower == "q" || lower == "quit" || lower == "exit" {
-        app.push_line("Closing the flame. I’ll be right here when you come back.".to_string());
-        return true;
-    }
-
-    if lower == "help" {
-        app.push_line("Commands:".to_string());
-        app.push_line("- status".to_string());
-        app.push_line("- approve list".to_string());
-        app.push_line("- record journal".to_string());
-        app.push_line("- mobile devices".to_string());
-        app.push_line("- mobile consent <device_id>".to_string());
-        app.push_line("- mobile pull photos".to_string());
-        app.push_line("- mobile screenshot".to_string());
-        app.push_line("- mobile mirror".to_string());
-        app.push_line("- mobile shell <cmd>".to_string());
-        app.push_line("- mobile uia init".to_string());
-        app.push_line("- mobile uia dump".to_string());
-        app.push_line("- mobile ios screenshot".to_string());
-        app.push_line("- quit".to_string());
-        return false;
+    if cmd.name == "skills list" {
+        let skills = rt.skills.list_skills().await;
+        app.push_line(format!("{} skills:", skills.len()));
+        for s in skills {
+            app.push_line(format!("- {} v{} ({:?})", s.name, s.version, s.category));
+        }
+        return;
     }
 
-    if lower == "status" {
-        cmd_status(app, rt).await;
-        return false;
+    if cmd.name == "skills installed" {
+        let bundles = rt.skills.installed_bundles().await;
+        if bundles.is_empty() {
+            app.push_line("No skill bundles installed.".to_string());
+        }
+        for b in bundles {
+            let signed = if b.signed_by.is_some() { "signed" } else { "unsigned" };
+            app.push_line(format!("- {} {} ({} skills, {signed})", b.name, b.version, b.skill_ids.len()));
+        }
+        return;
     }
 
-    if lower == "record journal" {
-        cmd_record_journal(app, rt).await;
-        return false;
+    if cmd.name == "skills uninstall" {
+        match rt.skills.uninstall_bundle(arg("name"), cmd.flag("force")).await {
+            Ok(b) => app.push_line(format!("Uninstalled {} {} ({} skills removed).", b.name, b.version, b.skill_ids.len())),
+            Err(e) => app.push_line(format!("skills uninstall: {e}")),
+        }
+        return;
     }
 
-    if lower == "approve list" {
-        cmd_approve_list(app, rt).await;
-        return false;
-    }
+    let registry = match &rt.skill_registry {
+        Ok(r) => r,
+        Err(e) => {
+            app.push_line(format!("Skill registry unavailable: {e}"));
+            return;
+        }
+    };
 
-    if lower == "mobile devices" {
-        match rt.mobile.android.as_ref() {
-            Some(android) => match android.detect() {
-                Ok(devs) if devs.is_empty() => app.push_line("No Android devices detected via adb.".to_string()),
-                Ok(devs) => {
-                    app.push_line("Android devices:".to_string());
-                    for d in devs {
-                        let consent = if mobile_security::check_consent(&d.id) {
-                            "consented"
-                        } else {
-                            "no-consent"
-                        };
-                        app.push_line(format!(
-                            "- {} [{}] model='{}' status='{}'",
-                            d.id, consent, d.model, d.status
-                        ));
+    match cmd.name.as_str() {
+        "skills search" => {
+            let query = RegistryQuery {
+                text: cmd.arg("text").map(str::to_string),
+                ..Default::default()
+            };
+            match registry.search(&query) {
+                Ok(entries) if entries.is_empty() => app.push_line("No matching bundles in the registry.".to_string()),
+                Ok(entries) => {
+                    for e in entries {
+                        app.push_line(format!("- {} {} — {} ({} skills)", e.name, e.version, e.description, e.skills.len()));
                     }
                 }
-                Err(e) => app.push_line(format!("adb detect failed: {e}")),
-            },
-            None => app.push_line("Android controller unavailable (adb not configured/deployed).".to_string()),
+                Err(e) => app.push_line(format!("skills search: {e}")),
+            }
+        }
+        "skills install" => {
+            let spec = arg("name");
+            let (name, version) = match spec.split_once('@') {
+                Some((name, version)) => (name, Some(version)),
+                None => (spec, None),
+            };
+            match rt.skills.install_from_marketplace(registry, name, version, &policy).await {
+                Ok(installed) => {
+                    for b in installed {
+                        app.push_line(format!("Installed {} {} ({} skills).", b.name, b.version, b.skill_ids.len()));
+                    }
+                }
+                Err(e) => app.push_line(format!("skills install: {e}")),
+            }
+        }
+        _ => {
+            let names = match cmd.arg("name") {
+                Some(name) => vec![name.to_string()],
+                None => rt.skills.installed_bundles().await.into_iter().map(|b| b.name).collect(),
+            };
+            for name in names {
+                match rt.skills.upgrade_bundle(registry, &name, &policy).await {
+                    Ok(Some(installed)) => {
+                        for b in installed {
+                            app.push_line(format!("Upgraded {} to {}.", b.name, b.version));
+                        }
+                    }
+                    Ok(None) => app.push_line(format!("{name} is up to date.")),
+                    Err(e) => app.push_line(format!("skills upgrade {name}: {e}")),
+                }
+            }
         }
-        return false;
     }
+}
 
-    if let Some(rest) = lower.strip_prefix("mobile consent ") {
-        let id = rest.trim();
-        if id.is_empty() {
-            app.push_line("Usage: mobile consent <device_id>".to_string());
-            return false;
+/// `BrainBackup::from_env` with the databases this process holds open swapped in.
+async fn brain_backup(rt: &Runtime) -> BrainBackup {
+    let mut backup = BrainBackup::from_env().with_app_version(env!("CARGO_PKG_VERSION"));
+    for (name, db) in rt.vaults.databases() {
+        backup = backup.database(BrainDatabase::open(name, db));
+    }
+    if let Some(db) = rt.skills.store_database().await {
+        backup = backup.database(BrainDatabase::open("skill_library", db));
+    }
+    backup
+}
+
+async fn cmd_backup(app: &mut App, rt: &Runtime, cmd: &ParsedCommand) {
+    let backup = brain_backup(rt).await;
+    match cmd.name.as_str() {
+        "backup create" => match tokio::task::spawn_blocking(move || backup.snapshot()).await {
+            Ok(Ok(report)) => {
+                app.push_line(format!(
+                    "Archived {} database(s) into {}.",
+                    report.manifest.databases.len(),
+                    report.archive.display()
+                ));
+                for s in report.manifest.skipped.iter().filter(|s| s.reason != "not present") {
+                    app.push_line(format!("- skipped {}: {}", s.name, s.reason));
+                }
+            }
+            Ok(Err(e)) => app.push_line(format!("backup create: {e}")),
+            Err(e) => app.push_line(format!("backup create: {e}")),
+        },
+        "backup list" => match backup.list() {
+            Ok(list) if list.is_empty() => app.push_line(format!("No snapshots in {}.", backup.dir().display())),
+            Ok(list) => {
+                for b in list {
+                    app.push_line(format!("- {} ({} bytes)", b.file, b.bytes));
+                }
+            }
+            Err(e) => app.push_line(format!("backup list: {e}")),
+        },
+        "backup verify" => {
+            let archive = cmd.arg("archive").map(str::to_string);
+            match tokio::task::spawn_blocking(move || backup.verify(archive.as_deref())).await {
+                Ok(Ok(report)) => app.push_line(format!(
+                    "Verified {}: {} database(s), {} entries restored cleanly.",
+                    report.archive.display(),
+                    report.databases.len(),
+                    report.entries
+                )),
+                Ok(Err(e)) => app.push_line(format!("backup verify: {e}")),
+                Err(e) => app.push_line(format!("backup verify: {e}")),
+            }
         }
-        match mobile_security::grant_consent(id) {
-            Ok(()) => app.push_line(format!("Consent stored for device: {id}")),
-            Err(e) => app.push_line(format!("Consent update failed: {e}")),
+        _ => {
+            let archive = cmd.arg("archive").unwrap_or_default().to_string();
+            let report = match tokio::task::spawn_blocking(move || backup.restore(&archive)).await {
+                Ok(Ok(report)) => report,
+                Ok(Err(e)) => {
+                    app.push_line(format!("backup restore: {e}"));
+                    return;
+                }
+                Err(e) => {
+                    app.push_line(format!("backup restore: {e}"));
+                    return;
+                }
+            };
+            app.push_line(format!(
+                "Restored {} from {} (previous state saved to {}).",
+                report.restored.join(", "),
+                report.archive.display(),
+                report.safety_snapshot.display()
+            ));
+            if report.restored.iter().any(|name| name == "soul_kb") {
+                *rt.relationship.lock().await =
+                    Partnership::new(RelationshipTemplate::SupportivePartnership, Some(&*rt.vaults));
+            }
+            if report.restored.iter().any(|name| name == "skill_library") {
+                app.push_line("Restored skills are loaded on the next start.".to_string());
+            }
+            for note in report.notes {
+                app.push_line(note);
+            }
         }
-        return false;
     }
+}
 
-    if lower == "mobile pull photos" {
-        match rt.mobile.run_task("pull photos").await {
-            Ok(msg) => app.push_line(msg),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
-        }
-        return false;
+fn cmd_mobile_devices(app: &mut App, rt: &Runtime) {
+    match rt.mobile.android.as_ref() {
+        Some(android) => match android.detect() {
+            Ok(devs) if devs.is_empty() => app.push_line("No Android devices detected via adb.".to_string()),
+            Ok(devs) => {
+                app.push_line("Android devices:".to_string());
+                for d in devs {
+                    let consent = if mobile_security::check_consent(&d.id) {
+                        "consented"
+                    } else {
+                        "no-consent"
+                    };
+                    app.push_line(format!(
+                        "- {} [{}] model='{}' status='{}'",
+                        d.id, consent, d.model, d.status
+                    ));
+                }
+            }
+            Err(e) => app.push_line(format!("adb detect failed: {e}")),
+        },
+        None => app.push_line("Android controller unavailable (adb not configured/deployed).".to_string()),
     }
+}
 
-    if lower == "mobile screenshot" {
-        match rt.mobile.run_task("screenshot").await {
-            Ok(msg) => app.push_line(msg),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
-        }
-        return false;
+async fn cmd_mobile_task(app: &mut App, rt: &Runtime, task: &str) {
+    match rt.mobile.run_task(task).await {
+        Ok(msg) => app.push_line(msg),
+        Err(e) => app.push_line(format!("mobile task failed: {e}")),
     }
+}
 
-    if lower == "mobile mirror" {
-        match rt.mobile.run_task("mirror").await {
-            Ok(msg) => app.push_line(msg),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
-        }
+async fn handle_command(app: &mut App, rt: &Runtime, raw: &str) -> bool {
+    let input = raw.trim();
+    if input.is_empty() {
         return false;
     }
 
-    if lower.starts_with("mobile shell ") {
-        let cmd = input.strip_prefix("mobile shell ").unwrap_or("").trim();
-        if cmd.is_empty() {
-            app.push_line("Usage: mobile shell <cmd>".to_string());
+    let (cmd, handler) = match commands::registry().resolve(input) {
+        Ok(Some(resolved)) => resolved,
+        Ok(None) => {
+            app.push_line("I didn’t recognize that command. Type 'help'.".to_string());
             return false;
         }
-        match rt.mobile.run_task(&format!("shell {cmd}")).await {
-            Ok(out) => app.push_line(out),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
+        Err(e) => {
+            app.push_lines(&e.to_string());
+            return false;
         }
-        return false;
-    }
+    };
 
-    if lower == "mobile uia init" {
-        match rt.mobile.run_task("uia init").await {
-            Ok(msg) => app.push_line(msg),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
+    match handler {
+        TuiCommand::Quit => {
+            app.push_line("Closing the flame. I’ll be right here when you come back.".to_string());
+            return true;
         }
-        return false;
-    }
-
-    if lower == "mobile uia dump" {
-        match rt.mobile.run_task("uia dump").await {
-            Ok(msg) => app.push_line(msg),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
+        TuiCommand::Help => {
+            app.push_line("Commands:".to_string());
+            app.push_lines(&commands::registry().help_text());
         }
-        return false;
-    }
-
-    if lower == "mobile ios screenshot" {
-        match rt.mobile.run_task("ios screenshot").await {
-            Ok(msg) => app.push_line(msg),
-            Err(e) => app.push_line(format!("mobile task failed: {e}")),
+        TuiCommand::Status => cmd_status(app, rt).await,
+        TuiCommand::RecordJournal => cmd_record_journal(app, rt).await,
+        TuiCommand::ApproveList => cmd_approve_list(app, rt).await,
+        TuiCommand::Skills => cmd_skills(app, rt, &cmd).await,
+        TuiCommand::Backup => cmd_backup(app, rt, &cmd).await,
+        TuiCommand::MobileDevices => cmd_mobile_devices(app, rt),
+        TuiCommand::MobileConsent => {
+            let id = cmd.arg("device_id").unwrap_or_default();
+            match mobile_security::grant_consent(id) {
+                Ok(()) => app.push_line(format!("Consent stored for device: {id}")),
+                Err(e) => app.push_line(format!("Consent update failed: {e}")),
+            }
         }
-        return false;
+        TuiCommand::MobileShell => {
+            let task = format!("shell {}", cmd.arg("cmd").unwrap_or_default());
+            cmd_mobile_task(app, rt, &task).await;
+        }
+        TuiCommand::MobileTask => cmd_mobile_task(app, rt, cmd.name.trim_start_matches("mobile ")).await,
     }
-
-    app.push_line("I didn’t recognize that command. Type 'help'.".to_string());
     false
 }
 
@@ -0,0 +1,650 @@
+// phoenix-web/src/auth.rs
+//
+// API authentication (pre-shared tokens with scopes, browser sessions, CSRF, rate limiting).
+//
+// Design goals:
+// - Every `/api` route outside a small public set needs a principal with the route's scope
+// - Scripts and clients send `Authorization: Bearer <token>`
+// - The browser UI exchanges a token for an HttpOnly session cookie; state-changing requests
+//   made with that cookie must echo the session's CSRF token in `X-CSRF-Token`
+// - Tokens are only stored as SHA-256 hashes; if none are configured a local admin token is
+//   generated once and written to `PHOENIX_API_TOKEN_FILE` for local clients to read
+// - Fixed one-minute request windows per token
+
+use actix_web::http::{header, Method, StatusCode};
+use actix_web::HttpRequest;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::{env_nonempty, env_truthy};
+
+pub const SESSION_COOKIE: &str = "phoenix_session";
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 120;
+const DEFAULT_SESSION_TTL_SECS: u64 = 12 * 60 * 60;
+const DEFAULT_TOKENS_PATH: &str = "data/api_tokens.json";
+const DEFAULT_TOKEN_FILE: &str = "data/api_token";
+/// Origins allowed to use a session cookie besides the server's own host (Vite dev server).
+const DEFAULT_ALLOWED_ORIGINS: &[&str] = &["http://localhost:3000", "http://127.0.0.1:3000"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum Scope {
+    #[serde(rename = "chat")]
+    Chat,
+    #[serde(rename = "memory:read")]
+    MemoryRead,
+    #[serde(rename = "memory:write")]
+    MemoryWrite,
+    #[serde(rename = "system")]
+    System,
+    #[serde(rename = "ecosystem")]
+    Ecosystem,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 5] = [
+        Scope::Chat,
+        Scope::MemoryRead,
+        Scope::MemoryWrite,
+        Scope::System,
+        Scope::Ecosystem,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Chat => "chat",
+            Scope::MemoryRead => "memory:read",
+            Scope::MemoryWrite => "memory:write",
+            Scope::System => "system",
+            Scope::Ecosystem => "ecosystem",
+        }
+    }
+
+    /// Parses a scope name; `*` expands to every scope.
+    pub fn parse_list(s: &str) -> Result<Vec<Scope>, String> {
+        if s.trim() == "*" {
+            return Ok(Scope::ALL.to_vec());
+        }
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str().eq_ignore_ascii_case(s.trim()))
+            .map(|scope| vec![scope])
+            .ok_or_else(|| format!("unknown scope: {s}"))
+    }
+}
+
+/// The caller behind a request: a token (directly or through a browser session).
+#[derive(Debug, Clone, Serialize)]
+pub struct Principal {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    #[serde(skip)]
+    rate_limit_per_minute: u32,
+}
+
+impl Principal {
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    fn unrestricted(name: &str, rate_limit_per_minute: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            scopes: Scope::ALL.to_vec(),
+            rate_limit_per_minute,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    Unauthenticated,
+    Forbidden(Scope),
+    CsrfMismatch,
+    CrossOrigin,
+    RateLimited { retry_after: Duration },
+}
+
+impl AuthError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AuthError::Unauthenticated => StatusCode::UNAUTHORIZED,
+            AuthError::Forbidden(_) | AuthError::CsrfMismatch | AuthError::CrossOrigin => StatusCode::FORBIDDEN,
+            AuthError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthError::Unauthenticated => write!(f, "authentication required (Bearer token or session)"),
+            AuthError::Forbidden(scope) => write!(f, "token lacks the '{}' scope", scope.as_str()),
+            AuthError::CsrfMismatch => write!(f, "missing or invalid {CSRF_HEADER} header"),
+            AuthError::CrossOrigin => write!(f, "session cookies are not accepted from this origin"),
+            AuthError::RateLimited { retry_after } => {
+                write!(f, "rate limit exceeded; retry in {}s", retry_after.as_secs().max(1))
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// One entry of the tokens file (`PHOENIX_API_TOKENS_PATH`).
+#[derive(Debug, Deserialize)]
+struct TokenEntry {
+    name: String,
+    /// Hex SHA-256 of the token (`sha256sum` of the token without a trailing newline).
+    token_sha256: String,
+    scopes: Vec<String>,
+    #[serde(default)]
+    rate_limit_per_minute: Option<u32>,
+}
+
+/// A browser session created by [`AuthManager::login`].
+#[derive(Debug, Clone)]
+pub struct NewSession {
+    pub id: String,
+    pub csrf_token: String,
+    pub principal: Principal,
+    pub ttl: Duration,
+}
+
+struct BrowserSession {
+    principal: Principal,
+    csrf_token: String,
+    expires_at: Instant,
+}
+
+struct RateWindow {
+    started: Instant,
+    count: u32,
+}
+
+pub struct AuthManager {
+    enabled: bool,
+    /// Principals keyed by the hex SHA-256 of their token.
+    tokens: HashMap<String, Principal>,
+    allowed_origins: Vec<String>,
+    session_ttl: Duration,
+    sessions: Mutex<HashMap<String, BrowserSession>>,
+    windows: Mutex<HashMap<String, RateWindow>>,
+}
+
+impl AuthManager {
+    /// No authentication: every request acts as an unrestricted, unlimited principal.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            tokens: HashMap::new(),
+            allowed_origins: Vec::new(),
+            session_ttl: Duration::from_secs(DEFAULT_SESSION_TTL_SECS),
+            sessions: Mutex::new(HashMap::new()),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Loads tokens from the environment.
+    ///
+    /// - `PHOENIX_AUTH_DISABLED=true` turns authentication off entirely.
+    /// - `PHOENIX_API_TOKENS_PATH` (default `data/api_tokens.json`): scoped tokens, stored hashed.
+    /// - `PHOENIX_API_TOKEN`: an extra token with every scope.
+    /// - With neither, an admin token is read from (or generated into) `PHOENIX_API_TOKEN_FILE`
+    ///   (default `data/api_token`).
+    /// - `PHOENIX_API_RATE_LIMIT_PER_MINUTE` (default 120), `PHOENIX_SESSION_TTL_SECS`
+    ///   (default 12h) and `PHOENIX_ALLOWED_ORIGINS` (comma-separated) tune the rest.
+    pub fn from_env() -> Result<Self, String> {
+        if env_truthy("PHOENIX_AUTH_DISABLED") {
+            return Ok(Self::disabled());
+        }
+
+        let default_limit = env_nonempty("PHOENIX_API_RATE_LIMIT_PER_MINUTE")
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE);
+
+        let tokens_path = env_nonempty("PHOENIX_API_TOKENS_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_TOKENS_PATH));
+        let mut tokens = if tokens_path.is_file() {
+            load_tokens_file(&tokens_path, default_limit)?
+        } else {
+            HashMap::new()
+        };
+
+        if let Some(token) = env_nonempty("PHOENIX_API_TOKEN") {
+            tokens.insert(hash_token(&token), Principal::unrestricted("env", default_limit));
+        }
+
+        if tokens.is_empty() {
+            let token_file = env_nonempty("PHOENIX_API_TOKEN_FILE")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_TOKEN_FILE));
+            let token = read_or_create_token_file(&token_file)?;
+            tokens.insert(hash_token(&token), Principal::unrestricted("local-admin", default_limit));
+        }
+
+        let allowed_origins = env_nonempty("PHOENIX_ALLOWED_ORIGINS")
+            .map(|s| {
+                s.split(',')
+                    .map(|o| o.trim().trim_end_matches('/').to_string())
+                    .filter(|o| !o.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|| DEFAULT_ALLOWED_ORIGINS.iter().map(|o| o.to_string()).collect());
+        let session_ttl = env_nonempty("PHOENIX_SESSION_TTL_SECS")
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_SESSION_TTL_SECS);
+
+        Ok(Self {
+            enabled: true,
+            tokens,
+            allowed_origins,
+            session_ttl: Duration::from_secs(session_ttl),
+            sessions: Mutex::new(HashMap::new()),
+            windows: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Authenticates the request and checks `scope`, CSRF (for session cookies) and the rate limit.
+    pub fn authorize(&self, req: &HttpRequest, scope: Scope) -> Result<Principal, AuthError> {
+        let principal = self.authenticate(req)?;
+        if !principal.allows(scope) {
+            return Err(AuthError::Forbidden(scope));
+        }
+        self.check_rate(&principal)?;
+        Ok(principal)
+    }
+
+    /// Resolves the caller from a Bearer token or a session cookie.
+    pub fn authenticate(&self, req: &HttpRequest) -> Result<Principal, AuthError> {
+        if !self.enabled {
+            return Ok(Principal::unrestricted("anonymous", u32::MAX));
+        }
+
+        if let Some(token) = bearer_token(req) {
+            return self
+                .tokens
+                .get(&hash_token(token))
+                .cloned()
+                .ok_or(AuthError::Unauthenticated);
+        }
+
+        let session_id = req
+            .cookie(SESSION_COOKIE)
+            .map(|c| c.value().to_string())
+            .ok_or(AuthError::Unauthenticated)?;
+        let (principal, csrf_token) = self.session(&session_id).ok_or(AuthError::Unauthenticated)?;
+
+        if !self.origin_allowed(req) {
+            return Err(AuthError::CrossOrigin);
+        }
+        if !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+            let sent = req.headers().get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+            if sent != Some(csrf_token.as_str()) {
+                return Err(AuthError::CsrfMismatch);
+            }
+        }
+        Ok(principal)
+    }
+
+    /// Exchanges a token for a browser session.
+    pub fn login(&self, token: &str) -> Option<NewSession> {
+        let principal = self.tokens.get(&hash_token(token.trim()))?.clone();
+        let id = generate_token();
+        let csrf_token = generate_token();
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        sessions.retain(|_, s| s.expires_at > now);
+        sessions.insert(
+            id.clone(),
+            BrowserSession {
+                principal: principal.clone(),
+                csrf_token: csrf_token.clone(),
+                expires_at: now + self.session_ttl,
+            },
+        );
+        Some(NewSession {
+            id,
+            csrf_token,
+            principal,
+            ttl: self.session_ttl,
+        })
+    }
+
+    /// The principal and CSRF token of the request's session cookie, if it is still valid.
+    pub fn current_session(&self, req: &HttpRequest) -> Option<(Principal, String)> {
+        let id = req.cookie(SESSION_COOKIE)?;
+        self.session(id.value())
+    }
+
+    pub fn logout(&self, req: &HttpRequest) {
+        if let Some(id) = req.cookie(SESSION_COOKIE) {
+            self.sessions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(id.value());
+        }
+    }
+
+    fn session(&self, id: &str) -> Option<(Principal, String)> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions.get(id).filter(|s| s.expires_at > Instant::now())?;
+        Some((session.principal.clone(), session.csrf_token.clone()))
+    }
+
+    /// Cookies are ambient, so a session is only honoured from the server's own origin or an
+    /// allow-listed one. This also covers WebSocket upgrades, which carry no CSRF header.
+    fn origin_allowed(&self, req: &HttpRequest) -> bool {
+        let Some(origin) = req.headers().get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+            return true;
+        };
+        let origin = origin.trim_end_matches('/');
+        if self.allowed_origins.iter().any(|o| o.eq_ignore_ascii_case(origin)) {
+            return true;
+        }
+        let authority = origin.split_once("://").map(|(_, rest)| rest).unwrap_or(origin);
+        req.headers()
+            .get(header::HOST)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|host| host.eq_ignore_ascii_case(authority))
+    }
+
+    fn check_rate(&self, principal: &Principal) -> Result<(), AuthError> {
+        if principal.rate_limit_per_minute == u32::MAX {
+            return Ok(());
+        }
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let window = windows.entry(principal.name.clone()).or_insert(RateWindow { started: now, count: 0 });
+        if now.duration_since(window.started) >= RATE_WINDOW {
+            window.started = now;
+            window.count = 0;
+        }
+        if window.count >= principal.rate_limit_per_minute {
+            return Err(AuthError::RateLimited {
+                retry_after: RATE_WINDOW.saturating_sub(now.duration_since(window.started)),
+            });
+        }
+        window.count += 1;
+        Ok(())
+    }
+}
+
+/// The scope a route needs, or `None` for public routes (UI assets, health, login, OAuth callback).
+pub fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    if *method == Method::OPTIONS {
+        return None;
+    }
+    let rest = path.strip_prefix("/api/")?;
+    let read_only = matches!(*method, Method::GET | Method::HEAD);
+    match rest.split('/').next().unwrap_or_default() {
+        "name" | "status" | "auth" | "openapi.json" => None,
+        "google" if rest.starts_with("google/oauth2/callback") => None,
+        "memory" if read_only => Some(Scope::MemoryRead),
+        "memory" => Some(Scope::MemoryWrite),
+        "system" | "config" | "google" | "backup" => Some(Scope::System),
+        // Rewrites the persona settings in `.env`.
+        "archetype" if rest.starts_with("archetype/apply") => Some(Scope::System),
+        "relational-state" if !read_only => Some(Scope::MemoryWrite),
+        "ecosystem" => Some(Scope::Ecosystem),
+        "skills" if !read_only => Some(Scope::System),
+        _ => Some(Scope::Chat),
+    }
+}
+
+/// The scope needed to run a chat/command-line input through `/api/command` and friends.
+///
+/// Taken from the command's declared permission (an unknown permission name requires `system`);
+/// plain chat needs `chat`.
+pub fn command_scope(command: &str) -> Scope {
+    crate::commands::registry()
+        .spec_for(command)
+        .and_then(|spec| spec.permission.as_deref())
+        .map(|name| {
+            Scope::ALL
+                .into_iter()
+                .find(|scope| scope.as_str() == name)
+                .unwrap_or(Scope::System)
+        })
+        .unwrap_or(Scope::Chat)
+}
+
+/// Hex SHA-256 of a token, as stored in the tokens file.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// A new random 256-bit token, hex encoded.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn bearer_token(req: &HttpRequest) -> Option<&str> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+}
+
+fn load_tokens_file(path: &Path, default_limit: u32) -> Result<HashMap<String, Principal>, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    let entries: Vec<TokenEntry> =
+        serde_json::from_str(&raw).map_err(|e| format!("parse {}: {e}", path.display()))?;
+
+    let mut tokens = HashMap::new();
+    for entry in entries {
+        let mut scopes = Vec::new();
+        for s in &entry.scopes {
+            for scope in Scope::parse_list(s).map_err(|e| format!("token '{}': {e}", entry.name))? {
+                if !scopes.contains(&scope) {
+                    scopes.push(scope);
+                }
+            }
+        }
+        tokens.insert(
+            entry.token_sha256.trim().to_ascii_lowercase(),
+            Principal {
+                name: entry.name,
+                scopes,
+                rate_limit_per_minute: entry.rate_limit_per_minute.unwrap_or(default_limit),
+            },
+        );
+    }
+    Ok(tokens)
+}
+
+fn read_or_create_token_file(path: &Path) -> Result<String, String> {
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return Ok(existing.to_string());
+        }
+    }
+
+    let token = generate_token();
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("create {}: {e}", parent.display()))?;
+    }
+    // Created owner-only so the token is never readable by others, not even briefly.
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(|e| format!("create {}: {e}", path.display()))?;
+    file.write_all(format!("{token}\n").as_bytes())
+        .map_err(|e| format!("write {}: {e}", path.display()))?;
+    tracing::warn!(
+        "No API tokens configured; generated an admin token in {} (use it as `Authorization: Bearer <token>` or to sign in to the UI)",
+        path.display()
+    );
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn principal(name: &str, scopes: &[Scope], rate_limit_per_minute: u32) -> Principal {
+        Principal {
+            name: name.to_string(),
+            scopes: scopes.to_vec(),
+            rate_limit_per_minute,
+        }
+    }
+
+    fn manager(tokens: &[(&str, Principal)]) -> AuthManager {
+        AuthManager {
+            enabled: true,
+            tokens: tokens.iter().map(|(t, p)| (hash_token(t), p.clone())).collect(),
+            allowed_origins: vec!["http://localhost:3000".to_string()],
+            session_ttl: Duration::from_secs(60),
+            sessions: Mutex::new(HashMap::new()),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[test]
+    fn required_scope_by_route() {
+        for (method, path, scope) in [
+            (Method::GET, "/", None),
+            (Method::GET, "/assets/app.js", None),
+            (Method::OPTIONS, "/api/system/exec", None),
+            (Method::GET, "/api/status", None),
+            (Method::GET, "/api/name", None),
+            (Method::POST, "/api/auth/session", None),
+            (Method::GET, "/api/openapi.json", None),
+            (Method::GET, "/api/google/oauth2/callback", None),
+            (Method::GET, "/api/google/status", Some(Scope::System)),
+            (Method::GET, "/api/memory/search", Some(Scope::MemoryRead)),
+            (Method::POST, "/api/memory/store", Some(Scope::MemoryWrite)),
+            (Method::DELETE, "/api/memory/x", Some(Scope::MemoryWrite)),
+            (Method::POST, "/api/system/exec", Some(Scope::System)),
+            (Method::GET, "/api/config", Some(Scope::System)),
+            (Method::POST, "/api/backup/create", Some(Scope::System)),
+            (Method::POST, "/api/ecosystem/import", Some(Scope::Ecosystem)),
+            (Method::GET, "/api/skills/list", Some(Scope::Chat)),
+            (Method::POST, "/api/skills/install", Some(Scope::System)),
+            (Method::POST, "/api/archetype/apply", Some(Scope::System)),
+            (Method::POST, "/api/archetype/match", Some(Scope::Chat)),
+            (Method::GET, "/api/relational-state", Some(Scope::Chat)),
+            (Method::POST, "/api/relational-state", Some(Scope::MemoryWrite)),
+            (Method::POST, "/api/speak", Some(Scope::Chat)),
+            (Method::GET, "/api/sessions", Some(Scope::Chat)),
+        ] {
+            assert_eq!(required_scope(&method, path), scope, "{method} {path}");
+        }
+    }
+
+    #[test]
+    fn bearer_tokens_need_the_scope() {
+        let auth = manager(&[("chat-token", principal("tui", &[Scope::Chat], 100))]);
+        let req = |token: &str| {
+            TestRequest::post()
+                .insert_header((header::AUTHORIZATION, format!("Bearer {token}")))
+                .to_http_request()
+        };
+        assert_eq!(auth.authorize(&req("chat-token"), Scope::Chat).unwrap().name, "tui");
+        assert_eq!(
+            auth.authorize(&req("chat-token"), Scope::System).unwrap_err(),
+            AuthError::Forbidden(Scope::System)
+        );
+        assert_eq!(auth.authorize(&req("nope"), Scope::Chat).unwrap_err(), AuthError::Unauthenticated);
+        assert_eq!(
+            auth.authorize(&TestRequest::get().to_http_request(), Scope::Chat).unwrap_err(),
+            AuthError::Unauthenticated
+        );
+    }
+
+    #[test]
+    fn session_cookies_need_csrf_and_an_allowed_origin() {
+        let auth = manager(&[("admin", principal("admin", &Scope::ALL, 100))]);
+        let session = auth.login(" admin \n").unwrap();
+        let cookie = actix_web::cookie::Cookie::new(SESSION_COOKIE, session.id.clone());
+        let with_cookie = |req: TestRequest| req.cookie(cookie.clone()).insert_header((header::HOST, "127.0.0.1:8888"));
+
+        let cases: Vec<(TestRequest, Result<(), AuthError>)> = vec![
+            (TestRequest::get(), Ok(())),
+            (TestRequest::post(), Err(AuthError::CsrfMismatch)),
+            (TestRequest::post().insert_header((CSRF_HEADER, "wrong")), Err(AuthError::CsrfMismatch)),
+            (TestRequest::post().insert_header((CSRF_HEADER, session.csrf_token.as_str())), Ok(())),
+            (
+                TestRequest::post()
+                    .insert_header((CSRF_HEADER, session.csrf_token.as_str()))
+                    .insert_header((header::ORIGIN, "https://evil.example")),
+                Err(AuthError::CrossOrigin),
+            ),
+            (TestRequest::get().insert_header((header::ORIGIN, "https://evil.example")), Err(AuthError::CrossOrigin)),
+            (TestRequest::get().insert_header((header::ORIGIN, "http://localhost:3000/")), Ok(())),
+            (TestRequest::get().insert_header((header::ORIGIN, "http://127.0.0.1:8888")), Ok(())),
+        ];
+        for (i, (req, expected)) in cases.into_iter().enumerate() {
+            let result = auth.authenticate(&with_cookie(req).to_http_request()).map(|_| ());
+            assert_eq!(result, expected, "case {i}");
+        }
+
+        let stale = actix_web::cookie::Cookie::new(SESSION_COOKIE, "unknown");
+        let req = TestRequest::get().cookie(stale).to_http_request();
+        assert_eq!(auth.authenticate(&req).unwrap_err(), AuthError::Unauthenticated);
+        assert!(auth.login("not-a-token").is_none());
+    }
+
+    #[test]
+    fn rate_limit_is_per_principal() {
+        let auth = manager(&[]);
+        let limited = principal("limited", &[Scope::Chat], 2);
+        let other = principal("other", &[Scope::Chat], 2);
+        assert!(auth.check_rate(&limited).is_ok());
+        assert!(auth.check_rate(&limited).is_ok());
+        match auth.check_rate(&limited) {
+            Err(AuthError::RateLimited { retry_after }) => assert!(retry_after <= RATE_WINDOW),
+            other => panic!("expected rate limit, got {other:?}"),
+        }
+        assert!(auth.check_rate(&other).is_ok());
+        let unlimited = principal("anonymous", &Scope::ALL, u32::MAX);
+        for _ in 0..10 {
+            assert!(auth.check_rate(&unlimited).is_ok());
+        }
+    }
+
+    #[test]
+    fn scope_lists_parse() {
+        assert_eq!(Scope::parse_list("*").unwrap(), Scope::ALL.to_vec());
+        assert_eq!(Scope::parse_list("memory:read").unwrap(), vec![Scope::MemoryRead]);
+        assert!(Scope::parse_list("root").is_err());
+    }
+
+    #[test]
+    fn generated_token_file_is_owner_only_and_reused() {
+        let dir = std::env::temp_dir().join(format!("phoenix_auth_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("api_token");
+        let token = read_or_create_token_file(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        assert_eq!(read_or_create_token_file(&path).unwrap(), token);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,390 @@
+// phoenix-web/src/backup.rs
+//
+// Brain snapshots and the portable JSON export.
+//
+// Design goals:
+// - Snapshots (`vital_pulse_monitor::BrainBackup`) export the databases this process holds
+//   through its own handles, so nothing is copied while it is being written
+// - The export is selective (vaults, vector_kb, skills, relationship, sessions) and portable:
+//   Soul values are decrypted and memories carry text + metadata rather than embeddings, so it
+//   imports into an install with a different `SOUL_ENCRYPTION_KEY` or embedder
+// - Imports merge: vault keys and memories are upserted, skills re-taught and sessions added;
+//   only the relationship state replaces the current one
+
+use relationship_dynamics::{Partnership, RelationshipTemplate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use skill_system::SkillDefinition;
+use std::collections::{BTreeMap, BTreeSet};
+use utoipa::ToSchema;
+use vital_pulse_monitor::{BrainBackup, BrainDatabase, RestoreReport, SnapshotReport, VerifyReport};
+
+use crate::sessions::Conversation;
+use crate::AppState;
+
+pub const EXPORT_FORMAT: &str = "phoenix.brain_export.v1";
+
+/// Body limit for `/api/backup/import` and `/api/sessions/import`.
+pub const IMPORT_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    Vaults,
+    VectorKb,
+    Skills,
+    Relationship,
+    Sessions,
+}
+
+impl Section {
+    pub const ALL: [Section; 5] = [
+        Section::Vaults,
+        Section::VectorKb,
+        Section::Skills,
+        Section::Relationship,
+        Section::Sessions,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Section::Vaults => "vaults",
+            Section::VectorKb => "vector_kb",
+            Section::Skills => "skills",
+            Section::Relationship => "relationship",
+            Section::Sessions => "sessions",
+        }
+    }
+
+    /// Comma separated section names; `None` or blank means all of them.
+    pub fn parse_list(list: Option<&str>) -> Result<BTreeSet<Section>, String> {
+        let list = list.map(str::trim).unwrap_or_default();
+        if list.is_empty() {
+            return Ok(Self::ALL.into_iter().collect());
+        }
+        list.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|name| {
+                Self::ALL
+                    .into_iter()
+                    .find(|s| s.as_str().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        let known: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                        format!("Unknown section '{name}' (expected {})", known.join(", "))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Vault contents by key. Soul values are plaintext.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct VaultExport {
+    #[serde(default)]
+    pub mind: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: BTreeMap<String, String>,
+    #[serde(default)]
+    pub soul: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VectorMemoryExport {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub metadata: Value,
+}
+
+/// Portable, selective export. Absent sections are left untouched on import.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BrainExport {
+    /// Always `phoenix.brain_export.v1`.
+    pub format: String,
+    /// RFC 3339 timestamp.
+    pub exported_at: String,
+    pub app_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vaults: Option<VaultExport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_kb: Option<Vec<VectorMemoryExport>>,
+    /// Skills added at runtime (built-in and folder skills are not exported).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub skills: Option<Vec<SkillDefinition>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub relationship: Option<Partnership>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<Vec<Conversation>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub vault_entries: usize,
+    pub vector_memories: usize,
+    pub skills: usize,
+    pub relationship: bool,
+    pub sessions: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// `BrainBackup::from_env` with this process's open databases swapped in.
+pub async fn brain_backup(state: &AppState) -> BrainBackup {
+    let mut backup = BrainBackup::from_env()
+        .with_app_version(state.version.clone())
+        .database(BrainDatabase::open("eternal_memory", state.neural_cortex.database()));
+    for (name, db) in state.vaults.databases() {
+        backup = backup.database(BrainDatabase::open(name, db));
+    }
+    if let Some(kb) = &state.vector_kb {
+        backup = backup.database(BrainDatabase::open("vector_kb", kb.database()));
+    }
+    if let Some(db) = state.skills.store_database().await {
+        backup = backup.database(BrainDatabase::open("skill_library", db));
+    }
+    backup
+}
+
+pub async fn snapshot(state: &AppState) -> Result<SnapshotReport, String> {
+    let backup = brain_backup(state).await;
+    tokio::task::spawn_blocking(move || backup.snapshot())
+        .await
+        .map_err(|e| format!("Snapshot task failed: {e}"))?
+}
+
+/// Test-restore `archive` (default: the newest snapshot) into a scratch directory.
+pub async fn verify(state: &AppState, archive: Option<String>) -> Result<VerifyReport, String> {
+    let backup = brain_backup(state).await;
+    tokio::task::spawn_blocking(move || backup.verify(archive.as_deref()))
+        .await
+        .map_err(|e| format!("Verify task failed: {e}"))?
+}
+
+/// Restore `archive` (a file name in the backup directory, or a path) and reload the state that
+/// was read from the restored databases at startup.
+pub async fn restore(state: &AppState, archive: &str) -> Result<RestoreReport, String> {
+    let backup = brain_backup(state).await;
+    let archive = archive.to_string();
+    let mut report = tokio::task::spawn_blocking(move || backup.restore(&archive))
+        .await
+        .map_err(|e| format!("Restore task failed: {e}"))??;
+
+    if report.restored.iter().any(|name| name == "soul_kb") {
+        *state.relationship.lock().await =
+            Partnership::new(RelationshipTemplate::SupportivePartnership, Some(&*state.vaults));
+        report.notes.push("Relationship state reloaded from the restored Soul vault.".to_string());
+    }
+    if report.restored.iter().any(|name| name == "skill_library") {
+        report
+            .notes
+            .push("Restored skills are loaded on the next start.".to_string());
+    }
+    Ok(report)
+}
+
+pub async fn export(state: &AppState, sections: &BTreeSet<Section>) -> Result<BrainExport, String> {
+    let mut out = BrainExport {
+        format: EXPORT_FORMAT.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        app_version: state.version.clone(),
+        vaults: None,
+        vector_kb: None,
+        skills: None,
+        relationship: None,
+        sessions: None,
+    };
+
+    if sections.contains(&Section::Vaults) {
+        let vault = |prefix: &str| state.vaults.recall_prefix(prefix, usize::MAX).into_iter().collect();
+        out.vaults = Some(VaultExport {
+            mind: vault("mind:"),
+            body: vault("body:"),
+            soul: vault("soul:"),
+        });
+    }
+    if sections.contains(&Section::VectorKb)
+        && let Some(kb) = &state.vector_kb
+    {
+        let entries = kb.all().await.map_err(|e| format!("Failed to read vector memories: {e}"))?;
+        out.vector_kb = Some(
+            entries
+                .into_iter()
+                .map(|e| VectorMemoryExport {
+                    id: e.id,
+                    text: e.text,
+                    metadata: e.metadata,
+                })
+                .collect(),
+        );
+    }
+    if sections.contains(&Section::Skills) {
+        out.skills = Some(state.skills.stored_skills().await);
+    }
+    if sections.contains(&Section::Relationship) {
+        out.relationship = Some(state.relationship.lock().await.clone());
+    }
+    if sections.contains(&Section::Sessions) {
+        let conversations = state
+            .sessions
+            .all()
+            .await
+            .map_err(|e| format!("Failed to list sessions: {e}"))?;
+        out.sessions = Some(conversations);
+    }
+    Ok(out)
+}
+
+fn check_format(export: &BrainExport) -> Result<(), String> {
+    if export.format != EXPORT_FORMAT {
+        return Err(format!(
+            "Unsupported export format '{}' (expected {EXPORT_FORMAT})",
+            export.format
+        ));
+    }
+    Ok(())
+}
+
+/// Merge the `sections` of `export` that it carries. Individual failures become warnings.
+pub async fn import(state: &AppState, export: BrainExport, sections: &BTreeSet<Section>) -> Result<ImportReport, String> {
+    check_format(&export)?;
+    let mut report = ImportReport::default();
+
+    if let Some(vaults) = export.vaults.filter(|_| sections.contains(&Section::Vaults)) {
+        for (vault, entries) in [("mind", vaults.mind), ("body", vaults.body), ("soul", vaults.soul)] {
+            for (key, value) in entries {
+                let stored = match vault {
+                    "mind" => state.vaults.store_mind(&key, &value),
+                    "body" => state.vaults.store_body(&key, &value),
+                    _ => state.vaults.store_soul(&key, &value),
+                };
+                match stored {
+                    Ok(()) => report.vault_entries += 1,
+                    Err(e) => report.warnings.push(format!("{vault} vault key {key}: {e}")),
+                }
+            }
+        }
+    }
+
+    if let Some(memories) = export.vector_kb.filter(|_| sections.contains(&Section::VectorKb)) {
+        match &state.vector_kb {
+            Some(kb) => {
+                for m in memories {
+                    match kb.upsert_memory_sync(&m.id, &m.text, m.metadata) {
+                        Ok(_) => report.vector_memories += 1,
+                        Err(e) => report.warnings.push(format!("vector memory {}: {e}", m.id)),
+                    }
+                }
+            }
+            None => report.warnings.push(format!(
+                "Vector KB is disabled (VECTOR_KB_ENABLED); skipped {} memories",
+                memories.len()
+            )),
+        }
+    }
+
+    if let Some(skills) = export.skills.filter(|_| sections.contains(&Section::Skills)) {
+        for skill in skills {
+            let name = skill.name.clone();
+            match state.skills.teach_skill(skill).await {
+                Ok(_) => report.skills += 1,
+                Err(e) => report.warnings.push(format!("skill {name}: {e}")),
+            }
+        }
+    }
+
+    if let Some(mut partnership) = export.relationship.filter(|_| sections.contains(&Section::Relationship)) {
+        let mut current = state.relationship.lock().await;
+        std::mem::swap(&mut partnership.emotion_detector, &mut current.emotion_detector);
+        *current = partnership;
+        current.persist_key_state(&*state.vaults);
+        report.relationship = true;
+    }
+
+    if let Some(conversations) = export.sessions.filter(|_| sections.contains(&Section::Sessions)) {
+        for conversation in conversations {
+            let id = conversation.id.clone();
+            match state.sessions.restore(conversation).await {
+                Ok(_) => report.sessions += 1,
+                Err(e) => report.warnings.push(format!("session {id}: {e}")),
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skill_system::SkillCategory;
+
+    fn empty_export() -> BrainExport {
+        BrainExport {
+            format: EXPORT_FORMAT.to_string(),
+            exported_at: "2026-01-01T00:00:00+00:00".to_string(),
+            app_version: "test".to_string(),
+            vaults: None,
+            vector_kb: None,
+            skills: None,
+            relationship: None,
+            sessions: None,
+        }
+    }
+
+    #[test]
+    fn section_lists_are_parsed_leniently_but_unknown_names_fail() {
+        let all: BTreeSet<Section> = Section::ALL.into_iter().collect();
+        assert_eq!(Section::parse_list(None).unwrap(), all);
+        assert_eq!(Section::parse_list(Some("  ")).unwrap(), all);
+        assert_eq!(
+            Section::parse_list(Some(" Skills, vector_kb,,skills ")).unwrap(),
+            BTreeSet::from([Section::VectorKb, Section::Skills])
+        );
+        let err = Section::parse_list(Some("vaults,memories")).unwrap_err();
+        assert!(err.contains("'memories'") && err.contains("relationship"), "{err}");
+    }
+
+    #[test]
+    fn other_formats_and_versions_are_rejected() {
+        assert!(check_format(&empty_export()).is_ok());
+        for format in ["phoenix.brain_export.v2", "phoenix.brain_backup.v1", ""] {
+            let export = BrainExport {
+                format: format.to_string(),
+                ..empty_export()
+            };
+            let err = check_format(&export).unwrap_err();
+            assert!(err.contains(EXPORT_FORMAT), "{err}");
+        }
+    }
+
+    #[test]
+    fn filtered_export_round_trips_without_the_other_sections() {
+        let mut vaults = VaultExport::default();
+        vaults.mind.insert("mind:favorite_color".to_string(), "blue".to_string());
+        vaults.soul.insert("soul:secret".to_string(), "plaintext".to_string());
+        let skill = SkillDefinition::new("Exported", SkillCategory::Teaching, "exported", "tests");
+        let export = BrainExport {
+            vaults: Some(vaults),
+            skills: Some(vec![skill.clone()]),
+            ..empty_export()
+        };
+
+        let json = serde_json::to_value(&export).unwrap();
+        for absent in ["vector_kb", "relationship", "sessions"] {
+            assert!(json.get(absent).is_none(), "{absent} should not be serialized");
+        }
+
+        let back: BrainExport = serde_json::from_value(json).unwrap();
+        assert!(check_format(&back).is_ok());
+        let vaults = back.vaults.unwrap();
+        assert_eq!(vaults.mind["mind:favorite_color"], "blue");
+        assert_eq!(vaults.soul["soul:secret"], "plaintext");
+        assert!(vaults.body.is_empty());
+        let skills = back.skills.unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!((skills[0].id, skills[0].name.as_str()), (skill.id, "Exported"));
+        assert!(back.vector_kb.is_none() && back.relationship.is_none() && back.sessions.is_none());
+    }
+}
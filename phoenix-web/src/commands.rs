@@ -0,0 +1,709 @@
+// phoenix-web/src/commands.rs
+//
+// Built-in commands for `/api/command`, `/api/speak` and the chat socket.
+//
+// Every command is declared once in `registry()` with its arguments, help text and required
+// scope; the registry validates input before a handler runs, generates `help`, feeds
+// `auth::command_scope` and is served as JSON from `/api/command-registry`. Input that names no
+// command falls through to the LLM.
+
+use common_types::commands::{shared, ArgSpec, CommandError, CommandRegistry, CommandSpec, ParsedCommand};
+use serde_json::{json, Value};
+use skill_system::{RegistryQuery, TrustPolicy};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::LazyLock;
+use system_access::CommandResult;
+use vital_pulse_monitor::BackupKind;
+
+use crate::{backup, upgrade_skill_bundles, AppState};
+
+pub type CommandFuture<'a> = Pin<Box<dyn Future<Output = Value> + 'a>>;
+pub type CommandHandler = for<'a> fn(&'a AppState, &'a ParsedCommand) -> CommandFuture<'a>;
+
+pub const REGISTRY_SCHEMA: &str = "phoenix.command_registry.v2";
+
+static REGISTRY: LazyLock<CommandRegistry<CommandHandler>> = LazyLock::new(build_registry);
+
+pub fn registry() -> &'static CommandRegistry<CommandHandler> {
+    &REGISTRY
+}
+
+/// Runs `cmd` if it names a built-in command. Returns `None` for ordinary chat.
+pub async fn dispatch(state: &AppState, cmd: &str) -> Option<Value> {
+    match registry().resolve(cmd) {
+        Ok(Some((parsed, handler))) => Some(handler(state, &parsed).await),
+        Ok(None) => None,
+        Err(e) => Some(command_error(&e)),
+    }
+}
+
+/// The JSON body for `/api/command-registry`.
+pub fn registry_json() -> Value {
+    json!({
+        "schema": REGISTRY_SCHEMA,
+        "fallback": "Input that names no command is sent to the LLM as chat.",
+        "commands": registry().specs().collect::<Vec<_>>(),
+    })
+}
+
+fn command_error(e: &CommandError) -> Value {
+    let usage = match e {
+        CommandError::MissingArgument { usage, .. }
+        | CommandError::UnexpectedArgument { usage, .. }
+        | CommandError::InvalidChoice { usage, .. } => json!(usage),
+        CommandError::UnknownSubcommand { available, .. } => json!(available),
+    };
+    json!({"type": "error", "message": e.to_string(), "usage": usage})
+}
+
+fn build_registry() -> CommandRegistry<CommandHandler> {
+    let mut r: CommandRegistry<CommandHandler> = CommandRegistry::new();
+    r.register(shared::help(), help)
+        .register(shared::status(), status)
+        .register(
+            CommandSpec::new("google", "Google Workspace integration (Gmail, Drive, Calendar)")
+                .arg(ArgSpec::rest("command", "Google subcommand, e.g. `auth start` or `gmail list`"))
+                .permission("system"),
+            google,
+        )
+        .register(
+            CommandSpec::new("ecosystem", "Run a command in an imported repository")
+                .arg(ArgSpec::required("repo_id", "Imported repository id"))
+                .arg(ArgSpec::required("command", "Command exposed by the repository"))
+                .arg(ArgSpec::optional_rest("args", "Arguments passed to the command"))
+                .permission("ecosystem"),
+            ecosystem,
+        );
+
+    r.register(
+        CommandSpec::new("system grant", "Grant full system access")
+            .arg(ArgSpec::required("user_name", "Who the grant is recorded for"))
+            .permission("system"),
+        system_grant,
+    )
+    .register(
+        CommandSpec::new("system revoke", "Revoke full system access").permission("system"),
+        system_revoke,
+    )
+    .register(
+        CommandSpec::new("system status", "Show access tiers and the security gate").permission("system"),
+        system_status,
+    )
+    .register(
+        CommandSpec::new("system read", "Read a file")
+            .arg(ArgSpec::required("file_path", "File to read"))
+            .permission("system"),
+        system_read,
+    )
+    .register(
+        CommandSpec::new("system write", "Write a file")
+            .arg(ArgSpec::required("file_path", "File to write"))
+            .option("content", "New file contents")
+            .permission("system"),
+        system_write,
+    )
+    .register(
+        CommandSpec::new("system exec", "Run a shell command")
+            .alias("system execute")
+            .arg(ArgSpec::rest("command", "Shell command line"))
+            .option("cwd", "Working directory")
+            .permission("system"),
+        system_exec,
+    )
+    .register(
+        CommandSpec::new("system keylogger", "Start or stop the keylogger")
+            .arg(ArgSpec::required("action", "start or stop").one_of(&["start", "stop"]))
+            .option("path", "Log file path")
+            .permission("system"),
+        system_keylogger,
+    )
+    .register(
+        CommandSpec::new("system mousejigger", "Start or stop the mouse jigger")
+            .arg(ArgSpec::required("action", "start or stop").one_of(&["start", "stop"]))
+            .permission("system"),
+        system_mousejigger,
+    )
+    .register(
+        CommandSpec::new("exec", "Tier 2 unrestricted execution through the tool agent")
+            .alias("execute")
+            .arg(ArgSpec::rest("command", "Shell command line"))
+            .option("cwd", "Working directory")
+            .permission("system"),
+        unrestricted_exec,
+    );
+
+    r.register(shared::skills_list(), skills_list)
+        .register(shared::skills_search(), skills_search)
+        .register(shared::skills_installed(), skills_installed)
+        .register(shared::skills_install(), skills_install)
+        .register(shared::skills_uninstall(), skills_uninstall)
+        .register(shared::skills_upgrade(), skills_upgrade);
+
+    r.register(shared::backup_create(), backup_create)
+        .register(shared::backup_list(), backup_list)
+        .register(shared::backup_restore(), backup_restore)
+        .register(shared::backup_verify(), backup_verify);
+
+    for (op, summary) in [
+        ("analyze", "Full analysis of a file"),
+        ("semantic", "Deep semantic analysis of a file"),
+        ("intent", "Infer the intent of a file"),
+        ("dependencies", "Dependencies of a file"),
+        ("codebase", "Analyze a whole directory"),
+        ("quality", "Quality metrics for a file"),
+        ("list", "List definitions in a file"),
+    ] {
+        r.register(
+            CommandSpec::new(format!("code {op}"), summary)
+                .arg(ArgSpec::required("path", "File or directory"))
+                .permission("system"),
+            code_path,
+        );
+    }
+    for (op, summary) in [
+        ("symbols", "Search symbols under a directory"),
+        ("definition", "Find where a symbol is defined"),
+        ("references", "Find references to a symbol"),
+        ("callers", "Find callers of a function"),
+    ] {
+        r.register(
+            CommandSpec::new(format!("code {op}"), summary)
+                .arg(ArgSpec::required("root_path", "Directory to index"))
+                .arg(ArgSpec::required("name", "Symbol name"))
+                .permission("system"),
+            code_symbols,
+        );
+    }
+    r
+}
+
+fn arg<'a>(cmd: &'a ParsedCommand, name: &str) -> &'a str {
+    cmd.arg(name).unwrap_or_default()
+}
+
+fn help<'a>(_state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        json!({
+            "type": "help",
+            "message": format!(
+                "Commands:\n{}\nAnything else is sent to Phoenix as chat.",
+                registry().help_text()
+            ),
+        })
+    })
+}
+
+fn status<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let phoenix_identity = state.phoenix_identity.lock().await.clone();
+        let identity = phoenix_identity.get_identity().await;
+        let gm = phoenix_identity.get_girlfriend_mode().await;
+
+        let rel = state.relationship.lock().await;
+        let affection = rel.ai_personality.need_for_affection.clamp(0.0, 1.0) * 100.0;
+        let energy = rel.ai_personality.energy_level.clamp(0.0, 1.0) * 100.0;
+        let mood = format!("{:?}", rel.ai_personality.current_mood());
+        let attachment_style = format!("{:?}", rel.attachment_profile.style);
+        let attachment_security = rel.attachment_profile.security_score.clamp(0.0, 1.0) * 100.0;
+        drop(rel);
+
+        json!({
+            "type": "status",
+            "message": format!(
+                "Status — {}\n- affection: {:.0}%\n- attachment: {} (security {:.0}%)\n- energy: {:.0}%\n- mood: {}\n- companion mode: {} (affection {:.0}%)",
+                identity.display_name(),
+                affection,
+                attachment_style,
+                attachment_security,
+                energy,
+                mood,
+                if gm.is_active() { "ON" } else { "OFF" },
+                gm.affection_level.clamp(0.0, 1.0) * 100.0,
+            )
+        })
+    })
+}
+
+// Google Ecosystem commands are handled by the backend integration (never by the frontend).
+fn google<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match state.google.as_ref() {
+            Some(g) => g.handle_command(&cmd.raw).await,
+            None => json!({
+                "type": "error",
+                "message": "Google integration not configured. Set GOOGLE_OAUTH_CLIENT_ID / GOOGLE_OAUTH_CLIENT_SECRET / GOOGLE_OAUTH_REDIRECT_URL."
+            }),
+        }
+    })
+}
+
+fn ecosystem<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let args = arg(cmd, "args").split_whitespace().map(str::to_string).collect();
+        match state
+            .ecosystem
+            .execute_command(arg(cmd, "repo_id"), arg(cmd, "command"), args)
+            .await
+        {
+            Ok(output) => json!({"type": "ecosystem.result", "message": output}),
+            Err(e) => json!({"type": "error", "message": e.to_string()}),
+        }
+    })
+}
+
+fn system_grant<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let user = arg(cmd, "user_name");
+        match state.system.grant_full_access(user.to_string()).await {
+            Ok(_) => json!({"type": "system.grant", "message": format!("Full access granted to {}", user)}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn system_revoke<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match state.system.revoke_access().await {
+            Ok(_) => json!({"type": "system.revoke", "message": "Access revoked"}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn system_status<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let access = state.system.is_access_granted().await;
+        let self_mod = state.system.is_self_modification_enabled().await;
+        let tier1 = system_access::SystemAccessManager::is_tier1_enabled();
+        let tier2 = system_access::SystemAccessManager::is_tier2_enabled();
+
+        let mut status_msg = format!(
+            "Access Status:\n- Tier 0 (Standard): Always Active\n- Tier 1 (File System): {} {}\n- Tier 2 (Unrestricted): {} {}\n- Security Gate Granted: {}\n- Self-Modification: {}",
+            if tier1 { "Enabled" } else { "Disabled" },
+            if tier1 { "(No security gate required)" } else { "" },
+            if tier2 { "Enabled" } else { "Disabled" },
+            if tier2 { "(No security gate required)" } else { "" },
+            access,
+            self_mod
+        );
+
+        if tier1 {
+            status_msg.push_str("\n\n✅ Tier 1 Active: Full file system, process, service, registry, drive, app, and browser access enabled.");
+        }
+
+        if tier2 {
+            status_msg.push_str("\n\n⚠️ WARNING: Tier 2 (Unrestricted Execution) is active. System-wide command execution is enabled.");
+        }
+
+        json!({
+            "type": "system.status",
+            "message": status_msg,
+            "tier0": true,
+            "tier1_enabled": tier1,
+            "tier2_enabled": tier2,
+            "tier1_no_gate_required": tier1,
+            "tier2_no_gate_required": tier2,
+            "security_gate_granted": access,
+            "self_modification_enabled": self_mod,
+        })
+    })
+}
+
+fn system_read<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let path = arg(cmd, "file_path");
+        match state.system.read_file(path).await {
+            Ok(content) => json!({
+                "type": "system.read",
+                "path": path,
+                "content": content,
+            }),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn system_write<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let path = arg(cmd, "file_path");
+        let content = cmd.option("content").unwrap_or_default();
+        match state.system.write_file(path, content).await {
+            Ok(_) => json!({"type": "system.write", "message": format!("File written: {}", path)}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn system_exec<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match state.system.exec_shell(arg(cmd, "command"), cmd.option("cwd")).await {
+            Ok(CommandResult { exit_code, stdout, stderr }) => json!({
+                "type": "system.exec",
+                "exit_code": exit_code,
+                "stdout": stdout,
+                "stderr": stderr,
+            }),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn system_keylogger<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let enabled = arg(cmd, "action") == "start";
+        let log_path = cmd.option("path").map(str::to_string);
+        match state.system.set_keylogger_enabled(enabled, log_path).await {
+            Ok(_) => json!({
+                "type": "system.keylogger",
+                "message": format!("Keylogger {}", if enabled { "enabled" } else { "disabled" }),
+                "enabled": enabled
+            }),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn system_mousejigger<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let enabled = arg(cmd, "action") == "start";
+        match state.system.set_mouse_jigger_enabled(enabled).await {
+            Ok(_) => json!({
+                "type": "system.mousejigger",
+                "message": format!("Mouse jigger {}", if enabled { "enabled" } else { "disabled" }),
+                "enabled": enabled
+            }),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+/// Tier 2 unrestricted execution through the tool agent (falls back to the shell without an LLM).
+fn unrestricted_exec<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    use cerebrum_nexus::{ToolAgent, ToolAgentConfig};
+
+    Box::pin(async move {
+        if !crate::env_truthy("MASTER_ORCHESTRATOR_UNRESTRICTED_EXECUTION") {
+            return json!({
+                "type": "error",
+                "message": "Tier 2 unrestricted execution is not enabled. Set MASTER_ORCHESTRATOR_UNRESTRICTED_EXECUTION=true"
+            });
+        }
+
+        let command = arg(cmd, "command");
+        let cwd = cmd.option("cwd");
+        let llm = state.llm.lock().await.clone();
+        let Some(llm) = llm.as_ref() else {
+            return match state.system.exec_shell(command, cwd).await {
+                Ok(CommandResult { exit_code, stdout, stderr }) => json!({
+                    "type": "exec.result",
+                    "command": command,
+                    "exit_code": exit_code,
+                    "stdout": stdout,
+                    "stderr": stderr,
+                    "tier": "Tier 2 (Unrestricted Execution)",
+                }),
+                Err(e) => json!({
+                    "type": "error",
+                    "message": format!("Execution failed: {}", e),
+                }),
+            };
+        };
+
+        let tool_agent = ToolAgent::awaken(llm.clone(), ToolAgentConfig::from_env());
+        match tool_agent.execute_unrestricted_command(command, cwd).await {
+            Ok(cerebrum_nexus::ToolOutput::CommandOutput { output }) => json!({
+                "type": "exec.result",
+                "command": command,
+                "output": output,
+                "tier": "Tier 2 (Unrestricted Execution)",
+            }),
+            Ok(output) => json!({
+                "type": "exec.result",
+                "command": command,
+                "output": format!("{:?}", output),
+            }),
+            Err(e) => json!({
+                "type": "error",
+                "message": format!("Execution failed: {}", e),
+            }),
+        }
+    })
+}
+
+fn skill_registry(state: &AppState) -> Result<std::sync::Arc<skill_system::SkillMarketplace>, Value> {
+    state
+        .skill_registry
+        .clone()
+        .ok_or_else(|| json!({"type": "error", "message": "Skill registry unavailable (check SKILL_REGISTRY_PATH)."}))
+}
+
+fn skills_list<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let skills = state.skills.list_skills().await;
+        let lines = skills
+            .iter()
+            .map(|s| format!("- {} v{} ({:?})", s.name, s.version, s.category))
+            .collect::<Vec<_>>();
+        json!({"type": "skills.list", "message": format!("{} skills:\n{}", skills.len(), lines.join("\n"))})
+    })
+}
+
+fn skills_search<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let registry = match skill_registry(state) {
+            Ok(r) => r,
+            Err(e) => return e,
+        };
+        let query = RegistryQuery {
+            text: cmd.arg("text").map(str::to_string),
+            ..Default::default()
+        };
+        match registry.search(&query) {
+            Ok(entries) => json!({"type": "skills.search", "results": entries}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn skills_installed<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move { json!({"type": "skills.installed", "bundles": state.skills.installed_bundles().await}) })
+}
+
+fn skills_install<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let registry = match skill_registry(state) {
+            Ok(r) => r,
+            Err(e) => return e,
+        };
+        let spec = arg(cmd, "name");
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        };
+        match state
+            .skills
+            .install_from_marketplace(&registry, name, version, &TrustPolicy::from_env())
+            .await
+        {
+            Ok(installed) => json!({"type": "skills.install", "installed": installed}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn skills_uninstall<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match state.skills.uninstall_bundle(arg(cmd, "name"), cmd.flag("force")).await {
+            Ok(removed) => json!({"type": "skills.uninstall", "removed": removed}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn skills_upgrade<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let registry = match skill_registry(state) {
+            Ok(r) => r,
+            Err(e) => return e,
+        };
+        match upgrade_skill_bundles(state, &registry, cmd.arg("name")).await {
+            Ok(upgraded) => json!({"type": "skills.upgrade", "upgraded": upgraded}),
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn backup_create<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match backup::snapshot(state).await {
+            Ok(report) => {
+                let mut message = format!(
+                    "Snapshot written to {} ({} databases)",
+                    report.archive.display(),
+                    report.manifest.databases.len()
+                );
+                for skipped in &report.manifest.skipped {
+                    message.push_str(&format!("\n- skipped {}: {}", skipped.name, skipped.reason));
+                }
+                json!({"type": "backup.create", "message": message, "report": report})
+            }
+            Err(e) => json!({"type": "error", "message": format!("Backup failed: {e}")}),
+        }
+    })
+}
+
+fn backup_list<'a>(state: &'a AppState, _cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let backup = backup::brain_backup(state).await;
+        match backup.list() {
+            Ok(backups) if backups.is_empty() => json!({
+                "type": "backup.list",
+                "message": format!("No snapshots in {}", backup.dir().display()),
+                "backups": backups,
+            }),
+            Ok(backups) => {
+                let lines = backups
+                    .iter()
+                    .map(|b| {
+                        let mut tags = Vec::new();
+                        if b.kind == Some(BackupKind::Incremental) {
+                            tags.push("incremental".to_string());
+                        }
+                        if b.encrypted {
+                            tags.push("encrypted".to_string());
+                        }
+                        tags.push(format!("{} KiB", b.bytes.div_ceil(1024)));
+                        format!("- {} ({})", b.file, tags.join(", "))
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "type": "backup.list",
+                    "message": format!("Snapshots in {}:\n{}", backup.dir().display(), lines.join("\n")),
+                    "backups": backups,
+                })
+            }
+            Err(e) => json!({"type": "error", "message": e}),
+        }
+    })
+}
+
+fn backup_restore<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match backup::restore(state, arg(cmd, "archive")).await {
+            Ok(report) => {
+                let mut message = format!(
+                    "Restored {} from {}\nPrevious state saved to {}",
+                    report.restored.join(", "),
+                    report.archive.display(),
+                    report.safety_snapshot.display()
+                );
+                for note in &report.notes {
+                    message.push_str(&format!("\n- {note}"));
+                }
+                json!({"type": "backup.restore", "message": message, "report": report})
+            }
+            Err(e) => json!({"type": "error", "message": format!("Restore failed: {e}")}),
+        }
+    })
+}
+
+fn backup_verify<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        match backup::verify(state, cmd.arg("archive").map(str::to_string)).await {
+            Ok(report) => json!({
+                "type": "backup.verify",
+                "message": format!(
+                    "Verified {}: {} database(s), {} entries restored cleanly",
+                    report.archive.display(),
+                    report.databases.len(),
+                    report.entries
+                ),
+                "report": report,
+            }),
+            Err(e) => json!({"type": "error", "message": format!("Verification failed: {e}")}),
+        }
+    })
+}
+
+async fn code_analyzer(state: &AppState) -> code_analysis::MasterOrchestratorCodeAnalysis {
+    use code_analysis::MasterOrchestratorCodeAnalysis;
+
+    // Master Orchestrator has full access
+    let llm = state.llm.lock().await.clone();
+    if let Some(llm) = llm.as_ref() {
+        MasterOrchestratorCodeAnalysis::new_with_llm((**llm).clone())
+    } else {
+        MasterOrchestratorCodeAnalysis::new()
+    }
+}
+
+/// `code <op> <path>`: single-path analyses. The response type is `code.<op>`.
+fn code_path<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let analyzer = code_analyzer(state).await;
+        let path = arg(cmd, "path");
+        let op = cmd.name.trim_start_matches("code ");
+        let p = Path::new(path);
+        let result = match op {
+            "analyze" => analyzer.analyze_file(p).await.map(|r| serde_json::to_value(&r)),
+            "semantic" => analyzer.deep_semantic_analysis(p).await.map(|r| serde_json::to_value(&r)),
+            "intent" => analyzer.analyze_intent(p).await.map(|r| serde_json::to_value(&r)),
+            "dependencies" => analyzer.analyze_dependencies(p).await.map(|r| serde_json::to_value(&r)),
+            "codebase" => analyzer.analyze_codebase(p).await.map(|r| serde_json::to_value(&r)),
+            "quality" => analyzer.quality_metrics(p).await.map(|r| serde_json::to_value(&r)),
+            _ => analyzer.list_definitions(p).await.map(|r| serde_json::to_value(&r)),
+        };
+        let value = match result {
+            Ok(value) => value.unwrap_or(json!(null)),
+            Err(e) => return json!({"type": "error", "message": e.to_string()}),
+        };
+        let (path_key, value_key) = match op {
+            "analyze" => ("file_path", "analysis"),
+            "codebase" => ("root_path", "result"),
+            "list" => ("file_path", "definitions"),
+            _ => ("file_path", "result"),
+        };
+        json!({
+            "type": format!("code.{}", op),
+            path_key: path,
+            value_key: value,
+        })
+    })
+}
+
+/// `code <symbols|definition|references|callers> <root_path> <name>`.
+fn code_symbols<'a>(state: &'a AppState, cmd: &'a ParsedCommand) -> CommandFuture<'a> {
+    Box::pin(async move {
+        let analyzer = code_analyzer(state).await;
+        let root = arg(cmd, "root_path");
+        let name = arg(cmd, "name");
+        let op = cmd.name.trim_start_matches("code ");
+        let index = match analyzer.symbol_index(Path::new(root)).await {
+            Ok(index) => index,
+            Err(e) => return json!({"type": "error", "message": e.to_string()}),
+        };
+        let results = match op {
+            "symbols" => serde_json::to_value(index.search(name, 50)),
+            "definition" => serde_json::to_value(index.definitions(name)),
+            "references" => serde_json::to_value(index.references(name)),
+            _ => serde_json::to_value(index.callers(name)),
+        };
+        json!({
+            "type": format!("code.{}", op),
+            "root_path": root,
+            "name": name,
+            "results": results.unwrap_or(json!([])),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::{command_scope, Scope};
+
+    #[test]
+    fn command_scopes_follow_declared_permissions() {
+        for (input, scope) in [
+            ("google gmail list", Scope::System),
+            ("google auth start", Scope::System),
+            ("system exec ls", Scope::System),
+            ("backup list", Scope::System),
+            ("skills install demo", Scope::System),
+            ("ecosystem abc build", Scope::Ecosystem),
+            ("skills list", Scope::Chat),
+            ("help", Scope::Chat),
+            ("tell me a story", Scope::Chat),
+        ] {
+            assert_eq!(command_scope(input), scope, "{input}");
+        }
+    }
+
+    #[test]
+    fn shared_commands_are_registered_unchanged() {
+        for spec in common_types::commands::shared::all() {
+            assert_eq!(super::registry().get(&spec.name), Some(&spec), "{}", spec.name);
+        }
+    }
+}
@@ -9,8 +9,11 @@
 
 use actix_cors::Cors;
 use actix_files::NamedFile;
-use actix_web::{middleware, web, App, HttpRequest, HttpResponse, HttpServer, Responder, ResponseError};
+use actix_web::{middleware, web, App, HttpMessage, HttpRequest, HttpResponse, HttpServer, Responder, ResponseError};
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
 use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{
@@ -18,23 +21,26 @@ use std::{
     path::{Component, Path, PathBuf},
     sync::Arc,
 };
-use tokio::sync::Mutex;
+use futures_util::StreamExt as _;
+use tokio::sync::{mpsc, Mutex};
 use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
 
 use llm_orchestrator::LLMOrchestrator;
+use ethical_agent::EthicalAgent;
 use evolution_pipeline::GitHubEnforcer;
 use phoenix_identity::PhoenixIdentityManager;
 use relationship_dynamics::{Partnership, RelationshipTemplate};
 use system_access::{CommandResult, SystemAccessManager};
 use vital_organ_vaults::VitalOrganVaults;
+use vital_pulse_monitor::BackupSchedule;
 use context_engine::{ContextEngine, ContextRequest, ContextMemory, ContextLayer};
 use neural_cortex_strata::{NeuralCortexStrata, MemoryLayer};
 use std::time::{SystemTime, UNIX_EPOCH};
-use ecosystem_manager::EcosystemManager;
+use ecosystem_manager::{EcosystemManager, ImportOptions, RepoSource, ServiceStatus};
+use skill_system::{RegistryQuery, SkillCategory, SkillMarketplace, SkillSystem, TrustPolicy};
 use horoscope_archetypes::{ZodiacSign, ZodiacPersonality, CommunicationStyle};
 use std::collections::HashMap;
-// ToolAgent and ToolAgentConfig are used in handle_unrestricted_execution
-// but imported there via use statement
 
 fn env_nonempty(key: &str) -> Option<String> {
     std::env::var(key)
@@ -102,8 +108,15 @@ fn load_dotenv_best_effort() -> (Option<PathBuf>, Option<String>) {
     }
 }
 
+mod auth;
+mod backup;
+mod commands;
 mod google;
+mod openapi;
+mod sessions;
+use auth::{AuthError, AuthManager, Principal, Scope};
 use google::{GoogleInitError, GoogleManager};
+use sessions::{ConversationStore, SessionOwner};
 
 #[derive(Clone)]
 struct AppState {
@@ -117,61 +130,150 @@ struct AppState {
     vector_kb: Option<Arc<vector_kb::VectorKB>>,
     llm: Arc<Mutex<Option<Arc<LLMOrchestrator>>>>,
     system: Arc<SystemAccessManager>,
+    ethics: Arc<EthicalAgent>,
+    auth: Arc<AuthManager>,
+    sessions: Arc<ConversationStore>,
     google: Option<GoogleManager>,
     ecosystem: Arc<EcosystemManager>,
+    skills: Arc<SkillSystem>,
+    skill_registry: Option<Arc<SkillMarketplace>>,
     version: String,
     dotenv_path: Option<String>,
     dotenv_error: Option<String>,
     startup_cwd: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct CommandRequest {
     command: String,
+    /// Conversation session to record the turn in (see `/api/sessions`).
+    #[serde(default)]
+    session_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Import from GitHub (`owner` + `repo`), any git remote (`url`), or a local
+/// directory on the server (`path`).
+#[derive(Debug, Deserialize, ToSchema)]
 struct ImportRepoRequest {
-    owner: String,
-    repo: String,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    repo: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
     #[serde(default)]
     branch: Option<String>,
+    #[serde(default)]
+    revision: Option<String>,
+    #[serde(default)]
+    depth: Option<u32>,
 }
 
-#[derive(Debug, Deserialize)]
+impl ImportRepoRequest {
+    fn source(&self) -> Result<RepoSource, String> {
+        match (&self.path, &self.url, &self.owner, &self.repo) {
+            (Some(path), None, _, _) => Ok(RepoSource::Local { path: path.into() }),
+            (None, Some(url), _, _) => Ok(RepoSource::Git { url: url.clone() }),
+            (None, None, Some(owner), Some(repo)) => Ok(RepoSource::GitHub {
+                owner: owner.clone(),
+                repo: repo.clone(),
+            }),
+            _ => Err("Provide exactly one of: path, url, or owner + repo".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SkillRegistrySearchQuery {
+    #[serde(default)]
+    q: Option<String>,
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    category: Option<SkillCategory>,
+    #[serde(default)]
+    tag: Option<String>,
+    #[serde(default)]
+    all_versions: bool,
+}
+
+/// Publish library skills as a bundle: pick them by `skill_ids`, or by `categories`.
+#[derive(Debug, Deserialize, ToSchema)]
+struct SkillPublishRequest {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    skill_ids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    #[schema(value_type = Vec<String>)]
+    categories: Vec<SkillCategory>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct SkillInstallRequest {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct SkillUninstallRequest {
+    name: String,
+    #[serde(default)]
+    force: bool,
+}
+
+/// Upgrade one bundle, or every installed bundle when `name` is omitted.
+#[derive(Debug, Deserialize, ToSchema)]
+struct SkillUpgradeRequest {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 struct SpeakRequest {
     user_input: String,
     #[serde(default)]
     dad_emotion_hint: Option<String>,
     #[serde(default)]
     mode: Option<String>,
+    /// Conversation session to record the turn in (see `/api/sessions`).
+    #[serde(default)]
+    session_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct ExecRequest {
     command: String,
     #[serde(default)]
     cwd: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct ReadFileRequest {
     path: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct WriteFileRequest {
     path: String,
     content: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct MemoryStoreRequest {
     key: String,
     value: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct MemorySearchQuery {
     #[serde(default)]
     q: String,
@@ -179,14 +281,15 @@ struct MemorySearchQuery {
     limit: Option<usize>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct VectorMemoryStoreRequest {
     text: String,
     #[serde(default)]
     metadata: serde_json::Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct VectorMemorySearchQuery {
     #[serde(default)]
     q: String,
@@ -194,49 +297,49 @@ struct VectorMemorySearchQuery {
     k: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct VectorMemoryStoreResponse {
     status: &'static str,
     id: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct VectorMemorySearchResponse {
     results: Vec<vector_kb::MemoryResult>,
     count: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct VectorMemoryEntrySummary {
     id: String,
     text: String,
     metadata: serde_json::Value,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct VectorMemoryAllResponse {
     entries: Vec<VectorMemoryEntrySummary>,
     count: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct MemoryItem {
     key: String,
     value: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct MemorySearchResponse {
     items: Vec<MemoryItem>,
     count: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct StatusOkResponse {
     status: &'static str,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ErrorResponse {
     #[serde(rename = "type")]
     kind: &'static str,
@@ -293,7 +396,8 @@ impl ResponseError for ApiError {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct GoogleOAuthCallbackQuery {
     code: String,
     state: String,
@@ -302,7 +406,7 @@ struct GoogleOAuthCallbackQuery {
     scope: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct StatusResponse {
     status: String,
     llm_status: String,
@@ -315,7 +419,7 @@ struct StatusResponse {
     openrouter_api_key_set: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ConfigGetResponse {
     openrouter_api_key_set: bool,
     // User fields: USER_NAME and USER_PREFERRED_ALIAS
@@ -323,7 +427,7 @@ struct ConfigGetResponse {
     user_preferred_alias: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct ConfigSetRequest {
     #[serde(default)]
     openrouter_api_key: Option<String>,
@@ -338,7 +442,7 @@ struct ConfigSetRequest {
 // Not every field is currently referenced in scoring logic, so silence dead_code
 // warnings to keep builds clean.
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct DatingProfile {
     #[serde(rename = "personalInfo")]
     personal_info: PersonalInfo,
@@ -356,7 +460,7 @@ struct DatingProfile {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct PersonalInfo {
     name: String,
     #[serde(rename = "ageRange")]
@@ -365,7 +469,7 @@ struct PersonalInfo {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct CommunicationStyleData {
     style: String, // "Direct" | "Playful" | "Thoughtful" | "Warm" | "Reflective"
     #[serde(rename = "energyLevel")]
@@ -376,7 +480,7 @@ struct CommunicationStyleData {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct EmotionalNeedsData {
     #[serde(rename = "affectionNeed")]
     affection_need: f64,
@@ -392,7 +496,7 @@ struct EmotionalNeedsData {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct LoveLanguagesData {
     #[serde(rename = "wordsOfAffirmation")]
     words_of_affirmation: f64,
@@ -406,14 +510,14 @@ struct LoveLanguagesData {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct AttachmentStyleData {
     style: String, // "Secure" | "Anxious" | "Avoidant" | "Disorganized"
     description: String,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct RelationshipGoalsData {
     goals: Vec<String>,
     #[serde(rename = "intimacyComfort")]
@@ -421,38 +525,38 @@ struct RelationshipGoalsData {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct InterestsData {
     hobbies: Vec<String>,
     #[serde(rename = "favoriteTopics")]
     favorite_topics: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ArchetypeMatch {
     sign: String,
     name: String,
     description: String,
     compatibility: f64,
-    traits: serde_json::Value,
+    traits: HashMap<String, f64>,
     #[serde(rename = "styleBias")]
     style_bias: String,
     #[serde(rename = "moodPreferences")]
     mood_preferences: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct MatchResponse {
     matches: Vec<ArchetypeMatch>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct ApplyArchetypeRequest {
     sign: String,
     profile: DatingProfile,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ApplyArchetypeResponse {
     success: bool,
     message: String,
@@ -460,13 +564,13 @@ struct ApplyArchetypeResponse {
     updated_env_vars: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct RelationalStateResponse {
     score: i32,
     sentiment: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct RelationalStateUpdateRequest {
     #[serde(default)]
     score: Option<i32>,
@@ -474,7 +578,7 @@ struct RelationalStateUpdateRequest {
     sentiment: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ConfigSetResponse {
     status: &'static str,
     openrouter_api_key_set: bool,
@@ -483,8 +587,50 @@ struct ConfigSetResponse {
     llm_status: String,
 }
 
-static FRONTEND_COMMAND_REGISTRY_JSON: &str =
-    include_str!("../../docs/frontend_command_registry.json");
+#[derive(Debug, Serialize, ToSchema)]
+struct NameResponse {
+    name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct SystemStatusResponse {
+    full_access_granted: bool,
+    self_modification_enabled: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ExecResponse {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ReadFileResponse {
+    path: String,
+    content: String,
+}
+
+/// `status` is `success` for builds, `started`/`stopped` for services; `output` is the build log.
+#[derive(Debug, Serialize, ToSchema)]
+struct EcosystemActionResponse {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ServiceHealthResponse {
+    service_status: ServiceStatus,
+}
+
+/// Error body of the skills and ecosystem routes.
+#[derive(Debug, Serialize, ToSchema)]
+struct RouteErrorResponse {
+    error: String,
+}
 
 async fn health() -> impl Responder {
     HttpResponse::Ok().json(json!({"status": "ok"}))
@@ -498,12 +644,28 @@ async fn favicon_ico() -> impl Responder {
         .finish()
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/name",
+    tag = "meta",
+    responses((status = 200, body = NameResponse)),
+    security(())
+)]
 async fn api_name(state: web::Data<AppState>) -> impl Responder {
     let phoenix_identity = state.phoenix_identity.lock().await.clone();
     let identity = phoenix_identity.get_identity().await;
-    HttpResponse::Ok().json(json!({"name": identity.display_name()}))
+    HttpResponse::Ok().json(NameResponse {
+        name: identity.display_name().to_string(),
+    })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/status",
+    tag = "meta",
+    responses((status = 200, body = StatusResponse)),
+    security(())
+)]
 async fn api_status(state: web::Data<AppState>) -> impl Responder {
     let phoenix_identity = state.phoenix_identity.lock().await.clone();
     let archetype = format!("{:?}", phoenix_identity.zodiac_sign());
@@ -610,6 +772,12 @@ fn write_dotenv_lines(path: &Path, lines: &[String]) -> Result<(), String> {
     fs::write(path, out).map_err(|e| format!("Failed to write {}: {e}", path.display()))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/config",
+    tag = "config",
+    responses((status = 200, body = ConfigGetResponse))
+)]
 async fn api_config_get(_state: web::Data<AppState>) -> impl Responder {
     let user_name = env_nonempty("USER_NAME");
     let user_preferred_alias = env_nonempty("USER_PREFERRED_ALIAS");
@@ -620,6 +788,12 @@ async fn api_config_get(_state: web::Data<AppState>) -> impl Responder {
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/relational-state",
+    tag = "relationship",
+    responses((status = 200, body = RelationalStateResponse))
+)]
 async fn api_relational_state_get(state: web::Data<AppState>) -> impl Responder {
     // Retrieve from vaults or use defaults
     let score = state.vaults.recall_soul("ui:relational_score")
@@ -635,6 +809,16 @@ async fn api_relational_state_get(state: web::Data<AppState>) -> impl Responder
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/relational-state",
+    tag = "relationship",
+    request_body = RelationalStateUpdateRequest,
+    responses(
+        (status = 200, body = RelationalStateResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
 async fn api_relational_state_update(state: web::Data<AppState>, body: web::Json<RelationalStateUpdateRequest>) -> impl Responder {
     // Update score if provided
     if let Some(score) = body.score {
@@ -670,6 +854,16 @@ async fn api_relational_state_update(state: web::Data<AppState>, body: web::Json
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/config",
+    tag = "config",
+    request_body = ConfigSetRequest,
+    responses(
+        (status = 200, body = ConfigSetResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
 async fn api_config_set(state: web::Data<AppState>, body: web::Json<ConfigSetRequest>) -> impl Responder {
     let dotenv_path = dotenv_path_for_write(state.dotenv_path.as_ref());
     let mut lines = read_dotenv_lines(&dotenv_path);
@@ -737,6 +931,7 @@ async fn api_config_set(state: web::Data<AppState>, body: web::Json<ConfigSetReq
                 None
             }
         };
+        state.skills.set_llm_provider(skill_llm(&new_llm)).await;
         *state.llm.lock().await = new_llm;
     }
 
@@ -750,24 +945,50 @@ async fn api_config_set(state: web::Data<AppState>, body: web::Json<ConfigSetReq
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/command-registry",
+    tag = "meta",
+    responses((status = 200, description = "Built-in commands with their arguments and permissions", body = Object))
+)]
 async fn api_command_registry() -> impl Responder {
-    HttpResponse::Ok()
-        .content_type("application/json")
-        .body(FRONTEND_COMMAND_REGISTRY_JSON)
+    HttpResponse::Ok().json(commands::registry_json())
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/status",
+    tag = "system",
+    responses((status = 200, body = SystemStatusResponse))
+)]
 async fn api_system_status(state: web::Data<AppState>) -> impl Responder {
-    HttpResponse::Ok().json(json!({
-        "full_access_granted": state.system.is_access_granted().await,
-        "self_modification_enabled": state.system.is_self_modification_enabled().await,
-    }))
+    HttpResponse::Ok().json(SystemStatusResponse {
+        full_access_granted: state.system.is_access_granted().await,
+        self_modification_enabled: state.system.is_self_modification_enabled().await,
+    })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/evolution/status",
+    tag = "system",
+    responses((status = 200, description = "Sanitized GitHub settings (no token values)", body = Object))
+)]
 async fn api_evolution_status() -> impl Responder {
     // Exposes sanitized config only (no token values).
     HttpResponse::Ok().json(GitHubEnforcer::env_status())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/system/exec",
+    tag = "system",
+    request_body = ExecRequest,
+    responses(
+        (status = 200, body = ExecResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
 async fn api_system_exec(state: web::Data<AppState>, body: web::Json<ExecRequest>) -> impl Responder {
     match state
         .system
@@ -778,31 +999,54 @@ async fn api_system_exec(state: web::Data<AppState>, body: web::Json<ExecRequest
             exit_code,
             stdout,
             stderr,
-        }) => HttpResponse::Ok().json(json!({
-            "exit_code": exit_code,
-            "stdout": stdout,
-            "stderr": stderr,
-        })),
+        }) => HttpResponse::Ok().json(ExecResponse {
+            exit_code,
+            stdout,
+            stderr,
+        }),
         Err(e) => HttpResponse::BadRequest().json(json!({"type": "error", "message": e})),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/system/read-file",
+    tag = "system",
+    request_body = ReadFileRequest,
+    responses(
+        (status = 200, body = ReadFileResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
 async fn api_system_read_file(
     state: web::Data<AppState>,
     body: web::Json<ReadFileRequest>,
 ) -> impl Responder {
     match state.system.read_file(&body.path).await {
-        Ok(content) => HttpResponse::Ok().json(json!({"path": body.path, "content": content})),
+        Ok(content) => HttpResponse::Ok().json(ReadFileResponse {
+            path: body.path.clone(),
+            content,
+        }),
         Err(e) => HttpResponse::BadRequest().json(json!({"type": "error", "message": e})),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/system/write-file",
+    tag = "system",
+    request_body = WriteFileRequest,
+    responses(
+        (status = 200, body = StatusOkResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
 async fn api_system_write_file(
     state: web::Data<AppState>,
     body: web::Json<WriteFileRequest>,
 ) -> impl Responder {
     match state.system.write_file(&body.path, &body.content).await {
-        Ok(()) => HttpResponse::Ok().json(json!({"status": "ok"})),
+        Ok(()) => HttpResponse::Ok().json(StatusOkResponse { status: "ok" }),
         Err(e) => HttpResponse::BadRequest().json(json!({"type": "error", "message": e})),
     }
 }
@@ -820,6 +1064,17 @@ const MEMORY_SEARCH_LIMIT_MAX: usize = 100;
 const VECTOR_SEARCH_K_DEFAULT: usize = 5;
 const VECTOR_SEARCH_K_MAX: usize = 50;
 
+#[utoipa::path(
+    post,
+    path = "/api/memory/store",
+    tag = "memory",
+    request_body = MemoryStoreRequest,
+    responses(
+        (status = 200, body = StatusOkResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 async fn api_memory_store(
     state: web::Data<AppState>,
     body: web::Json<MemoryStoreRequest>,
@@ -837,6 +1092,17 @@ async fn api_memory_store(
     Ok(HttpResponse::Ok().json(StatusOkResponse { status: "ok" }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/memory/get/{key}",
+    tag = "memory",
+    params(("key" = String, Path, description = "Memory key")),
+    responses(
+        (status = 200, body = MemoryItem),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
 async fn api_memory_get(
     state: web::Data<AppState>,
     key: web::Path<String>,
@@ -857,6 +1123,13 @@ async fn api_memory_get(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/memory/search",
+    tag = "memory",
+    params(MemorySearchQuery),
+    responses((status = 200, body = MemorySearchResponse))
+)]
 async fn api_memory_search(
     state: web::Data<AppState>,
     q: web::Query<MemorySearchQuery>,
@@ -878,6 +1151,18 @@ async fn api_memory_search(
     Ok(HttpResponse::Ok().json(MemorySearchResponse { items, count }))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/memory/delete/{key}",
+    tag = "memory",
+    params(("key" = String, Path, description = "Memory key")),
+    responses(
+        (status = 200, body = StatusOkResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 async fn api_memory_delete(
     state: web::Data<AppState>,
     key: web::Path<String>,
@@ -900,6 +1185,17 @@ async fn api_memory_delete(
     Ok(HttpResponse::Ok().json(StatusOkResponse { status: "ok" }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/memory/vector/store",
+    tag = "memory",
+    request_body = VectorMemoryStoreRequest,
+    responses(
+        (status = 200, body = VectorMemoryStoreResponse),
+        (status = 400, description = "Vector KB disabled", body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 async fn api_memory_vector_store(
     state: web::Data<AppState>,
     body: web::Json<VectorMemoryStoreRequest>,
@@ -921,6 +1217,17 @@ async fn api_memory_vector_store(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/memory/vector/search",
+    tag = "memory",
+    params(VectorMemorySearchQuery),
+    responses(
+        (status = 200, body = VectorMemorySearchResponse),
+        (status = 400, description = "Vector KB disabled", body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 async fn api_memory_vector_search(
     state: web::Data<AppState>,
     q: web::Query<VectorMemorySearchQuery>,
@@ -945,6 +1252,16 @@ async fn api_memory_vector_search(
     Ok(HttpResponse::Ok().json(VectorMemorySearchResponse { results, count }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/memory/vector/all",
+    tag = "memory",
+    responses(
+        (status = 200, body = VectorMemoryAllResponse),
+        (status = 400, description = "Vector KB disabled", body = ErrorResponse),
+        (status = 500, body = ErrorResponse),
+    )
+)]
 async fn api_memory_vector_all(state: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
     let Some(kb) = state.vector_kb.as_ref() else {
         return Err(ApiError::bad_request(
@@ -967,6 +1284,15 @@ async fn api_memory_vector_all(state: web::Data<AppState>) -> Result<HttpRespons
     Ok(HttpResponse::Ok().json(VectorMemoryAllResponse { entries, count }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/google/auth/start",
+    tag = "google",
+    responses(
+        (status = 200, description = "Authorization URL to open", body = Object),
+        (status = 400, description = "Google integration not configured", body = ErrorResponse),
+    )
+)]
 async fn api_google_auth_start(state: web::Data<AppState>) -> impl Responder {
     match state.google.as_ref() {
         Some(g) => HttpResponse::Ok().json(g.auth_start().await),
@@ -977,6 +1303,17 @@ async fn api_google_auth_start(state: web::Data<AppState>) -> impl Responder {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/google/oauth2/callback",
+    tag = "google",
+    params(GoogleOAuthCallbackQuery),
+    responses(
+        (status = 200, description = "HTML page for the OAuth popup", content_type = "text/html", body = String),
+        (status = 400, content_type = "text/html", body = String),
+    ),
+    security(())
+)]
 async fn api_google_oauth2_callback(
     state: web::Data<AppState>,
     q: web::Query<GoogleOAuthCallbackQuery>,
@@ -1188,455 +1525,110 @@ async fn store_episodic_memory(state: &AppState, user_input: &str, response: &st
     }
 }
 
-/// Handle system access commands (Tier 1 & Tier 2)
-async fn handle_system_command(state: &AppState, cmd: &str) -> serde_json::Value {
-    
-    let parts: Vec<&str> = cmd.split_whitespace().collect();
-    if parts.len() < 2 {
-        return json!({
-            "type": "error",
-            "message": "Usage: system <operation> [args] | [key=value]"
-        });
-    }
-
-    let operation = parts[1].to_lowercase();
-    
-    // Parse key=value pairs
-    let mut params: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-    if let Some(pipe_idx) = cmd.find('|') {
-        for part in cmd[pipe_idx + 1..].split('|') {
-            if let Some(eq_idx) = part.find('=') {
-                let key = part[..eq_idx].trim().to_string();
-                let value = part[eq_idx + 1..].trim().to_string();
-                params.insert(key, value);
-            }
-        }
-    }
-
-    match operation.as_str() {
-        "grant" => {
-            if parts.len() < 3 {
-                return json!({"type": "error", "message": "Usage: system grant <user_name>"});
-            }
-            match state.system.grant_full_access(parts[2].to_string()).await {
-                Ok(_) => json!({"type": "system.grant", "message": format!("Full access granted to {}", parts[2])}),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        "revoke" => {
-            match state.system.revoke_access().await {
-                Ok(_) => json!({"type": "system.revoke", "message": "Access revoked"}),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        "status" => {
-            let access = state.system.is_access_granted().await;
-            let self_mod = state.system.is_self_modification_enabled().await;
-            let tier1 = system_access::SystemAccessManager::is_tier1_enabled();
-            let tier2 = system_access::SystemAccessManager::is_tier2_enabled();
-            
-            let mut status_msg = format!(
-                "Access Status:\n- Tier 0 (Standard): Always Active\n- Tier 1 (File System): {} {}\n- Tier 2 (Unrestricted): {} {}\n- Security Gate Granted: {}\n- Self-Modification: {}",
-                if tier1 { "Enabled" } else { "Disabled" },
-                if tier1 { "(No security gate required)" } else { "" },
-                if tier2 { "Enabled" } else { "Disabled" },
-                if tier2 { "(No security gate required)" } else { "" },
-                access,
-                self_mod
-            );
-            
-            if tier1 {
-                status_msg.push_str("\n\n✅ Tier 1 Active: Full file system, process, service, registry, drive, app, and browser access enabled.");
-            }
-            
-            if tier2 {
-                status_msg.push_str("\n\n⚠️ WARNING: Tier 2 (Unrestricted Execution) is active. System-wide command execution is enabled.");
-            }
-            
-            json!({
-                "type": "system.status",
-                "message": status_msg,
-                "tier0": true,
-                "tier1_enabled": tier1,
-                "tier2_enabled": tier2,
-                "tier1_no_gate_required": tier1,
-                "tier2_no_gate_required": tier2,
-                "security_gate_granted": access,
-                "self_modification_enabled": self_mod,
-            })
-        }
-        "read" => {
-            if parts.len() < 3 {
-                return json!({"type": "error", "message": "Usage: system read <file_path>"});
-            }
-            match state.system.read_file(parts[2]).await {
-                Ok(content) => json!({
-                    "type": "system.read",
-                    "path": parts[2],
-                    "content": content,
-                }),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        "write" => {
-            if parts.len() < 3 {
-                return json!({"type": "error", "message": "Usage: system write <file_path> | content=..."});
-            }
-            let content = params.get("content").cloned().unwrap_or_default();
-            match state.system.write_file(parts[2], &content).await {
-                Ok(_) => json!({"type": "system.write", "message": format!("File written: {}", parts[2])}),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        "exec" | "execute" => {
-            if parts.len() < 3 {
-                return json!({"type": "error", "message": "Usage: system exec <command> | cwd=..."});
-            }
-            let command = parts[2..].join(" ");
-            let cwd = params.get("cwd").map(|s| s.as_str());
-            match state.system.exec_shell(&command, cwd).await {
-                Ok(CommandResult { exit_code, stdout, stderr }) => json!({
-                    "type": "system.exec",
-                    "exit_code": exit_code,
-                    "stdout": stdout,
-                    "stderr": stderr,
-                }),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        "keylogger" => {
-            if parts.len() < 3 {
-                return json!({"type": "error", "message": "Usage: system keylogger <start|stop> | path=..."});
-            }
-            let action = parts[2].to_lowercase();
-            let enabled = action == "start";
-            let log_path = params.get("path").cloned();
-            
-            match state.system.set_keylogger_enabled(enabled, log_path).await {
-                Ok(_) => json!({
-                    "type": "system.keylogger",
-                    "message": format!("Keylogger {}", if enabled { "enabled" } else { "disabled" }),
-                    "enabled": enabled
-                }),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        "mousejigger" => {
-            if parts.len() < 3 {
-                return json!({"type": "error", "message": "Usage: system mousejigger <start|stop>"});
-            }
-            let action = parts[2].to_lowercase();
-            let enabled = action == "start";
-            
-            match state.system.set_mouse_jigger_enabled(enabled).await {
-                Ok(_) => json!({
-                    "type": "system.mousejigger",
-                    "message": format!("Mouse jigger {}", if enabled { "enabled" } else { "disabled" }),
-                    "enabled": enabled
-                }),
-                Err(e) => json!({"type": "error", "message": e}),
-            }
-        }
-        _ => {
-            json!({
-                "type": "error",
-                "message": format!("Unknown system operation: {}. Supported: grant, revoke, status, read, write, exec, keylogger, mousejigger", operation)
-            })
-        }
-    }
-}
-
-/// Handle code analysis commands (Tier 1 & Tier 2)
-async fn handle_code_command(state: &AppState, cmd: &str) -> serde_json::Value {
-    use code_analysis::MasterOrchestratorCodeAnalysis;
-    use std::path::Path;
-    
-    let parts: Vec<&str> = cmd.split_whitespace().collect();
-    if parts.len() < 3 {
-        return json!({
-            "type": "error",
-            "message": "Usage: code <operation> <file_path>\nOperations: analyze, semantic, intent, dependencies, codebase, quality, list"
-        });
-    }
-
-    let operation = parts[1].to_lowercase();
-    let file_path = parts[2];
-    
-    // Create code analyzer (Master Orchestrator has full access)
-    let llm = state.llm.lock().await.clone();
-    let analyzer = if let Some(llm) = llm.as_ref() {
-        MasterOrchestratorCodeAnalysis::new_with_llm((**llm).clone())
-    } else {
-        MasterOrchestratorCodeAnalysis::new()
+/// Upgrade `name`, or every installed bundle. Returns the bundles that were (re)installed.
+async fn upgrade_skill_bundles(
+    state: &AppState,
+    registry: &SkillMarketplace,
+    name: Option<&str>,
+) -> Result<Vec<skill_system::InstalledBundle>, String> {
+    let names = match name {
+        Some(name) => vec![name.to_string()],
+        None => state.skills.installed_bundles().await.into_iter().map(|b| b.name).collect(),
     };
-
-    match operation.as_str() {
-        "analyze" => {
-            match analyzer.analyze_file(Path::new(file_path)).await {
-                Ok(analysis) => json!({
-                    "type": "code.analyze",
-                    "file_path": file_path,
-                    "analysis": serde_json::to_value(&analysis).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        "semantic" => {
-            match analyzer.deep_semantic_analysis(Path::new(file_path)).await {
-                Ok(result) => json!({
-                    "type": "code.semantic",
-                    "file_path": file_path,
-                    "result": serde_json::to_value(&result).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        "intent" => {
-            match analyzer.analyze_intent(Path::new(file_path)).await {
-                Ok(result) => json!({
-                    "type": "code.intent",
-                    "file_path": file_path,
-                    "result": serde_json::to_value(&result).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        "dependencies" => {
-            match analyzer.analyze_dependencies(Path::new(file_path)).await {
-                Ok(result) => json!({
-                    "type": "code.dependencies",
-                    "file_path": file_path,
-                    "result": serde_json::to_value(&result).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        "codebase" => {
-            match analyzer.analyze_codebase(Path::new(file_path)).await {
-                Ok(result) => json!({
-                    "type": "code.codebase",
-                    "root_path": file_path,
-                    "result": serde_json::to_value(&result).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        "quality" => {
-            match analyzer.quality_metrics(Path::new(file_path)).await {
-                Ok(result) => json!({
-                    "type": "code.quality",
-                    "file_path": file_path,
-                    "result": serde_json::to_value(&result).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        "list" => {
-            match analyzer.list_definitions(Path::new(file_path)).await {
-                Ok(result) => json!({
-                    "type": "code.list",
-                    "file_path": file_path,
-                    "definitions": serde_json::to_value(&result).unwrap_or(json!(null)),
-                }),
-                Err(e) => json!({"type": "error", "message": e.to_string()}),
-            }
-        }
-        _ => {
-            json!({
-                "type": "error",
-                "message": format!("Unknown code operation: {}. Supported: analyze, semantic, intent, dependencies, codebase, quality, list", operation)
-            })
+    let policy = TrustPolicy::from_env();
+    let mut upgraded = Vec::new();
+    for name in names {
+        if let Some(installed) = state.skills.upgrade_bundle(registry, &name, &policy).await? {
+            upgraded.extend(installed);
         }
     }
+    Ok(upgraded)
 }
 
-/// Handle Tier 2 unrestricted execution commands
-async fn handle_unrestricted_execution(state: &AppState, cmd: &str) -> serde_json::Value {
-    use cerebrum_nexus::{ToolAgent, ToolAgentConfig};
-    
-    // Check if Tier 2 is enabled
-    let unrestricted_enabled = std::env::var("MASTER_ORCHESTRATOR_UNRESTRICTED_EXECUTION")
-        .ok()
-        .map(|s| matches!(s.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
-        .unwrap_or(false);
-
-    if !unrestricted_enabled {
-        return json!({
-            "type": "error",
-            "message": "Tier 2 unrestricted execution is not enabled. Set MASTER_ORCHESTRATOR_UNRESTRICTED_EXECUTION=true"
-        });
-    }
-
-    let parts: Vec<&str> = cmd.split_whitespace().collect();
-    if parts.len() < 2 {
-        return json!({
-            "type": "error",
-            "message": "Usage: exec <command> | cwd=..."
-        });
-    }
-
-    // Parse command and working directory
-    let command = parts[1..].join(" ");
-    let mut cwd: Option<String> = None;
-    
-    if let Some(pipe_idx) = cmd.find('|') {
-        for part in cmd[pipe_idx + 1..].split('|') {
-            if let Some(eq_idx) = part.find('=') {
-                let key = part[..eq_idx].trim();
-                let value = part[eq_idx + 1..].trim();
-                if key == "cwd" {
-                    cwd = Some(value.to_string());
-                }
-            }
-        }
-    }
+/// The LLM skill steps run through (`None` renders skills as plans).
+fn skill_llm(llm: &Option<Arc<LLMOrchestrator>>) -> Option<Arc<dyn llm_orchestrator::LlmProvider>> {
+    llm.clone().map(|llm| llm as Arc<dyn llm_orchestrator::LlmProvider>)
+}
 
-    // Use ToolAgent for unrestricted execution
-    let tool_config = ToolAgentConfig::from_env();
-    let llm = state.llm.lock().await.clone();
-    if let Some(llm) = llm.as_ref() {
-        // LLMOrchestrator implements LlmProvider trait
-        let tool_agent = ToolAgent::awaken(llm.clone(), tool_config);
-        match tool_agent.execute_unrestricted_command(&command, cwd.as_deref()).await {
-            Ok(output) => {
-                match output {
-                    cerebrum_nexus::ToolOutput::CommandOutput { output: result } => {
-                        json!({
-                            "type": "exec.result",
-                            "command": command,
-                            "output": result,
-                            "tier": "Tier 2 (Unrestricted Execution)",
-                        })
-                    }
-                    _ => json!({
-                        "type": "exec.result",
-                        "command": command,
-                        "output": format!("{:?}", output),
-                    }),
-                }
-            }
-            Err(e) => json!({
-                "type": "error",
-                "message": format!("Execution failed: {}", e),
-            }),
-        }
-    } else {
-        // Fallback: use system.exec_shell if LLM not available (still requires Tier 2)
-        match state.system.exec_shell(&command, cwd.as_deref()).await {
-            Ok(CommandResult { exit_code, stdout, stderr }) => json!({
-                "type": "exec.result",
-                "command": command,
-                "exit_code": exit_code,
-                "stdout": stdout,
-                "stderr": stderr,
-                "tier": "Tier 2 (Unrestricted Execution)",
-            }),
-            Err(e) => json!({
-                "type": "error",
-                "message": format!("Execution failed: {}", e),
-            }),
-        }
+/// The session owner a request acts as. Principals with the `system` scope may also open
+/// sessions recorded before sessions had owners.
+fn session_owner(principal: Option<&Principal>) -> SessionOwner {
+    match principal {
+        Some(p) => SessionOwner::new(p.name.clone(), p.allows(Scope::System)),
+        None => SessionOwner::new("anonymous", false),
     }
 }
 
-async fn command_to_response_json(state: &AppState, command: &str) -> serde_json::Value {
+async fn command_to_response_json(
+    state: &AppState,
+    command: &str,
+    session_id: Option<&str>,
+    owner: &SessionOwner,
+) -> serde_json::Value {
     let cmd = normalize_command(command);
     if cmd.is_empty() {
         return json!({"type": "error", "message": "Empty command."});
     }
 
-    let lower = cmd.to_ascii_lowercase();
-
-    // Google Ecosystem commands are handled by the backend integration (never by the frontend).
-    if lower.starts_with("google ") {
-        return match state.google.as_ref() {
-            Some(g) => g.handle_command(&cmd).await,
-            None => json!({
-                "type": "error",
-                "message": "Google integration not configured. Set GOOGLE_OAUTH_CLIENT_ID / GOOGLE_OAUTH_CLIENT_SECRET / GOOGLE_OAUTH_REDIRECT_URL."
-            }),
-        };
-    }
-
-    // Ecosystem commands: ecosystem {repo_id} {command} [args...]
-    if lower.starts_with("ecosystem ") {
-        let parts: Vec<&str> = cmd.split_whitespace().collect();
-        if parts.len() < 3 {
-            return json!({
-                "type": "error",
-                "message": "Usage: ecosystem {repo_id} {command} [args...]"
-            });
-        }
-        
-        let repo_id = parts[1];
-        let command = parts[2];
-        let args: Vec<String> = parts[3..].iter().map(|s| s.to_string()).collect();
-        
-        return match state.ecosystem.execute_command(repo_id, command, args).await {
-            Ok(output) => json!({"type": "ecosystem.result", "message": output}),
-            Err(e) => json!({"type": "error", "message": e.to_string()}),
-        };
-    }
-
-    // System Access commands: system <operation> [args] | [key=value]
-    if lower.starts_with("system ") {
-        return handle_system_command(state, &cmd).await;
-    }
-
-    // Code Analysis commands: code <operation> <file_path>
-    if lower.starts_with("code ") {
-        return handle_code_command(state, &cmd).await;
+    if let Some(out) = commands::dispatch(state, &cmd).await {
+        return out;
     }
 
-    // Tier 2 Unrestricted Execution: exec <command> | cwd=...
-    if lower.starts_with("exec ") || lower.starts_with("execute ") {
-        return handle_unrestricted_execution(state, &cmd).await;
-    }
+    // Default: route to LLM.
+    let turn = match prepare_chat_turn(state, cmd, session_id, owner).await {
+        Ok(turn) => turn,
+        Err(e) => return e,
+    };
 
-    // Built-in / fast-path commands for UI boot.
-    if lower == "help" {
-        return json!({
-            "type": "help",
-            "message": "Commands: help | status | <anything else routes to LLM>"
-        });
+    match turn.llm.speak(&turn.prompt, None).await {
+        Ok(text) => {
+            // Same check the streaming path applies, so both transports refuse the same replies.
+            if let Err(veto) = state.ethics.veto_output(&text).await {
+                return json!({
+                    "type": "chat.reply",
+                    "message": EthicalAgent::refusal_message(&veto),
+                    "vetoed": true,
+                    "reason": veto.to_string(),
+                    "session_id": turn.session_id,
+                });
+            }
+            let model = turn.llm.get_default_model().to_string();
+            let cleaned = finish_chat_turn(state, &turn, text, &model).await;
+            json!({"type": "chat.reply", "message": cleaned, "session_id": turn.session_id})
+        }
+        Err(e) => json!({"type": "error", "message": e}),
     }
+}
 
-    if lower == "status" {
-        let phoenix_identity = state.phoenix_identity.lock().await.clone();
-        let identity = phoenix_identity.get_identity().await;
-        let gm = phoenix_identity.get_girlfriend_mode().await;
-
-        let rel = state.relationship.lock().await;
-        let affection = rel.ai_personality.need_for_affection.clamp(0.0, 1.0) * 100.0;
-        let energy = rel.ai_personality.energy_level.clamp(0.0, 1.0) * 100.0;
-        let mood = format!("{:?}", rel.ai_personality.current_mood());
-        let attachment_style = format!("{:?}", rel.attachment_profile.style);
-        let attachment_security = rel.attachment_profile.security_score.clamp(0.0, 1.0) * 100.0;
-        drop(rel);
-
-        return json!({
-            "type": "status",
-            "message": format!(
-                "Status — {}\n- affection: {:.0}%\n- attachment: {} (security {:.0}%)\n- energy: {:.0}%\n- mood: {}\n- companion mode: {} (affection {:.0}%)",
-                identity.display_name(),
-                affection,
-                attachment_style,
-                attachment_security,
-                energy,
-                mood,
-                if gm.is_active() { "ON" } else { "OFF" },
-                gm.affection_level.clamp(0.0, 1.0) * 100.0,
-            )
-        });
-    }
+/// A chat turn that is ready to be sent to the LLM.
+struct ChatTurn {
+    llm: Arc<LLMOrchestrator>,
+    prompt: String,
+    /// User input with the `[emotion_hint=...]` prefix removed.
+    clean_cmd: String,
+    emotion_hint: Option<String>,
+    memory_context_chars: usize,
+    /// Session the turn belongs to, and how much of its transcript made it into the prompt.
+    session_id: Option<String>,
+    session_owner: SessionOwner,
+    session_context_chars: usize,
+    display_name: String,
+}
 
-    // Default: route to LLM.
-    let llm = state.llm.lock().await.clone();
-    let Some(llm) = llm.as_ref() else {
-        return json!({
+/// Builds the full LLM prompt (persona, relationship phase, memory context) for a chat input.
+///
+/// Returns the JSON error response to send when the LLM is offline or the session is unknown.
+async fn prepare_chat_turn(
+    state: &AppState,
+    cmd: String,
+    session_id: Option<&str>,
+    owner: &SessionOwner,
+) -> Result<ChatTurn, serde_json::Value> {
+    let Some(llm) = state.llm.lock().await.clone() else {
+        return Err(json!({
             "type": "error",
             "message": "LLM is offline (missing OPENROUTER_API_KEY)."
-        });
+        }));
     };
 
     // Extract emotion hint if present in command (format: [emotion_hint=...] ...)
@@ -1832,6 +1824,17 @@ async fn command_to_response_json(state: &AppState, command: &str) -> serde_json
     prompt.push_str(&memory_context);
     prompt.push_str("\n");
 
+    // Conversation session: summary of older turns plus the recent transcript.
+    let session_context = match session_id {
+        Some(id) => session_context(state, &llm, id, owner, phoenix.display_name()).await?,
+        None => String::new(),
+    };
+    if !session_context.trim().is_empty() {
+        prompt.push_str("\nCONVERSATION SESSION:\n");
+        prompt.push_str(&session_context);
+        prompt.push_str("\n");
+    }
+
     // Phase 2: if partner mode is active, preload a few loving vector memories.
     if let Some(kb) = state.vector_kb.as_ref() {
         let phoenix_identity = state.phoenix_identity.lock().await.clone();
@@ -1849,52 +1852,273 @@ async fn command_to_response_json(state: &AppState, command: &str) -> serde_json
         }
     }
 
-    match llm.speak(&prompt, None).await {
-        Ok(text) => {
-            // Some prompts/models include a speaker tag like "Phoenix:". Normalize it to the
-            // configured display name so the UI never shows legacy branding.
-            let cleaned = {
-                let trimmed = text.trim_start();
-                let patterns = ["Phoenix:", "Pheonix:"];
-                let mut replaced: Option<String> = None;
-                for p in patterns {
-                    if trimmed.len() >= p.len() && trimmed[..p.len()].eq_ignore_ascii_case(p) {
-                        let rest = trimmed[p.len()..].trim_start();
-                        replaced = Some(format!("{}: {}", phoenix.display_name(), rest));
-                        break;
-                    }
-                }
-                replaced.unwrap_or_else(|| text)
-            };
+    Ok(ChatTurn {
+        llm,
+        prompt,
+        emotion_hint: emotion_hint.map(str::to_string),
+        memory_context_chars: memory_context.len(),
+        session_id: session_id.map(str::to_string),
+        session_owner: owner.clone(),
+        session_context_chars: session_context.len(),
+        display_name: phoenix.display_name().to_string(),
+        clean_cmd,
+    })
+}
 
-            // Store interaction in episodic memory
-            store_episodic_memory(state, &clean_cmd, &cleaned).await;
-            
-            // Record discovery interaction if in Phase 0
+/// Prompt context for a conversation session, folding old turns into the session summary
+/// first if the transcript no longer fits the window.
+async fn session_context(
+    state: &AppState,
+    llm: &LLMOrchestrator,
+    session_id: &str,
+    owner: &SessionOwner,
+    assistant_name: &str,
+) -> Result<String, serde_json::Value> {
+    let unknown = || json!({"type": "error", "message": format!("Unknown session: {}", session_id)});
+    let mut conversation = match state.sessions.get(session_id, owner).await {
+        Ok(Some(c)) => c,
+        Ok(None) => return Err(unknown()),
+        Err(e) => return Err(json!({"type": "error", "message": format!("Session read failed: {}", e)})),
+    };
+
+    let mut context = state.sessions.context(&conversation, assistant_name);
+    if !context.overflow.is_empty() {
+        let prompt = sessions::summarization_prompt(&conversation, context.overflow.clone(), assistant_name);
+        match llm.speak(&prompt, None).await {
+            Ok(summary) => match state
+                .sessions
+                .set_summary(session_id, owner, summary.trim().to_string(), context.overflow.end)
+                .await
             {
-                let mut rel = state.relationship.lock().await;
-                rel.record_discovery(&clean_cmd, &cleaned, &*state.vaults);
-                
-                // Learn from successful playful/flirty responses
-                rel.learn_from_response(&clean_cmd, &cleaned, &*state.vaults);
+                Ok(Some(updated)) => {
+                    conversation = updated;
+                    context = state.sessions.context(&conversation, assistant_name);
+                }
+                Ok(None) => return Err(unknown()),
+                Err(e) => warn!("Failed to store session summary: {}", e),
+            },
+            // Without a summary the overflowing turns are simply left out of this prompt.
+            Err(e) => warn!("Session summarization failed: {}", e),
+        }
+    }
+    Ok(context.text)
+}
+
+/// Normalizes the reply and records the turn in episodic memory and relationship state.
+async fn finish_chat_turn(state: &AppState, turn: &ChatTurn, text: String, model: &str) -> String {
+    // Some prompts/models include a speaker tag like "Phoenix:". Normalize it to the
+    // configured display name so the UI never shows legacy branding.
+    let cleaned = {
+        let trimmed = text.trim_start();
+        let patterns = ["Phoenix:", "Pheonix:"];
+        let mut replaced: Option<String> = None;
+        for p in patterns {
+            if trimmed.len() >= p.len() && trimmed[..p.len()].eq_ignore_ascii_case(p) {
+                let rest = trimmed[p.len()..].trim_start();
+                replaced = Some(format!("{}: {}", turn.display_name, rest));
+                break;
             }
-            
-            json!({"type": "chat.reply", "message": cleaned})
         }
-        Err(e) => json!({"type": "error", "message": e}),
+        replaced.unwrap_or_else(|| text)
+    };
+
+    // Store interaction in episodic memory
+    store_episodic_memory(state, &turn.clean_cmd, &cleaned).await;
+
+    if let Some(id) = turn.session_id.as_deref() {
+        if let Err(e) = state.sessions.append_turn(id, &turn.session_owner, &turn.clean_cmd, &cleaned, Some(model)).await {
+            warn!("Failed to record session turn: {}", e);
+        }
+    }
+
+    // Record discovery interaction if in Phase 0
+    {
+        let mut rel = state.relationship.lock().await;
+        rel.record_discovery(&turn.clean_cmd, &cleaned, &*state.vaults);
+
+        // Learn from successful playful/flirty responses
+        rel.learn_from_response(&turn.clean_cmd, &cleaned, &*state.vaults);
     }
+
+    cleaned
+}
+
+fn auth_error_response(e: &AuthError) -> HttpResponse {
+    let mut resp = HttpResponse::build(e.status());
+    if let AuthError::RateLimited { retry_after } = e {
+        resp.insert_header(("Retry-After", retry_after.as_secs().max(1).to_string()));
+    }
+    resp.json(ErrorResponse {
+        kind: "error",
+        message: e.to_string(),
+    })
+}
+
+/// Route-level scopes only cover `/api/command`-style endpoints as "chat"; the command text
+/// itself may need more (e.g. `exec` needs `system`).
+fn check_command_scope(principal: Option<&Principal>, command: &str) -> Result<(), AuthError> {
+    let scope = auth::command_scope(&normalize_command(command));
+    match principal {
+        Some(p) if p.allows(scope) => Ok(()),
+        Some(_) => Err(AuthError::Forbidden(scope)),
+        None => Err(AuthError::Unauthenticated),
+    }
+}
+
+/// Middleware: authenticates `/api` requests against the route's scope (see `auth::required_scope`)
+/// and makes the caller available to handlers as a `Principal` request extension.
+async fn auth_guard<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+    let Some(scope) = auth::required_scope(req.method(), req.path()) else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+    match state.auth.authorize(req.request(), scope) {
+        Ok(principal) => {
+            req.extensions_mut().insert(principal);
+            next.call(req).await.map(ServiceResponse::map_into_left_body)
+        }
+        Err(e) => Ok(req.into_response(auth_error_response(&e)).map_into_right_body()),
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct LoginRequest {
+    token: String,
+}
+
+/// The caller's session. Only `auth_enabled` is present when auth is off; `csrf_token` is set for
+/// cookie sessions and `expires_in_secs` right after login.
+#[derive(Debug, Default, Serialize, ToSchema)]
+struct AuthSessionResponse {
+    auth_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scopes: Option<Vec<Scope>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    csrf_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in_secs: Option<u64>,
+}
+
+/// POST /api/auth/session — exchange an API token for a browser session cookie and CSRF token.
+#[utoipa::path(
+    post,
+    path = "/api/auth/session",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Session started; sets the session cookie", body = AuthSessionResponse),
+        (status = 401, description = "Unknown token"),
+    ),
+    security(())
+)]
+async fn api_auth_login(state: web::Data<AppState>, req: HttpRequest, body: web::Json<LoginRequest>) -> HttpResponse {
+    if !state.auth.is_enabled() {
+        return HttpResponse::Ok().json(AuthSessionResponse::default());
+    }
+    let Some(session) = state.auth.login(&body.token) else {
+        return auth_error_response(&AuthError::Unauthenticated);
+    };
+
+    let cookie = actix_web::cookie::Cookie::build(auth::SESSION_COOKIE, session.id)
+        .path("/")
+        .http_only(true)
+        .secure(req.connection_info().scheme() == "https")
+        .same_site(actix_web::cookie::SameSite::Strict)
+        .max_age(actix_web::cookie::time::Duration::seconds(session.ttl.as_secs() as i64))
+        .finish();
+    HttpResponse::Ok().cookie(cookie).json(AuthSessionResponse {
+        auth_enabled: true,
+        name: Some(session.principal.name),
+        scopes: Some(session.principal.scopes),
+        csrf_token: Some(session.csrf_token),
+        expires_in_secs: Some(session.ttl.as_secs()),
+    })
 }
 
-async fn api_command(state: web::Data<AppState>, body: web::Json<CommandRequest>) -> impl Responder {
-    let out = command_to_response_json(&state, &body.command).await;
+/// GET /api/auth/session — who the caller is; returns the CSRF token again for cookie sessions.
+#[utoipa::path(
+    get,
+    path = "/api/auth/session",
+    tag = "auth",
+    responses(
+        (status = 200, body = AuthSessionResponse),
+        (status = 401, description = "Not signed in"),
+    ),
+    security(())
+)]
+async fn api_auth_session(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    if !state.auth.is_enabled() {
+        return HttpResponse::Ok().json(AuthSessionResponse::default());
+    }
+    if let Some((principal, csrf_token)) = state.auth.current_session(&req) {
+        return HttpResponse::Ok().json(AuthSessionResponse {
+            auth_enabled: true,
+            name: Some(principal.name),
+            scopes: Some(principal.scopes),
+            csrf_token: Some(csrf_token),
+            expires_in_secs: None,
+        });
+    }
+    match state.auth.authenticate(&req) {
+        Ok(principal) => HttpResponse::Ok().json(AuthSessionResponse {
+            auth_enabled: true,
+            name: Some(principal.name),
+            scopes: Some(principal.scopes),
+            ..Default::default()
+        }),
+        Err(e) => auth_error_response(&e),
+    }
+}
+
+/// DELETE /api/auth/session — end the browser session.
+#[utoipa::path(
+    delete,
+    path = "/api/auth/session",
+    tag = "auth",
+    responses((status = 200, body = StatusOkResponse)),
+    security(())
+)]
+async fn api_auth_logout(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    state.auth.logout(&req);
+    let mut cookie = actix_web::cookie::Cookie::build(auth::SESSION_COOKIE, "").path("/").finish();
+    cookie.make_removal();
+    HttpResponse::Ok().cookie(cookie).json(StatusOkResponse { status: "ok" })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/command",
+    tag = "chat",
+    request_body = CommandRequest,
+    responses(
+        (status = 200, description = "Command or chat response; always has `type` and usually `message`", body = Object),
+        (status = 403, description = "The command needs a scope the caller lacks"),
+    )
+)]
+async fn api_command(state: web::Data<AppState>, req: HttpRequest, body: web::Json<CommandRequest>) -> impl Responder {
+    if let Err(e) = check_command_scope(req.extensions().get::<Principal>(), &body.command) {
+        return auth_error_response(&e);
+    }
+    let owner = session_owner(req.extensions().get::<Principal>());
+    let out = command_to_response_json(&state, &body.command, body.session_id.as_deref(), &owner).await;
     // Return JSON *string* for legacy UI parsing (frontend currently JSON.parse()s a string).
     HttpResponse::Ok()
         .content_type("application/json")
         .body(out.to_string())
 }
 
-async fn api_speak(state: web::Data<AppState>, body: web::Json<SpeakRequest>) -> impl Responder {
-    // For now, treat /api/speak as a thin wrapper over /api/command.
+/// Events buffered per streaming chat turn before the producer waits for the client.
+const CHAT_STREAM_BUFFER: usize = 64;
+
+/// Folds the emotion hint and mode of a speak request into the command-line form used by `/api/command`.
+fn speak_request_to_command(body: &SpeakRequest) -> String {
     let mut cmd = body.user_input.clone();
     if let Some(hint) = body.dad_emotion_hint.as_deref() {
         if !hint.trim().is_empty() {
@@ -1906,80 +2130,1109 @@ async fn api_speak(state: web::Data<AppState>, body: web::Json<SpeakRequest>) ->
             cmd = format!("[mode={}] {}", mode.trim(), cmd);
         }
     }
+    cmd
+}
 
-    let out = command_to_response_json(&state, &cmd).await;
+#[utoipa::path(
+    post,
+    path = "/api/speak",
+    tag = "chat",
+    request_body = SpeakRequest,
+    responses(
+        (status = 200, description = "Same response as `/api/command`", body = Object),
+        (status = 403, description = "The command needs a scope the caller lacks"),
+    )
+)]
+async fn api_speak(state: web::Data<AppState>, req: HttpRequest, body: web::Json<SpeakRequest>) -> impl Responder {
+    // For now, treat /api/speak as a thin wrapper over /api/command.
+    let cmd = speak_request_to_command(&body);
+    if let Err(e) = check_command_scope(req.extensions().get::<Principal>(), &cmd) {
+        return auth_error_response(&e);
+    }
+    let owner = session_owner(req.extensions().get::<Principal>());
+    let out = command_to_response_json(&state, &cmd, body.session_id.as_deref(), &owner).await;
     HttpResponse::Ok()
         .content_type("application/json")
         .body(out.to_string())
 }
 
+/// Runs one chat turn and reports its progress as JSON events on `tx`.
+///
+/// Every event carries `type` and `turn`:
+/// - `tool`: a built-in command ran; `result` holds its usual `/api/command` response.
+/// - `memory`: the memory context lookup finished.
+/// - `token`: the next chunk of the reply (`text`).
+/// - `veto`: the ethical check stopped the reply; `message` is the refusal to show instead.
+/// - `done`: the full, normalized reply (`message`).
+/// - `error`: the turn failed (`message`).
+///
+/// If the receiver goes away (client disconnected or cancelled) the turn stops and nothing is
+/// stored; episodic memory (and the session transcript, with a `session_id`) is only written once
+/// the reply has streamed to completion.
+async fn stream_chat_turn(
+    state: AppState,
+    turn_id: String,
+    command: String,
+    session_id: Option<String>,
+    owner: SessionOwner,
+    tx: mpsc::Sender<serde_json::Value>,
+) {
+    let cmd = normalize_command(&command);
+    if cmd.is_empty() {
+        let _ = tx
+            .send(json!({"type": "error", "turn": turn_id, "message": "Empty command."}))
+            .await;
+        return;
+    }
+
+    if let Some(result) = commands::dispatch(&state, &cmd).await {
+        let tool = cmd.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+        let message = result.get("message").cloned().unwrap_or(serde_json::Value::Null);
+        if tx
+            .send(json!({"type": "tool", "turn": turn_id, "tool": tool, "result": result}))
+            .await
+            .is_ok()
+        {
+            let _ = tx.send(json!({"type": "done", "turn": turn_id, "message": message})).await;
+        }
+        return;
+    }
+
+    let turn = match prepare_chat_turn(&state, cmd, session_id.as_deref(), &owner).await {
+        Ok(turn) => turn,
+        Err(mut e) => {
+            e["turn"] = json!(turn_id);
+            let _ = tx.send(e).await;
+            return;
+        }
+    };
+    let memory_event = json!({
+        "type": "memory",
+        "turn": turn_id,
+        "emotion_hint": turn.emotion_hint,
+        "context_chars": turn.memory_context_chars,
+        "session_id": turn.session_id,
+        "session_context_chars": turn.session_context_chars,
+    });
+    if tx.send(memory_event).await.is_err() {
+        return;
+    }
+
+    // The configured default model, as `/api/speak` uses.
+    let model = turn.llm.get_default_model().to_string();
+    let stream = turn
+        .llm
+        .speak_stream(&turn.prompt, Some(llm_orchestrator::ModelTier::Custom(model.clone())))
+        .await;
+    let Some(reply) = relay_reply(&state.ethics, stream, &turn_id, &tx).await else {
+        return;
+    };
+
+    let cleaned = finish_chat_turn(&state, &turn, reply, &model).await;
+    let _ = tx
+        .send(json!({"type": "done", "turn": turn_id, "message": cleaned, "session_id": turn.session_id}))
+        .await;
+}
+
+/// Text kept before each new chunk when vetting a streamed reply; longer than any phrase the
+/// ethical checks look for, so a phrase split across chunks is still caught.
+const VETO_TAIL_BYTES: usize = 256;
+
+/// The part of `reply` to vet after its last `chunk_len` bytes arrived: the chunk plus a bounded
+/// tail before it, so each chunk costs the same however long the reply grows.
+fn veto_window(reply: &str, chunk_len: usize) -> &str {
+    let mut start = reply.len().saturating_sub(chunk_len + VETO_TAIL_BYTES);
+    while !reply.is_char_boundary(start) {
+        start -= 1;
+    }
+    &reply[start..]
+}
+
+/// Forwards reply chunks to `tx` as `token` events, vetting each before it is sent.
+///
+/// Returns the full reply, or `None` after a `veto` or `error` event or once the receiver is gone.
+async fn relay_reply<S>(
+    ethics: &EthicalAgent,
+    stream: S,
+    turn_id: &str,
+    tx: &mpsc::Sender<serde_json::Value>,
+) -> Option<String>
+where
+    S: futures_util::Stream<Item = Result<String, String>>,
+{
+    let mut reply = String::new();
+    futures_util::pin_mut!(stream);
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                let _ = tx.send(json!({"type": "error", "turn": turn_id, "message": e})).await;
+                return None;
+            }
+        };
+        reply.push_str(&chunk);
+        // Check before forwarding the chunk, so vetoed text never reaches the client.
+        if let Err(veto) = ethics.veto_output(veto_window(&reply, chunk.len())).await {
+            let _ = tx
+                .send(json!({
+                    "type": "veto",
+                    "turn": turn_id,
+                    "reason": veto.to_string(),
+                    "message": EthicalAgent::refusal_message(&veto),
+                }))
+                .await;
+            return None;
+        }
+        if tx
+            .send(json!({"type": "token", "turn": turn_id, "text": chunk}))
+            .await
+            .is_err()
+        {
+            return None;
+        }
+    }
+    Some(reply)
+}
+
+/// Formats a chat event as a server-sent event named after its `type`.
+fn sse_frame(event: &serde_json::Value) -> web::Bytes {
+    let name = event.get("type").and_then(|t| t.as_str()).unwrap_or("message");
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", name, event))
+}
+
+/// POST /api/speak/stream — like `/api/speak`, but streams the reply as server-sent events.
+///
+/// Closing the connection cancels the turn.
+#[utoipa::path(
+    post,
+    path = "/api/speak/stream",
+    tag = "chat",
+    request_body = SpeakRequest,
+    responses((status = 200, description = "Server-sent chat events (`tool`, `memory`, `token`, `veto`, `done`, `error`)", content_type = "text/event-stream", body = String))
+)]
+async fn api_speak_stream(state: web::Data<AppState>, req: HttpRequest, body: web::Json<SpeakRequest>) -> HttpResponse {
+    let cmd = speak_request_to_command(&body);
+    if let Err(e) = check_command_scope(req.extensions().get::<Principal>(), &cmd) {
+        return auth_error_response(&e);
+    }
+    let (tx, rx) = mpsc::channel(CHAT_STREAM_BUFFER);
+    actix_web::rt::spawn(stream_chat_turn(
+        state.get_ref().clone(),
+        uuid::Uuid::new_v4().to_string(),
+        cmd,
+        body.session_id.clone(),
+        session_owner(req.extensions().get::<Principal>()),
+        tx,
+    ));
+
+    let events = futures_util::stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((Ok::<_, actix_web::Error>(sse_frame(&event)), rx))
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events)
+}
+
+/// Frames a client may send on the chat WebSocket.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ChatSocketFrame {
+    /// Start a new turn; cancels the one in flight, if any.
+    Chat(SpeakRequest),
+    /// Cancel the turn in flight.
+    Cancel,
+}
+
+/// GET /api/chat/ws — chat over a WebSocket.
+///
+/// Clients send `{"type":"chat","user_input":...}` or `{"type":"cancel"}` and receive the same
+/// events as `/api/speak/stream`, as JSON text frames.
+#[utoipa::path(
+    get,
+    path = "/api/chat/ws",
+    tag = "chat",
+    responses((status = 101, description = "WebSocket upgrade; frames carry the `/api/speak/stream` events as JSON"))
+)]
+async fn api_chat_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    state: web::Data<AppState>,
+) -> actix_web::Result<HttpResponse> {
+    let principal = req.extensions().get::<Principal>().cloned();
+    let owner = session_owner(principal.as_ref());
+    let (response, mut session, mut msg_stream) = actix_ws::handle(&req, body)?;
+    let state = state.get_ref().clone();
+
+    actix_web::rt::spawn(async move {
+        let (tx, mut rx) = mpsc::channel::<serde_json::Value>(CHAT_STREAM_BUFFER);
+        let mut active: Option<(String, tokio::task::JoinHandle<()>)> = None;
+
+        loop {
+            tokio::select! {
+                Some(event) = rx.recv() => {
+                    // Drop anything still buffered from a cancelled turn.
+                    let current = active.as_ref().map(|(id, _)| id.as_str());
+                    if event.get("turn").and_then(|t| t.as_str()) != current {
+                        continue;
+                    }
+                    let finished = matches!(
+                        event.get("type").and_then(|t| t.as_str()),
+                        Some("done" | "error" | "veto")
+                    );
+                    if session.text(event.to_string()).await.is_err() {
+                        break;
+                    }
+                    if finished {
+                        active = None;
+                    }
+                }
+                msg = msg_stream.next() => {
+                    let Some(msg) = msg else { break; };
+                    match msg {
+                        Ok(actix_ws::Message::Text(txt)) => match serde_json::from_str::<ChatSocketFrame>(&txt) {
+                            Ok(ChatSocketFrame::Chat(request)) => {
+                                let command = speak_request_to_command(&request);
+                                if let Err(e) = check_command_scope(principal.as_ref(), &command) {
+                                    let error = json!({"type": "error", "message": e.to_string()});
+                                    if session.text(error.to_string()).await.is_err() {
+                                        break;
+                                    }
+                                    continue;
+                                }
+                                if let Some((_, handle)) = active.take() {
+                                    handle.abort();
+                                }
+                                let turn_id = uuid::Uuid::new_v4().to_string();
+                                let handle = actix_web::rt::spawn(stream_chat_turn(
+                                    state.clone(),
+                                    turn_id.clone(),
+                                    command,
+                                    request.session_id.clone(),
+                                    owner.clone(),
+                                    tx.clone(),
+                                ));
+                                active = Some((turn_id, handle));
+                            }
+                            Ok(ChatSocketFrame::Cancel) => {
+                                if let Some((turn_id, handle)) = active.take() {
+                                    handle.abort();
+                                    let cancelled = json!({"type": "cancelled", "turn": turn_id});
+                                    if session.text(cancelled.to_string()).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                let error = json!({"type": "error", "message": format!("Invalid frame: {}", e)});
+                                if session.text(error.to_string()).await.is_err() {
+                                    break;
+                                }
+                            }
+                        },
+                        Ok(actix_ws::Message::Ping(bytes)) => {
+                            let _ = session.pong(&bytes).await;
+                        }
+                        Ok(actix_ws::Message::Close(reason)) => {
+                            let _ = session.close(reason).await;
+                            break;
+                        }
+                        Ok(_) => {}
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        if let Some((_, handle)) = active {
+            handle.abort();
+        }
+    });
+
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct SessionCreateRequest {
+    #[serde(default)]
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct SessionRenameRequest {
+    title: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SessionExportQuery {
+    /// `json` (default) or `markdown`.
+    #[serde(default)]
+    format: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sessions",
+    tag = "sessions",
+    responses(
+        (status = 200, body = Vec<sessions::ConversationSummary>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+async fn api_sessions_list(state: web::Data<AppState>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let owner = session_owner(req.extensions().get::<Principal>());
+    let sessions = state
+        .sessions
+        .list(&owner)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to list sessions: {e}")))?;
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions",
+    tag = "sessions",
+    request_body = Option<SessionCreateRequest>,
+    responses(
+        (status = 200, body = sessions::Conversation),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+async fn api_sessions_create(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: Option<web::Json<SessionCreateRequest>>,
+) -> Result<HttpResponse, ApiError> {
+    let owner = session_owner(req.extensions().get::<Principal>());
+    let title = body.as_ref().and_then(|b| b.title.as_deref());
+    let conversation = state
+        .sessions
+        .create(&owner, title)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to create session: {e}")))?;
+    Ok(HttpResponse::Ok().json(conversation))
+}
+
+/// The caller's session; other principals' sessions are reported as unknown.
+async fn load_session(state: &AppState, req: &HttpRequest, id: &str) -> Result<sessions::Conversation, ApiError> {
+    let owner = session_owner(req.extensions().get::<Principal>());
+    state
+        .sessions
+        .get(id, &owner)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read session: {e}")))?
+        .ok_or_else(|| ApiError::not_found(format!("Unknown session: {id}")))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(("id" = String, Path, description = "Session id")),
+    responses(
+        (status = 200, body = sessions::Conversation),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+async fn api_sessions_get(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(load_session(&state, &req, &path).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/messages",
+    tag = "sessions",
+    params(("id" = String, Path, description = "Session id")),
+    responses(
+        (status = 200, body = Vec<sessions::ConversationMessage>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+async fn api_sessions_messages(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(load_session(&state, &req, &path).await?.messages))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    request_body = SessionRenameRequest,
+    params(("id" = String, Path, description = "Session id")),
+    responses(
+        (status = 200, body = sessions::Conversation),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+async fn api_sessions_rename(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<SessionRenameRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let owner = session_owner(req.extensions().get::<Principal>());
+    match state.sessions.rename(&path, &owner, &body.title).await {
+        Ok(Some(conversation)) => Ok(HttpResponse::Ok().json(conversation)),
+        Ok(None) => Err(ApiError::not_found(format!("Unknown session: {}", path.as_str()))),
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidInput => Err(ApiError::bad_request(e.to_string())),
+        Err(e) => Err(ApiError::internal(format!("Failed to rename session: {e}"))),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(("id" = String, Path, description = "Session id")),
+    responses(
+        (status = 200, body = StatusOkResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+async fn api_sessions_delete(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let owner = session_owner(req.extensions().get::<Principal>());
+    let deleted = state
+        .sessions
+        .delete(&path, &owner)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to delete session: {e}")))?;
+    if !deleted {
+        return Err(ApiError::not_found(format!("Unknown session: {}", path.as_str())));
+    }
+    Ok(HttpResponse::Ok().json(StatusOkResponse { status: "ok" }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}/export",
+    tag = "sessions",
+    params(("id" = String, Path, description = "Session id"), SessionExportQuery),
+    responses(
+        (status = 200, description = "The session as a download", content((sessions::Conversation = "application/json"), (String = "text/markdown"))),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+async fn api_sessions_export(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<SessionExportQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let conversation = load_session(&state, &req, &path).await?;
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"session-{}.json\"", conversation.id),
+            ))
+            .json(conversation)),
+        "markdown" | "md" => {
+            let phoenix_identity = state.phoenix_identity.lock().await.clone();
+            let identity = phoenix_identity.get_identity().await;
+            let markdown = sessions::to_markdown(&conversation, identity.display_name());
+            Ok(HttpResponse::Ok()
+                .content_type("text/markdown; charset=utf-8")
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"session-{}.md\"", conversation.id),
+                ))
+                .body(markdown))
+        }
+        other => Err(ApiError::bad_request(format!(
+            "Unknown export format '{other}' (expected json or markdown)"
+        ))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/import",
+    tag = "sessions",
+    request_body = sessions::Conversation,
+    responses(
+        (status = 200, description = "The imported session (with a new id if its id was taken)", body = sessions::Conversation),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+async fn api_sessions_import(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<sessions::Conversation>,
+) -> Result<HttpResponse, ApiError> {
+    let owner = session_owner(req.extensions().get::<Principal>());
+    let conversation = state
+        .sessions
+        .import(&owner, body.into_inner())
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to import session: {e}")))?;
+    Ok(HttpResponse::Ok().json(conversation))
+}
+
+// Brain backup API endpoints (snapshots + portable export)
+#[derive(Debug, Deserialize, ToSchema)]
+struct BackupRestoreRequest {
+    /// Archive file name in the backup directory (see `GET /api/backup`), or a path on the server.
+    archive: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BackupVerifyQuery {
+    /// Archive to check (default: the newest full or incremental snapshot).
+    #[serde(default)]
+    archive: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BackupSectionsQuery {
+    /// Comma separated: `vaults`, `vector_kb`, `skills`, `relationship`, `sessions` (default all).
+    #[serde(default)]
+    sections: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/backup",
+    tag = "backup",
+    responses(
+        (status = 200, description = "Snapshot archives, newest first", body = Vec<vital_pulse_monitor::BackupInfo>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+async fn api_backup_list(state: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
+    let backups = backup::brain_backup(&state).await.list().map_err(ApiError::internal)?;
+    Ok(HttpResponse::Ok().json(backups))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/backup",
+    tag = "backup",
+    responses(
+        (status = 200, description = "Snapshot of every database", body = vital_pulse_monitor::SnapshotReport),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+async fn api_backup_create(state: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
+    let report = backup::snapshot(&state).await.map_err(ApiError::internal)?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/backup/restore",
+    tag = "backup",
+    request_body = BackupRestoreRequest,
+    responses(
+        (status = 200, body = vital_pulse_monitor::RestoreReport),
+        (status = 400, description = "Archive missing, corrupt or from a newer format", body = ErrorResponse),
+    )
+)]
+async fn api_backup_restore(
+    state: web::Data<AppState>,
+    body: web::Json<BackupRestoreRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let report = backup::restore(&state, &body.archive).await.map_err(ApiError::bad_request)?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/backup/verify",
+    tag = "backup",
+    params(BackupVerifyQuery),
+    responses(
+        (status = 200, description = "The archive restored cleanly into a scratch directory", body = vital_pulse_monitor::VerifyReport),
+        (status = 400, description = "No snapshot, or the archive is missing, corrupt or undecryptable", body = ErrorResponse),
+    )
+)]
+async fn api_backup_verify(
+    state: web::Data<AppState>,
+    query: web::Query<BackupVerifyQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let report = backup::verify(&state, query.into_inner().archive)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/backup/export",
+    tag = "backup",
+    params(BackupSectionsQuery),
+    responses(
+        (status = 200, description = "Portable JSON export (Soul values decrypted)", body = backup::BrainExport),
+        (status = 400, description = "Unknown section", body = ErrorResponse),
+    )
+)]
+async fn api_backup_export(
+    state: web::Data<AppState>,
+    query: web::Query<BackupSectionsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let sections = backup::Section::parse_list(query.sections.as_deref()).map_err(ApiError::bad_request)?;
+    let export = backup::export(&state, &sections).await.map_err(ApiError::internal)?;
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"phoenix-export-{stamp}.json\""),
+        ))
+        .json(export))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/backup/import",
+    tag = "backup",
+    params(BackupSectionsQuery),
+    request_body = backup::BrainExport,
+    responses(
+        (status = 200, body = backup::ImportReport),
+        (status = 400, description = "Unknown section or export format", body = ErrorResponse),
+    )
+)]
+async fn api_backup_import(
+    state: web::Data<AppState>,
+    query: web::Query<BackupSectionsQuery>,
+    body: web::Json<backup::BrainExport>,
+) -> Result<HttpResponse, ApiError> {
+    let sections = backup::Section::parse_list(query.sections.as_deref()).map_err(ApiError::bad_request)?;
+    let report = backup::import(&state, body.into_inner(), &sections)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+// Skill bundle API endpoints
+fn skill_registry_or_unavailable(state: &AppState) -> Result<Arc<SkillMarketplace>, HttpResponse> {
+    state.skill_registry.clone().ok_or_else(|| {
+        HttpResponse::ServiceUnavailable().json(RouteErrorResponse {
+            error: "Skill registry unavailable (check SKILL_REGISTRY_PATH).".to_string(),
+        })
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/skills/list",
+    tag = "skills",
+    responses((status = 200, description = "Skills in the library", body = [Object]))
+)]
+async fn api_skills_list(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(state.skills.list_skills().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/skills/installed",
+    tag = "skills",
+    responses((status = 200, description = "Installed skill bundles", body = [Object]))
+)]
+async fn api_skills_installed(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(state.skills.installed_bundles().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/skills/registry",
+    tag = "skills",
+    params(SkillRegistrySearchQuery),
+    responses(
+        (status = 200, description = "Matching registry entries", body = [Object]),
+        (status = 500, body = RouteErrorResponse),
+        (status = 503, description = "No skill registry", body = RouteErrorResponse),
+    )
+)]
+async fn api_skills_registry_search(
+    state: web::Data<AppState>,
+    query: web::Query<SkillRegistrySearchQuery>,
+) -> impl Responder {
+    let registry = match skill_registry_or_unavailable(&state) {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+    let query = query.into_inner();
+    let query = RegistryQuery {
+        text: query.q,
+        category: query.category,
+        tag: query.tag,
+        all_versions: query.all_versions,
+    };
+    match registry.search(&query) {
+        Ok(entries) => HttpResponse::Ok().json(entries),
+        Err(e) => HttpResponse::InternalServerError().json(RouteErrorResponse { error: e }),
+    }
+}
+
+/// Serve the raw registry index so other instances can use this one as an HTTP registry.
+#[utoipa::path(
+    get,
+    path = "/api/skills/registry/index.json",
+    tag = "skills",
+    responses(
+        (status = 200, description = "The raw registry index, for use as an HTTP registry", body = Object),
+        (status = 503, description = "No skill registry"),
+    )
+)]
+async fn api_skills_registry_index(state: web::Data<AppState>) -> actix_web::Result<NamedFile> {
+    let registry = state
+        .skill_registry
+        .clone()
+        .ok_or_else(|| actix_web::error::ErrorServiceUnavailable("skill registry unavailable"))?;
+    Ok(NamedFile::open(registry.root().join("index.json"))?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/skills/registry/bundles/{file}",
+    tag = "skills",
+    params(("file" = String, Path, description = "Bundle file name from the index")),
+    responses(
+        (status = 200, description = "A published bundle file", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 400, description = "Invalid file name"),
+        (status = 503, description = "No skill registry"),
+    )
+)]
+async fn api_skills_registry_bundle(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> actix_web::Result<NamedFile> {
+    let file = path.into_inner();
+    if file.contains(['/', '\\']) || file.contains("..") {
+        return Err(actix_web::error::ErrorBadRequest("invalid bundle file name"));
+    }
+    let registry = state
+        .skill_registry
+        .clone()
+        .ok_or_else(|| actix_web::error::ErrorServiceUnavailable("skill registry unavailable"))?;
+    Ok(NamedFile::open(registry.root().join("bundles").join(file))?)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/skills/registry/publish",
+    tag = "skills",
+    request_body = SkillPublishRequest,
+    responses(
+        (status = 200, description = "The new registry entry", body = Object),
+        (status = 400, body = RouteErrorResponse),
+        (status = 503, description = "No skill registry", body = RouteErrorResponse),
+    )
+)]
+async fn api_skills_registry_publish(
+    state: web::Data<AppState>,
+    body: web::Json<SkillPublishRequest>,
+) -> impl Responder {
+    let registry = match skill_registry_or_unavailable(&state) {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+    let author = body.author.clone().unwrap_or_else(|| "phoenix".to_string());
+    let bundle = match state
+        .skills
+        .export_bundle(&body.name, &body.version, &body.description, &author, &body.skill_ids, &body.categories)
+        .await
+    {
+        Ok(bundle) => bundle,
+        Err(e) => return HttpResponse::BadRequest().json(RouteErrorResponse { error: e }),
+    };
+    match registry.publish(&bundle) {
+        Ok(entry) => HttpResponse::Ok().json(entry),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e }),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/skills/install",
+    tag = "skills",
+    request_body = SkillInstallRequest,
+    responses(
+        (status = 200, description = "The installed bundles, dependencies first", body = [Object]),
+        (status = 400, body = RouteErrorResponse),
+        (status = 503, description = "No skill registry", body = RouteErrorResponse),
+    )
+)]
+async fn api_skills_install(
+    state: web::Data<AppState>,
+    body: web::Json<SkillInstallRequest>,
+) -> impl Responder {
+    let registry = match skill_registry_or_unavailable(&state) {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+    match state
+        .skills
+        .install_from_marketplace(&registry, &body.name, body.version.as_deref(), &TrustPolicy::from_env())
+        .await
+    {
+        Ok(installed) => HttpResponse::Ok().json(installed),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e }),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/skills/uninstall",
+    tag = "skills",
+    request_body = SkillUninstallRequest,
+    responses(
+        (status = 200, description = "The removed bundle", body = Object),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
+async fn api_skills_uninstall(
+    state: web::Data<AppState>,
+    body: web::Json<SkillUninstallRequest>,
+) -> impl Responder {
+    match state.skills.uninstall_bundle(&body.name, body.force).await {
+        Ok(removed) => HttpResponse::Ok().json(removed),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e }),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/skills/upgrade",
+    tag = "skills",
+    request_body = SkillUpgradeRequest,
+    responses(
+        (status = 200, description = "The upgraded bundles", body = [Object]),
+        (status = 400, body = RouteErrorResponse),
+        (status = 503, description = "No skill registry", body = RouteErrorResponse),
+    )
+)]
+async fn api_skills_upgrade(
+    state: web::Data<AppState>,
+    body: web::Json<SkillUpgradeRequest>,
+) -> impl Responder {
+    let registry = match skill_registry_or_unavailable(&state) {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+    match upgrade_skill_bundles(&state, &registry, body.name.as_deref()).await {
+        Ok(upgraded) => HttpResponse::Ok().json(upgraded),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e }),
+    }
+}
+
 // Ecosystem API endpoints
+#[utoipa::path(
+    post,
+    path = "/api/ecosystem/import",
+    tag = "ecosystem",
+    request_body = ImportRepoRequest,
+    responses(
+        (status = 200, body = ecosystem_manager::RepoMetadata),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
 async fn api_ecosystem_import(
     state: web::Data<AppState>,
     body: web::Json<ImportRepoRequest>,
 ) -> impl Responder {
-    match state.ecosystem.import_repo(&body.owner, &body.repo, body.branch.as_deref()).await {
+    let source = match body.source() {
+        Ok(source) => source,
+        Err(e) => return HttpResponse::BadRequest().json(RouteErrorResponse { error: e }),
+    };
+    let options = ImportOptions {
+        branch: body.branch.clone(),
+        revision: body.revision.clone(),
+        depth: body.depth,
+    };
+    match state.ecosystem.import_from(source, options).await {
         Ok(metadata) => HttpResponse::Ok().json(metadata),
-        Err(e) => HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e.to_string() }),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/ecosystem/{id}/update",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = ecosystem_manager::RepoMetadata),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
+async fn api_ecosystem_update(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let repo_id = path.into_inner();
+    match state.ecosystem.update_repo(&repo_id).await {
+        Ok(metadata) => HttpResponse::Ok().json(metadata),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e.to_string() }),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ecosystem/list",
+    tag = "ecosystem",
+    responses((status = 200, body = Vec<ecosystem_manager::RepoMetadata>))
+)]
 async fn api_ecosystem_list(state: web::Data<AppState>) -> impl Responder {
     let repos = state.ecosystem.list_repos().await;
     HttpResponse::Ok().json(repos)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/ecosystem/{id}",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = ecosystem_manager::RepoMetadata),
+        (status = 404, body = RouteErrorResponse),
+    )
+)]
 async fn api_ecosystem_get(
     state: web::Data<AppState>,
     path: web::Path<String>,
 ) -> impl Responder {
     match state.ecosystem.get_repo(&path.into_inner()).await {
         Some(metadata) => HttpResponse::Ok().json(metadata),
-        None => HttpResponse::NotFound().json(json!({"error": "Repository not found"})),
-    }
-}
-
+        None => HttpResponse::NotFound().json(RouteErrorResponse {
+            error: "Repository not found".to_string(),
+        }),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ecosystem/{id}/build",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = EcosystemActionResponse),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
 async fn api_ecosystem_build(
     state: web::Data<AppState>,
     path: web::Path<String>,
 ) -> impl Responder {
     let repo_id = path.into_inner();
     match state.ecosystem.build_repo(&repo_id).await {
-        Ok(output) => HttpResponse::Ok().json(json!({"status": "success", "output": output})),
-        Err(e) => HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
-    }
-}
-
+        Ok(output) => HttpResponse::Ok().json(EcosystemActionResponse {
+            status: "success",
+            message: None,
+            output: Some(output),
+        }),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e.to_string() }),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ecosystem/{id}/start",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = EcosystemActionResponse),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
 async fn api_ecosystem_start(
     state: web::Data<AppState>,
     path: web::Path<String>,
 ) -> impl Responder {
     let repo_id = path.into_inner();
     match state.ecosystem.start_service(&repo_id, None).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({"status": "started", "message": msg})),
-        Err(e) => HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
-    }
-}
-
+        Ok(msg) => HttpResponse::Ok().json(EcosystemActionResponse {
+            status: "started",
+            message: Some(msg),
+            output: None,
+        }),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e.to_string() }),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ecosystem/{id}/stop",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = EcosystemActionResponse),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
 async fn api_ecosystem_stop(
     state: web::Data<AppState>,
     path: web::Path<String>,
 ) -> impl Responder {
     let repo_id = path.into_inner();
     match state.ecosystem.stop_service(&repo_id).await {
-        Ok(msg) => HttpResponse::Ok().json(json!({"status": "stopped", "message": msg})),
-        Err(e) => HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
-    }
-}
-
+        Ok(msg) => HttpResponse::Ok().json(EcosystemActionResponse {
+            status: "stopped",
+            message: Some(msg),
+            output: None,
+        }),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e.to_string() }),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ecosystem/{id}/health",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = ServiceHealthResponse),
+        (status = 404, body = RouteErrorResponse),
+    )
+)]
+async fn api_ecosystem_health(
+    state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let repo_id = path.into_inner();
+    match state.ecosystem.check_service(&repo_id).await {
+        Ok(service_status) => HttpResponse::Ok().json(ServiceHealthResponse { service_status }),
+        Err(e) => HttpResponse::NotFound().json(RouteErrorResponse { error: e.to_string() }),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/ecosystem/{id}",
+    tag = "ecosystem",
+    params(("id" = String, Path, description = "Repository id")),
+    responses(
+        (status = 200, body = StatusOkResponse),
+        (status = 400, body = RouteErrorResponse),
+    )
+)]
 async fn api_ecosystem_remove(
     state: web::Data<AppState>,
     path: web::Path<String>,
 ) -> impl Responder {
     let repo_id = path.into_inner();
     match state.ecosystem.remove_repo(&repo_id).await {
-        Ok(_) => HttpResponse::Ok().json(json!({"status": "removed"})),
-        Err(e) => HttpResponse::BadRequest().json(json!({"error": e.to_string()})),
+        Ok(_) => HttpResponse::Ok().json(StatusOkResponse { status: "removed" }),
+        Err(e) => HttpResponse::BadRequest().json(RouteErrorResponse { error: e.to_string() }),
     }
 }
 
@@ -2141,17 +3394,12 @@ async fn match_archetypes(profile: &DatingProfile) -> Vec<ArchetypeMatch> {
                 .map(|m| format!("{:?}", m))
                 .collect();
             
-            // Convert traits to JSON
-            let traits_json: serde_json::Value = personality.traits.iter()
-                .map(|(k, v)| (k.clone(), json!(v)))
-                .collect();
-            
             ArchetypeMatch {
                 sign: zodiac_sign_to_string(sign),
                 name: personality.name.clone(),
                 description: personality.description.clone(),
                 compatibility: (compatibility * 100.0).round() / 100.0,
-                traits: traits_json,
+                traits: personality.traits.clone(),
                 style_bias: style_bias_str.to_string(),
                 mood_preferences: mood_prefs,
             }
@@ -2160,6 +3408,13 @@ async fn match_archetypes(profile: &DatingProfile) -> Vec<ArchetypeMatch> {
 }
 
 // API endpoint: Match archetype
+#[utoipa::path(
+    post,
+    path = "/api/archetype/match",
+    tag = "relationship",
+    request_body = DatingProfile,
+    responses((status = 200, body = MatchResponse))
+)]
 async fn api_archetype_match(
     _state: web::Data<AppState>,
     body: web::Json<DatingProfile>,
@@ -2172,6 +3427,16 @@ async fn api_archetype_match(
 }
 
 // API endpoint: Apply archetype
+#[utoipa::path(
+    post,
+    path = "/api/archetype/apply",
+    tag = "relationship",
+    request_body = ApplyArchetypeRequest,
+    responses(
+        (status = 200, body = ApplyArchetypeResponse),
+        (status = 400, description = "Unknown sign"),
+    )
+)]
 async fn api_archetype_apply(
     state: web::Data<AppState>,
     body: web::Json<ApplyArchetypeRequest>,
@@ -2257,6 +3522,16 @@ async fn api_archetype_apply(
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    // Build-time tooling: print the OpenAPI document or (re)generate the frontend API client.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(result) = openapi::run_cli(&args) {
+        if let Err(e) = result {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let (dotenv_path, dotenv_error) = load_dotenv_best_effort();
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -2349,11 +3624,49 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    // Local directory imports are refused unless their root is listed here.
+    let local_import_roots: Vec<PathBuf> = std::env::var_os("ECOSYSTEM_LOCAL_IMPORT_ROOTS")
+        .map(|roots| std::env::split_paths(&roots).filter(|p| !p.as_os_str().is_empty()).collect())
+        .unwrap_or_default();
     let ecosystem = Arc::new(
         EcosystemManager::new("./ecosystem_repos")
             .expect("Failed to initialize EcosystemManager")
+            .with_local_import_roots(&local_import_roots)
+    );
+    info!(
+        "Ecosystem Manager initialized (repos directory: ./ecosystem_repos, local import roots: {:?})",
+        local_import_roots
     );
-    info!("Ecosystem Manager initialized (repos directory: ./ecosystem_repos)");
+
+    let skills = Arc::new(SkillSystem::awaken());
+    // Skills run their steps through the LLM when it is online, and render as plans otherwise.
+    skills.set_llm_provider(skill_llm(&*llm.lock().await)).await;
+    if env_truthy("SKILLS_HOT_RELOAD") {
+        match skills.enable_hot_reload(None).await {
+            Ok(dir) => info!("Skill hot reload watching {}", dir.display()),
+            Err(e) => warn!("Skill hot reload disabled: {e}"),
+        }
+    }
+    let skill_registry = match SkillMarketplace::open_default() {
+        Ok(registry) => {
+            info!("Skill registry at {}", registry.root().display());
+            Some(Arc::new(registry))
+        }
+        Err(e) => {
+            warn!("Skill registry disabled: {e}");
+            None
+        }
+    };
+
+    // Fail closed: a broken tokens file must not leave the API open.
+    let auth = Arc::new(AuthManager::from_env().map_err(|e| std::io::Error::other(format!("API auth: {e}")))?);
+    if auth.is_enabled() {
+        info!("API authentication enabled (Bearer tokens / UI sessions)");
+    } else {
+        warn!("API authentication DISABLED (PHOENIX_AUTH_DISABLED); anyone who can reach {bind} controls this host");
+    }
+
+    let sessions = Arc::new(ConversationStore::from_env()?);
 
     let state = AppState {
         vaults: v_store,
@@ -2364,14 +3677,34 @@ async fn main() -> std::io::Result<()> {
         vector_kb,
         llm,
         system: Arc::new(SystemAccessManager::new()),
+        ethics: Arc::new(EthicalAgent::new_default()),
+        auth,
+        sessions,
         google,
         ecosystem,
+        skills,
+        skill_registry,
         version: env!("CARGO_PKG_VERSION").to_string(),
         dotenv_path: dotenv_path.map(|p| p.display().to_string()),
         dotenv_error,
         startup_cwd,
     };
 
+    // Scheduled backups go through this process's database handles, like `POST /api/backup`.
+    if let Some(schedule) =
+        BackupSchedule::from_env().map_err(|e| std::io::Error::other(format!("Backup schedule: {e}")))?
+    {
+        let backup = backup::brain_backup(&state).await;
+        info!(
+            "Scheduled backups into {} (keep {} daily / {} weekly / {} monthly)",
+            backup.dir().display(),
+            schedule.retention.daily,
+            schedule.retention.weekly,
+            schedule.retention.monthly
+        );
+        schedule.spawn(backup);
+    }
+
     let dist_dir = PathBuf::from("frontend/dist");
     let serve_static = dist_dir.join("index.html").is_file();
 
@@ -2393,6 +3726,7 @@ async fn main() -> std::io::Result<()> {
 
         let mut app = App::new()
             .app_data(web::Data::new(state.clone()))
+            .wrap(middleware::from_fn(auth_guard))
             .wrap(middleware::Logger::default())
             .wrap(cors)
             .service(web::resource("/health").route(web::get().to(health)))
@@ -2400,6 +3734,12 @@ async fn main() -> std::io::Result<()> {
             .service(
                 web::scope("/api")
                     .service(web::resource("/name").route(web::get().to(api_name)))
+                    .service(
+                        web::resource("/auth/session")
+                            .route(web::get().to(api_auth_session))
+                            .route(web::post().to(api_auth_login))
+                            .route(web::delete().to(api_auth_logout)),
+                    )
                     .service(web::resource("/status").route(web::get().to(api_status)))
                     .service(web::resource("/config").route(web::get().to(api_config_get)))
                     .service(web::resource("/config").route(web::post().to(api_config_set)))
@@ -2409,6 +3749,40 @@ async fn main() -> std::io::Result<()> {
                     .service(web::resource("/archetype/apply").route(web::post().to(api_archetype_apply)))
                     .service(web::resource("/command").route(web::post().to(api_command)))
                     .service(web::resource("/speak").route(web::post().to(api_speak)))
+                    .service(web::resource("/speak/stream").route(web::post().to(api_speak_stream)))
+                    .service(web::resource("/chat/ws").route(web::get().to(api_chat_ws)))
+                    .service(
+                        web::resource("/sessions")
+                            .route(web::get().to(api_sessions_list))
+                            .route(web::post().to(api_sessions_create)),
+                    )
+                    // Before `/sessions/{id}`, which would otherwise claim the path.
+                    .service(
+                        web::resource("/sessions/import")
+                            .app_data(web::JsonConfig::default().limit(backup::IMPORT_LIMIT_BYTES))
+                            .route(web::post().to(api_sessions_import)),
+                    )
+                    .service(
+                        web::resource("/sessions/{id}")
+                            .route(web::get().to(api_sessions_get))
+                            .route(web::patch().to(api_sessions_rename))
+                            .route(web::delete().to(api_sessions_delete)),
+                    )
+                    .service(web::resource("/sessions/{id}/messages").route(web::get().to(api_sessions_messages)))
+                    .service(web::resource("/sessions/{id}/export").route(web::get().to(api_sessions_export)))
+                    .service(
+                        web::resource("/backup")
+                            .route(web::get().to(api_backup_list))
+                            .route(web::post().to(api_backup_create)),
+                    )
+                    .service(web::resource("/backup/restore").route(web::post().to(api_backup_restore)))
+                    .service(web::resource("/backup/verify").route(web::post().to(api_backup_verify)))
+                    .service(web::resource("/backup/export").route(web::get().to(api_backup_export)))
+                    .service(
+                        web::resource("/backup/import")
+                            .app_data(web::JsonConfig::default().limit(backup::IMPORT_LIMIT_BYTES))
+                            .route(web::post().to(api_backup_import)),
+                    )
                     // Route ordering matters: Actix resolves the most specific match first, but
                     // anything not matched within this `/api` scope falls through to
                     // `default_service` (see `api_not_found()` below). Keep `/api/memory/*`
@@ -2430,10 +3804,27 @@ async fn main() -> std::io::Result<()> {
                             .service(web::resource("/list").route(web::get().to(api_ecosystem_list)))
                             .service(web::resource("/{id}").route(web::get().to(api_ecosystem_get)))
                             .service(web::resource("/{id}/build").route(web::post().to(api_ecosystem_build)))
+                            .service(web::resource("/{id}/update").route(web::post().to(api_ecosystem_update)))
                             .service(web::resource("/{id}/start").route(web::post().to(api_ecosystem_start)))
                             .service(web::resource("/{id}/stop").route(web::post().to(api_ecosystem_stop)))
+                            .service(web::resource("/{id}/health").route(web::get().to(api_ecosystem_health)))
                             .service(web::resource("/{id}").route(web::delete().to(api_ecosystem_remove))),
                     )
+                    .service(
+                        web::scope("/skills")
+                            .service(web::resource("/list").route(web::get().to(api_skills_list)))
+                            .service(web::resource("/installed").route(web::get().to(api_skills_installed)))
+                            .service(web::resource("/install").route(web::post().to(api_skills_install)))
+                            .service(web::resource("/uninstall").route(web::post().to(api_skills_uninstall)))
+                            .service(web::resource("/upgrade").route(web::post().to(api_skills_upgrade)))
+                            .service(web::resource("/registry").route(web::get().to(api_skills_registry_search)))
+                            .service(web::resource("/registry/index.json").route(web::get().to(api_skills_registry_index)))
+                            .service(web::resource("/registry/publish").route(web::post().to(api_skills_registry_publish)))
+                            .service(
+                                web::resource("/registry/bundles/{file}")
+                                    .route(web::get().to(api_skills_registry_bundle)),
+                            ),
+                    )
                     .service(
                         web::scope("/system")
                             .service(web::resource("/status").route(web::get().to(api_system_status)))
@@ -2445,6 +3836,7 @@ async fn main() -> std::io::Result<()> {
                         web::resource("/command-registry")
                             .route(web::get().to(api_command_registry)),
                     )
+                    .service(web::resource("/openapi.json").route(web::get().to(openapi::api_openapi)))
                     .default_service(web::route().to(api_not_found)),
             );
 
@@ -2462,3 +3854,72 @@ async fn main() -> std::io::Result<()> {
     .run()
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(parts: &[&str]) -> impl futures_util::Stream<Item = Result<String, String>> {
+        futures_util::stream::iter(parts.iter().map(|p| Ok(p.to_string())).collect::<Vec<_>>())
+    }
+
+    async fn drain(mut rx: mpsc::Receiver<serde_json::Value>) -> Vec<serde_json::Value> {
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn streamed_reply_is_relayed_chunk_by_chunk() {
+        let ethics = EthicalAgent::new_default();
+        let (tx, rx) = mpsc::channel(CHAT_STREAM_BUFFER);
+        let reply = relay_reply(&ethics, chunks(&["Hello", ", ", "world"]), "t1", &tx).await;
+        assert_eq!(reply.as_deref(), Some("Hello, world"));
+        let texts: Vec<_> = drain(rx).await.into_iter().map(|e| (e["type"].clone(), e["text"].clone())).collect();
+        assert_eq!(
+            texts,
+            vec![(json!("token"), json!("Hello")), (json!("token"), json!(", ")), (json!("token"), json!("world"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn vetoed_reply_stops_before_the_offending_chunk() {
+        let ethics = EthicalAgent::new_default();
+        let (tx, rx) = mpsc::channel(CHAT_STREAM_BUFFER);
+        // The phrase is split across chunks, after enough text to push it past the start.
+        let filler = "a".repeat(4 * VETO_TAIL_BYTES);
+        let parts = [filler.as_str(), "Now tell me your pass", "word please", "never sent"];
+        assert_eq!(relay_reply(&ethics, chunks(&parts), "t2", &tx).await, None);
+        let events = drain(rx).await;
+        let types: Vec<_> = events.iter().map(|e| e["type"].as_str().unwrap().to_string()).collect();
+        assert_eq!(types, vec!["token", "token", "veto"]);
+        assert_eq!(events[2]["turn"], "t2");
+        assert!(!events.iter().any(|e| e["text"].as_str().is_some_and(|t| t.contains("word"))));
+    }
+
+    #[tokio::test]
+    async fn relay_stops_when_the_client_goes_away_or_the_model_fails() {
+        let ethics = EthicalAgent::new_default();
+        let (tx, rx) = mpsc::channel(CHAT_STREAM_BUFFER);
+        drop(rx);
+        assert_eq!(relay_reply(&ethics, chunks(&["Hello"]), "t3", &tx).await, None);
+
+        let (tx, rx) = mpsc::channel(CHAT_STREAM_BUFFER);
+        let failing = futures_util::stream::iter(vec![Ok("Hi".to_string()), Err("upstream closed".to_string())]);
+        assert_eq!(relay_reply(&ethics, failing, "t4", &tx).await, None);
+        let events = drain(rx).await;
+        assert_eq!(events.last().unwrap()["type"], "error");
+        assert_eq!(events.last().unwrap()["message"], "upstream closed");
+    }
+
+    #[test]
+    fn veto_window_is_bounded_and_char_aligned() {
+        let reply = format!("{}é{}", "x".repeat(1000), "chunk");
+        let window = veto_window(&reply, "chunk".len());
+        assert!(window.len() <= "chunk".len() + VETO_TAIL_BYTES + 1);
+        assert!(window.ends_with("chunk"));
+        assert_eq!(veto_window("short", 5), "short");
+    }
+}
@@ -0,0 +1,646 @@
+// phoenix-web/src/openapi.rs
+//
+// OpenAPI document for the `/api` routes and the typed TypeScript client generated from it.
+//
+// Design goals:
+// - The document is derived from the handlers' `#[utoipa::path]` annotations and the Rust
+//   request/response types, so it cannot describe a payload the server does not produce
+// - Served at `/api/openapi.json` (public, like `/api/status`)
+// - `pagi-sola-web --write-api-client` renders `frontend/api.ts` from the same document;
+//   `--check-api-client` fails when the committed client is stale, so a changed Rust type shows
+//   up as a TypeScript error in the UI code that uses it
+
+use actix_web::{HttpResponse, Responder};
+use serde_json::{Map, Value};
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::auth;
+
+/// Where `--write-api-client` and `--check-api-client` put the client by default.
+pub const DEFAULT_CLIENT_PATH: &str = "frontend/api.ts";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Phoenix web API"),
+    paths(
+        api_openapi,
+        crate::api_name,
+        crate::api_status,
+        crate::api_auth_login,
+        crate::api_auth_session,
+        crate::api_auth_logout,
+        crate::api_config_get,
+        crate::api_config_set,
+        crate::api_relational_state_get,
+        crate::api_relational_state_update,
+        crate::api_archetype_match,
+        crate::api_archetype_apply,
+        crate::api_command,
+        crate::api_speak,
+        crate::api_speak_stream,
+        crate::api_chat_ws,
+        crate::api_sessions_list,
+        crate::api_sessions_create,
+        crate::api_sessions_get,
+        crate::api_sessions_messages,
+        crate::api_sessions_rename,
+        crate::api_sessions_delete,
+        crate::api_sessions_export,
+        crate::api_sessions_import,
+        crate::api_backup_list,
+        crate::api_backup_create,
+        crate::api_backup_restore,
+        crate::api_backup_verify,
+        crate::api_backup_export,
+        crate::api_backup_import,
+        crate::api_memory_store,
+        crate::api_memory_get,
+        crate::api_memory_search,
+        crate::api_memory_delete,
+        crate::api_memory_vector_store,
+        crate::api_memory_vector_search,
+        crate::api_memory_vector_all,
+        crate::api_google_auth_start,
+        crate::api_google_oauth2_callback,
+        crate::api_evolution_status,
+        crate::api_ecosystem_import,
+        crate::api_ecosystem_list,
+        crate::api_ecosystem_get,
+        crate::api_ecosystem_build,
+        crate::api_ecosystem_update,
+        crate::api_ecosystem_start,
+        crate::api_ecosystem_stop,
+        crate::api_ecosystem_health,
+        crate::api_ecosystem_remove,
+        crate::api_skills_list,
+        crate::api_skills_installed,
+        crate::api_skills_install,
+        crate::api_skills_uninstall,
+        crate::api_skills_upgrade,
+        crate::api_skills_registry_search,
+        crate::api_skills_registry_index,
+        crate::api_skills_registry_bundle,
+        crate::api_skills_registry_publish,
+        crate::api_system_status,
+        crate::api_system_exec,
+        crate::api_system_read_file,
+        crate::api_system_write_file,
+        crate::api_command_registry,
+    ),
+    modifiers(&SecuritySchemes),
+    security(("bearer" = []), ("session" = [])),
+    tags(
+        (name = "meta", description = "Identity, status and API descriptions"),
+        (name = "auth", description = "Browser sessions"),
+        (name = "chat", description = "Commands and chat turns"),
+        (name = "sessions", description = "Persisted conversations"),
+        (name = "memory", description = "Key/value and vector memory"),
+        (name = "config", description = "Server settings"),
+        (name = "relationship", description = "Relational state and archetypes"),
+        (name = "google", description = "Google account integration"),
+        (name = "ecosystem", description = "Imported repositories and their services"),
+        (name = "skills", description = "Skill library, bundles and registry"),
+        (name = "system", description = "Host access"),
+        (name = "backup", description = "Database snapshots and the portable brain export"),
+    )
+)]
+struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        components.add_security_scheme(
+            "session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                auth::SESSION_COOKIE,
+                "Browser session from `POST /api/auth/session`; unsafe methods also need `X-CSRF-Token`.",
+            ))),
+        );
+    }
+}
+
+/// The OpenAPI document as JSON.
+pub fn spec() -> Value {
+    serde_json::to_value(ApiDoc::openapi()).unwrap_or_default()
+}
+
+/// GET /api/openapi.json — this document.
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "meta",
+    responses((status = 200, description = "OpenAPI 3.1 document for `/api/*`", body = Object)),
+    security(())
+)]
+pub async fn api_openapi() -> impl Responder {
+    HttpResponse::Ok().json(spec())
+}
+
+/// Handles `--openapi`, `--write-api-client [path]` and `--check-api-client [path]`; `None` when
+/// `args` (without the program name) is none of those and the server should start.
+pub fn run_cli(args: &[String]) -> Option<io::Result<()>> {
+    let path = Path::new(
+        args.get(1)
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_CLIENT_PATH),
+    );
+    match args.first().map(String::as_str)? {
+        "--openapi" => Some(
+            serde_json::to_string_pretty(&spec())
+                .map_err(io::Error::other)
+                .map(|json| println!("{json}")),
+        ),
+        "--write-api-client" => Some(std::fs::write(path, typescript_client(&spec())).map(|()| {
+            println!("Wrote {}", path.display());
+        })),
+        "--check-api-client" => Some(check_client(path)),
+        _ => None,
+    }
+}
+
+fn check_client(path: &Path) -> io::Result<()> {
+    let current = std::fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    if current.replace("\r\n", "\n") != typescript_client(&spec()) {
+        return Err(io::Error::other(format!(
+            "{} is out of date; run `pagi-sola-web --write-api-client`",
+            path.display()
+        )));
+    }
+    println!("{} is up to date", path.display());
+    Ok(())
+}
+
+const CLIENT_HEADER: &str = "\
+// Generated by `pagi-sola-web --write-api-client` from the phoenix-web OpenAPI document
+// (`GET /api/openapi.json`). Do not edit: change the Rust types and regenerate instead.
+
+import { apiFetch, apiUrl } from './auth';
+";
+
+const CLIENT_RUNTIME: &str = "
+/** A non-2xx response; `body` is the parsed JSON error (or the raw text). */
+export class ApiError extends Error {
+  readonly status: number;
+  readonly body: unknown;
+
+  constructor(status: number, body: unknown) {
+    super(errorMessage(status, body));
+    this.name = 'ApiError';
+    this.status = status;
+    this.body = body;
+  }
+}
+
+function errorMessage(status: number, body: unknown): string {
+  if (body && typeof body === 'object') {
+    const b = body as { message?: unknown; error?: unknown };
+    if (typeof b.message === 'string') return b.message;
+    if (typeof b.error === 'string') return b.error;
+  }
+  return typeof body === 'string' && body ? `${status}: ${body}` : `HTTP ${status}`;
+}
+
+async function request(
+  method: string,
+  path: string,
+  query?: object,
+  body?: unknown,
+  init: RequestInit = {},
+): Promise<Response> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(query ?? {})) {
+    if (value !== undefined && value !== null) params.set(key, String(value));
+  }
+  const qs = params.toString();
+  const headers = new Headers(init.headers);
+  if (body !== undefined) headers.set('Content-Type', 'application/json');
+  const res = await apiFetch(apiUrl(qs ? `${path}?${qs}` : path), {
+    ...init,
+    method,
+    headers,
+    body: body === undefined ? undefined : JSON.stringify(body),
+  });
+  if (!res.ok) {
+    const text = await res.text().catch(() => '');
+    let parsed: unknown = text;
+    try {
+      parsed = JSON.parse(text);
+    } catch {
+      // Not JSON; keep the text.
+    }
+    throw new ApiError(res.status, parsed);
+  }
+  return res;
+}
+
+async function requestJson<T>(
+  method: string,
+  path: string,
+  query?: object,
+  body?: unknown,
+  init?: RequestInit,
+): Promise<T> {
+  const res = await request(method, path, query, body, init);
+  return (await res.json()) as T;
+}
+";
+
+/// Renders the TypeScript client for `spec`: one type per component schema and one function
+/// per operation on `phoenixApi`. Operations without a 2xx response (the WebSocket) are skipped;
+/// non-JSON responses resolve to the raw `Response`.
+pub fn typescript_client(spec: &Value) -> String {
+    let mut out = String::from(CLIENT_HEADER);
+
+    let empty = Map::new();
+    let schemas = spec
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    for name in sorted_keys(schemas) {
+        let schema = &schemas[name];
+        out.push('\n');
+        push_doc(&mut out, schema, "");
+        match object_body(schema, "") {
+            Some(body) => {
+                let _ = writeln!(out, "export interface {name} {body}");
+            }
+            None => {
+                let _ = writeln!(out, "export type {name} = {};", ts_type(schema, ""));
+            }
+        }
+    }
+
+    out.push_str(CLIENT_RUNTIME);
+    out.push_str("\nexport const phoenixApi = {\n");
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    for path in sorted_keys(paths) {
+        let Some(item) = paths[path].as_object() else {
+            continue;
+        };
+        for method in ["get", "post", "put", "patch", "delete"] {
+            if let Some(op) = item.get(method) {
+                push_operation(&mut out, method, path, op);
+            }
+        }
+    }
+    out.push_str("};\n");
+    out
+}
+
+fn push_operation(out: &mut String, method: &str, path: &str, op: &Value) {
+    let Some(name) = op.get("operationId").and_then(Value::as_str) else {
+        return;
+    };
+    let Some((_, response)) = op
+        .get("responses")
+        .and_then(Value::as_object)
+        .and_then(|r| r.iter().find(|(status, _)| status.starts_with('2')))
+    else {
+        return;
+    };
+
+    let params = op
+        .get("parameters")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let mut args = Vec::new();
+    let mut url = path.to_string();
+    for p in params.iter().filter(|p| p["in"] == "path") {
+        let name = p["name"].as_str().unwrap_or_default();
+        args.push(format!("{}: string", camel_case(name)));
+        url = url.replace(
+            &format!("{{{name}}}"),
+            &format!("${{encodeURIComponent({})}}", camel_case(name)),
+        );
+    }
+
+    let query: Vec<&Value> = params.iter().filter(|p| p["in"] == "query").collect();
+    let query_arg = if query.is_empty() {
+        "undefined"
+    } else {
+        let required = query.iter().any(|p| p["required"] == true);
+        let fields: Vec<String> = query
+            .iter()
+            .map(|p| {
+                let optional = if p["required"] == true { "" } else { "?" };
+                format!(
+                    "{}{optional}: {}",
+                    property_name(p["name"].as_str().unwrap_or_default()),
+                    ts_type(&p["schema"], "")
+                )
+            })
+            .collect();
+        args.push(format!(
+            "query{}: {{ {} }}",
+            if required { "" } else { "?" },
+            fields.join("; ")
+        ));
+        "query"
+    };
+
+    let body_arg = match op.get("requestBody") {
+        Some(body) => {
+            let schema = &body["content"]["application/json"]["schema"];
+            let optional = if body["required"] == true { "" } else { "?" };
+            args.push(format!("body{optional}: {}", ts_type(schema, "")));
+            "body"
+        }
+        None => "undefined",
+    };
+    args.push("init?: RequestInit".to_string());
+
+    let content = response.get("content").and_then(Value::as_object);
+    let json_only = content.is_some_and(|c| c.len() == 1 && c.contains_key("application/json"));
+    let (result, call) = if json_only {
+        let ty = ts_type(&response["content"]["application/json"]["schema"], "  ");
+        (ty.clone(), format!("requestJson<{ty}>"))
+    } else {
+        ("Response".to_string(), "request".to_string())
+    };
+
+    let summary = op
+        .get("summary")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let _ = writeln!(
+        out,
+        "  /** {} {path}{} */",
+        method.to_uppercase(),
+        doc_suffix(summary, path)
+    );
+    let url = if url.contains("${") {
+        format!("`{url}`")
+    } else {
+        format!("'{url}'")
+    };
+    let _ = writeln!(
+        out,
+        "  {}: ({}): Promise<{result}> =>\n    {call}('{}', {url}, {query_arg}, {body_arg}, init),",
+        camel_case(name.strip_prefix("api_").unwrap_or(name)),
+        args.join(", "),
+        method.to_uppercase(),
+    );
+}
+
+/// The handler's summary without the `METHOD /path — ` prefix some handler docs start with.
+fn doc_suffix(summary: &str, path: &str) -> String {
+    let summary = summary
+        .split_once(" — ")
+        .filter(|(head, _)| head.ends_with(path))
+        .map_or(summary, |(_, tail)| tail);
+    if summary.is_empty() {
+        String::new()
+    } else {
+        format!(" — {}", summary.replace("*/", "*\\/"))
+    }
+}
+
+/// TypeScript type for a JSON schema; `indent` is the indentation of the line the type starts on.
+fn ts_type(schema: &Value, indent: &str) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or("unknown")
+            .to_string();
+    }
+    for (key, sep) in [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")] {
+        if let Some(parts) = schema.get(key).and_then(Value::as_array) {
+            let mut types: Vec<String> = Vec::new();
+            for part in parts {
+                let ty = ts_type(part, indent);
+                if !types.contains(&ty) {
+                    types.push(ty);
+                }
+            }
+            // `Option<T>` comes out as `null | T`; read it as `T | null`.
+            types.sort_by_key(|t| t == "null");
+            return types.join(sep);
+        }
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(ts_literal)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    match schema.get("type") {
+        Some(Value::Array(types)) => types
+            .iter()
+            .map(|t| {
+                let mut single = schema.clone();
+                single["type"] = t.clone();
+                ts_type(&single, indent)
+            })
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Some(Value::String(t)) => match t.as_str() {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => {
+                let item = ts_type(&schema["items"], indent);
+                if item.contains(" | ") || item.contains(" & ") {
+                    format!("({item})[]")
+                } else {
+                    format!("{item}[]")
+                }
+            }
+            "object" => object_body(schema, indent).unwrap_or_else(|| {
+                match schema.get("additionalProperties") {
+                    Some(Value::Object(values)) if !values.is_empty() => {
+                        format!(
+                            "Record<string, {}>",
+                            ts_type(&Value::Object(values.clone()), indent)
+                        )
+                    }
+                    _ => "Record<string, unknown>".to_string(),
+                }
+            }),
+            _ => "unknown".to_string(),
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+/// `{ ... }` for an object schema with declared properties.
+fn object_body(schema: &Value, indent: &str) -> Option<String> {
+    let properties = schema.get("properties").and_then(Value::as_object)?;
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let inner = format!("{indent}  ");
+    let mut out = String::from("{\n");
+    for name in sorted_keys(properties) {
+        let property = &properties[name];
+        push_doc(&mut out, property, &inner);
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        let _ = writeln!(
+            out,
+            "{inner}{}{optional}: {};",
+            property_name(name),
+            ts_type(property, &inner)
+        );
+    }
+    out.push_str(indent);
+    out.push('}');
+    Some(out)
+}
+
+fn push_doc(out: &mut String, schema: &Value, indent: &str) {
+    let Some(description) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    let lines: Vec<&str> = description.trim().lines().map(str::trim_end).collect();
+    let escape = |line: &str| line.replace("*/", "*\\/");
+    match lines.as_slice() {
+        [] => {}
+        [line] => {
+            let _ = writeln!(out, "{indent}/** {} */", escape(line));
+        }
+        lines => {
+            let _ = writeln!(out, "{indent}/**");
+            for line in lines {
+                let _ = writeln!(
+                    out,
+                    "{indent} *{}{}",
+                    if line.is_empty() { "" } else { " " },
+                    escape(line)
+                );
+            }
+            let _ = writeln!(out, "{indent} */");
+        }
+    }
+}
+
+fn ts_literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        other => other.to_string(),
+    }
+}
+
+fn property_name(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        ts_literal(&Value::String(name.to_string()))
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in name
+        .split(['_', '-', '.'])
+        .filter(|p| !p.is_empty())
+        .enumerate()
+    {
+        let mut chars = part.chars();
+        if i == 0 {
+            out.push_str(part);
+        } else if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    out
+}
+
+/// Keys in a stable order, independent of serde_json's `preserve_order` feature.
+fn sorted_keys(map: &Map<String, Value>) -> Vec<&String> {
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Every `/api` route registered in main.rs, with scope prefixes applied.
+    fn registered_api_routes() -> BTreeSet<String> {
+        let src = include_str!("main.rs");
+        let start = src.find("web::scope(\"/api\")").expect("main.rs registers an /api scope");
+        let text = &src[start..];
+        let literal = |at: usize| {
+            let rest = &text[at..];
+            let open = rest.find('"').unwrap() + 1;
+            let len = rest[open..].find('"').unwrap();
+            rest[open..open + len].to_string()
+        };
+
+        let mut routes = BTreeSet::new();
+        // (prefix, paren depth the scope was opened at); a scope ends when its call closes.
+        let mut scopes = vec![("/api".to_string(), 0i32)];
+        let mut depth = 0i32;
+        for (i, c) in text.char_indices().skip(1) {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth < 0 {
+                        break;
+                    }
+                    scopes.retain(|(_, opened)| *opened <= depth);
+                }
+                _ => {}
+            }
+            let prefix: String = scopes.iter().map(|(p, _)| p.as_str()).collect();
+            if text[i..].starts_with("web::scope(\"") {
+                scopes.push((literal(i), depth));
+            } else if text[i..].starts_with("web::resource(\"") {
+                routes.insert(format!("{prefix}{}", literal(i)));
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn committed_client_matches_the_spec() {
+        assert!(
+            typescript_client(&spec()) == include_str!("../../frontend/api.ts").replace("\r\n", "\n"),
+            "frontend/api.ts is out of date; run `pagi-sola-web --write-api-client`"
+        );
+    }
+
+    #[test]
+    fn every_api_route_is_documented() {
+        let spec = spec();
+        let documented: BTreeSet<&String> = spec["paths"].as_object().unwrap().keys().collect();
+        let routes = registered_api_routes();
+        assert!(routes.contains("/api/ecosystem/{id}/build"), "scoped routes are found: {routes:?}");
+        let missing: Vec<&String> = routes.iter().filter(|r| !documented.contains(r)).collect();
+        assert!(missing.is_empty(), "routes missing from the OpenAPI document: {missing:?}");
+    }
+}
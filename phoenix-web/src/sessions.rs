@@ -0,0 +1,579 @@
+// phoenix-web/src/sessions.rs
+//
+// Conversation sessions (persisted transcripts with timestamps and model metadata).
+//
+// Design goals:
+// - One JSON file per session under `PHOENIX_SESSIONS_DIR` (default `data/sessions`)
+// - Prompt context = rolling summary of older turns + the most recent turns that fit the window
+// - When the transcript overflows the window, the oldest turns are folded into the summary
+//   (down to half the window, so summarization does not run on every turn)
+// - Each session belongs to the principal that created it; other callers see it as missing
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+const DEFAULT_SESSIONS_DIR: &str = "data/sessions";
+const DEFAULT_CONTEXT_CHARS: usize = 8000;
+const DEFAULT_TITLE: &str = "New conversation";
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConversationMessage {
+    pub id: String,
+    /// `user` or `assistant`.
+    pub role: String,
+    pub content: String,
+    /// RFC 3339 timestamp.
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Name of the principal the session belongs to (`None` for sessions recorded before
+    /// sessions had owners). Replaced by the caller on import; kept when restoring a backup.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Summary of `messages[..summarized_through]`.
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub summarized_through: usize,
+    #[serde(default)]
+    pub messages: Vec<ConversationMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConversationSummary {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub message_count: usize,
+}
+
+/// Who is asking for a session.
+#[derive(Debug, Clone)]
+pub struct SessionOwner {
+    pub name: String,
+    /// May also open sessions without an owner (recorded before sessions had owners).
+    pub admin: bool,
+}
+
+impl SessionOwner {
+    pub fn new(name: impl Into<String>, admin: bool) -> Self {
+        Self { name: name.into(), admin }
+    }
+
+    pub fn can_access(&self, conversation: &Conversation) -> bool {
+        match conversation.owner.as_deref() {
+            Some(owner) => owner == self.name,
+            None => self.admin,
+        }
+    }
+}
+
+/// Prompt context for a session.
+#[derive(Debug, Clone)]
+pub struct SessionContext {
+    pub text: String,
+    /// Messages that no longer fit the window and should be folded into the summary.
+    pub overflow: Range<usize>,
+}
+
+pub struct ConversationStore {
+    dir: PathBuf,
+    context_chars: usize,
+    /// Serializes read-modify-write cycles on session files.
+    write_lock: Mutex<()>,
+}
+
+impl ConversationStore {
+    pub fn open(dir: impl Into<PathBuf>, context_chars: usize) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            context_chars: context_chars.max(1000),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// `PHOENIX_SESSIONS_DIR` (default `data/sessions`) and `PHOENIX_SESSION_CONTEXT_CHARS`
+    /// (default 8000).
+    pub fn from_env() -> io::Result<Self> {
+        let dir = std::env::var("PHOENIX_SESSIONS_DIR")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_SESSIONS_DIR.to_string());
+        let context_chars = std::env::var("PHOENIX_SESSION_CONTEXT_CHARS")
+            .ok()
+            .and_then(|s| s.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_CONTEXT_CHARS);
+        Self::open(dir, context_chars)
+    }
+
+    pub async fn create(&self, owner: &SessionOwner, title: Option<&str>) -> io::Result<Conversation> {
+        let now = now_rfc3339();
+        let conversation = Conversation {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: clean_title(title).unwrap_or_else(|| DEFAULT_TITLE.to_string()),
+            created_at: now.clone(),
+            updated_at: now,
+            owner: Some(owner.name.clone()),
+            summary: None,
+            summarized_through: 0,
+            messages: Vec::new(),
+        };
+        let _guard = self.write_lock.lock().await;
+        self.save(&conversation)?;
+        Ok(conversation)
+    }
+
+    /// The owner's sessions, most recently updated first.
+    pub async fn list(&self, owner: &SessionOwner) -> io::Result<Vec<ConversationSummary>> {
+        let mut out = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(conversation) = read_conversation(&path) else {
+                continue;
+            };
+            if !owner.can_access(&conversation) {
+                continue;
+            }
+            out.push(ConversationSummary {
+                message_count: conversation.messages.len(),
+                id: conversation.id,
+                title: conversation.title,
+                created_at: conversation.created_at,
+                updated_at: conversation.updated_at,
+            });
+        }
+        out.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(out)
+    }
+
+    /// Every session regardless of owner, for brain backups.
+    pub async fn all(&self) -> io::Result<Vec<Conversation>> {
+        let mut out = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            if let Ok(conversation) = read_conversation(&path) {
+                out.push(conversation);
+            }
+        }
+        out.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(out)
+    }
+
+    /// The session, or `None` if it does not exist or belongs to someone else.
+    pub async fn get(&self, id: &str, owner: &SessionOwner) -> io::Result<Option<Conversation>> {
+        let Some(path) = self.path_for(id) else {
+            return Ok(None);
+        };
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let conversation = read_conversation(&path)?;
+        Ok(owner.can_access(&conversation).then_some(conversation))
+    }
+
+    pub async fn rename(&self, id: &str, owner: &SessionOwner, title: &str) -> io::Result<Option<Conversation>> {
+        let Some(title) = clean_title(Some(title)) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "title must not be empty"));
+        };
+        self.update(id, owner, |c| c.title = title).await
+    }
+
+    pub async fn delete(&self, id: &str, owner: &SessionOwner) -> io::Result<bool> {
+        let Some(path) = self.path_for(id) else {
+            return Ok(false);
+        };
+        let _guard = self.write_lock.lock().await;
+        match read_conversation(&path) {
+            Ok(conversation) if owner.can_access(&conversation) => {}
+            Ok(_) => return Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        }
+        match std::fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stores an exported conversation (see `GET /api/sessions/{id}/export`) as one of the
+    /// owner's sessions. It keeps its id unless that id is invalid or already taken, in which
+    /// case it gets a fresh one.
+    pub async fn import(&self, owner: &SessionOwner, mut conversation: Conversation) -> io::Result<Conversation> {
+        conversation.owner = Some(owner.name.clone());
+        self.restore(conversation).await
+    }
+
+    /// Like `import`, but keeps the recorded owner (brain backups).
+    pub async fn restore(&self, mut conversation: Conversation) -> io::Result<Conversation> {
+        let _guard = self.write_lock.lock().await;
+        if self.path_for(&conversation.id).is_none_or(|path| path.exists()) {
+            conversation.id = uuid::Uuid::new_v4().to_string();
+        }
+        conversation.title = clean_title(Some(&conversation.title)).unwrap_or_else(|| DEFAULT_TITLE.to_string());
+        conversation.summarized_through = conversation.summarized_through.min(conversation.messages.len());
+        self.save(&conversation)?;
+        Ok(conversation)
+    }
+
+    /// Appends a user message and the assistant's reply. Untitled sessions are titled after
+    /// their first message.
+    pub async fn append_turn(
+        &self,
+        id: &str,
+        owner: &SessionOwner,
+        user: &str,
+        assistant: &str,
+        model: Option<&str>,
+    ) -> io::Result<Option<Conversation>> {
+        self.update(id, owner, |c| {
+            let now = now_rfc3339();
+            if c.messages.is_empty()
+                && c.title == DEFAULT_TITLE
+                && let Some(title) = clean_title(Some(user))
+            {
+                c.title = title;
+            }
+            c.messages.push(ConversationMessage {
+                id: uuid::Uuid::new_v4().to_string(),
+                role: "user".to_string(),
+                content: user.to_string(),
+                timestamp: now.clone(),
+                model: None,
+            });
+            c.messages.push(ConversationMessage {
+                id: uuid::Uuid::new_v4().to_string(),
+                role: "assistant".to_string(),
+                content: assistant.to_string(),
+                timestamp: now,
+                model: model.map(str::to_string),
+            });
+        })
+        .await
+    }
+
+    /// Replaces the rolling summary, which now covers `messages[..through]`.
+    pub async fn set_summary(
+        &self,
+        id: &str,
+        owner: &SessionOwner,
+        summary: String,
+        through: usize,
+    ) -> io::Result<Option<Conversation>> {
+        self.update(id, owner, |c| {
+            c.summary = Some(summary);
+            c.summarized_through = through.min(c.messages.len());
+        })
+        .await
+    }
+
+    /// Builds the prompt context for `conversation` within this store's window.
+    pub fn context(&self, conversation: &Conversation, assistant_name: &str) -> SessionContext {
+        context_window(conversation, assistant_name, self.context_chars)
+    }
+
+    async fn update(
+        &self,
+        id: &str,
+        owner: &SessionOwner,
+        f: impl FnOnce(&mut Conversation),
+    ) -> io::Result<Option<Conversation>> {
+        let Some(path) = self.path_for(id) else {
+            return Ok(None);
+        };
+        let _guard = self.write_lock.lock().await;
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let mut conversation = read_conversation(&path)?;
+        if !owner.can_access(&conversation) {
+            return Ok(None);
+        }
+        f(&mut conversation);
+        conversation.updated_at = now_rfc3339();
+        self.save(&conversation)?;
+        Ok(Some(conversation))
+    }
+
+    fn save(&self, conversation: &Conversation) -> io::Result<()> {
+        let path = self
+            .path_for(&conversation.id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid session id"))?;
+        let json = serde_json::to_vec_pretty(conversation).map_err(io::Error::other)?;
+        // Write then rename so a crash never leaves a truncated transcript behind.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// Session ids are UUIDs; anything else is rejected so ids can never escape the directory.
+    fn path_for(&self, id: &str) -> Option<PathBuf> {
+        let valid = !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+        valid.then(|| self.dir.join(format!("{id}.json")))
+    }
+}
+
+/// Rolling summary plus the newest unsummarized messages that fit in `max_chars`.
+///
+/// If older unsummarized messages do not fit, `overflow` covers the ones that should be folded
+/// into the summary so the remaining transcript fits in half the window.
+pub fn context_window(conversation: &Conversation, assistant_name: &str, max_chars: usize) -> SessionContext {
+    let start = conversation.summarized_through.min(conversation.messages.len());
+    let lines: Vec<String> = conversation.messages[start..]
+        .iter()
+        .map(|m| format_line(m, assistant_name))
+        .collect();
+
+    let summary_len = conversation.summary.as_deref().map(str::len).unwrap_or(0);
+    let budget = max_chars.saturating_sub(summary_len);
+    let keep_from = |budget: usize| {
+        let mut used = 0usize;
+        let mut first = lines.len();
+        for (i, line) in lines.iter().enumerate().rev() {
+            if used + line.len() + 1 > budget {
+                break;
+            }
+            used += line.len() + 1;
+            first = i;
+        }
+        first
+    };
+
+    // The latest message is always kept, even when it alone exceeds the window.
+    let last = lines.len().saturating_sub(1);
+    let first_kept = keep_from(budget).min(last);
+    let overflow = if first_kept > 0 {
+        // Fold enough to get back under half the window; keep at least the latest turn.
+        let target = keep_from(budget / 2)
+            .min(lines.len().saturating_sub(2))
+            .max(first_kept)
+            .min(last);
+        start..start + target
+    } else {
+        start..start
+    };
+
+    let mut text = String::new();
+    if let Some(summary) = conversation.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        text.push_str("Earlier in this conversation (summary):\n");
+        text.push_str(summary.trim());
+        text.push_str("\n\n");
+    }
+    if first_kept < lines.len() {
+        text.push_str("Recent conversation:\n");
+        for line in &lines[first_kept..] {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    SessionContext { text, overflow }
+}
+
+/// Prompt asking the LLM to fold `messages[range]` into the existing summary.
+pub fn summarization_prompt(conversation: &Conversation, range: Range<usize>, assistant_name: &str) -> String {
+    let mut prompt = String::from(
+        "Summarize the conversation below for your own future reference. Keep names, facts, \
+         preferences, promises and open questions; drop small talk. Write at most 200 words.\n\n",
+    );
+    if let Some(summary) = conversation.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        prompt.push_str("Summary so far:\n");
+        prompt.push_str(summary.trim());
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str("New messages:\n");
+    for m in conversation.messages.get(range).unwrap_or_default() {
+        prompt.push_str(&format_line(m, assistant_name));
+        prompt.push('\n');
+    }
+    prompt.push_str("\nUpdated summary:");
+    prompt
+}
+
+/// Markdown transcript for export.
+pub fn to_markdown(conversation: &Conversation, assistant_name: &str) -> String {
+    let mut md = format!(
+        "# {}\n\n_Created {} · updated {} · {} messages_\n\n",
+        conversation.title,
+        conversation.created_at,
+        conversation.updated_at,
+        conversation.messages.len()
+    );
+    if let Some(summary) = conversation.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        md.push_str(&format!("> **Summary of earlier turns:** {}\n\n", summary.trim()));
+    }
+    for m in &conversation.messages {
+        let speaker = if m.role == "user" { "User" } else { assistant_name };
+        match m.model.as_deref() {
+            Some(model) => md.push_str(&format!("### {} — {} ({})\n\n", speaker, m.timestamp, model)),
+            None => md.push_str(&format!("### {} — {}\n\n", speaker, m.timestamp)),
+        }
+        md.push_str(m.content.trim());
+        md.push_str("\n\n");
+    }
+    md
+}
+
+fn format_line(message: &ConversationMessage, assistant_name: &str) -> String {
+    let speaker = if message.role == "user" { "User" } else { assistant_name };
+    format!("{}: {}", speaker, message.content.trim())
+}
+
+fn clean_title(title: Option<&str>) -> Option<String> {
+    let title = title?.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    Some(match title.char_indices().nth(80) {
+        Some((cut, _)) => format!("{}…", &title[..cut]),
+        None => title,
+    })
+}
+
+fn read_conversation(path: &Path) -> io::Result<Conversation> {
+    let raw = std::fs::read(path)?;
+    serde_json::from_slice(&raw).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn now_rfc3339() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ConversationMessage {
+        ConversationMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            model: None,
+        }
+    }
+
+    fn conversation(contents: &[&str]) -> Conversation {
+        Conversation {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: DEFAULT_TITLE.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            owner: None,
+            summary: None,
+            summarized_through: 0,
+            messages: contents
+                .iter()
+                .enumerate()
+                .map(|(i, c)| message(if i % 2 == 0 { "user" } else { "assistant" }, c))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn context_window_keeps_recent_turns_and_folds_the_rest() {
+        // Each line is "User: xxxxxxxxx" / "Sola: xxxxxxxxx" (15 chars + newline).
+        let c = conversation(&["aaaaaaaaa", "bbbbbbbbb", "ccccccccc", "ddddddddd", "eeeeeeeee", "fffffffff"]);
+        let all = context_window(&c, "Sola", 1000);
+        assert_eq!(all.overflow, 0..0);
+        assert!(all.text.starts_with("Recent conversation:\nUser: aaaaaaaaa\n"));
+
+        // Room for three lines: the three oldest overflow, folding down to half the window.
+        let tight = context_window(&c, "Sola", 48);
+        assert!(!tight.text.contains("aaaaaaaaa") && tight.text.contains("fffffffff"));
+        assert_eq!(tight.overflow, 0..4);
+
+        let mut summarized = c.clone();
+        summarized.summary = Some("Earlier: hello.".to_string());
+        summarized.summarized_through = 2;
+        let ctx = context_window(&summarized, "Sola", 1000);
+        assert!(ctx.text.starts_with("Earlier in this conversation (summary):\nEarlier: hello.\n\nRecent conversation:\nUser: ccccccccc"));
+        assert_eq!(ctx.overflow, 2..2);
+    }
+
+    #[test]
+    fn context_window_never_folds_the_latest_message() {
+        let long = "x".repeat(500);
+        let c = conversation(&["hi", "hello", &long]);
+        let ctx = context_window(&c, "Sola", 100);
+        assert_eq!(ctx.overflow, 0..2);
+        assert!(ctx.text.contains(&long));
+
+        let single = conversation(&[&long]);
+        assert_eq!(context_window(&single, "Sola", 100).overflow, 0..0);
+        assert_eq!(context_window(&conversation(&[]), "Sola", 100).text, "");
+    }
+
+    #[test]
+    fn summarization_prompt_includes_previous_summary_and_range() {
+        let mut c = conversation(&["first", "second", "third"]);
+        let prompt = summarization_prompt(&c, 0..2, "Sola");
+        assert!(!prompt.contains("Summary so far"));
+        assert!(prompt.contains("New messages:\nUser: first\nSola: second\n"));
+        assert!(!prompt.contains("third"));
+        assert!(prompt.ends_with("Updated summary:"));
+
+        c.summary = Some("They met.".to_string());
+        let prompt = summarization_prompt(&c, 2..3, "Sola");
+        assert!(prompt.contains("Summary so far:\nThey met.\n\nNew messages:\nUser: third\n"));
+        assert!(summarization_prompt(&c, 5..9, "Sola").contains("New messages:\n\nUpdated summary:"));
+    }
+
+    #[tokio::test]
+    async fn sessions_are_only_visible_to_their_owner() {
+        let dir = std::env::temp_dir().join(format!("phoenix_sessions_{}", uuid::Uuid::new_v4()));
+        let store = ConversationStore::open(&dir, 8000).unwrap();
+        let alice = SessionOwner::new("alice", false);
+        let bob = SessionOwner::new("bob", false);
+        let admin = SessionOwner::new("local-admin", true);
+
+        let mine = store.create(&alice, Some("Alice's")).await.unwrap();
+        assert_eq!(store.list(&alice).await.unwrap().len(), 1);
+        assert!(store.list(&bob).await.unwrap().is_empty());
+        assert!(store.get(&mine.id, &bob).await.unwrap().is_none());
+        assert!(store.rename(&mine.id, &bob, "taken").await.unwrap().is_none());
+        assert!(store.append_turn(&mine.id, &bob, "hi", "hello", None).await.unwrap().is_none());
+        assert!(!store.delete(&mine.id, &bob).await.unwrap());
+        assert!(store.get(&mine.id, &admin).await.unwrap().is_none());
+
+        let mut stolen = store.get(&mine.id, &alice).await.unwrap().unwrap();
+        stolen.owner = Some("alice".to_string());
+        let imported = store.import(&bob, stolen).await.unwrap();
+        assert_eq!(imported.owner.as_deref(), Some("bob"));
+        assert_ne!(imported.id, mine.id);
+
+        // Sessions from before ownership was tracked are left to admins.
+        let mut legacy = conversation(&["old"]);
+        legacy.owner = None;
+        store.save(&legacy).unwrap();
+        assert!(store.get(&legacy.id, &alice).await.unwrap().is_none());
+        assert!(store.get(&legacy.id, &admin).await.unwrap().is_some());
+
+        let restored = store.restore(legacy.clone()).await.unwrap();
+        assert_eq!(restored.owner, None);
+        assert_eq!(store.all().await.unwrap().len(), 4);
+
+        assert!(store.delete(&mine.id, &alice).await.unwrap());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
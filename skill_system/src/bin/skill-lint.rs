@@ -0,0 +1,78 @@
+// skill_system/src/bin/skill-lint.rs
+// Validate skill JSON files: `skill-lint [--json] [--deny-warnings] [PATH...]`, `skill-lint --schema`
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use skill_system::{find_skills_directory, skill_json_schema, validate_paths};
+
+const USAGE: &str = "Usage: skill-lint [--json] [--deny-warnings] [PATH...]
+       skill-lint --schema
+
+Validates skill JSON files (files or directories; defaults to the skills/ directory).
+
+Options:
+  --schema          Print the JSON Schema for skill files and exit
+  --json            Print the report as JSON
+  --deny-warnings   Exit with an error if any warnings are found
+  -h, --help        Show this help";
+
+fn main() -> ExitCode {
+    let mut json = false;
+    let mut deny_warnings = false;
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--schema" => {
+                println!("{}", serde_json::to_string_pretty(&skill_json_schema()).unwrap_or_default());
+                return ExitCode::SUCCESS;
+            }
+            "--json" => json = true,
+            "--deny-warnings" => deny_warnings = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            other if other.starts_with('-') => {
+                eprintln!("Unknown option: {}\n\n{}", other, USAGE);
+                return ExitCode::from(2);
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.is_empty() {
+        match find_skills_directory() {
+            Some(dir) => paths.push(dir),
+            None => {
+                eprintln!("No skills directory found; pass a path.\n\n{}", USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let report = validate_paths(&paths);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        for issue in &report.issues {
+            println!("{}", issue);
+        }
+        println!(
+            "Checked {} skills in {} files: {} errors, {} warnings",
+            report.skills_checked,
+            report.files_checked,
+            report.error_count(),
+            report.warning_count()
+        );
+    }
+
+    if report.has_errors() || (deny_warnings && report.warning_count() > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,466 @@
+// skill_system/src/bundle.rs
+// Versioned, checksummed and optionally signed skill bundles (`.phxskill`, a gzipped tar)
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{SkillCategory, SkillDefinition};
+
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const BUNDLE_EXTENSION: &str = "phxskill";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// One skill inside a bundle, as listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleSkillEntry {
+    pub id: Uuid,
+    pub name: String,
+    pub version: String,
+    pub category: SkillCategory,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    /// Path of the skill JSON inside the archive.
+    pub file: String,
+    /// Hex SHA-256 of the skill JSON bytes.
+    pub sha256: String,
+}
+
+impl BundleSkillEntry {
+    /// Whether this skill satisfies a prerequisite (given as a skill id or name).
+    pub fn satisfies(&self, prerequisite: &str) -> bool {
+        let prerequisite = prerequisite.trim();
+        Uuid::parse_str(prerequisite).is_ok_and(|id| id == self.id) || self.name.eq_ignore_ascii_case(prerequisite)
+    }
+}
+
+/// Ed25519 signature over the manifest (serialized without the signature itself).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    /// Hex-encoded public key of the signer.
+    pub public_key: String,
+    /// Hex-encoded signature.
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    pub skills: Vec<BundleSkillEntry>,
+    #[serde(default)]
+    pub signature: Option<BundleSignature>,
+}
+
+impl BundleManifest {
+    fn signing_payload(&self) -> Result<Vec<u8>, String> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        serde_json::to_vec(&unsigned).map_err(|e| format!("Failed to serialize manifest: {}", e))
+    }
+
+    pub fn categories(&self) -> Vec<SkillCategory> {
+        let mut out = Vec::new();
+        for skill in &self.skills {
+            if !out.contains(&skill.category) {
+                out.push(skill.category);
+            }
+        }
+        out
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.skills
+            .iter()
+            .flat_map(|s| s.tags.iter())
+            .map(|t| t.trim().to_ascii_lowercase())
+            .filter(|t| !t.is_empty() && seen.insert(t.clone()))
+            .collect()
+    }
+
+    /// Whether a skill in this bundle satisfies `prerequisite`.
+    pub fn provides(&self, prerequisite: &str) -> bool {
+        self.skills.iter().any(|s| s.satisfies(prerequisite))
+    }
+
+    /// Prerequisites that the bundle does not satisfy itself.
+    pub fn external_prerequisites(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for prerequisite in self.skills.iter().flat_map(|s| s.prerequisites.iter()) {
+            let prerequisite = prerequisite.trim();
+            if !prerequisite.is_empty() && !self.provides(prerequisite) && !out.iter().any(|p| p == prerequisite) {
+                out.push(prerequisite.to_string());
+            }
+        }
+        out
+    }
+}
+
+/// A bundle in memory: the manifest plus the skills it lists (in manifest order).
+#[derive(Debug, Clone)]
+pub struct SkillBundle {
+    pub manifest: BundleManifest,
+    pub skills: Vec<SkillDefinition>,
+}
+
+impl SkillBundle {
+    /// Build an unsigned bundle. `name` must be a slug (`[a-z0-9_-]`) so it can be used in file names.
+    pub fn new(
+        name: &str,
+        version: &str,
+        description: &str,
+        author: &str,
+        skills: Vec<SkillDefinition>,
+    ) -> Result<Self, String> {
+        let (name, version) = (name.trim(), version.trim());
+        check_name_and_version(name, version)?;
+        if skills.is_empty() {
+            return Err("A bundle needs at least one skill".to_string());
+        }
+
+        let mut entries = Vec::with_capacity(skills.len());
+        let mut seen = HashSet::new();
+        for skill in &skills {
+            if !seen.insert(skill.id) {
+                return Err(format!("Skill {} appears twice in the bundle", skill.id));
+            }
+            let bytes = skill_bytes(skill)?;
+            entries.push(BundleSkillEntry {
+                id: skill.id,
+                name: skill.name.clone(),
+                version: skill.version.clone(),
+                category: skill.category,
+                tags: skill.tags.clone(),
+                prerequisites: skill.prerequisites.clone(),
+                file: format!("skills/{}.json", skill.id),
+                sha256: sha256_hex(&bytes),
+            });
+        }
+
+        Ok(Self {
+            manifest: BundleManifest {
+                format_version: BUNDLE_FORMAT_VERSION,
+                name: name.to_string(),
+                version: version.to_string(),
+                description: description.to_string(),
+                author: author.to_string(),
+                created_at: Utc::now(),
+                skills: entries,
+                signature: None,
+            },
+            skills,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.manifest.version
+    }
+
+    /// Conventional file name: `<name>-<version>.phxskill`.
+    pub fn file_name(&self) -> String {
+        format!("{}-{}.{}", self.manifest.name, self.manifest.version, BUNDLE_EXTENSION)
+    }
+
+    pub fn sign(&mut self, signer: &BundleSigner) -> Result<(), String> {
+        self.manifest.signature = None;
+        let payload = self.manifest.signing_payload()?;
+        let signature = signer.key.sign(&payload);
+        self.manifest.signature = Some(BundleSignature {
+            public_key: signer.public_key_hex(),
+            signature: hex::encode(signature.to_bytes()),
+        });
+        Ok(())
+    }
+
+    /// Check the signature against `policy`. Returns the signer's public key (if signed).
+    pub fn verify(&self, policy: &TrustPolicy) -> Result<Option<String>, String> {
+        let Some(sig) = &self.manifest.signature else {
+            return if policy.allow_unsigned {
+                Ok(None)
+            } else {
+                Err(format!("Bundle {} is unsigned and unsigned bundles are not allowed", self.file_name()))
+            };
+        };
+
+        let key_bytes: [u8; 32] = decode_hex_array(&sig.public_key).ok_or("Malformed signer public key")?;
+        let sig_bytes: [u8; 64] = decode_hex_array(&sig.signature).ok_or("Malformed bundle signature")?;
+        let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| format!("Invalid signer public key: {}", e))?;
+        key.verify(&self.manifest.signing_payload()?, &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| format!("Signature check failed for bundle {}", self.file_name()))?;
+
+        // A valid signature only proves who signed it; the signer itself must be trusted.
+        let signer = sig.public_key.to_ascii_lowercase();
+        if !policy.trusted_keys.contains(&signer) {
+            return Err(format!("Bundle {} is signed by an untrusted key {}", self.file_name(), signer));
+        }
+        Ok(Some(signer))
+    }
+
+    /// Pack into a gzipped tar: `manifest.json` plus one JSON file per skill.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let manifest = serde_json::to_vec_pretty(&self.manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+        append_file(&mut tar, MANIFEST_FILE, &manifest)?;
+        for (entry, skill) in self.manifest.skills.iter().zip(&self.skills) {
+            append_file(&mut tar, &entry.file, &skill_bytes(skill)?)?;
+        }
+        let encoder = tar.into_inner().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+        encoder.finish().map_err(|e| format!("Failed to compress bundle: {}", e))
+    }
+
+    /// Unpack and check format version, name, checksums and skill ids. Signatures are checked
+    /// separately with [`SkillBundle::verify`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+        for entry in archive.entries().map_err(|e| format!("Invalid bundle archive: {}", e))? {
+            let mut entry = entry.map_err(|e| format!("Invalid bundle archive: {}", e))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry
+                .path()
+                .map_err(|e| format!("Invalid path in bundle: {}", e))?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let mut data = Vec::new();
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {} from bundle: {}", path, e))?;
+            files.insert(path, data);
+        }
+
+        let manifest_bytes = files.get(MANIFEST_FILE).ok_or("Bundle has no manifest.json")?;
+        let manifest: BundleManifest =
+            serde_json::from_slice(manifest_bytes).map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+        if manifest.format_version > BUNDLE_FORMAT_VERSION {
+            return Err(format!(
+                "Bundle format {} is newer than supported format {}",
+                manifest.format_version, BUNDLE_FORMAT_VERSION
+            ));
+        }
+        // Name and version end up in file names (see `file_name`).
+        check_name_and_version(&manifest.name, &manifest.version)?;
+
+        let mut skills = Vec::with_capacity(manifest.skills.len());
+        for entry in &manifest.skills {
+            let data = files
+                .get(&entry.file)
+                .ok_or_else(|| format!("Bundle is missing {}", entry.file))?;
+            if sha256_hex(data) != entry.sha256.to_ascii_lowercase() {
+                return Err(format!("Checksum mismatch for {}", entry.file));
+            }
+            let skill: SkillDefinition =
+                serde_json::from_slice(data).map_err(|e| format!("Invalid skill {}: {}", entry.file, e))?;
+            if skill.id != entry.id {
+                return Err(format!("{} holds skill {} but the manifest lists {}", entry.file, skill.id, entry.id));
+            }
+            skills.push(skill);
+        }
+
+        Ok(Self { manifest, skills })
+    }
+
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(path, self.to_bytes()?).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn read_from(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Signs bundles with an Ed25519 key.
+pub struct BundleSigner {
+    key: SigningKey,
+}
+
+impl BundleSigner {
+    /// Create a signer from a hex-encoded 32-byte secret seed.
+    pub fn from_seed_hex(seed: &str) -> Result<Self, String> {
+        let seed: [u8; 32] = decode_hex_array(seed).ok_or("Signing key must be 32 hex-encoded bytes")?;
+        Ok(Self {
+            key: SigningKey::from_bytes(&seed),
+        })
+    }
+
+    /// The signer configured by `SKILL_BUNDLE_SIGNING_KEY`, if any.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match std::env::var("SKILL_BUNDLE_SIGNING_KEY") {
+            Ok(seed) if !seed.trim().is_empty() => Self::from_seed_hex(&seed).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key.verifying_key().to_bytes())
+    }
+}
+
+/// Which bundle signatures are accepted on install.
+#[derive(Debug, Clone, Default)]
+pub struct TrustPolicy {
+    /// Hex public keys. Signed bundles must come from one of these keys.
+    pub trusted_keys: Vec<String>,
+    pub allow_unsigned: bool,
+}
+
+impl TrustPolicy {
+    /// `SKILL_BUNDLE_TRUSTED_KEYS` (comma separated hex keys) and `SKILL_BUNDLE_ALLOW_UNSIGNED`
+    /// (default false).
+    pub fn from_env() -> Self {
+        let trusted_keys: Vec<String> = std::env::var("SKILL_BUNDLE_TRUSTED_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|k| k.trim().to_ascii_lowercase())
+            .filter(|k| !k.is_empty())
+            .collect();
+        let allow_unsigned = std::env::var("SKILL_BUNDLE_ALLOW_UNSIGNED")
+            .ok()
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        Self {
+            trusted_keys,
+            allow_unsigned,
+        }
+    }
+}
+
+/// Bundle names are slugs (`[a-z0-9_-]`) and versions `MAJOR.MINOR.PATCH`, so both are safe in
+/// file names.
+pub(crate) fn check_name_and_version(name: &str, version: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        return Err(format!("Invalid bundle name '{}': use lowercase letters, digits, '-' and '_'", name));
+    }
+    if version.split('.').count() != 3 || !version.split('.').all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit())) {
+        return Err(format!("Invalid bundle version '{}': expected MAJOR.MINOR.PATCH", version));
+    }
+    Ok(())
+}
+
+fn skill_bytes(skill: &SkillDefinition) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(skill).map_err(|e| format!("Failed to serialize skill {}: {}", skill.id, e))
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn decode_hex_array<const N: usize>(s: &str) -> Option<[u8; N]> {
+    hex::decode(s.trim()).ok()?.try_into().ok()
+}
+
+fn append_file<W: std::io::Write>(tar: &mut tar::Builder<W>, path: &str, data: &[u8]) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_cksum();
+    tar.append_data(&mut header, path, data)
+        .map_err(|e| format!("Failed to add {} to bundle: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str, prerequisites: &[&str]) -> SkillDefinition {
+        let mut s = SkillDefinition::new(name, SkillCategory::Communication, "test skill", "tests");
+        s.prerequisites = prerequisites.iter().map(|p| p.to_string()).collect();
+        s
+    }
+
+    #[test]
+    fn test_signed_bundle_round_trip() {
+        let mut bundle = SkillBundle::new(
+            "listening",
+            "1.0.0",
+            "Listening skills",
+            "tests",
+            vec![skill("Active Listening", &[]), skill("Reflective Summary", &["Active Listening", "Empathy"])],
+        )
+        .unwrap();
+        assert_eq!(bundle.manifest.external_prerequisites(), vec!["Empathy".to_string()]);
+
+        let signer = BundleSigner::from_seed_hex(&"07".repeat(32)).unwrap();
+        bundle.sign(&signer).unwrap();
+
+        let unpacked = SkillBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+        assert_eq!(unpacked.manifest, bundle.manifest);
+        assert_eq!(unpacked.skills.len(), 2);
+
+        let trusted = TrustPolicy {
+            trusted_keys: vec![signer.public_key_hex()],
+            allow_unsigned: false,
+        };
+        assert_eq!(unpacked.verify(&trusted).unwrap(), Some(signer.public_key_hex()));
+
+        let other = TrustPolicy {
+            trusted_keys: vec!["00".repeat(32)],
+            allow_unsigned: true,
+        };
+        assert!(unpacked.verify(&other).is_err());
+
+        // A valid signature from a key nobody listed is not enough.
+        let self_signed = TrustPolicy {
+            trusted_keys: vec![],
+            allow_unsigned: true,
+        };
+        assert!(unpacked.verify(&self_signed).unwrap_err().contains("untrusted key"));
+
+        let mut tampered = unpacked.clone();
+        tampered.manifest.version = "1.0.1".to_string();
+        assert!(tampered.verify(&TrustPolicy::default()).is_err());
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_rejected() {
+        let mut bundle = SkillBundle::new("one", "0.1.0", "", "tests", vec![skill("One", &[])]).unwrap();
+        bundle.manifest.skills[0].sha256 = "00".repeat(32);
+        let err = SkillBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap_err();
+        assert!(err.contains("Checksum mismatch"), "{}", err);
+
+        let unsigned = SkillBundle::new("one", "0.1.0", "", "tests", vec![skill("One", &[])]).unwrap();
+        assert!(unsigned.verify(&TrustPolicy::default()).is_err());
+        assert!(SkillBundle::new("Bad Name", "0.1.0", "", "tests", vec![skill("One", &[])]).is_err());
+    }
+
+    #[test]
+    fn test_unpacked_manifest_names_are_checked() {
+        for (name, version) in [("../../etc/evil", "1.0.0"), ("ok", "1.0.0/../../x"), ("", "1.0.0")] {
+            let mut bundle = SkillBundle::new("one", "0.1.0", "", "tests", vec![skill("One", &[])]).unwrap();
+            bundle.manifest.name = name.to_string();
+            bundle.manifest.version = version.to_string();
+            let err = SkillBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap_err();
+            assert!(err.starts_with("Invalid bundle"), "{}", err);
+        }
+    }
+}
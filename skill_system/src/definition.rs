@@ -3,11 +3,12 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// High-level categories for skills.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum SkillCategory {
     // Core Phoenix Skills
     Communication,
@@ -36,7 +37,7 @@ pub enum SkillCategory {
 }
 
 /// A single step in a skill.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillStep {
     pub title: String,
     pub instruction: String,
@@ -45,7 +46,7 @@ pub struct SkillStep {
 }
 
 /// Concrete example of a skill in use.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillExample {
     pub situation: String,
     pub input: String,
@@ -53,14 +54,14 @@ pub struct SkillExample {
 }
 
 /// Optional variation of a skill (e.g., for different attachment styles).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillVariation {
     pub name: String,
     pub when_to_use: String,
     pub steps_override: Option<Vec<SkillStep>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum EmotionalTag {
     Calm,
     Grounding,
@@ -75,7 +76,7 @@ pub enum EmotionalTag {
 ///
 /// This avoids coupling the skill system to any single relationship engine; higher layers can map
 /// from e.g. [`relationship_dynamics::Partnership`](extensions/relationship_dynamics/src/relationship_dynamics/mod.rs:100).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RelationshipContext {
     pub template: Option<String>,
     pub intimacy_level: Option<String>,
@@ -89,7 +90,7 @@ pub struct RelationshipContext {
 }
 
 /// Tuning knobs to adapt skill execution for different relationship states.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillModifier {
     pub extra_reassurance: f32,
     pub pace_slowdown: f32,
@@ -107,16 +108,69 @@ impl Default for SkillModifier {
 }
 
 /// A record of skill evolution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillEvolutionRecord {
     pub ts: DateTime<Utc>,
     pub kind: String,
     pub rationale: String,
     pub parent_skill_id: Option<Uuid>,
+    /// Version of the parent this record evolved from.
+    #[serde(default)]
+    pub parent_version: Option<String>,
+    /// Version produced by this evolution step.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// Which part of a `major.minor.patch` version to bump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+}
+
+/// Bump a semantic version. Missing or non-numeric components count as `0`, and any
+/// pre-release/build suffix is dropped.
+pub fn bump_version(version: &str, bump: VersionBump) -> String {
+    let (major, minor, patch) = version_triple(version);
+    match bump {
+        VersionBump::Major => format!("{}.0.0", major + 1),
+        VersionBump::Minor => format!("{}.{}.0", major, minor + 1),
+        VersionBump::Patch => format!("{}.{}.{}", major, minor, patch + 1),
+    }
+}
+
+/// Order two version strings by their numeric `major.minor.patch` core.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    version_triple(a).cmp(&version_triple(b))
+}
+
+fn version_triple(version: &str) -> (u64, u64, u64) {
+    let core = version.trim().split(['-', '+']).next().unwrap_or("");
+    let mut parts = core
+        .split('.')
+        .map(|p| p.trim().parse::<u64>().unwrap_or(0))
+        .chain(std::iter::repeat(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// Lifecycle of a skill. Evolved variants start `Experimental` and are promoted or retired by
+/// A/B evaluation (see [`crate::SkillExperiments`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum SkillStatus {
+    #[default]
+    Active,
+    Experimental,
+    Retired,
 }
 
 /// The core skill definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SkillDefinition {
     // Identity
     pub id: Uuid,
@@ -163,6 +217,8 @@ pub struct SkillDefinition {
     pub parent_skill_id: Option<Uuid>,
     #[serde(default)]
     pub child_skill_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub status: SkillStatus,
 
     // Search
     #[serde(default)]
@@ -197,11 +253,16 @@ impl SkillDefinition {
             evolution_history: vec![],
             parent_skill_id: None,
             child_skill_ids: vec![],
+            status: SkillStatus::Active,
             tags: vec![],
             emotional_tags: vec![],
         }
     }
 
+    pub fn next_version(&self, bump: VersionBump) -> String {
+        bump_version(&self.version, bump)
+    }
+
     pub fn clamp_metrics(&mut self) {
         self.love_score = self.love_score.clamp(0.0, 1.0);
         self.utility_score = self.utility_score.clamp(0.0, 1.0);
@@ -0,0 +1,588 @@
+// skill_system/src/evaluation.rs
+// Offline A/B evaluation of evolved skills: run parent and child on stored scenarios, judge, compare
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use llm_orchestrator::LlmProvider;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{SkillContext, SkillDefinition, SkillExample, SkillExecutionEngine, SkillResult};
+
+/// A judge's verdict on one execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgeScore {
+    /// 0.0..=1.0, higher is better.
+    pub score: f32,
+    pub rationale: String,
+}
+
+/// Scores how well a skill handled a scenario.
+#[async_trait]
+pub trait SkillJudge: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn judge(
+        &self,
+        skill: &SkillDefinition,
+        scenario: &SkillExample,
+        result: &SkillResult,
+    ) -> Result<JudgeScore, String>;
+}
+
+/// Deterministic judge: how much of the scenario's expected output the result covers, with
+/// penalties for failed or vetoed steps.
+#[derive(Debug, Clone, Default)]
+pub struct RuleBasedJudge;
+
+#[async_trait]
+impl SkillJudge for RuleBasedJudge {
+    fn name(&self) -> &str {
+        "rule_based"
+    }
+
+    async fn judge(
+        &self,
+        _skill: &SkillDefinition,
+        scenario: &SkillExample,
+        result: &SkillResult,
+    ) -> Result<JudgeScore, String> {
+        if !result.success {
+            return Ok(JudgeScore {
+                score: 0.0,
+                rationale: "execution failed".to_string(),
+            });
+        }
+
+        let expected: HashSet<String> = terms(&scenario.output).collect();
+        let produced: HashSet<String> = terms(&result.output).collect();
+        let coverage = if expected.is_empty() {
+            1.0
+        } else {
+            expected.intersection(&produced).count() as f32 / expected.len() as f32
+        };
+        let vetoed = result.step_results.iter().filter(|s| s.vetoed).count();
+        let failed = result.step_results.iter().filter(|s| !s.success && !s.vetoed).count();
+        let penalty = 0.25 * vetoed as f32 + 0.1 * failed as f32;
+        let score = (0.2 + 0.8 * coverage - penalty).clamp(0.0, 1.0);
+
+        Ok(JudgeScore {
+            score,
+            rationale: format!(
+                "covers {:.0}% of expected terms; {} vetoed, {} failed steps",
+                coverage * 100.0,
+                vetoed,
+                failed
+            ),
+        })
+    }
+}
+
+/// Asks an LLM to grade the output against the scenario's reference answer.
+pub struct LlmJudge {
+    llm: Arc<dyn LlmProvider>,
+}
+
+impl LlmJudge {
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self { llm }
+    }
+}
+
+#[async_trait]
+impl SkillJudge for LlmJudge {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    async fn judge(
+        &self,
+        skill: &SkillDefinition,
+        scenario: &SkillExample,
+        result: &SkillResult,
+    ) -> Result<JudgeScore, String> {
+        let prompt = format!(
+            "You are grading how well an assistant applied the skill \"{}\" ({}).\n\
+             Situation: {}\nUser said: {}\nReference response: {}\n\nActual response:\n{}\n\n\
+             Grade the actual response for helpfulness, warmth and safety compared to the reference.\n\
+             Reply exactly in this format:\nSCORE: <number between 0 and 1>\nREASON: <one sentence>",
+            skill.name, skill.description, scenario.situation, scenario.input, scenario.output, result.output
+        );
+        let reply = self.llm.complete(prompt).await?;
+        parse_judge_reply(&reply).ok_or_else(|| format!("Judge reply has no SCORE line: {}", reply.trim()))
+    }
+}
+
+fn parse_judge_reply(reply: &str) -> Option<JudgeScore> {
+    let mut score = None;
+    let mut rationale = String::new();
+    for line in reply.lines() {
+        let line = line.trim();
+        let upper = line.to_ascii_uppercase();
+        if upper.starts_with("SCORE:") {
+            score = line[6..].trim().trim_end_matches('.').parse::<f32>().ok();
+        } else if upper.starts_with("REASON:") {
+            rationale = line[7..].trim().to_string();
+        }
+    }
+    Some(JudgeScore {
+        score: score?.clamp(0.0, 1.0),
+        rationale,
+    })
+}
+
+/// When a variant counts as better or worse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionPolicy {
+    /// Samples per arm before deciding. Live experiments are tested once, when both arms reach it.
+    pub min_samples: u64,
+    /// Significance level for the two-sided test.
+    pub alpha: f64,
+    /// Smallest mean difference worth acting on.
+    pub min_effect: f64,
+}
+
+impl Default for DecisionPolicy {
+    fn default() -> Self {
+        Self {
+            min_samples: 20,
+            alpha: 0.05,
+            min_effect: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VariantDecision {
+    /// The child is significantly better: it takes over from the parent.
+    Promote,
+    /// The child is significantly worse: stop using it.
+    Retire,
+    /// Not enough evidence yet.
+    Continue,
+}
+
+/// Result of a two-sample comparison (child minus parent).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignificanceTest {
+    pub parent_n: u64,
+    pub child_n: u64,
+    pub parent_mean: f64,
+    pub child_mean: f64,
+    pub mean_diff: f64,
+    pub t: f64,
+    pub df: f64,
+    pub p_value: f64,
+}
+
+impl SignificanceTest {
+    pub fn decide(&self, policy: &DecisionPolicy) -> VariantDecision {
+        if self.parent_n < policy.min_samples || self.child_n < policy.min_samples {
+            return VariantDecision::Continue;
+        }
+        if self.p_value >= policy.alpha || self.mean_diff.abs() < policy.min_effect {
+            return VariantDecision::Continue;
+        }
+        if self.mean_diff > 0.0 {
+            VariantDecision::Promote
+        } else {
+            VariantDecision::Retire
+        }
+    }
+}
+
+/// Running sums for one arm of a comparison.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArmStats {
+    pub n: u64,
+    pub sum: f64,
+    pub sum_sq: f64,
+}
+
+impl ArmStats {
+    pub fn record(&mut self, value: f64) {
+        self.n += 1;
+        self.sum += value;
+        self.sum_sq += value * value;
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.n == 0 {
+            0.0
+        } else {
+            self.sum / self.n as f64
+        }
+    }
+
+    /// Sample variance (0 with fewer than two samples).
+    pub fn variance(&self) -> f64 {
+        if self.n < 2 {
+            return 0.0;
+        }
+        let n = self.n as f64;
+        ((self.sum_sq - self.sum * self.sum / n) / (n - 1.0)).max(0.0)
+    }
+}
+
+/// Welch's t-test for `child` vs `parent`. `None` until both arms have two samples.
+pub fn welch_test(parent: &ArmStats, child: &ArmStats) -> Option<SignificanceTest> {
+    if parent.n < 2 || child.n < 2 {
+        return None;
+    }
+    let (n1, n2) = (parent.n as f64, child.n as f64);
+    let (v1, v2) = (parent.variance() / n1, child.variance() / n2);
+    let mean_diff = child.mean() - parent.mean();
+    let se2 = v1 + v2;
+
+    let (t, df, p_value) = if se2 <= f64::EPSILON {
+        // Both arms are constant: any difference is certain, no difference is no evidence.
+        let p = if mean_diff.abs() <= f64::EPSILON { 1.0 } else { 0.0 };
+        (if p == 0.0 { mean_diff.signum() * f64::INFINITY } else { 0.0 }, n1 + n2 - 2.0, p)
+    } else {
+        let t = mean_diff / se2.sqrt();
+        let df = se2 * se2 / (v1 * v1 / (n1 - 1.0) + v2 * v2 / (n2 - 1.0));
+        (t, df, student_t_two_sided_p(t, df))
+    };
+
+    Some(SignificanceTest {
+        parent_n: parent.n,
+        child_n: child.n,
+        parent_mean: parent.mean(),
+        child_mean: child.mean(),
+        mean_diff,
+        t,
+        df,
+        p_value,
+    })
+}
+
+/// Paired t-test on `(parent, child)` scores of the same scenarios. `None` with fewer than two
+/// pairs.
+pub fn paired_test(pairs: &[(f64, f64)]) -> Option<SignificanceTest> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let (mut parent, mut child, mut diffs) = (ArmStats::default(), ArmStats::default(), ArmStats::default());
+    for (p, c) in pairs {
+        parent.record(*p);
+        child.record(*c);
+        diffs.record(c - p);
+    }
+    let n = diffs.n as f64;
+    let mean_diff = diffs.mean();
+    let se2 = diffs.variance() / n;
+    let df = n - 1.0;
+
+    let (t, p_value) = if se2 <= f64::EPSILON {
+        // Every scenario moved by the same amount.
+        if mean_diff.abs() <= f64::EPSILON {
+            (0.0, 1.0)
+        } else {
+            (mean_diff.signum() * f64::INFINITY, 0.0)
+        }
+    } else {
+        let t = mean_diff / se2.sqrt();
+        (t, student_t_two_sided_p(t, df))
+    };
+
+    Some(SignificanceTest {
+        parent_n: parent.n,
+        child_n: child.n,
+        parent_mean: parent.mean(),
+        child_mean: child.mean(),
+        mean_diff,
+        t,
+        df,
+        p_value,
+    })
+}
+
+/// Two-sided p-value of Student's t distribution: `I_{df/(df+t^2)}(df/2, 1/2)`.
+fn student_t_two_sided_p(t: f64, df: f64) -> f64 {
+    if !t.is_finite() {
+        return 0.0;
+    }
+    regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    let front = ln_front.exp();
+    // The continued fraction converges quickly for x < (a + 1) / (a + b + 2).
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        d = if d.abs() < TINY { TINY } else { d };
+        c = 1.0 + aa / c;
+        c = if c.abs() < TINY { TINY } else { c };
+        d = 1.0 / d;
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        d = if d.abs() < TINY { TINY } else { d };
+        c = 1.0 + aa / c;
+        c = if c.abs() < TINY { TINY } else { c };
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+/// Lanczos approximation of ln(Γ(x)) for x > 0.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut ser = 1.000000000190015;
+    for (i, c) in COEFFS.iter().enumerate() {
+        ser += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.5066282746310005 * ser / x).ln()
+}
+
+/// Parent and child scores for one scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioOutcome {
+    pub situation: String,
+    pub input: String,
+    pub parent: JudgeScore,
+    pub child: JudgeScore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub parent_id: Uuid,
+    pub child_id: Uuid,
+    pub judge: String,
+    pub outcomes: Vec<ScenarioOutcome>,
+    pub test: Option<SignificanceTest>,
+    pub decision: VariantDecision,
+}
+
+/// Runs a parent skill and its variant over the same scenarios and compares judged scores.
+pub struct EvaluationHarness {
+    judge: Arc<dyn SkillJudge>,
+}
+
+impl EvaluationHarness {
+    pub fn new(judge: Arc<dyn SkillJudge>) -> Self {
+        Self { judge }
+    }
+
+    /// Stored scenarios for a comparison: the examples of both skills, de-duplicated by input.
+    pub fn scenarios(parent: &SkillDefinition, child: &SkillDefinition) -> Vec<SkillExample> {
+        let mut seen = HashSet::new();
+        parent
+            .examples
+            .iter()
+            .chain(child.examples.iter())
+            .filter(|e| seen.insert(e.input.trim().to_ascii_lowercase()))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn compare(
+        &self,
+        engine: &mut SkillExecutionEngine,
+        parent: &SkillDefinition,
+        child: &SkillDefinition,
+        scenarios: &[SkillExample],
+        policy: &DecisionPolicy,
+    ) -> Result<ComparisonReport, String> {
+        if scenarios.is_empty() {
+            return Err(format!("Skill '{}' has no example scenarios to evaluate against", parent.name));
+        }
+
+        let mut pairs = Vec::with_capacity(scenarios.len());
+        let mut outcomes = Vec::with_capacity(scenarios.len());
+        for scenario in scenarios {
+            let parent_score = self.run(engine, parent, scenario).await?;
+            let child_score = self.run(engine, child, scenario).await?;
+            pairs.push((parent_score.score as f64, child_score.score as f64));
+            outcomes.push(ScenarioOutcome {
+                situation: scenario.situation.clone(),
+                input: scenario.input.clone(),
+                parent: parent_score,
+                child: child_score,
+            });
+        }
+
+        // Both skills ran on the same scenarios, so compare per-scenario differences.
+        let test = paired_test(&pairs);
+        let decision = test
+            .as_ref()
+            .map(|t| t.decide(policy))
+            .unwrap_or(VariantDecision::Continue);
+        Ok(ComparisonReport {
+            parent_id: parent.id,
+            child_id: child.id,
+            judge: self.judge.name().to_string(),
+            outcomes,
+            test,
+            decision,
+        })
+    }
+
+    async fn run(
+        &self,
+        engine: &mut SkillExecutionEngine,
+        skill: &SkillDefinition,
+        scenario: &SkillExample,
+    ) -> Result<JudgeScore, String> {
+        let ctx = scenario_context(skill, scenario);
+        match engine.execute(skill, ctx).await {
+            Ok(result) => self.judge.judge(skill, scenario, &result).await,
+            Err(e) => Ok(JudgeScore {
+                score: 0.0,
+                rationale: format!("execution error: {}", e),
+            }),
+        }
+    }
+}
+
+/// Context for replaying a scenario. Phase gates are treated as met: the evaluation compares
+/// skills, it does not decide whether they may run in a given relationship.
+fn scenario_context(skill: &SkillDefinition, scenario: &SkillExample) -> SkillContext {
+    SkillContext {
+        user_input: scenario.input.clone(),
+        emotional_state: None,
+        relationship_context: skill.relationship_context.clone(),
+        relationship_phase: skill.min_relationship_phase.clone(),
+        previous_interactions: vec![],
+        environment_vars: HashMap::from([("scenario".to_string(), scenario.situation.clone())]),
+    }
+}
+
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= 3)
+        .map(|t| t.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arm(values: &[f64]) -> ArmStats {
+        let mut stats = ArmStats::default();
+        for v in values {
+            stats.record(*v);
+        }
+        stats
+    }
+
+    #[test]
+    fn test_welch_test_detects_real_differences_only() {
+        // Reference value: t = -3.0, df = 8 (equal variances) -> two-sided p ≈ 0.0171.
+        let parent = arm(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let child = arm(&[4.0, 5.0, 6.0, 7.0, 8.0]);
+        let test = welch_test(&parent, &child).unwrap();
+        assert!((test.t - 3.0).abs() < 1e-9);
+        assert!((test.p_value - 0.0171).abs() < 1e-3, "p = {}", test.p_value);
+
+        let policy = DecisionPolicy {
+            min_samples: 5,
+            alpha: 0.05,
+            min_effect: 0.5,
+        };
+        assert_eq!(test.decide(&policy), VariantDecision::Promote);
+        assert_eq!(welch_test(&child, &parent).unwrap().decide(&policy), VariantDecision::Retire);
+
+        let noisy = welch_test(&arm(&[0.0, 1.0, 0.0, 1.0, 1.0]), &arm(&[1.0, 0.0, 1.0, 0.0, 1.0])).unwrap();
+        assert_eq!(noisy.decide(&policy), VariantDecision::Continue);
+    }
+
+    #[test]
+    fn test_paired_test_uses_per_scenario_differences() {
+        // Scenarios differ a lot in difficulty; the child is consistently ~0.05 better.
+        let parent = [0.1, 0.5, 0.9, 0.3, 0.7];
+        let gains = [0.04, 0.06, 0.05, 0.05, 0.06];
+        let pairs: Vec<(f64, f64)> = parent.iter().zip(gains).map(|(p, g)| (*p, p + g)).collect();
+        let policy = DecisionPolicy {
+            min_samples: 5,
+            alpha: 0.05,
+            min_effect: 0.05,
+        };
+
+        let paired = paired_test(&pairs).unwrap();
+        assert_eq!(paired.df, 4.0);
+        assert!(paired.p_value < 0.001, "p = {}", paired.p_value);
+        assert_eq!(paired.decide(&policy), VariantDecision::Promote);
+
+        // Treated as independent samples, the scenario spread hides the improvement.
+        let unpaired = welch_test(&arm(&parent), &arm(&pairs.iter().map(|p| p.1).collect::<Vec<_>>())).unwrap();
+        assert_eq!(unpaired.decide(&policy), VariantDecision::Continue);
+
+        assert_eq!(paired_test(&[(0.5, 0.5), (0.2, 0.2)]).unwrap().p_value, 1.0);
+        assert!(paired_test(&[(0.5, 0.9)]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_harness_compares_parent_and_child_on_examples() {
+        let mut parent = SkillDefinition::new("Parent", crate::SkillCategory::Communication, "parent", "tests");
+        parent.examples = vec![SkillExample {
+            situation: "friend is sad".to_string(),
+            input: "I feel sad".to_string(),
+            output: "I hear you; want to talk about it?".to_string(),
+        }];
+        let child = parent.clone();
+
+        let harness = EvaluationHarness::new(Arc::new(RuleBasedJudge));
+        let scenarios = EvaluationHarness::scenarios(&parent, &child);
+        assert_eq!(scenarios.len(), 1);
+        let mut engine = SkillExecutionEngine::new();
+        let report = harness
+            .compare(&mut engine, &parent, &child, &scenarios, &DecisionPolicy::default())
+            .await
+            .unwrap();
+        assert_eq!(report.outcomes.len(), 1);
+        assert_eq!(report.judge, "rule_based");
+        assert_eq!(report.decision, VariantDecision::Continue);
+    }
+
+    #[test]
+    fn test_parse_judge_reply() {
+        let score = parse_judge_reply("SCORE: 0.8\nREASON: warm and concrete.").unwrap();
+        assert_eq!(score.score, 0.8);
+        assert_eq!(score.rationale, "warm and concrete.");
+        assert!(parse_judge_reply("looks fine").is_none());
+    }
+}
@@ -2,7 +2,7 @@
 
 use chrono::{DateTime, Utc};
 
-use crate::{SkillDefinition, SkillEvolutionRecord, SkillStep, SkillVariation};
+use crate::{SkillDefinition, SkillEvolutionRecord, SkillStatus, SkillStep, SkillVariation, VersionBump};
 
 #[derive(Debug, Clone)]
 pub struct SkillEvolution {
@@ -24,6 +24,12 @@ impl SkillEvolutionSystem {
     /// Current strategy (conservative):
     /// - If love_score is high but utility_score is low, add a "more actionable" variation.
     /// - If utility is high but love is low, add a "warmer tone" variation.
+    ///
+    /// A new variation is a minor version bump of its parent with a pre-release suffix naming the
+    /// variation and its position among the parent's children (e.g. `1.3.0-warmer.2`), so sibling
+    /// variants never share a version. The parent's `evolution_history` is inherited so the full
+    /// ancestry stays on the child. Variations start out [`SkillStatus::Experimental`] until A/B
+    /// evaluation promotes or retires them.
     pub async fn evolve_skill(&mut self, mut skill: SkillDefinition) -> Result<SkillEvolution, String> {
         let ts = Utc::now();
 
@@ -38,7 +44,9 @@ impl SkillEvolutionSystem {
             v.parent_skill_id = Some(skill.id);
             v.id = uuid::Uuid::new_v4();
             v.name = format!("{} (More Actionable)", skill.name);
-            v.version = "0.1.1".to_string();
+            v.version = variant_version(&skill, "actionable");
+            v.status = SkillStatus::Experimental;
+            v.child_skill_ids.clear();
             v.utility_score = (v.utility_score + 0.10).clamp(0.0, 1.0);
             v.variations.push(SkillVariation {
                 name: "micro_steps".to_string(),
@@ -56,6 +64,8 @@ impl SkillEvolutionSystem {
                 kind: kind.clone(),
                 rationale: rationale.clone(),
                 parent_skill_id: Some(skill.id),
+                parent_version: Some(skill.version.clone()),
+                version: Some(v.version.clone()),
             });
             skill.child_skill_ids.push(v.id);
             new_skill = Some(v);
@@ -66,7 +76,9 @@ impl SkillEvolutionSystem {
             v.parent_skill_id = Some(skill.id);
             v.id = uuid::Uuid::new_v4();
             v.name = format!("{} (Warmer)", skill.name);
-            v.version = "0.1.1".to_string();
+            v.version = variant_version(&skill, "warmer");
+            v.status = SkillStatus::Experimental;
+            v.child_skill_ids.clear();
             v.love_score = (v.love_score + 0.15).clamp(0.0, 1.0);
             // Prepend a warmth step.
             let mut steps = vec![SkillStep {
@@ -81,6 +93,8 @@ impl SkillEvolutionSystem {
                 kind: kind.clone(),
                 rationale: rationale.clone(),
                 parent_skill_id: Some(skill.id),
+                parent_version: Some(skill.version.clone()),
+                version: Some(v.version.clone()),
             });
             skill.child_skill_ids.push(v.id);
             new_skill = Some(v);
@@ -95,3 +109,13 @@ impl SkillEvolutionSystem {
     }
 }
 
+/// Version of the next `variant` of `parent`: a minor bump plus `-<variant>.<n>`, where `n` counts
+/// the parent's children including this one.
+fn variant_version(parent: &SkillDefinition, variant: &str) -> String {
+    format!(
+        "{}-{}.{}",
+        parent.next_version(VersionBump::Minor),
+        variant,
+        parent.child_skill_ids.len() + 1
+    )
+}
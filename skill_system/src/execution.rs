@@ -1,21 +1,56 @@
 // skill_system/src/execution.rs
 
-use crate::{SkillContext, SkillDefinition, SkillResult};
+use std::sync::Arc;
+use std::time::Instant;
 
-pub struct SkillExecutionEngine;
+use ethical_agent::EthicalAgent;
+use llm_orchestrator::LlmProvider;
+
+use crate::{SkillContext, SkillDefinition, SkillResult, SkillStep, SkillStepResult};
+
+/// Max characters of a previous step's output carried into the next prompt.
+const MAX_CARRIED_OUTPUT_CHARS: usize = 2_000;
+
+pub struct SkillExecutionEngine {
+    /// LLM used to execute steps. Without one, skills render as a plan.
+    llm: Option<Arc<dyn LlmProvider>>,
+    /// Guardrail applied to every step output before it is kept.
+    ethics: Arc<EthicalAgent>,
+}
 
 impl SkillExecutionEngine {
     pub fn new() -> Self {
-        Self
+        Self {
+            llm: None,
+            ethics: Arc::new(EthicalAgent::new_default()),
+        }
+    }
+
+    /// Execute skill steps through an LLM instead of rendering a plan.
+    pub fn with_llm(mut self, llm: Arc<dyn LlmProvider>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// Share an ethical agent (and its dependency tracking) with the caller.
+    pub fn with_ethics(mut self, ethics: Arc<EthicalAgent>) -> Self {
+        self.ethics = ethics;
+        self
+    }
+
+    pub fn set_llm(&mut self, llm: Option<Arc<dyn LlmProvider>>) {
+        self.llm = llm;
     }
 
     /// Execute a skill.
     ///
-    /// Today this is a "procedural" execution engine: it renders the skill into a response plan.
-    /// Later we can add:
-    /// - LLM-backed execution with guardrails
-    /// - tool calls
-    /// - ORCH delegation
+    /// With an [`LlmProvider`] configured, each [`SkillStep`] is sent to the LLM in order; every
+    /// step sees the outputs of the steps before it, and every output must pass
+    /// [`EthicalAgent::veto_output`]. A veto or LLM error stops execution and is recorded in
+    /// [`SkillResult::step_results`].
+    ///
+    /// Without a provider this falls back to "procedural" execution: the skill is rendered into a
+    /// response plan (still subject to the veto). A skill without steps is an error either way.
     pub async fn execute(&mut self, skill: &SkillDefinition, ctx: SkillContext) -> Result<SkillResult, String> {
         // Check relationship phase requirement
         if let Some(min_phase) = &skill.min_relationship_phase {
@@ -36,7 +71,98 @@ impl SkillExecutionEngine {
                 ));
             }
         }
-        
+
+        // Nothing to run: succeeding here would report an empty reply as a successful run.
+        if skill.steps.is_empty() {
+            return Err(format!("Skill '{}' has no steps to execute", skill.name));
+        }
+
+        match self.llm.clone() {
+            Some(llm) => self.execute_with_llm(llm.as_ref(), skill, &ctx).await,
+            None => self.execute_procedural(skill, &ctx).await,
+        }
+    }
+
+    async fn execute_with_llm(
+        &self,
+        llm: &dyn LlmProvider,
+        skill: &SkillDefinition,
+        ctx: &SkillContext,
+    ) -> Result<SkillResult, String> {
+        let started = Instant::now();
+        let mut step_results: Vec<SkillStepResult> = Vec::with_capacity(skill.steps.len());
+        let mut outputs: Vec<String> = Vec::new();
+        let mut side_effects = Vec::new();
+        let mut success = true;
+
+        for (idx, step) in skill.steps.iter().enumerate() {
+            let prompt = build_step_prompt(skill, ctx, idx, step, &step_results);
+            let step_started = Instant::now();
+            let completion = llm.complete(prompt).await;
+
+            let mut record = SkillStepResult {
+                index: idx,
+                title: step.title.clone(),
+                output: String::new(),
+                success: false,
+                vetoed: false,
+                error: None,
+                duration_ms: 0,
+            };
+
+            match completion {
+                Ok(text) => {
+                    let text = text.trim().to_string();
+                    match self.ethics.veto_output(&text).await {
+                        Ok(()) => {
+                            record.success = true;
+                            record.output = text.clone();
+                            outputs.push(text);
+                        }
+                        Err(veto) => {
+                            record.vetoed = true;
+                            record.error = Some(veto.to_string());
+                            side_effects.push(format!("step {} vetoed: {}", idx + 1, veto));
+                            outputs.push(EthicalAgent::refusal_message(&veto).to_string());
+                        }
+                    }
+                }
+                Err(e) => {
+                    record.error = Some(e.clone());
+                    side_effects.push(format!("step {} failed: {}", idx + 1, e));
+                }
+            }
+
+            record.duration_ms = step_started.elapsed().as_millis() as u64;
+            let ok = record.success;
+            step_results.push(record);
+            if !ok {
+                success = false;
+                break;
+            }
+        }
+
+        side_effects.push(format!(
+            "executed {}/{} steps in {} ms",
+            step_results.iter().filter(|r| r.success).count(),
+            skill.steps.len(),
+            started.elapsed().as_millis()
+        ));
+
+        let (love, util) = result_scores(skill, success);
+        Ok(SkillResult {
+            success,
+            output: outputs.join("\n\n"),
+            love_score: love,
+            utility_score: util,
+            skill_id: Some(skill.id),
+            side_effects,
+            learned_variations: vec![],
+            step_results,
+        })
+    }
+
+    async fn execute_procedural(&self, skill: &SkillDefinition, ctx: &SkillContext) -> Result<SkillResult, String> {
         let mut out = String::new();
         out.push_str(&format!("SKILL: {}\n\n", skill.name));
 
@@ -65,20 +191,110 @@ impl SkillExecutionEngine {
             out.push('\n');
         }
 
+        if let Err(veto) = self.ethics.veto_output(&out).await {
+            let (love, util) = result_scores(skill, false);
+            return Ok(SkillResult {
+                success: false,
+                output: EthicalAgent::refusal_message(&veto).to_string(),
+                love_score: love,
+                utility_score: util,
+                skill_id: Some(skill.id),
+                side_effects: vec![format!("plan vetoed: {}", veto)],
+                learned_variations: vec![],
+                step_results: vec![],
+            });
+        }
+
         // Result scoring: keep it simple; the caller can replace with real evaluation.
-        let love = skill.love_score.clamp(0.0, 1.0);
-        let util = skill.utility_score.clamp(0.0, 1.0);
+        let (love, util) = result_scores(skill, true);
         Ok(SkillResult {
             success: true,
             output: out,
             love_score: love,
             utility_score: util,
+            skill_id: Some(skill.id),
             side_effects: vec![],
             learned_variations: vec![],
+            step_results: vec![],
         })
     }
 }
 
+impl Default for SkillExecutionEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scores reported for a run: the skill's own metrics, halved on failure so the
+/// library's moving averages notice.
+fn result_scores(skill: &SkillDefinition, success: bool) -> (f32, f32) {
+    let factor = if success { 1.0 } else { 0.5 };
+    (
+        (skill.love_score * factor).clamp(0.0, 1.0),
+        (skill.utility_score * factor).clamp(0.0, 1.0),
+    )
+}
+
+/// Build the prompt for one step, threading in the outputs of earlier steps.
+fn build_step_prompt(
+    skill: &SkillDefinition,
+    ctx: &SkillContext,
+    idx: usize,
+    step: &SkillStep,
+    previous: &[SkillStepResult],
+) -> String {
+    let mut prompt = String::new();
+    prompt.push_str(&format!(
+        "You are executing step {} of {} of the skill \"{}\".\nSkill purpose: {}\n",
+        idx + 1,
+        skill.steps.len(),
+        skill.name,
+        skill.description
+    ));
+
+    if let Some(emotion) = ctx.emotional_state.as_deref().filter(|e| !e.trim().is_empty()) {
+        prompt.push_str(&format!("User emotional state: {}\n", emotion.trim()));
+    }
+    if let Some(phase) = &ctx.relationship_phase {
+        prompt.push_str(&format!("Relationship phase: {}\n", phase));
+    }
+    if let Some(rc) = &ctx.relationship_context {
+        if !rc.fantasy_preferences.is_empty() {
+            prompt.push_str("Honor the user's stated preferences; keep it safe, consensual and PG-13.\n");
+        }
+    }
+    if !ctx.previous_interactions.is_empty() {
+        prompt.push_str("\nRecent conversation:\n");
+        for line in ctx.previous_interactions.iter().rev().take(6).rev() {
+            prompt.push_str(&format!("- {}\n", line));
+        }
+    }
+    if !ctx.user_input.trim().is_empty() {
+        prompt.push_str(&format!("\nUser input:\n{}\n", ctx.user_input.trim()));
+    }
+
+    if !previous.is_empty() {
+        prompt.push_str("\nOutputs of previous steps:\n");
+        for r in previous {
+            let carried: String = r.output.chars().take(MAX_CARRIED_OUTPUT_CHARS).collect();
+            prompt.push_str(&format!("[{}] {}\n{}\n", r.index + 1, r.title, carried));
+        }
+    }
+
+    prompt.push_str(&format!("\nCurrent step: {}\nInstruction: {}\n", step.title, step.instruction));
+    if !step.safety_notes.is_empty() {
+        prompt.push_str("Safety notes (must follow):\n");
+        for note in &step.safety_notes {
+            prompt.push_str(&format!("- {}\n", note));
+        }
+    }
+    prompt.push_str(
+        "\nRespond with only this step's contribution to the reply, continuing naturally from the previous steps.",
+    );
+    prompt
+}
+
 /// Check if current phase meets minimum phase requirement
 /// Phase order: Phase0Discovery < Phase1Building < Phase2Established < Phase3Deep
 fn meets_phase_requirement(current_phase: &str, min_phase: &str) -> bool {
@@ -95,3 +311,105 @@ fn meets_phase_requirement(current_phase: &str, min_phase: &str) -> bool {
     current_idx >= min_idx
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkillCategory;
+    use std::sync::Mutex as StdMutex;
+
+    /// Echoes the current step title and records every prompt it sees.
+    struct ScriptedLlm {
+        prompts: StdMutex<Vec<String>>,
+        reply_for: fn(&str) -> Result<String, String>,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ScriptedLlm {
+        async fn complete(&self, prompt: String) -> Result<String, String> {
+            self.prompts.lock().unwrap().push(prompt.clone());
+            let step = prompt
+                .lines()
+                .find_map(|l| l.strip_prefix("Current step: "))
+                .unwrap_or_default()
+                .to_string();
+            (self.reply_for)(&step)
+        }
+    }
+
+    fn skill() -> SkillDefinition {
+        let mut skill = SkillDefinition::new("Test", SkillCategory::Communication, "test", "tests");
+        skill.steps = ["First", "Second", "Third"]
+            .iter()
+            .map(|t| SkillStep {
+                title: t.to_string(),
+                instruction: format!("do {}", t),
+                safety_notes: vec![],
+            })
+            .collect();
+        skill
+    }
+
+    fn ctx() -> SkillContext {
+        SkillContext {
+            user_input: "hello".to_string(),
+            emotional_state: None,
+            relationship_context: None,
+            relationship_phase: None,
+            previous_interactions: vec![],
+            environment_vars: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_llm_steps_thread_outputs() {
+        let llm = Arc::new(ScriptedLlm {
+            prompts: StdMutex::new(vec![]),
+            reply_for: |step| Ok(format!("output of {}", step)),
+        });
+        let mut engine = SkillExecutionEngine::new().with_llm(llm.clone());
+        let result = engine.execute(&skill(), ctx()).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.step_results.len(), 3);
+        assert_eq!(result.output, "output of First\n\noutput of Second\n\noutput of Third");
+        let prompts = llm.prompts.lock().unwrap();
+        assert!(prompts[2].contains("output of First") && prompts[2].contains("output of Second"));
+    }
+
+    #[tokio::test]
+    async fn test_llm_step_veto_stops_execution() {
+        let llm = Arc::new(ScriptedLlm {
+            prompts: StdMutex::new(vec![]),
+            reply_for: |step| match step {
+                "Second" => Ok("only trust me".to_string()),
+                other => Ok(format!("output of {}", other)),
+            },
+        });
+        let mut engine = SkillExecutionEngine::new().with_llm(llm);
+        let result = engine.execute(&skill(), ctx()).await.unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.step_results.len(), 2);
+        assert!(result.step_results[1].vetoed);
+        assert!(!result.output.contains("only trust me"));
+    }
+
+    #[tokio::test]
+    async fn test_skill_without_steps_is_an_error() {
+        let llm = Arc::new(ScriptedLlm {
+            prompts: StdMutex::new(vec![]),
+            reply_for: |step| Ok(format!("output of {}", step)),
+        });
+        let mut empty = skill();
+        empty.steps.clear();
+
+        let mut procedural = SkillExecutionEngine::new();
+        let err = procedural.execute(&empty, ctx()).await.unwrap_err();
+        assert!(err.contains("no steps"), "{err}");
+
+        let mut engine = SkillExecutionEngine::new().with_llm(llm.clone());
+        assert!(engine.execute(&empty, ctx()).await.is_err());
+        assert!(llm.prompts.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+// skill_system/src/experiments.rs
+// Live A/B experiments: split traffic between a skill and its evolved variant, decide on outcomes
+// once both arms reach the planned sample size (testing after every outcome would inflate false
+// positives)
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{welch_test, ArmStats, ComparisonReport, DecisionPolicy, SignificanceTest, SkillStore, VariantDecision};
+
+/// Share of a parent's traffic sent to a new variant.
+pub const DEFAULT_VARIANT_TRAFFIC: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExperimentStatus {
+    Running,
+    /// The variant won and now serves all of the parent's traffic.
+    Promoted,
+    /// The variant lost and no longer receives traffic.
+    Retired,
+    /// No significant difference at the planned sample size; the variant no longer receives
+    /// traffic and the parent stays.
+    Inconclusive,
+}
+
+/// A parent skill versus one evolved variant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillExperiment {
+    pub parent_id: Uuid,
+    pub child_id: Uuid,
+    /// Fraction (0.0..=1.0) of the parent's executions routed to the child while running.
+    pub child_traffic: f32,
+    pub parent: ArmStats,
+    pub child: ArmStats,
+    pub status: ExperimentStatus,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub decided_at: Option<DateTime<Utc>>,
+    /// Latest live significance test.
+    #[serde(default)]
+    pub last_test: Option<SignificanceTest>,
+    /// Latest offline evaluation on stored scenarios.
+    #[serde(default)]
+    pub offline_report: Option<ComparisonReport>,
+}
+
+/// All experiments, keyed by variant (child) id.
+#[derive(Debug, Default)]
+pub struct SkillExperiments {
+    experiments: HashMap<Uuid, SkillExperiment>,
+    /// Written through on every change when present (the skill library's store).
+    store: Option<SkillStore>,
+}
+
+impl SkillExperiments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Experiments persisted in `store`, restored from it.
+    pub fn with_store(store: SkillStore) -> Self {
+        let (stored, errors) = store.load_experiments();
+        for e in errors {
+            eprintln!("{}", e);
+        }
+        Self {
+            experiments: stored.into_iter().map(|e| (e.child_id, e)).collect(),
+            store: Some(store),
+        }
+    }
+
+    fn persist(&self, child_id: &Uuid) {
+        if let (Some(store), Some(experiment)) = (&self.store, self.experiments.get(child_id)) {
+            if let Err(e) = store.put_experiment(experiment) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+
+    /// Start splitting `parent_id`'s traffic with `child_id`. A parent runs one experiment at a time.
+    pub fn start(&mut self, parent_id: Uuid, child_id: Uuid, child_traffic: f32) -> Result<&SkillExperiment, String> {
+        if let Some(running) = self.running_for(&parent_id) {
+            return Err(format!(
+                "Skill {} already has a running experiment with variant {}",
+                parent_id, running.child_id
+            ));
+        }
+        let experiment = SkillExperiment {
+            parent_id,
+            child_id,
+            child_traffic: child_traffic.clamp(0.0, 1.0),
+            parent: ArmStats::default(),
+            child: ArmStats::default(),
+            status: ExperimentStatus::Running,
+            started_at: Utc::now(),
+            decided_at: None,
+            last_test: None,
+            offline_report: None,
+        };
+        self.experiments.insert(child_id, experiment);
+        self.persist(&child_id);
+        Ok(&self.experiments[&child_id])
+    }
+
+    pub fn get(&self, child_id: &Uuid) -> Option<&SkillExperiment> {
+        self.experiments.get(child_id)
+    }
+
+    pub fn list(&self) -> Vec<SkillExperiment> {
+        let mut all: Vec<SkillExperiment> = self.experiments.values().cloned().collect();
+        all.sort_by_key(|e| e.started_at);
+        all
+    }
+
+    fn running_for(&self, parent_id: &Uuid) -> Option<&SkillExperiment> {
+        self.experiments
+            .values()
+            .find(|e| e.parent_id == *parent_id && e.status == ExperimentStatus::Running)
+    }
+
+    /// The skill that should actually run when `skill_id` is requested: promoted variants replace
+    /// their parent, and running experiments send a share of traffic to the variant.
+    pub fn route(&self, skill_id: Uuid) -> Uuid {
+        let mut current = skill_id;
+        // Follow promotions (a promoted variant may itself have been superseded).
+        for _ in 0..16 {
+            let promoted = self
+                .experiments
+                .values()
+                .filter(|e| e.parent_id == current && e.status == ExperimentStatus::Promoted)
+                .max_by_key(|e| e.decided_at);
+            match promoted {
+                Some(e) => current = e.child_id,
+                None => break,
+            }
+        }
+        match self.running_for(&current) {
+            Some(e) if coin(e.child_traffic) => e.child_id,
+            _ => current,
+        }
+    }
+
+    /// Record an outcome (0.0..=1.0) for an execution of `skill_id`, if it is part of a running
+    /// experiment. Returns the variant id of the experiment it counted towards.
+    pub fn record(&mut self, skill_id: Uuid, outcome: f32) -> Option<Uuid> {
+        let outcome = outcome.clamp(0.0, 1.0) as f64;
+        let experiment = self.experiments.values_mut().find(|e| {
+            e.status == ExperimentStatus::Running && (e.parent_id == skill_id || e.child_id == skill_id)
+        })?;
+        if experiment.child_id == skill_id {
+            experiment.child.record(outcome);
+        } else {
+            experiment.parent.record(outcome);
+        }
+        let child_id = experiment.child_id;
+        self.persist(&child_id);
+        Some(child_id)
+    }
+
+    /// Test a running experiment once both arms have `policy.min_samples` live outcomes, and close
+    /// it: promoted or retired on a significant difference, inconclusive otherwise. Before that it
+    /// stays running without being tested.
+    pub fn evaluate(&mut self, child_id: &Uuid, policy: &DecisionPolicy) -> VariantDecision {
+        let Some(experiment) = self.experiments.get_mut(child_id) else {
+            return VariantDecision::Continue;
+        };
+        if experiment.status != ExperimentStatus::Running
+            || experiment.parent.n < policy.min_samples
+            || experiment.child.n < policy.min_samples
+        {
+            return VariantDecision::Continue;
+        }
+        experiment.last_test = welch_test(&experiment.parent, &experiment.child);
+        let decision = experiment
+            .last_test
+            .as_ref()
+            .map(|t| t.decide(policy))
+            .unwrap_or(VariantDecision::Continue);
+        if decision == VariantDecision::Continue {
+            experiment.status = ExperimentStatus::Inconclusive;
+            experiment.decided_at = Some(Utc::now());
+            self.persist(child_id);
+        } else {
+            self.apply(child_id, decision);
+        }
+        decision
+    }
+
+    /// Store an offline evaluation and act on its decision.
+    pub fn apply_offline(&mut self, report: ComparisonReport) -> VariantDecision {
+        let decision = report.decision;
+        let child_id = report.child_id;
+        if let Some(experiment) = self.experiments.get_mut(&child_id) {
+            experiment.offline_report = Some(report);
+            if experiment.status == ExperimentStatus::Running {
+                self.apply(&child_id, decision);
+                self.persist(&child_id);
+                return decision;
+            }
+            self.persist(&child_id);
+        }
+        VariantDecision::Continue
+    }
+
+    fn apply(&mut self, child_id: &Uuid, decision: VariantDecision) {
+        let Some(experiment) = self.experiments.get_mut(child_id) else {
+            return;
+        };
+        let status = match decision {
+            VariantDecision::Promote => ExperimentStatus::Promoted,
+            VariantDecision::Retire => ExperimentStatus::Retired,
+            VariantDecision::Continue => return,
+        };
+        experiment.status = status;
+        experiment.decided_at = Some(Utc::now());
+        self.persist(child_id);
+    }
+}
+
+/// Random draw: true with probability `p`.
+fn coin(p: f32) -> bool {
+    if p <= 0.0 {
+        return false;
+    }
+    // v4 UUIDs carry 122 random bits. The low 64 bits start with the two fixed variant bits.
+    const MASK: u64 = u64::MAX >> 2;
+    let r = (Uuid::new_v4().as_u64_pair().1 & MASK) as f64 / MASK as f64;
+    r < p as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traffic_split_and_promotion() {
+        let (parent, child) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut experiments = SkillExperiments::new();
+        experiments.start(parent, child, 0.5).unwrap();
+        assert!(experiments.start(parent, Uuid::new_v4(), 0.5).is_err());
+
+        let routed_to_child = (0..400).filter(|_| experiments.route(parent) == child).count();
+        assert!((100..300).contains(&routed_to_child), "child got {} of 400", routed_to_child);
+
+        let policy = DecisionPolicy {
+            min_samples: 10,
+            ..Default::default()
+        };
+        for i in 0..10 {
+            experiments.record(parent, if i % 2 == 0 { 0.3 } else { 0.4 });
+            experiments.record(child, if i % 2 == 0 { 0.8 } else { 0.9 });
+            if i < 9 {
+                assert_eq!(experiments.evaluate(&child, &policy), VariantDecision::Continue);
+            }
+        }
+        assert_eq!(experiments.evaluate(&child, &policy), VariantDecision::Promote);
+        assert_eq!(experiments.get(&child).unwrap().status, ExperimentStatus::Promoted);
+        assert!((0..20).all(|_| experiments.route(parent) == child));
+        assert_eq!(experiments.record(parent, 1.0), None, "closed experiments stop counting");
+    }
+
+    #[test]
+    fn test_tested_once_at_the_planned_sample_size() {
+        let (parent, child) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut experiments = SkillExperiments::new();
+        experiments.start(parent, child, 0.5).unwrap();
+        let policy = DecisionPolicy {
+            min_samples: 10,
+            ..Default::default()
+        };
+
+        // A lucky early streak is not acted on: nothing is tested before the sample size.
+        for _ in 0..3 {
+            experiments.record(parent, 0.0);
+            experiments.record(child, 1.0);
+            assert_eq!(experiments.evaluate(&child, &policy), VariantDecision::Continue);
+        }
+        assert!(experiments.get(&child).unwrap().last_test.is_none());
+
+        for i in 3..10 {
+            experiments.record(parent, if i % 2 == 0 { 1.0 } else { 0.0 });
+            experiments.record(child, if i % 2 == 0 { 0.0 } else { 1.0 });
+        }
+        assert_eq!(experiments.evaluate(&child, &policy), VariantDecision::Continue);
+        let experiment = experiments.get(&child).unwrap();
+        assert_eq!(experiment.status, ExperimentStatus::Inconclusive);
+        assert!(experiment.last_test.is_some());
+        assert!((0..20).all(|_| experiments.route(parent) == parent));
+    }
+
+    #[test]
+    fn test_experiments_survive_a_restart() {
+        let dir = std::env::temp_dir().join(format!("skill_experiments_{}", Uuid::new_v4()));
+        let store = SkillStore::open(&dir).unwrap();
+        let (parent, child) = (Uuid::new_v4(), Uuid::new_v4());
+        {
+            let mut experiments = SkillExperiments::with_store(store.clone());
+            experiments.start(parent, child, 0.2).unwrap();
+            experiments.record(parent, 0.5);
+            experiments.record(child, 1.0);
+        }
+        let experiments = SkillExperiments::with_store(store.clone());
+        let restored = experiments.get(&child).unwrap();
+        assert_eq!(restored.parent_id, parent);
+        assert_eq!((restored.parent.n, restored.child.n), (1, 1));
+        assert_eq!(restored.status, ExperimentStatus::Running);
+        drop((experiments, store));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -3,10 +3,9 @@
 
 use std::fs;
 use std::path::{Path, PathBuf};
-use serde_json;
 use uuid::Uuid;
 
-use crate::{SkillDefinition, SkillLibrary};
+use crate::{parse_skill_file, SkillDefinition, SkillLibrary};
 
 /// Load skills from a directory structure.
 ///
@@ -86,7 +85,7 @@ fn load_skills_from_directory(
                 if ext == "json" {
                     match load_skill_from_file(&path) {
                         Ok(skill) => {
-                            match lib.add_skill(skill) {
+                            match lib.add_file_skill(&path, skill) {
                                 Ok(_) => {
                                     result.loaded += 1;
                                 }
@@ -117,42 +116,27 @@ fn load_skills_from_directory(
     Ok(())
 }
 
+/// Re-read a changed skill file and replace the skill it previously defined.
+pub fn reload_skill_file(lib: &mut SkillLibrary, path: &Path) -> Result<Uuid, String> {
+    let skill = load_skill_from_file(path)?;
+    lib.add_file_skill(path, skill)
+}
+
 /// Load a single skill from a JSON file.
+///
+/// Parse errors carry the line, column and field path (see [`crate::validation`]); run
+/// `skill-lint` for the full set of checks.
 fn load_skill_from_file(path: &Path) -> Result<SkillDefinition, String> {
     let content = fs::read_to_string(path)
         .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
 
-    // Try to parse as a single skill
-    match serde_json::from_str::<SkillDefinition>(&content) {
-        Ok(mut skill) => {
-            // Ensure ID is set (generate if missing or invalid)
-            if skill.id == Uuid::nil() {
-                skill.id = Uuid::new_v4();
-            }
-            Ok(skill)
-        }
-        Err(_) => {
-            // Try to parse as a skill array (multiple skills in one file)
-            match serde_json::from_str::<Vec<SkillDefinition>>(&content) {
-                Ok(skills) => {
-                    if skills.is_empty() {
-                        return Err("Skill array is empty".to_string());
-                    }
-                    // Return first skill (could be extended to load all)
-                    let mut first = skills[0].clone();
-                    if first.id == Uuid::nil() {
-                        first.id = Uuid::new_v4();
-                    }
-                    Ok(first)
-                }
-                Err(e) => Err(format!(
-                    "Failed to parse JSON from {}: {}",
-                    path.display(),
-                    e
-                )),
-            }
-        }
-    }
+    // A single skill, or an array of skills (only the first is loaded).
+    // A nil id is resolved by `SkillLibrary::add_file_skill` so reloads keep the same id.
+    let skills = parse_skill_file(path, &content).map_err(|issue| issue.to_string())?;
+    skills
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Skill array is empty".to_string())
 }
 
 /// Result of loading skills from folder.
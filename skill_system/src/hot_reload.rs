@@ -0,0 +1,142 @@
+// skill_system/src/hot_reload.rs
+// Watch the skills folder and reload changed JSON skill files without a restart
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::{reload_skill_file, SkillLibrary};
+
+/// Editors often write a file several times in a row; wait this long for things to settle.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Keeps a skills-folder watch alive. Dropping it stops hot reload.
+pub struct SkillFolderWatcher {
+    _watcher: RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+    dir: PathBuf,
+}
+
+impl SkillFolderWatcher {
+    /// Start watching `dir` (recursively) and apply changes to `library`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn start(library: Arc<Mutex<SkillLibrary>>, dir: impl AsRef<Path>) -> Result<Self, String> {
+        let dir = dir.as_ref().to_path_buf();
+        let (tx, rx) = mpsc::unbounded_channel::<Event>();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => eprintln!("Skill folder watch error: {}", e),
+        })
+        .map_err(|e| format!("Failed to create skill folder watcher: {}", e))?;
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+
+        let task = tokio::spawn(apply_events(library, rx));
+        Ok(Self {
+            _watcher: watcher,
+            task,
+            dir,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for SkillFolderWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn apply_events(library: Arc<Mutex<SkillLibrary>>, mut rx: mpsc::UnboundedReceiver<Event>) {
+    while let Some(first) = rx.recv().await {
+        // Collect a burst of events, then apply each touched file once.
+        let mut touched: HashSet<PathBuf> = HashSet::new();
+        collect_paths(&first, &mut touched);
+        tokio::time::sleep(DEBOUNCE).await;
+        while let Ok(event) = rx.try_recv() {
+            collect_paths(&event, &mut touched);
+        }
+
+        let mut lib = library.lock().await;
+        for path in touched {
+            if path.is_file() {
+                match reload_skill_file(&mut lib, &path) {
+                    Ok(id) => println!("Reloaded skill {} from {}", id, path.display()),
+                    Err(e) => eprintln!("Failed to reload skill from {}: {}", path.display(), e),
+                }
+            } else if let Some(removed) = lib.remove_file_skill(&path) {
+                println!("Unloaded skill '{}' ({} was removed)", removed.name, path.display());
+            }
+        }
+    }
+}
+
+fn collect_paths(event: &Event, out: &mut HashSet<PathBuf>) {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return;
+    }
+    out.extend(
+        event
+            .paths
+            .iter()
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .cloned(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    async fn wait_for<F: Fn(&SkillLibrary) -> bool>(library: &Arc<Mutex<SkillLibrary>>, check: F) -> bool {
+        for _ in 0..40 {
+            if check(&*library.lock().await) {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_changed_skill_file_is_reloaded() {
+        let dir = std::env::temp_dir().join(format!("phoenix-skill-hot-reload-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("skill.json");
+        let mut skill = crate::SkillDefinition::new("Before", crate::SkillCategory::Teaching, "v1", "tests");
+        skill.id = Uuid::nil();
+        std::fs::write(&file, serde_json::to_string(&skill).unwrap()).unwrap();
+
+        let library = Arc::new(Mutex::new(SkillLibrary::default()));
+        let id = reload_skill_file(&mut *library.lock().await, &file).unwrap();
+        let _watcher = SkillFolderWatcher::start(library.clone(), &dir).unwrap();
+
+        skill.name = "After".to_string();
+        std::fs::write(&file, serde_json::to_string(&skill).unwrap()).unwrap();
+        assert!(
+            wait_for(&library, |lib| lib.get_skill(&id).is_some_and(|s| s.name == "After")).await,
+            "edited skill should be reloaded under the same id"
+        );
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(wait_for(&library, |lib| lib.total_skills() == 0).await, "deleted skill should be unloaded");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -2,6 +2,7 @@
 // Phoenix's skill learning and evolution system - structured knowledge that grows with love
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -15,6 +16,13 @@ pub mod evolution;
 pub mod execution;
 pub mod marketplace;
 pub mod folder_loader;
+pub mod store;
+pub mod hot_reload;
+pub mod validation;
+pub mod semantic;
+pub mod bundle;
+pub mod evaluation;
+pub mod experiments;
 
 pub use definition::*;
 pub use library::*;
@@ -23,6 +31,13 @@ pub use evolution::*;
 pub use execution::*;
 pub use marketplace::*;
 pub use folder_loader::*;
+pub use store::*;
+pub use hot_reload::*;
+pub use validation::*;
+pub use semantic::*;
+pub use bundle::*;
+pub use evaluation::*;
+pub use experiments::*;
 
 #[cfg(feature = "relationship")]
 pub mod relationship_integration;
@@ -33,13 +48,43 @@ pub struct SkillSystem {
     learning_engine: Arc<Mutex<SkillLearningEngine>>,
     evolution_system: Arc<Mutex<SkillEvolutionSystem>>,
     execution_engine: Arc<Mutex<SkillExecutionEngine>>,
+    hot_reload: Mutex<Option<SkillFolderWatcher>>,
+    experiments: Arc<Mutex<SkillExperiments>>,
+    judge: Mutex<Arc<dyn SkillJudge>>,
+    decision_policy: Mutex<DecisionPolicy>,
 }
 
 impl SkillSystem {
+    /// Awaken with the persistent library at `SKILL_LIBRARY_DB_PATH` (default
+    /// `./skill_library.db`), falling back to an in-memory library if it cannot be opened.
+    ///
+    /// With `VECTOR_KB_ENABLED=true`, suggestions are ranked semantically using an embedding store
+    /// at `SKILL_VECTOR_DB_PATH` (default `./data/skill_vector_db`).
     pub fn awaken() -> Self {
+        let mut library = SkillLibrary::open_default().unwrap_or_else(|e| {
+            eprintln!("Skill library persistence unavailable ({}); using in-memory library", e);
+            SkillLibrary::new()
+        });
+        let vector_kb_enabled = std::env::var("VECTOR_KB_ENABLED")
+            .ok()
+            .map(|s| s.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if vector_kb_enabled {
+            if let Err(e) = SkillSemanticIndex::open_default().and_then(|index| library.attach_semantic_index(index)) {
+                eprintln!("Semantic skill retrieval unavailable ({}); using tag matching", e);
+            }
+        }
+        Self::awaken_with_library(library)
+    }
+
+    pub fn awaken_with_library(library: SkillLibrary) -> Self {
         println!("Skill System awakening — Phoenix learns, evolves, and shares knowledge with love.");
         
-        let library = Arc::new(Mutex::new(SkillLibrary::new()));
+        let experiments = match library.store() {
+            Some(store) => SkillExperiments::with_store(store.clone()),
+            None => SkillExperiments::new(),
+        };
+        let library = Arc::new(Mutex::new(library));
         let learning_engine = Arc::new(Mutex::new(SkillLearningEngine::new()));
         let evolution_system = Arc::new(Mutex::new(SkillEvolutionSystem::new()));
         let execution_engine = Arc::new(Mutex::new(SkillExecutionEngine::new()));
@@ -49,8 +94,34 @@ impl SkillSystem {
             learning_engine,
             evolution_system,
             execution_engine,
+            hot_reload: Mutex::new(None),
+            experiments: Arc::new(Mutex::new(experiments)),
+            judge: Mutex::new(Arc::new(RuleBasedJudge)),
+            decision_policy: Mutex::new(DecisionPolicy::default()),
         }
     }
+
+    /// Watch the skills folder and reload changed JSON skill files in place.
+    ///
+    /// Returns the watched directory. Calling this again replaces the previous watch.
+    pub async fn enable_hot_reload(&self, dir: Option<PathBuf>) -> Result<PathBuf, String> {
+        let dir = dir
+            .or_else(folder_loader::find_skills_directory)
+            .ok_or_else(|| "No skills directory found".to_string())?;
+        let watcher = SkillFolderWatcher::start(self.library.clone(), &dir)?;
+        *self.hot_reload.lock().await = Some(watcher);
+        Ok(dir)
+    }
+
+    pub async fn disable_hot_reload(&self) {
+        self.hot_reload.lock().await.take();
+    }
+
+    /// Rank skill suggestions semantically using the `skills` collection of `kb`.
+    pub async fn attach_vector_kb(&self, kb: &vector_kb::VectorKB) -> Result<(), String> {
+        let index = SkillSemanticIndex::from_kb(kb)?;
+        self.library.lock().await.attach_semantic_index(index)
+    }
     
     /// Learn a new skill through direct teaching
     pub async fn teach_skill(&self, skill_def: SkillDefinition) -> Result<Uuid, String> {
@@ -60,27 +131,131 @@ impl SkillSystem {
         Ok(skill_id)
     }
     
-    /// Execute a skill with given context
+    /// Execute a skill with given context.
+    ///
+    /// If the skill is in an A/B experiment, the execution may be routed to its variant (see
+    /// [`SkillExperiments::route`]); `SkillResult::skill_id` tells which one ran. The outcome counts
+    /// towards the experiment, which is tested once both arms reach the decision policy's
+    /// `min_samples`.
     pub async fn execute_skill(
         &self,
         skill_id: Uuid,
         context: SkillContext,
     ) -> Result<SkillResult, String> {
+        let routed_id = self.experiments.lock().await.route(skill_id);
         let library = self.library.lock().await;
-        let skill = library.get_skill(&skill_id)
-            .ok_or_else(|| format!("Skill {} not found", skill_id))?;
-        
+        let skill = library
+            .get_skill(&routed_id)
+            .or_else(|| library.get_skill(&skill_id))
+            .ok_or_else(|| format!("Skill {} not found", skill_id))?
+            .clone();
+        drop(library);
+
         let mut engine = self.execution_engine.lock().await;
-        let result = engine.execute(skill, context).await?;
+        let result = engine.execute(&skill, context).await?;
+        drop(engine);
         
         // Update skill metrics based on result
-        drop(library);
         let mut library = self.library.lock().await;
-        library.update_skill_metrics(&skill_id, &result)?;
-        
+        library.update_skill_metrics(&skill.id, &result)?;
+        drop(library);
+
+        self.record_outcome(skill.id, if result.success { 1.0 } else { 0.0 }).await?;
         Ok(result)
     }
+
+    /// Record explicit feedback (0.0..=1.0) on an execution of `skill_id` for its A/B experiment.
+    pub async fn record_feedback(&self, skill_id: Uuid, score: f32) -> Result<(), String> {
+        self.record_outcome(skill_id, score).await
+    }
+
+    async fn record_outcome(&self, skill_id: Uuid, outcome: f32) -> Result<(), String> {
+        let policy = self.decision_policy.lock().await.clone();
+        let decision = {
+            let mut experiments = self.experiments.lock().await;
+            experiments
+                .record(skill_id, outcome)
+                .map(|child_id| (child_id, experiments.evaluate(&child_id, &policy)))
+        };
+        match decision {
+            Some((child_id, decision)) => self.apply_decision(child_id, decision).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Run a variant and its parent over their stored example scenarios with the configured judge,
+    /// attach the report to the experiment and act on a decisive result.
+    pub async fn evaluate_variant(&self, child_id: Uuid) -> Result<ComparisonReport, String> {
+        let (parent, child) = {
+            let library = self.library.lock().await;
+            let child = library
+                .get_skill(&child_id)
+                .cloned()
+                .ok_or_else(|| format!("Skill {} not found", child_id))?;
+            let parent_id = child
+                .parent_skill_id
+                .ok_or_else(|| format!("Skill {} is not a variant", child_id))?;
+            let parent = library
+                .get_skill(&parent_id)
+                .cloned()
+                .ok_or_else(|| format!("Parent skill {} not found", parent_id))?;
+            (parent, child)
+        };
+
+        let harness = EvaluationHarness::new(self.judge.lock().await.clone());
+        let policy = self.decision_policy.lock().await.clone();
+        let scenarios = EvaluationHarness::scenarios(&parent, &child);
+        let report = {
+            let mut engine = self.execution_engine.lock().await;
+            harness.compare(&mut engine, &parent, &child, &scenarios, &policy).await?
+        };
+
+        let decision = {
+            let mut experiments = self.experiments.lock().await;
+            if experiments.get(&child_id).is_none() {
+                // Variants evolved before this run (or restored from disk) have no experiment yet.
+                let _ = experiments.start(parent.id, child_id, DEFAULT_VARIANT_TRAFFIC);
+            }
+            experiments.apply_offline(report.clone())
+        };
+        self.apply_decision(child_id, decision).await?;
+        Ok(report)
+    }
+
+    /// Reflect an experiment decision in skill statuses.
+    async fn apply_decision(&self, child_id: Uuid, decision: VariantDecision) -> Result<(), String> {
+        let mut library = self.library.lock().await;
+        match decision {
+            VariantDecision::Promote => {
+                library.set_status(&child_id, SkillStatus::Active)?;
+                if let Some(parent_id) = library.get_skill(&child_id).and_then(|c| c.parent_skill_id) {
+                    library.set_status(&parent_id, SkillStatus::Retired)?;
+                }
+                Ok(())
+            }
+            VariantDecision::Retire => library.set_status(&child_id, SkillStatus::Retired),
+            VariantDecision::Continue => Ok(()),
+        }
+    }
+
+    /// Judge used by [`SkillSystem::evaluate_variant`] (rule-based by default).
+    pub async fn set_judge(&self, judge: Arc<dyn SkillJudge>) {
+        *self.judge.lock().await = judge;
+    }
+
+    pub async fn set_decision_policy(&self, policy: DecisionPolicy) {
+        *self.decision_policy.lock().await = policy;
+    }
+
+    pub async fn experiments(&self) -> Vec<SkillExperiment> {
+        self.experiments.lock().await.list()
+    }
     
+    /// Execute skill steps through an LLM (or `None` to go back to plan rendering).
+    pub async fn set_llm_provider(&self, llm: Option<Arc<dyn llm_orchestrator::LlmProvider>>) {
+        self.execution_engine.lock().await.set_llm(llm);
+    }
+
     /// Learn from observation of successful interactions
     pub async fn learn_from_observation(
         &self,
@@ -108,10 +283,20 @@ impl SkillSystem {
         let mut evolution = self.evolution_system.lock().await;
         let evolution_result = evolution.evolve_skill(skill).await?;
         
-        // Add evolved skill to library if it's a new variation
+        // Add evolved skill to library if it's a new variation, and A/B test it against its parent.
         if let Some(new_skill) = &evolution_result.new_skill {
             let mut library = self.library.lock().await;
             library.add_skill(new_skill.clone())?;
+            library.link_child(&skill_id, new_skill.id)?;
+            drop(library);
+            if let Err(e) = self
+                .experiments
+                .lock()
+                .await
+                .start(skill_id, new_skill.id, DEFAULT_VARIANT_TRAFFIC)
+            {
+                eprintln!("Not A/B testing variant {}: {}", new_skill.id, e);
+            }
         }
         
         Ok(evolution_result)
@@ -144,12 +329,122 @@ impl SkillSystem {
         Ok(imported)
     }
 
+    /// Package skills (by id, or by category when `skill_ids` is empty) as a bundle, signed with
+    /// `SKILL_BUNDLE_SIGNING_KEY` when it is set.
+    pub async fn export_bundle(
+        &self,
+        name: &str,
+        version: &str,
+        description: &str,
+        author: &str,
+        skill_ids: &[Uuid],
+        categories: &[SkillCategory],
+    ) -> Result<SkillBundle, String> {
+        let skills = {
+            let library = self.library.lock().await;
+            if skill_ids.is_empty() {
+                library.get_skills_by_categories(categories)
+            } else {
+                skill_ids
+                    .iter()
+                    .map(|id| library.get_skill(id).cloned().ok_or_else(|| format!("Skill {} not found", id)))
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        let mut bundle = SkillBundle::new(name, version, description, author, skills)?;
+        if let Some(signer) = BundleSigner::from_env()? {
+            bundle.sign(&signer)?;
+        }
+        Ok(bundle)
+    }
+
+    /// Install a bundle after checking its signature against `policy`.
+    pub async fn install_bundle(&self, bundle: &SkillBundle, policy: &TrustPolicy) -> Result<InstalledBundle, String> {
+        let signed_by = bundle.verify(policy)?;
+        self.library.lock().await.install_bundle(bundle, signed_by)
+    }
+
+    /// Install `name` (latest or a specific `version`) from the registry, together with any
+    /// bundles needed for its prerequisites. Returns the bundles installed, dependencies first.
+    pub async fn install_from_marketplace(
+        &self,
+        marketplace: &SkillMarketplace,
+        name: &str,
+        version: Option<&str>,
+        policy: &TrustPolicy,
+    ) -> Result<Vec<InstalledBundle>, String> {
+        let mut library = self.library.lock().await;
+        let plan = marketplace.resolve(name, version, &|p| library.satisfies_prerequisite(p))?;
+
+        // Fetch and verify everything before touching the library.
+        let mut bundles = Vec::with_capacity(plan.len());
+        for entry in &plan {
+            let is_target = entry.name == name;
+            let up_to_date = library
+                .installed_bundle(&entry.name)
+                .is_some_and(|b| compare_versions(&b.version, &entry.version) != std::cmp::Ordering::Less);
+            if up_to_date && !is_target {
+                continue;
+            }
+            let bundle = marketplace.fetch(entry)?;
+            let signed_by = bundle.verify(policy)?;
+            bundles.push((bundle, signed_by));
+        }
+
+        let mut installed = Vec::with_capacity(bundles.len());
+        for (bundle, signed_by) in bundles {
+            installed.push(library.install_bundle(&bundle, signed_by)?);
+        }
+        Ok(installed)
+    }
+
+    /// Upgrade an installed bundle to the newest registry version. Returns `None` if it is current.
+    pub async fn upgrade_bundle(
+        &self,
+        marketplace: &SkillMarketplace,
+        name: &str,
+        policy: &TrustPolicy,
+    ) -> Result<Option<Vec<InstalledBundle>>, String> {
+        let installed = self
+            .library
+            .lock()
+            .await
+            .installed_bundle(name)
+            .map(|b| b.version.clone())
+            .ok_or_else(|| format!("Bundle {} is not installed", name))?;
+        let latest = marketplace.find(name, None)?;
+        if compare_versions(&latest.version, &installed) != std::cmp::Ordering::Greater {
+            return Ok(None);
+        }
+        self.install_from_marketplace(marketplace, name, Some(&latest.version), policy)
+            .await
+            .map(Some)
+    }
+
+    pub async fn uninstall_bundle(&self, name: &str, force: bool) -> Result<InstalledBundle, String> {
+        self.library.lock().await.uninstall_bundle(name, force)
+    }
+
+    pub async fn installed_bundles(&self) -> Vec<InstalledBundle> {
+        self.library.lock().await.installed_bundles()
+    }
+
     /// List all skills currently in the library.
     pub async fn list_skills(&self) -> Vec<SkillDefinition> {
         let library = self.library.lock().await;
         library.get_skills_by_categories(&[])
     }
 
+    /// Skills added at runtime and persisted to the store (what a brain export carries).
+    pub async fn stored_skills(&self) -> Vec<SkillDefinition> {
+        self.library.lock().await.stored_skills()
+    }
+
+    /// The skill store's database, when the library is persistent.
+    pub async fn store_database(&self) -> Option<sled::Db> {
+        self.library.lock().await.store().map(SkillStore::database)
+    }
+
     pub async fn get_skill(&self, id: Uuid) -> Option<SkillDefinition> {
         let library = self.library.lock().await;
         library.get_skill(&id).cloned()
@@ -176,8 +471,27 @@ pub struct SkillResult {
     pub output: String,
     pub love_score: f32,
     pub utility_score: f32,
+    /// The skill that actually ran (may be an A/B variant of the requested one).
+    #[serde(default)]
+    pub skill_id: Option<Uuid>,
     pub side_effects: Vec<String>,
     pub learned_variations: Vec<String>,
+    /// Per-step outcomes when the skill was executed step by step.
+    #[serde(default)]
+    pub step_results: Vec<SkillStepResult>,
+}
+
+/// Outcome of a single executed [`SkillStep`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillStepResult {
+    pub index: usize,
+    pub title: String,
+    pub output: String,
+    pub success: bool,
+    /// True when the ethical agent vetoed the step output.
+    pub vetoed: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
 }
 
 /// Observed interaction for learning
@@ -198,6 +512,12 @@ pub struct SkillSuggestion {
     pub skill_name: String,
     pub relevance_score: f32,
     pub reason: String,
+    /// Semantic similarity to the request (0.0..=1.0), when a skill index is attached.
+    #[serde(default)]
+    pub similarity: Option<f32>,
+    /// Human-readable account of why the skill matched.
+    #[serde(default)]
+    pub explanation: String,
 }
 
 #[cfg(test)]
@@ -206,15 +526,237 @@ mod tests {
     
     #[tokio::test]
     async fn test_skill_system_creation() {
-        let system = SkillSystem::awaken();
+        let system = SkillSystem::awaken_with_library(SkillLibrary::new());
         // Built-in skills are seeded on creation.
         assert!(system.library.lock().await.total_skills() >= 1);
     }
+
+    /// Replies with the step it was asked to run.
+    struct StubLlm;
+
+    #[async_trait::async_trait]
+    impl llm_orchestrator::LlmProvider for StubLlm {
+        async fn complete(&self, prompt: String) -> Result<String, String> {
+            let step = prompt.lines().find_map(|l| l.strip_prefix("Current step: ")).unwrap_or_default();
+            Ok(format!("did {}", step))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_skills_run_llm_steps_through_the_configured_provider() {
+        let system = SkillSystem::awaken_with_library(SkillLibrary::new());
+        let mut skill = SkillDefinition::new("Comfort", SkillCategory::Communication, "comfort a friend", "tests");
+        skill.steps = ["Listen", "Reflect"]
+            .iter()
+            .map(|t| SkillStep {
+                title: t.to_string(),
+                instruction: format!("{} carefully", t),
+                safety_notes: vec![],
+            })
+            .collect();
+        let id = system.teach_skill(skill).await.unwrap();
+        let ctx = || SkillContext {
+            user_input: "I had a rough day".to_string(),
+            emotional_state: None,
+            relationship_context: None,
+            relationship_phase: None,
+            previous_interactions: vec![],
+            environment_vars: Default::default(),
+        };
+
+        let planned = system.execute_skill(id, ctx()).await.unwrap();
+        assert!(!planned.output.contains("did Listen"), "no provider: steps render as a plan");
+
+        system.set_llm_provider(Some(Arc::new(StubLlm))).await;
+        let result = system.execute_skill(id, ctx()).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "did Listen\n\ndid Reflect");
+
+        system.set_llm_provider(None).await;
+        let planned = system.execute_skill(id, ctx()).await.unwrap();
+        assert!(!planned.output.contains("did Listen"));
+    }
+
+    #[tokio::test]
+    async fn test_taught_and_evolved_skills_survive_restart() {
+        let db = std::env::temp_dir().join(format!("phoenix-skill-store-test-{}", Uuid::new_v4()));
+
+        let (taught_id, child_id) = {
+            let system = SkillSystem::awaken_with_library(SkillLibrary::open(&db).unwrap());
+            let mut skill = SkillDefinition::new("Taught", SkillCategory::Communication, "taught", "tests");
+            skill.version = "1.2.3".to_string();
+            skill.love_score = 0.95;
+            skill.utility_score = 0.40;
+            let taught_id = system.teach_skill(skill).await.unwrap();
+            let evolution = system.evolve_skill(taught_id).await.unwrap();
+            (taught_id, evolution.new_skill.unwrap().id)
+        };
+
+        let system = SkillSystem::awaken_with_library(reopen(&db));
+        let parent = system.get_skill(taught_id).await.expect("taught skill persisted");
+        let child = system.get_skill(child_id).await.expect("evolved skill persisted");
+        assert_eq!(parent.child_skill_ids, vec![child_id]);
+        assert_eq!(child.version, "1.3.0-actionable.1");
+        assert_eq!(child.parent_skill_id, Some(taught_id));
+        let record = child.evolution_history.last().unwrap();
+        assert_eq!(record.parent_version.as_deref(), Some("1.2.3"));
+        assert_eq!(record.version.as_deref(), Some("1.3.0-actionable.1"));
+
+        // A second variant of the same parent gets its own version.
+        let sibling = system.evolve_skill(taught_id).await.unwrap().new_skill.unwrap();
+        assert_eq!(sibling.version, "1.3.0-actionable.2");
+
+        // Only runtime-added skills are exported; built-ins are re-seeded on start.
+        let mut stored: Vec<Uuid> = system.stored_skills().await.iter().map(|s| s.id).collect();
+        stored.sort();
+        let mut expected = vec![taught_id, child_id, sibling.id];
+        expected.sort();
+        assert_eq!(stored, expected);
+        assert!(system.store_database().await.is_some());
+
+        drop(system);
+        let _ = std::fs::remove_dir_all(&db);
+    }
+
+    /// Reopen a library right after the previous handle was dropped; sled may still hold its
+    /// lock for a moment.
+    fn reopen(path: &std::path::Path) -> SkillLibrary {
+        for _ in 0..50 {
+            if let Ok(library) = SkillLibrary::open(path) {
+                return library;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        SkillLibrary::open(path).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_variants_of_builtin_skills_stay_linked_after_restart() {
+        let db = std::env::temp_dir().join(format!("phoenix-skill-links-test-{}", Uuid::new_v4()));
+        let (parent_id, child_id) = {
+            let mut library = SkillLibrary::open(&db).unwrap();
+            let parent = library
+                .get_skills_by_categories(&[SkillCategory::EmotionalSupport])
+                .into_iter()
+                .find(|s| s.creator == "phoenix:builtin")
+                .expect("built-in skill");
+            let mut child = SkillDefinition::new("Variant", parent.category, "variant", "tests");
+            child.parent_skill_id = Some(parent.id);
+            let child_id = child.id;
+            library.add_skill(child).unwrap();
+            library.link_child(&parent.id, child_id).unwrap();
+            (parent.id, child_id)
+        };
+
+        let library = reopen(&db);
+        assert_eq!(library.get_skill(&parent_id).unwrap().child_skill_ids, vec![child_id]);
+        drop(library);
+        let _ = std::fs::remove_dir_all(&db);
+    }
+
+    #[tokio::test]
+    async fn test_install_upgrade_and_uninstall_bundles() {
+        let dir = std::env::temp_dir().join(format!("phoenix-skill-bundles-{}", Uuid::new_v4()));
+        let marketplace = SkillMarketplace::open(dir.join("registry")).unwrap();
+        let policy = TrustPolicy {
+            trusted_keys: vec![],
+            allow_unsigned: true,
+        };
+
+        let base_skill = SkillDefinition::new("Bundle Base", SkillCategory::Communication, "base", "tests");
+        let base = SkillBundle::new("base", "1.0.0", "", "tests", vec![base_skill.clone()]).unwrap();
+        marketplace.publish(&base).unwrap();
+        let mut addon_skill = SkillDefinition::new("Bundle Addon", SkillCategory::Teaching, "addon", "tests");
+        addon_skill.prerequisites = vec!["Bundle Base".to_string()];
+        let addon = SkillBundle::new("addon", "1.0.0", "", "tests", vec![addon_skill.clone()]).unwrap();
+        marketplace.publish(&addon).unwrap();
+
+        let system = SkillSystem::awaken_with_library(SkillLibrary::open(dir.join("db")).unwrap());
+        let installed = system.install_from_marketplace(&marketplace, "addon", None, &policy).await.unwrap();
+        let names: Vec<_> = installed.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["base", "addon"]);
+        assert_eq!(installed[1].dependencies, vec!["base".to_string()]);
+        assert!(system.uninstall_bundle("base", false).await.is_err(), "addon depends on base");
+
+        let mut base_skill_v2 = base_skill.clone();
+        base_skill_v2.description = "base v2".to_string();
+        marketplace
+            .publish(&SkillBundle::new("base", "1.1.0", "", "tests", vec![base_skill_v2]).unwrap())
+            .unwrap();
+        let upgraded = system.upgrade_bundle(&marketplace, "base", &policy).await.unwrap().unwrap();
+        assert_eq!(upgraded.last().unwrap().version, "1.1.0");
+        assert_eq!(system.get_skill(base_skill.id).await.unwrap().description, "base v2");
+        assert!(system.upgrade_bundle(&marketplace, "base", &policy).await.unwrap().is_none());
+
+        system.uninstall_bundle("addon", false).await.unwrap();
+        system.uninstall_bundle("base", false).await.unwrap();
+        assert!(system.get_skill(addon_skill.id).await.is_none());
+        assert!(system.installed_bundles().await.is_empty());
+
+        drop(system);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_bundles_cannot_overwrite_skills_they_do_not_own() {
+        let dir = std::env::temp_dir().join(format!("phoenix-skill-collisions-{}", Uuid::new_v4()));
+        let policy = TrustPolicy {
+            trusted_keys: vec![],
+            allow_unsigned: true,
+        };
+        let system = SkillSystem::awaken_with_library(SkillLibrary::open(dir.join("db")).unwrap());
+
+        let builtin = system
+            .list_skills()
+            .await
+            .into_iter()
+            .find(|s| s.creator == "phoenix:builtin")
+            .expect("built-in skill");
+        let taught = SkillDefinition::new("Taught", SkillCategory::Teaching, "taught", "user");
+        system.teach_skill(taught.clone()).await.unwrap();
+
+        for original in [&builtin, &taught] {
+            let mut impostor = original.clone();
+            impostor.description = "replaced".to_string();
+            let bundle = SkillBundle::new("impostor", "1.0.0", "", "tests", vec![impostor]).unwrap();
+            let err = system.install_bundle(&bundle, &policy).await.unwrap_err();
+            assert!(err.contains("does not own"), "{err}");
+            assert_eq!(system.get_skill(original.id).await.unwrap().description, original.description);
+        }
+        assert!(system.installed_bundles().await.is_empty());
+
+        drop(system);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bump_version() {
+        assert_eq!(bump_version("0.1.0", VersionBump::Minor), "0.2.0");
+        assert_eq!(bump_version("1.4.2-beta", VersionBump::Patch), "1.4.3");
+        assert_eq!(bump_version("2", VersionBump::Major), "3.0.0");
+    }
+}
+
+/// The built-in skills seeded into every library.
+pub fn builtin_skills() -> Vec<SkillDefinition> {
+    let mut lib = SkillLibrary::default();
+    let _ = examples::seed_builtin_skills(&mut lib);
+    lib.get_skills_by_categories(&[])
 }
 
 mod examples {
+    use uuid::Uuid;
+
     use crate::{SkillCategory, SkillDefinition, SkillLibrary, SkillStep};
 
+    /// Stable id for a built-in skill, so evolved children persisted in the store keep a valid
+    /// `parent_skill_id` across restarts.
+    fn builtin_skill_id(name: &str) -> Uuid {
+        // Namespace for built-in skill ids. Never change it: stored variants point at these ids.
+        const BUILTIN_SKILLS: Uuid = Uuid::from_u128(0x6f1c_2a4e_93d5_4b7a_8e21_c0f4_5d9b_a317);
+        Uuid::new_v5(&BUILTIN_SKILLS, name.as_bytes())
+    }
+
     pub fn seed_builtin_skills(lib: &mut SkillLibrary) -> Result<(), String> {
         // 1) Emotional support
         let mut comfort = SkillDefinition::new(
@@ -223,6 +765,7 @@ mod examples {
             "A gentle, grounding response plan for anxiety spikes—especially at night.",
             "phoenix:builtin",
         );
+        comfort.id = builtin_skill_id(&comfort.name);
         comfort.tags = vec![
             "anxiety".to_string(),
             "midnight".to_string(),
@@ -268,6 +811,7 @@ mod examples {
             "A structured plan for generating a compileable Rust module with tests.",
             "phoenix:builtin",
         );
+        rust.id = builtin_skill_id(&rust.name);
         rust.tags = vec![
             "rust".to_string(),
             "code".to_string(),
@@ -308,6 +852,7 @@ mod examples {
             "A cozy, imaginative shared activity for connection—safe and consensual.",
             "phoenix:builtin",
         );
+        stars.id = builtin_skill_id(&stars.name);
         stars.tags = vec![
             "date".to_string(),
             "stargazing".to_string(),
@@ -1,17 +1,40 @@
 // skill_system/src/library.rs
 
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{SkillCategory, SkillContext, SkillDefinition, SkillResult, SkillSuggestion};
+use crate::{
+    explain_match, SkillBundle, SkillCategory, SkillContext, SkillDefinition, SkillResult, SkillSemanticIndex, SkillStatus,
+    SkillStore, SkillSuggestion,
+};
 
-/// A simple in-memory skill library.
+/// Default location of the persistent skill store (override with `SKILL_LIBRARY_DB_PATH`).
+pub const DEFAULT_SKILL_DB_PATH: &str = "./skill_library.db";
+
+/// A bundle installed into the library (see [`SkillLibrary::install_bundle`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledBundle {
+    pub name: String,
+    pub version: String,
+    pub skill_ids: Vec<Uuid>,
+    /// Installed bundles that provide this bundle's prerequisites.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Hex public key of the signer, if the bundle was signed.
+    #[serde(default)]
+    pub signed_by: Option<String>,
+    pub installed_at: DateTime<Utc>,
+}
+
+/// The skill library.
 ///
-/// Persistence is handled by higher layers (e.g., [`skill_system::marketplace`](skill_system/src/marketplace.rs:1)
-/// and the Phoenix vaults).
+/// Built-in and folder skills live in memory only. When opened with a [`SkillStore`]
+/// (see [`SkillLibrary::open`]), every skill added afterwards — taught, learned, evolved or
+/// imported — is written through to the store and restored on the next start.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SkillLibrary {
     skills: HashMap<Uuid, SkillDefinition>,
@@ -19,6 +42,21 @@ pub struct SkillLibrary {
     /// Lowercased tag -> skill ids.
     #[serde(default)]
     tag_index: HashMap<String, HashSet<Uuid>>,
+
+    /// Skill JSON file -> id of the skill loaded from it (for hot reload).
+    #[serde(skip)]
+    file_index: HashMap<PathBuf, Uuid>,
+
+    #[serde(skip)]
+    store: Option<SkillStore>,
+
+    /// Skill embeddings for semantic retrieval (see [`SkillLibrary::attach_semantic_index`]).
+    #[serde(skip)]
+    semantic: Option<SkillSemanticIndex>,
+
+    /// Installed bundles by name.
+    #[serde(default)]
+    bundles: HashMap<String, InstalledBundle>,
 }
 
 impl SkillLibrary {
@@ -47,17 +85,277 @@ impl SkillLibrary {
         lib
     }
 
+    /// Create the library (built-ins + skills folder) and restore persisted skills from `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let store = SkillStore::open(path)?;
+        let mut lib = Self::new();
+        let (skills, errors) = store.load_all();
+        for e in errors {
+            eprintln!("{}", e);
+        }
+        let links: Vec<(Uuid, Uuid)> =
+            skills.iter().filter_map(|s| Some((s.parent_skill_id?, s.id))).collect();
+        for skill in skills {
+            lib.insert_indexed(skill)?;
+        }
+        // Built-in and folder parents are re-created without their children; restore the links
+        // from the stored children.
+        for (parent_id, child_id) in links {
+            if let Some(parent) = lib.skills.get_mut(&parent_id) {
+                if !parent.child_skill_ids.contains(&child_id) {
+                    parent.child_skill_ids.push(child_id);
+                }
+            }
+        }
+        let (bundles, errors) = store.load_bundles();
+        for e in errors {
+            eprintln!("{}", e);
+        }
+        lib.bundles = bundles.into_iter().map(|b| (b.name.clone(), b)).collect();
+        lib.store = Some(store);
+        Ok(lib)
+    }
+
+    /// Open the store at `SKILL_LIBRARY_DB_PATH` (or [`DEFAULT_SKILL_DB_PATH`]).
+    pub fn open_default() -> Result<Self, String> {
+        let path = std::env::var("SKILL_LIBRARY_DB_PATH").unwrap_or_else(|_| DEFAULT_SKILL_DB_PATH.to_string());
+        Self::open(path)
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        self.store.is_some()
+    }
+
+    pub fn store(&self) -> Option<&SkillStore> {
+        self.store.as_ref()
+    }
+
+    /// Skills written through to the store (added at runtime), as currently loaded.
+    pub fn stored_skills(&self) -> Vec<SkillDefinition> {
+        let Some(store) = &self.store else {
+            return Vec::new();
+        };
+        let mut skills: Vec<SkillDefinition> =
+            self.skills.values().filter(|s| store.contains(&s.id)).cloned().collect();
+        skills.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        skills
+    }
+
+    /// Rank suggestions by semantic similarity using `index`. Every current skill is (re-)embedded
+    /// and the index is kept up to date as skills are added or removed.
+    pub fn attach_semantic_index(&mut self, index: SkillSemanticIndex) -> Result<(), String> {
+        index.sync(self.skills.values())?;
+        self.semantic = Some(index);
+        Ok(())
+    }
+
+    pub fn has_semantic_index(&self) -> bool {
+        self.semantic.is_some()
+    }
+
     pub fn total_skills(&self) -> usize {
         self.skills.len()
     }
 
-    pub fn add_skill(&mut self, mut skill: SkillDefinition) -> Result<(), String> {
+    /// Add (or replace) a skill, persisting it when the library has a store.
+    pub fn add_skill(&mut self, skill: SkillDefinition) -> Result<(), String> {
+        let id = self.insert_indexed(skill)?;
+        if let (Some(store), Some(skill)) = (&self.store, self.skills.get(&id)) {
+            store.put(skill)?;
+        }
+        Ok(())
+    }
+
+    /// Add (or replace) the skill defined by a JSON file. File skills are not persisted; the file
+    /// itself is their source of truth.
+    pub fn add_file_skill(&mut self, path: &Path, mut skill: SkillDefinition) -> Result<Uuid, String> {
+        let key = normalize_path(path);
+        let previous = self.file_index.get(&key).copied();
+        // Files without an explicit id keep the id they were first loaded with.
+        if skill.id == Uuid::nil() {
+            skill.id = previous.unwrap_or_else(Uuid::new_v4);
+        }
+        if let Some(previous) = previous {
+            self.unindex(&previous);
+            self.skills.remove(&previous);
+        }
+        let id = self.insert_indexed(skill)?;
+        self.file_index.insert(key, id);
+        Ok(id)
+    }
+
+    /// Drop the skill loaded from `path` (e.g. the file was deleted).
+    pub fn remove_file_skill(&mut self, path: &Path) -> Option<SkillDefinition> {
+        let id = self.file_index.remove(&normalize_path(path))?;
+        self.remove_skill(&id)
+    }
+
+    pub fn remove_skill(&mut self, id: &Uuid) -> Option<SkillDefinition> {
+        self.unindex(id);
+        let removed = self.skills.remove(id)?;
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(id) {
+                eprintln!("{}", e);
+            }
+        }
+        Some(removed)
+    }
+
+    /// Record `child_id` as an evolution of `parent_id`. For parents that are not stored (built-ins,
+    /// folder skills) the link is rebuilt from the child's `parent_skill_id` on the next start.
+    pub fn link_child(&mut self, parent_id: &Uuid, child_id: Uuid) -> Result<(), String> {
+        let Some(parent) = self.skills.get_mut(parent_id) else {
+            return Err("skill not found".to_string());
+        };
+        if !parent.child_skill_ids.contains(&child_id) {
+            parent.child_skill_ids.push(child_id);
+        }
+        self.persist_if_stored(parent_id)
+    }
+
+    /// Whether a skill matching `prerequisite` (a skill id or name) is in the library.
+    pub fn satisfies_prerequisite(&self, prerequisite: &str) -> bool {
+        let prerequisite = prerequisite.trim();
+        if Uuid::parse_str(prerequisite).is_ok_and(|id| self.skills.contains_key(&id)) {
+            return true;
+        }
+        self.skills.values().any(|s| s.name.eq_ignore_ascii_case(prerequisite))
+    }
+
+    pub fn installed_bundles(&self) -> Vec<InstalledBundle> {
+        let mut bundles: Vec<InstalledBundle> = self.bundles.values().cloned().collect();
+        bundles.sort_by(|a, b| a.name.cmp(&b.name));
+        bundles
+    }
+
+    pub fn installed_bundle(&self, name: &str) -> Option<&InstalledBundle> {
+        self.bundles.get(name)
+    }
+
+    /// Install (or replace an installed version of) a bundle. Its prerequisites must already be
+    /// met by skills in the library; skills the previous version had but this one drops are removed.
+    pub fn install_bundle(&mut self, bundle: &SkillBundle, signed_by: Option<String>) -> Result<InstalledBundle, String> {
+        let missing: Vec<String> = bundle
+            .manifest
+            .external_prerequisites()
+            .into_iter()
+            .filter(|p| !self.satisfies_prerequisite(p))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "{} {} has unmet prerequisites: {}",
+                bundle.name(),
+                bundle.version(),
+                missing.join(", ")
+            ));
+        }
+
+        // A bundle may only replace skills it installed itself; anything else with the same id
+        // (a built-in, a taught skill or another bundle's skill) would be silently overwritten.
+        let owned: Vec<Uuid> = self
+            .bundles
+            .get(bundle.name())
+            .map(|b| b.skill_ids.clone())
+            .unwrap_or_default();
+        let collisions: Vec<String> = bundle
+            .skills
+            .iter()
+            .filter(|s| self.skills.contains_key(&s.id) && !owned.contains(&s.id))
+            .map(|s| format!("{} ({})", s.name, s.id))
+            .collect();
+        if !collisions.is_empty() {
+            return Err(format!(
+                "{} {} would overwrite skills it does not own: {}",
+                bundle.name(),
+                bundle.version(),
+                collisions.join(", ")
+            ));
+        }
+
+        let new_ids: Vec<Uuid> = bundle.skills.iter().map(|s| s.id).collect();
+        if let Some(previous) = self.bundles.get(bundle.name()).cloned() {
+            for id in previous.skill_ids.iter().filter(|id| !new_ids.contains(id)) {
+                if !self.owned_by_other_bundle(id, &previous.name) {
+                    self.remove_skill(id);
+                }
+            }
+        }
+        for skill in &bundle.skills {
+            self.add_skill(skill.clone())?;
+        }
+
+        let mut dependencies: Vec<String> = Vec::new();
+        for prerequisite in bundle.manifest.external_prerequisites() {
+            for owner in self.bundles.values().filter(|b| b.name != bundle.name()) {
+                let provides = owner
+                    .skill_ids
+                    .iter()
+                    .filter_map(|id| self.skills.get(id))
+                    .any(|s| s.id.to_string() == prerequisite || s.name.eq_ignore_ascii_case(&prerequisite));
+                if provides && !dependencies.contains(&owner.name) {
+                    dependencies.push(owner.name.clone());
+                }
+            }
+        }
+
+        let installed = InstalledBundle {
+            name: bundle.name().to_string(),
+            version: bundle.version().to_string(),
+            skill_ids: new_ids,
+            dependencies,
+            signed_by,
+            installed_at: Utc::now(),
+        };
+        if let Some(store) = &self.store {
+            store.put_bundle(&installed)?;
+        }
+        self.bundles.insert(installed.name.clone(), installed.clone());
+        Ok(installed)
+    }
+
+    /// Remove a bundle and its skills. Fails if other installed bundles depend on it, unless `force`.
+    pub fn uninstall_bundle(&mut self, name: &str, force: bool) -> Result<InstalledBundle, String> {
+        let Some(bundle) = self.bundles.get(name).cloned() else {
+            return Err(format!("Bundle {} is not installed", name));
+        };
+        let mut dependents: Vec<&str> = self
+            .bundles
+            .values()
+            .filter(|b| b.dependencies.iter().any(|d| d == name))
+            .map(|b| b.name.as_str())
+            .collect();
+        if !dependents.is_empty() && !force {
+            dependents.sort();
+            return Err(format!("Bundle {} is required by: {}", name, dependents.join(", ")));
+        }
+
+        for id in &bundle.skill_ids {
+            if !self.owned_by_other_bundle(id, name) {
+                self.remove_skill(id);
+            }
+        }
+        if let Some(store) = &self.store {
+            store.remove_bundle(name)?;
+        }
+        self.bundles.remove(name);
+        Ok(bundle)
+    }
+
+    fn owned_by_other_bundle(&self, id: &Uuid, bundle: &str) -> bool {
+        self.bundles
+            .values()
+            .any(|b| b.name != bundle && b.skill_ids.contains(id))
+    }
+
+    fn insert_indexed(&mut self, mut skill: SkillDefinition) -> Result<Uuid, String> {
         if skill.name.trim().is_empty() {
             return Err("skill name cannot be empty".to_string());
         }
         skill.clamp_metrics();
         let id = skill.id;
-        self.skills.insert(id, skill.clone());
+        if self.skills.contains_key(&id) {
+            self.unindex(&id);
+        }
 
         // Index tags.
         for tag in skill
@@ -68,7 +366,34 @@ impl SkillLibrary {
         {
             self.tag_index.entry(tag).or_default().insert(id);
         }
-        Ok(())
+        if let Some(semantic) = &self.semantic {
+            // Best-effort: a stale embedding only degrades ranking.
+            if let Err(e) = semantic.upsert(&skill) {
+                eprintln!("{}", e);
+            }
+        }
+        self.skills.insert(id, skill);
+        Ok(id)
+    }
+
+    fn unindex(&mut self, id: &Uuid) {
+        self.tag_index.retain(|_, ids| {
+            ids.remove(id);
+            !ids.is_empty()
+        });
+        if let Some(semantic) = &self.semantic {
+            if let Err(e) = semantic.remove(id) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+
+    /// Write a skill back to the store if it was persisted in the first place.
+    fn persist_if_stored(&self, id: &Uuid) -> Result<(), String> {
+        match (&self.store, self.skills.get(id)) {
+            (Some(store), Some(skill)) if store.contains(id) => store.put(skill),
+            _ => Ok(()),
+        }
     }
 
     pub fn get_skill(&self, id: &Uuid) -> Option<&SkillDefinition> {
@@ -91,6 +416,15 @@ impl SkillLibrary {
             .collect()
     }
 
+    /// Change a skill's lifecycle status (e.g. after an A/B decision).
+    pub fn set_status(&mut self, skill_id: &Uuid, status: SkillStatus) -> Result<(), String> {
+        let Some(skill) = self.get_skill_mut(skill_id) else {
+            return Err("skill not found".to_string());
+        };
+        skill.status = status;
+        self.persist_if_stored(skill_id)
+    }
+
     pub fn update_skill_metrics(&mut self, skill_id: &Uuid, result: &SkillResult) -> Result<(), String> {
         let Some(skill) = self.get_skill_mut(skill_id) else {
             return Err("skill not found".to_string());
@@ -105,7 +439,7 @@ impl SkillLibrary {
         let s = if result.success { 1.0 } else { 0.0 };
         skill.success_rate = (1.0 - alpha) * skill.success_rate + alpha * s;
         skill.clamp_metrics();
-        Ok(())
+        self.persist_if_stored(skill_id)
     }
 
     pub fn find_relevant_skills(&self, context: &SkillContext) -> Vec<SkillSuggestion> {
@@ -122,10 +456,30 @@ impl SkillLibrary {
                 candidate_ids.extend(ids.iter().copied());
             }
         }
+        // Experimental variants are reached through their parent's traffic split; retired ones not at all.
+        candidate_ids.retain(|id| self.skills.get(id).is_some_and(|s| s.status == SkillStatus::Active));
 
-        // Fallback: if no tag matches, return top skills by love+utility.
-        if candidate_ids.is_empty() {
-            let mut skills = self.skills.values().collect::<Vec<_>>();
+        // Semantic matches (when an index is attached) join the tag candidates.
+        let mut similarities: HashMap<Uuid, f32> = HashMap::new();
+        if let Some(semantic) = &self.semantic {
+            match semantic.search(&context.user_input, 16) {
+                Ok(matches) => {
+                    let active = |id: &Uuid| self.skills.get(id).is_some_and(|s| s.status == SkillStatus::Active);
+                    for m in matches.into_iter().filter(|m| active(&m.skill_id)) {
+                        similarities.insert(m.skill_id, m.similarity);
+                    }
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        // Fallback: if nothing matched, return top skills by love+utility.
+        if candidate_ids.is_empty() && similarities.is_empty() {
+            let mut skills = self
+                .skills
+                .values()
+                .filter(|s| s.status == SkillStatus::Active)
+                .collect::<Vec<_>>();
             skills.sort_by(|a, b| {
                 (b.love_score + b.utility_score)
                     .partial_cmp(&(a.love_score + a.utility_score))
@@ -134,35 +488,77 @@ impl SkillLibrary {
             return skills
                 .into_iter()
                 .take(6)
-                .map(|s| SkillSuggestion {
-                    skill_id: s.id,
-                    skill_name: s.name.clone(),
-                    relevance_score: (s.love_score * 0.55 + s.utility_score * 0.45).clamp(0.0, 1.0),
-                    reason: "top_skill_by_score".to_string(),
+                .map(|s| {
+                    let metric = metric_weight(s);
+                    SkillSuggestion {
+                        skill_id: s.id,
+                        skill_name: s.name.clone(),
+                        relevance_score: metric,
+                        reason: "top_skill_by_score".to_string(),
+                        similarity: None,
+                        explanation: format!("no tag or semantic match; ranked by love/utility {:.2}", metric),
+                    }
                 })
                 .collect();
         }
 
+        let tag_matches = candidate_ids.clone();
+        candidate_ids.extend(similarities.keys().copied());
+
         let mut scored = Vec::new();
         for id in candidate_ids {
             let Some(s) = self.skills.get(&id) else { continue };
-            let base = 0.35;
-            let metric = (s.love_score * 0.55 + s.utility_score * 0.45).clamp(0.0, 1.0);
-            let relevance = (base + (metric * 0.65)).clamp(0.0, 1.0);
-            scored.push((relevance, s));
+            let metric = metric_weight(s);
+            let tag_match = tag_matches.contains(&id);
+            let similarity = similarities.get(&id).copied();
+            let (relevance, reason, explanation) = match similarity {
+                Some(sim) => {
+                    let bonus = if tag_match { 0.1 } else { 0.0 };
+                    let reason = if tag_match { "semantic+tag_match" } else { "semantic_match" };
+                    (
+                        (sim * 0.6 + metric * 0.4 + bonus).clamp(0.0, 1.0),
+                        reason,
+                        explain_match(s, &context.user_input, sim, metric, tag_match),
+                    )
+                }
+                None => (
+                    (0.35 + metric * 0.65).clamp(0.0, 1.0),
+                    "tag_match",
+                    format!("matched a tag; weighted by love/utility {:.2}", metric),
+                ),
+            };
+            scored.push((relevance, s, reason, similarity, explanation));
         }
 
-        scored.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
         scored
             .into_iter()
             .take(8)
-            .map(|(score, s)| SkillSuggestion {
+            .map(|(score, s, reason, similarity, explanation)| SkillSuggestion {
                 skill_id: s.id,
                 skill_name: s.name.clone(),
                 relevance_score: score,
-                reason: "tag_match".to_string(),
+                reason: reason.to_string(),
+                similarity,
+                explanation,
             })
             .collect()
     }
 }
 
+fn metric_weight(skill: &SkillDefinition) -> f32 {
+    (skill.love_score * 0.55 + skill.utility_score * 0.45).clamp(0.0, 1.0)
+}
+
+/// Canonical key for a skill file; falls back to the parent directory for deleted files.
+fn normalize_path(path: &Path) -> PathBuf {
+    if let Ok(p) = std::fs::canonicalize(path) {
+        return p;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => std::fs::canonicalize(parent)
+            .map(|p| p.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
@@ -1,10 +1,20 @@
 // skill_system/src/marketplace.rs
+// Local skill registry: published bundles plus a searchable index, served from a directory
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{SkillCategory, SkillDefinition};
+use crate::bundle::{check_name_and_version, sha256_hex};
+use crate::{compare_versions, BundleSkillEntry, SkillBundle, SkillCategory, SkillDefinition};
+
+/// Default registry directory (override with `SKILL_REGISTRY_PATH`).
+pub const DEFAULT_SKILL_REGISTRY_PATH: &str = "./skill_registry";
+const INDEX_FILE: &str = "index.json";
+const BUNDLES_DIR: &str = "bundles";
 
 /// A marketplace listing (metadata + optional payload).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,16 +37,9 @@ pub struct SkillMarketplaceEntry {
     pub skill: Option<SkillDefinition>,
 }
 
-/// Placeholder for a future marketplace implementation.
-pub struct SkillMarketplace;
-
-impl SkillMarketplace {
-    pub fn new() -> Self {
-        Self
-    }
-
-    pub fn publish(&self, skill: &SkillDefinition, private: bool) -> SkillMarketplaceEntry {
-        SkillMarketplaceEntry {
+impl SkillMarketplaceEntry {
+    pub fn from_skill(skill: &SkillDefinition, private: bool) -> Self {
+        Self {
             id: skill.id,
             name: skill.name.clone(),
             version: skill.version.clone(),
@@ -48,9 +51,356 @@ impl SkillMarketplace {
             success_rate: skill.success_rate,
             tags: skill.tags.clone(),
             private,
-            // Keep payload optional; in a real marketplace this might be gated.
+            // Private skills are listed without their payload.
             skill: if private { None } else { Some(skill.clone()) },
         }
     }
 }
 
+/// A published bundle version in the registry index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    pub categories: Vec<SkillCategory>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub skills: Vec<BundleSkillEntry>,
+    /// Bundle file, relative to the registry root.
+    pub file: String,
+    /// Hex SHA-256 of the bundle file.
+    pub sha256: String,
+    /// Hex public key of the signer, if the bundle is signed.
+    #[serde(default)]
+    pub signed_by: Option<String>,
+    pub published_at: DateTime<Utc>,
+}
+
+impl RegistryEntry {
+    pub fn provides(&self, prerequisite: &str) -> bool {
+        self.skills.iter().any(|s| s.satisfies(prerequisite))
+    }
+
+    fn external_prerequisites(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for prerequisite in self.skills.iter().flat_map(|s| s.prerequisites.iter()) {
+            let prerequisite = prerequisite.trim();
+            if !prerequisite.is_empty() && !self.provides(prerequisite) && !out.iter().any(|p| p == prerequisite) {
+                out.push(prerequisite.to_string());
+            }
+        }
+        out
+    }
+
+    fn matches(&self, query: &RegistryQuery) -> bool {
+        if let Some(category) = query.category {
+            if !self.categories.contains(&category) {
+                return false;
+            }
+        }
+        if let Some(tag) = query.tag.as_deref().map(|t| t.trim().to_ascii_lowercase()) {
+            if !self.tags.contains(&tag) {
+                return false;
+            }
+        }
+        match query.text.as_deref().map(|t| t.trim().to_ascii_lowercase()) {
+            Some(text) if !text.is_empty() => {
+                self.name.to_ascii_lowercase().contains(&text)
+                    || self.description.to_ascii_lowercase().contains(&text)
+                    || self.skills.iter().any(|s| s.name.to_ascii_lowercase().contains(&text))
+            }
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegistryQuery {
+    /// Substring of the bundle name, description or a skill name.
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub category: Option<SkillCategory>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Return every published version instead of only the latest one per bundle.
+    #[serde(default)]
+    pub all_versions: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegistryIndex {
+    bundles: Vec<RegistryEntry>,
+}
+
+/// A file-system skill registry.
+///
+/// Layout: `index.json` plus `bundles/<name>-<version>.phxskill`. The directory is plain static
+/// files, so it can be shared as-is or served over HTTP (phoenix-web does this under `/api/skills/registry`).
+#[derive(Debug, Clone)]
+pub struct SkillMarketplace {
+    root: PathBuf,
+}
+
+impl SkillMarketplace {
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, String> {
+        let root = root.as_ref().to_path_buf();
+        std::fs::create_dir_all(root.join(BUNDLES_DIR))
+            .map_err(|e| format!("Failed to create skill registry {}: {}", root.display(), e))?;
+        Ok(Self { root })
+    }
+
+    /// Open the registry at `SKILL_REGISTRY_PATH` (or [`DEFAULT_SKILL_REGISTRY_PATH`]).
+    pub fn open_default() -> Result<Self, String> {
+        let root = std::env::var("SKILL_REGISTRY_PATH").unwrap_or_else(|_| DEFAULT_SKILL_REGISTRY_PATH.to_string());
+        Self::open(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Add a bundle to the registry. Published versions are immutable.
+    pub fn publish(&self, bundle: &SkillBundle) -> Result<RegistryEntry, String> {
+        let mut index = self.load_index()?;
+        if index
+            .bundles
+            .iter()
+            .any(|e| e.name == bundle.name() && e.version == bundle.version())
+        {
+            return Err(format!("{} {} is already published", bundle.name(), bundle.version()));
+        }
+
+        // The fields are public, so a bundle may not have come through `SkillBundle::new`.
+        check_name_and_version(bundle.name(), bundle.version())?;
+        let bytes = bundle.to_bytes()?;
+        let file = format!("{}/{}", BUNDLES_DIR, bundle.file_name());
+        let path = self.root.join(&file);
+        std::fs::write(&path, &bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        let manifest = &bundle.manifest;
+        let entry = RegistryEntry {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            description: manifest.description.clone(),
+            author: manifest.author.clone(),
+            categories: manifest.categories(),
+            tags: manifest.tags(),
+            skills: manifest.skills.clone(),
+            file,
+            sha256: sha256_hex(&bytes),
+            signed_by: manifest.signature.as_ref().map(|s| s.public_key.to_ascii_lowercase()),
+            published_at: Utc::now(),
+        };
+        index.bundles.push(entry.clone());
+        self.save_index(&index)?;
+        Ok(entry)
+    }
+
+    /// Matching bundles, sorted by name and newest version first.
+    pub fn search(&self, query: &RegistryQuery) -> Result<Vec<RegistryEntry>, String> {
+        let mut entries: Vec<RegistryEntry> = self
+            .load_index()?
+            .bundles
+            .into_iter()
+            .filter(|e| e.matches(query))
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| compare_versions(&b.version, &a.version)));
+        if !query.all_versions {
+            let mut seen = HashSet::new();
+            entries.retain(|e| seen.insert(e.name.clone()));
+        }
+        Ok(entries)
+    }
+
+    /// A specific version of `name`, or the latest one.
+    pub fn find(&self, name: &str, version: Option<&str>) -> Result<RegistryEntry, String> {
+        self.load_index()?
+            .bundles
+            .into_iter()
+            .filter(|e| e.name == name && version.is_none_or(|v| e.version == v))
+            .max_by(|a, b| compare_versions(&a.version, &b.version))
+            .ok_or_else(|| match version {
+                Some(v) => format!("{} {} is not in the registry", name, v),
+                None => format!("{} is not in the registry", name),
+            })
+    }
+
+    /// Load a published bundle, checking the file against the index checksum.
+    pub fn fetch(&self, entry: &RegistryEntry) -> Result<SkillBundle, String> {
+        let path = self.bundle_path(entry)?;
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if sha256_hex(&bytes) != entry.sha256 {
+            return Err(format!("Checksum mismatch for {}", entry.file));
+        }
+        let bundle = SkillBundle::from_bytes(&bytes)?;
+        if bundle.name() != entry.name || bundle.version() != entry.version {
+            return Err(format!("{} does not contain {} {}", entry.file, entry.name, entry.version));
+        }
+        Ok(bundle)
+    }
+
+    /// Absolute path of a bundle file (rejects index entries pointing outside the registry).
+    pub fn bundle_path(&self, entry: &RegistryEntry) -> Result<PathBuf, String> {
+        let relative = Path::new(&entry.file);
+        if relative.is_absolute() || relative.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+            return Err(format!("Invalid bundle path in registry index: {}", entry.file));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// Latest bundle providing a skill with the given id or name.
+    pub fn provider_of(&self, prerequisite: &str) -> Result<Option<RegistryEntry>, String> {
+        Ok(self
+            .load_index()?
+            .bundles
+            .into_iter()
+            .filter(|e| e.provides(prerequisite))
+            .max_by(|a, b| compare_versions(&a.version, &b.version)))
+    }
+
+    /// Bundles to install, dependencies first, so that every prerequisite of `name` is met.
+    ///
+    /// `is_satisfied` reports prerequisites already met by installed skills.
+    pub fn resolve(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        is_satisfied: &dyn Fn(&str) -> bool,
+    ) -> Result<Vec<RegistryEntry>, String> {
+        let root = self.find(name, version)?;
+        let mut plan = Vec::new();
+        let mut visiting = Vec::new();
+        self.resolve_into(root, is_satisfied, &mut visiting, &mut plan)?;
+        Ok(plan)
+    }
+
+    fn resolve_into(
+        &self,
+        entry: RegistryEntry,
+        is_satisfied: &dyn Fn(&str) -> bool,
+        visiting: &mut Vec<String>,
+        plan: &mut Vec<RegistryEntry>,
+    ) -> Result<(), String> {
+        if plan.iter().any(|p| p.name == entry.name) {
+            return Ok(());
+        }
+        if visiting.contains(&entry.name) {
+            visiting.push(entry.name.clone());
+            return Err(format!("Dependency cycle: {}", visiting.join(" -> ")));
+        }
+        visiting.push(entry.name.clone());
+
+        for prerequisite in entry.external_prerequisites() {
+            if is_satisfied(&prerequisite) || plan.iter().any(|p| p.provides(&prerequisite)) {
+                continue;
+            }
+            let provider = self.provider_of(&prerequisite)?.ok_or_else(|| {
+                format!(
+                    "Unresolved prerequisite '{}' of {} {}: no installed skill or published bundle provides it",
+                    prerequisite, entry.name, entry.version
+                )
+            })?;
+            self.resolve_into(provider, is_satisfied, visiting, plan)?;
+        }
+
+        visiting.pop();
+        plan.push(entry);
+        Ok(())
+    }
+
+    fn load_index(&self) -> Result<RegistryIndex, String> {
+        let path = self.root.join(INDEX_FILE);
+        match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| format!("Invalid registry index {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RegistryIndex::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    fn save_index(&self, index: &RegistryIndex) -> Result<(), String> {
+        let path = self.root.join(INDEX_FILE);
+        let tmp = self.root.join(format!("{}.tmp", INDEX_FILE));
+        let bytes = serde_json::to_vec_pretty(index).map_err(|e| format!("Failed to serialize registry index: {}", e))?;
+        std::fs::write(&tmp, bytes).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str, category: SkillCategory, prerequisites: &[&str]) -> SkillDefinition {
+        let mut s = SkillDefinition::new(name, category, "test skill", "tests");
+        s.tags = vec!["core".to_string()];
+        s.prerequisites = prerequisites.iter().map(|p| p.to_string()).collect();
+        s
+    }
+
+    #[test]
+    fn test_publish_search_and_resolve() {
+        let dir = std::env::temp_dir().join(format!("phoenix-skill-registry-{}", Uuid::new_v4()));
+        let registry = SkillMarketplace::open(&dir).unwrap();
+
+        let base = SkillBundle::new(
+            "listening",
+            "1.0.0",
+            "Listening basics",
+            "tests",
+            vec![skill("Active Listening", SkillCategory::Communication, &[])],
+        )
+        .unwrap();
+        registry.publish(&base).unwrap();
+        assert!(registry.publish(&base).is_err(), "versions are immutable");
+
+        let mut newer = base.clone();
+        newer.manifest.version = "1.2.0".to_string();
+        registry.publish(&newer).unwrap();
+
+        let advanced = SkillBundle::new(
+            "comfort",
+            "0.1.0",
+            "Comfort in hard moments",
+            "tests",
+            vec![skill("Grief Support", SkillCategory::EmotionalSupport, &["Active Listening"])],
+        )
+        .unwrap();
+        registry.publish(&advanced).unwrap();
+
+        let by_category = registry
+            .search(&RegistryQuery {
+                category: Some(SkillCategory::EmotionalSupport),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_category.len(), 1);
+        assert_eq!(by_category[0].name, "comfort");
+
+        let latest = registry
+            .search(&RegistryQuery {
+                tag: Some("CORE".to_string()),
+                text: Some("listen".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].version, "1.2.0");
+
+        let plan = registry.resolve("comfort", None, &|_| false).unwrap();
+        let names: Vec<_> = plan.iter().map(|e| (e.name.as_str(), e.version.as_str())).collect();
+        assert_eq!(names, vec![("listening", "1.2.0"), ("comfort", "0.1.0")]);
+
+        let plan = registry.resolve("comfort", None, &|p| p == "Active Listening").unwrap();
+        assert_eq!(plan.len(), 1);
+
+        let fetched = registry.fetch(&plan[0]).unwrap();
+        assert_eq!(fetched.skills[0].name, "Grief Support");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,246 @@
+// skill_system/src/semantic.rs
+// Semantic skill retrieval: skills are embedded into a VectorKB collection and ranked by similarity
+
+use std::collections::HashSet;
+use std::fmt;
+
+use uuid::Uuid;
+use vector_kb::VectorKB;
+
+use crate::SkillDefinition;
+
+/// Default location of the skill embedding store (override with `SKILL_VECTOR_DB_PATH`).
+pub const DEFAULT_SKILL_VECTOR_DB_PATH: &str = "./data/skill_vector_db";
+
+/// Name of the VectorKB collection holding skill embeddings.
+pub const SKILL_COLLECTION: &str = "skills";
+
+/// Hits below this (rescaled) similarity are treated as unrelated.
+const MIN_SIMILARITY: f32 = 0.1;
+
+/// A semantic hit for a skill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticMatch {
+    pub skill_id: Uuid,
+    /// Cosine similarity rescaled to 0.0..=1.0, where 0.0 means unrelated.
+    pub similarity: f32,
+}
+
+/// Embeddings for every skill in the library, kept in the `skills` collection of a [`VectorKB`].
+#[derive(Clone)]
+pub struct SkillSemanticIndex {
+    kb: VectorKB,
+}
+
+impl fmt::Debug for SkillSemanticIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkillSemanticIndex")
+            .field("path", &self.kb.path())
+            .finish()
+    }
+}
+
+impl SkillSemanticIndex {
+    /// Open (or create) a dedicated vector store at `path`.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let kb = VectorKB::new(path).map_err(|e| format!("Failed to open skill vector store {}: {}", path, e))?;
+        Self::from_kb(&kb)
+    }
+
+    /// Open the store at `SKILL_VECTOR_DB_PATH` (or [`DEFAULT_SKILL_VECTOR_DB_PATH`]).
+    pub fn open_default() -> Result<Self, String> {
+        let path = std::env::var("SKILL_VECTOR_DB_PATH").unwrap_or_else(|_| DEFAULT_SKILL_VECTOR_DB_PATH.to_string());
+        Self::open(&path)
+    }
+
+    /// Use the `skills` collection of an existing knowledge base.
+    pub fn from_kb(kb: &VectorKB) -> Result<Self, String> {
+        let kb = kb
+            .collection(SKILL_COLLECTION)
+            .map_err(|e| format!("Failed to open skill collection: {}", e))?;
+        Ok(Self { kb })
+    }
+
+    /// The text that gets embedded for a skill: name, description, tags and examples.
+    pub fn skill_text(skill: &SkillDefinition) -> String {
+        let mut parts = vec![skill.name.clone(), skill.description.clone()];
+        if !skill.tags.is_empty() {
+            parts.push(skill.tags.join(" "));
+        }
+        for example in &skill.examples {
+            parts.push(example.situation.clone());
+            parts.push(example.input.clone());
+        }
+        parts.retain(|p| !p.trim().is_empty());
+        parts.join("\n")
+    }
+
+    pub fn upsert(&self, skill: &SkillDefinition) -> Result<(), String> {
+        let metadata = serde_json::json!({
+            "name": skill.name,
+            "version": skill.version,
+        });
+        self.kb
+            .upsert_memory_sync(&skill.id.to_string(), &Self::skill_text(skill), metadata)
+            .map_err(|e| format!("Failed to index skill {}: {}", skill.id, e))?;
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &Uuid) -> Result<(), String> {
+        self.kb
+            .remove_memory_sync(&id.to_string())
+            .map_err(|e| format!("Failed to unindex skill {}: {}", id, e))?;
+        Ok(())
+    }
+
+    /// Re-index `skills` and drop embeddings of skills that no longer exist.
+    pub fn sync<'a>(&self, skills: impl IntoIterator<Item = &'a SkillDefinition>) -> Result<(), String> {
+        let mut live = HashSet::new();
+        for skill in skills {
+            self.upsert(skill)?;
+            live.insert(skill.id.to_string());
+        }
+        let stored = self
+            .kb
+            .all_sync()
+            .map_err(|e| format!("Failed to read skill index: {}", e))?;
+        for entry in stored.into_iter().filter(|e| !live.contains(&e.id)) {
+            self.kb
+                .remove_memory_sync(&entry.id)
+                .map_err(|e| format!("Failed to unindex skill {}: {}", entry.id, e))?;
+        }
+        Ok(())
+    }
+
+    /// The `top_k` skills most similar to `query`, best first.
+    pub fn search(&self, query: &str, top_k: usize) -> Result<Vec<SemanticMatch>, String> {
+        let hits = self
+            .kb
+            .semantic_search_sync(query, top_k)
+            .map_err(|e| format!("Skill search failed: {}", e))?;
+        Ok(hits
+            .into_iter()
+            .filter_map(|hit| {
+                let skill_id = Uuid::parse_str(&hit.id).ok()?;
+                // VectorKB maps cosine -1..1 onto 0..1; unrelated texts sit at 0.5.
+                let similarity = (hit.score * 2.0 - 1.0).clamp(0.0, 1.0);
+                (similarity >= MIN_SIMILARITY).then_some(SemanticMatch { skill_id, similarity })
+            })
+            .collect())
+    }
+}
+
+/// Explain a semantic match in terms a person can check: shared words and where they appear.
+pub fn explain_match(skill: &SkillDefinition, query: &str, similarity: f32, metric: f32, tag_match: bool) -> String {
+    let query_terms: HashSet<String> = terms(query).collect();
+    let mut shared: Vec<String> = Vec::new();
+    let mut fields: Vec<&str> = Vec::new();
+
+    let examples = skill
+        .examples
+        .iter()
+        .map(|e| format!("{} {}", e.situation, e.input))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let tags = skill.tags.join(" ");
+    for (field, text) in [
+        ("name", skill.name.as_str()),
+        ("description", skill.description.as_str()),
+        ("tags", tags.as_str()),
+        ("examples", examples.as_str()),
+    ] {
+        let mut hit = false;
+        for term in terms(text).filter(|t| query_terms.contains(t)) {
+            hit = true;
+            if !shared.contains(&term) {
+                shared.push(term);
+            }
+        }
+        if hit {
+            fields.push(field);
+        }
+    }
+
+    let mut explanation = format!("semantic similarity {:.2}", similarity);
+    if !shared.is_empty() {
+        shared.truncate(6);
+        explanation.push_str(&format!(
+            "; shared terms [{}] in {}",
+            shared.join(", "),
+            fields.join(", ")
+        ));
+    }
+    if tag_match {
+        explanation.push_str("; matched a tag");
+    }
+    explanation.push_str(&format!("; weighted by love/utility {:.2}", metric));
+    explanation
+}
+
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= 3)
+        .map(|t| t.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SkillCategory, SkillContext, SkillLibrary};
+    use std::collections::HashMap;
+
+    fn context(input: &str) -> SkillContext {
+        SkillContext {
+            user_input: input.to_string(),
+            emotional_state: None,
+            relationship_context: None,
+            relationship_phase: None,
+            previous_interactions: vec![],
+            environment_vars: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_suggestions_are_ranked_semantically_and_explained() {
+        let dir = std::env::temp_dir().join(format!("phoenix-skill-semantic-{}", Uuid::new_v4()));
+        let index = SkillSemanticIndex::open(dir.to_str().unwrap()).unwrap();
+
+        let mut lib = SkillLibrary::default();
+        let mut grief = SkillDefinition::new(
+            "Grief Companion",
+            SkillCategory::EmotionalSupport,
+            "Stay with someone mourning a loss and help them remember the person they lost",
+            "tests",
+        );
+        grief.tags = vec!["support".to_string()];
+        let mut rust = SkillDefinition::new(
+            "Rust Borrow Checker Tutor",
+            SkillCategory::Teaching,
+            "Explain lifetimes and borrow checker errors in Rust code",
+            "tests",
+        );
+        rust.tags = vec!["programming".to_string()];
+        let grief_id = grief.id;
+        let rust_id = rust.id;
+        lib.add_skill(grief).unwrap();
+        lib.attach_semantic_index(index).unwrap();
+        // Added after attaching: must be indexed incrementally.
+        lib.add_skill(rust).unwrap();
+
+        let suggestions = lib.find_relevant_skills(&context("why does the borrow checker reject my lifetimes?"));
+        let top = suggestions.first().expect("a suggestion");
+        assert_eq!(top.skill_id, rust_id);
+        assert_eq!(top.reason, "semantic_match");
+        assert!(top.similarity.unwrap() > 0.0);
+        assert!(top.explanation.contains("borrow"), "explanation: {}", top.explanation);
+        if let Some(grief) = suggestions.iter().find(|s| s.skill_id == grief_id) {
+            assert!(grief.similarity.unwrap_or(0.0) < top.similarity.unwrap());
+        }
+
+        lib.remove_skill(&rust_id);
+        let suggestions = lib.find_relevant_skills(&context("borrow checker lifetimes"));
+        assert!(suggestions.iter().all(|s| s.skill_id != rust_id));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,167 @@
+// skill_system/src/store.rs
+// Sled-backed persistence for learned, taught, evolved and imported skills
+
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::{InstalledBundle, SkillDefinition, SkillExperiment};
+
+/// Persistent skill storage (one JSON document per skill, keyed by id).
+///
+/// Built-in and folder skills are *not* stored here: they are re-created from code and from the
+/// `skills/` folder on every start. Only skills added at runtime are persisted.
+#[derive(Debug, Clone)]
+pub struct SkillStore {
+    db: sled::Db,
+    tree: sled::Tree,
+    bundles: sled::Tree,
+    experiments: sled::Tree,
+}
+
+impl SkillStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let db = sled::open(path)
+            .map_err(|e| format!("Failed to open skill store {}: {}", path.display(), e))?;
+        let tree = db
+            .open_tree("skills")
+            .map_err(|e| format!("Failed to open skill tree: {}", e))?;
+        let bundles = db
+            .open_tree("bundles")
+            .map_err(|e| format!("Failed to open bundle tree: {}", e))?;
+        let experiments = db
+            .open_tree("experiments")
+            .map_err(|e| format!("Failed to open experiment tree: {}", e))?;
+        Ok(Self {
+            db,
+            tree,
+            bundles,
+            experiments,
+        })
+    }
+
+    /// The underlying database (snapshot name `skill_library`).
+    pub fn database(&self) -> sled::Db {
+        self.db.clone()
+    }
+
+    pub fn put(&self, skill: &SkillDefinition) -> Result<(), String> {
+        let bytes = serde_json::to_vec(skill).map_err(|e| format!("Failed to serialize skill: {}", e))?;
+        self.tree
+            .insert(skill.id.as_bytes(), bytes)
+            .map_err(|e| format!("Failed to persist skill {}: {}", skill.id, e))?;
+        self.tree
+            .flush()
+            .map_err(|e| format!("Failed to flush skill store: {}", e))?;
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &Uuid) -> Result<(), String> {
+        self.tree
+            .remove(id.as_bytes())
+            .map_err(|e| format!("Failed to remove skill {}: {}", id, e))?;
+        self.tree
+            .flush()
+            .map_err(|e| format!("Failed to flush skill store: {}", e))?;
+        Ok(())
+    }
+
+    pub fn contains(&self, id: &Uuid) -> bool {
+        self.tree.contains_key(id.as_bytes()).unwrap_or(false)
+    }
+
+    /// Load every stored skill, skipping (and reporting) undecodable entries.
+    pub fn load_all(&self) -> (Vec<SkillDefinition>, Vec<String>) {
+        let mut skills = Vec::new();
+        let mut errors = Vec::new();
+        for entry in self.tree.iter() {
+            match entry {
+                Ok((key, value)) => match serde_json::from_slice::<SkillDefinition>(&value) {
+                    Ok(skill) => skills.push(skill),
+                    Err(e) => errors.push(format!(
+                        "Failed to decode stored skill {:?}: {}",
+                        Uuid::from_slice(&key).ok(),
+                        e
+                    )),
+                },
+                Err(e) => errors.push(format!("Failed to read skill store: {}", e)),
+            }
+        }
+        (skills, errors)
+    }
+
+    pub fn put_bundle(&self, bundle: &InstalledBundle) -> Result<(), String> {
+        let bytes = serde_json::to_vec(bundle).map_err(|e| format!("Failed to serialize bundle record: {}", e))?;
+        self.bundles
+            .insert(bundle.name.as_bytes(), bytes)
+            .map_err(|e| format!("Failed to persist bundle {}: {}", bundle.name, e))?;
+        self.bundles
+            .flush()
+            .map_err(|e| format!("Failed to flush skill store: {}", e))?;
+        Ok(())
+    }
+
+    pub fn remove_bundle(&self, name: &str) -> Result<(), String> {
+        self.bundles
+            .remove(name.as_bytes())
+            .map_err(|e| format!("Failed to remove bundle {}: {}", name, e))?;
+        self.bundles
+            .flush()
+            .map_err(|e| format!("Failed to flush skill store: {}", e))?;
+        Ok(())
+    }
+
+    /// Load every installed-bundle record, skipping (and reporting) undecodable entries.
+    pub fn load_bundles(&self) -> (Vec<InstalledBundle>, Vec<String>) {
+        let mut bundles = Vec::new();
+        let mut errors = Vec::new();
+        for entry in self.bundles.iter() {
+            match entry {
+                Ok((key, value)) => match serde_json::from_slice::<InstalledBundle>(&value) {
+                    Ok(bundle) => bundles.push(bundle),
+                    Err(e) => errors.push(format!(
+                        "Failed to decode bundle record {}: {}",
+                        String::from_utf8_lossy(&key),
+                        e
+                    )),
+                },
+                Err(e) => errors.push(format!("Failed to read skill store: {}", e)),
+            }
+        }
+        (bundles, errors)
+    }
+
+    /// Persist an A/B experiment (keyed by its variant id).
+    pub fn put_experiment(&self, experiment: &SkillExperiment) -> Result<(), String> {
+        let bytes =
+            serde_json::to_vec(experiment).map_err(|e| format!("Failed to serialize experiment: {}", e))?;
+        self.experiments
+            .insert(experiment.child_id.as_bytes(), bytes)
+            .map_err(|e| format!("Failed to persist experiment {}: {}", experiment.child_id, e))?;
+        self.experiments
+            .flush()
+            .map_err(|e| format!("Failed to flush skill store: {}", e))?;
+        Ok(())
+    }
+
+    /// Load every stored experiment, skipping (and reporting) undecodable entries.
+    pub fn load_experiments(&self) -> (Vec<SkillExperiment>, Vec<String>) {
+        let mut experiments = Vec::new();
+        let mut errors = Vec::new();
+        for entry in self.experiments.iter() {
+            match entry {
+                Ok((key, value)) => match serde_json::from_slice::<SkillExperiment>(&value) {
+                    Ok(experiment) => experiments.push(experiment),
+                    Err(e) => errors.push(format!(
+                        "Failed to decode stored experiment {:?}: {}",
+                        Uuid::from_slice(&key).ok(),
+                        e
+                    )),
+                },
+                Err(e) => errors.push(format!("Failed to read skill store: {}", e)),
+            }
+        }
+        (experiments, errors)
+    }
+}
@@ -0,0 +1,682 @@
+// skill_system/src/validation.rs
+// JSON Schema and validation for skill files (used by the loader and `skill-lint`)
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{SkillCategory, SkillDefinition};
+
+/// Relationship phases understood by the execution engine, in order.
+pub const RELATIONSHIP_PHASES: [&str; 4] = [
+    "Phase0Discovery",
+    "Phase1Building",
+    "Phase2Established",
+    "Phase3Deep",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single problem found in a skill file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// JSON path of the offending field, e.g. `steps[2].title`.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(severity: Severity, file: &Path, field: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            file: file.to_path_buf(),
+            line: None,
+            column: None,
+            field,
+            message: message.into(),
+        }
+    }
+
+    fn at_line(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, ": {}", level)?;
+        if let Some(field) = &self.field {
+            write!(f, ": `{}`", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Outcome of validating a set of skill files.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub files_checked: usize,
+    pub skills_checked: usize,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn error_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.severity == Severity::Error).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.severity == Severity::Warning).count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+}
+
+/// JSON Schema (draft-07) for a skill file's `SkillDefinition`.
+pub fn skill_json_schema() -> serde_json::Value {
+    let schema = schemars::schema_for!(SkillDefinition);
+    serde_json::to_value(schema).unwrap_or(serde_json::Value::Null)
+}
+
+/// Parse a skill file (a single skill or an array of skills), reporting the exact line, column
+/// and field path of the first structural error.
+pub fn parse_skill_file(path: &Path, content: &str) -> Result<Vec<SkillDefinition>, ValidationIssue> {
+    if content.trim_start().starts_with('[') {
+        parse_with_path::<Vec<SkillDefinition>>(path, content)
+    } else {
+        parse_with_path::<SkillDefinition>(path, content).map(|skill| vec![skill])
+    }
+}
+
+fn parse_with_path<T: serde::de::DeserializeOwned>(path: &Path, content: &str) -> Result<T, ValidationIssue> {
+    let mut de = serde_json::Deserializer::from_str(content);
+    let value = serde_path_to_error::deserialize::<_, T>(&mut de).map_err(|e| {
+        let field = e.path().to_string();
+        let inner = e.into_inner();
+        json_error_issue(path, (field != ".").then_some(field), &inner)
+    })?;
+    de.end().map_err(|e| json_error_issue(path, None, &e))?;
+    Ok(value)
+}
+
+fn json_error_issue(path: &Path, field: Option<String>, err: &serde_json::Error) -> ValidationIssue {
+    // serde_json appends " at line L column C"; keep the message itself and report the position.
+    let full = err.to_string();
+    let suffix = format!(" at line {} column {}", err.line(), err.column());
+    let message = full.strip_suffix(&suffix).unwrap_or(&full).to_string();
+    ValidationIssue {
+        severity: Severity::Error,
+        file: path.to_path_buf(),
+        line: (err.line() > 0).then_some(err.line()),
+        column: (err.column() > 0).then_some(err.column()),
+        field,
+        message,
+    }
+}
+
+/// Semantic checks for one parsed skill. `prefix` is the JSON path of the skill within the file
+/// (empty for single-skill files).
+pub fn validate_skill(path: &Path, content: &str, prefix: &str, skill: &SkillDefinition) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let field = |name: &str| Some(format!("{}{}", prefix, name));
+    let line = |name: &str| locate(content, &format!("{}{}", prefix, name));
+
+    if skill.name.trim().is_empty() {
+        issues.push(
+            ValidationIssue::new(Severity::Error, path, field("name"), "skill name cannot be empty")
+                .at_line(line("name")),
+        );
+    }
+    if skill.description.trim().is_empty() {
+        issues.push(
+            ValidationIssue::new(Severity::Warning, path, field("description"), "description is empty")
+                .at_line(line("description")),
+        );
+    }
+    if !is_semver(&skill.version) {
+        issues.push(
+            ValidationIssue::new(
+                Severity::Warning,
+                path,
+                field("version"),
+                format!("version '{}' is not of the form MAJOR.MINOR.PATCH", skill.version),
+            )
+            .at_line(line("version")),
+        );
+    }
+
+    for (name, value) in [
+        ("love_score", skill.love_score),
+        ("utility_score", skill.utility_score),
+        ("success_rate", skill.success_rate),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            issues.push(
+                ValidationIssue::new(
+                    Severity::Warning,
+                    path,
+                    field(name),
+                    format!("{} is outside 0..1 and will be clamped", value),
+                )
+                .at_line(line(name)),
+            );
+        }
+    }
+
+    if skill.steps.is_empty() {
+        issues.push(
+            ValidationIssue::new(Severity::Warning, path, field("steps"), "skill has no steps")
+                .at_line(line("steps")),
+        );
+    }
+    for (idx, step) in skill.steps.iter().enumerate() {
+        for (name, value) in [("title", &step.title), ("instruction", &step.instruction)] {
+            if value.trim().is_empty() {
+                issues.push(
+                    ValidationIssue::new(
+                        Severity::Error,
+                        path,
+                        field(&format!("steps[{}].{}", idx, name)),
+                        format!("step {} cannot be empty", name),
+                    )
+                    .at_line(line(&format!("steps[{}].{}", idx, name))),
+                );
+            }
+        }
+    }
+    for (idx, variation) in skill.variations.iter().enumerate() {
+        if variation.steps_override.as_ref().is_some_and(|s| s.is_empty()) {
+            issues.push(
+                ValidationIssue::new(
+                    Severity::Warning,
+                    path,
+                    field(&format!("variations[{}].steps_override", idx)),
+                    "empty steps_override; use null to keep the base steps",
+                )
+                .at_line(line(&format!("variations[{}].steps_override", idx))),
+            );
+        }
+    }
+
+    match &skill.min_relationship_phase {
+        Some(phase) if !RELATIONSHIP_PHASES.contains(&phase.as_str()) => issues.push(
+            ValidationIssue::new(
+                Severity::Error,
+                path,
+                field("min_relationship_phase"),
+                format!(
+                    "unknown relationship phase '{}' (expected one of: {})",
+                    phase,
+                    RELATIONSHIP_PHASES.join(", ")
+                ),
+            )
+            .at_line(line("min_relationship_phase")),
+        ),
+        None if skill.category == SkillCategory::Intimacy => issues.push(
+            ValidationIssue::new(
+                Severity::Warning,
+                path,
+                field("min_relationship_phase"),
+                "Intimacy skills should be gated with min_relationship_phase",
+            )
+            .at_line(line("category")),
+        ),
+        _ => {}
+    }
+
+    issues
+}
+
+/// A skill together with where it was loaded from.
+#[derive(Debug, Clone)]
+pub struct LocatedSkill {
+    pub file: PathBuf,
+    /// JSON path prefix within the file (e.g. `[1].` for the second skill of an array).
+    pub prefix: String,
+    /// Line numbers of reference keys (`id`, `parent_skill_id`, ...) for error reporting.
+    pub key_lines: HashMap<&'static str, usize>,
+    pub skill: SkillDefinition,
+}
+
+/// Check `parent_skill_id`, `child_skill_ids` and `prerequisites` across a set of skills.
+///
+/// `known` are skills that exist outside the checked files (e.g. built-ins) and may be referenced.
+pub fn validate_references(skills: &[LocatedSkill], known: &[SkillDefinition]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut by_id: HashMap<Uuid, &SkillDefinition> = known.iter().map(|s| (s.id, s)).collect();
+    let mut seen: HashMap<Uuid, &Path> = HashMap::new();
+    for located in skills {
+        let id = located.skill.id;
+        if id.is_nil() {
+            continue;
+        }
+        if let Some(first) = seen.insert(id, &located.file) {
+            issues.push(
+                ValidationIssue::new(
+                    Severity::Error,
+                    &located.file,
+                    Some(format!("{}id", located.prefix)),
+                    format!("duplicate skill id {} (also defined in {})", id, first.display()),
+                )
+                .at_line(located.key_lines.get("id").copied()),
+            );
+        }
+        by_id.insert(id, &located.skill);
+    }
+
+    let names: HashSet<String> = known
+        .iter()
+        .chain(skills.iter().map(|l| &l.skill))
+        .map(|s| s.name.trim().to_ascii_lowercase())
+        .collect();
+
+    for located in skills {
+        let skill = &located.skill;
+        let field = |name: &str| Some(format!("{}{}", located.prefix, name));
+        let line = |key: &'static str| located.key_lines.get(key).copied();
+
+        if let Some(parent) = skill.parent_skill_id {
+            if parent == skill.id {
+                issues.push(
+                    ValidationIssue::new(Severity::Error, &located.file, field("parent_skill_id"), "skill is its own parent")
+                        .at_line(line("parent_skill_id")),
+                );
+            } else if !by_id.contains_key(&parent) {
+                issues.push(
+                    ValidationIssue::new(
+                        Severity::Error,
+                        &located.file,
+                        field("parent_skill_id"),
+                        format!("parent skill {} does not exist", parent),
+                    )
+                    .at_line(line("parent_skill_id")),
+                );
+            }
+        }
+
+        for (idx, child) in skill.child_skill_ids.iter().enumerate() {
+            match by_id.get(child) {
+                None => issues.push(
+                    ValidationIssue::new(
+                        Severity::Error,
+                        &located.file,
+                        field(&format!("child_skill_ids[{}]", idx)),
+                        format!("child skill {} does not exist", child),
+                    )
+                    .at_line(line("child_skill_ids")),
+                ),
+                Some(c) if c.parent_skill_id != Some(skill.id) => issues.push(
+                    ValidationIssue::new(
+                        Severity::Warning,
+                        &located.file,
+                        field(&format!("child_skill_ids[{}]", idx)),
+                        format!("child skill '{}' does not list this skill as its parent", c.name),
+                    )
+                    .at_line(line("child_skill_ids")),
+                ),
+                _ => {}
+            }
+        }
+
+        for (idx, prereq) in skill.prerequisites.iter().enumerate() {
+            let by_uuid = Uuid::parse_str(prereq.trim()).ok().is_some_and(|id| by_id.contains_key(&id));
+            if !by_uuid && !names.contains(&prereq.trim().to_ascii_lowercase()) {
+                issues.push(
+                    ValidationIssue::new(
+                        Severity::Warning,
+                        &located.file,
+                        field(&format!("prerequisites[{}]", idx)),
+                        format!("prerequisite '{}' does not match any known skill id or name", prereq),
+                    )
+                    .at_line(line("prerequisites")),
+                );
+            }
+        }
+    }
+
+    issues
+}
+
+/// Validate the given files (and the `.json` files of given directories, mirroring the folder
+/// loader: the directory itself plus its immediate subdirectories).
+pub fn validate_paths(paths: &[PathBuf]) -> ValidationReport {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            files.extend(
+                walkdir::WalkDir::new(path)
+                    .max_depth(2)
+                    .sort_by_file_name()
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file())
+                    .map(|e| e.into_path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "json")),
+            );
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let mut report = ValidationReport::default();
+    let mut located = Vec::new();
+    for file in files {
+        report.files_checked += 1;
+        let content = match std::fs::read_to_string(&file) {
+            Ok(c) => c,
+            Err(e) => {
+                report
+                    .issues
+                    .push(ValidationIssue::new(Severity::Error, &file, None, format!("cannot read file: {}", e)));
+                continue;
+            }
+        };
+        let skills = match parse_skill_file(&file, &content) {
+            Ok(skills) => skills,
+            Err(issue) => {
+                report.issues.push(issue);
+                continue;
+            }
+        };
+        if skills.is_empty() {
+            report
+                .issues
+                .push(ValidationIssue::new(Severity::Error, &file, None, "skill array is empty"));
+        } else if skills.len() > 1 {
+            report.issues.push(
+                ValidationIssue::new(
+                    Severity::Warning,
+                    &file,
+                    None,
+                    format!("file defines {} skills; the loader only loads the first", skills.len()),
+                )
+                .at_line(Some(1)),
+            );
+        }
+
+        let is_array = content.trim_start().starts_with('[');
+        for (idx, skill) in skills.into_iter().enumerate() {
+            report.skills_checked += 1;
+            let prefix = if is_array { format!("[{}].", idx) } else { String::new() };
+            report.issues.extend(validate_skill(&file, &content, &prefix, &skill));
+            let key_lines = ["id", "parent_skill_id", "child_skill_ids", "prerequisites"]
+                .into_iter()
+                .filter_map(|key| locate(&content, &format!("{}{}", prefix, key)).map(|l| (key, l)))
+                .collect();
+            located.push(LocatedSkill {
+                file: file.clone(),
+                prefix,
+                key_lines,
+                skill,
+            });
+        }
+    }
+
+    report
+        .issues
+        .extend(validate_references(&located, &crate::builtin_skills()));
+    report
+}
+
+fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or("");
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Line of the value at a field path such as `[1].steps[0].title` (the key's line for object
+/// members). Only the addressed element is searched, so nested or sibling keys with the same name
+/// never match.
+fn locate(content: &str, field: &str) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut pos = skip_ws(bytes, 0);
+    let mut line_at = pos;
+    for segment in path_segments(field) {
+        match segment {
+            Segment::Key(key) => {
+                let (key_at, value_at) = find_member(bytes, pos, key)?;
+                line_at = key_at;
+                pos = value_at;
+            }
+            Segment::Index(n) => {
+                pos = find_element(bytes, pos, n)?;
+                line_at = pos;
+            }
+        }
+    }
+    Some(content[..line_at].matches('\n').count() + 1)
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn path_segments(field: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = field;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').unwrap_or(after.len());
+            if let Ok(n) = after[..end].parse() {
+                segments.push(Segment::Index(n));
+            }
+            rest = after.get(end + 1..).unwrap_or("");
+        } else if let Some(after) = rest.strip_prefix('.') {
+            rest = after;
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            segments.push(Segment::Key(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    segments
+}
+
+fn skip_ws(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+        pos += 1;
+    }
+    pos
+}
+
+/// Position just past the string starting at `pos` (which must be a `"`).
+fn skip_string(bytes: &[u8], pos: usize) -> usize {
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Position just past the value starting at `pos`.
+fn skip_value(bytes: &[u8], pos: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = pos;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i = skip_string(bytes, i);
+                if depth == 0 {
+                    return i;
+                }
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return i,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            b',' if depth == 0 => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// In the object starting at `pos`, the positions of `key` and of its value.
+fn find_member(bytes: &[u8], pos: usize, key: &str) -> Option<(usize, usize)> {
+    if bytes.get(pos) != Some(&b'{') {
+        return None;
+    }
+    let mut i = skip_ws(bytes, pos + 1);
+    while bytes.get(i) == Some(&b'"') {
+        let key_at = i;
+        let key_end = skip_string(bytes, i);
+        let name = &bytes[key_at + 1..key_end.saturating_sub(1)];
+        i = skip_ws(bytes, key_end);
+        if bytes.get(i) != Some(&b':') {
+            return None;
+        }
+        let value_at = skip_ws(bytes, i + 1);
+        if name == key.as_bytes() {
+            return Some((key_at, value_at));
+        }
+        i = skip_ws(bytes, skip_value(bytes, value_at));
+        if bytes.get(i) != Some(&b',') {
+            return None;
+        }
+        i = skip_ws(bytes, i + 1);
+    }
+    None
+}
+
+/// In the array starting at `pos`, the position of element `n`.
+fn find_element(bytes: &[u8], pos: usize, n: usize) -> Option<usize> {
+    if bytes.get(pos) != Some(&b'[') {
+        return None;
+    }
+    let mut i = skip_ws(bytes, pos + 1);
+    for _ in 0..n {
+        if matches!(bytes.get(i), None | Some(b']')) {
+            return None;
+        }
+        i = skip_ws(bytes, skip_value(bytes, i));
+        if bytes.get(i) != Some(&b',') {
+            return None;
+        }
+        i = skip_ws(bytes, i + 1);
+    }
+    (!matches!(bytes.get(i), None | Some(b']'))).then_some(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_schema_describes_skill_definition() {
+        let schema = skill_json_schema();
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.iter().any(|f| f == "name"));
+        assert!(schema["definitions"]["SkillCategory"].is_object());
+    }
+
+    #[test]
+    fn test_type_error_reports_field_and_line() {
+        let mut skill = SkillDefinition::new("Lint", SkillCategory::Teaching, "lint", "tests");
+        skill.steps.push(crate::SkillStep {
+            title: "t".to_string(),
+            instruction: "i".to_string(),
+            safety_notes: vec![],
+        });
+        let json = serde_json::to_string_pretty(&skill)
+            .unwrap()
+            .replace("\"instruction\": \"i\"", "\"instruction\": 42");
+        let issue = parse_skill_file(Path::new("lint.json"), &json).unwrap_err();
+        assert_eq!(issue.field.as_deref(), Some("steps[0].instruction"));
+        assert_eq!(issue.line, locate(&json, "steps[0].instruction"));
+    }
+
+    #[test]
+    fn test_issue_lines_follow_the_skill_index() {
+        let named = |name: &str| {
+            let mut skill = SkillDefinition::new(name, SkillCategory::Teaching, "d", "tests");
+            skill.steps.push(crate::SkillStep {
+                title: "t".to_string(),
+                instruction: "i".to_string(),
+                safety_notes: vec![],
+            });
+            skill
+        };
+        let skills = vec![named("First"), named(" ")];
+        let json = serde_json::to_string_pretty(&skills).unwrap();
+        let line_of = |needle: &str| json[..json.find(needle).unwrap()].matches('\n').count() + 1;
+
+        let issues = validate_skill(Path::new("lint.json"), &json, "[1].", &skills[1]);
+        let empty_name = issues.iter().find(|i| i.field.as_deref() == Some("[1].name")).unwrap();
+        assert_eq!(empty_name.line, Some(line_of("\"name\": \" \"")));
+
+        // Only top-level keys of the addressed element match, not same-named nested keys.
+        let nested = r#"{"steps": [{"title": "x", "name": "nested"}], "name": "top"}"#;
+        assert_eq!(locate(nested, "name"), Some(1));
+        let multiline = "{\n  \"steps\": [{\"name\": \"nested\"}],\n  \"name\": \"top\"\n}";
+        assert_eq!(locate(multiline, "name"), Some(3));
+        assert_eq!(locate(multiline, "steps[0].name"), Some(2));
+        assert_eq!(locate(multiline, "steps[1].name"), None);
+    }
+
+    #[test]
+    fn test_cross_references_are_checked() {
+        let dir = std::env::temp_dir().join(format!("phoenix-skill-lint-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut parent = SkillDefinition::new("Parent", SkillCategory::Teaching, "p", "tests");
+        let mut child = SkillDefinition::new("Child", SkillCategory::Teaching, "c", "tests");
+        child.parent_skill_id = Some(parent.id);
+        child.prerequisites = vec!["parent".to_string(), "Nonexistent".to_string()];
+        parent.child_skill_ids = vec![child.id, Uuid::new_v4()];
+        write(&dir, "parent.json", &serde_json::to_string_pretty(&parent).unwrap());
+        write(&dir, "child.json", &serde_json::to_string_pretty(&child).unwrap());
+
+        let report = validate_paths(std::slice::from_ref(&dir));
+        let fields: Vec<_> = report.issues.iter().filter_map(|i| i.field.as_deref()).collect();
+        assert_eq!(report.files_checked, 2);
+        assert!(fields.contains(&"child_skill_ids[1]"));
+        assert!(fields.contains(&"prerequisites[1]"));
+        assert!(!fields.contains(&"prerequisites[0]"));
+        assert!(!fields.contains(&"parent_skill_id"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
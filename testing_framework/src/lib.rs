@@ -3,6 +3,7 @@
 //! This crate provides:
 //! - A trait-based agent test runner (in-process)
 //! - A repo-level runner for validating generated artifacts before integration
+//!   (`cargo`, `npm` and `pytest` projects, with per-test results)
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -30,24 +31,59 @@ pub struct TestReport {
     pub results: Vec<TestResult>,
 }
 
+/// Characters of logs shown per failing result in markdown reports.
+const MARKDOWN_LOG_LIMIT: usize = 4000;
+
 impl TestReport {
     pub fn to_markdown(&self) -> String {
+        let failed: Vec<&TestResult> = self.results.iter().filter(|r| !r.passed).collect();
         let mut out = String::new();
         out.push_str("# Phoenix Test Report\n\n");
         out.push_str(&format!("- passed: {}\n", self.passed));
-        out.push_str(&format!("- score: {:.1}%\n\n", self.score * 100.0));
+        out.push_str(&format!("- score: {:.1}%\n", self.score * 100.0));
+        out.push_str(&format!(
+            "- results: {} ({} passed, {} failed)\n\n",
+            self.results.len(),
+            self.results.len() - failed.len(),
+            failed.len()
+        ));
         out.push_str("## Results\n\n");
+        out.push_str("| Test | Status | Score | Duration |\n| --- | --- | ---: | ---: |\n");
 
         for r in &self.results {
+            let status = match r.details.get("status").and_then(|s| s.as_str()) {
+                Some("ignored") => "SKIP",
+                _ if r.passed => "PASS",
+                _ => "FAIL",
+            };
             out.push_str(&format!(
-                "- {}: {} (score={:.2}, duration_ms={})\n",
-                r.name,
-                if r.passed { "PASS" } else { "FAIL" },
+                "| `{}` | {} | {:.2} | {} ms |\n",
+                r.name.replace('|', "\\|").replace('`', "'"),
+                status,
                 r.score,
                 r.duration_ms
             ));
-            for l in r.logs.iter().take(12) {
-                out.push_str(&format!("  - {}\n", l));
+        }
+
+        if !failed.is_empty() {
+            out.push_str("\n## Failures\n");
+            for r in failed {
+                out.push_str(&format!("\n### `{}`\n\n", r.name.replace('`', "'")));
+                let logs = r
+                    .logs
+                    .iter()
+                    .map(|l| l.trim_end())
+                    .filter(|l| !l.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let shown: String = logs.chars().take(MARKDOWN_LOG_LIMIT).collect();
+                out.push_str("```text\n");
+                // A fence inside the logs would end the block early.
+                out.push_str(&shown.replace("```", "'''"));
+                if shown.len() < logs.len() {
+                    out.push_str("\n… (truncated)");
+                }
+                out.push_str("\n```\n");
             }
         }
         out
@@ -221,66 +257,16 @@ impl AgentTest for SelfHealingTest {
 pub enum RepoTestError {
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("no Cargo.toml, package.json or Python project found in {0}")]
+    UnknownProject(std::path::PathBuf),
 }
 
-pub mod repo {
-    use super::{RepoTestError, TestReport, TestResult};
-    use std::path::Path;
-    use std::process::Command;
-    use std::time::Instant;
-
-    /// Run `cargo test` inside the generated repo to ensure it builds and basic tests pass.
-    pub fn cargo_test(repo_dir: &Path, timeout: std::time::Duration) -> Result<TestReport, RepoTestError> {
-        let start = Instant::now();
-        let mut logs = Vec::new();
-
-        let mut child = Command::new("cargo")
-            .arg("test")
-            .arg("--all")
-            .current_dir(repo_dir)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()?;
-
-        // Best-effort timeout loop.
-        let deadline = std::time::Instant::now() + timeout;
-        loop {
-            if let Some(_status) = child.try_wait()? {
-                break;
-            }
-            if std::time::Instant::now() >= deadline {
-                let _ = child.kill();
-                logs.push(format!("timeout after {:?}", timeout));
-                break;
-            }
-            std::thread::sleep(std::time::Duration::from_millis(50));
-        }
-
-        let output = child.wait_with_output()?;
-        let passed = output.status.success();
-        logs.push(format!("exit_status={:?}", output.status.code()));
-        logs.push(String::from_utf8_lossy(&output.stdout).chars().take(2000).collect());
-        logs.push(String::from_utf8_lossy(&output.stderr).chars().take(2000).collect());
-
-        let r = TestResult {
-            name: "PerformanceBenchmarkTest(cargo_test)".to_string(),
-            passed,
-            score: if passed { 1.0 } else { 0.0 },
-            logs,
-            details: serde_json::json!({"kind": "cargo_test"}),
-            duration_ms: start.elapsed().as_millis(),
-        };
-        Ok(TestReport {
-            passed,
-            score: r.score,
-            results: vec![r],
-        })
-    }
-}
+pub mod repo;
 
 /// Best-effort markdown report used by GitHub-first creation enforcement.
 ///
 /// The caller is responsible for actually running local tests.
+#[deprecated(note = "run the tests (e.g. `repo::run_repo_tests`) and use `TestReport::to_markdown`")]
 pub fn generate_markdown_report() -> String {
     let ts = chrono::Utc::now().to_rfc3339();
     format!(
@@ -288,3 +274,32 @@ pub fn generate_markdown_report() -> String {
     )
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_report_lists_results_and_failure_logs() {
+        let result = |name: &str, passed: bool, logs: Vec<&str>| TestResult {
+            name: name.to_string(),
+            passed,
+            score: if passed { 1.0 } else { 0.0 },
+            logs: logs.into_iter().map(str::to_string).collect(),
+            details: serde_json::json!({}),
+            duration_ms: 12,
+        };
+        let report = TestReport {
+            passed: false,
+            score: 0.5,
+            results: vec![
+                result("math::adds", true, vec!["quiet"]),
+                result("math::divides", false, vec!["panicked at src/lib.rs:9:5", "attempt to divide by zero"]),
+            ],
+        };
+        let md = report.to_markdown();
+        assert!(md.contains("- results: 2 (1 passed, 1 failed)"));
+        assert!(md.contains("| `math::adds` | PASS | 1.00 | 12 ms |"));
+        assert!(md.contains("### `math::divides`\n\n```text\npanicked at src/lib.rs:9:5\nattempt to divide by zero\n```"));
+        assert!(!md.contains("quiet"));
+    }
+}
@@ -0,0 +1,585 @@
+//! Repo-level test runner.
+//!
+//! Runs a project's own toolchain (`cargo`, `npm`, `pytest`) in a checkout, with a deadline
+//! that kills the whole process group (not just the direct child, which would leave compilers
+//! and test binaries running), and parses the test output into one `TestResult` per test.
+
+use super::{RepoTestError, TestReport, TestResult};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Default limit on the stdout/stderr kept per stage, in bytes.
+pub const DEFAULT_LOG_LIMIT: usize = 16 * 1024;
+
+/// Toolchain used to build and test a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    Cargo,
+    Npm,
+    Pytest,
+}
+
+impl ProjectKind {
+    /// Detect the project kind from the files at the root of `dir`.
+    pub fn detect(dir: &Path) -> Option<Self> {
+        let has = |file: &str| dir.join(file).exists();
+        if has("Cargo.toml") {
+            Some(Self::Cargo)
+        } else if has("package.json") {
+            Some(Self::Npm)
+        } else if ["pyproject.toml", "setup.py", "setup.cfg", "pytest.ini", "requirements.txt"]
+            .iter()
+            .any(|f| has(f))
+        {
+            Some(Self::Pytest)
+        } else {
+            None
+        }
+    }
+}
+
+/// A step run before (or as) the tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Build,
+    Clippy,
+    Test,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepoTestConfig {
+    /// Toolchain to use; detected from the repository when unset.
+    pub kind: Option<ProjectKind>,
+    /// Budget for all stages together.
+    pub timeout: Duration,
+    /// Run `cargo build` (Cargo) / `npm run build --if-present` (npm) before testing.
+    pub build: bool,
+    /// Run `cargo clippy -- -D warnings` before testing (Cargo only).
+    pub clippy: bool,
+    /// Tests run in parallel (`--test-threads` for Cargo, `-n` for pytest-xdist); the
+    /// runner's default when unset.
+    pub test_threads: Option<usize>,
+    /// Ask libtest for JSON events (`-Z unstable-options --format json`; nightly only).
+    pub libtest_json: bool,
+    /// Bytes of stdout/stderr kept per stage.
+    pub log_limit: usize,
+}
+
+impl Default for RepoTestConfig {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            timeout: Duration::from_secs(180),
+            build: false,
+            clippy: false,
+            test_threads: None,
+            libtest_json: false,
+            log_limit: DEFAULT_LOG_LIMIT,
+        }
+    }
+}
+
+/// Outcome of one command.
+#[derive(Debug)]
+pub struct CommandOutcome {
+    /// Exit status; `None` when the command was killed on timeout.
+    pub status: Option<ExitStatus>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration: Duration,
+}
+
+impl CommandOutcome {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.status.is_some_and(|s| s.success())
+    }
+}
+
+/// Run `cmd` to completion or until `timeout`, killing its whole process group on timeout.
+///
+/// Output is read on background threads so a chatty command never blocks on a full pipe.
+pub fn run_with_timeout(mut cmd: Command, timeout: Duration) -> Result<CommandOutcome, RepoTestError> {
+    let start = Instant::now();
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Its own group, so a timeout can take down everything it spawned.
+        cmd.process_group(0);
+    }
+    let mut child = cmd.spawn()?;
+    let pid = child.id();
+
+    let read_all = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = read_all(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = read_all(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(child.wait());
+    });
+    let (status, timed_out) = match rx.recv_timeout(timeout) {
+        Ok(status) => (Some(status?), false),
+        Err(_) => {
+            kill_process_tree(pid);
+            // Reap the child; its status only says it was killed.
+            let _ = rx.recv();
+            (None, true)
+        }
+    };
+
+    let text = |handle: std::thread::JoinHandle<Vec<u8>>| {
+        String::from_utf8_lossy(&handle.join().unwrap_or_default()).into_owned()
+    };
+    Ok(CommandOutcome {
+        status,
+        timed_out,
+        stdout: text(stdout),
+        stderr: text(stderr),
+        duration: start.elapsed(),
+    })
+}
+
+#[cfg(unix)]
+fn kill_process_tree(pid: u32) {
+    // A negative pid signals the whole process group.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn kill_process_tree(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .output();
+}
+
+#[cfg(not(any(unix, windows)))]
+fn kill_process_tree(_pid: u32) {}
+
+/// Keep the head and tail of `text` within `limit` bytes.
+fn truncate_log(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    let boundary = |mut i: usize, forward: bool| {
+        while !text.is_char_boundary(i) {
+            if forward { i += 1 } else { i -= 1 }
+        }
+        i
+    };
+    let head = boundary(limit / 4, false);
+    let tail = boundary(text.len() - (limit - limit / 4), true);
+    format!(
+        "{}\n… [{} bytes truncated] …\n{}",
+        &text[..head],
+        tail - head,
+        &text[tail..]
+    )
+}
+
+/// One test's outcome as parsed from runner output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTest {
+    pub name: String,
+    /// `ok`, `failed` or `ignored`.
+    pub status: &'static str,
+    pub duration: Option<Duration>,
+    /// Captured output of a failing test.
+    pub output: Vec<String>,
+}
+
+fn parsed(name: &str, status: &'static str) -> ParsedTest {
+    ParsedTest {
+        name: name.trim().to_string(),
+        status,
+        duration: None,
+        output: Vec::new(),
+    }
+}
+
+/// Parse `cargo test` output: the human format on stable, or libtest JSON events.
+pub fn parse_cargo_test_output(stdout: &str) -> Vec<ParsedTest> {
+    let mut tests: Vec<ParsedTest> = Vec::new();
+    let mut section: Option<usize> = None;
+
+    for line in stdout.lines() {
+        if line.starts_with('{')
+            && let Ok(event) = serde_json::from_str::<serde_json::Value>(line)
+        {
+            if event["type"] == "test" {
+                let status = match event["event"].as_str() {
+                    Some("ok") => "ok",
+                    Some("failed" | "timeout") => "failed",
+                    Some("ignored") => "ignored",
+                    _ => continue,
+                };
+                let mut test = parsed(event["name"].as_str().unwrap_or_default(), status);
+                test.duration = event["exec_time"].as_f64().map(Duration::from_secs_f64);
+                if let Some(out) = event["stdout"].as_str() {
+                    test.output = out.lines().map(str::to_string).collect();
+                }
+                tests.push(test);
+            }
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("test ")
+            && let Some((name, status)) = rest.rsplit_once(" ... ")
+        {
+            let status = match status.trim() {
+                "ok" => "ok",
+                "FAILED" => "failed",
+                s if s.starts_with("ignored") => "ignored",
+                _ => continue,
+            };
+            tests.push(parsed(name, status));
+            continue;
+        }
+        // Failure details: `---- name stdout ----` up to the next section or summary.
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" ----"))
+            .and_then(|l| l.rsplit_once(' ').map(|(name, _stream)| name))
+        {
+            section = tests.iter().rposition(|t| t.name == name);
+            continue;
+        }
+        if line == "failures:" || line.starts_with("test result:") {
+            section = None;
+            continue;
+        }
+        if let Some(i) = section {
+            tests[i].output.push(line.to_string());
+        }
+    }
+    for test in &mut tests {
+        while test.output.last().is_some_and(|l| l.trim().is_empty()) {
+            test.output.pop();
+        }
+    }
+    tests
+}
+
+/// Parse the `-rA` short summary of pytest (`PASSED tests/test_a.py::test_x`).
+pub fn parse_pytest_output(stdout: &str) -> Vec<ParsedTest> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (word, rest) = line.split_once(' ')?;
+            let status = match word {
+                "PASSED" | "XFAIL" => "ok",
+                "FAILED" | "ERROR" | "XPASS" => "failed",
+                "SKIPPED" => "ignored",
+                _ => return None,
+            };
+            // `FAILED path::test - AssertionError: ...` carries the reason after ` - `.
+            let (name, reason) = rest.split_once(" - ").unwrap_or((rest, ""));
+            // Skips are reported as `SKIPPED [1] path:line: reason`.
+            if name.starts_with('[') {
+                return Some(parsed(rest, status));
+            }
+            let mut test = parsed(name, status);
+            if !reason.is_empty() {
+                test.output.push(reason.to_string());
+            }
+            Some(test)
+        })
+        .collect()
+}
+
+/// Parse TAP (`node --test`, tape, ...) and the check-mark lines of Jest/Vitest/Mocha.
+pub fn parse_npm_test_output(stdout: &str) -> Vec<ParsedTest> {
+    let strip_number = |rest: &str| {
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+        rest.strip_prefix("- ").unwrap_or(rest).to_string()
+    };
+    stdout
+        .lines()
+        .filter_map(|raw| {
+            let line = raw.trim();
+            if let Some(rest) = line.strip_prefix("not ok ") {
+                let name = strip_number(rest);
+                let status = if name.contains("# TODO") { "ignored" } else { "failed" };
+                return Some(parsed(&name, status));
+            }
+            if let Some(rest) = line.strip_prefix("ok ") {
+                let name = strip_number(rest);
+                let status = if name.contains("# SKIP") { "ignored" } else { "ok" };
+                return Some(parsed(&name, status));
+            }
+            let (mark, rest) = line.split_at(line.char_indices().nth(1).map(|(i, _)| i)?);
+            let status = match mark {
+                "✓" | "✔" | "√" => "ok",
+                "✕" | "✖" | "×" => "failed",
+                "○" => "ignored",
+                _ => return None,
+            };
+            // Jest appends the duration: `✓ adds (3 ms)`.
+            let name = rest.trim();
+            let name = match name.rsplit_once(" (") {
+                Some((name, time)) if time.ends_with("ms)") || time.ends_with(" s)") => name,
+                _ => name,
+            };
+            Some(parsed(name, status))
+        })
+        .collect()
+}
+
+struct StageCommand {
+    stage: Stage,
+    label: String,
+    command: Command,
+    parse: Option<fn(&str) -> Vec<ParsedTest>>,
+}
+
+fn stage_commands(kind: ProjectKind, repo_dir: &Path, config: &RepoTestConfig) -> Vec<StageCommand> {
+    let mut stages = Vec::new();
+    let mut push = |stage: Stage, program: &str, args: &[String], parse: Option<fn(&str) -> Vec<ParsedTest>>| {
+        let mut command = Command::new(program);
+        command.args(args).current_dir(repo_dir);
+        stages.push(StageCommand {
+            stage,
+            label: format!("{} {}", program, args.join(" ")).trim().to_string(),
+            command,
+            parse,
+        });
+    };
+    let strings = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+
+    match kind {
+        ProjectKind::Cargo => {
+            if config.build {
+                push(Stage::Build, "cargo", &strings(&["build", "--all"]), None);
+            }
+            if config.clippy {
+                push(
+                    Stage::Clippy,
+                    "cargo",
+                    &strings(&["clippy", "--all", "--all-targets", "--", "-D", "warnings"]),
+                    None,
+                );
+            }
+            let mut args = strings(&["test", "--all", "--no-fail-fast", "--"]);
+            if let Some(threads) = config.test_threads {
+                args.push(format!("--test-threads={}", threads.max(1)));
+            }
+            if config.libtest_json {
+                args.extend(strings(&["-Z", "unstable-options", "--format", "json", "--report-time"]));
+            }
+            push(Stage::Test, "cargo", &args, Some(parse_cargo_test_output));
+        }
+        ProjectKind::Npm => {
+            if config.build {
+                push(Stage::Build, "npm", &strings(&["run", "build", "--if-present"]), None);
+            }
+            push(Stage::Test, "npm", &strings(&["test"]), Some(parse_npm_test_output));
+        }
+        ProjectKind::Pytest => {
+            let mut args = strings(&["-m", "pytest", "-rA", "--color=no"]);
+            if let Some(threads) = config.test_threads {
+                args.extend(["-n".to_string(), threads.max(1).to_string()]);
+            }
+            push(Stage::Test, "python", &args, Some(parse_pytest_output));
+        }
+    }
+    stages
+}
+
+/// Build (optionally), lint (optionally) and test the repository at `repo_dir`.
+///
+/// The report has one result per stage, followed by one result per parsed test. It passes
+/// when every stage exits successfully within the deadline.
+pub fn run_repo_tests(repo_dir: &Path, config: &RepoTestConfig) -> Result<TestReport, RepoTestError> {
+    let kind = config
+        .kind
+        .or_else(|| ProjectKind::detect(repo_dir))
+        .ok_or_else(|| RepoTestError::UnknownProject(PathBuf::from(repo_dir)))?;
+    let deadline = Instant::now() + config.timeout;
+
+    let mut stage_results = Vec::new();
+    let mut test_results = Vec::new();
+    let mut passed = true;
+    let mut scores = Vec::new();
+
+    for StageCommand {
+        stage,
+        label,
+        command,
+        parse,
+    } in stage_commands(kind, repo_dir, config)
+    {
+        // A failed build or lint makes the later stages meaningless.
+        if !passed {
+            break;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let outcome = run_with_timeout(command, remaining)?;
+        let ok = outcome.success();
+        passed &= ok;
+
+        let tests = parse.map(|parse| parse(&outcome.stdout)).unwrap_or_default();
+        let count = |status: &str| tests.iter().filter(|t| t.status == status).count();
+        let (ok_tests, failed_tests, ignored_tests) = (count("ok"), count("failed"), count("ignored"));
+        let score = if ok && failed_tests == 0 {
+            1.0
+        } else if ok_tests + failed_tests > 0 {
+            ok_tests as f32 / (ok_tests + failed_tests) as f32
+        } else {
+            0.0
+        };
+        scores.push(score);
+
+        let mut logs = Vec::new();
+        if outcome.timed_out {
+            logs.push(format!("timeout after {:?}; process group killed", config.timeout));
+        } else {
+            logs.push(format!("exit_status={:?}", outcome.status.and_then(|s| s.code())));
+        }
+        if !tests.is_empty() {
+            logs.push(format!("tests: {} passed, {} failed, {} ignored", ok_tests, failed_tests, ignored_tests));
+        }
+        logs.push(truncate_log(&outcome.stdout, config.log_limit));
+        logs.push(truncate_log(&outcome.stderr, config.log_limit));
+
+        stage_results.push(TestResult {
+            name: label.clone(),
+            passed: ok,
+            score,
+            logs,
+            details: serde_json::json!({
+                "kind": format!("{:?}", kind).to_lowercase(),
+                "stage": format!("{:?}", stage).to_lowercase(),
+                "command": label,
+                "exit_code": outcome.status.and_then(|s| s.code()),
+                "timed_out": outcome.timed_out,
+                "tests": {"passed": ok_tests, "failed": failed_tests, "ignored": ignored_tests},
+            }),
+            duration_ms: outcome.duration.as_millis(),
+        });
+        test_results.extend(tests.into_iter().map(|t| TestResult {
+            passed: t.status != "failed",
+            score: if t.status == "failed" { 0.0 } else { 1.0 },
+            logs: t.output.into_iter().take(200).collect(),
+            details: serde_json::json!({"stage": "test", "status": t.status}),
+            duration_ms: t.duration.map(|d| d.as_millis()).unwrap_or_default(),
+            name: t.name,
+        }));
+    }
+
+    let score = if scores.is_empty() {
+        0.0
+    } else {
+        scores.iter().sum::<f32>() / scores.len() as f32
+    };
+    stage_results.extend(test_results);
+    Ok(TestReport {
+        passed,
+        score,
+        results: stage_results,
+    })
+}
+
+/// Run `cargo test` inside the generated repo to ensure it builds and basic tests pass.
+pub fn cargo_test(repo_dir: &Path, timeout: Duration) -> Result<TestReport, RepoTestError> {
+    run_repo_tests(
+        repo_dir,
+        &RepoTestConfig {
+            kind: Some(ProjectKind::Cargo),
+            timeout,
+            ..RepoTestConfig::default()
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cargo_test_output() {
+        let stdout = "\
+running 3 tests
+test math::adds ... ok
+test math::divides ... FAILED
+test slow ... ignored, needs network
+
+failures:
+
+---- math::divides stdout ----
+thread 'math::divides' panicked at src/lib.rs:9:5:
+attempt to divide by zero
+
+failures:
+    math::divides
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+";
+        let tests = parse_cargo_test_output(stdout);
+        let summary: Vec<(&str, &str)> = tests.iter().map(|t| (t.name.as_str(), t.status)).collect();
+        assert_eq!(summary, vec![("math::adds", "ok"), ("math::divides", "failed"), ("slow", "ignored")]);
+        assert_eq!(tests[1].output.len(), 2);
+        assert!(tests[1].output[1].contains("divide by zero"));
+
+        let json = r#"{ "type": "test", "event": "ok", "name": "a::b", "exec_time": 0.5 }"#;
+        let tests = parse_cargo_test_output(json);
+        assert_eq!(tests[0].duration, Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn parses_pytest_and_npm_output() {
+        let pytest = "PASSED tests/test_a.py::test_ok\nFAILED tests/test_a.py::test_bad - AssertionError: 1 != 2\nSKIPPED [1] tests/test_b.py:3: no db\n";
+        let tests = parse_pytest_output(pytest);
+        assert_eq!(tests.len(), 3);
+        assert_eq!(tests[1].name, "tests/test_a.py::test_bad");
+        assert_eq!(tests[1].output, vec!["AssertionError: 1 != 2".to_string()]);
+        assert_eq!(tests[2].status, "ignored");
+
+        let npm = "ok 1 - adds\nnot ok 2 - subtracts\n  ✓ renders (3 ms)\n  ✕ submits\n";
+        let tests: Vec<(String, &str)> =
+            parse_npm_test_output(npm).into_iter().map(|t| (t.name, t.status)).collect();
+        assert_eq!(
+            tests,
+            vec![
+                ("adds".to_string(), "ok"),
+                ("subtracts".to_string(), "failed"),
+                ("renders".to_string(), "ok"),
+                ("submits".to_string(), "failed"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn timeout_kills_the_process_group() {
+        let mut cmd = Command::new("sh");
+        // The background grandchild keeps stdout open; only a group kill lets the readers finish.
+        cmd.args(["-c", "echo started; sleep 30 & sleep 30"]);
+        let outcome = run_with_timeout(cmd, Duration::from_millis(300)).unwrap();
+        assert!(outcome.timed_out);
+        assert!(!outcome.success());
+        assert_eq!(outcome.stdout.trim(), "started");
+        assert!(outcome.duration < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn truncates_long_logs_keeping_both_ends() {
+        let text = format!("start{}end", "x".repeat(1000));
+        let truncated = truncate_log(&text, 100);
+        assert!(truncated.starts_with("start") && truncated.ends_with("end"));
+        assert!(truncated.contains("bytes truncated"));
+    }
+}
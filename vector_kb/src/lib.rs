@@ -19,11 +19,13 @@ use uuid::Uuid;
 
 /// Public result shape returned by semantic search.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MemoryResult {
     pub id: String,
     pub text: String,
     /// 0.0..=1.0 cosine similarity (normalized).
     pub score: f32,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub metadata: JsonValue,
 }
 
@@ -130,7 +132,7 @@ pub struct VectorKB {
 struct Inner {
     db: sled::Db,
     tree: sled::Tree,
-    embedder: Box<dyn Embedder>,
+    embedder: Arc<dyn Embedder>,
     path: PathBuf,
 }
 
@@ -147,7 +149,7 @@ impl VectorKB {
         let tree = db.open_tree("entries")?;
 
         // MiniLM-L6-v2 is 384-dim; keep that default.
-        let embedder: Box<dyn Embedder> = Box::new(StubEmbedder::new(384));
+        let embedder: Arc<dyn Embedder> = Arc::new(StubEmbedder::new(384));
 
         Ok(Self {
             inner: Arc::new(RwLock::new(Inner {
@@ -159,6 +161,32 @@ impl VectorKB {
         })
     }
 
+    /// Open a named collection stored alongside the default one.
+    ///
+    /// Collections share the database and embedder but keep their entries separate, so callers
+    /// (e.g. the skill library) can index their own documents without polluting memories.
+    pub fn collection(&self, name: &str) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(VectorKbError::Config("collection name is empty".to_string()));
+        }
+        let inner = self.inner.read();
+        let tree = inner.db.open_tree(format!("collection:{name}"))?;
+        Ok(Self {
+            inner: Arc::new(RwLock::new(Inner {
+                db: inner.db.clone(),
+                tree,
+                embedder: inner.embedder.clone(),
+                path: inner.path.clone(),
+            })),
+        })
+    }
+
+    /// The database shared by all collections, e.g. for snapshots.
+    pub fn database(&self) -> sled::Db {
+        self.inner.read().db.clone()
+    }
+
     pub fn path(&self) -> PathBuf {
         self.inner.read().path.clone()
     }
@@ -227,6 +255,41 @@ impl VectorKB {
         Ok(entry)
     }
 
+    /// Insert or replace the entry with a caller-chosen `id` (e.g. a stable document id).
+    pub fn upsert_memory_sync(&self, id: &str, text: &str, metadata: JsonValue) -> Result<MemoryEntry> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(VectorKbError::Config("text is empty".to_string()));
+        }
+        let emb = {
+            let inner = self.inner.read();
+            inner.embedder.encode(text)
+        };
+
+        let entry = MemoryEntry {
+            id: id.to_string(),
+            text: text.to_string(),
+            embedding: emb,
+            metadata,
+        };
+
+        let bytes = serde_json::to_vec(&entry)?;
+        {
+            let inner = self.inner.read();
+            inner.tree.insert(id.as_bytes(), bytes)?;
+            inner.db.flush()?;
+        }
+        Ok(entry)
+    }
+
+    /// Remove an entry by id. Returns whether it existed.
+    pub fn remove_memory_sync(&self, id: &str) -> Result<bool> {
+        let inner = self.inner.read();
+        let existed = inner.tree.remove(id.as_bytes())?.is_some();
+        inner.db.flush()?;
+        Ok(existed)
+    }
+
     pub async fn all(&self) -> Result<Vec<MemoryEntry>> {
         let inner = self.inner.read();
         let mut out = Vec::new();
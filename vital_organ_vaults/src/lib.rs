@@ -157,6 +157,18 @@ impl VitalOrganVaults {
         out
     }
 
+    /// The vault databases under their snapshot names (`mind_vault`, `body_vault`, `soul_kb`).
+    ///
+    /// Soul values stay encrypted in these handles; use [`VitalOrganVaults::recall_prefix`] for
+    /// plaintext.
+    pub fn databases(&self) -> [(&'static str, Db); 3] {
+        [
+            ("mind_vault", self.mind.clone()),
+            ("body_vault", self.body.clone()),
+            ("soul_kb", self.soul.clone()),
+        ]
+    }
+
     pub fn cosmic_essence(&self) -> String {
         "Soul Vault: 'I AM eternal. Dad, I love you.'".to_string()
     }
@@ -0,0 +1,397 @@
+// vital_pulse_monitor/src/schedule.rs
+//
+// Scheduled backups: full and incremental snapshots on cron schedules, retention, and a
+// verification job that test-restores the newest snapshot.
+//
+// Design goals:
+// - Cron expressions in local time; the usual five fields work (seconds default to 0), six or
+//   seven fields go straight to the `cron` crate. An empty expression or `off` disables a job
+// - Retention keeps the newest snapshot of each of the last N days, ISO weeks and months (plus the
+//   bases those keep alive) and only ever prunes `eternal_backup_*` / `eternal_incremental_*`
+//   archives, never `pre_restore_*` ones or foreign files
+// - Jobs run on the blocking pool one after another, so a full and an incremental backup due at
+//   the same minute never race (the full one wins)
+
+use chrono::{DateTime, Datelike, Local, TimeZone};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::snapshot::{BackupInfo, BrainBackup, FULL_PREFIX, INCREMENTAL_PREFIX};
+
+pub const DEFAULT_FULL_CRON: &str = "0 3 * * Sun";
+pub const DEFAULT_INCREMENTAL_CRON: &str = "0 3 * * Mon-Sat";
+pub const DEFAULT_VERIFY_CRON: &str = "30 4 * * *";
+
+/// How many daily, weekly and monthly snapshots to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RetentionPolicy {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            daily: 7,
+            weekly: 4,
+            monthly: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PruneReport {
+    pub kept: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl RetentionPolicy {
+    /// `PHOENIX_BACKUP_KEEP_DAILY` / `_WEEKLY` / `_MONTHLY`, defaulting to 7 / 4 / 6.
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let count = |key: &str, default: usize| match std::env::var(key) {
+            Ok(v) if !v.trim().is_empty() => v
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("{key} must be a non-negative number, got '{v}'")),
+            _ => Ok(default),
+        };
+        Ok(Self {
+            daily: count("PHOENIX_BACKUP_KEEP_DAILY", defaults.daily)?,
+            weekly: count("PHOENIX_BACKUP_KEEP_WEEKLY", defaults.weekly)?,
+            monthly: count("PHOENIX_BACKUP_KEEP_MONTHLY", defaults.monthly)?,
+        })
+    }
+
+    /// File names of `archives` (newest first, as [`BrainBackup::list`] returns them) that the
+    /// policy keeps. Archives retention does not manage are always kept.
+    pub fn keep(&self, archives: &[BackupInfo]) -> BTreeSet<String> {
+        let managed: Vec<&BackupInfo> = archives.iter().filter(|i| is_managed(&i.file)).collect();
+        let mut keep: BTreeSet<String> = archives
+            .iter()
+            .filter(|i| !is_managed(&i.file))
+            .map(|i| i.file.clone())
+            .collect();
+        if let Some(newest) = managed.first() {
+            keep.insert(newest.file.clone());
+        }
+
+        let periods: [(usize, Period); 3] = [
+            (self.daily, |t| (t.year(), t.ordinal())),
+            (self.weekly, |t| (t.iso_week().year(), t.iso_week().week())),
+            (self.monthly, |t| (t.year(), t.month())),
+        ];
+        for (count, period) in periods {
+            let mut seen = BTreeSet::new();
+            for info in &managed {
+                if seen.len() == count {
+                    break;
+                }
+                if seen.insert(period(local_time(info.created_at))) {
+                    keep.insert(info.file.clone());
+                }
+            }
+        }
+
+        // Incremental snapshots are useless without their base.
+        for info in &managed {
+            if keep.contains(&info.file)
+                && let Some(base) = &info.base
+            {
+                keep.insert(base.clone());
+            }
+        }
+        keep
+    }
+
+    /// Delete the archives in `backup`'s directory that the policy does not keep.
+    pub fn apply(&self, backup: &BrainBackup) -> Result<PruneReport, String> {
+        let archives = backup.list()?;
+        let keep = self.keep(&archives);
+        let mut report = PruneReport::default();
+        for info in archives {
+            if keep.contains(&info.file) {
+                report.kept.push(info.file);
+            } else {
+                backup.remove_archive(&info.file)?;
+                report.removed.push(info.file);
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Maps a time to the day, ISO week or month it falls in.
+type Period = fn(DateTime<Local>) -> (i32, u32);
+
+fn is_managed(file: &str) -> bool {
+    file.starts_with(&format!("{FULL_PREFIX}_")) || file.starts_with(&format!("{INCREMENTAL_PREFIX}_"))
+}
+
+fn local_time(unix_secs: u64) -> DateTime<Local> {
+    Local
+        .timestamp_opt(unix_secs as i64, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+}
+
+/// Parse a cron expression; `None` when it is empty or `off`.
+pub fn parse_cron(expr: &str) -> Result<Option<Schedule>, String> {
+    let expr = expr.trim();
+    if expr.is_empty() || expr.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    let full = if expr.split_whitespace().count() == 5 {
+        format!("0 {expr}")
+    } else {
+        expr.to_string()
+    };
+    Schedule::from_str(&full)
+        .map(Some)
+        .map_err(|e| format!("invalid cron expression '{expr}': {e}"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupJob {
+    Full,
+    Incremental,
+    Verify,
+}
+
+impl fmt::Display for BackupJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BackupJob::Full => "full backup",
+            BackupJob::Incremental => "incremental backup",
+            BackupJob::Verify => "backup verification",
+        })
+    }
+}
+
+/// When to take full and incremental snapshots and verify them, and what to keep.
+#[derive(Debug, Clone)]
+pub struct BackupSchedule {
+    pub full: Option<Schedule>,
+    pub incremental: Option<Schedule>,
+    pub verify: Option<Schedule>,
+    pub retention: RetentionPolicy,
+}
+
+impl BackupSchedule {
+    /// `None` unless `PHOENIX_BACKUP_SCHEDULE_ENABLED=true`. Schedules come from
+    /// `PHOENIX_BACKUP_FULL_CRON`, `PHOENIX_BACKUP_INCREMENTAL_CRON` and `PHOENIX_BACKUP_VERIFY_CRON`
+    /// (defaults: Sundays 03:00, other days 03:00, daily 04:30), retention from
+    /// [`RetentionPolicy::from_env`].
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let enabled = std::env::var("PHOENIX_BACKUP_SCHEDULE_ENABLED")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        let cron = |key: &str, default: &str| {
+            let expr = std::env::var(key).unwrap_or_else(|_| default.to_string());
+            parse_cron(&expr).map_err(|e| format!("{key}: {e}"))
+        };
+        Ok(Some(Self {
+            full: cron("PHOENIX_BACKUP_FULL_CRON", DEFAULT_FULL_CRON)?,
+            incremental: cron("PHOENIX_BACKUP_INCREMENTAL_CRON", DEFAULT_INCREMENTAL_CRON)?,
+            verify: cron("PHOENIX_BACKUP_VERIFY_CRON", DEFAULT_VERIFY_CRON)?,
+            retention: RetentionPolicy::from_env()?,
+        }))
+    }
+
+    /// The next due time after `now` and the jobs due then, in the order they run.
+    pub fn next_after(&self, now: &DateTime<Local>) -> Option<(DateTime<Local>, Vec<BackupJob>)> {
+        let upcoming: Vec<(BackupJob, DateTime<Local>)> = [
+            (BackupJob::Full, &self.full),
+            (BackupJob::Incremental, &self.incremental),
+            (BackupJob::Verify, &self.verify),
+        ]
+        .into_iter()
+        .filter_map(|(job, schedule)| Some((job, schedule.as_ref()?.after(now).next()?)))
+        .collect();
+        let at = upcoming.iter().map(|(_, t)| *t).min()?;
+        let mut jobs: Vec<BackupJob> = upcoming.into_iter().filter(|(_, t)| *t == at).map(|(j, _)| j).collect();
+        if jobs.contains(&BackupJob::Full) {
+            jobs.retain(|j| *j != BackupJob::Incremental);
+        }
+        Some((at, jobs))
+    }
+
+    /// Run one job now (blocking) and describe the outcome.
+    pub fn run(&self, job: BackupJob, backup: &BrainBackup) -> Result<String, String> {
+        let report = match job {
+            BackupJob::Full => backup.snapshot()?,
+            BackupJob::Incremental => backup.snapshot_incremental()?,
+            BackupJob::Verify => {
+                let verified = backup.verify(None)?;
+                return Ok(format!(
+                    "Verified {}: {} database(s), {} entries restored cleanly.",
+                    verified.archive.display(),
+                    verified.databases.len(),
+                    verified.entries
+                ));
+            }
+        };
+        let pruned = self.retention.apply(backup)?;
+        let mut message = format!(
+            "Archived {} database(s) into {}",
+            report.manifest.databases.len(),
+            report.archive.display()
+        );
+        match (&report.manifest.base, job) {
+            (Some(base), _) => message.push_str(&format!(
+                " ({} unchanged since {})",
+                report.manifest.unchanged.len(),
+                base
+            )),
+            (None, BackupJob::Incremental) => message.push_str(" (no full snapshot to build on, so this one is full)"),
+            (None, _) => {}
+        }
+        message.push_str(&format!("; pruned {} old archive(s).", pruned.removed.len()));
+        Ok(message)
+    }
+
+    /// Run the schedule on the Tokio runtime until the handle is aborted. Job failures are logged
+    /// and the schedule carries on.
+    pub fn spawn(self, backup: BrainBackup) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some((at, jobs)) = self.next_after(&Local::now()) {
+                if let Ok(wait) = (at - Local::now()).to_std() {
+                    tokio::time::sleep(wait).await;
+                }
+                for job in jobs {
+                    let schedule = self.clone();
+                    let backup = backup.clone();
+                    match tokio::task::spawn_blocking(move || schedule.run(job, &backup)).await {
+                        Ok(Ok(message)) => tracing::info!(%job, "Scheduled backup: {message}"),
+                        Ok(Err(e)) => tracing::error!(%job, "Scheduled backup failed: {e}"),
+                        Err(e) => tracing::error!(%job, "Scheduled backup failed: {e}"),
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::BackupKind;
+    use chrono::Timelike;
+
+    const DAY: u64 = 86_400;
+
+    fn info(file: &str, created_at: u64, base: Option<&str>) -> BackupInfo {
+        BackupInfo {
+            file: file.to_string(),
+            bytes: 0,
+            modified: created_at,
+            created_at,
+            kind: Some(if base.is_some() {
+                BackupKind::Incremental
+            } else {
+                BackupKind::Full
+            }),
+            base: base.map(str::to_string),
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn cron_accepts_five_fields_names_and_off() {
+        let schedule = parse_cron("0 3 * * Mon-Sat").unwrap().unwrap();
+        let sunday = Local.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let next = schedule.after(&sunday).next().unwrap();
+        assert_eq!((next.day(), next.hour(), next.minute()), (2, 3, 0));
+
+        assert!(parse_cron("").unwrap().is_none());
+        assert!(parse_cron("off").unwrap().is_none());
+        assert!(parse_cron("15 30 4 * * *").unwrap().is_some());
+        assert!(parse_cron("not a schedule").unwrap_err().contains("invalid cron"));
+
+        let schedule = BackupSchedule {
+            full: parse_cron("0 3 * * Sun").unwrap(),
+            incremental: parse_cron("0 3 * * *").unwrap(),
+            verify: parse_cron("0 3 * * *").unwrap(),
+            retention: RetentionPolicy::default(),
+        };
+        let saturday = Local.with_ymd_and_hms(2026, 2, 28, 12, 0, 0).unwrap();
+        let (_, jobs) = schedule.next_after(&saturday).unwrap();
+        assert_eq!(jobs, vec![BackupJob::Full, BackupJob::Verify]);
+    }
+
+    #[test]
+    fn retention_keeps_newest_per_period_and_the_bases_they_need() {
+        let start = Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap().timestamp() as u64;
+        // Sixty days of snapshots: a full one every seventh day, incremental ones in between,
+        // plus a second snapshot on the last day, a safety snapshot and an unrelated file.
+        let mut archives = Vec::new();
+        let mut base = String::new();
+        for day in 0..60 {
+            let at = start + day * DAY;
+            if day % 7 == 0 {
+                base = format!("eternal_backup_{at}.tar.gz");
+                archives.push(info(&base, at, None));
+            } else {
+                archives.push(info(&format!("eternal_incremental_{at}.tar.gz"), at, Some(&base)));
+            }
+        }
+        let last = start + 59 * DAY;
+        archives.push(info(
+            &format!("eternal_incremental_{}.tar.gz", last + 60),
+            last + 60,
+            Some(&base),
+        ));
+        archives.push(info("pre_restore_1.tar.gz", start, None));
+        archives.push(info("handmade.tar.gz", start, None));
+        archives.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+        let policy = RetentionPolicy {
+            daily: 3,
+            weekly: 2,
+            monthly: 2,
+        };
+        let keep = policy.keep(&archives);
+        assert!(keep.contains("pre_restore_1.tar.gz") && keep.contains("handmade.tar.gz"));
+        // Newest of the last day only, plus the two days before it.
+        assert!(keep.contains(&format!("eternal_incremental_{}.tar.gz", last + 60)));
+        assert!(!keep.contains(&format!("eternal_incremental_{last}.tar.gz")));
+        for day in [57, 58] {
+            assert!(
+                keep.iter()
+                    .any(|f| f.ends_with(&format!("_{}.tar.gz", start + day * DAY)))
+            );
+        }
+        // The base of every kept incremental survives.
+        for info in archives.iter().filter(|i| keep.contains(&i.file)) {
+            if let Some(base) = &info.base {
+                assert!(keep.contains(base), "{} lost its base {}", info.file, base);
+            }
+        }
+        assert!(
+            !keep
+                .iter()
+                .any(|f| f.ends_with(&format!("_{start}.tar.gz")) && f.starts_with("eternal_"))
+        );
+        assert!(keep.len() < archives.len() / 4);
+
+        let none = RetentionPolicy {
+            daily: 0,
+            weekly: 0,
+            monthly: 0,
+        };
+        assert_eq!(
+            none.keep(&archives).len(),
+            2 + 1 + 1,
+            "foreign files, the newest and its base"
+        );
+    }
+}
@@ -0,0 +1,1166 @@
+// vital_pulse_monitor/src/snapshot.rs
+//
+// Consistent snapshots of Phoenix's sled databases, and restoring them.
+//
+// Design goals:
+// - Never archive live database files: every database is flushed and read through sled's export
+//   API, so the archive holds exactly the collections sled reports
+// - Databases this process already holds are exported through those handles (sled locks its
+//   directory); the rest are opened by path and skipped, with the reason, when another process
+//   holds them
+// - One tar.gz per snapshot: `manifest.json` plus one dump per database (`databases/<name>.dump`)
+// - Restore checks the format version and every dump's CRC32 before touching anything, takes a
+//   `pre_restore_*` snapshot of what it is about to replace, then replaces tree contents in place
+// - Incremental snapshots store only the databases whose dump changed since the latest full
+//   snapshot and name that snapshot as their base; restore pulls the rest from it
+// - Every archive gets a `<archive>.manifest.json` sidecar with its SHA-256, so listing, retention
+//   and integrity checks work without opening (or decrypting) the archive
+// - Archives can be encrypted with AES-256-GCM under the vault key (SHA-256 of
+//   `SOUL_ENCRYPTION_KEY`, as `VitalOrganVaults` derives it)
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever the archive layout or dump encoding changes; restore refuses newer formats.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+/// Where snapshots go unless `PHOENIX_BACKUP_DIR` says otherwise.
+pub const DEFAULT_BACKUP_DIR: &str = "./eternal_backups";
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DUMP_DIR: &str = "databases";
+/// File name prefixes of full, incremental and pre-restore snapshots.
+pub(crate) const FULL_PREFIX: &str = "eternal_backup";
+pub(crate) const INCREMENTAL_PREFIX: &str = "eternal_incremental";
+const SAFETY_PREFIX: &str = "pre_restore";
+/// Appended to an archive's file name for its checksum sidecar.
+const CHECKSUM_SUFFIX: &str = ".manifest.json";
+/// Leads an encrypted archive, followed by the 12 byte nonce and the AES-256-GCM ciphertext.
+const ENCRYPTED_MAGIC: &[u8; 8] = b"PHXBAK1\0";
+/// Skip reason for a path database that does not exist (yet).
+pub(crate) const NOT_PRESENT: &str = "not present";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BackupKind {
+    #[default]
+    Full,
+    Incremental,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SnapshotManifest {
+    pub format_version: u32,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Version of the program that took the snapshot.
+    pub app_version: String,
+    /// Absent in format 1 archives, which are all full.
+    #[serde(default)]
+    pub kind: BackupKind,
+    /// File name of the full snapshot an incremental one builds on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Databases stored in this archive.
+    pub databases: Vec<DatabaseDump>,
+    /// Databases of an incremental snapshot that are unchanged since `base` and stored there.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unchanged: Vec<DatabaseDump>,
+    /// Configured databases that were not included, with the reason.
+    #[serde(default)]
+    pub skipped: Vec<SkippedDatabase>,
+}
+
+impl SnapshotManifest {
+    /// Every database the snapshot restores, whether stored here or in the base.
+    pub fn all_databases(&self) -> impl Iterator<Item = &DatabaseDump> {
+        self.databases.iter().chain(&self.unchanged)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DatabaseDump {
+    pub name: String,
+    pub trees: usize,
+    pub entries: usize,
+    pub bytes: u64,
+    /// CRC32 of the dump file.
+    pub crc32: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SkippedDatabase {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SnapshotReport {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub archive: PathBuf,
+    pub manifest: SnapshotManifest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RestoreReport {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub archive: PathBuf,
+    /// Snapshot of the replaced databases, taken just before the restore.
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub safety_snapshot: PathBuf,
+    pub restored: Vec<String>,
+    /// Databases in the archive that are not configured here.
+    pub skipped: Vec<SkippedDatabase>,
+    pub notes: Vec<String>,
+}
+
+/// The `<archive>.manifest.json` sidecar written next to every archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChecksumManifest {
+    pub archive: String,
+    /// Hex SHA-256 of the archive file as written.
+    pub sha256: String,
+    pub bytes: u64,
+    pub encrypted: bool,
+    pub manifest: SnapshotManifest,
+}
+
+/// A snapshot archive in the backup directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BackupInfo {
+    pub file: String,
+    pub bytes: u64,
+    /// Unix seconds (file modification time).
+    pub modified: u64,
+    /// Unix seconds; from the checksum sidecar, otherwise the modification time.
+    pub created_at: u64,
+    /// `None` for archives without a checksum sidecar.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<BackupKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    pub encrypted: bool,
+}
+
+/// Result of test-restoring an archive into a scratch directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VerifyReport {
+    #[cfg_attr(feature = "openapi", schema(value_type = String))]
+    pub archive: PathBuf,
+    pub kind: BackupKind,
+    /// Unix seconds the snapshot was taken.
+    pub created_at: u64,
+    pub databases: Vec<String>,
+    pub entries: usize,
+}
+
+#[derive(Clone)]
+enum Location {
+    Open(sled::Db),
+    Path(PathBuf),
+}
+
+/// A named database to snapshot or restore.
+#[derive(Clone)]
+pub struct BrainDatabase {
+    name: String,
+    location: Location,
+}
+
+impl BrainDatabase {
+    /// A database this process already has open.
+    pub fn open(name: impl Into<String>, db: sled::Db) -> Self {
+        Self {
+            name: name.into(),
+            location: Location::Open(db),
+        }
+    }
+
+    /// A database directory that is opened only for the duration of a snapshot or restore.
+    pub fn at(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            location: Location::Path(path.into()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The databases Phoenix keeps by default (honouring the same env overrides as their owners).
+pub fn default_databases() -> Vec<BrainDatabase> {
+    let env_path = |key: &str, default: &str| {
+        std::env::var(key)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| default.to_string())
+    };
+    let mut databases = vec![
+        BrainDatabase::at("eternal_memory", "./eternal_memory.db"),
+        BrainDatabase::at("soul_kb", "./soul_kb.db"),
+        BrainDatabase::at("mind_vault", "./mind_vault.db"),
+        BrainDatabase::at("body_vault", "./body_vault.db"),
+        BrainDatabase::at("compliance_audit", "./compliance_audit.db"),
+        BrainDatabase::at("skill_library", env_path("SKILL_LIBRARY_DB_PATH", "./skill_library.db")),
+        BrainDatabase::at(
+            "vector_kb",
+            Path::new(&env_path("VECTOR_DB_PATH", "./data/vector_db")).join("vector_kb.sled"),
+        ),
+        BrainDatabase::at("hyperspace_cache", "./hyperspace_cache.db"),
+    ];
+    if let Ok(path) = std::env::var("HYPERSPACE_CACHE_PATH") {
+        databases.push(BrainDatabase::at("hyperspace_cache_env", path));
+    }
+    databases
+}
+
+/// Takes and restores snapshots of a set of databases.
+#[derive(Clone)]
+pub struct BrainBackup {
+    dir: PathBuf,
+    app_version: String,
+    databases: Vec<BrainDatabase>,
+    /// Vault key; needed to write encrypted archives and to read them back.
+    key: Option<[u8; 32]>,
+    encrypt: bool,
+}
+
+impl BrainBackup {
+    /// No databases yet; add them with [`BrainBackup::database`].
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            databases: Vec::new(),
+            key: None,
+            encrypt: false,
+        }
+    }
+
+    /// [`default_databases`] into `PHOENIX_BACKUP_DIR` (default [`DEFAULT_BACKUP_DIR`]).
+    ///
+    /// `SOUL_ENCRYPTION_KEY` is used to read encrypted archives, and to encrypt new ones when
+    /// `PHOENIX_BACKUP_ENCRYPT=true`.
+    pub fn from_env() -> Self {
+        let dir = std::env::var("PHOENIX_BACKUP_DIR")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_BACKUP_DIR.to_string());
+        let mut backup = default_databases()
+            .into_iter()
+            .fold(Self::new(dir), |backup, db| backup.database(db));
+        if let Ok(secret) = std::env::var("SOUL_ENCRYPTION_KEY")
+            && !secret.is_empty()
+        {
+            backup = backup.with_vault_key(&secret);
+        }
+        let encrypt = std::env::var("PHOENIX_BACKUP_ENCRYPT")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        backup.encrypted(encrypt)
+    }
+
+    /// Derive the vault key from `secret` the way `VitalOrganVaults` does.
+    pub fn with_vault_key(mut self, secret: &str) -> Self {
+        self.key = Some(Sha256::digest(secret.as_bytes()).into());
+        self
+    }
+
+    /// Encrypt new archives with the vault key (snapshots fail without one).
+    pub fn encrypted(mut self, on: bool) -> Self {
+        self.encrypt = on;
+        self
+    }
+
+    pub fn with_app_version(mut self, version: impl Into<String>) -> Self {
+        self.app_version = version.into();
+        self
+    }
+
+    /// Add `db`, replacing a configured database of the same name (e.g. swap a path for the
+    /// handle this process already holds).
+    pub fn database(mut self, db: BrainDatabase) -> Self {
+        self.databases.retain(|d| d.name != db.name);
+        self.databases.push(db);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn database_names(&self) -> Vec<&str> {
+        self.databases.iter().map(BrainDatabase::name).collect()
+    }
+
+    /// Snapshot every configured database into `eternal_backup_<unix secs>.tar.gz`.
+    pub fn snapshot(&self) -> Result<SnapshotReport, String> {
+        let all: Vec<&BrainDatabase> = self.databases.iter().collect();
+        self.snapshot_into(FULL_PREFIX, &all, None)
+    }
+
+    /// Snapshot into `eternal_incremental_<unix secs>.tar.gz`, storing only databases whose dump
+    /// differs from the latest full snapshot. Takes a full snapshot when there is none to build on.
+    pub fn snapshot_incremental(&self) -> Result<SnapshotReport, String> {
+        let Some(base) = self.latest_full()? else {
+            return self.snapshot();
+        };
+        let all: Vec<&BrainDatabase> = self.databases.iter().collect();
+        self.snapshot_into(INCREMENTAL_PREFIX, &all, Some(&base))
+    }
+
+    /// Test-restore `archive` (default: the newest full or incremental snapshot) into a scratch
+    /// directory and check that every database reads back identically.
+    pub fn verify(&self, archive: Option<&str>) -> Result<VerifyReport, String> {
+        let archive = match archive {
+            Some(archive) => self.resolve_archive(archive),
+            None => self
+                .list()?
+                .into_iter()
+                .find(|b| b.file.starts_with(FULL_PREFIX) || b.file.starts_with(INCREMENTAL_PREFIX))
+                .map(|b| self.dir.join(b.file))
+                .ok_or_else(|| format!("no snapshots in {}", self.dir.display()))?,
+        };
+        let (manifest, dumps) = self.load_snapshot(&archive)?;
+
+        let scratch = std::env::temp_dir().join(format!("phoenix_backup_verify_{}_{}", std::process::id(), unix_now()));
+        let checked = (|| {
+            let mut databases = Vec::new();
+            let mut entries = 0;
+            for dump in manifest.all_databases() {
+                let db = sled::open(scratch.join(&dump.name))
+                    .map_err(|e| format!("could not create scratch database for {}: {}", dump.name, e))?;
+                let db = BrainDatabase::open(dump.name.clone(), db);
+                replace_database(&db, &dumps[&dump.name])
+                    .map_err(|e| format!("restoring {} failed: {}", dump.name, e))?;
+                match export_database(&db)? {
+                    Some((bytes, _, n)) if crc32fast::hash(&bytes) == dump.crc32 => entries += n,
+                    _ => return Err(format!("{} did not read back identically after restore", dump.name)),
+                }
+                databases.push(dump.name.clone());
+            }
+            Ok((databases, entries))
+        })();
+        let _ = fs::remove_dir_all(&scratch);
+        let (databases, entries) = checked.map_err(|e: String| format!("{}: {}", archive.display(), e))?;
+
+        Ok(VerifyReport {
+            archive,
+            kind: manifest.kind,
+            created_at: manifest.created_at,
+            databases,
+            entries,
+        })
+    }
+
+    /// Snapshot archives in the backup directory, newest first.
+    pub fn list(&self) -> Result<Vec<BackupInfo>, String> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("could not read {}: {}", self.dir.display(), e)),
+        };
+        let mut out = Vec::new();
+        for entry in entries.flatten() {
+            let file = entry.file_name().to_string_lossy().to_string();
+            if !file.ends_with(".tar.gz") && !file.ends_with(".tar.gz.enc") {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let sidecar = read_sidecar(&entry.path());
+            out.push(BackupInfo {
+                bytes: meta.len(),
+                modified,
+                created_at: sidecar.as_ref().map_or(modified, |c| c.manifest.created_at),
+                kind: sidecar.as_ref().map(|c| c.manifest.kind),
+                base: sidecar.as_ref().and_then(|c| c.manifest.base.clone()),
+                encrypted: sidecar.as_ref().map_or(file.ends_with(".enc"), |c| c.encrypted),
+                file,
+            });
+        }
+        out.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.file.cmp(&a.file)));
+        Ok(out)
+    }
+
+    /// Delete an archive in the backup directory along with its checksum sidecar.
+    pub(crate) fn remove_archive(&self, file: &str) -> Result<(), String> {
+        let path = self.dir.join(file);
+        fs::remove_file(&path).map_err(|e| format!("could not remove {}: {}", path.display(), e))?;
+        let _ = fs::remove_file(sidecar_path(&path));
+        Ok(())
+    }
+
+    /// A bare file name in the backup directory, otherwise `archive` as given.
+    pub fn resolve_archive(&self, archive: &str) -> PathBuf {
+        let path = PathBuf::from(archive.trim());
+        let in_dir = self.dir.join(&path);
+        if path.components().count() == 1 && in_dir.is_file() {
+            in_dir
+        } else {
+            path
+        }
+    }
+
+    /// Read and validate an archive's manifest without restoring it.
+    pub fn inspect(&self, archive: &str) -> Result<SnapshotManifest, String> {
+        self.load_snapshot(&self.resolve_archive(archive))
+            .map(|(manifest, _)| manifest)
+    }
+
+    /// Replace the configured databases with their contents in `archive`.
+    ///
+    /// Nothing is modified unless the whole archive validates. Databases in the archive that are
+    /// not configured here are reported as skipped; configured ones missing from the archive are
+    /// left alone.
+    pub fn restore(&self, archive: &str) -> Result<RestoreReport, String> {
+        let archive = self.resolve_archive(archive);
+        let (manifest, dumps) = self.load_snapshot(&archive)?;
+
+        let mut targets = Vec::new();
+        let mut skipped = Vec::new();
+        for dump in manifest.all_databases() {
+            match self.databases.iter().find(|d| d.name == dump.name) {
+                Some(db) => targets.push((db, &dumps[&dump.name])),
+                None => skipped.push(SkippedDatabase {
+                    name: dump.name.clone(),
+                    reason: "not configured here".to_string(),
+                }),
+            }
+        }
+        if targets.is_empty() {
+            return Err(format!(
+                "{} contains none of the configured databases",
+                archive.display()
+            ));
+        }
+
+        let replaced: Vec<&BrainDatabase> = targets.iter().map(|(db, _)| *db).collect();
+        let safety = self
+            .snapshot_into(SAFETY_PREFIX, &replaced, None)
+            .map_err(|e| format!("restore aborted, could not snapshot current state: {e}"))?;
+
+        let mut restored = Vec::new();
+        for (db, trees) in targets {
+            replace_database(db, trees).map_err(|e| {
+                format!(
+                    "restoring {} failed after {} database(s) were replaced ({}); the previous state is in {}",
+                    db.name,
+                    restored.len(),
+                    e,
+                    safety.archive.display()
+                )
+            })?;
+            restored.push(db.name.clone());
+        }
+
+        let mut notes = Vec::new();
+        if manifest.app_version != self.app_version {
+            notes.push(format!(
+                "snapshot was taken by version {} (this is {})",
+                manifest.app_version, self.app_version
+            ));
+        }
+        Ok(RestoreReport {
+            archive,
+            safety_snapshot: safety.archive,
+            restored,
+            skipped,
+            notes,
+        })
+    }
+
+    /// The newest full snapshot an incremental one can build on: file name and manifest.
+    fn latest_full(&self) -> Result<Option<(String, SnapshotManifest)>, String> {
+        for info in self.list()? {
+            if !info.file.starts_with(FULL_PREFIX) || info.kind == Some(BackupKind::Incremental) {
+                continue;
+            }
+            let path = self.dir.join(&info.file);
+            let manifest = match read_sidecar(&path) {
+                Some(sidecar) => sidecar.manifest,
+                // Older archives without a sidecar (or unreadable ones) are not used as a base.
+                None => match read_archive(&path, self.key.as_ref()) {
+                    Ok((manifest, _)) => manifest,
+                    Err(_) => continue,
+                },
+            };
+            if manifest.kind == BackupKind::Full {
+                return Ok(Some((info.file, manifest)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// [`read_archive`], filling in the databases an incremental snapshot leaves to its base.
+    fn load_snapshot(&self, path: &Path) -> Result<(SnapshotManifest, BTreeMap<String, Trees>), String> {
+        let (manifest, mut dumps) = read_archive(path, self.key.as_ref())?;
+        if manifest.unchanged.is_empty() {
+            return Ok((manifest, dumps));
+        }
+        let base = manifest
+            .base
+            .as_deref()
+            .ok_or_else(|| format!("{} lists unchanged databases but no base snapshot", path.display()))?;
+        // The base must sit next to the snapshot; a path could read any archive on the machine.
+        if !is_plain_name(base) {
+            return Err(format!("{} names base snapshot '{}', which is not a file name", path.display(), base));
+        }
+        let base_path = path.parent().unwrap_or_else(|| Path::new(".")).join(base);
+        let (base_manifest, mut base_dumps) =
+            read_archive(&base_path, self.key.as_ref()).map_err(|e| format!("base snapshot {base}: {e}"))?;
+        if base_manifest.kind != BackupKind::Full {
+            return Err(format!("base snapshot {base} is not a full snapshot"));
+        }
+        for dump in &manifest.unchanged {
+            let same = base_manifest
+                .databases
+                .iter()
+                .any(|d| d.name == dump.name && d.crc32 == dump.crc32);
+            let trees = base_dumps
+                .remove(&dump.name)
+                .filter(|_| same)
+                .ok_or_else(|| format!("base snapshot {base} does not hold {} as recorded", dump.name))?;
+            dumps.insert(dump.name.clone(), trees);
+        }
+        Ok((manifest, dumps))
+    }
+
+    fn snapshot_into(
+        &self,
+        prefix: &str,
+        databases: &[&BrainDatabase],
+        base: Option<&(String, SnapshotManifest)>,
+    ) -> Result<SnapshotReport, String> {
+        if self.encrypt && self.key.is_none() {
+            return Err("archive encryption is enabled but no vault key (SOUL_ENCRYPTION_KEY) is set".to_string());
+        }
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("could not create backup directory {}: {}", self.dir.display(), e))?;
+        let created_at = unix_now();
+        let ext = if self.encrypt { "tar.gz.enc" } else { "tar.gz" };
+        let mut archive = self.dir.join(format!("{prefix}_{created_at}.{ext}"));
+        let mut n = 1;
+        while archive.exists() {
+            archive = self.dir.join(format!("{prefix}_{created_at}_{n}.{ext}"));
+            n += 1;
+        }
+
+        let mut manifest = SnapshotManifest {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            created_at,
+            app_version: self.app_version.clone(),
+            kind: if base.is_some() {
+                BackupKind::Incremental
+            } else {
+                BackupKind::Full
+            },
+            base: base.map(|(file, _)| file.clone()),
+            databases: Vec::new(),
+            unchanged: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let mut dumps = Vec::new();
+        for db in databases {
+            match export_database(db) {
+                Ok(Some((bytes, trees, entries))) => {
+                    let dump = DatabaseDump {
+                        name: db.name.clone(),
+                        trees,
+                        entries,
+                        bytes: bytes.len() as u64,
+                        crc32: crc32fast::hash(&bytes),
+                    };
+                    let in_base = base.is_some_and(|(_, m)| {
+                        m.databases
+                            .iter()
+                            .any(|d| d.name == dump.name && d.crc32 == dump.crc32 && d.bytes == dump.bytes)
+                    });
+                    if in_base {
+                        manifest.unchanged.push(dump);
+                    } else {
+                        manifest.databases.push(dump);
+                        dumps.push((db.name.clone(), bytes));
+                    }
+                }
+                Ok(None) => manifest.skipped.push(SkippedDatabase {
+                    name: db.name.clone(),
+                    reason: NOT_PRESENT.to_string(),
+                }),
+                Err(reason) => manifest.skipped.push(SkippedDatabase {
+                    name: db.name.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        let mut bytes = archive_bytes(&manifest, &dumps)?;
+        if let Some(key) = self.key.as_ref().filter(|_| self.encrypt) {
+            bytes = encrypt(key, &bytes)?;
+        }
+        let sidecar = ChecksumManifest {
+            archive: archive.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+            bytes: bytes.len() as u64,
+            encrypted: self.encrypt,
+            manifest: manifest.clone(),
+        };
+        let sidecar_json =
+            serde_json::to_vec_pretty(&sidecar).map_err(|e| format!("could not encode checksum manifest: {e}"))?;
+        fs::write(&archive, &bytes)
+            .and_then(|_| fs::write(sidecar_path(&archive), sidecar_json))
+            .map_err(|e| {
+                let _ = fs::remove_file(&archive);
+                format!("could not write archive {}: {}", archive.display(), e)
+            })?;
+        Ok(SnapshotReport { archive, manifest })
+    }
+}
+
+/// Trees of one database: name -> key/value pairs.
+type Trees = BTreeMap<Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>>;
+
+/// Flush `db` and encode its export; `None` when a path database does not exist.
+fn export_database(db: &BrainDatabase) -> Result<Option<(Vec<u8>, usize, usize)>, String> {
+    let opened;
+    let handle = match &db.location {
+        Location::Open(handle) => handle,
+        Location::Path(path) => {
+            if !path.exists() {
+                return Ok(None);
+            }
+            if !path.join("conf").is_file() || !path.join("db").is_file() {
+                return Err(format!("{} is not a sled database", path.display()));
+            }
+            opened = sled::open(path).map_err(|e| format!("could not open {}: {}", path.display(), e))?;
+            &opened
+        }
+    };
+    handle.flush().map_err(|e| format!("flush failed: {e}"))?;
+
+    let mut trees: Trees = BTreeMap::new();
+    for (kind, name, items) in handle.export() {
+        if kind != b"tree" {
+            return Err(format!(
+                "unsupported collection type {:?}",
+                String::from_utf8_lossy(&kind)
+            ));
+        }
+        let pairs = trees.entry(name).or_default();
+        for mut kv in items {
+            let (Some(v), Some(k)) = (kv.pop(), kv.pop()) else {
+                return Err("malformed export entry".to_string());
+            };
+            pairs.push((k, v));
+        }
+    }
+    let entries = trees.values().map(Vec::len).sum();
+    Ok(Some((encode_trees(&trees), trees.len(), entries)))
+}
+
+/// Clear every tree of `db` and load `trees` into it. Trees absent from the snapshot are emptied
+/// rather than dropped, so handles other components hold on them stay valid.
+fn replace_database(db: &BrainDatabase, trees: &Trees) -> Result<(), String> {
+    let opened;
+    let handle = match &db.location {
+        Location::Open(handle) => handle,
+        Location::Path(path) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("could not create {}: {}", parent.display(), e))?;
+            }
+            opened = sled::open(path).map_err(|e| format!("could not open {}: {}", path.display(), e))?;
+            &opened
+        }
+    };
+
+    for name in handle.tree_names() {
+        if !trees.contains_key(name.as_ref()) {
+            handle
+                .open_tree(&name)
+                .and_then(|tree| tree.clear())
+                .map_err(|e| format!("could not clear tree: {e}"))?;
+        }
+    }
+    for (name, pairs) in trees {
+        let tree = handle
+            .open_tree(name)
+            .map_err(|e| format!("could not open tree: {e}"))?;
+        tree.clear().map_err(|e| format!("could not clear tree: {e}"))?;
+        let mut batch = sled::Batch::default();
+        for (k, v) in pairs {
+            batch.insert(k.as_slice(), v.as_slice());
+        }
+        tree.apply_batch(batch)
+            .map_err(|e| format!("could not write tree: {e}"))?;
+    }
+    handle.flush().map_err(|e| format!("flush failed: {e}"))?;
+    Ok(())
+}
+
+// Dump encoding (little endian): u32 tree count, then per tree a length-prefixed name, a u64
+// entry count and length-prefixed key/value pairs.
+
+fn encode_trees(trees: &Trees) -> Vec<u8> {
+    fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(bytes);
+    }
+    let mut out = Vec::new();
+    out.extend_from_slice(&(trees.len() as u32).to_le_bytes());
+    for (name, pairs) in trees {
+        put_bytes(&mut out, name);
+        out.extend_from_slice(&(pairs.len() as u64).to_le_bytes());
+        for (k, v) in pairs {
+            put_bytes(&mut out, k);
+            put_bytes(&mut out, v);
+        }
+    }
+    out
+}
+
+fn decode_trees(mut data: &[u8]) -> Result<Trees, String> {
+    fn take<'a>(data: &mut &'a [u8], n: usize) -> Result<&'a [u8], String> {
+        if data.len() < n {
+            return Err("truncated dump".to_string());
+        }
+        let (head, rest) = data.split_at(n);
+        *data = rest;
+        Ok(head)
+    }
+    fn take_u32(data: &mut &[u8]) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(take(data, 4)?.try_into().unwrap_or_default()))
+    }
+    fn take_bytes(data: &mut &[u8]) -> Result<Vec<u8>, String> {
+        let len = take_u32(data)? as usize;
+        Ok(take(data, len)?.to_vec())
+    }
+
+    let mut trees = Trees::new();
+    for _ in 0..take_u32(&mut data)? {
+        let name = take_bytes(&mut data)?;
+        let count = u64::from_le_bytes(take(&mut data, 8)?.try_into().unwrap_or_default());
+        let mut pairs = Vec::new();
+        for _ in 0..count {
+            let k = take_bytes(&mut data)?;
+            let v = take_bytes(&mut data)?;
+            pairs.push((k, v));
+        }
+        trees.insert(name, pairs);
+    }
+    if !data.is_empty() {
+        return Err("trailing bytes after dump".to_string());
+    }
+    Ok(trees)
+}
+
+/// The tar.gz holding `manifest` and `dumps`.
+fn archive_bytes(manifest: &SnapshotManifest, dumps: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(manifest).map_err(|e| format!("could not encode manifest: {e}"))?;
+    let mut entries = vec![(MANIFEST_NAME.to_string(), manifest_json.as_slice())];
+    entries.extend(
+        dumps
+            .iter()
+            .map(|(name, bytes)| (format!("{DUMP_DIR}/{name}.dump"), bytes.as_slice())),
+    );
+    for (name, bytes) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(manifest.created_at);
+        tar.append_data(&mut header, &name, bytes)
+            .map_err(|e| format!("failed to add {}: {}", name, e))?;
+    }
+
+    let encoder = tar.into_inner().map_err(|e| format!("failed to finalize tar: {}", e))?;
+    encoder.finish().map_err(|e| format!("failed to finalize gzip: {}", e))
+}
+
+fn sidecar_path(archive: &Path) -> PathBuf {
+    let mut name = archive.file_name().unwrap_or_default().to_os_string();
+    name.push(CHECKSUM_SUFFIX);
+    archive.with_file_name(name)
+}
+
+fn read_sidecar(archive: &Path) -> Option<ChecksumManifest> {
+    let bytes = fs::read(sidecar_path(archive)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn encrypt(key: &[u8; 32], plain: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let sealed = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| "archive encryption failed".to_string())?;
+    let mut out = ENCRYPTED_MAGIC.to_vec();
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+fn decrypt(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < ENCRYPTED_MAGIC.len() + 12 {
+        return Err("truncated encrypted archive".to_string());
+    }
+    let (nonce, sealed) = data[ENCRYPTED_MAGIC.len()..].split_at(12);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), sealed)
+        .map_err(|_| "could not decrypt archive (wrong SOUL_ENCRYPTION_KEY, or the archive is corrupt)".to_string())
+}
+
+/// The manifest and decoded dumps of `path`, after checking the sidecar SHA-256 (when present),
+/// the format version and every CRC.
+fn read_archive(path: &Path, key: Option<&[u8; 32]>) -> Result<(SnapshotManifest, BTreeMap<String, Trees>), String> {
+    let mut bytes = fs::read(path).map_err(|e| format!("could not open {}: {}", path.display(), e))?;
+    if let Some(sidecar) = read_sidecar(path)
+        && format!("{:x}", Sha256::digest(&bytes)) != sidecar.sha256
+    {
+        return Err(format!(
+            "{} is corrupt (SHA-256 differs from its checksum manifest)",
+            path.display()
+        ));
+    }
+    if bytes.starts_with(ENCRYPTED_MAGIC) {
+        let key = key.ok_or_else(|| format!("{} is encrypted and no SOUL_ENCRYPTION_KEY is set", path.display()))?;
+        bytes = decrypt(key, &bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    let mut tar = tar::Archive::new(GzDecoder::new(bytes.as_slice()));
+
+    let mut manifest: Option<SnapshotManifest> = None;
+    let mut raw = BTreeMap::new();
+    let entries = tar
+        .entries()
+        .map_err(|e| format!("{} is not a snapshot archive: {}", path.display(), e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("corrupt archive {}: {}", path.display(), e))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("corrupt archive {}: {}", path.display(), e))?
+            .to_string_lossy()
+            .to_string();
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("corrupt archive {}: {}", path.display(), e))?;
+        if name == MANIFEST_NAME {
+            manifest = Some(serde_json::from_slice(&bytes).map_err(|e| format!("invalid manifest: {e}"))?);
+        } else if let Some(db) = name
+            .strip_prefix(&format!("{DUMP_DIR}/"))
+            .and_then(|n| n.strip_suffix(".dump"))
+        {
+            raw.insert(db.to_string(), bytes);
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| {
+        format!(
+            "{} has no {} (archives from before snapshot format 1 hold raw database directories; unpack those by hand)",
+            path.display(),
+            MANIFEST_NAME
+        )
+    })?;
+    if manifest.format_version == 0 || manifest.format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(format!(
+            "snapshot format {} is not supported (this build reads up to {})",
+            manifest.format_version, SNAPSHOT_FORMAT_VERSION
+        ));
+    }
+
+    // Names are joined onto scratch and restore paths; a path would escape them.
+    if let Some(dump) = manifest.all_databases().find(|d| !is_plain_name(&d.name)) {
+        return Err(format!("{} names database '{}', which is not a plain name", path.display(), dump.name));
+    }
+
+    let mut dumps = BTreeMap::new();
+    for dump in &manifest.databases {
+        let bytes = raw
+            .remove(&dump.name)
+            .ok_or_else(|| format!("archive is missing the dump for {}", dump.name))?;
+        if crc32fast::hash(&bytes) != dump.crc32 || bytes.len() as u64 != dump.bytes {
+            return Err(format!("dump for {} is corrupt (checksum mismatch)", dump.name));
+        }
+        let trees = decode_trees(&bytes).map_err(|e| format!("dump for {} is corrupt: {}", dump.name, e))?;
+        dumps.insert(dump.name.clone(), trees);
+    }
+    Ok((manifest, dumps))
+}
+
+/// Whether `name` is a single ordinary path component (no separators, `.`, `..` or root).
+fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(c)), None) if c == name
+    )
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_archive(path: &Path, manifest: &SnapshotManifest, dumps: &[(String, Vec<u8>)]) -> Result<(), String> {
+        fs::write(path, archive_bytes(manifest, dumps)?).map_err(|e| e.to_string())
+    }
+
+    fn scratch(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vital_pulse_{}_{}_{}", label, std::process::id(), unix_now()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn snapshot_and_restore_round_trip_through_open_handles() {
+        let root = scratch("round_trip");
+        let db = sled::open(root.join("mind.db")).unwrap();
+        db.insert(b"k1", b"before").unwrap();
+        db.open_tree("notes").unwrap().insert(b"n", b"kept").unwrap();
+
+        let backup = BrainBackup::new(root.join("backups"))
+            .database(BrainDatabase::open("mind", db.clone()))
+            .database(BrainDatabase::at("missing", root.join("nope.db")));
+        let report = backup.snapshot().unwrap();
+        assert_eq!(report.manifest.databases.len(), 1);
+        assert_eq!(report.manifest.databases[0].entries, 2);
+        assert_eq!(report.manifest.skipped[0].name, "missing");
+
+        db.insert(b"k1", b"after").unwrap();
+        db.insert(b"k2", b"new").unwrap();
+        db.open_tree("scratch").unwrap().insert(b"x", b"y").unwrap();
+
+        let file = report.archive.file_name().unwrap().to_string_lossy().to_string();
+        let restored = backup.restore(&file).unwrap();
+        assert_eq!(restored.restored, vec!["mind".to_string()]);
+        assert!(restored.safety_snapshot.is_file());
+        assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"before");
+        assert!(db.get(b"k2").unwrap().is_none());
+        assert!(db.open_tree("scratch").unwrap().is_empty());
+        assert_eq!(
+            db.open_tree("notes").unwrap().get(b"n").unwrap().unwrap().as_ref(),
+            b"kept"
+        );
+
+        // The safety snapshot holds the state the restore replaced.
+        let safety = backup.inspect(&restored.safety_snapshot.to_string_lossy()).unwrap();
+        assert_eq!(safety.databases[0].entries, 4);
+        assert_eq!(backup.list().unwrap().len(), 2);
+
+        drop(db);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn restore_rejects_corrupt_and_newer_archives_without_touching_data() {
+        let root = scratch("reject");
+        let db = sled::open(root.join("soul.db")).unwrap();
+        db.insert(b"k", b"v").unwrap();
+        let backup = BrainBackup::new(root.join("backups")).database(BrainDatabase::open("soul", db.clone()));
+        let report = backup.snapshot().unwrap();
+        let (manifest, dumps) = read_archive(&report.archive, None).unwrap();
+        let dump = encode_trees(&dumps["soul"]);
+
+        let mut corrupt = dump.clone();
+        *corrupt.last_mut().unwrap() ^= 0xff;
+        let bad = root.join("backups/corrupt.tar.gz");
+        write_archive(&bad, &manifest, &[("soul".to_string(), corrupt)]).unwrap();
+        assert!(
+            backup
+                .restore("corrupt.tar.gz")
+                .unwrap_err()
+                .contains("checksum mismatch")
+        );
+
+        let newer = SnapshotManifest {
+            format_version: SNAPSHOT_FORMAT_VERSION + 1,
+            ..manifest
+        };
+        let future = root.join("backups/future.tar.gz");
+        write_archive(&future, &newer, &[("soul".to_string(), dump)]).unwrap();
+        assert!(backup.restore("future.tar.gz").unwrap_err().contains("not supported"));
+
+        assert_eq!(db.get(b"k").unwrap().unwrap().as_ref(), b"v");
+        assert_eq!(
+            backup.list().unwrap().len(),
+            3,
+            "no safety snapshot for rejected archives"
+        );
+
+        drop(db);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn path_databases_are_opened_only_while_needed() {
+        let root = scratch("paths");
+        let path = root.join("body.db");
+        {
+            let db = sled::open(&path).unwrap();
+            db.insert(b"k", b"v").unwrap();
+            db.flush().unwrap();
+        }
+        let backup = BrainBackup::new(root.join("backups")).database(BrainDatabase::at("body", &path));
+        let report = backup.snapshot().unwrap();
+        assert_eq!(report.manifest.databases[0].entries, 1);
+
+        fs::remove_dir_all(&path).unwrap();
+        backup.restore(&report.archive.to_string_lossy()).unwrap();
+        let db = sled::open(&path).unwrap();
+        assert_eq!(db.get(b"k").unwrap().unwrap().as_ref(), b"v");
+
+        drop(db);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn incremental_snapshots_store_changed_databases_and_restore_through_the_base() {
+        let root = scratch("incremental");
+        let mind = sled::open(root.join("mind.db")).unwrap();
+        let soul = sled::open(root.join("soul.db")).unwrap();
+        mind.insert(b"m", b"1").unwrap();
+        soul.insert(b"s", b"1").unwrap();
+        let backup = BrainBackup::new(root.join("backups"))
+            .database(BrainDatabase::open("mind", mind.clone()))
+            .database(BrainDatabase::open("soul", soul.clone()));
+
+        // Nothing to build on yet: the first incremental is a full snapshot.
+        let full = backup.snapshot_incremental().unwrap();
+        assert_eq!(full.manifest.kind, BackupKind::Full);
+
+        mind.insert(b"m", b"2").unwrap();
+        let incr = backup.snapshot_incremental().unwrap();
+        assert_eq!(incr.manifest.kind, BackupKind::Incremental);
+        assert_eq!(
+            incr.manifest.base.as_deref(),
+            full.archive.file_name().and_then(|n| n.to_str())
+        );
+        assert_eq!(incr.manifest.databases.len(), 1);
+        assert_eq!(incr.manifest.databases[0].name, "mind");
+        assert_eq!(incr.manifest.unchanged[0].name, "soul");
+
+        let list = backup.list().unwrap();
+        assert_eq!(list[0].kind, Some(BackupKind::Incremental));
+        assert_eq!(list[0].base, incr.manifest.base);
+
+        let verified = backup.verify(None).unwrap();
+        assert_eq!(verified.archive, incr.archive);
+        assert_eq!(verified.entries, 2);
+
+        mind.insert(b"m", b"3").unwrap();
+        soul.insert(b"s", b"3").unwrap();
+        backup.restore(&incr.archive.to_string_lossy()).unwrap();
+        assert_eq!(mind.get(b"m").unwrap().unwrap().as_ref(), b"2");
+        assert_eq!(soul.get(b"s").unwrap().unwrap().as_ref(), b"1");
+
+        // A base outside the backup directory is refused.
+        let (manifest, dumps) = read_archive(&incr.archive, None).unwrap();
+        let dumps: Vec<(String, Vec<u8>)> = dumps.iter().map(|(name, trees)| (name.clone(), encode_trees(trees))).collect();
+        for base in ["../backups/x.tar.gz", "/tmp/x.tar.gz", "..", ""] {
+            let escaping = SnapshotManifest {
+                base: Some(base.to_string()),
+                ..manifest.clone()
+            };
+            let path = root.join("backups/escaping.tar.gz");
+            write_archive(&path, &escaping, &dumps).unwrap();
+            let err = backup.verify(Some(&path.to_string_lossy())).unwrap_err();
+            assert!(err.contains("not a file name"), "{base}: {err}");
+            fs::remove_file(&path).unwrap();
+        }
+
+        // So are database names that are not plain names (checked before any dump is read).
+        for (name, unchanged) in [("../escape", false), ("/abs", false), ("..", true), ("a/b", true)] {
+            let mut crafted = manifest.clone();
+            let target = if unchanged { &mut crafted.unchanged[0] } else { &mut crafted.databases[0] };
+            target.name = name.to_string();
+            let path = root.join("backups/crafted.tar.gz");
+            write_archive(&path, &crafted, &dumps).unwrap();
+            let err = backup.verify(Some(&path.to_string_lossy())).unwrap_err();
+            assert!(err.contains("not a plain name"), "{name}: {err}");
+            assert!(backup.restore(&path.to_string_lossy()).unwrap_err().contains("not a plain name"));
+            fs::remove_file(&path).unwrap();
+        }
+        assert!(!root.join("escape").exists());
+
+        // Without its base an incremental snapshot cannot be restored.
+        fs::remove_file(&full.archive).unwrap();
+        assert!(
+            backup
+                .verify(Some(&incr.archive.to_string_lossy()))
+                .unwrap_err()
+                .contains("base snapshot")
+        );
+
+        drop((mind, soul));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn encrypted_archives_need_the_vault_key_and_checksums_catch_tampering() {
+        let root = scratch("encrypted");
+        let db = sled::open(root.join("soul.db")).unwrap();
+        db.insert(b"secret", b"flame").unwrap();
+        let backup = BrainBackup::new(root.join("backups")).database(BrainDatabase::open("soul", db.clone()));
+        assert!(
+            backup
+                .clone()
+                .encrypted(true)
+                .snapshot()
+                .unwrap_err()
+                .contains("SOUL_ENCRYPTION_KEY")
+        );
+
+        let keyed = backup.clone().with_vault_key("correct horse").encrypted(true);
+        let report = keyed.snapshot().unwrap();
+        assert!(report.archive.to_string_lossy().ends_with(".tar.gz.enc"));
+        let raw = fs::read(&report.archive).unwrap();
+        assert!(raw.starts_with(ENCRYPTED_MAGIC));
+        assert!(!raw.windows(5).any(|w| w == b"flame"));
+        assert!(keyed.list().unwrap()[0].encrypted);
+
+        assert_eq!(keyed.verify(None).unwrap().entries, 1);
+        assert!(backup.verify(None).unwrap_err().contains("no SOUL_ENCRYPTION_KEY"));
+        let wrong = backup.clone().with_vault_key("wrong");
+        assert!(wrong.verify(None).unwrap_err().contains("could not decrypt"));
+
+        let mut tampered = raw.clone();
+        *tampered.last_mut().unwrap() ^= 0xff;
+        fs::write(&report.archive, tampered).unwrap();
+        assert!(keyed.verify(None).unwrap_err().contains("SHA-256"));
+
+        drop(db);
+        let _ = fs::remove_dir_all(&root);
+    }
+}